@@ -259,6 +259,22 @@ impl Client {
         self.decode_json::<ConfigFileResponse>(&url, &ct, &body)
     }
 
+    /// Cancel a task's in-flight turn by POSTing to `tasks/{id}/cancel`.
+    pub async fn cancel_task(&self, task_id: &str) -> Result<()> {
+        let url = match self.path_style {
+            PathStyle::CodexApi => format!("{}/api/codex/tasks/{}/cancel", self.base_url, task_id),
+            PathStyle::ChatGptApi => format!("{}/wham/tasks/{}/cancel", self.base_url, task_id),
+        };
+        let req = self
+            .http
+            .post(&url)
+            .headers(self.headers())
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .json(&serde_json::json!({}));
+        self.exec_request(req, "POST", &url).await?;
+        Ok(())
+    }
+
     /// Create a new task (user turn) by POSTing to the appropriate backend path
     /// based on `path_style`. Returns the created task id.
     pub async fn create_task(&self, request_body: serde_json::Value) -> Result<String> {
@@ -167,4 +167,8 @@ pub trait CloudBackend: Send + Sync {
         qa_mode: bool,
         best_of_n: usize,
     ) -> Result<CreatedTask>;
+    /// Stop the task's in-flight attempt. Backends without cancellation return `Unimplemented`.
+    async fn cancel_task(&self, _id: TaskId) -> Result<()> {
+        Err(CloudTaskError::Unimplemented("cancel_task"))
+    }
 }
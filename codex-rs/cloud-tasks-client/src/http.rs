@@ -117,6 +117,10 @@ impl CloudBackend for HttpClient {
             .create(env_id, prompt, git_ref, qa_mode, best_of_n)
             .await
     }
+
+    async fn cancel_task(&self, id: TaskId) -> Result<()> {
+        self.tasks_api().cancel(id).await
+    }
 }
 
 mod api {
@@ -372,6 +376,15 @@ mod api {
             }
         }
 
+        pub(crate) async fn cancel(&self, id: TaskId) -> Result<()> {
+            self.backend
+                .cancel_task(&id.0)
+                .await
+                .map_err(|e| CloudTaskError::Http(format!("cancel_task failed: {e}")))?;
+            append_error_log(&format!("cancel_task: id={}", id.0));
+            Ok(())
+        }
+
         async fn details_with_body(
             &self,
             id: &str,
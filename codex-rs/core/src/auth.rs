@@ -931,6 +931,8 @@ pub struct AuthManager {
     codex_home: PathBuf,
     inner: RwLock<CachedAuth>,
     enable_codex_api_key_env: bool,
+    /// API key that replaces stored credentials, including across reloads.
+    pinned_api_key: Option<String>,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
     forced_chatgpt_workspace_id: RwLock<Option<String>>,
 }
@@ -959,11 +961,32 @@ impl AuthManager {
                 external_refresher: None,
             }),
             enable_codex_api_key_env,
+            pinned_api_key: None,
             auth_credentials_store_mode,
             forced_chatgpt_workspace_id: RwLock::new(None),
         }
     }
 
+    /// Create a manager that always authenticates with `api_key`, ignoring
+    /// `auth.json` and `CODEX_API_KEY`. `codex_home` is still used for logout.
+    pub fn shared_with_api_key(
+        codex_home: PathBuf,
+        api_key: &str,
+        auth_credentials_store_mode: AuthCredentialsStoreMode,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            codex_home,
+            inner: RwLock::new(CachedAuth {
+                auth: Some(CodexAuth::from_api_key(api_key)),
+                external_refresher: None,
+            }),
+            enable_codex_api_key_env: false,
+            pinned_api_key: Some(api_key.to_string()),
+            auth_credentials_store_mode,
+            forced_chatgpt_workspace_id: RwLock::new(None),
+        })
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     /// Create an AuthManager with a specific CodexAuth, for testing only.
     pub fn from_auth_for_testing(auth: CodexAuth) -> Arc<Self> {
@@ -976,6 +999,7 @@ impl AuthManager {
             codex_home: PathBuf::from("non-existent"),
             inner: RwLock::new(cached),
             enable_codex_api_key_env: false,
+            pinned_api_key: None,
             auth_credentials_store_mode: AuthCredentialsStoreMode::File,
             forced_chatgpt_workspace_id: RwLock::new(None),
         })
@@ -992,6 +1016,7 @@ impl AuthManager {
             codex_home,
             inner: RwLock::new(cached),
             enable_codex_api_key_env: false,
+            pinned_api_key: None,
             auth_credentials_store_mode: AuthCredentialsStoreMode::File,
             forced_chatgpt_workspace_id: RwLock::new(None),
         })
@@ -1055,6 +1080,9 @@ impl AuthManager {
     }
 
     fn load_auth_from_storage(&self) -> Option<CodexAuth> {
+        if let Some(api_key) = self.pinned_api_key.as_deref() {
+            return Some(CodexAuth::from_api_key(api_key));
+        }
         load_auth(
             &self.codex_home,
             self.enable_codex_api_key_env,
@@ -1646,6 +1674,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pinned_api_key_survives_reload() {
+        let codex_home = tempdir().unwrap();
+        login_with_api_key(codex_home.path(), "sk-stored", AuthCredentialsStoreMode::File)
+            .expect("seed api key");
+
+        let manager = AuthManager::shared_with_api_key(
+            codex_home.path().to_path_buf(),
+            "sk-pinned",
+            AuthCredentialsStoreMode::File,
+        );
+        assert_eq!(
+            manager.auth_cached().and_then(|auth| auth.api_key().map(str::to_string)),
+            Some("sk-pinned".to_string())
+        );
+
+        manager.reload();
+        assert_eq!(
+            manager.auth_cached().and_then(|auth| auth.api_key().map(str::to_string)),
+            Some("sk-pinned".to_string())
+        );
+    }
+
     #[test]
     fn plan_type_maps_known_plan() {
         let codex_home = tempdir().unwrap();
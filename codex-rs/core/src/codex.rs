@@ -36,6 +36,7 @@ use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
 use crate::stream_events_utils::last_assistant_message_from_item;
 use crate::terminal;
+use crate::truncate::HeadTailSplit;
 use crate::truncate::TruncationPolicy;
 use crate::turn_metadata::build_turn_metadata_header;
 use crate::util::error_or_panic;
@@ -537,6 +538,14 @@ pub(crate) struct TurnContext {
     turn_metadata_header: OnceCell<Option<String>>,
 }
 impl TurnContext {
+    /// How truncated tool output divides its budget between the start and the end.
+    pub(crate) fn tool_output_split(&self) -> HeadTailSplit {
+        self.config
+            .tool_output_head_tail_split
+            .map(HeadTailSplit::new)
+            .unwrap_or_default()
+    }
+
     pub(crate) fn model_context_window(&self) -> Option<i64> {
         let effective_context_window_percent = self.model_info.effective_context_window_percent;
         self.model_info.context_window.map(|context_window| {
@@ -4024,6 +4033,7 @@ async fn run_sampling_request(
                 .collect(),
         ),
         turn_context.dynamic_tools.as_slice(),
+        &turn_context.config.external_tool_layers,
     ));
 
     let model_supports_parallel = turn_context.model_info.supports_parallel_tool_calls;
@@ -5553,7 +5563,11 @@ mod tests {
         };
         let (_, turn_context) = make_session_and_context().await;
 
-        let out = format_exec_output_str(&exec, turn_context.truncation_policy);
+        let out = format_exec_output_str(
+            &exec,
+            turn_context.truncation_policy,
+            turn_context.tool_output_split(),
+        );
 
         assert_eq!(
             out,
@@ -6340,6 +6354,7 @@ mod tests {
                     .collect(),
             ),
             turn_context.dynamic_tools.as_slice(),
+            &turn_context.config.external_tool_layers,
         );
         let item = ResponseItem::CustomToolCall {
             id: None,
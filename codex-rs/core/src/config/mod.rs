@@ -2,6 +2,7 @@ use crate::auth::AuthCredentialsStoreMode;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::ExecSessionMode;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
@@ -15,6 +16,8 @@ use crate::config::types::OtelExporterKind;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
+use crate::config::types::ShellPtyConfig;
+use crate::config::types::ShellPtyToml;
 use crate::config::types::SkillsConfig;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
@@ -42,6 +45,7 @@ use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::tools::registry::ExternalToolLayers;
 use crate::windows_sandbox::WindowsSandboxLevelExt;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
@@ -267,6 +271,10 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Share of the tool output budget kept from the start of truncated command output,
+    /// `0.0..=1.0`; the rest keeps its end. Defaults to an even split.
+    pub tool_output_head_tail_split: Option<f64>,
+
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
@@ -329,6 +337,12 @@ pub struct Config {
     /// If set to `true`, used only the experimental unified exec tool.
     pub use_experimental_unified_exec_tool: bool,
 
+    /// Whether `shell` tool calls share one persistent shell per thread.
+    pub exec_session: ExecSessionMode,
+
+    /// When set, `shell` tool commands run attached to a PTY of this size.
+    pub shell_pty: Option<ShellPtyConfig>,
+
     /// Settings for ghost snapshots (used for undo).
     pub ghost_snapshot: GhostSnapshotConfig,
 
@@ -371,6 +385,10 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// Tools, filters and interceptors native bindings add to every tool router the session
+    /// builds. Not read from `config.toml`.
+    pub external_tool_layers: ExternalToolLayers,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -891,6 +909,9 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Share (0.0 to 1.0) of the tool output budget kept from the start of truncated command output.
+    pub tool_output_head_tail_split: Option<f64>,
+
     /// Profile to use from the `profiles` map.
     pub profile: Option<String>,
 
@@ -962,6 +983,14 @@ pub struct ConfigToml {
     #[serde(default)]
     pub ghost_snapshot: Option<GhostSnapshotToml>,
 
+    /// How the `shell` tool runs commands: a fresh process per call (default) or one persistent
+    /// shell per thread.
+    pub exec_session: Option<ExecSessionMode>,
+
+    /// Run `shell` tool commands attached to a PTY, so test runners and linters print colored,
+    /// correctly wrapped output. Commands use pipes when unset.
+    pub shell_pty: Option<ShellPtyToml>,
+
     /// Markers used to detect the project root when searching parent
     /// directories for `.codex` folders. Defaults to [".git"] when unset.
     #[serde(default)]
@@ -1238,6 +1267,10 @@ pub struct ConfigOverrides {
     pub ephemeral: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Tools, filter, limits and interceptors native bindings add to the session's tool router.
+    pub external_tool_layers: ExternalToolLayers,
+    /// Endpoint for the built-in OpenAI provider, in place of `OPENAI_BASE_URL`.
+    pub openai_base_url: Option<String>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -1340,6 +1373,8 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             ephemeral,
             additional_writable_roots,
+            external_tool_layers,
+            openai_base_url,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1596,7 +1631,7 @@ impl Config {
         let mcp_servers = constrain_mcp_servers(cfg.mcp_servers.clone(), mcp_servers.as_ref())
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
-        let config = Self {
+        let mut config = Self {
             model,
             review_model,
             model_context_window: cfg.model_context_window,
@@ -1640,6 +1675,7 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            tool_output_head_tail_split: cfg.tool_output_head_tail_split,
             agent_max_threads,
             codex_home,
             log_dir,
@@ -1672,6 +1708,8 @@ impl Config {
             include_apply_patch_tool: include_apply_patch_tool_flag,
             web_search_mode,
             use_experimental_unified_exec_tool,
+            exec_session: cfg.exec_session.unwrap_or_default(),
+            shell_pty: cfg.shell_pty.map(ShellPtyConfig::from),
             ghost_snapshot,
             features,
             suppress_unstable_features_warning: cfg
@@ -1728,7 +1766,11 @@ impl Config {
                     metrics_exporter: OtelExporterKind::Statsig,
                 }
             },
+            external_tool_layers,
         };
+        if let Some(base_url) = openai_base_url {
+            config.set_openai_base_url(base_url);
+        }
         Ok(config)
     }
 
@@ -1793,6 +1835,17 @@ impl Config {
             self.features.disable(Feature::WindowsSandboxElevated);
         }
     }
+
+    /// Point the built-in OpenAI provider at `base_url`, as exporting `OPENAI_BASE_URL`
+    /// would, without touching the process environment.
+    pub fn set_openai_base_url(&mut self, base_url: String) {
+        if self.model_provider.is_openai() {
+            self.model_provider.base_url = Some(base_url.clone());
+        }
+        if let Some(provider) = self.model_providers.get_mut("openai") {
+            provider.base_url = Some(base_url);
+        }
+    }
 }
 
 pub(crate) fn uses_deprecated_instructions_file(config_layer_stack: &ConfigLayerStack) -> bool {
@@ -3849,6 +3902,7 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                tool_output_head_tail_split: None,
                 agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
                 codex_home: fixture.codex_home(),
                 log_dir: fixture.codex_home().join("log"),
@@ -3873,6 +3927,8 @@ model_verbosity = "high"
                 include_apply_patch_tool: false,
                 web_search_mode: None,
                 use_experimental_unified_exec_tool: !cfg!(windows),
+                exec_session: ExecSessionMode::PerCall,
+                shell_pty: None,
                 ghost_snapshot: GhostSnapshotConfig::default(),
                 features: Features::with_defaults(),
                 suppress_unstable_features_warning: false,
@@ -3892,6 +3948,7 @@ model_verbosity = "high"
                 tui_alternate_screen: AltScreenMode::Auto,
                 tui_status_line: None,
                 otel: OtelConfig::default(),
+                external_tool_layers: ExternalToolLayers::default(),
             },
             o3_profile_config
         );
@@ -3936,6 +3993,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_head_tail_split: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             codex_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -3960,6 +4018,8 @@ model_verbosity = "high"
             include_apply_patch_tool: false,
             web_search_mode: None,
             use_experimental_unified_exec_tool: !cfg!(windows),
+            exec_session: ExecSessionMode::PerCall,
+            shell_pty: None,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             suppress_unstable_features_warning: false,
@@ -3979,6 +4039,7 @@ model_verbosity = "high"
             tui_alternate_screen: AltScreenMode::Auto,
             tui_status_line: None,
             otel: OtelConfig::default(),
+            external_tool_layers: ExternalToolLayers::default(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -4038,6 +4099,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_head_tail_split: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             codex_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -4062,6 +4124,8 @@ model_verbosity = "high"
             include_apply_patch_tool: false,
             web_search_mode: None,
             use_experimental_unified_exec_tool: !cfg!(windows),
+            exec_session: ExecSessionMode::PerCall,
+            shell_pty: None,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             suppress_unstable_features_warning: false,
@@ -4081,6 +4145,7 @@ model_verbosity = "high"
             tui_alternate_screen: AltScreenMode::Auto,
             tui_status_line: None,
             otel: OtelConfig::default(),
+            external_tool_layers: ExternalToolLayers::default(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -4126,6 +4191,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_head_tail_split: None,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             codex_home: fixture.codex_home(),
             log_dir: fixture.codex_home().join("log"),
@@ -4150,6 +4216,8 @@ model_verbosity = "high"
             include_apply_patch_tool: false,
             web_search_mode: None,
             use_experimental_unified_exec_tool: !cfg!(windows),
+            exec_session: ExecSessionMode::PerCall,
+            shell_pty: None,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             suppress_unstable_features_warning: false,
@@ -4169,6 +4237,7 @@ model_verbosity = "high"
             tui_alternate_screen: AltScreenMode::Auto,
             tui_status_line: None,
             otel: OtelConfig::default(),
+            external_tool_layers: ExternalToolLayers::default(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
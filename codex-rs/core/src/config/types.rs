@@ -315,6 +315,60 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// How the `shell` tool runs a thread's commands.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecSessionMode {
+    /// Start a fresh process for every command.
+    #[default]
+    PerCall,
+    /// Run commands in one long-lived shell per thread, so `cd` and exported variables persist.
+    Persistent,
+}
+
+pub const DEFAULT_SHELL_PTY_COLS: u16 = 80;
+pub const DEFAULT_SHELL_PTY_ROWS: u16 = 24;
+pub const DEFAULT_SHELL_PTY_TERM: &str = "xterm-256color";
+
+/// Terminal settings for `shell` tool commands run on a PTY.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ShellPtyToml {
+    /// Terminal width in columns (default 80).
+    pub cols: Option<u16>,
+    /// Terminal height in rows (default 24).
+    pub rows: Option<u16>,
+    /// `TERM` for the commands (default `xterm-256color`).
+    pub term: Option<String>,
+}
+
+/// Resolved `[shell_pty]` settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellPtyConfig {
+    pub cols: u16,
+    pub rows: u16,
+    pub term: String,
+}
+
+impl From<ShellPtyToml> for ShellPtyConfig {
+    fn from(toml: ShellPtyToml) -> Self {
+        Self {
+            cols: toml
+                .cols
+                .filter(|cols| *cols > 0)
+                .unwrap_or(DEFAULT_SHELL_PTY_COLS),
+            rows: toml
+                .rows
+                .filter(|rows| *rows > 0)
+                .unwrap_or(DEFAULT_SHELL_PTY_ROWS),
+            term: toml
+                .term
+                .filter(|term| !term.is_empty())
+                .unwrap_or_else(|| DEFAULT_SHELL_PTY_TERM.to_string()),
+        }
+    }
+}
+
 // ===== Analytics configuration =====
 
 /// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
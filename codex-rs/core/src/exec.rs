@@ -16,6 +16,7 @@ use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::types::ShellPtyConfig;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
@@ -32,7 +33,10 @@ use crate::sandboxing::SandboxPermissions;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use crate::text_encoding::bytes_to_string_smart;
+use codex_utils_pty::SpawnedProcess;
+use codex_utils_pty::TerminalSize;
 use codex_utils_pty::process_group::kill_child_process_group;
+use codex_utils_pty::spawn_pty_process_with_size;
 
 pub const DEFAULT_EXEC_COMMAND_TIMEOUT_MS: u64 = 10_000;
 
@@ -51,7 +55,7 @@ const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
 ///
 /// This mirrors unified exec's output cap so a single runaway command cannot
 /// OOM the process by dumping huge amounts of data to stdout/stderr.
-const EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
+pub(crate) const EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
 
 /// Limit the number of ExecCommandOutputDelta events emitted per exec call.
 /// Aggregation still collects full output; only the live event stream is capped.
@@ -245,6 +249,20 @@ pub(crate) async fn execute_exec_env(
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
 
+/// Like [`execute_exec_env`], but runs the command attached to a PTY (`[shell_pty]`). The
+/// command's stdout and stderr share the terminal, so all output is reported as stdout.
+pub(crate) async fn execute_exec_env_in_pty(
+    env: ExecEnv,
+    pty: &ShellPtyConfig,
+    stdout_stream: Option<StdoutStream>,
+) -> Result<ExecToolCallOutput> {
+    let sandbox = env.sandbox;
+    let start = Instant::now();
+    let raw_output_result = exec_in_pty(env, pty, stdout_stream).await;
+    let duration = start.elapsed();
+    finalize_exec_result(raw_output_result, sandbox, duration)
+}
+
 #[cfg(target_os = "windows")]
 fn extract_create_process_as_user_error_code(err: &str) -> Option<String> {
     let marker = "CreateProcessAsUserW failed: ";
@@ -593,7 +611,7 @@ impl StreamOutput<Vec<u8>> {
 }
 
 #[inline]
-fn append_capped(dst: &mut Vec<u8>, src: &[u8], max_bytes: usize) {
+pub(crate) fn append_capped(dst: &mut Vec<u8>, src: &[u8], max_bytes: usize) {
     if dst.len() >= max_bytes {
         return;
     }
@@ -858,6 +876,124 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     })
 }
 
+async fn exec_in_pty(
+    env: ExecEnv,
+    pty: &ShellPtyConfig,
+    stdout_stream: Option<StdoutStream>,
+) -> Result<RawExecToolCallOutput> {
+    let ExecEnv {
+        command,
+        cwd,
+        mut env,
+        expiration,
+        arg0,
+        ..
+    } = env;
+    let (program, args) = command.split_first().ok_or_else(|| {
+        CodexErr::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "command args are empty",
+        ))
+    })?;
+    env.insert("TERM".to_string(), pty.term.clone());
+    let size = TerminalSize {
+        rows: pty.rows,
+        cols: pty.cols,
+    };
+    let SpawnedProcess {
+        session,
+        mut output_rx,
+        mut exit_rx,
+    } = spawn_pty_process_with_size(program, args, &cwd, &env, &arg0, size)
+        .await
+        .map_err(|err| CodexErr::Io(io::Error::other(err.to_string())))?;
+
+    let mut output = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut emitted_deltas: usize = 0;
+    let expiration = expiration.wait();
+    tokio::pin!(expiration);
+    let (exit_status, timed_out) = loop {
+        tokio::select! {
+            chunk = output_rx.recv() => match chunk {
+                Ok(chunk) => {
+                    send_pty_output_delta(stdout_stream.as_ref(), &chunk, &mut emitted_deltas)
+                        .await;
+                    append_capped(&mut output, &chunk, EXEC_OUTPUT_MAX_BYTES);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                    let code = (&mut exit_rx).await.unwrap_or(-1);
+                    break (pty_exit_status(code), false);
+                }
+            },
+            code = &mut exit_rx => break (pty_exit_status(code.unwrap_or(-1)), false),
+            _ = &mut expiration => {
+                session.terminate();
+                break (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true);
+            }
+        }
+    };
+
+    // The PTY reader can still hold output written right before the process exited.
+    const PTY_DRAIN_QUIET_MS: u64 = 50;
+    while let Ok(Ok(chunk)) =
+        tokio::time::timeout(Duration::from_millis(PTY_DRAIN_QUIET_MS), output_rx.recv()).await
+    {
+        send_pty_output_delta(stdout_stream.as_ref(), &chunk, &mut emitted_deltas).await;
+        append_capped(&mut output, &chunk, EXEC_OUTPUT_MAX_BYTES);
+    }
+
+    let output = StreamOutput {
+        text: output,
+        truncated_after_lines: None,
+    };
+    Ok(RawExecToolCallOutput {
+        exit_status,
+        stdout: output.clone(),
+        stderr: StreamOutput {
+            text: Vec::new(),
+            truncated_after_lines: None,
+        },
+        aggregated_output: output,
+        timed_out,
+    })
+}
+
+async fn send_pty_output_delta(
+    stream: Option<&StdoutStream>,
+    chunk: &[u8],
+    emitted_deltas: &mut usize,
+) {
+    let Some(stream) = stream else {
+        return;
+    };
+    if *emitted_deltas >= MAX_EXEC_OUTPUT_DELTAS_PER_CALL {
+        return;
+    }
+    let event = Event {
+        id: stream.sub_id.clone(),
+        msg: EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+            call_id: stream.call_id.clone(),
+            stream: ExecOutputStream::Stdout,
+            chunk: chunk.to_vec(),
+        }),
+    };
+    #[allow(clippy::let_unit_value)]
+    let _ = stream.tx_event.send(event).await;
+    *emitted_deltas += 1;
+}
+
+#[cfg(unix)]
+fn pty_exit_status(code: i32) -> ExitStatus {
+    // Raw wait statuses keep the exit code in the second byte.
+    synthetic_exit_status((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn pty_exit_status(code: i32) -> ExitStatus {
+    synthetic_exit_status(code)
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -0,0 +1,546 @@
+//! Persistent shell sessions for the `shell` tool (`exec_session = "persistent"`).
+//!
+//! By default every shell tool call starts a fresh process, so `cd`, exported variables and
+//! activated virtualenvs are gone by the next call. In persistent mode a thread's commands run one
+//! at a time in a single long-lived shell attached to a PTY. The shell is keyed by thread id and
+//! outlives the turn, so embedders that resume a thread in the same process keep its state too.
+//!
+//! The shell is spawned under the sandbox of the call that starts it. Calls that need a different
+//! sandbox, such as escalated retries, run in a fresh process as before. Environment variables a
+//! call sets differently from earlier calls are exported before it runs. Output is capped like a
+//! fresh process's. [`reset_exec_session`] ends a thread's shell so its next call starts a new
+//! one.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use codex_utils_pty::ExecCommandSession;
+use codex_utils_pty::TerminalSize;
+use codex_utils_pty::spawn_pty_process_with_size;
+use tokio::sync::Mutex;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::bash::extract_bash_command;
+use crate::config::types::ShellPtyConfig;
+use crate::error::CodexErr;
+use crate::error::SandboxErr;
+use crate::exec::DEFAULT_EXEC_COMMAND_TIMEOUT_MS;
+use crate::exec::EXEC_OUTPUT_MAX_BYTES;
+use crate::exec::ExecExpiration;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::exec::StreamOutput;
+use crate::exec::append_capped;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::ExecEnv;
+use crate::shell::Shell;
+use crate::shell::ShellType;
+use crate::tools::runtimes::build_command_spec;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::ToolError;
+
+const DONE_MARKER: &str = "__CODEX_EXEC_DONE";
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+type SharedShell = Arc<Mutex<PersistentShell>>;
+
+fn sessions() -> &'static StdMutex<HashMap<String, SharedShell>> {
+    static SESSIONS: OnceLock<StdMutex<HashMap<String, SharedShell>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| StdMutex::new(HashMap::new()))
+}
+
+/// End `thread_id`'s persistent shell. Returns `false` when the thread has none.
+pub fn reset_exec_session(thread_id: &str) -> bool {
+    let removed = match sessions().lock() {
+        Ok(mut sessions) => sessions.remove(thread_id),
+        Err(err) => {
+            warn!("exec sessions mutex poisoned: {err}");
+            None
+        }
+    };
+    let Some(shell) = removed else {
+        return false;
+    };
+    // A call still running in the shell terminates it when it drops the last handle.
+    if let Ok(shell) = shell.try_lock() {
+        shell.process.terminate();
+    }
+    true
+}
+
+fn end_session(thread_id: &str, shell: &SharedShell) {
+    if let Ok(mut sessions) = sessions().lock()
+        && sessions
+            .get(thread_id)
+            .is_some_and(|current| Arc::ptr_eq(current, shell))
+    {
+        sessions.remove(thread_id);
+    }
+}
+
+/// Run `req` in the thread's persistent shell, starting one if needed.
+///
+/// Returns `None` when the call has to run in a fresh process instead: the command is not a
+/// POSIX shell script, the session shell is not a POSIX shell, or the attempt's sandbox differs
+/// from the one the persistent shell was started under.
+pub(crate) async fn run_in_persistent_shell(
+    thread_id: &str,
+    req: &ShellRequest,
+    attempt: &SandboxAttempt<'_>,
+    session_shell: &Shell,
+    pty: Option<&ShellPtyConfig>,
+) -> Result<Option<ExecToolCallOutput>, ToolError> {
+    let Some(shell_command) = persistent_shell_command(session_shell) else {
+        return Ok(None);
+    };
+    let Some(script) = command_script(&req.command) else {
+        return Ok(None);
+    };
+    let existing = sessions()
+        .lock()
+        .ok()
+        .and_then(|sessions| sessions.get(thread_id).cloned());
+    let shared = match existing {
+        Some(shared) => shared,
+        None => {
+            if req.sandbox_permissions.requires_escalated_permissions() {
+                return Ok(None);
+            }
+            let spec = build_command_spec(
+                &shell_command,
+                &req.cwd,
+                &req.env,
+                ExecExpiration::DefaultTimeout,
+                req.sandbox_permissions,
+                None,
+            )?;
+            let env = attempt
+                .env_for(spec)
+                .map_err(|err| ToolError::Codex(err.into()))?;
+            let snapshot = session_shell
+                .shell_snapshot()
+                .map(|snapshot| snapshot.path.clone());
+            let mut shell = PersistentShell::spawn(env, attempt.policy, snapshot, pty)
+                .await
+                .map_err(|err| ToolError::Codex(err.into()))?;
+            shell.env = req.env.clone();
+            let shared = Arc::new(Mutex::new(shell));
+            match sessions().lock() {
+                Ok(mut sessions) => Arc::clone(
+                    sessions
+                        .entry(thread_id.to_string())
+                        .or_insert_with(|| Arc::clone(&shared)),
+                ),
+                Err(_) => shared,
+            }
+        }
+    };
+
+    let mut shell = shared.lock().await;
+    if shell.sandbox != attempt.sandbox || shell.policy != *attempt.policy {
+        return Ok(None);
+    }
+    let Some(exports) = env_exports(&shell.env, &req.env) else {
+        return Ok(None);
+    };
+    shell.env.extend(req.env.clone());
+    let script = if shell.requested_cwd == req.cwd {
+        script
+    } else {
+        let Ok(cwd) = shlex::try_quote(&req.cwd.to_string_lossy()).map(|cwd| cwd.into_owned())
+        else {
+            return Ok(None);
+        };
+        shell.requested_cwd = req.cwd.clone();
+        format!("cd {cwd}\n{script}")
+    };
+    let script = format!("{exports}{script}");
+    let timeout = Duration::from_millis(req.timeout_ms.unwrap_or(DEFAULT_EXEC_COMMAND_TIMEOUT_MS));
+    let started = Instant::now();
+    let result = shell.run_script(&script, timeout).await;
+    let duration = started.elapsed();
+    drop(shell);
+
+    match result {
+        Ok(ScriptOutcome::Completed { output, exit_code }) => {
+            Ok(Some(exec_output(output, exit_code, duration, false)))
+        }
+        Ok(ScriptOutcome::Exited { output, exit_code }) => {
+            end_session(thread_id, &shared);
+            Ok(Some(exec_output(output, exit_code, duration, false)))
+        }
+        Ok(ScriptOutcome::TimedOut { output }) => {
+            // The shell is still busy with the command, so the next call starts a new one.
+            end_session(thread_id, &shared);
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout {
+                output: Box::new(exec_output(output, TIMEOUT_EXIT_CODE, duration, true)),
+            })))
+        }
+        Err(err) => {
+            end_session(thread_id, &shared);
+            Err(ToolError::Codex(err.into()))
+        }
+    }
+}
+
+/// The long-lived shell to start, without rc files that could print or change the prompt.
+fn persistent_shell_command(session_shell: &Shell) -> Option<Vec<String>> {
+    let path = session_shell.shell_path.to_string_lossy().into_owned();
+    match session_shell.shell_type {
+        ShellType::Bash => Some(vec![path, "--noprofile".to_string(), "--norc".to_string()]),
+        ShellType::Zsh => Some(vec![path, "-f".to_string()]),
+        ShellType::Sh => Some(vec![path]),
+        ShellType::PowerShell | ShellType::Cmd => None,
+    }
+}
+
+/// `export` lines for the variables in `env` the shell doesn't have yet, or has with another
+/// value. Variables the thread exported itself are left alone unless the call sets them. `None`
+/// when one of them cannot be exported safely.
+fn env_exports(current: &HashMap<String, String>, env: &HashMap<String, String>) -> Option<String> {
+    let mut changed: Vec<(&String, &String)> = env
+        .iter()
+        .filter(|(key, value)| current.get(*key) != Some(*value))
+        .collect();
+    changed.sort();
+    let mut exports = String::new();
+    for (key, value) in changed {
+        let valid_name = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return None;
+        }
+        let value = shlex::try_quote(value).ok()?;
+        exports.push_str(&format!("export {key}={value}\n"));
+    }
+    Some(exports)
+}
+
+/// The script to type into the shell: the `-c` script of a `bash -lc` style call, or the argv
+/// quoted as one command.
+fn command_script(command: &[String]) -> Option<String> {
+    match extract_bash_command(command) {
+        Some((_, script)) => Some(script.to_string()),
+        None => shlex::try_join(command.iter().map(String::as_str)).ok(),
+    }
+}
+
+fn exec_output(
+    output: String,
+    exit_code: i32,
+    duration: Duration,
+    timed_out: bool,
+) -> ExecToolCallOutput {
+    ExecToolCallOutput {
+        exit_code,
+        stdout: StreamOutput::new(output.clone()),
+        aggregated_output: StreamOutput::new(output),
+        duration,
+        timed_out,
+        ..Default::default()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ScriptOutcome {
+    Completed {
+        output: String,
+        exit_code: i32,
+    },
+    /// The script ended the shell itself, e.g. with `exit`.
+    Exited {
+        output: String,
+        exit_code: i32,
+    },
+    TimedOut {
+        output: String,
+    },
+}
+
+struct PersistentShell {
+    process: ExecCommandSession,
+    output_rx: broadcast::Receiver<Vec<u8>>,
+    sandbox: SandboxType,
+    policy: SandboxPolicy,
+    /// The cwd the previous call asked for. A call asking for another one `cd`s there first;
+    /// otherwise commands run wherever the last one left the shell.
+    requested_cwd: PathBuf,
+    /// The environment calls asked for so far. A call setting other values exports them first.
+    env: HashMap<String, String>,
+    next_marker: u64,
+}
+
+impl PersistentShell {
+    async fn spawn(
+        mut env: ExecEnv,
+        policy: &SandboxPolicy,
+        snapshot: Option<PathBuf>,
+        pty: Option<&ShellPtyConfig>,
+    ) -> io::Result<Self> {
+        let size = match pty {
+            Some(pty) => {
+                env.env.insert("TERM".to_string(), pty.term.clone());
+                TerminalSize {
+                    rows: pty.rows,
+                    cols: pty.cols,
+                }
+            }
+            None => TerminalSize::default(),
+        };
+        let (program, args) = env
+            .command
+            .split_first()
+            .ok_or_else(|| io::Error::other("missing persistent shell command"))?;
+        let spawned =
+            spawn_pty_process_with_size(program, args, &env.cwd, &env.env, &env.arg0, size)
+                .await
+                .map_err(|err| io::Error::other(format!("failed to start shell: {err}")))?;
+        let mut shell = Self {
+            process: spawned.session,
+            output_rx: spawned.output_rx,
+            sandbox: env.sandbox,
+            policy: policy.clone(),
+            requested_cwd: env.cwd.clone(),
+            env: HashMap::new(),
+            next_marker: 0,
+        };
+
+        // Keep the PTY from echoing commands back and the shell from printing prompts.
+        let mut setup = "stty -echo 2>/dev/null; PS1=''; PS2=''; unset PROMPT_COMMAND".to_string();
+        if let Some(snapshot) = snapshot
+            && snapshot.exists()
+        {
+            setup.push_str(&format!("; . \"{}\"", snapshot.display()));
+        }
+        let ready = shell
+            .run_script(
+                &setup,
+                Duration::from_millis(DEFAULT_EXEC_COMMAND_TIMEOUT_MS),
+            )
+            .await?;
+        match ready {
+            ScriptOutcome::Completed { .. } => Ok(shell),
+            ScriptOutcome::Exited { output, .. } | ScriptOutcome::TimedOut { output } => Err(
+                io::Error::other(format!("shell did not start: {}", output.trim())),
+            ),
+        }
+    }
+
+    /// Type `script` into the shell and wait for the marker line that reports its exit status.
+    async fn run_script(&mut self, script: &str, timeout: Duration) -> io::Result<ScriptOutcome> {
+        while !matches!(
+            self.output_rx.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty | broadcast::error::TryRecvError::Closed)
+        ) {}
+
+        self.next_marker += 1;
+        let marker = format!("{DONE_MARKER}_{}_{}:", std::process::id(), self.next_marker);
+        // The marker is printed in two halves so an echoed command line never contains it.
+        let (head, tail) = marker.split_at(DONE_MARKER.len());
+        let input = format!("{script}\nprintf '%s%s%s\\n' '{head}' '{tail}' \"$?\"\n");
+        self.process
+            .writer_sender()
+            .send(input.into_bytes())
+            .await
+            .map_err(|_| io::Error::other("shell stdin closed"))?;
+
+        let deadline = Instant::now() + timeout;
+        let mut output = Vec::new();
+        let mut received = 0;
+        // The last bytes received, enough to hold a marker line split across chunks. Only these
+        // are searched, so a long-running command's output is not rescanned per chunk.
+        let mut tail = Vec::new();
+        loop {
+            let wait = deadline
+                .saturating_duration_since(Instant::now())
+                .min(EXIT_POLL_INTERVAL);
+            match tokio::time::timeout(wait, self.output_rx.recv()).await {
+                Ok(Ok(chunk)) => {
+                    append_capped(&mut output, &chunk, EXEC_OUTPUT_MAX_BYTES);
+                    received += chunk.len();
+                    tail.extend_from_slice(&chunk);
+                    match marker_line(&tail, marker.as_bytes()) {
+                        MarkerLine::Complete { start, exit_code } => {
+                            output.truncate(received - tail.len() + start);
+                            return Ok(ScriptOutcome::Completed {
+                                output: normalize_output(&String::from_utf8_lossy(&output)),
+                                exit_code,
+                            });
+                        }
+                        MarkerLine::Started { start } => {
+                            tail.drain(..start);
+                        }
+                        MarkerLine::Missing => {
+                            tail.drain(..tail.len().saturating_sub(marker.len()));
+                        }
+                    }
+                }
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => {}
+                Ok(Err(broadcast::error::RecvError::Closed)) => {
+                    return Ok(self.exited(&output));
+                }
+                Err(_) if self.process.has_exited() => return Ok(self.exited(&output)),
+                Err(_) if Instant::now() >= deadline => {
+                    return Ok(ScriptOutcome::TimedOut {
+                        output: normalize_output(&String::from_utf8_lossy(&output)),
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn exited(&self, output: &[u8]) -> ScriptOutcome {
+        ScriptOutcome::Exited {
+            output: normalize_output(&String::from_utf8_lossy(output)),
+            exit_code: self.process.exit_code().unwrap_or(-1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum MarkerLine {
+    Missing,
+    /// The marker arrived but its exit status is still on the way.
+    Started {
+        start: usize,
+    },
+    Complete {
+        start: usize,
+        exit_code: i32,
+    },
+}
+
+/// Where the marker line starts in `tail`, and the exit code it reports once it has arrived.
+fn marker_line(tail: &[u8], marker: &[u8]) -> MarkerLine {
+    let Some(start) = tail
+        .windows(marker.len())
+        .position(|window| window == marker)
+    else {
+        return MarkerLine::Missing;
+    };
+    let status = &tail[start + marker.len()..];
+    match status.iter().position(|byte| *byte == b'\n') {
+        Some(end) => MarkerLine::Complete {
+            start,
+            exit_code: String::from_utf8_lossy(&status[..end])
+                .trim()
+                .parse()
+                .unwrap_or(-1),
+        },
+        None => MarkerLine::Started { start },
+    }
+}
+
+/// PTYs turn `\n` into `\r\n`; report output the way a pipe would have.
+fn normalize_output(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use codex_protocol::config_types::WindowsSandboxLevel;
+    use codex_protocol::models::SandboxPermissions;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn persistent_shell_keeps_cwd_and_environment_between_scripts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("nested")).expect("nested dir");
+        let env = ExecEnv {
+            command: vec!["/bin/sh".to_string()],
+            cwd: dir.path().to_path_buf(),
+            env: HashMap::from([(
+                "PATH".to_string(),
+                std::env::var("PATH").unwrap_or_default(),
+            )]),
+            expiration: ExecExpiration::DefaultTimeout,
+            sandbox: SandboxType::None,
+            windows_sandbox_level: WindowsSandboxLevel::Disabled,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+        };
+        let timeout = Duration::from_secs(10);
+        let mut shell = PersistentShell::spawn(env, &SandboxPolicy::DangerFullAccess, None, None)
+            .await
+            .expect("spawn shell");
+
+        let first = shell
+            .run_script("cd nested && export GREETING=hello", timeout)
+            .await
+            .expect("first script");
+        assert_eq!(
+            first,
+            ScriptOutcome::Completed {
+                output: String::new(),
+                exit_code: 0,
+            }
+        );
+
+        let second = shell
+            .run_script(
+                "echo \"$GREETING from $(basename \"$PWD\")\"; false",
+                timeout,
+            )
+            .await
+            .expect("second script");
+        assert_eq!(
+            second,
+            ScriptOutcome::Completed {
+                output: "hello from nested\n".to_string(),
+                exit_code: 1,
+            }
+        );
+
+        let exited = shell.run_script("exit 3", timeout).await.expect("exit");
+        assert!(matches!(exited, ScriptOutcome::Exited { .. }));
+    }
+
+    #[test]
+    fn marker_line_waits_for_the_exit_status() {
+        let marker = b"__DONE_1:";
+        assert_eq!(marker_line(b"output\n__DO", marker), MarkerLine::Missing);
+        assert_eq!(
+            marker_line(b"t\n__DONE_1:4", marker),
+            MarkerLine::Started { start: 2 }
+        );
+        assert_eq!(
+            marker_line(b"t\n__DONE_1:42\r\n", marker),
+            MarkerLine::Complete {
+                start: 2,
+                exit_code: 42,
+            }
+        );
+    }
+
+    #[test]
+    fn env_exports_set_only_new_and_changed_variables() {
+        let current = HashMap::from([
+            ("KEEP".to_string(), "same".to_string()),
+            ("MODE".to_string(), "old".to_string()),
+        ]);
+        let env = HashMap::from([
+            ("KEEP".to_string(), "same".to_string()),
+            ("MODE".to_string(), "new value".to_string()),
+            ("TOKEN".to_string(), "abc".to_string()),
+        ]);
+        assert_eq!(
+            env_exports(&current, &env),
+            Some("export MODE='new value'\nexport TOKEN=abc\n".to_string())
+        );
+
+        let invalid = HashMap::from([("NOT-A-NAME".to_string(), "x".to_string())]);
+        assert_eq!(env_exports(&current, &invalid), None);
+    }
+}
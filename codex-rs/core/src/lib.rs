@@ -32,6 +32,8 @@ mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
+mod exec_session;
+pub use exec_session::reset_exec_session;
 mod exec_policy;
 pub mod features;
 mod file_watcher;
@@ -92,6 +94,7 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod project_doc;
+pub mod prompt_preview;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -104,14 +107,23 @@ pub mod terminal;
 mod tools;
 pub use tools::context::ToolInvocation;
 pub use tools::context::ToolOutput;
+pub use tools::context::ToolOutputSender;
+pub use tools::context::ToolOutputStream;
+pub use tools::context::ToolOutputStreamEvent;
 pub use tools::context::ToolPayload;
+pub use tools::direct::DirectToolCall;
+pub use tools::direct::DirectToolOutput;
+pub use tools::direct::call_builtin_tool;
+pub use tools::direct::run_direct_command;
+pub use tools::registry::ALL_TOOLS_INTERCEPTOR;
 pub use tools::registry::ExternalInterceptorRegistration;
+pub use tools::registry::ExternalToolFilter;
+pub use tools::registry::ExternalToolLayers;
+pub use tools::registry::ExternalToolLimit;
 pub use tools::registry::ExternalToolRegistration;
 pub use tools::registry::ToolHandler;
 pub use tools::registry::ToolInterceptor;
 pub use tools::registry::ToolKind;
-pub use tools::registry::set_pending_external_interceptors;
-pub use tools::registry::set_pending_external_tools;
 pub use tools::spec::create_function_tool_spec_from_schema;
 pub mod turn_diff_tracker;
 mod turn_metadata;
@@ -135,7 +147,13 @@ pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
 pub use rollout::list::read_session_meta_line;
 pub use rollout::rollout_date_parts;
+pub use rollout::session_index::append_thread_name;
 pub use rollout::session_index::find_thread_names_by_ids;
+pub use rollout::store::JsonlRolloutStore;
+pub use rollout::store::RolloutStore;
+pub use rollout::store::SqliteRolloutStore;
+pub use rollout::store::ThreadListQuery;
+pub use rollout::store::rollout_store_for_config;
 mod function_tool;
 pub use function_tool::FunctionCallError;
 mod state;
@@ -0,0 +1,173 @@
+//! Offline construction of the first-turn model request for a new thread.
+//!
+//! Mirrors the instruction assembly performed by `Session::build_initial_context`
+//! and the tool-spec generation done per turn, without starting a session,
+//! connecting MCP servers, or contacting the model provider.
+
+use crate::client_common::Prompt;
+use crate::config::Config;
+use crate::config::resolve_web_search_mode_for_turn;
+use crate::environment_context::EnvironmentContext;
+use crate::error::Result;
+use crate::exec_policy::load_exec_policy;
+use crate::features::Feature;
+use crate::instructions::UserInstructions;
+use crate::models_manager::manager::ModelsManager;
+use crate::project_doc::get_user_instructions;
+use crate::shell;
+use crate::skills::SkillsManager;
+use crate::tools::spec::ToolsConfig;
+use crate::tools::spec::ToolsConfigParams;
+use crate::tools::spec::build_specs;
+use crate::tools::spec::create_tools_json_for_responses_api;
+use codex_execpolicy::Policy;
+use codex_protocol::dynamic_tools::DynamicToolSpec;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::DeveloperInstructions;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use serde_json::Value;
+
+/// The Responses API payload a fresh thread would send for its first turn.
+#[derive(Debug, Clone)]
+pub struct PromptPreview {
+    pub model: String,
+    pub instructions: String,
+    pub input: Vec<ResponseItem>,
+    pub tools: Vec<Value>,
+    pub parallel_tool_calls: bool,
+    pub output_schema: Option<Value>,
+}
+
+/// Build the first-turn request for `config` without any network access.
+///
+/// `extra_context` items are inserted after the standard initial context and before the user
+/// turn, which is where callers inject retrieved memories. MCP tools are omitted because
+/// listing them requires starting the configured servers. The config's external tool layers
+/// are applied, matching a real session start.
+pub async fn build_prompt_preview(
+    config: &Config,
+    user_input: Vec<UserInput>,
+    extra_context: Vec<ResponseItem>,
+    dynamic_tools: &[DynamicToolSpec],
+    output_schema: Option<Value>,
+) -> Result<PromptPreview> {
+    let model = ModelsManager::get_model_offline(config.model.as_deref());
+    let model_info = ModelsManager::construct_model_info_offline(&model, config);
+    let base_instructions = config
+        .base_instructions
+        .clone()
+        .unwrap_or_else(|| model_info.get_model_instructions(config.personality));
+
+    let enabled_skills = SkillsManager::new(config.codex_home.clone())
+        .skills_for_config(config)
+        .enabled_skills();
+    let user_instructions = get_user_instructions(config, Some(&enabled_skills)).await;
+    let exec_policy = load_exec_policy(&config.config_layer_stack)
+        .await
+        .unwrap_or_else(|_| Policy::empty());
+
+    let mut input = vec![ResponseItem::from(DeveloperInstructions::from_policy(
+        config.sandbox_policy.get(),
+        config.approval_policy.value(),
+        &exec_policy,
+        config.features.enabled(Feature::RequestRule),
+        &config.cwd,
+    ))];
+    if let Some(developer_instructions) = config.developer_instructions.as_deref() {
+        input.push(DeveloperInstructions::new(developer_instructions.to_string()).into());
+    }
+    if let Some(user_instructions) = user_instructions {
+        input.push(
+            UserInstructions {
+                text: user_instructions,
+                directory: config.cwd.to_string_lossy().into_owned(),
+            }
+            .into(),
+        );
+    }
+    input.push(ResponseItem::from(EnvironmentContext::new(
+        Some(config.cwd.clone()),
+        shell::default_user_shell(),
+    )));
+    input.extend(extra_context);
+    if !user_input.is_empty() {
+        input.push(ResponseInputItem::from(user_input).into());
+    }
+
+    let web_search_mode =
+        resolve_web_search_mode_for_turn(config.web_search_mode, config.sandbox_policy.get());
+    let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        model_info: &model_info,
+        features: &config.features,
+        web_search_mode: Some(web_search_mode),
+    });
+    let (specs, _registry) =
+        build_specs(&tools_config, None, dynamic_tools).build(&config.external_tool_layers);
+
+    let prompt = Prompt {
+        input,
+        tools: specs.into_iter().map(|configured| configured.spec).collect(),
+        parallel_tool_calls: model_info.supports_parallel_tool_calls,
+        base_instructions: BaseInstructions {
+            text: base_instructions,
+        },
+        personality: config.personality,
+        output_schema,
+    };
+
+    Ok(PromptPreview {
+        model,
+        instructions: prompt.base_instructions.text.clone(),
+        input: prompt.get_formatted_input(),
+        tools: create_tools_json_for_responses_api(&prompt.tools)?,
+        parallel_tool_calls: prompt.parallel_tool_calls,
+        output_schema: prompt.output_schema,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn preview_ends_with_user_turn_and_lists_builtin_tools() {
+        let codex_home = tempdir().expect("temp dir");
+        let config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("load default test config");
+
+        let preview = build_prompt_preview(
+            &config,
+            vec![UserInput::Text {
+                text: "hello".to_string(),
+                text_elements: Vec::new(),
+            }],
+            Vec::new(),
+            &[],
+            None,
+        )
+        .await
+        .expect("build preview");
+
+        assert!(!preview.instructions.is_empty());
+        assert!(!preview.tools.is_empty());
+        let Some(ResponseItem::Message { role, content, .. }) = preview.input.last() else {
+            panic!("expected trailing user message");
+        };
+        assert_eq!(role, "user");
+        assert_eq!(
+            content,
+            &vec![ContentItem::InputText {
+                text: "hello".to_string()
+            }]
+        );
+    }
+}
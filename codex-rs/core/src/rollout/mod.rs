@@ -13,6 +13,7 @@ pub(crate) mod metadata;
 pub(crate) mod policy;
 pub mod recorder;
 pub(crate) mod session_index;
+pub mod store;
 pub(crate) mod truncation;
 
 pub use codex_protocol::protocol::SessionMeta;
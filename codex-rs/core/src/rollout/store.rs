@@ -0,0 +1,218 @@
+//! Storage backends for listing and locating recorded threads.
+//!
+//! Rollouts are always written as JSONL files under `sessions/`. What differs is
+//! how they are found again: [`JsonlRolloutStore`] walks the directory tree, while
+//! [`SqliteRolloutStore`] queries the state database, which indexes threads by
+//! id, update time and cwd so listing thousands of sessions doesn't read each
+//! file. [`rollout_store_for_config`] picks the backend from the `sqlite`
+//! feature flag.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::SessionSource;
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::features::Feature;
+use crate::rollout::list::Cursor;
+use crate::rollout::list::ThreadSortKey;
+use crate::rollout::list::ThreadsPage;
+use crate::rollout::list::build_next_cursor;
+use crate::rollout::list::find_thread_path_by_id_str;
+use crate::rollout::list::get_threads;
+use crate::state_db;
+use crate::state_db::StateDbHandle;
+
+/// Filters and pagination for [`RolloutStore::list_threads`].
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadListQuery<'a> {
+    pub page_size: usize,
+    pub cursor: Option<&'a Cursor>,
+    pub sort_key: ThreadSortKey,
+    /// Empty means every source.
+    pub allowed_sources: &'a [SessionSource],
+    pub model_providers: Option<&'a [String]>,
+    /// Only threads recorded with exactly this working directory.
+    pub cwd: Option<&'a Path>,
+}
+
+impl ThreadListQuery<'_> {
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size,
+            cursor: None,
+            sort_key: ThreadSortKey::UpdatedAt,
+            allowed_sources: &[],
+            model_providers: None,
+            cwd: None,
+        }
+    }
+}
+
+#[async_trait]
+pub trait RolloutStore: Send + Sync {
+    /// Short name of the backend, for diagnostics.
+    fn name(&self) -> &'static str;
+
+    async fn list_threads(&self, query: ThreadListQuery<'_>) -> io::Result<ThreadsPage>;
+
+    async fn find_thread_path(&self, thread_id: &str) -> io::Result<Option<PathBuf>>;
+}
+
+/// Finds threads by scanning the rollout files under `sessions/`.
+pub struct JsonlRolloutStore {
+    codex_home: PathBuf,
+    default_provider: String,
+}
+
+impl JsonlRolloutStore {
+    pub fn new(codex_home: PathBuf, default_provider: String) -> Self {
+        Self {
+            codex_home,
+            default_provider,
+        }
+    }
+}
+
+#[async_trait]
+impl RolloutStore for JsonlRolloutStore {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    async fn list_threads(&self, query: ThreadListQuery<'_>) -> io::Result<ThreadsPage> {
+        let mut page = get_threads(
+            &self.codex_home,
+            query.page_size,
+            query.cursor,
+            query.sort_key,
+            query.allowed_sources,
+            query.model_providers,
+            &self.default_provider,
+        )
+        .await?;
+        let Some(cwd) = query.cwd else {
+            return Ok(page);
+        };
+
+        // The files carry no cwd index, so keep reading pages until enough match.
+        let mut items = Vec::new();
+        let mut num_scanned_files = 0;
+        loop {
+            num_scanned_files += page.num_scanned_files;
+            let page_items = std::mem::take(&mut page.items);
+            for item in page_items {
+                if item.cwd.as_deref() == Some(cwd) {
+                    items.push(item);
+                }
+                if items.len() == query.page_size {
+                    return Ok(ThreadsPage {
+                        next_cursor: build_next_cursor(&items, query.sort_key),
+                        items,
+                        num_scanned_files,
+                        reached_scan_cap: page.reached_scan_cap,
+                    });
+                }
+            }
+            let Some(cursor) = page.next_cursor.take() else {
+                return Ok(ThreadsPage {
+                    items,
+                    next_cursor: None,
+                    num_scanned_files,
+                    reached_scan_cap: page.reached_scan_cap,
+                });
+            };
+            page = get_threads(
+                &self.codex_home,
+                query.page_size,
+                Some(&cursor),
+                query.sort_key,
+                query.allowed_sources,
+                query.model_providers,
+                &self.default_provider,
+            )
+            .await?;
+        }
+    }
+
+    async fn find_thread_path(&self, thread_id: &str) -> io::Result<Option<PathBuf>> {
+        find_thread_path_by_id_str(&self.codex_home, thread_id).await
+    }
+}
+
+/// Finds threads through the SQLite state database.
+pub struct SqliteRolloutStore {
+    runtime: StateDbHandle,
+}
+
+impl SqliteRolloutStore {
+    pub fn new(runtime: StateDbHandle) -> Self {
+        Self { runtime }
+    }
+}
+
+#[async_trait]
+impl RolloutStore for SqliteRolloutStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn list_threads(&self, query: ThreadListQuery<'_>) -> io::Result<ThreadsPage> {
+        let anchor = state_db::cursor_to_anchor(query.cursor);
+        let allowed_sources: Vec<String> = query
+            .allowed_sources
+            .iter()
+            .map(|source| match serde_json::to_value(source) {
+                Ok(Value::String(source)) => source,
+                Ok(other) => other.to_string(),
+                Err(_) => String::new(),
+            })
+            .collect();
+        let page = self
+            .runtime
+            .list_threads_in_cwd(
+                query.page_size,
+                anchor.as_ref(),
+                match query.sort_key {
+                    ThreadSortKey::CreatedAt => codex_state::SortKey::CreatedAt,
+                    ThreadSortKey::UpdatedAt => codex_state::SortKey::UpdatedAt,
+                },
+                &allowed_sources,
+                query.model_providers,
+                false,
+                query.cwd,
+            )
+            .await
+            .map_err(io::Error::other)?;
+        Ok(page.into())
+    }
+
+    async fn find_thread_path(&self, thread_id: &str) -> io::Result<Option<PathBuf>> {
+        let Ok(thread_id) = ThreadId::from_string(thread_id) else {
+            return Ok(None);
+        };
+        self.runtime
+            .find_rollout_path_by_id(thread_id, Some(false))
+            .await
+            .map_err(io::Error::other)
+    }
+}
+
+/// The SQLite store when the `sqlite` feature is enabled and its database is ready, otherwise
+/// the JSONL store.
+pub async fn rollout_store_for_config(config: &Config) -> Arc<dyn RolloutStore> {
+    if config.features.enabled(Feature::Sqlite)
+        && let Some(runtime) = state_db::get_state_db(config, None).await
+    {
+        return Arc::new(SqliteRolloutStore::new(runtime));
+    }
+    Arc::new(JsonlRolloutStore::new(
+        config.codex_home.clone(),
+        config.model_provider_id.clone(),
+    ))
+}
@@ -26,6 +26,10 @@ use crate::rollout::list::get_threads;
 use crate::rollout::list::read_head_for_summary;
 use crate::rollout::recorder::RolloutRecorder;
 use crate::rollout::rollout_date_parts;
+use crate::rollout::store::JsonlRolloutStore;
+use crate::rollout::store::RolloutStore;
+use crate::rollout::store::SqliteRolloutStore;
+use crate::rollout::store::ThreadListQuery;
 use anyhow::Result;
 use codex_protocol::ThreadId;
 use codex_protocol::models::ContentItem;
@@ -1476,3 +1480,109 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn jsonl_store_filters_threads_by_cwd_across_pages() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    for (minute, cwd) in [
+        (1, "/repo/a"),
+        (2, "/repo/b"),
+        (3, "/repo/a"),
+        (4, "/repo/b"),
+    ] {
+        let ts = format!("2025-04-02T10-0{minute}-00");
+        let uuid = Uuid::from_u128(400 + minute);
+        let payload = serde_json::json!({
+            "id": uuid,
+            "timestamp": ts,
+            "cwd": cwd,
+            "originator": "test_originator",
+            "cli_version": "test_version",
+            "source": "cli",
+            "model_provider": TEST_PROVIDER,
+        });
+        write_session_file_with_meta_payload(home, &ts, uuid, payload).unwrap();
+    }
+
+    let store = JsonlRolloutStore::new(home.to_path_buf(), TEST_PROVIDER.to_string());
+    let cwd = Path::new("/repo/a");
+    let first = store
+        .list_threads(ThreadListQuery {
+            cwd: Some(cwd),
+            ..ThreadListQuery::new(1)
+        })
+        .await
+        .unwrap();
+    let second = store
+        .list_threads(ThreadListQuery {
+            cwd: Some(cwd),
+            cursor: first.next_cursor.as_ref(),
+            ..ThreadListQuery::new(1)
+        })
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = first
+        .items
+        .iter()
+        .chain(&second.items)
+        .map(|item| item.thread_id)
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            Some(thread_id_from_uuid(Uuid::from_u128(403))),
+            Some(thread_id_from_uuid(Uuid::from_u128(401))),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sqlite_store_lists_threads_by_cwd() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let thread_id = thread_id_from_uuid(Uuid::from_u128(501));
+    let rollout_path = home.join(format!(
+        "sessions/2025/01/03/rollout-2025-01-03T12-00-00-{thread_id}.jsonl"
+    ));
+    // The helper records the thread with `home` as its cwd.
+    insert_state_db_thread(home, thread_id, rollout_path.as_path(), false).await;
+    let runtime =
+        codex_state::StateRuntime::init(home.to_path_buf(), TEST_PROVIDER.to_string(), None)
+            .await
+            .unwrap();
+    let store = SqliteRolloutStore::new(runtime);
+
+    let matching = store
+        .list_threads(ThreadListQuery {
+            cwd: Some(home),
+            ..ThreadListQuery::new(10)
+        })
+        .await
+        .unwrap();
+    let elsewhere = store
+        .list_threads(ThreadListQuery {
+            cwd: Some(Path::new("/somewhere/else")),
+            ..ThreadListQuery::new(10)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        matching
+            .items
+            .iter()
+            .map(|item| item.path.clone())
+            .collect::<Vec<_>>(),
+        vec![rollout_path.clone()]
+    );
+    assert_eq!(elsewhere.items.len(), 0);
+    assert_eq!(
+        store
+            .find_thread_path(&thread_id.to_string())
+            .await
+            .unwrap(),
+        Some(rollout_path)
+    );
+}
@@ -6,11 +6,13 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+use crate::config::types::ShellPtyConfig;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
+use crate::exec::execute_exec_env_in_pty;
 use crate::landlock::create_linux_sandbox_command_args;
 use crate::protocol::SandboxPolicy;
 #[cfg(target_os = "macos")]
@@ -210,3 +212,12 @@ pub async fn execute_env(
 ) -> crate::error::Result<ExecToolCallOutput> {
     execute_exec_env(env, policy, stdout_stream).await
 }
+
+/// Like [`execute_env`], with the command attached to a PTY.
+pub(crate) async fn execute_env_in_pty(
+    env: ExecEnv,
+    pty: &ShellPtyConfig,
+    stdout_stream: Option<StdoutStream>,
+) -> crate::error::Result<ExecToolCallOutput> {
+    execute_exec_env_in_pty(env, pty, stdout_stream).await
+}
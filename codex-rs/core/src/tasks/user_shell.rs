@@ -226,6 +226,7 @@ pub(crate) async fn execute_user_shell_command(
                         formatted_output: format_exec_output_str(
                             &output,
                             turn_context.truncation_policy,
+                            turn_context.tool_output_split(),
                         ),
                     }),
                 )
@@ -265,6 +266,7 @@ pub(crate) async fn execute_user_shell_command(
                         formatted_output: format_exec_output_str(
                             &exec_output,
                             turn_context.truncation_policy,
+                            turn_context.tool_output_split(),
                         ),
                     }),
                 )
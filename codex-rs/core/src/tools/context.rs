@@ -1,5 +1,7 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::SandboxPolicy;
 use crate::tools::TELEMETRY_PREVIEW_MAX_BYTES;
 use crate::tools::TELEMETRY_PREVIEW_MAX_LINES;
 use crate::tools::TELEMETRY_PREVIEW_TRUNCATION_NOTICE;
@@ -13,6 +15,7 @@ use codex_utils_string::take_bytes_at_char_boundary;
 use std::borrow::Cow;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio::sync::mpsc;
 
 pub type SharedTurnDiffTracker = Arc<Mutex<TurnDiffTracker>>;
 
@@ -26,6 +29,28 @@ pub struct ToolInvocation {
     pub payload: ToolPayload,
 }
 
+impl ToolInvocation {
+    /// Identifier of the thread that issued this tool call.
+    pub fn thread_id(&self) -> String {
+        self.session.conversation_id.to_string()
+    }
+
+    /// Working directory of the turn that issued this tool call.
+    pub fn cwd(&self) -> &std::path::Path {
+        &self.turn.cwd
+    }
+
+    /// Sandbox the turn runs commands under.
+    pub fn sandbox_policy(&self) -> &SandboxPolicy {
+        &self.turn.sandbox_policy
+    }
+
+    /// Helper executable the Linux sandbox runs commands through, when configured.
+    pub fn codex_linux_sandbox_exe(&self) -> Option<&std::path::Path> {
+        self.turn.codex_linux_sandbox_exe.as_deref()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum ToolPayload {
     Function {
@@ -66,6 +91,58 @@ pub enum ToolOutput {
     Mcp {
         result: Result<CallToolResult, String>,
     },
+    /// Output the tool is still producing. The registry forwards each chunk to clients as it
+    /// arrives and hands interceptors and the model the finished output.
+    Stream { stream: ToolOutputStream },
+}
+
+/// One step of a [ToolOutputStream].
+pub enum ToolOutputStreamEvent {
+    Chunk(String),
+    Finished(Result<ToolOutput, FunctionCallError>),
+}
+
+/// Receiving half of a streaming tool output.
+#[derive(Clone)]
+pub struct ToolOutputStream {
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<ToolOutputStreamEvent>>>,
+}
+
+/// Sending half of a streaming tool output, held by the tool while it runs.
+pub struct ToolOutputSender {
+    sender: mpsc::UnboundedSender<ToolOutputStreamEvent>,
+}
+
+impl ToolOutputStream {
+    pub fn channel() -> (ToolOutputSender, ToolOutputStream) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            ToolOutputSender { sender },
+            ToolOutputStream {
+                receiver: Arc::new(Mutex::new(receiver)),
+            },
+        )
+    }
+
+    /// The next chunk or the final result, or `None` once the sender is dropped.
+    pub async fn next_event(&self) -> Option<ToolOutputStreamEvent> {
+        self.receiver.lock().await.recv().await
+    }
+}
+
+impl ToolOutputSender {
+    /// Returns `false` once nobody is listening anymore.
+    pub fn chunk(&self, chunk: impl Into<String>) -> bool {
+        self.sender
+            .send(ToolOutputStreamEvent::Chunk(chunk.into()))
+            .is_ok()
+    }
+
+    /// Ends the stream with the output handed to the model. Dropping the sender instead ends it
+    /// with the concatenated chunks.
+    pub fn finish(self, result: Result<ToolOutput, FunctionCallError>) {
+        let _ = self.sender.send(ToolOutputStreamEvent::Finished(result));
+    }
 }
 
 impl ToolOutput {
@@ -75,6 +152,7 @@ impl ToolOutput {
                 telemetry_preview(&body.to_text().unwrap_or_default())
             }
             ToolOutput::Mcp { result } => format!("{result:?}"),
+            ToolOutput::Stream { .. } => String::new(),
         }
     }
 
@@ -82,6 +160,7 @@ impl ToolOutput {
         match self {
             ToolOutput::Function { success, .. } => success.unwrap_or(true),
             ToolOutput::Mcp { result } => result.is_ok(),
+            ToolOutput::Stream { .. } => true,
         }
     }
 
@@ -113,6 +192,15 @@ impl ToolOutput {
                 call_id: call_id.to_string(),
                 result,
             },
+            // The registry resolves streams before building the response; an unresolved one has
+            // nothing to report yet.
+            ToolOutput::Stream { .. } => ResponseInputItem::FunctionCallOutput {
+                call_id: call_id.to_string(),
+                output: FunctionCallOutputPayload {
+                    body: FunctionCallOutputBody::Text(String::new()),
+                    success: None,
+                },
+            },
         }
     }
 }
@@ -280,4 +368,27 @@ mod tests {
         assert!(lines.len() <= TELEMETRY_PREVIEW_MAX_LINES + 1);
         assert_eq!(lines.last(), Some(&TELEMETRY_PREVIEW_TRUNCATION_NOTICE));
     }
+
+    #[tokio::test]
+    async fn output_streams_deliver_chunks_then_the_final_output() {
+        let (sender, stream) = ToolOutputStream::channel();
+        assert!(sender.chunk("compiling"));
+        sender.finish(Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text("built".to_string()),
+            success: Some(true),
+        }));
+
+        match stream.next_event().await {
+            Some(ToolOutputStreamEvent::Chunk(chunk)) => assert_eq!(chunk, "compiling"),
+            _ => panic!("expected a chunk"),
+        }
+        match stream.next_event().await {
+            Some(ToolOutputStreamEvent::Finished(Ok(ToolOutput::Function { body, success }))) => {
+                assert_eq!(body.to_text(), Some("built".to_string()));
+                assert_eq!(success, Some(true));
+            }
+            _ => panic!("expected the finished output"),
+        }
+        assert!(stream.next_event().await.is_none());
+    }
 }
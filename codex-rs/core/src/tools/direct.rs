@@ -0,0 +1,364 @@
+//! Runs builtin tools outside of any model turn.
+//!
+//! Hosts embedding Codex sometimes want its execution layer without a
+//! conversation: run a command under the same sandbox the agent would use,
+//! apply a patch with the same writable-root checks, or read a file the way
+//! `read_file` does. There is no session here, so nothing is approved
+//! interactively: calls the sandbox policy doesn't allow outright are rejected.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::maybe_parse_apply_patch_verified;
+use codex_protocol::config_types::WindowsSandboxLevel;
+use codex_protocol::models::ShellCommandToolCallParams;
+use codex_protocol::models::ShellToolCallParams;
+use serde::Deserialize;
+
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::error::CodexErr;
+use crate::error::SandboxErr;
+use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::process_exec_tool_call;
+use crate::exec_env::create_env;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::AskForApproval;
+use crate::protocol::SandboxPolicy;
+use crate::safety::SafetyCheck;
+use crate::safety::assess_patch_safety;
+use crate::sandboxing::SandboxPermissions;
+use crate::shell::default_user_shell;
+use crate::tools::format_exec_output_for_model_freeform;
+use crate::tools::handlers::read_file;
+use crate::truncate::HeadTailSplit;
+use crate::truncate::TruncationPolicy;
+
+/// Output budget for command results, matching what the model would receive.
+const DIRECT_OUTPUT_TRUNCATION: TruncationPolicy = TruncationPolicy::Bytes(10 * 1024);
+
+/// A builtin tool call made directly by the host.
+#[derive(Debug, Clone)]
+pub struct DirectToolCall {
+    pub tool_name: String,
+    /// JSON arguments, in the same shape the model sends for the tool.
+    pub arguments: String,
+    /// Directory relative paths and commands resolve against.
+    pub cwd: PathBuf,
+    pub sandbox_policy: SandboxPolicy,
+    pub codex_linux_sandbox_exe: Option<PathBuf>,
+}
+
+/// Result of a direct builtin call: the text the model would have seen, and whether the
+/// call succeeded (for commands, whether they exited 0).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectToolOutput {
+    pub output: String,
+    pub success: bool,
+}
+
+#[derive(Deserialize)]
+struct ExecCommandArgs {
+    cmd: String,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    login: Option<bool>,
+    #[serde(default, alias = "timeout")]
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ApplyPatchArgs {
+    #[serde(alias = "patch")]
+    input: String,
+}
+
+/// Dispatches `shell`, `shell_command`, `exec_command`, `apply_patch` or `read_file`.
+pub async fn call_builtin_tool(
+    call: DirectToolCall,
+) -> Result<DirectToolOutput, FunctionCallError> {
+    match call.tool_name.as_str() {
+        "shell" | "local_shell" | "container.exec" => {
+            let params: ShellToolCallParams = parse_arguments(&call.arguments)?;
+            run_command(&call, params.command, params.workdir, params.timeout_ms).await
+        }
+        "shell_command" => {
+            let params: ShellCommandToolCallParams = parse_arguments(&call.arguments)?;
+            let command = default_user_shell()
+                .derive_exec_args(&params.command, params.login.unwrap_or(true));
+            run_command(&call, command, params.workdir, params.timeout_ms).await
+        }
+        "exec_command" => {
+            let args: ExecCommandArgs = parse_arguments(&call.arguments)?;
+            let command =
+                default_user_shell().derive_exec_args(&args.cmd, args.login.unwrap_or(true));
+            run_command(&call, command, args.workdir, args.timeout_ms).await
+        }
+        "apply_patch" => {
+            let args: ApplyPatchArgs = parse_arguments(&call.arguments)?;
+            apply_patch(&call, &args.input)
+        }
+        "read_file" => {
+            let arguments = read_file_arguments(&call.arguments, &call.cwd)?;
+            Ok(DirectToolOutput {
+                output: read_file(&arguments).await?,
+                success: true,
+            })
+        }
+        other => Err(FunctionCallError::RespondToModel(format!(
+            "{other} can't be called directly; supported tools are shell, shell_command, exec_command, apply_patch and read_file"
+        ))),
+    }
+}
+
+fn parse_arguments<T>(arguments: &str) -> Result<T, FunctionCallError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_json::from_str(arguments).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err}"))
+    })
+}
+
+async fn run_command(
+    call: &DirectToolCall,
+    command: Vec<String>,
+    workdir: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<DirectToolOutput, FunctionCallError> {
+    let cwd = workdir.map_or_else(|| call.cwd.clone(), |dir| call.cwd.join(dir));
+    let output = run_direct_command(call, command, cwd, timeout_ms).await?;
+    Ok(command_output(&output))
+}
+
+/// Runs `command` in `cwd` under the call's sandbox and returns its complete output, for hosts
+/// that read the output themselves instead of handing it to a model. Commands the sandbox denies
+/// or that time out still return their output.
+pub async fn run_direct_command(
+    call: &DirectToolCall,
+    command: Vec<String>,
+    cwd: PathBuf,
+    timeout_ms: Option<u64>,
+) -> Result<ExecToolCallOutput, FunctionCallError> {
+    let params = ExecParams {
+        command,
+        cwd,
+        expiration: timeout_ms.into(),
+        env: create_env(&ShellEnvironmentPolicy::default(), None),
+        // Escalation needs an approval flow, which direct calls don't have.
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        windows_sandbox_level: WindowsSandboxLevel::default(),
+        justification: None,
+        arg0: None,
+    };
+    match process_exec_tool_call(
+        params,
+        &call.sandbox_policy,
+        &call.cwd,
+        &call.codex_linux_sandbox_exe,
+        false,
+        None,
+    )
+    .await
+    {
+        Ok(output) => Ok(output),
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => Ok(*output),
+        Err(CodexErr::Sandbox(SandboxErr::Timeout { output })) => Ok(*output),
+        Err(err) => Err(FunctionCallError::RespondToModel(err.to_string())),
+    }
+}
+
+fn command_output(output: &ExecToolCallOutput) -> DirectToolOutput {
+    DirectToolOutput {
+        output: format_exec_output_for_model_freeform(
+            output,
+            DIRECT_OUTPUT_TRUNCATION,
+            HeadTailSplit::default(),
+        ),
+        success: output.exit_code == 0 && !output.timed_out,
+    }
+}
+
+fn apply_patch(call: &DirectToolCall, patch: &str) -> Result<DirectToolOutput, FunctionCallError> {
+    let argv = vec!["apply_patch".to_string(), patch.to_string()];
+    let action = match maybe_parse_apply_patch_verified(&argv, &call.cwd) {
+        MaybeApplyPatchVerified::Body(action) => action,
+        MaybeApplyPatchVerified::CorrectnessError(err) => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "apply_patch verification failed: {err}"
+            )));
+        }
+        MaybeApplyPatchVerified::ShellParseError(err) => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "failed to parse apply_patch input: {err:?}"
+            )));
+        }
+        MaybeApplyPatchVerified::NotApplyPatch => {
+            return Err(FunctionCallError::RespondToModel(
+                "input is not an apply_patch patch".to_string(),
+            ));
+        }
+    };
+    match assess_patch_safety(
+        &action,
+        AskForApproval::Never,
+        &call.sandbox_policy,
+        &call.cwd,
+        WindowsSandboxLevel::default(),
+    ) {
+        SafetyCheck::AutoApprove { .. } => {}
+        SafetyCheck::Reject { reason } => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "patch rejected: {reason}"
+            )));
+        }
+        SafetyCheck::AskUser => {
+            return Err(FunctionCallError::RespondToModel(
+                "patch rejected: it needs approval, which direct calls can't request".to_string(),
+            ));
+        }
+    }
+    write_changes(&action).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to apply patch: {err}"))
+    })?;
+    Ok(DirectToolOutput {
+        output: patch_summary(&action),
+        success: true,
+    })
+}
+
+fn write_changes(action: &ApplyPatchAction) -> std::io::Result<()> {
+    for (path, change) in action.changes() {
+        match change {
+            ApplyPatchFileChange::Add { content } => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, content)?;
+            }
+            ApplyPatchFileChange::Delete { .. } => std::fs::remove_file(path)?,
+            ApplyPatchFileChange::Update {
+                move_path,
+                new_content,
+                ..
+            } => match move_path {
+                Some(dest) => {
+                    if let Some(parent) = dest.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(dest, new_content)?;
+                    std::fs::remove_file(path)?;
+                }
+                None => std::fs::write(path, new_content)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// `apply_patch`'s own summary format: one `A`/`M`/`D` line per file.
+fn patch_summary(action: &ApplyPatchAction) -> String {
+    let mut lines: Vec<String> = action
+        .changes()
+        .iter()
+        .map(|(path, change)| {
+            let (marker, path) = match change {
+                ApplyPatchFileChange::Add { .. } => ("A", path),
+                ApplyPatchFileChange::Delete { .. } => ("D", path),
+                ApplyPatchFileChange::Update { move_path, .. } => {
+                    ("M", move_path.as_ref().unwrap_or(path))
+                }
+            };
+            format!("{marker} {}", path.display())
+        })
+        .collect();
+    lines.sort_unstable();
+    format!(
+        "Success. Updated the following files:\n{}",
+        lines.join("\n")
+    )
+}
+
+/// `read_file` only accepts absolute paths; direct callers may pass one relative to `cwd`.
+fn read_file_arguments(arguments: &str, cwd: &Path) -> Result<String, FunctionCallError> {
+    let mut args: HashMap<String, serde_json::Value> = parse_arguments(arguments)?;
+    if let Some(serde_json::Value::String(file_path)) = args.get_mut("file_path")
+        && Path::new(file_path.as_str()).is_relative()
+    {
+        *file_path = cwd.join(&*file_path).to_string_lossy().into_owned();
+    }
+    serde_json::to_string(&args).map_err(|err| FunctionCallError::Fatal(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn direct_call(tool_name: &str, arguments: serde_json::Value, cwd: &Path) -> DirectToolCall {
+        DirectToolCall {
+            tool_name: tool_name.to_string(),
+            arguments: arguments.to_string(),
+            cwd: cwd.to_path_buf(),
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            codex_linux_sandbox_exe: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn patches_apply_and_files_read_relative_to_cwd() {
+        let dir = tempdir().expect("tempdir");
+        let patch = "*** Begin Patch\n*** Add File: notes.txt\n+first\n+second\n*** End Patch";
+        let applied = call_builtin_tool(direct_call(
+            "apply_patch",
+            serde_json::json!({ "input": patch }),
+            dir.path(),
+        ))
+        .await
+        .expect("apply_patch");
+        assert!(applied.success);
+        assert!(applied.output.ends_with("notes.txt"), "{}", applied.output);
+
+        let read = call_builtin_tool(direct_call(
+            "read_file",
+            serde_json::json!({ "file_path": "notes.txt", "offset": 2 }),
+            dir.path(),
+        ))
+        .await
+        .expect("read_file");
+        assert_eq!(
+            read,
+            DirectToolOutput {
+                output: "L2: second".to_string(),
+                success: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn read_only_sandbox_rejects_patches() {
+        let dir = tempdir().expect("tempdir");
+        let mut call = direct_call(
+            "apply_patch",
+            serde_json::json!({
+                "input": "*** Begin Patch\n*** Add File: blocked.txt\n+nope\n*** End Patch"
+            }),
+            dir.path(),
+        );
+        call.sandbox_policy = SandboxPolicy::ReadOnly;
+        let err = call_builtin_tool(call)
+            .await
+            .expect_err("read-only rejects writes");
+        assert!(
+            matches!(&err, FunctionCallError::RespondToModel(message) if message.starts_with("patch rejected")),
+            "{err:?}"
+        );
+        assert!(!dir.path().join("blocked.txt").exists());
+    }
+}
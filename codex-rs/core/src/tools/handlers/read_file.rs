@@ -110,47 +110,52 @@ impl ToolHandler for ReadFileHandler {
             }
         };
 
-        let args: ReadFileArgs = parse_arguments(&arguments)?;
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(read_file(&arguments).await?),
+            success: Some(true),
+        })
+    }
+}
 
-        let ReadFileArgs {
-            file_path,
-            offset,
-            limit,
-            mode,
-            indentation,
-        } = args;
+/// Runs a `read_file` call given its JSON arguments and returns the text for the model.
+pub(crate) async fn read_file(arguments: &str) -> Result<String, FunctionCallError> {
+    let args: ReadFileArgs = parse_arguments(arguments)?;
 
-        if offset == 0 {
-            return Err(FunctionCallError::RespondToModel(
-                "offset must be a 1-indexed line number".to_string(),
-            ));
-        }
+    let ReadFileArgs {
+        file_path,
+        offset,
+        limit,
+        mode,
+        indentation,
+    } = args;
 
-        if limit == 0 {
-            return Err(FunctionCallError::RespondToModel(
-                "limit must be greater than zero".to_string(),
-            ));
-        }
+    if offset == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "offset must be a 1-indexed line number".to_string(),
+        ));
+    }
 
-        let path = PathBuf::from(&file_path);
-        if !path.is_absolute() {
-            return Err(FunctionCallError::RespondToModel(
-                "file_path must be an absolute path".to_string(),
-            ));
-        }
+    if limit == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "limit must be greater than zero".to_string(),
+        ));
+    }
 
-        let collected = match mode {
-            ReadMode::Slice => slice::read(&path, offset, limit).await?,
-            ReadMode::Indentation => {
-                let indentation = indentation.unwrap_or_default();
-                indentation::read_block(&path, offset, limit, indentation).await?
-            }
-        };
-        Ok(ToolOutput::Function {
-            body: FunctionCallOutputBody::Text(collected.join("\n")),
-            success: Some(true),
-        })
+    let path = PathBuf::from(&file_path);
+    if !path.is_absolute() {
+        return Err(FunctionCallError::RespondToModel(
+            "file_path must be an absolute path".to_string(),
+        ));
     }
+
+    let collected = match mode {
+        ReadMode::Slice => slice::read(&path, offset, limit).await?,
+        ReadMode::Indentation => {
+            let indentation = indentation.unwrap_or_default();
+            indentation::read_block(&path, offset, limit, indentation).await?
+        }
+    };
+    Ok(collected.join("\n"))
 }
 
 mod slice {
@@ -1,4 +1,5 @@
 pub mod context;
+pub mod direct;
 pub mod events;
 pub(crate) mod handlers;
 pub mod orchestrator;
@@ -10,9 +11,10 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::truncate::HeadTailSplit;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::formatted_truncate_text;
-use crate::truncate::truncate_text;
+use crate::truncate::formatted_truncate_text_with_split;
+use crate::truncate::truncate_text_with_split;
 pub use router::ToolRouter;
 use serde::Serialize;
 
@@ -27,6 +29,7 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    split: HeadTailSplit,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -49,7 +52,7 @@ pub fn format_exec_output_for_model_structured(
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let formatted_output = format_exec_output_str(exec_output, truncation_policy, split);
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -66,6 +69,7 @@ pub fn format_exec_output_for_model_structured(
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    split: HeadTailSplit,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -74,7 +78,7 @@ pub fn format_exec_output_for_model_freeform(
 
     let total_lines = content.lines().count();
 
-    let formatted_output = truncate_text(&content, truncation_policy);
+    let formatted_output = truncate_text_with_split(&content, truncation_policy, split);
 
     let mut sections = Vec::new();
 
@@ -93,11 +97,12 @@ pub fn format_exec_output_for_model_freeform(
 pub fn format_exec_output_str(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    split: HeadTailSplit,
 ) -> String {
     let content = build_content_with_timeout(exec_output);
 
     // Truncate for model consumption before serialization.
-    formatted_truncate_text(&content, truncation_policy)
+    formatted_truncate_text_with_split(&content, truncation_policy, split)
 }
 
 /// Extracts exec output content and prepends a timeout message if the command timed out.
@@ -2,24 +2,30 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::Mutex;
-use std::sync::OnceLock;
 use std::time::Duration;
+use std::time::Instant;
 
 use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
 use codex_protocol::models::ResponseInputItem;
 use codex_utils_readiness::Readiness;
-use tracing::error;
 use tracing::trace;
 use tracing::warn;
 
 use crate::client_common::tools::ToolSpec;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::exec::MAX_EXEC_OUTPUT_DELTAS_PER_CALL;
 use crate::exec::SandboxType;
 use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecCommandOutputDeltaEvent;
+use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
 use crate::safety::get_platform_sandbox;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolOutputStreamEvent;
 use crate::tools::context::ToolPayload;
 use codex_protocol::config_types::WindowsSandboxLevel;
 
@@ -38,6 +44,10 @@ pub struct ExternalToolRegistration {
     pub supports_parallel_tool_calls: bool,
 }
 
+/// Interceptor name that wraps every tool, outside the interceptors registered for the tool
+/// itself.
+pub const ALL_TOOLS_INTERCEPTOR: &str = "*";
+
 /// Registration describing an interceptor that can wrap a builtin/registered tool.
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -46,103 +56,142 @@ pub struct ExternalInterceptorRegistration {
     pub handler: Arc<dyn ToolInterceptor>,
 }
 
-impl std::fmt::Debug for ExternalToolRegistration {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ExternalToolRegistration")
-            .field("name", &self.spec.name())
-            .field(
-                "supports_parallel_tool_calls",
-                &self.supports_parallel_tool_calls,
-            )
-            .finish()
+/// Names of the tools a session/router build may expose. Denied names win over allowed ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExternalToolFilter {
+    /// When set, only these tools are exposed.
+    pub allowed: Option<Vec<String>>,
+    pub denied: Vec<String>,
+}
+
+impl ExternalToolFilter {
+    pub fn permits(&self, tool_name: &str) -> bool {
+        if self.denied.iter().any(|name| name == tool_name) {
+            return false;
+        }
+        self.allowed
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|name| name == tool_name))
     }
 }
 
-impl std::fmt::Debug for ExternalInterceptorRegistration {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ExternalInterceptorRegistration")
-            .field("name", &self.name)
-            .finish()
+/// What native bindings add to every tool router a session builds. The session rebuilds its
+/// router for each sampling request, so these layers live on its
+/// [`Config`](crate::config::Config) rather than being taken by the first build.
+#[derive(Clone, Debug, Default)]
+pub struct ExternalToolLayers {
+    pub tools: Vec<ExternalToolRegistration>,
+    /// Tools exposed to the model; `None` exposes all of them.
+    pub tool_filter: Option<ExternalToolFilter>,
+    pub interceptors: Vec<ExternalInterceptorRegistration>,
+    /// Shared by every router built from these layers, so a call still holding a slot from one
+    /// sampling request counts against the next.
+    limiters: HashMap<String, Arc<ToolLimiter>>,
+}
+
+impl ExternalToolLayers {
+    /// Replace the per-tool scheduling limits, starting each limiter afresh.
+    pub fn set_tool_limits(&mut self, limits: HashMap<String, ExternalToolLimit>) {
+        self.limiters = limits
+            .into_iter()
+            .map(|(name, limit)| (name, Arc::new(ToolLimiter::new(limit))))
+            .collect();
     }
 }
 
-#[allow(dead_code)]
-fn pending_external_tools() -> &'static Mutex<Vec<ExternalToolRegistration>> {
-    static PENDING: OnceLock<Mutex<Vec<ExternalToolRegistration>>> = OnceLock::new();
-    PENDING.get_or_init(|| Mutex::new(Vec::new()))
+impl PartialEq for ExternalToolLayers {
+    fn eq(&self, other: &Self) -> bool {
+        self.tools == other.tools
+            && self.tool_filter == other.tool_filter
+            && self.interceptors == other.interceptors
+            && self.limiters.len() == other.limiters.len()
+            && self.limiters.iter().all(|(name, limiter)| {
+                other
+                    .limiters
+                    .get(name)
+                    .is_some_and(|other| Arc::ptr_eq(limiter, other))
+            })
+    }
 }
 
-/// Set the list of external tools to be registered for the next session/router build.
-#[allow(dead_code)]
-pub fn set_pending_external_tools(tools: Vec<ExternalToolRegistration>) {
-    match pending_external_tools().lock() {
-        Ok(mut guard) => {
-            *guard = tools;
+/// Scheduling limits for one tool within a session, so parallel tool calls don't stampede
+/// expensive tools.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExternalToolLimit {
+    /// Calls of the tool allowed to run at once.
+    pub max_concurrent: Option<usize>,
+    /// Minimum time between the starts of two calls of the tool.
+    pub min_interval: Option<Duration>,
+}
+
+/// Per-tool scheduler enforcing an [ExternalToolLimit] across concurrent dispatches.
+#[derive(Debug)]
+struct ToolLimiter {
+    slots: Option<Arc<tokio::sync::Semaphore>>,
+    min_interval: Option<Duration>,
+    next_start: tokio::sync::Mutex<Option<Instant>>,
+}
+
+impl ToolLimiter {
+    fn new(limit: ExternalToolLimit) -> Self {
+        Self {
+            slots: limit
+                .max_concurrent
+                .map(|max| Arc::new(tokio::sync::Semaphore::new(max.max(1)))),
+            min_interval: limit.min_interval,
+            next_start: tokio::sync::Mutex::new(None),
         }
-        Err(err) => {
-            error!(
-                error = ?err,
-                "failed to acquire pending external tools mutex; pending tools unchanged"
-            );
+    }
+
+    /// Waits until the tool may start another call. The returned permit holds a concurrency
+    /// slot until dropped.
+    async fn acquire(&self) -> Option<tokio::sync::OwnedSemaphorePermit> {
+        let permit = match &self.slots {
+            Some(slots) => Arc::clone(slots).acquire_owned().await.ok(),
+            None => None,
+        };
+        if let Some(min_interval) = self.min_interval {
+            let mut next_start = self.next_start.lock().await;
+            if let Some(at) = *next_start {
+                tokio::time::sleep_until(at.into()).await;
+            }
+            *next_start = Some(Instant::now() + min_interval);
         }
+        permit
     }
 }
 
-#[allow(dead_code)]
-pub(crate) fn take_pending_external_tools() -> Vec<ExternalToolRegistration> {
-    match pending_external_tools().lock() {
-        Ok(mut guard) => {
-            let tools = guard.clone();
-            guard.clear();
-            tools
-        }
-        Err(err) => {
-            error!(
-                error = ?err,
-                "failed to acquire pending external tools mutex; returning empty list"
-            );
-            Vec::new()
-        }
+impl std::fmt::Debug for ExternalToolRegistration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalToolRegistration")
+            .field("name", &self.spec.name())
+            .field(
+                "supports_parallel_tool_calls",
+                &self.supports_parallel_tool_calls,
+            )
+            .finish()
     }
 }
 
-#[allow(dead_code)]
-fn pending_external_interceptors() -> &'static Mutex<Vec<ExternalInterceptorRegistration>> {
-    static PENDING: OnceLock<Mutex<Vec<ExternalInterceptorRegistration>>> = OnceLock::new();
-    PENDING.get_or_init(|| Mutex::new(Vec::new()))
+impl PartialEq for ExternalToolRegistration {
+    fn eq(&self, other: &Self) -> bool {
+        self.spec == other.spec
+            && Arc::ptr_eq(&self.handler, &other.handler)
+            && self.supports_parallel_tool_calls == other.supports_parallel_tool_calls
+    }
 }
 
-/// Set the list of external interceptors to be registered for the next session/router build.
-#[allow(dead_code)]
-pub fn set_pending_external_interceptors(interceptors: Vec<ExternalInterceptorRegistration>) {
-    match pending_external_interceptors().lock() {
-        Ok(mut guard) => {
-            *guard = interceptors;
-        }
-        Err(err) => {
-            error!(
-                error = ?err,
-                "failed to acquire pending external interceptors mutex; pending interceptors unchanged"
-            );
-        }
+impl PartialEq for ExternalInterceptorRegistration {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Arc::ptr_eq(&self.handler, &other.handler)
     }
 }
 
-#[allow(dead_code)]
-pub(crate) fn take_pending_external_interceptors() -> Vec<ExternalInterceptorRegistration> {
-    match pending_external_interceptors().lock() {
-        Ok(mut guard) => {
-            let list = guard.clone();
-            guard.clear();
-            list
-        }
-        Err(err) => {
-            error!(
-                error = ?err,
-                "failed to acquire pending external interceptors mutex; returning empty list"
-            );
-            Vec::new()
-        }
+impl std::fmt::Debug for ExternalInterceptorRegistration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalInterceptorRegistration")
+            .field("name", &self.name)
+            .finish()
     }
 }
 
@@ -189,6 +238,7 @@ pub trait ToolInterceptor: Send + Sync {
 pub struct ToolRegistry {
     handlers: HashMap<String, Arc<dyn ToolHandler>>,
     interceptors: HashMap<String, Vec<Arc<dyn ToolInterceptor>>>,
+    limiters: HashMap<String, Arc<ToolLimiter>>,
 }
 
 impl ToolRegistry {
@@ -199,6 +249,7 @@ impl ToolRegistry {
         Self {
             handlers,
             interceptors,
+            limiters: HashMap::new(),
         }
     }
 
@@ -269,71 +320,25 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
-        // If interceptors are registered for this tool, compose them; otherwise call handler.
-        if let Some(list) = self.interceptors.get(&tool_name) {
-            // Compose a simple chain: first interceptor gets the original handler as `next`.
-            // For minimalism we apply the interceptors in registration order, without nesting chains.
-            // Only the first interceptor is applied to keep complexity low.
-            if let Some(interceptor) = list.first() {
-                let next_handler = handler.clone();
-                let call_id_owned = invocation.call_id.clone();
-                let result = otel
-                    .log_tool_result_with_tags(
-                        tool_name.as_ref(),
-                        &call_id_owned,
-                        log_payload.as_ref(),
-                        &metric_tags,
-                        || {
-                            let interceptor = interceptor.clone();
-                            let next_handler = next_handler.clone();
-                            let invocation = invocation.clone();
-                            async move {
-                                let next = move |inv: ToolInvocation| {
-                                    let next_handler = next_handler.clone();
-                                    Box::pin(async move {
-                                        wait_for_tool_gate_if_needed(&next_handler, &inv).await;
-                                        next_handler.handle(inv).await
-                                    })
-                                        as Pin<
-                                            Box<
-                                                dyn Future<
-                                                        Output = Result<
-                                                            ToolOutput,
-                                                            FunctionCallError,
-                                                        >,
-                                                    > + Send,
-                                            >,
-                                        >
-                                };
-                                match interceptor.intercept(invocation, Box::new(next)).await {
-                                    Ok(output) => {
-                                        let preview = output.log_preview();
-                                        let success = output.success_for_logging();
-                                        Ok((preview, success))
-                                    }
-                                    Err(err) => Err(err),
-                                }
-                            }
-                        },
-                    )
-                    .await;
-
-                return match result {
-                    Ok(_) => {
-                        // We need to re-run the interceptor to actually get the ToolOutput to return.
-                        // To avoid double-call, simply call the handler and ignore preview/success;
-                        // The otel log already captured the metadata.
-                        wait_for_tool_gate_if_needed(&handler, &invocation).await;
-                        let out = handler.handle(invocation).await?;
-                        Ok(out.into_response(&call_id_owned, &payload_for_response))
-                    }
-                    Err(err) => Err(err),
-                };
-            }
-        }
+        // Calls wait for the tool's concurrency slot and start interval before anything runs, and
+        // hold the slot until the whole chain finishes.
+        let _slot = match self.limiters.get(&tool_name) {
+            Some(limiter) => limiter.acquire().await,
+            None => None,
+        };
 
-        // No interceptors; call the handler directly and log via OTEL wrapper.
-        let call_id_owned = invocation.call_id.clone();
+        // Interceptors wrap the handler in registration order: the first registered interceptor
+        // runs outermost and each one's `next` invokes the remainder of the chain. Interceptors
+        // registered for every tool run before the tool's own.
+        let interceptors = self
+            .interceptors
+            .get(ALL_TOOLS_INTERCEPTOR)
+            .into_iter()
+            .chain(self.interceptors.get(&tool_name))
+            .flatten()
+            .cloned()
+            .collect();
+        let chain = interceptor_chain(interceptors, handler);
         let output_cell = tokio::sync::Mutex::new(None);
         let result = otel
             .log_tool_result_with_tags(
@@ -342,12 +347,10 @@ impl ToolRegistry {
                 log_payload.as_ref(),
                 &metric_tags,
                 || {
-                    let handler = handler.clone();
                     let output_cell = &output_cell;
                     let invocation = invocation;
                     async move {
-                        wait_for_tool_gate_if_needed(&handler, &invocation).await;
-                        match handler.handle(invocation).await {
+                        match chain(invocation).await {
                             Ok(output) => {
                                 let preview = output.log_preview();
                                 let success = output.success_for_logging();
@@ -375,6 +378,75 @@ impl ToolRegistry {
     }
 }
 
+type ToolFuture = Pin<Box<dyn Future<Output = Result<ToolOutput, FunctionCallError>> + Send>>;
+type ToolNext = Box<dyn FnOnce(ToolInvocation) -> ToolFuture + Send>;
+
+/// Build the callable that runs `interceptors` (outermost first) around `handler`.
+fn interceptor_chain(
+    interceptors: Vec<Arc<dyn ToolInterceptor>>,
+    handler: Arc<dyn ToolHandler>,
+) -> ToolNext {
+    let mut remaining = interceptors.into_iter();
+    match remaining.next() {
+        Some(interceptor) => {
+            let next = interceptor_chain(remaining.collect(), handler);
+            Box::new(move |invocation: ToolInvocation| -> ToolFuture {
+                Box::pin(async move { interceptor.intercept(invocation, next).await })
+            })
+        }
+        None => Box::new(move |invocation: ToolInvocation| -> ToolFuture {
+            Box::pin(async move {
+                wait_for_tool_gate_if_needed(&handler, &invocation).await;
+                let session = Arc::clone(&invocation.session);
+                let turn = Arc::clone(&invocation.turn);
+                let call_id = invocation.call_id.clone();
+                let result = handler.handle(invocation).await;
+                resolve_streamed_output(result, &session, &turn, &call_id).await
+            })
+        }),
+    }
+}
+
+/// Waits for a streaming tool output to finish, forwarding each chunk to clients as an exec
+/// output delta for `call_id`. Interceptors only ever see finished outputs.
+async fn resolve_streamed_output(
+    mut result: Result<ToolOutput, FunctionCallError>,
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+) -> Result<ToolOutput, FunctionCallError> {
+    let mut emitted_deltas = 0;
+    while let Ok(ToolOutput::Stream { stream }) = result {
+        let mut text = String::new();
+        result = loop {
+            match stream.next_event().await {
+                Some(ToolOutputStreamEvent::Chunk(chunk)) => {
+                    if emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL {
+                        emitted_deltas += 1;
+                        let delta = ExecCommandOutputDeltaEvent {
+                            call_id: call_id.to_string(),
+                            stream: ExecOutputStream::Stdout,
+                            chunk: chunk.as_bytes().to_vec(),
+                        };
+                        session
+                            .send_event(turn, EventMsg::ExecCommandOutputDelta(delta))
+                            .await;
+                    }
+                    text.push_str(&chunk);
+                }
+                Some(ToolOutputStreamEvent::Finished(finished)) => break finished,
+                None => {
+                    break Ok(ToolOutput::Function {
+                        body: FunctionCallOutputBody::Text(text),
+                        success: None,
+                    });
+                }
+            }
+        };
+    }
+    result
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfiguredToolSpec {
     pub spec: ToolSpec,
@@ -476,32 +548,43 @@ impl ToolRegistryBuilder {
     //     }
     // }
 
-    pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
+    pub fn build(self, layers: &ExternalToolLayers) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
         let mut specs = self.specs;
         let mut handlers = self.handlers;
         let mut interceptors = self.interceptors;
 
-        // Attach any external tools registered by native bindings for this build.
-        for external in take_pending_external_tools() {
+        // Attach the external tools native bindings registered with the session.
+        for external in &layers.tools {
             let name = external.spec.name().to_string();
             specs.push(ConfiguredToolSpec::new(
-                external.spec,
+                external.spec.clone(),
                 external.supports_parallel_tool_calls,
             ));
-            if handlers.insert(name.clone(), external.handler).is_some() {
+            if handlers
+                .insert(name.clone(), Arc::clone(&external.handler))
+                .is_some()
+            {
                 warn!("overwriting handler for tool {name}");
             }
         }
 
+        // Drop tools the session's layers filter out, so the model is never offered them and
+        // calls to them are rejected as unsupported.
+        if let Some(filter) = &layers.tool_filter {
+            specs.retain(|configured| filter.permits(configured.spec.name()));
+            handlers.retain(|name, _| filter.permits(name));
+        }
+
         // Attach any external interceptors that wrap builtin or external tools.
-        for external in take_pending_external_interceptors() {
+        for external in &layers.interceptors {
             interceptors
                 .entry(external.name.clone())
                 .or_default()
-                .push(external.handler);
+                .push(Arc::clone(&external.handler));
         }
 
-        let registry = ToolRegistry::new(handlers, interceptors);
+        let mut registry = ToolRegistry::new(handlers, interceptors);
+        registry.limiters = layers.limiters.clone();
         (specs, registry)
     }
 }
@@ -546,3 +629,33 @@ fn sandbox_policy_tag(policy: &SandboxPolicy) -> &'static str {
         SandboxPolicy::ExternalSandbox { .. } => "external-sandbox",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn routers_built_from_the_same_layers_share_limiters() {
+        let mut layers = ExternalToolLayers::default();
+        layers.set_tool_limits(HashMap::from([(
+            "slow_tool".to_string(),
+            ExternalToolLimit {
+                max_concurrent: Some(1),
+                min_interval: None,
+            },
+        )]));
+        let (_, first) = ToolRegistryBuilder::new().build(&layers);
+        let (_, second) = ToolRegistryBuilder::new().build(&layers.clone());
+
+        let held = first.limiters["slow_tool"].acquire().await;
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            second.limiters["slow_tool"].acquire(),
+        )
+        .await;
+        assert!(blocked.is_err(), "second router ignored the held slot");
+
+        drop(held);
+        assert!(second.limiters["slow_tool"].acquire().await.is_some());
+    }
+}
@@ -7,6 +7,7 @@ use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
 use crate::tools::registry::ConfiguredToolSpec;
+use crate::tools::registry::ExternalToolLayers;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::build_specs;
@@ -38,9 +39,10 @@ impl ToolRouter {
         config: &ToolsConfig,
         mcp_tools: Option<HashMap<String, Tool>>,
         dynamic_tools: &[DynamicToolSpec],
+        layers: &ExternalToolLayers,
     ) -> Self {
         let builder = build_specs(config, mcp_tools, dynamic_tools);
-        let (specs, registry) = builder.build();
+        let (specs, registry) = builder.build(layers);
 
         Self { registry, specs }
     }
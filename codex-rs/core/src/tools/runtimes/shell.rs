@@ -2,13 +2,20 @@
 Runtime: shell
 
 Executes shell requests under the orchestrator: asks for approval when needed,
-builds a CommandSpec, and runs it under the current SandboxAttempt.
+builds a CommandSpec, and runs it under the current SandboxAttempt. With
+`exec_session = "persistent"` the command runs in the thread's persistent
+shell instead (see `crate::exec_session`); with `[shell_pty]` it runs attached
+to a PTY of the configured size.
 */
+use crate::config::types::ExecSessionMode;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::exec_session::run_in_persistent_shell;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
 use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
+use crate::sandboxing::execute_env_in_pty;
 use crate::shell::ShellType;
 use crate::tools::runtimes::build_command_spec;
 use crate::tools::runtimes::maybe_wrap_shell_lc_with_snapshot;
@@ -144,8 +151,21 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
-        let base_command = &req.command;
         let session_shell = ctx.session.user_shell();
+        if ctx.turn.config.exec_session == ExecSessionMode::Persistent
+            && let Some(out) = run_in_persistent_shell(
+                &ctx.session.conversation_id.to_string(),
+                req,
+                attempt,
+                session_shell.as_ref(),
+                ctx.turn.config.shell_pty.as_ref(),
+            )
+            .await?
+        {
+            return Ok(out);
+        }
+
+        let base_command = &req.command;
         let command = maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref());
         let command = if matches!(session_shell.shell_type, ShellType::PowerShell)
             && ctx.session.features().enabled(Feature::PowershellUtf8)
@@ -166,6 +186,14 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         let env = attempt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
+        // The Windows sandbox spawns through its own runner, which has no PTY support.
+        if let Some(pty) = ctx.turn.config.shell_pty.as_ref()
+            && env.sandbox != SandboxType::WindowsRestrictedToken
+        {
+            return execute_env_in_pty(env, pty, Self::stdout_stream(ctx))
+                .await
+                .map_err(ToolError::Codex);
+        }
         let out = execute_env(env, attempt.policy, Self::stdout_stream(ctx))
             .await
             .map_err(ToolError::Codex)?;
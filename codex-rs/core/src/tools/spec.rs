@@ -1494,6 +1494,7 @@ mod tests {
     use crate::config::test_config;
     use crate::models_manager::manager::ModelsManager;
     use crate::tools::registry::ConfiguredToolSpec;
+    use crate::tools::registry::ExternalToolLayers;
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -1636,7 +1637,7 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
         });
-        let (tools, _) = build_specs(&config, None, &[]).build();
+        let (tools, _) = build_specs(&config, None, &[]).build(&ExternalToolLayers::default());
 
         // Build actual map name -> spec
         use std::collections::BTreeMap;
@@ -1700,7 +1701,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
         assert_contains_tool_names(
             &tools,
             &["spawn_agent", "send_input", "wait", "close_agent"],
@@ -1718,7 +1720,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
         assert!(
             !tools.iter().any(|t| t.spec.name() == "request_user_input"),
             "request_user_input should be disabled when collaboration_modes feature is off"
@@ -1730,7 +1733,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
         assert_contains_tool_names(&tools, &["request_user_input"]);
     }
 
@@ -1745,7 +1749,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
         assert!(
             !tools.iter().any(|t| t.spec.name() == "get_memory"),
             "get_memory should be disabled when memory_tool feature is off"
@@ -1757,7 +1762,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
         assert_contains_tool_names(&tools, &["get_memory"]);
     }
 
@@ -1774,7 +1780,8 @@ mod tests {
             features,
             web_search_mode,
         });
-        let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[]).build();
+        let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[])
+            .build(&ExternalToolLayers::default());
         let tool_names = tools.iter().map(|t| t.spec.name()).collect::<Vec<_>>();
         assert_eq!(&tool_names, &expected_tools,);
     }
@@ -1806,7 +1813,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "web_search");
         assert_eq!(
@@ -1828,7 +1836,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "web_search");
         assert_eq!(
@@ -2074,7 +2083,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
         });
-        let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[]).build();
+        let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[])
+            .build(&ExternalToolLayers::default());
 
         // Only check the shell variant and a couple of core tools.
         let mut subset = vec!["exec_command", "write_stdin", "update_plan"];
@@ -2096,7 +2106,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
 
         assert!(find_tool(&tools, "exec_command").supports_parallel_tool_calls);
         assert!(!find_tool(&tools, "write_stdin").supports_parallel_tool_calls);
@@ -2115,7 +2126,8 @@ mod tests {
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
-        let (tools, _) = build_specs(&tools_config, None, &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, None, &[]).build(&ExternalToolLayers::default());
 
         assert!(
             tools
@@ -2173,7 +2185,7 @@ mod tests {
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "test_server/do_something_cool");
         assert_eq!(
@@ -2248,7 +2260,8 @@ mod tests {
             ),
         ]);
 
-        let (tools, _) = build_specs(&tools_config, Some(tools_map), &[]).build();
+        let (tools, _) =
+            build_specs(&tools_config, Some(tools_map), &[]).build(&ExternalToolLayers::default());
 
         // Only assert that the MCP tools themselves are sorted by fully-qualified name.
         let mcp_names: Vec<_> = tools
@@ -2293,7 +2306,7 @@ mod tests {
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "dash/search");
         assert_eq!(
@@ -2343,7 +2356,7 @@ mod tests {
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "dash/paginate");
         assert_eq!(
@@ -2392,7 +2405,7 @@ mod tests {
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "dash/tags");
         assert_eq!(
@@ -2445,7 +2458,7 @@ mod tests {
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "dash/value");
         assert_eq!(
@@ -2570,7 +2583,7 @@ Examples of valid command strings:
             )])),
             &[],
         )
-        .build();
+        .build(&ExternalToolLayers::default());
 
         let tool = find_tool(&tools, "test_server/do_something_cool");
         assert_eq!(
@@ -61,6 +61,27 @@ impl TruncationPolicy {
     }
 }
 
+/// Share of a truncation budget spent on the start of the text; the rest keeps its end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadTailSplit(f64);
+
+impl HeadTailSplit {
+    /// `head_share` is clamped to `0.0..=1.0`; `0.0` keeps only the end of the text.
+    pub fn new(head_share: f64) -> Self {
+        if head_share.is_finite() {
+            Self(head_share.clamp(0.0, 1.0))
+        } else {
+            Self::default()
+        }
+    }
+}
+
+impl Default for HeadTailSplit {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
 impl std::ops::Mul<f64> for TruncationPolicy {
     type Output = Self;
 
@@ -77,19 +98,35 @@ impl std::ops::Mul<f64> for TruncationPolicy {
 }
 
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
+    formatted_truncate_text_with_split(content, policy, HeadTailSplit::default())
+}
+
+pub(crate) fn formatted_truncate_text_with_split(
+    content: &str,
+    policy: TruncationPolicy,
+    split: HeadTailSplit,
+) -> String {
     if content.len() <= policy.byte_budget() {
         return content.to_string();
     }
     let total_lines = content.lines().count();
-    let result = truncate_text(content, policy);
+    let result = truncate_text_with_split(content, policy, split);
     format!("Total output lines: {total_lines}\n\n{result}")
 }
 
 pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
+    truncate_text_with_split(content, policy, HeadTailSplit::default())
+}
+
+pub(crate) fn truncate_text_with_split(
+    content: &str,
+    policy: TruncationPolicy,
+    split: HeadTailSplit,
+) -> String {
     match policy {
-        TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy),
+        TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy, split),
         TruncationPolicy::Tokens(_) => {
-            let (truncated, _) = truncate_with_token_budget(content, policy);
+            let (truncated, _) = truncate_with_token_budget(content, policy, split);
             truncated
         }
     }
@@ -159,7 +196,11 @@ pub(crate) fn truncate_function_output_items_with_policy(
 /// preserving the beginning and the end. Returns the possibly truncated string
 /// and `Some(original_token_count)` if truncation occurred; otherwise returns
 /// the original string and `None`.
-fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Option<u64>) {
+fn truncate_with_token_budget(
+    s: &str,
+    policy: TruncationPolicy,
+    split: HeadTailSplit,
+) -> (String, Option<u64>) {
     if s.is_empty() {
         return (String::new(), None);
     }
@@ -170,7 +211,7 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
         return (s.to_string(), None);
     }
 
-    let truncated = truncate_with_byte_estimate(s, policy);
+    let truncated = truncate_with_byte_estimate(s, policy, split);
     let approx_total_usize = approx_token_count(s);
     let approx_total = u64::try_from(approx_total_usize).unwrap_or(u64::MAX);
     if truncated == s {
@@ -183,7 +224,7 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
-fn truncate_with_byte_estimate(s: &str, policy: TruncationPolicy) -> String {
+fn truncate_with_byte_estimate(s: &str, policy: TruncationPolicy, split: HeadTailSplit) -> String {
     if s.is_empty() {
         return String::new();
     }
@@ -206,7 +247,7 @@ fn truncate_with_byte_estimate(s: &str, policy: TruncationPolicy) -> String {
 
     let total_bytes = s.len();
 
-    let (left_budget, right_budget) = split_budget(max_bytes);
+    let (left_budget, right_budget) = split_budget(max_bytes, split);
 
     let (removed_chars, left, right) = split_string(s, left_budget, right_budget);
 
@@ -265,8 +306,8 @@ fn format_truncation_marker(policy: TruncationPolicy, removed_count: u64) -> Str
     }
 }
 
-fn split_budget(budget: usize) -> (usize, usize) {
-    let left = budget / 2;
+fn split_budget(budget: usize, split: HeadTailSplit) -> (usize, usize) {
+    let left = ((budget as f64 * split.0).floor() as usize).min(budget);
     (left, budget - left)
 }
 
@@ -307,12 +348,14 @@ pub(crate) fn approx_tokens_from_byte_count(bytes: usize) -> u64 {
 #[cfg(test)]
 mod tests {
 
+    use super::HeadTailSplit;
     use super::TruncationPolicy;
     use super::approx_token_count;
     use super::formatted_truncate_text;
     use super::split_string;
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
+    use super::truncate_text_with_split;
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
@@ -392,6 +435,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn head_tail_split_moves_the_kept_bytes() {
+        let content = "0123456789abcdefghij";
+
+        assert_eq!(
+            "…10 chars truncated…abcdefghij",
+            truncate_text_with_split(
+                content,
+                TruncationPolicy::Bytes(10),
+                HeadTailSplit::new(0.0)
+            ),
+        );
+        assert_eq!(
+            "0123456789…10 chars truncated…",
+            truncate_text_with_split(
+                content,
+                TruncationPolicy::Bytes(10),
+                HeadTailSplit::new(7.0)
+            ),
+        );
+        assert_eq!(
+            truncate_text(content, TruncationPolicy::Bytes(10)),
+            truncate_text_with_split(
+                content,
+                TruncationPolicy::Bytes(10),
+                HeadTailSplit::new(f64::NAN)
+            ),
+        );
+    }
+
     #[test]
     fn truncate_tokens_over_limit_returns_truncated() {
         let content = "this is an example of a long output that should be truncated";
@@ -438,7 +511,11 @@ mod tests {
     fn truncate_with_token_budget_returns_original_when_under_limit() {
         let s = "short output";
         let limit = 100;
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(limit));
+        let (out, original) = truncate_with_token_budget(
+            s,
+            TruncationPolicy::Tokens(limit),
+            HeadTailSplit::default(),
+        );
         assert_eq!(out, s);
         assert_eq!(original, None);
     }
@@ -446,7 +523,8 @@ mod tests {
     #[test]
     fn truncate_with_token_budget_reports_truncation_at_zero_limit() {
         let s = "abcdef";
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(0));
+        let (out, original) =
+            truncate_with_token_budget(s, TruncationPolicy::Tokens(0), HeadTailSplit::default());
         assert_eq!(out, "…2 tokens truncated…");
         assert_eq!(original, Some(2));
     }
@@ -454,7 +532,8 @@ mod tests {
     #[test]
     fn truncate_middle_tokens_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
-        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::Tokens(8));
+        let (out, tokens) =
+            truncate_with_token_budget(s, TruncationPolicy::Tokens(8), HeadTailSplit::default());
         assert_eq!(out, "😀😀😀😀…8 tokens truncated… line with text\n");
         assert_eq!(tokens, Some(16));
     }
@@ -37,6 +37,7 @@ fn format_user_shell_command_body(
     sections.push(format_exec_output_str(
         exec_output,
         turn_context.truncation_policy,
+        turn_context.tool_output_split(),
     ));
     sections.push("</result>".to_string());
     sections.join("\n")
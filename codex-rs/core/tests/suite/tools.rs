@@ -2,11 +2,24 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
 use std::fs;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
+use async_trait::async_trait;
+use codex_core::ALL_TOOLS_INTERCEPTOR;
+use codex_core::ExternalInterceptorRegistration;
+use codex_core::ExternalToolFilter;
+use codex_core::FunctionCallError;
+use codex_core::ToolInterceptor;
+use codex_core::ToolInvocation;
+use codex_core::ToolOutput;
 use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -338,6 +351,177 @@ async fn unified_exec_spec_toggle_end_to_end() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_filter_applies_to_every_sampling_request() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let call_id = "filtered-plan";
+    let responses = vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "update_plan", "{\"plan\":[]}"),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    ];
+    let mock = mount_sse_sequence(&server, responses).await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.external_tool_layers.tool_filter = Some(ExternalToolFilter {
+            allowed: None,
+            denied: vec!["update_plan".to_string()],
+        });
+    });
+    let test = builder.build(&server).await?;
+
+    test.submit_turn_with_policies(
+        "plan without the plan tool",
+        AskForApproval::Never,
+        SandboxPolicy::DangerFullAccess,
+    )
+    .await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        let tools = tool_names(&request.body_json());
+        assert!(
+            !tools.iter().any(|name| name == "update_plan"),
+            "filtered tool offered to the model: {tools:?}"
+        );
+    }
+    assert_eq!(
+        requests[1].function_call_output_text(call_id).as_deref(),
+        Some("unsupported call: update_plan")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn allowed_tools_hold_for_every_step_of_a_turn() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let plan_args = "{\"plan\":[]}";
+    let responses = vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("plan-1", "update_plan", plan_args),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_response_created("resp-2"),
+            ev_function_call("plan-2", "update_plan", plan_args),
+            ev_completed("resp-2"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-3"),
+        ]),
+    ];
+    let mock = mount_sse_sequence(&server, responses).await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.external_tool_layers.tool_filter = Some(ExternalToolFilter {
+            allowed: Some(vec!["update_plan".to_string()]),
+            denied: Vec::new(),
+        });
+    });
+    let test = builder.build(&server).await?;
+
+    test.submit_turn_with_policies(
+        "update the plan twice",
+        AskForApproval::Never,
+        SandboxPolicy::DangerFullAccess,
+    )
+    .await?;
+
+    let offered: Vec<Vec<String>> = mock
+        .requests()
+        .iter()
+        .map(|request| tool_names(&request.body_json()))
+        .collect();
+    assert_eq!(offered, vec![vec!["update_plan".to_string()]; 3]);
+
+    Ok(())
+}
+
+struct CountingInterceptor {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolInterceptor for CountingInterceptor {
+    async fn intercept(
+        &self,
+        invocation: ToolInvocation,
+        next: Box<
+            dyn FnOnce(
+                    ToolInvocation,
+                ) -> Pin<
+                    Box<dyn Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<ToolOutput, FunctionCallError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        next(invocation).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn interceptors_wrap_calls_from_every_sampling_request() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let plan_args = "{\"plan\":[]}";
+    let responses = vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("plan-1", "update_plan", plan_args),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_response_created("resp-2"),
+            ev_function_call("plan-2", "update_plan", plan_args),
+            ev_completed("resp-2"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-3"),
+        ]),
+    ];
+    let mock = mount_sse_sequence(&server, responses).await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let interceptor = Arc::new(CountingInterceptor {
+        calls: Arc::clone(&calls),
+    });
+    let mut builder = test_codex().with_config(move |config| {
+        config.external_tool_layers.interceptors = vec![ExternalInterceptorRegistration {
+            name: ALL_TOOLS_INTERCEPTOR.to_string(),
+            handler: interceptor,
+        }];
+    });
+    let test = builder.build(&server).await?;
+
+    test.submit_turn_with_policies(
+        "update the plan twice",
+        AskForApproval::Never,
+        SandboxPolicy::DangerFullAccess,
+    )
+    .await?;
+
+    assert_eq!(mock.requests().len(), 3);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn shell_timeout_includes_timeout_prefix_and_metadata() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -1,8 +1,10 @@
+use crate::escalation::RunEscalationHandler;
 use clap::Args;
 use clap::FromArgMatches;
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
+use codex_core::ExternalToolLayers;
 use codex_protocol::config_types::Personality;
 use codex_protocol::dynamic_tools::DynamicToolSpec;
 use codex_protocol::user_input::UserInput;
@@ -117,6 +119,45 @@ pub struct Cli {
     )]
     pub dynamic_tools_json: Option<String>,
 
+    /// Directory whose stored credentials are used instead of those in codex home
+    /// (for programmatic callers running several accounts in one process).
+    #[clap(skip)]
+    pub auth_home: Option<PathBuf>,
+
+    /// API key used instead of any stored credentials or `CODEX_API_KEY`.
+    #[clap(skip)]
+    pub api_key: Option<String>,
+
+    /// Endpoint for the built-in OpenAI provider, in place of `OPENAI_BASE_URL`.
+    #[clap(skip)]
+    pub openai_base_url: Option<String>,
+
+    /// Replaces the model's built-in base instructions.
+    #[clap(skip)]
+    pub base_instructions: Option<String>,
+
+    /// Developer message, in place of config.toml's `developer_instructions`.
+    #[clap(skip)]
+    pub developer_instructions: Option<String>,
+
+    /// Prompt used when compacting history, in place of config.toml's `compact_prompt`.
+    #[clap(skip)]
+    pub compact_prompt: Option<String>,
+
+    /// Forward every protocol event to event callbacks as a `raw_event`, ahead of the thread
+    /// events mapped from it.
+    #[clap(skip)]
+    pub include_raw_events: bool,
+
+    /// Tools, filter, limits and interceptors added to the session's tool router (for
+    /// programmatic callers).
+    #[clap(skip)]
+    pub tool_layers: ExternalToolLayers,
+
+    /// Decides approval requests raised during the run (for programmatic callers).
+    #[clap(skip)]
+    pub escalation_handler: RunEscalationHandler,
+
     /// Override the personality for this turn.
     #[arg(long = "turn-personality", value_enum, value_name = "PERSONALITY")]
     pub turn_personality: Option<PersonalityCliArg>,
@@ -134,6 +175,14 @@ pub struct Cli {
     )]
     pub json: bool,
 
+    /// With `--json`, also print tool output as it streams, as `tool.output_chunk` events.
+    #[arg(
+        long = "json-stream-output",
+        default_value_t = false,
+        requires = "json"
+    )]
+    pub json_stream_output: bool,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -0,0 +1,152 @@
+//! Approval requests raised while a headless run is in progress.
+//!
+//! Interactive clients answer `ExecApprovalRequest` / `ApplyPatchApprovalRequest`
+//! events from the user; exec has nobody to ask. Embedders can give a run an
+//! [`EscalationHandler`] on its `Cli` to decide them instead. Without one,
+//! every request is denied so the turn never waits on an answer that cannot come.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::ReviewDecision;
+
+/// Output kept per running command, from the end, to explain a sandbox denial.
+const MAX_SANDBOX_OUTPUT_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationKind {
+    /// A shell command, usually one the sandbox denied, wants to run again unsandboxed.
+    Command,
+    /// A patch wants to write outside the writable roots.
+    Patch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscalationRequest {
+    pub kind: EscalationKind,
+    pub thread_id: String,
+    pub call_id: String,
+    /// Command awaiting approval; empty for patches.
+    pub command: Vec<String>,
+    pub cwd: Option<PathBuf>,
+    /// Files a patch touches; empty for commands.
+    pub files: Vec<PathBuf>,
+    /// Why approval is needed, e.g. `command failed; retry without sandbox?`.
+    pub reason: Option<String>,
+    /// Tail of what the command printed in the sandbox before it was denied.
+    pub sandbox_output: Option<String>,
+}
+
+impl EscalationRequest {
+    pub(crate) fn command(
+        thread_id: String,
+        event: &ExecApprovalRequestEvent,
+        sandbox_output: Option<String>,
+    ) -> Self {
+        Self {
+            kind: EscalationKind::Command,
+            thread_id,
+            call_id: event.call_id.clone(),
+            command: event.command.clone(),
+            cwd: Some(event.cwd.clone()),
+            files: Vec::new(),
+            reason: event.reason.clone(),
+            sandbox_output,
+        }
+    }
+
+    pub(crate) fn patch(thread_id: String, event: &ApplyPatchApprovalRequestEvent) -> Self {
+        let mut files: Vec<PathBuf> = event.changes.keys().cloned().collect();
+        files.sort();
+        Self {
+            kind: EscalationKind::Patch,
+            thread_id,
+            call_id: event.call_id.clone(),
+            command: Vec::new(),
+            cwd: event.grant_root.clone(),
+            files,
+            reason: event.reason.clone(),
+            sandbox_output: None,
+        }
+    }
+}
+
+pub type EscalationFuture = Pin<Box<dyn Future<Output = ReviewDecision> + Send>>;
+
+/// Decides one approval request. `ReviewDecision::Approved` grants a one-time retry.
+pub type EscalationHandler = Arc<dyn Fn(EscalationRequest) -> EscalationFuture + Send + Sync>;
+
+/// The [`EscalationHandler`] a run was given, carried on its `Cli`. Without one, approval
+/// requests are denied.
+#[derive(Clone, Default)]
+pub struct RunEscalationHandler(pub Option<EscalationHandler>);
+
+impl std::fmt::Debug for RunEscalationHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RunEscalationHandler")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// Recent output of each running command, keyed by call id.
+#[derive(Debug, Default)]
+pub(crate) struct CommandOutputTails {
+    tails: HashMap<String, Vec<u8>>,
+}
+
+impl CommandOutputTails {
+    pub(crate) fn push(&mut self, call_id: &str, chunk: &[u8]) {
+        let tail = self.tails.entry(call_id.to_string()).or_default();
+        tail.extend_from_slice(chunk);
+        if tail.len() > MAX_SANDBOX_OUTPUT_BYTES {
+            tail.drain(..tail.len() - MAX_SANDBOX_OUTPUT_BYTES);
+        }
+    }
+
+    /// Output recorded for `call_id` so far, which then starts over.
+    pub(crate) fn take(&mut self, call_id: &str) -> Option<String> {
+        let tail = self.tails.remove(call_id)?;
+        let text = String::from_utf8_lossy(&tail).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    pub(crate) fn remove(&mut self, call_id: &str) {
+        self.tails.remove(call_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn output_tails_keep_the_end_of_each_command() {
+        let mut tails = CommandOutputTails::default();
+        tails.push("call-1", b"touch: ");
+        tails.push("call-1", b"/etc/hosts: Operation not permitted\n");
+        tails.push("call-2", &vec![b'x'; MAX_SANDBOX_OUTPUT_BYTES]);
+        tails.push("call-2", b"done");
+        tails.push("call-3", b"  \n");
+
+        assert_eq!(
+            tails.take("call-1").as_deref(),
+            Some("touch: /etc/hosts: Operation not permitted")
+        );
+        assert_eq!(tails.take("call-1"), None);
+        let tail = tails.take("call-2").unwrap();
+        assert_eq!(tail.len(), MAX_SANDBOX_OUTPUT_BYTES);
+        assert!(tail.ends_with("xdone"));
+        assert_eq!(tails.take("call-3"), None);
+
+        tails.push("call-4", b"partial");
+        tails.remove("call-4");
+        assert_eq!(tails.take("call-4"), None);
+    }
+}
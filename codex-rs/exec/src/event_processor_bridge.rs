@@ -1,6 +1,8 @@
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use crate::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use crate::event_processor_with_jsonl_output::shell_commands_use_tty;
+use crate::exec_events::RawEvent;
 use crate::exec_events::ThreadEvent;
 use codex_core::config::Config;
 use codex_core::protocol::Event;
@@ -11,16 +13,21 @@ use std::path::PathBuf;
 struct CallbackEventProcessor {
     mapper: EventProcessorWithJsonOutput,
     callback: Box<dyn FnMut(ThreadEvent) + Send>,
+    include_raw_events: bool,
 }
 
 impl CallbackEventProcessor {
     fn new(
         callback: Box<dyn FnMut(ThreadEvent) + Send>,
         last_message_file: Option<PathBuf>,
+        include_raw_events: bool,
     ) -> Self {
+        let mut mapper = EventProcessorWithJsonOutput::new(last_message_file);
+        mapper.set_stream_tool_output(true);
         Self {
-            mapper: EventProcessorWithJsonOutput::new(last_message_file),
+            mapper,
             callback,
+            include_raw_events,
         }
     }
 }
@@ -28,10 +35,13 @@ impl CallbackEventProcessor {
 impl EventProcessor for CallbackEventProcessor {
     fn print_config_summary(
         &mut self,
-        _config: &Config,
+        config: &Config,
         _prompt: &str,
         ev: &SessionConfiguredEvent,
     ) {
+        self.mapper.set_shell_is_tty(shell_commands_use_tty(config));
+        self.mapper
+            .set_config_profile(config.active_profile.clone());
         let _ = self.process_event(Event {
             id: "".to_string(),
             msg: EventMsg::SessionConfigured(ev.clone()),
@@ -39,6 +49,12 @@ impl EventProcessor for CallbackEventProcessor {
     }
 
     fn process_event(&mut self, event: Event) -> CodexStatus {
+        if self.include_raw_events {
+            match serde_json::to_value(&event) {
+                Ok(raw) => (self.callback)(ThreadEvent::Raw(RawEvent { raw })),
+                Err(err) => tracing::warn!("failed to serialize raw event: {err}"),
+            }
+        }
         let aggregated = self.mapper.collect_thread_events(&event);
         for e in aggregated {
             (self.callback)(e);
@@ -56,23 +72,32 @@ impl EventProcessor for CallbackEventProcessor {
 pub(crate) fn callback_event_processor(
     callback: Box<dyn FnMut(ThreadEvent) + Send>,
     last_message_file: Option<PathBuf>,
+    include_raw_events: bool,
 ) -> Box<dyn EventProcessor> {
-    Box::new(CallbackEventProcessor::new(callback, last_message_file))
+    Box::new(CallbackEventProcessor::new(
+        callback,
+        last_message_file,
+        include_raw_events,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::callback_event_processor;
     use crate::event_processor::CodexStatus;
+    use crate::exec_events::ThreadEvent;
     use codex_core::protocol::Event;
     use codex_core::protocol::EventMsg;
     use codex_core::protocol::TurnAbortReason;
     use codex_core::protocol::TurnAbortedEvent;
     use codex_core::protocol::TurnCompleteEvent;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::sync::Mutex;
 
     #[test]
     fn callback_processor_initiates_shutdown_on_turn_aborted() {
-        let mut processor = callback_event_processor(Box::new(|_| {}), None);
+        let mut processor = callback_event_processor(Box::new(|_| {}), None, false);
         let status = processor.process_event(Event {
             id: "".to_string(),
             msg: EventMsg::TurnAborted(TurnAbortedEvent {
@@ -84,7 +109,7 @@ mod tests {
 
     #[test]
     fn callback_processor_returns_shutdown_on_shutdown_complete() {
-        let mut processor = callback_event_processor(Box::new(|_| {}), None);
+        let mut processor = callback_event_processor(Box::new(|_| {}), None, false);
         let status = processor.process_event(Event {
             id: "".to_string(),
             msg: EventMsg::ShutdownComplete,
@@ -94,7 +119,7 @@ mod tests {
 
     #[test]
     fn callback_processor_initiates_shutdown_on_turn_complete() {
-        let mut processor = callback_event_processor(Box::new(|_| {}), None);
+        let mut processor = callback_event_processor(Box::new(|_| {}), None, false);
         let status = processor.process_event(Event {
             id: "".to_string(),
             msg: EventMsg::TurnComplete(TurnCompleteEvent {
@@ -103,4 +128,29 @@ mod tests {
         });
         assert!(matches!(status, CodexStatus::InitiateShutdown));
     }
+
+    #[test]
+    fn callback_processor_forwards_raw_events_before_mapped_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let mut processor = callback_event_processor(
+            Box::new(move |event| sink.lock().unwrap().push(event)),
+            None,
+            true,
+        );
+        processor.process_event(Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: None,
+            }),
+        });
+
+        let events = events.lock().unwrap();
+        let ThreadEvent::Raw(raw) = &events[0] else {
+            panic!("expected a raw event first, got {:?}", events[0]);
+        };
+        assert_eq!(raw.raw["id"], "sub-1");
+        assert_eq!(raw.raw["msg"]["type"], "task_complete");
+        assert!(matches!(events[1], ThreadEvent::TurnCompleted(_)));
+    }
 }
@@ -11,8 +11,11 @@ use crate::exec_events::CollabAgentStatus;
 use crate::exec_events::CollabTool;
 use crate::exec_events::CollabToolCallItem;
 use crate::exec_events::CollabToolCallStatus;
+use crate::exec_events::CommandCompletedEvent;
 use crate::exec_events::CommandExecutionItem;
 use crate::exec_events::CommandExecutionStatus;
+use crate::exec_events::CommandSandboxDecision;
+use crate::exec_events::CommandStartedEvent;
 use crate::exec_events::ErrorItem;
 use crate::exec_events::FileChangeItem;
 use crate::exec_events::FileUpdateChange;
@@ -25,6 +28,7 @@ use crate::exec_events::McpToolCallItemResult;
 use crate::exec_events::McpToolCallStatus;
 use crate::exec_events::PatchApplyStatus;
 use crate::exec_events::PatchChangeKind;
+use crate::exec_events::PlanUpdatedEvent;
 use crate::exec_events::ReasoningItem;
 use crate::exec_events::ThreadErrorEvent;
 use crate::exec_events::ThreadEvent;
@@ -33,12 +37,14 @@ use crate::exec_events::ThreadItemDetails;
 use crate::exec_events::ThreadStartedEvent;
 use crate::exec_events::TodoItem;
 use crate::exec_events::TodoListItem;
+use crate::exec_events::ToolOutputChunkEvent;
 use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
 use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
+use codex_core::config::types::ExecSessionMode;
 use codex_core::protocol;
 use codex_core::protocol::AgentStatus as CoreAgentStatus;
 use codex_core::protocol::CollabAgentInteractionBeginEvent;
@@ -56,6 +62,15 @@ use serde_json::Value as JsonValue;
 use tracing::error;
 use tracing::warn;
 
+/// Output kept in `command_completed`, from the end.
+const MAX_COMMAND_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Line core puts before command output it truncated for the model.
+const TRUNCATED_OUTPUT_PREFIX: &str = "Total output lines: ";
+
+/// stderr core reports for a command whose approval was denied.
+const REJECTED_COMMAND_MESSAGE: &str = "exec command rejected by user";
+
 pub struct EventProcessorWithJsonOutput {
     last_message_path: Option<PathBuf>,
     last_proposed_plan: Option<String>,
@@ -70,6 +85,14 @@ pub struct EventProcessorWithJsonOutput {
     running_collab_tool_calls: HashMap<String, RunningCollabToolCall>,
     running_web_search_calls: HashMap<String, String>,
     last_critical_error: Option<ThreadErrorEvent>,
+    // Whether `shell` tool commands run attached to a PTY, reported as `is_tty`.
+    shell_is_tty: bool,
+    // Config profile reported in `thread.started`.
+    config_profile: Option<String>,
+    // Whether the session's sandbox policy runs commands in Codex's sandbox.
+    commands_sandboxed: bool,
+    // Whether tool output deltas are emitted as `tool.output_chunk` events.
+    stream_tool_output: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +100,10 @@ struct RunningCommand {
     command: String,
     item_id: String,
     aggregated_output: String,
+    is_tty: Option<bool>,
+    argv: Vec<String>,
+    // Set once the command asked for approval, to the reason it gave.
+    approval: Option<Option<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -113,9 +140,31 @@ impl EventProcessorWithJsonOutput {
             running_collab_tool_calls: HashMap::new(),
             running_web_search_calls: HashMap::new(),
             last_critical_error: None,
+            shell_is_tty: false,
+            config_profile: None,
+            commands_sandboxed: true,
+            stream_tool_output: false,
         }
     }
 
+    /// Set whether `shell` tool commands run attached to a PTY. Called from
+    /// `print_config_summary` with [`shell_commands_use_tty`].
+    pub fn set_shell_is_tty(&mut self, is_tty: bool) {
+        self.shell_is_tty = is_tty;
+    }
+
+    /// Set the config profile reported in `thread.started`. Called from
+    /// `print_config_summary` with the config's active profile.
+    pub fn set_config_profile(&mut self, profile: Option<String>) {
+        self.config_profile = profile;
+    }
+
+    /// Set whether tool output deltas are emitted as `tool.output_chunk` events. Off by
+    /// default, so `--json` output has one completed item per command.
+    pub fn set_stream_tool_output(&mut self, stream: bool) {
+        self.stream_tool_output = stream;
+    }
+
     pub fn collect_thread_events(&mut self, event: &protocol::Event) -> Vec<ThreadEvent> {
         match &event.msg {
             protocol::EventMsg::SessionConfigured(ev) => self.handle_session_configured(ev),
@@ -131,6 +180,12 @@ impl EventProcessorWithJsonOutput {
             protocol::EventMsg::AgentReasoning(ev) => self.handle_reasoning_event(ev),
             protocol::EventMsg::ExecCommandBegin(ev) => self.handle_exec_command_begin(ev),
             protocol::EventMsg::ExecCommandEnd(ev) => self.handle_exec_command_end(ev),
+            protocol::EventMsg::ExecApprovalRequest(ev) => {
+                if let Some(running) = self.running_commands.get_mut(&ev.call_id) {
+                    running.approval = Some(ev.reason.clone());
+                }
+                Vec::new()
+            }
             protocol::EventMsg::TerminalInteraction(ev) => self.handle_terminal_interaction(ev),
             protocol::EventMsg::ExecCommandOutputDelta(ev) => {
                 self.handle_output_chunk(&ev.call_id, &ev.chunk)
@@ -200,11 +255,25 @@ impl EventProcessorWithJsonOutput {
     }
 
     fn handle_session_configured(
-        &self,
+        &mut self,
         payload: &protocol::SessionConfiguredEvent,
     ) -> Vec<ThreadEvent> {
+        self.commands_sandboxed = !matches!(
+            payload.sandbox_policy,
+            protocol::SandboxPolicy::DangerFullAccess
+                | protocol::SandboxPolicy::ExternalSandbox { .. }
+        );
         vec![ThreadEvent::ThreadStarted(ThreadStartedEvent {
             thread_id: payload.session_id.to_string(),
+            model: Some(payload.model.clone()),
+            model_provider: Some(payload.model_provider_id.clone()),
+            sandbox_policy: Some(payload.sandbox_policy.to_string()),
+            cwd: Some(payload.cwd.to_string_lossy().into_owned()),
+            rollout_path: payload
+                .rollout_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned()),
+            config_profile: self.config_profile.clone(),
         })]
     }
 
@@ -244,9 +313,19 @@ impl EventProcessorWithJsonOutput {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
     }
 
-    fn handle_output_chunk(&mut self, _call_id: &str, _chunk: &[u8]) -> Vec<ThreadEvent> {
-        //TODO see how we want to process them
-        vec![]
+    fn handle_output_chunk(&mut self, call_id: &str, chunk: &[u8]) -> Vec<ThreadEvent> {
+        if !self.stream_tool_output {
+            return Vec::new();
+        }
+        let item_id = self
+            .running_commands
+            .get(call_id)
+            .map(|running| running.item_id.clone());
+        vec![ThreadEvent::ToolOutputChunk(ToolOutputChunkEvent {
+            call_id: call_id.to_string(),
+            item_id,
+            chunk: String::from_utf8_lossy(chunk).into_owned(),
+        })]
     }
 
     fn handle_terminal_interaction(
@@ -297,14 +376,29 @@ impl EventProcessorWithJsonOutput {
             }
         };
 
+        // Only `shell` tool calls follow `[shell_pty]`; unified exec sessions pick their own.
+        let is_tty =
+            matches!(ev.source, protocol::ExecCommandSource::Agent).then_some(self.shell_is_tty);
         self.running_commands.insert(
             ev.call_id.clone(),
             RunningCommand {
                 command: command_string.clone(),
                 item_id: item_id.clone(),
                 aggregated_output: String::new(),
+                is_tty,
+                argv: ev.command.clone(),
+                approval: None,
             },
         );
+        let started = ThreadEvent::CommandStarted(CommandStartedEvent {
+            call_id: ev.call_id.clone(),
+            item_id: item_id.clone(),
+            command: ev.command.clone(),
+            cwd: ev.cwd.to_string_lossy().into_owned(),
+            source: ev.source,
+            process_id: ev.process_id.clone(),
+            sandboxed: self.commands_sandboxed,
+        });
 
         let item = ThreadItem {
             id: item_id,
@@ -313,10 +407,12 @@ impl EventProcessorWithJsonOutput {
                 aggregated_output: String::new(),
                 exit_code: None,
                 status: CommandExecutionStatus::InProgress,
+                cache_hit: None,
+                is_tty,
             }),
         };
 
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
+        vec![ThreadEvent::ItemStarted(ItemStartedEvent { item }), started]
     }
 
     fn handle_mcp_tool_call_begin(
@@ -347,6 +443,7 @@ impl EventProcessorWithJsonOutput {
                 result: None,
                 error: None,
                 status: McpToolCallStatus::InProgress,
+                cache_hit: None,
             }),
         };
 
@@ -407,6 +504,7 @@ impl EventProcessorWithJsonOutput {
                 result,
                 error,
                 status,
+                cache_hit: None,
             }),
         };
 
@@ -678,6 +776,9 @@ impl EventProcessorWithJsonOutput {
             command,
             item_id,
             aggregated_output,
+            is_tty,
+            argv,
+            approval,
         }) = self.running_commands.remove(&ev.call_id)
         else {
             warn!(
@@ -696,6 +797,28 @@ impl EventProcessorWithJsonOutput {
         } else {
             ev.aggregated_output.clone()
         };
+        let sandbox_decision = match &approval {
+            Some(_) if ev.stderr == REJECTED_COMMAND_MESSAGE => CommandSandboxDecision::Denied,
+            Some(_) => CommandSandboxDecision::Approved,
+            None if self.commands_sandboxed => CommandSandboxDecision::Sandboxed,
+            None => CommandSandboxDecision::Unsandboxed,
+        };
+        let (output, output_truncated) = command_output_tail(&aggregated_output);
+        let model_output = (ev.formatted_output.starts_with(TRUNCATED_OUTPUT_PREFIX)
+            && !aggregated_output.starts_with(TRUNCATED_OUTPUT_PREFIX))
+        .then(|| ev.formatted_output.clone());
+        let completed = ThreadEvent::CommandCompleted(CommandCompletedEvent {
+            call_id: ev.call_id.clone(),
+            item_id: item_id.clone(),
+            command: argv,
+            exit_code: ev.exit_code,
+            duration_ms: u64::try_from(ev.duration.as_millis()).unwrap_or(u64::MAX),
+            output,
+            output_truncated,
+            sandbox_decision,
+            approval_reason: approval.flatten(),
+            model_output,
+        });
         let item = ThreadItem {
             id: item_id,
 
@@ -704,10 +827,15 @@ impl EventProcessorWithJsonOutput {
                 aggregated_output,
                 exit_code: Some(ev.exit_code),
                 status,
+                cache_hit: None,
+                is_tty,
             }),
         };
 
-        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
+        vec![
+            ThreadEvent::ItemCompleted(ItemCompletedEvent { item }),
+            completed,
+        ]
     }
 
     fn todo_items_from_plan(&self, args: &UpdatePlanArgs) -> Vec<TodoItem> {
@@ -722,6 +850,7 @@ impl EventProcessorWithJsonOutput {
 
     fn handle_plan_update(&mut self, args: &UpdatePlanArgs) -> Vec<ThreadEvent> {
         let items = self.todo_items_from_plan(args);
+        let plan_updated = ThreadEvent::PlanUpdated(PlanUpdatedEvent::from(args));
 
         if let Some(running) = &mut self.running_todo_list {
             running.items = items.clone();
@@ -729,7 +858,10 @@ impl EventProcessorWithJsonOutput {
                 id: running.item_id.clone(),
                 details: ThreadItemDetails::TodoList(TodoListItem { items }),
             };
-            return vec![ThreadEvent::ItemUpdated(ItemUpdatedEvent { item })];
+            return vec![
+                ThreadEvent::ItemUpdated(ItemUpdatedEvent { item }),
+                plan_updated,
+            ];
         }
 
         let item_id = self.get_next_item_id();
@@ -741,7 +873,10 @@ impl EventProcessorWithJsonOutput {
             id: item_id,
             details: ThreadItemDetails::TodoList(TodoListItem { items }),
         };
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent { item }),
+            plan_updated,
+        ]
     }
 
     fn handle_task_started(&mut self, _: &protocol::TurnStartedEvent) -> Vec<ThreadEvent> {
@@ -781,6 +916,8 @@ impl EventProcessorWithJsonOutput {
                         aggregated_output: running.aggregated_output,
                         exit_code: None,
                         status: CommandExecutionStatus::Completed,
+                        cache_hit: None,
+                        is_tty: running.is_tty,
                     }),
                 };
                 items.push(ThreadEvent::ItemCompleted(ItemCompletedEvent { item }));
@@ -790,13 +927,29 @@ impl EventProcessorWithJsonOutput {
         if let Some(error) = self.last_critical_error.take() {
             items.push(ThreadEvent::TurnFailed(TurnFailedEvent { error }));
         } else {
-            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent { usage }));
+            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent {
+                usage,
+                redaction_applied: None,
+                dropped_events: None,
+            }));
         }
 
         items
     }
 }
 
+/// The last [`MAX_COMMAND_OUTPUT_BYTES`] of `output`, and whether anything was cut.
+fn command_output_tail(output: &str) -> (String, bool) {
+    if output.len() <= MAX_COMMAND_OUTPUT_BYTES {
+        return (output.to_string(), false);
+    }
+    let mut start = output.len() - MAX_COMMAND_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    (output[start..].to_string(), true)
+}
+
 fn is_collab_failure(status: &CoreAgentStatus) -> bool {
     matches!(
         status,
@@ -835,8 +988,21 @@ impl From<CoreAgentStatus> for CollabAgentState {
     }
 }
 
+/// Whether `config` runs `shell` tool commands attached to a PTY: with `[shell_pty]` set, or in
+/// a persistent shell session.
+pub fn shell_commands_use_tty(config: &Config) -> bool {
+    config.shell_pty.is_some() || config.exec_session == ExecSessionMode::Persistent
+}
+
 impl EventProcessor for EventProcessorWithJsonOutput {
-    fn print_config_summary(&mut self, _: &Config, _: &str, ev: &protocol::SessionConfiguredEvent) {
+    fn print_config_summary(
+        &mut self,
+        config: &Config,
+        _: &str,
+        ev: &protocol::SessionConfiguredEvent,
+    ) {
+        self.set_shell_is_tty(shell_commands_use_tty(config));
+        self.set_config_profile(config.active_profile.clone());
         self.process_event(protocol::Event {
             id: "".to_string(),
             msg: protocol::EventMsg::SessionConfigured(ev.clone()),
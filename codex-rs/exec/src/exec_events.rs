@@ -1,4 +1,7 @@
 use codex_protocol::models::WebSearchAction;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::ExecCommandSource;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
@@ -40,15 +43,69 @@ pub enum ThreadEvent {
     /// Background notification emitted alongside an active turn.
     #[serde(rename = "background_event")]
     BackgroundEvent(BackgroundEventEvent),
+    /// Output a running command or tool produced so far.
+    #[serde(rename = "tool.output_chunk")]
+    ToolOutputChunk(ToolOutputChunkEvent),
+    /// A tool call moved its work to the background and returned a task handle.
+    #[serde(rename = "background_task_started")]
+    BackgroundTaskStarted(BackgroundTaskEvent),
+    /// A background task finished, successfully or not.
+    #[serde(rename = "background_task_completed")]
+    BackgroundTaskCompleted(BackgroundTaskEvent),
+    /// The thread's plan changed, through the model's `update_plan` tool or the SDK.
+    #[serde(rename = "plan_updated")]
+    PlanUpdated(PlanUpdatedEvent),
+    /// Progress a tool or host reported for the thread with `setThreadStatus`.
+    #[serde(rename = "thread_status")]
+    ThreadStatus(ThreadStatusEvent),
+    /// Structured results of a test run through `run_tests` or the SDK's `runTests()`.
+    #[serde(rename = "test_results")]
+    TestResults(TestResultsEvent),
+    /// Post-patch verification commands failed after a patch was applied.
+    #[serde(rename = "verification_failed")]
+    VerificationFailed(VerificationFailedEvent),
+    /// Files the thread's tools read or modified during the turn.
+    #[serde(rename = "files_touched")]
+    FilesTouched(FilesTouchedEvent),
+    /// A shell command started, with its argv, working directory and sandbox.
+    #[serde(rename = "command_started")]
+    CommandStarted(CommandStartedEvent),
+    /// A shell command exited or was denied, with its exit code, duration and output.
+    #[serde(rename = "command_completed")]
+    CommandCompleted(CommandCompletedEvent),
     /// Raw protocol event payload forwarded for consumers that need full fidelity.
     #[serde(rename = "raw_event")]
     Raw(RawEvent),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
 pub struct ThreadStartedEvent {
     /// The identified of the new thread. Can be used to resume the thread later.
     pub thread_id: String,
+    /// Model the session resolved to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    /// Id of the model provider in effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model_provider: Option<String>,
+    /// Sandbox policy for model shell commands, e.g. `workspace-write`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_policy: Option<String>,
+    /// Working directory of the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cwd: Option<String>,
+    /// Rollout file the session is recorded to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rollout_path: Option<String>,
+    /// Config profile the session was loaded with, when one was selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub config_profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
@@ -58,6 +115,14 @@ pub struct TurnStartedEvent {}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct TurnCompletedEvent {
     pub usage: Usage,
+    /// Number of secrets masked during the turn, when redaction is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub redaction_applied: Option<u32>,
+    /// Number of events the SDK discarded during the turn, when event delivery is bounded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dropped_events: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -143,6 +208,231 @@ pub struct BackgroundEventEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ToolOutputChunkEvent {
+    /// Identifier of the tool call producing the output.
+    pub call_id: String,
+    /// The `command_execution` item the chunk belongs to, when the call has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_id: Option<String>,
+    /// Output text; invalid UTF-8 is replaced.
+    pub chunk: String,
+}
+
+/// The status of a background task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTaskStatus {
+    #[default]
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct BackgroundTaskEvent {
+    /// Handle the model passes to `check_background_task`.
+    pub task_id: String,
+    /// Identifier of the tool call that started the task.
+    pub call_id: String,
+    pub tool_name: String,
+    pub status: BackgroundTaskStatus,
+    /// The task's output, once it completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub output: Option<String>,
+    /// Why the task failed, when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+}
+
+/// The status of a plan step.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PlanStep {
+    pub step: String,
+    pub status: PlanStepStatus,
+}
+
+/// The full plan after an update, with every step's status.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PlanUpdatedEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub explanation: Option<String>,
+    pub plan: Vec<PlanStep>,
+}
+
+impl From<&UpdatePlanArgs> for PlanUpdatedEvent {
+    fn from(args: &UpdatePlanArgs) -> Self {
+        Self {
+            explanation: args.explanation.clone(),
+            plan: args
+                .plan
+                .iter()
+                .map(|item| PlanStep {
+                    step: item.step.clone(),
+                    status: match item.status {
+                        StepStatus::Pending => PlanStepStatus::Pending,
+                        StepStatus::InProgress => PlanStepStatus::InProgress,
+                        StepStatus::Completed => PlanStepStatus::Completed,
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Status text for a long-running thread. Both fields unset means the status was cleared.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, TS)]
+pub struct ThreadStatusEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub text: Option<String>,
+    /// Completion percentage, 0-100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub progress_pct: Option<f64>,
+}
+
+/// Outcome of one test case.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TestCaseStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TestCaseResult {
+    /// Full name of the test, as the framework reports it.
+    pub name: String,
+    pub status: TestCaseStatus,
+    /// File the test is defined in, when the framework reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub file: Option<String>,
+    /// Failure message or skip reason.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub duration_ms: Option<u32>,
+}
+
+/// Parsed results of a test command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TestResultsEvent {
+    /// Framework whose output was parsed: `cargo`, `jest` or `pytest`.
+    pub framework: String,
+    pub command: String,
+    /// Identifier of the `run_tests` call that ran the tests, when the model did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub call_id: Option<String>,
+    pub exit_code: i32,
+    /// The command exited 0 and no test failed.
+    pub success: bool,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub tests: Vec<TestCaseResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct VerificationFailure {
+    pub command: String,
+    /// Unset when the command could not be started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Tail of the command's combined stdout and stderr.
+    pub output: String,
+}
+
+/// Verification commands that failed after a patch was applied.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct VerificationFailedEvent {
+    /// Identifier of the tool call that applied the patch.
+    pub call_id: String,
+    pub failures: Vec<VerificationFailure>,
+}
+
+/// Summary of the files tools read or modified during a turn, emitted before the turn ends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct FilesTouchedEvent {
+    /// Absolute paths of files tools read, sorted.
+    pub read: Vec<String>,
+    /// Absolute paths of files patches added, updated or deleted, sorted.
+    pub modified: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct CommandStartedEvent {
+    pub call_id: String,
+    /// Id of the `command_execution` item tracking the command.
+    pub item_id: String,
+    pub command: Vec<String>,
+    pub cwd: String,
+    pub source: ExecCommandSource,
+    /// Identifier of the PTY process, for unified exec sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub process_id: Option<String>,
+    /// Whether the session's sandbox policy runs commands in Codex's sandbox.
+    pub sandboxed: bool,
+}
+
+/// How the sandbox and approvals treated a command.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandSandboxDecision {
+    /// Ran in the sandbox without asking for approval.
+    Sandboxed,
+    /// Ran without Codex's sandbox because the policy has none.
+    Unsandboxed,
+    /// Approval was requested, e.g. to retry outside the sandbox, and granted.
+    Approved,
+    /// Approval was requested and denied, so the command did not run again.
+    Denied,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct CommandCompletedEvent {
+    pub call_id: String,
+    /// Id of the `command_execution` item tracking the command.
+    pub item_id: String,
+    pub command: Vec<String>,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    /// Tail of the command's combined stdout and stderr.
+    pub output: String,
+    /// Whether `output` was cut to its last 16 KiB.
+    pub output_truncated: bool,
+    pub sandbox_decision: CommandSandboxDecision,
+    /// Why approval was requested, when it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub approval_reason: Option<String>,
+    /// The output as the model received it, truncation markers included, when the tool
+    /// output limit cut it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model_output: Option<String>,
+}
+
 /// Canonical representation of a thread item and its domain-specific payload.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ThreadItem {
@@ -213,6 +503,15 @@ pub struct CommandExecutionItem {
     pub aggregated_output: String,
     pub exit_code: Option<i32>,
     pub status: CommandExecutionStatus,
+    /// Set when the output was served from the tool result cache instead of running the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cache_hit: Option<bool>,
+    /// Whether the command ran attached to a terminal (`[shell_pty]` or a persistent shell).
+    /// Unset for commands started outside the `shell` tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub is_tty: Option<bool>,
 }
 
 /// A set of file changes by the agent.
@@ -337,6 +636,10 @@ pub struct McpToolCallItem {
     pub result: Option<McpToolCallItemResult>,
     pub error: Option<McpToolCallItemError>,
     pub status: McpToolCallStatus,
+    /// Set when the result was served from the tool result cache instead of calling the tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cache_hit: Option<bool>,
 }
 
 /// A web search request.
@@ -5,6 +5,7 @@
 #![deny(clippy::print_stdout)]
 
 mod cli;
+mod escalation;
 mod event_processor;
 mod event_processor_bridge;
 mod event_processor_with_human_output;
@@ -40,6 +41,7 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
@@ -47,6 +49,11 @@ use codex_protocol::approvals::ElicitationAction;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
+pub use escalation::EscalationFuture;
+pub use escalation::EscalationHandler;
+pub use escalation::EscalationKind;
+pub use escalation::EscalationRequest;
+pub use escalation::RunEscalationHandler;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use serde::de::DeserializeOwned;
@@ -68,6 +75,7 @@ use tracing_subscriber::prelude::*;
 use uuid::Uuid;
 
 use crate::cli::Command as ExecCommand;
+use crate::escalation::CommandOutputTails;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_client_residency_requirement;
@@ -142,6 +150,7 @@ async fn run_main_with_event_processor(
         color,
         last_message_file,
         json: json_mode,
+        json_stream_output,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
@@ -152,6 +161,15 @@ async fn run_main_with_event_processor(
         dynamic_tools,
         dynamic_tools_path,
         dynamic_tools_json,
+        auth_home,
+        api_key,
+        openai_base_url,
+        base_instructions,
+        developer_instructions,
+        compact_prompt,
+        include_raw_events,
+        tool_layers,
+        escalation_handler: RunEscalationHandler(escalation_handler),
         turn_personality,
     } = cli;
 
@@ -254,7 +272,7 @@ async fn run_main_with_event_processor(
     };
 
     let cloud_auth_manager = AuthManager::shared(
-        codex_home.clone(),
+        auth_home.clone().unwrap_or_else(|| codex_home.clone()),
         false,
         config_toml.cli_auth_credentials_store.unwrap_or_default(),
     );
@@ -306,15 +324,17 @@ async fn run_main_with_event_processor(
         cwd: resolved_cwd,
         model_provider: model_provider.clone(),
         codex_linux_sandbox_exe,
-        base_instructions: None,
-        developer_instructions: None,
+        base_instructions,
+        developer_instructions,
         personality: None,
-        compact_prompt: None,
+        compact_prompt,
         include_apply_patch_tool: None,
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
         ephemeral: ephemeral.then_some(true),
         additional_writable_roots: add_dir,
+        external_tool_layers: tool_layers,
+        openai_base_url,
     };
 
     let config = ConfigBuilder::default()
@@ -356,16 +376,22 @@ async fn run_main_with_event_processor(
 
     let mut event_processor: Box<dyn EventProcessor> = match event_processor_mode {
         EventProcessorMode::Default => match json_mode {
-            true => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
+            true => {
+                let mut processor = EventProcessorWithJsonOutput::new(last_message_file.clone());
+                processor.set_stream_tool_output(json_stream_output);
+                Box::new(processor)
+            }
             _ => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
                 stdout_with_ansi,
                 &config,
                 last_message_file.clone(),
             )),
         },
-        EventProcessorMode::Callback(callback) => {
-            event_processor_bridge::callback_event_processor(callback, last_message_file.clone())
-        }
+        EventProcessorMode::Callback(callback) => event_processor_bridge::callback_event_processor(
+            callback,
+            last_message_file.clone(),
+            include_raw_events,
+        ),
     };
 
     if oss {
@@ -401,11 +427,21 @@ async fn run_main_with_event_processor(
         std::process::exit(1);
     }
 
-    let auth_manager = AuthManager::shared(
-        config.codex_home.clone(),
-        true,
-        config.cli_auth_credentials_store_mode,
-    );
+    // An explicit auth home selects an account, so `CODEX_API_KEY` must not override it.
+    let auth_manager = match api_key.as_deref() {
+        Some(api_key) => AuthManager::shared_with_api_key(
+            config.codex_home.clone(),
+            api_key,
+            config.cli_auth_credentials_store_mode,
+        ),
+        None => AuthManager::shared(
+            auth_home
+                .clone()
+                .unwrap_or_else(|| config.codex_home.clone()),
+            auth_home.is_none(),
+            config.cli_auth_credentials_store_mode,
+        ),
+    };
     let thread_manager = Arc::new(ThreadManager::new(
         config.codex_home.clone(),
         auth_manager.clone(),
@@ -569,21 +605,49 @@ async fn run_main_with_event_processor(
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut command_output = CommandOutputTails::default();
     while let Some(envelope) = rx.recv().await {
         let ThreadEventEnvelope {
             thread_id,
             thread,
             event,
         } = envelope;
-        if let EventMsg::ElicitationRequest(ev) = &event.msg {
-            // Automatically cancel elicitation requests in exec mode.
-            thread
-                .submit(Op::ResolveElicitation {
-                    server_name: ev.server_name.clone(),
-                    request_id: ev.id.clone(),
-                    decision: ElicitationAction::Cancel,
-                })
-                .await?;
+        match &event.msg {
+            EventMsg::ElicitationRequest(ev) => {
+                // Automatically cancel elicitation requests in exec mode.
+                thread
+                    .submit(Op::ResolveElicitation {
+                        server_name: ev.server_name.clone(),
+                        request_id: ev.id.clone(),
+                        decision: ElicitationAction::Cancel,
+                    })
+                    .await?;
+            }
+            EventMsg::ExecCommandOutputDelta(ev) => command_output.push(&ev.call_id, &ev.chunk),
+            EventMsg::ExecCommandEnd(ev) => command_output.remove(&ev.call_id),
+            EventMsg::ExecApprovalRequest(ev) => {
+                let request = EscalationRequest::command(
+                    thread_id.to_string(),
+                    ev,
+                    command_output.take(&ev.call_id),
+                );
+                resolve_escalation(
+                    escalation_handler.clone(),
+                    &thread,
+                    event.id.clone(),
+                    request,
+                );
+            }
+            EventMsg::ApplyPatchApprovalRequest(ev) => {
+                let request = EscalationRequest::patch(thread_id.to_string(), ev);
+                resolve_escalation(
+                    escalation_handler.clone(),
+                    &thread,
+                    event.id.clone(),
+                    request,
+                );
+            }
+            _ => {}
         }
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
@@ -612,6 +676,31 @@ async fn run_main_with_event_processor(
     Ok(())
 }
 
+/// Answers an approval request with the handler's decision, or denies it when exec has no
+/// handler. The handler runs on its own task so events keep flowing while it decides.
+fn resolve_escalation(
+    handler: Option<EscalationHandler>,
+    thread: &Arc<codex_core::CodexThread>,
+    id: String,
+    request: EscalationRequest,
+) {
+    let thread = Arc::clone(thread);
+    tokio::spawn(async move {
+        let kind = request.kind;
+        let decision = match handler {
+            Some(handler) => handler(request).await,
+            None => ReviewDecision::Denied,
+        };
+        let op = match kind {
+            EscalationKind::Command => Op::ExecApproval { id, decision },
+            EscalationKind::Patch => Op::PatchApproval { id, decision },
+        };
+        if let Err(err) = thread.submit(op).await {
+            error!("Failed to submit approval decision: {err:?}");
+        }
+    });
+}
+
 fn spawn_thread_listener(
     thread_id: codex_protocol::ThreadId,
     thread: Arc<codex_core::CodexThread>,
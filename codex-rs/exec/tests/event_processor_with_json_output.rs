@@ -8,6 +8,7 @@ use codex_core::protocol::CollabWaitingEndEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
@@ -29,8 +30,11 @@ use codex_exec::exec_events::CollabAgentStatus;
 use codex_exec::exec_events::CollabTool;
 use codex_exec::exec_events::CollabToolCallItem;
 use codex_exec::exec_events::CollabToolCallStatus;
+use codex_exec::exec_events::CommandCompletedEvent;
 use codex_exec::exec_events::CommandExecutionItem;
 use codex_exec::exec_events::CommandExecutionStatus;
+use codex_exec::exec_events::CommandSandboxDecision;
+use codex_exec::exec_events::CommandStartedEvent;
 use codex_exec::exec_events::ErrorItem;
 use codex_exec::exec_events::ItemCompletedEvent;
 use codex_exec::exec_events::ItemStartedEvent;
@@ -41,6 +45,9 @@ use codex_exec::exec_events::McpToolCallItemResult;
 use codex_exec::exec_events::McpToolCallStatus;
 use codex_exec::exec_events::PatchApplyStatus;
 use codex_exec::exec_events::PatchChangeKind;
+use codex_exec::exec_events::PlanStep;
+use codex_exec::exec_events::PlanStepStatus;
+use codex_exec::exec_events::PlanUpdatedEvent;
 use codex_exec::exec_events::ReasoningItem;
 use codex_exec::exec_events::ThreadErrorEvent;
 use codex_exec::exec_events::ThreadEvent;
@@ -49,6 +56,7 @@ use codex_exec::exec_events::ThreadItemDetails;
 use codex_exec::exec_events::ThreadStartedEvent;
 use codex_exec::exec_events::TodoItem as ExecTodoItem;
 use codex_exec::exec_events::TodoListItem as ExecTodoListItem;
+use codex_exec::exec_events::ToolOutputChunkEvent;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
 use codex_exec::exec_events::TurnStartedEvent;
@@ -106,6 +114,12 @@ fn session_configured_produces_thread_started_event() {
         out,
         vec![ThreadEvent::ThreadStarted(ThreadStartedEvent {
             thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+            model: Some("codex-mini-latest".to_string()),
+            model_provider: Some("test-provider".to_string()),
+            sandbox_policy: Some("read-only".to_string()),
+            cwd: Some("/home/user/project".to_string()),
+            rollout_path: Some("/tmp/rollout.json".to_string()),
+            config_profile: None,
         })]
     );
 }
@@ -247,23 +261,38 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
     let out_first = ep.collect_thread_events(&first);
     assert_eq!(
         out_first,
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::TodoList(ExecTodoListItem {
-                    items: vec![
-                        ExecTodoItem {
-                            text: "step one".to_string(),
-                            completed: false
-                        },
-                        ExecTodoItem {
-                            text: "step two".to_string(),
-                            completed: false
-                        },
-                    ],
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::TodoList(ExecTodoListItem {
+                        items: vec![
+                            ExecTodoItem {
+                                text: "step one".to_string(),
+                                completed: false
+                            },
+                            ExecTodoItem {
+                                text: "step two".to_string(),
+                                completed: false
+                            },
+                        ],
+                    }),
+                },
+            }),
+            ThreadEvent::PlanUpdated(PlanUpdatedEvent {
+                explanation: None,
+                plan: vec![
+                    PlanStep {
+                        step: "step one".to_string(),
+                        status: PlanStepStatus::Pending,
+                    },
+                    PlanStep {
+                        step: "step two".to_string(),
+                        status: PlanStepStatus::InProgress,
+                    },
+                ],
+            }),
+        ]
     );
 
     // Second plan update in same turn => item.updated (same id)
@@ -286,23 +315,38 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
     let out_second = ep.collect_thread_events(&second);
     assert_eq!(
         out_second,
-        vec![ThreadEvent::ItemUpdated(ItemUpdatedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::TodoList(ExecTodoListItem {
-                    items: vec![
-                        ExecTodoItem {
-                            text: "step one".to_string(),
-                            completed: true
-                        },
-                        ExecTodoItem {
-                            text: "step two".to_string(),
-                            completed: false
-                        },
-                    ],
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemUpdated(ItemUpdatedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::TodoList(ExecTodoListItem {
+                        items: vec![
+                            ExecTodoItem {
+                                text: "step one".to_string(),
+                                completed: true
+                            },
+                            ExecTodoItem {
+                                text: "step two".to_string(),
+                                completed: false
+                            },
+                        ],
+                    }),
+                },
+            }),
+            ThreadEvent::PlanUpdated(PlanUpdatedEvent {
+                explanation: None,
+                plan: vec![
+                    PlanStep {
+                        step: "step one".to_string(),
+                        status: PlanStepStatus::Completed,
+                    },
+                    PlanStep {
+                        step: "step two".to_string(),
+                        status: PlanStepStatus::InProgress,
+                    },
+                ],
+            }),
+        ]
     );
 
     // Task completes => item.completed (same id, latest state)
@@ -335,6 +379,8 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             }),
             ThreadEvent::TurnCompleted(TurnCompletedEvent {
                 usage: Usage::default(),
+                redaction_applied: None,
+                dropped_events: None,
             }),
         ]
     );
@@ -369,6 +415,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
                     result: None,
                     error: None,
                     status: McpToolCallStatus::InProgress,
+                    cache_hit: None,
                 }),
             },
         })]
@@ -404,6 +451,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
                     }),
                     error: None,
                     status: McpToolCallStatus::Completed,
+                    cache_hit: None,
                 }),
             },
         })]
@@ -452,6 +500,7 @@ fn mcp_tool_call_failure_sets_failed_status() {
                         message: "tool exploded".to_string(),
                     }),
                     status: McpToolCallStatus::Failed,
+                    cache_hit: None,
                 }),
             },
         })]
@@ -487,6 +536,7 @@ fn mcp_tool_call_defaults_arguments_and_preserves_structured_content() {
                     result: None,
                     error: None,
                     status: McpToolCallStatus::InProgress,
+                    cache_hit: None,
                 }),
             },
         })]
@@ -522,6 +572,7 @@ fn mcp_tool_call_defaults_arguments_and_preserves_structured_content() {
                     }),
                     error: None,
                     status: McpToolCallStatus::Completed,
+                    cache_hit: None,
                 }),
             },
         })]
@@ -846,6 +897,7 @@ fn exec_command_end_success_produces_completed_command_item() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
     let command = vec!["bash".to_string(), "-lc".to_string(), "echo hi".to_string()];
     let cwd = std::env::current_dir().unwrap();
+    let cwd_string = cwd.to_string_lossy().into_owned();
     let parsed_cmd = Vec::new();
 
     // Begin -> no output
@@ -865,17 +917,30 @@ fn exec_command_end_success_produces_completed_command_item() {
     let out_begin = ep.collect_thread_events(&begin);
     assert_eq!(
         out_begin,
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "bash -lc 'echo hi'".to_string(),
-                    aggregated_output: String::new(),
-                    exit_code: None,
-                    status: CommandExecutionStatus::InProgress,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "bash -lc 'echo hi'".to_string(),
+                        aggregated_output: String::new(),
+                        exit_code: None,
+                        status: CommandExecutionStatus::InProgress,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandStarted(CommandStartedEvent {
+                call_id: "1".to_string(),
+                item_id: "item_0".to_string(),
+                command: command.clone(),
+                cwd: cwd_string,
+                source: ExecCommandSource::Agent,
+                process_id: None,
+                sandboxed: true,
+            }),
+        ]
     );
 
     // End (success) -> item.completed (item_0)
@@ -885,7 +950,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             call_id: "1".to_string(),
             process_id: None,
             turn_id: "turn-1".to_string(),
-            command,
+            command: command.clone(),
             cwd,
             parsed_cmd,
             source: ExecCommandSource::Agent,
@@ -901,29 +966,62 @@ fn exec_command_end_success_produces_completed_command_item() {
     let out_ok = ep.collect_thread_events(&end_ok);
     assert_eq!(
         out_ok,
-        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "bash -lc 'echo hi'".to_string(),
-                    aggregated_output: "hi\n".to_string(),
-                    exit_code: Some(0),
-                    status: CommandExecutionStatus::Completed,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemCompleted(ItemCompletedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "bash -lc 'echo hi'".to_string(),
+                        aggregated_output: "hi\n".to_string(),
+                        exit_code: Some(0),
+                        status: CommandExecutionStatus::Completed,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandCompleted(CommandCompletedEvent {
+                call_id: "1".to_string(),
+                item_id: "item_0".to_string(),
+                command,
+                exit_code: 0,
+                duration_ms: 5,
+                output: "hi\n".to_string(),
+                output_truncated: false,
+                sandbox_decision: CommandSandboxDecision::Sandboxed,
+                approval_reason: None,
+                model_output: None,
+            }),
+        ]
     );
 }
 
+#[test]
+fn output_deltas_are_not_emitted_unless_streaming() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let delta = event(
+        "d1",
+        EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+            call_id: "delta-1".to_string(),
+            stream: ExecOutputStream::Stdout,
+            chunk: b"partial output\n".to_vec(),
+        }),
+    );
+
+    assert_eq!(ep.collect_thread_events(&delta), Vec::new());
+}
+
 #[test]
 fn command_execution_output_delta_updates_item_progress() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
+    ep.set_stream_tool_output(true);
     let command = vec![
         "bash".to_string(),
         "-lc".to_string(),
         "echo delta".to_string(),
     ];
     let cwd = std::env::current_dir().unwrap();
+    let cwd_string = cwd.to_string_lossy().into_owned();
     let parsed_cmd = Vec::new();
 
     let begin = event(
@@ -942,17 +1040,30 @@ fn command_execution_output_delta_updates_item_progress() {
     let out_begin = ep.collect_thread_events(&begin);
     assert_eq!(
         out_begin,
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "bash -lc 'echo delta'".to_string(),
-                    aggregated_output: String::new(),
-                    exit_code: None,
-                    status: CommandExecutionStatus::InProgress,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "bash -lc 'echo delta'".to_string(),
+                        aggregated_output: String::new(),
+                        exit_code: None,
+                        status: CommandExecutionStatus::InProgress,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandStarted(CommandStartedEvent {
+                call_id: "delta-1".to_string(),
+                item_id: "item_0".to_string(),
+                command: command.clone(),
+                cwd: cwd_string,
+                source: ExecCommandSource::Agent,
+                process_id: Some("42".to_string()),
+                sandboxed: true,
+            }),
+        ]
     );
 
     let delta = event(
@@ -964,7 +1075,14 @@ fn command_execution_output_delta_updates_item_progress() {
         }),
     );
     let out_delta = ep.collect_thread_events(&delta);
-    assert_eq!(out_delta, Vec::<ThreadEvent>::new());
+    assert_eq!(
+        out_delta,
+        vec![ThreadEvent::ToolOutputChunk(ToolOutputChunkEvent {
+            call_id: "delta-1".to_string(),
+            item_id: Some("item_0".to_string()),
+            chunk: "partial output\n".to_string(),
+        })]
+    );
 
     let end = event(
         "d3",
@@ -972,7 +1090,7 @@ fn command_execution_output_delta_updates_item_progress() {
             call_id: "delta-1".to_string(),
             process_id: Some("42".to_string()),
             turn_id: "turn-1".to_string(),
-            command,
+            command: command.clone(),
             cwd,
             parsed_cmd,
             source: ExecCommandSource::Agent,
@@ -988,17 +1106,33 @@ fn command_execution_output_delta_updates_item_progress() {
     let out_end = ep.collect_thread_events(&end);
     assert_eq!(
         out_end,
-        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "bash -lc 'echo delta'".to_string(),
-                    aggregated_output: String::new(),
-                    exit_code: Some(0),
-                    status: CommandExecutionStatus::Completed,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemCompleted(ItemCompletedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "bash -lc 'echo delta'".to_string(),
+                        aggregated_output: String::new(),
+                        exit_code: Some(0),
+                        status: CommandExecutionStatus::Completed,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandCompleted(CommandCompletedEvent {
+                call_id: "delta-1".to_string(),
+                item_id: "item_0".to_string(),
+                command,
+                exit_code: 0,
+                duration_ms: 3,
+                output: String::new(),
+                output_truncated: false,
+                sandbox_decision: CommandSandboxDecision::Sandboxed,
+                approval_reason: None,
+                model_output: None,
+            }),
+        ]
     );
 }
 
@@ -1007,6 +1141,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
     let command = vec!["sh".to_string(), "-c".to_string(), "exit 1".to_string()];
     let cwd = std::env::current_dir().unwrap();
+    let cwd_string = cwd.to_string_lossy().into_owned();
     let parsed_cmd = Vec::new();
 
     // Begin -> no output
@@ -1025,17 +1160,30 @@ fn exec_command_end_failure_produces_failed_command_item() {
     );
     assert_eq!(
         ep.collect_thread_events(&begin),
-        vec![ThreadEvent::ItemStarted(ItemStartedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "sh -c 'exit 1'".to_string(),
-                    aggregated_output: String::new(),
-                    exit_code: None,
-                    status: CommandExecutionStatus::InProgress,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "sh -c 'exit 1'".to_string(),
+                        aggregated_output: String::new(),
+                        exit_code: None,
+                        status: CommandExecutionStatus::InProgress,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandStarted(CommandStartedEvent {
+                call_id: "2".to_string(),
+                item_id: "item_0".to_string(),
+                command: command.clone(),
+                cwd: cwd_string,
+                source: ExecCommandSource::Agent,
+                process_id: None,
+                sandboxed: true,
+            }),
+        ]
     );
 
     // End (failure) -> item.completed (item_0)
@@ -1045,7 +1193,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             call_id: "2".to_string(),
             process_id: None,
             turn_id: "turn-1".to_string(),
-            command,
+            command: command.clone(),
             cwd,
             parsed_cmd,
             source: ExecCommandSource::Agent,
@@ -1061,17 +1209,33 @@ fn exec_command_end_failure_produces_failed_command_item() {
     let out_fail = ep.collect_thread_events(&end_fail);
     assert_eq!(
         out_fail,
-        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
-            item: ThreadItem {
-                id: "item_0".to_string(),
-                details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
-                    command: "sh -c 'exit 1'".to_string(),
-                    aggregated_output: String::new(),
-                    exit_code: Some(1),
-                    status: CommandExecutionStatus::Failed,
-                }),
-            },
-        })]
+        vec![
+            ThreadEvent::ItemCompleted(ItemCompletedEvent {
+                item: ThreadItem {
+                    id: "item_0".to_string(),
+                    details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+                        command: "sh -c 'exit 1'".to_string(),
+                        aggregated_output: String::new(),
+                        exit_code: Some(1),
+                        status: CommandExecutionStatus::Failed,
+                        cache_hit: None,
+                        is_tty: Some(false),
+                    }),
+                },
+            }),
+            ThreadEvent::CommandCompleted(CommandCompletedEvent {
+                call_id: "2".to_string(),
+                item_id: "item_0".to_string(),
+                command,
+                exit_code: 1,
+                duration_ms: 2,
+                output: String::new(),
+                output_truncated: false,
+                sandbox_decision: CommandSandboxDecision::Sandboxed,
+                approval_reason: None,
+                model_output: None,
+            }),
+        ]
     );
 }
 
@@ -1103,6 +1267,173 @@ fn exec_command_end_without_begin_is_ignored() {
     assert!(out.is_empty());
 }
 
+#[test]
+fn command_completed_reports_sandbox_decision_and_output_tail() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let begin = |call_id: &str| {
+        event(
+            call_id,
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id: call_id.to_string(),
+                process_id: None,
+                turn_id: "turn-1".to_string(),
+                command: vec!["touch".to_string(), "/etc/hosts".to_string()],
+                cwd: PathBuf::from("/repo"),
+                parsed_cmd: Vec::new(),
+                source: ExecCommandSource::Agent,
+                interaction_input: None,
+            }),
+        )
+    };
+    let approval = |call_id: &str| {
+        event(
+            call_id,
+            EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+                call_id: call_id.to_string(),
+                turn_id: "turn-1".to_string(),
+                command: vec!["touch".to_string(), "/etc/hosts".to_string()],
+                cwd: PathBuf::from("/repo"),
+                reason: Some("command failed; retry without sandbox?".to_string()),
+                proposed_execpolicy_amendment: None,
+                parsed_cmd: Vec::new(),
+            }),
+        )
+    };
+    let end = |call_id: &str, stderr: &str, aggregated_output: String, formatted_output: &str| {
+        event(
+            call_id,
+            EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id: call_id.to_string(),
+                process_id: None,
+                turn_id: "turn-1".to_string(),
+                command: vec!["touch".to_string(), "/etc/hosts".to_string()],
+                cwd: PathBuf::from("/repo"),
+                parsed_cmd: Vec::new(),
+                source: ExecCommandSource::Agent,
+                interaction_input: None,
+                stdout: String::new(),
+                stderr: stderr.to_string(),
+                aggregated_output,
+                exit_code: -1,
+                duration: Duration::ZERO,
+                formatted_output: formatted_output.to_string(),
+            }),
+        )
+    };
+    let completed = |events: Vec<ThreadEvent>| match events.as_slice() {
+        [
+            ThreadEvent::ItemCompleted(_),
+            ThreadEvent::CommandCompleted(completed),
+        ] => completed.clone(),
+        other => panic!("unexpected events {other:?}"),
+    };
+
+    ep.collect_thread_events(&begin("denied"));
+    assert!(ep.collect_thread_events(&approval("denied")).is_empty());
+    let denied = completed(ep.collect_thread_events(&end(
+        "denied",
+        "exec command rejected by user",
+        "exec command rejected by user".to_string(),
+        "exec command rejected by user",
+    )));
+    assert_eq!(denied.sandbox_decision, CommandSandboxDecision::Denied);
+    assert_eq!(
+        denied.approval_reason.as_deref(),
+        Some("command failed; retry without sandbox?")
+    );
+
+    ep.collect_thread_events(&begin("approved"));
+    ep.collect_thread_events(&approval("approved"));
+    let approved = completed(ep.collect_thread_events(&end(
+        "approved",
+        "",
+        format!("{}done", "x".repeat(20_000)),
+        "Total output lines: 1\n\nxxxx…19997 chars truncated…done",
+    )));
+    assert_eq!(approved.sandbox_decision, CommandSandboxDecision::Approved);
+    assert_eq!(approved.output.len(), 16 * 1024);
+    assert!(approved.output.ends_with("xdone"));
+    assert!(approved.output_truncated);
+    assert_eq!(
+        approved.model_output.as_deref(),
+        Some("Total output lines: 1\n\nxxxx…19997 chars truncated…done")
+    );
+
+    ep.collect_thread_events(&event(
+        "session",
+        EventMsg::SessionConfigured(SessionConfiguredEvent {
+            session_id: ThreadId::new(),
+            forked_from_id: None,
+            thread_name: None,
+            model: "codex-mini-latest".to_string(),
+            model_provider_id: "test-provider".to_string(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            cwd: PathBuf::from("/repo"),
+            reasoning_effort: None,
+            history_log_id: 0,
+            history_entry_count: 0,
+            initial_messages: None,
+            rollout_path: None,
+        }),
+    ));
+    match ep.collect_thread_events(&begin("unsandboxed")).as_slice() {
+        [
+            ThreadEvent::ItemStarted(_),
+            ThreadEvent::CommandStarted(started),
+        ] => assert!(!started.sandboxed),
+        other => panic!("unexpected events {other:?}"),
+    }
+    let unsandboxed =
+        completed(ep.collect_thread_events(&end("unsandboxed", "", "ok".to_string(), "ok")));
+    assert_eq!(
+        unsandboxed.sandbox_decision,
+        CommandSandboxDecision::Unsandboxed
+    );
+    assert_eq!(unsandboxed.output, "ok");
+    assert!(!unsandboxed.output_truncated);
+}
+
+#[test]
+fn shell_commands_report_tty_when_pty_is_configured() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    ep.set_shell_is_tty(true);
+    let begin = |call_id: &str, source| {
+        event(
+            call_id,
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id: call_id.to_string(),
+                process_id: None,
+                turn_id: "turn-1".to_string(),
+                command: vec!["ls".to_string()],
+                cwd: PathBuf::from("."),
+                parsed_cmd: Vec::new(),
+                source,
+                interaction_input: None,
+            }),
+        )
+    };
+
+    let is_tty = |events: Vec<ThreadEvent>| match events.as_slice() {
+        [
+            ThreadEvent::ItemStarted(ItemStartedEvent { item }),
+            ThreadEvent::CommandStarted(_),
+        ] => match &item.details {
+            ThreadItemDetails::CommandExecution(command) => command.is_tty,
+            other => panic!("unexpected item {other:?}"),
+        },
+        other => panic!("unexpected events {other:?}"),
+    };
+    assert_eq!(
+        is_tty(ep.collect_thread_events(&begin("shell", ExecCommandSource::Agent))),
+        Some(true)
+    );
+    assert_eq!(
+        is_tty(ep.collect_thread_events(&begin("unified", ExecCommandSource::UnifiedExecStartup))),
+        None
+    );
+}
+
 #[test]
 fn patch_apply_success_produces_item_completed_patchapply() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -1287,6 +1618,8 @@ fn task_complete_produces_turn_completed_with_usage() {
                 cached_input_tokens: 200,
                 output_tokens: 345,
             },
+            redaction_applied: None,
+            dropped_events: None,
         })]
     );
 }
@@ -0,0 +1 @@
+CREATE INDEX idx_threads_cwd_updated_at ON threads(cwd, updated_at DESC, id DESC);
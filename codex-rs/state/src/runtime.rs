@@ -283,6 +283,30 @@ WHERE thread_id = ?
         allowed_sources: &[String],
         model_providers: Option<&[String]>,
         archived_only: bool,
+    ) -> anyhow::Result<crate::ThreadsPage> {
+        self.list_threads_in_cwd(
+            page_size,
+            anchor,
+            sort_key,
+            allowed_sources,
+            model_providers,
+            archived_only,
+            None,
+        )
+        .await
+    }
+
+    /// List threads using the underlying database, optionally only those recorded in `cwd`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_threads_in_cwd(
+        &self,
+        page_size: usize,
+        anchor: Option<&crate::Anchor>,
+        sort_key: crate::SortKey,
+        allowed_sources: &[String],
+        model_providers: Option<&[String]>,
+        archived_only: bool,
+        cwd: Option<&Path>,
     ) -> anyhow::Result<crate::ThreadsPage> {
         let limit = page_size.saturating_add(1);
 
@@ -317,6 +341,11 @@ FROM threads
             anchor,
             sort_key,
         );
+        if let Some(cwd) = cwd {
+            builder
+                .push(" AND cwd = ")
+                .push_bind(cwd.display().to_string());
+        }
         push_thread_order_and_limit(&mut builder, sort_key, limit);
 
         let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
@@ -930,6 +930,8 @@ impl App {
         use tokio_stream::StreamExt;
         let (app_event_tx, mut app_event_rx) = unbounded_channel();
         let app_event_tx = AppEventSender::new(app_event_tx);
+        let _thread_status_registration =
+            crate::thread_status::register_app_event_sender(app_event_tx.clone());
         emit_project_config_warnings(&app_event_tx, &config);
         tui.set_notification_method(config.tui_notification_method);
 
@@ -2261,6 +2263,15 @@ impl App {
                     }
                 }
             }
+            AppEvent::ThreadStatusUpdated { thread_id, status } => {
+                if self
+                    .chat_widget
+                    .thread_id()
+                    .is_some_and(|id| id.to_string() == thread_id)
+                {
+                    self.chat_widget.set_thread_status(status);
+                }
+            }
             AppEvent::StatusLineBranchUpdated { cwd, branch } => {
                 self.chat_widget.set_status_line_branch(cwd, branch);
                 self.refresh_status_line();
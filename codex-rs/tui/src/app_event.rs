@@ -21,6 +21,7 @@ use codex_protocol::openai_models::ModelPreset;
 use crate::bottom_pane::ApprovalRequest;
 use crate::bottom_pane::StatusLineItem;
 use crate::history_cell::HistoryCell;
+use crate::thread_status::ThreadStatus;
 
 use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
@@ -294,6 +295,12 @@ pub(crate) enum AppEvent {
     /// Launch the external editor after a normal draw has completed.
     LaunchExternalEditor,
 
+    /// Status reported for a thread from outside the TUI; `None` clears it.
+    ThreadStatusUpdated {
+        thread_id: String,
+        status: Option<ThreadStatus>,
+    },
+
     /// Async update of the current git branch for status line rendering.
     StatusLineBranchUpdated {
         cwd: PathBuf,
@@ -196,6 +196,7 @@ use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
+use crate::thread_status::ThreadStatus;
 use crate::tui::FrameRequester;
 mod interrupts;
 use self::interrupts::InterruptManager;
@@ -538,6 +539,8 @@ pub(crate) struct ChatWidget {
     current_status_header: String,
     // Previous status header to restore after a transient stream retry.
     retry_status_header: Option<String>,
+    // Progress reported from outside the TUI, shown as status details while a turn runs.
+    thread_status: Option<ThreadStatus>,
     thread_id: Option<ThreadId>,
     thread_name: Option<String>,
     forked_from: Option<ThreadId>,
@@ -808,6 +811,7 @@ impl ChatWidget {
     /// Passing `None` clears any existing details.
     fn set_status(&mut self, header: String, details: Option<String>) {
         self.current_status_header = header.clone();
+        let details = details.or_else(|| self.thread_status.as_ref().map(ThreadStatus::display));
         self.bottom_pane.update_status(header, details);
     }
 
@@ -817,6 +821,14 @@ impl ChatWidget {
         self.set_status(header, None);
     }
 
+    /// Shows progress reported for this thread with `thread_status::set_thread_status`.
+    ///
+    /// The status is cleared when the turn completes.
+    pub(crate) fn set_thread_status(&mut self, status: Option<ThreadStatus>) {
+        self.thread_status = status;
+        self.set_status_header(self.current_status_header.clone());
+    }
+
     /// Sets the currently rendered footer status-line value and schedules a redraw.
     pub(crate) fn set_status_line(&mut self, status_line: Option<Line<'static>>) {
         self.bottom_pane.set_status_line(status_line);
@@ -1259,6 +1271,7 @@ impl ChatWidget {
         // Mark task stopped and request redraw now that all content is in history.
         self.agent_turn_running = false;
         self.update_task_running_state();
+        self.thread_status = None;
         self.running_commands.clear();
         self.suppressed_exec_calls.clear();
         self.last_unified_wait = None;
@@ -2511,6 +2524,7 @@ impl ChatWidget {
             full_reasoning_buffer: String::new(),
             current_status_header: String::from("Working"),
             retry_status_header: None,
+            thread_status: None,
             thread_id: None,
             thread_name: None,
             forked_from: None,
@@ -2672,6 +2686,7 @@ impl ChatWidget {
             full_reasoning_buffer: String::new(),
             current_status_header: String::from("Working"),
             retry_status_header: None,
+            thread_status: None,
             thread_id: None,
             thread_name: None,
             forked_from: None,
@@ -2822,6 +2837,7 @@ impl ChatWidget {
             full_reasoning_buffer: String::new(),
             current_status_header: String::from("Working"),
             retry_status_header: None,
+            thread_status: None,
             thread_id: None,
             thread_name: None,
             forked_from: None,
@@ -936,6 +936,7 @@ async fn make_chatwidget_manual(
         full_reasoning_buffer: String::new(),
         current_status_header: String::from("Working"),
         retry_status_header: None,
+        thread_status: None,
         thread_id: None,
         thread_name: None,
         forked_from: None,
@@ -4401,6 +4402,40 @@ async fn background_event_updates_status_header() {
     assert!(drain_insert_history(&mut rx).is_empty());
 }
 
+#[tokio::test]
+async fn thread_status_shows_as_status_details_until_turn_completes() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+            collaboration_mode_kind: ModeKind::Default,
+        }),
+    });
+
+    chat.set_thread_status(Some(crate::thread_status::ThreadStatus {
+        text: "Indexing workspace".to_string(),
+        progress_pct: Some(42.4),
+    }));
+    let details = |chat: &ChatWidget| {
+        chat.bottom_pane
+            .status_widget()
+            .and_then(|status| status.details().map(str::to_string))
+    };
+    assert_eq!(details(&chat), Some("Indexing workspace (42%)".to_string()));
+
+    chat.set_status_header("Running tests".to_string());
+    assert_eq!(details(&chat), Some("Indexing workspace (42%)".to_string()));
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    assert_eq!(chat.thread_status, None);
+}
+
 #[tokio::test]
 async fn apply_patch_events_emit_history_cells() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
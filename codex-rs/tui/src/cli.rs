@@ -2,6 +2,7 @@ use clap::Parser;
 use clap::ValueHint;
 use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
+use codex_core::ExternalToolLayers;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -112,4 +113,17 @@ pub struct Cli {
 
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
+
+    /// Tools, filter, limits and interceptors added to the session's tool router (for
+    /// programmatic callers).
+    #[clap(skip)]
+    pub tool_layers: ExternalToolLayers,
+
+    /// API key used instead of any stored credentials or `CODEX_API_KEY`.
+    #[clap(skip)]
+    pub api_key: Option<String>,
+
+    /// Endpoint for the built-in OpenAI provider, in place of `OPENAI_BASE_URL`.
+    #[clap(skip)]
+    pub openai_base_url: Option<String>,
 }
@@ -102,6 +102,7 @@ mod streaming;
 mod style;
 mod terminal_palette;
 mod text_formatting;
+mod thread_status;
 mod tooltips;
 mod tui;
 mod ui_consts;
@@ -122,6 +123,8 @@ pub use cli::Cli;
 pub use markdown_render::render_markdown_text;
 pub use public_widgets::composer_input::ComposerAction;
 pub use public_widgets::composer_input::ComposerInput;
+pub use thread_status::ThreadStatus;
+pub use thread_status::set_thread_status;
 // (tests access modules directly within the crate)
 
 pub async fn run_main(
@@ -283,6 +286,8 @@ pub async fn run_main_with_shutdown_token(
         codex_linux_sandbox_exe,
         show_raw_agent_reasoning: cli.oss.then_some(true),
         additional_writable_roots: additional_dirs,
+        external_tool_layers: cli.tool_layers.clone(),
+        openai_base_url: cli.openai_base_url.clone(),
         ..Default::default()
     };
 
@@ -470,12 +475,24 @@ async fn run_ratatui_app(
     // Initialize high-fidelity session event logging if enabled.
     session_log::maybe_init(&initial_config);
 
-    let auth_manager = AuthManager::shared(
-        initial_config.codex_home.clone(),
-        false,
-        initial_config.cli_auth_credentials_store_mode,
-    );
-    let login_status = get_login_status(&initial_config);
+    let auth_manager = match cli.api_key.as_deref() {
+        Some(api_key) => AuthManager::shared_with_api_key(
+            initial_config.codex_home.clone(),
+            api_key,
+            initial_config.cli_auth_credentials_store_mode,
+        ),
+        None => AuthManager::shared(
+            initial_config.codex_home.clone(),
+            false,
+            initial_config.cli_auth_credentials_store_mode,
+        ),
+    };
+    // A key passed by the caller stands in for stored credentials, so skip the login screen.
+    let login_status = if cli.api_key.is_some() {
+        LoginStatus::AuthMode(AuthMode::ApiKey)
+    } else {
+        get_login_status(&initial_config)
+    };
     let should_show_trust_screen_flag = should_show_trust_screen(&initial_config);
     let should_show_onboarding =
         should_show_onboarding(login_status, &initial_config, should_show_trust_screen_flag);
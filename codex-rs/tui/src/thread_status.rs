@@ -0,0 +1,67 @@
+//! Progress for a thread reported from outside the TUI.
+//!
+//! Embedders such as the native SDK run the TUI in-process while their own tools do long-running
+//! work. [`set_thread_status`] lets those tools report progress ("Indexing workspace", 40%) without
+//! going through the agent. The running [`crate::app::App`] registers its event sender here, and
+//! the chat widget shows the status under the working indicator while the thread is active.
+
+use std::sync::Mutex;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+static APP_EVENT_SENDER: Mutex<Option<AppEventSender>> = Mutex::new(None);
+
+/// Status text for a thread, optionally with a completion percentage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadStatus {
+    pub text: String,
+    /// 0-100. Values outside the range are clamped when rendered.
+    pub progress_pct: Option<f64>,
+}
+
+impl ThreadStatus {
+    /// The text shown in the status indicator, e.g. `Indexing workspace (40%)`.
+    pub(crate) fn display(&self) -> String {
+        match self.progress_pct {
+            Some(pct) => format!("{} ({:.0}%)", self.text, pct.clamp(0.0, 100.0)),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Show `status` for `thread_id` in the running TUI, or clear it with `None`.
+///
+/// Returns `false` when no TUI is running in this process. Updates for a thread other than the one
+/// on screen are ignored.
+pub fn set_thread_status(thread_id: &str, status: Option<ThreadStatus>) -> bool {
+    let Ok(sender) = APP_EVENT_SENDER.lock() else {
+        return false;
+    };
+    let Some(sender) = sender.as_ref() else {
+        return false;
+    };
+    sender.send(AppEvent::ThreadStatusUpdated {
+        thread_id: thread_id.to_string(),
+        status,
+    });
+    true
+}
+
+/// Keeps the app's sender registered until dropped.
+pub(crate) struct ThreadStatusRegistration;
+
+pub(crate) fn register_app_event_sender(sender: AppEventSender) -> ThreadStatusRegistration {
+    if let Ok(mut slot) = APP_EVENT_SENDER.lock() {
+        *slot = Some(sender);
+    }
+    ThreadStatusRegistration
+}
+
+impl Drop for ThreadStatusRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = APP_EVENT_SENDER.lock() {
+            *slot = None;
+        }
+    }
+}
@@ -23,3 +23,7 @@ pub type SpawnedPty = SpawnedProcess;
 pub use pty::conpty_supported;
 /// Spawn a process attached to a PTY for interactive use.
 pub use pty::spawn_process as spawn_pty_process;
+/// Spawn a process attached to a PTY of a given size.
+pub use pty::spawn_process_with_size as spawn_pty_process_with_size;
+/// Rows and columns of a PTY.
+pub use pty::TerminalSize;
@@ -55,6 +55,19 @@ fn platform_native_pty_system() -> Box<dyn portable_pty::PtySystem + Send> {
     }
 }
 
+/// Rows and columns of a PTY.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
 /// Spawn a process attached to a PTY, returning handles for stdin, output, and exit.
 pub async fn spawn_process(
     program: &str,
@@ -62,6 +75,18 @@ pub async fn spawn_process(
     cwd: &Path,
     env: &HashMap<String, String>,
     arg0: &Option<String>,
+) -> Result<SpawnedProcess> {
+    spawn_process_with_size(program, args, cwd, env, arg0, TerminalSize::default()).await
+}
+
+/// Like [`spawn_process`], with a PTY of the given size.
+pub async fn spawn_process_with_size(
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    arg0: &Option<String>,
+    size: TerminalSize,
 ) -> Result<SpawnedProcess> {
     if program.is_empty() {
         anyhow::bail!("missing program for PTY spawn");
@@ -69,8 +94,8 @@ pub async fn spawn_process(
 
     let pty_system = platform_native_pty_system();
     let pair = pty_system.openpty(PtySize {
-        rows: 24,
-        cols: 80,
+        rows: size.rows,
+        cols: size.cols,
         pixel_width: 0,
         pixel_height: 0,
     })?;
@@ -5,6 +5,8 @@ use pretty_assertions::assert_eq;
 
 use crate::spawn_pipe_process;
 use crate::spawn_pty_process;
+use crate::spawn_pty_process_with_size;
+use crate::TerminalSize;
 
 fn find_python() -> Option<String> {
     for candidate in ["python3", "python"] {
@@ -226,6 +228,33 @@ async fn pipe_and_pty_share_interface() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pty_reports_requested_terminal_size() -> anyhow::Result<()> {
+    if cfg!(windows) {
+        return Ok(());
+    }
+    let env_map: HashMap<String, String> = std::env::vars().collect();
+    let (program, args) = shell_command("stty size");
+    let spawned = spawn_pty_process_with_size(
+        &program,
+        &args,
+        Path::new("."),
+        &env_map,
+        &None,
+        TerminalSize {
+            rows: 40,
+            cols: 120,
+        },
+    )
+    .await?;
+
+    let (output, code) = collect_output_until_exit(spawned.output_rx, spawned.exit_rx, 3_000).await;
+    assert_eq!(code, 0);
+    assert_eq!(String::from_utf8_lossy(&output).trim(), "40 120");
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn pipe_drains_stderr_without_stdout_activity() -> anyhow::Result<()> {
     let Some(python) = find_python() else {
@@ -5,6 +5,8 @@ use std::process::Command;
 
 fn main() {
   napi_build::setup();
+  #[cfg(feature = "grpc")]
+  compile_grpc_protos();
   if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("linux") {
     return;
   }
@@ -14,6 +16,26 @@ fn main() {
   }
 }
 
+#[cfg(feature = "grpc")]
+fn compile_grpc_protos() {
+  let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is available");
+  // SAFETY: build scripts are single-threaded; nothing else reads the environment concurrently.
+  unsafe { env::set_var("PROTOC", protoc) };
+  println!("cargo:rerun-if-changed=proto/codex_native.proto");
+  if let Err(err) = tonic_prost_build::configure()
+    .build_client(false)
+    .compile_protos(
+      &["proto/codex_native.proto"],
+      &[
+        PathBuf::from("proto"),
+        protoc_bin_vendored::include_path().expect("vendored protobuf includes are available"),
+      ],
+    )
+  {
+    panic!("failed to compile proto/codex_native.proto: {err}");
+  }
+}
+
 fn build_linux_sandbox() -> Result<(), Box<dyn std::error::Error>> {
   let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
   let workspace_root = manifest_dir.join("../../codex-rs").canonicalize()?;
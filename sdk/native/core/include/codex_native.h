@@ -0,0 +1,57 @@
+/*
+ * C interface to codex-native-core, built with `cargo build -p codex-native-core --features ffi`
+ * (libcodex_native_core.{so,dylib,a}, codex_native_core.dll).
+ *
+ * Requests and results are UTF-8 JSON. Functions returning `char *` return NULL on failure and,
+ * when `error_out` is not NULL, store an error message there. Free every returned string,
+ * results and errors alike, with codex_native_string_free().
+ */
+#ifndef CODEX_NATIVE_H
+#define CODEX_NATIVE_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Receives one event's JSON. `event_json` is only valid during the call. */
+typedef void (*codex_native_event_cb)(const char *event_json, void *user_data);
+
+/*
+ * Run a turn to completion and return the JSON array of its events. `request_json` is a run
+ * request: {"prompt", "threadId"?, "images"?, "model"?, "workingDirectory"?, "sandboxMode"?,
+ * "approvalMode"?, "skipGitRepoCheck"?, "apiKey"?, "baseUrl"?, "linuxSandboxPath"?,
+ * "allowedTools"?, "deniedTools"?, "toolLimits"?, "redaction"?}. Escalations are denied.
+ */
+char *codex_native_run(const char *request_json, char **error_out);
+
+/*
+ * Run a turn, calling `on_event` with each event as it happens. Blocks until the turn ends.
+ * `on_event` may be called from a runtime thread, one event at a time.
+ * Returns 0 on success and -1 on failure.
+ */
+int32_t codex_native_run_stream(const char *request_json, codex_native_event_cb on_event,
+                                void *user_data, char **error_out);
+
+/*
+ * Encode `text` and return the JSON array of token ids. `options_json` may be NULL or
+ * {"model"?, "encoding"? ("o200k_base" | "cl100k_base"), "withSpecialTokens"?}.
+ */
+char *codex_native_tokenize(const char *text, const char *options_json, char **error_out);
+
+/*
+ * Keyword search over the conversations under `codex_home`. Returns a JSON array of
+ * {"id", "path", "updatedAt", "relevanceScore", "matchingExcerpts"}, best match first.
+ */
+char *codex_native_reverie_search(const char *codex_home, const char *query, uint32_t limit,
+                                  char **error_out);
+
+/* Free a string returned by this library. NULL is ignored. */
+void codex_native_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CODEX_NATIVE_H */
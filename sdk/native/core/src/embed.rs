@@ -0,0 +1,152 @@
+//! Local text embeddings with FastEmbed.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use fastembed::EmbeddingModel;
+use fastembed::TextEmbedding;
+use fastembed::TextInitOptions;
+
+use crate::Error;
+use crate::Result;
+
+/// How to load an embedding model. Models are downloaded on first use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedderOptions {
+  /// FastEmbed model code, e.g. `BAAI/bge-small-en-v1.5`. FastEmbed's default when unset.
+  pub model: Option<String>,
+  pub cache_dir: Option<PathBuf>,
+  pub max_length: Option<usize>,
+  pub show_download_progress: Option<bool>,
+}
+
+impl EmbedderOptions {
+  pub fn init_options(&self) -> Result<TextInitOptions> {
+    let mut init_options = TextInitOptions::new(resolve_model(self.model.as_deref())?);
+    if let Some(max_length) = self.max_length {
+      init_options = init_options.with_max_length(max_length);
+    }
+    if let Some(cache_dir) = &self.cache_dir {
+      init_options = init_options.with_cache_dir(cache_dir.clone());
+    }
+    if let Some(show_download_progress) = self.show_download_progress {
+      init_options = init_options.with_show_download_progress(show_download_progress);
+    }
+    Ok(init_options)
+  }
+}
+
+/// A loaded embedding model. Calls to `embed` are serialized.
+pub struct Embedder {
+  model: Mutex<TextEmbedding>,
+}
+
+impl Embedder {
+  /// Load the model, downloading it if needed. Blocks.
+  pub fn new(options: &EmbedderOptions) -> Result<Self> {
+    let model = TextEmbedding::try_new(options.init_options()?)
+      .map_err(|err| Error::new(format!("Failed to initialise FastEmbed: {err}")))?;
+    Ok(Self {
+      model: Mutex::new(model),
+    })
+  }
+
+  pub fn embed(
+    &self,
+    inputs: Vec<String>,
+    batch_size: Option<usize>,
+    normalize: bool,
+  ) -> Result<Vec<Vec<f32>>> {
+    if inputs.is_empty() {
+      return Ok(Vec::new());
+    }
+    let mut vectors = self
+      .model
+      .lock()
+      .map_err(|_| Error::new("FastEmbed mutex poisoned"))?
+      .embed(inputs, batch_size)
+      .map_err(|err| Error::new(format!("FastEmbed embed failed: {err}")))?;
+    if normalize {
+      vectors
+        .iter_mut()
+        .for_each(|vector| normalize_vector(vector));
+    }
+    Ok(vectors)
+  }
+}
+
+/// The FastEmbed model for `name`, accepting `BAAI/bge-*` aliases and code suffixes.
+pub fn resolve_model(name: Option<&str>) -> Result<EmbeddingModel> {
+  let Some(name) = name else {
+    return Ok(EmbeddingModel::default());
+  };
+  let trimmed = name.trim();
+  let sanitized = sanitize_model_identifier(trimmed);
+  if let Ok(parsed) = sanitized.parse::<EmbeddingModel>() {
+    return Ok(parsed);
+  }
+  if let Some(matched) = match_supported_model(&sanitized) {
+    return Ok(matched);
+  }
+  Err(Error::new(format!(
+    "Unknown FastEmbed model '{trimmed}'. Run fastembed::TextEmbedding::list_supported_models() to inspect supported identifiers."
+  )))
+}
+
+fn sanitize_model_identifier(input: &str) -> String {
+  let trimmed = input.trim();
+  if trimmed.to_ascii_lowercase().starts_with("baai/bge-") {
+    let suffix = trimmed
+      .split_once('/')
+      .map(|(_, right)| right)
+      .unwrap_or(trimmed);
+    format!("Xenova/{suffix}")
+  } else {
+    trimmed.to_string()
+  }
+}
+
+fn match_supported_model(identifier: &str) -> Option<EmbeddingModel> {
+  let id_lower = identifier.to_ascii_lowercase();
+  TextEmbedding::list_supported_models()
+    .into_iter()
+    .find(|info| {
+      let code = info.model_code.to_ascii_lowercase();
+      code == id_lower || code.ends_with(&id_lower) || id_lower.ends_with(&code)
+    })
+    .map(|info| info.model)
+}
+
+/// Scale `vector` to unit length. Zero vectors are left alone.
+pub fn normalize_vector(vector: &mut [f32]) {
+  let norm = vector
+    .iter()
+    .fold(0f64, |sum, value| {
+      sum + f64::from(*value) * f64::from(*value)
+    })
+    .sqrt();
+  if norm > 0.0 {
+    for value in vector {
+      *value = (f64::from(*value) / norm) as f32;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn resolves_aliases_and_normalizes() {
+    assert_eq!(
+      resolve_model(Some("BAAI/bge-small-en-v1.5")).unwrap(),
+      EmbeddingModel::BGESmallENV15
+    );
+    assert!(resolve_model(Some("not-a-model")).is_err());
+
+    let mut vector = [3.0, 4.0];
+    normalize_vector(&mut vector);
+    assert_eq!(vector, [0.6, 0.8]);
+  }
+}
@@ -0,0 +1,25 @@
+//! JSON form of thread events, as the SDKs deliver them.
+
+use codex_exec::exec_events::ThreadEvent;
+use serde_json::Map as JsonMap;
+use serde_json::Value as JsonValue;
+
+/// Serialize `event`. `exited_review_mode` carries its review output as plain JSON.
+pub fn event_to_json(event: &ThreadEvent) -> serde_json::Result<JsonValue> {
+  match event {
+    ThreadEvent::ExitedReviewMode(inner) => {
+      let review_output = match &inner.review_output {
+        Some(output) => serde_json::to_value(output)?,
+        None => JsonValue::Null,
+      };
+      let mut map = JsonMap::new();
+      map.insert(
+        "type".to_string(),
+        JsonValue::String("exited_review_mode".to_string()),
+      );
+      map.insert("review_output".to_string(), review_output);
+      Ok(JsonValue::Object(map))
+    }
+    _ => serde_json::to_value(event),
+  }
+}
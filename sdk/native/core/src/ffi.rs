@@ -0,0 +1,251 @@
+//! C ABI over the core runtime, declared in `include/codex_native.h`.
+//!
+//! Requests and results are UTF-8 JSON strings. Functions returning `char *`
+//! return NULL on failure and store a message in `*error_out` (when it is not
+//! NULL). Every string returned to the caller, results and errors alike, must
+//! be released with `codex_native_string_free`.
+
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::Error;
+use crate::Result;
+use crate::reverie::search_rollouts;
+use crate::run::RunOptions;
+use crate::run::run;
+use crate::run::run_streaming;
+use crate::tokenizer::build_tokenizer;
+use crate::tokenizer::encode;
+
+/// Receives each event as JSON. The string is only valid for the duration of the call.
+pub type EventCallback = extern "C" fn(event_json: *const c_char, user_data: *mut c_void);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenizeOptions {
+  model: Option<String>,
+  encoding: Option<String>,
+  #[serde(default)]
+  with_special_tokens: bool,
+}
+
+/// The caller's `user_data`, handed back on whichever thread delivers events.
+struct CallbackTarget {
+  callback: EventCallback,
+  user_data: *mut c_void,
+}
+
+// SAFETY: codex_native_run_stream documents that the callback may run on another thread, and
+// events are delivered one at a time, so the caller's data is never used concurrently.
+unsafe impl Send for CallbackTarget {}
+
+/// # Safety
+/// `ptr` must be NULL or a NUL-terminated string that outlives the returned borrow.
+unsafe fn read_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+  if ptr.is_null() {
+    return Err(Error::new(format!("{name} must not be NULL")));
+  }
+  // SAFETY: checked for NULL above; the caller guarantees termination and lifetime.
+  unsafe { CStr::from_ptr(ptr) }
+    .to_str()
+    .map_err(|_| Error::new(format!("{name} must be UTF-8")))
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+  CString::new(text.replace('\0', ""))
+    .map(CString::into_raw)
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Run `body`, turning errors and panics into a NULL result and `*error_out`.
+///
+/// # Safety
+/// `error_out` must be NULL or valid for writing a pointer.
+unsafe fn ffi_call(
+  error_out: *mut *mut c_char,
+  body: impl FnOnce() -> Result<String>,
+) -> *mut c_char {
+  let result = std::panic::catch_unwind(AssertUnwindSafe(body))
+    .unwrap_or_else(|_| Err(Error::new("codex-native panicked")));
+  match result {
+    Ok(text) => into_c_string(text),
+    Err(err) => {
+      if !error_out.is_null() {
+        // SAFETY: the caller guarantees `error_out` is writable when non-NULL.
+        unsafe { *error_out = into_c_string(err.to_string()) };
+      }
+      std::ptr::null_mut()
+    }
+  }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+  serde_json::to_string(value).map_err(|e| Error::new(format!("Failed to serialize result: {e}")))
+}
+
+fn parse_run_options(request_json: &str) -> Result<RunOptions> {
+  serde_json::from_str(request_json).map_err(|e| Error::new(format!("Invalid run request: {e}")))
+}
+
+/// Run a turn to completion. Returns the JSON array of its events.
+///
+/// # Safety
+/// `request_json` must be a NUL-terminated string; `error_out` must be NULL or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_native_run(
+  request_json: *const c_char,
+  error_out: *mut *mut c_char,
+) -> *mut c_char {
+  // SAFETY: forwarded from this function's contract.
+  unsafe {
+    ffi_call(error_out, || {
+      let options = parse_run_options(read_str(request_json, "request_json")?)?;
+      to_json(&run(options)?)
+    })
+  }
+}
+
+/// Run a turn, calling `on_event` with each event's JSON as it happens. Blocks until the turn
+/// ends; `on_event` may be invoked from a runtime thread, one event at a time. Returns 0 on
+/// success and -1 on failure.
+///
+/// # Safety
+/// `request_json` must be a NUL-terminated string; `error_out` must be NULL or writable;
+/// `user_data` must be usable from the thread that invokes `on_event`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_native_run_stream(
+  request_json: *const c_char,
+  on_event: EventCallback,
+  user_data: *mut c_void,
+  error_out: *mut *mut c_char,
+) -> i32 {
+  let target = CallbackTarget {
+    callback: on_event,
+    user_data,
+  };
+  // SAFETY: forwarded from this function's contract.
+  let done = unsafe {
+    ffi_call(error_out, move || {
+      let options = parse_run_options(read_str(request_json, "request_json")?)?;
+      run_streaming(options, move |event| {
+        // Use the whole target, not just its fields, so the closure captures it (and stays Send).
+        let target = &target;
+        if let Ok(text) = CString::new(event.to_string()) {
+          (target.callback)(text.as_ptr(), target.user_data);
+        }
+      })?;
+      Ok(String::new())
+    })
+  };
+  if done.is_null() {
+    return -1;
+  }
+  // SAFETY: `done` came from CString::into_raw in ffi_call.
+  drop(unsafe { CString::from_raw(done) });
+  0
+}
+
+/// Encode `text` into tokens. `options_json` may be NULL or
+/// `{"model"?, "encoding"?, "withSpecialTokens"?}`. Returns a JSON array of token ids.
+///
+/// # Safety
+/// `text` must be a NUL-terminated string, `options_json` NULL or one; `error_out` must be
+/// NULL or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_native_tokenize(
+  text: *const c_char,
+  options_json: *const c_char,
+  error_out: *mut *mut c_char,
+) -> *mut c_char {
+  // SAFETY: forwarded from this function's contract.
+  unsafe {
+    ffi_call(error_out, || {
+      let text = read_str(text, "text")?;
+      let options: TokenizeOptions = if options_json.is_null() {
+        TokenizeOptions::default()
+      } else {
+        serde_json::from_str(read_str(options_json, "options_json")?)
+          .map_err(|e| Error::new(format!("Invalid tokenizer options: {e}")))?
+      };
+      let tokenizer = build_tokenizer(options.model.as_deref(), options.encoding.as_deref())?;
+      to_json(&encode(&tokenizer, text, options.with_special_tokens))
+    })
+  }
+}
+
+/// Keyword search over the conversations under `codex_home`. Returns a JSON array of
+/// `{id, path, updatedAt, relevanceScore, matchingExcerpts}`, best first.
+///
+/// # Safety
+/// `codex_home` and `query` must be NUL-terminated strings; `error_out` must be NULL or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_native_reverie_search(
+  codex_home: *const c_char,
+  query: *const c_char,
+  limit: u32,
+  error_out: *mut *mut c_char,
+) -> *mut c_char {
+  // SAFETY: forwarded from this function's contract.
+  unsafe {
+    ffi_call(error_out, || {
+      let codex_home = Path::new(read_str(codex_home, "codex_home")?);
+      let query = read_str(query, "query")?;
+      let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| Error::new(format!("Failed to create runtime: {e}")))?;
+      let matches = runtime.block_on(search_rollouts(codex_home, query, limit as usize))?;
+      to_json(&matches)
+    })
+  }
+}
+
+/// Release a string returned by any `codex_native_*` function. NULL is ignored.
+///
+/// # Safety
+/// `text` must be NULL or a string returned by this library that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_native_string_free(text: *mut c_char) {
+  if !text.is_null() {
+    // SAFETY: the caller guarantees `text` came from CString::into_raw here.
+    drop(unsafe { CString::from_raw(text) });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn take_string(ptr: *mut c_char) -> String {
+    assert!(!ptr.is_null());
+    // SAFETY: `ptr` was returned by this library and is freed right after.
+    let text = unsafe { CStr::from_ptr(ptr) }
+      .to_string_lossy()
+      .into_owned();
+    unsafe { codex_native_string_free(ptr) };
+    text
+  }
+
+  #[test]
+  fn tokenizes_and_reports_errors_through_the_c_abi() {
+    let text = CString::new("hello world").unwrap();
+    let options = CString::new(r#"{"encoding":"o200k_base"}"#).unwrap();
+    let mut error = std::ptr::null_mut();
+    // SAFETY: all pointers are valid for the call.
+    let tokens = unsafe { codex_native_tokenize(text.as_ptr(), options.as_ptr(), &mut error) };
+    let tokens: Vec<u32> = serde_json::from_str(&take_string(tokens)).unwrap();
+    assert!(error.is_null());
+    assert!(!tokens.is_empty());
+
+    let bad = CString::new(r#"{"encoding":"p50k"}"#).unwrap();
+    // SAFETY: as above.
+    let result = unsafe { codex_native_tokenize(text.as_ptr(), bad.as_ptr(), &mut error) };
+    assert!(result.is_null());
+    assert_eq!(take_string(error), "Unknown tokenizer encoding: p50k");
+  }
+}
@@ -0,0 +1,44 @@
+//! Runtime pieces of the Codex native SDK that do not depend on Node.
+//!
+//! `codex_native` (the napi crate) builds its bindings on these, and the
+//! `ffi` feature exposes them as a C ABI (see `include/codex_native.h`) so
+//! Python and other hosts can embed the same runtime without the Node
+//! bindings.
+
+#![deny(clippy::all)]
+
+pub mod embed;
+pub mod events;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod redaction;
+pub mod reverie;
+pub mod run;
+pub mod tokenizer;
+pub mod tools;
+
+use std::fmt;
+
+/// Errors from the core runtime. The message is what bindings show their callers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+  pub fn new(message: impl Into<String>) -> Self {
+    Self(message.into())
+  }
+
+  pub fn message(&self) -> &str {
+    &self.0
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
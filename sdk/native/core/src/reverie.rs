@@ -0,0 +1,161 @@
+//! Keyword search over past conversations (the lexical half of reverie search).
+
+use std::path::Path;
+
+use codex_core::OLLAMA_OSS_PROVIDER_ID;
+use codex_core::RolloutRecorder;
+use codex_core::ThreadSortKey;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::Error;
+use crate::Result;
+
+/// Characters of context kept on each side of a match in an excerpt.
+const EXCERPT_CONTEXT: usize = 50;
+/// Records scanned at each end of a rollout.
+const RECORD_LIMIT: usize = 10;
+
+/// A conversation that matched a search.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutMatch {
+  pub id: String,
+  pub path: String,
+  pub updated_at: Option<String>,
+  pub relevance_score: f64,
+  pub matching_excerpts: Vec<String>,
+}
+
+/// Case-insensitive literal matcher for `query`.
+pub fn query_regex(query: &str) -> Result<Regex> {
+  regex::RegexBuilder::new(&regex::escape(query))
+    .case_insensitive(true)
+    .unicode(true)
+    .build()
+    .map_err(|e| Error::new(format!("Invalid search query: {e}")))
+}
+
+/// One point per match of `regex` in `records`, with an excerpt around each match.
+pub fn lexical_matches<'a>(
+  regex: &Regex,
+  records: impl IntoIterator<Item = &'a String>,
+) -> (f64, Vec<String>) {
+  let mut score = 0.0;
+  let mut excerpts = Vec::new();
+  for record in records {
+    for mat in regex.find_iter(record) {
+      score += 1.0;
+      let start = floor_char_boundary(record, mat.start().saturating_sub(EXCERPT_CONTEXT));
+      let end = ceil_char_boundary(record, (mat.end() + EXCERPT_CONTEXT).min(record.len()));
+      excerpts.push(format!("...{}...", &record[start..end]));
+    }
+  }
+  (score, excerpts)
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+  while !text.is_char_boundary(index) {
+    index -= 1;
+  }
+  index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+  while !text.is_char_boundary(index) {
+    index += 1;
+  }
+  index
+}
+
+/// Search the newest conversations under `codex_home` for `query`, best matches first.
+pub async fn search_rollouts(
+  codex_home: &Path,
+  query: &str,
+  limit: usize,
+) -> Result<Vec<RolloutMatch>> {
+  let query = query.trim();
+  if query.is_empty() {
+    return Ok(Vec::new());
+  }
+  let limit = limit.max(1);
+  let regex = query_regex(query)?;
+  let page = RolloutRecorder::list_threads(
+    codex_home,
+    limit.saturating_mul(5).min(500),
+    None,
+    ThreadSortKey::UpdatedAt,
+    &[],
+    None,
+    OLLAMA_OSS_PROVIDER_ID,
+  )
+  .await
+  .map_err(|e| Error::new(format!("Failed to load conversations: {e}")))?;
+
+  let mut results = Vec::new();
+  for item in page.items {
+    let records = read_edge_records(&item.path);
+    let (relevance_score, matching_excerpts) = lexical_matches(&regex, &records);
+    if relevance_score > 0.0 {
+      results.push(RolloutMatch {
+        id: item
+          .path
+          .file_stem()
+          .and_then(|stem| stem.to_str())
+          .unwrap_or("unknown")
+          .to_string(),
+        path: item.path.to_string_lossy().into_owned(),
+        updated_at: item.updated_at,
+        relevance_score,
+        matching_excerpts,
+      });
+    }
+  }
+  results.sort_by(|a, b| b.relevance_score.total_cmp(&a.relevance_score));
+  results.truncate(limit);
+  Ok(results)
+}
+
+/// The first and last records of a rollout file, as JSON text.
+fn read_edge_records(path: &Path) -> Vec<String> {
+  let Ok(text) = std::fs::read_to_string(path) else {
+    return Vec::new();
+  };
+  let lines: Vec<&str> = text
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .collect();
+  let tail_start = lines.len().saturating_sub(RECORD_LIMIT).max(RECORD_LIMIT);
+  lines
+    .iter()
+    .take(RECORD_LIMIT)
+    .chain(lines.iter().skip(tail_start))
+    .map(|line| (*line).to_string())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn scores_each_match_with_an_excerpt() {
+    let regex = query_regex("Cache").unwrap();
+    let records = vec![
+      "the cache was stale".to_string(),
+      "no hits here".to_string(),
+      "clear CACHE, then éa cache".to_string(),
+    ];
+    let (score, excerpts) = lexical_matches(&regex, &records);
+    assert_eq!(score, 3.0);
+    assert_eq!(
+      excerpts,
+      vec![
+        "...the cache was stale...",
+        "...clear CACHE, then éa cache...",
+        "...clear CACHE, then éa cache...",
+      ]
+    );
+  }
+}
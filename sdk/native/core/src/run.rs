@@ -0,0 +1,635 @@
+//! Running a turn through codex-exec.
+//!
+//! This is the one exec front-end of the SDK: [`build_cli`] turns
+//! [`ExecOptions`] into codex-exec's `Cli`, and [`run_exec`] hands it the
+//! run's [`ExecLayers`] and runs the turn. The napi bindings add their
+//! Node-only layers (hooks, guardrails, cassettes, JS tools) on top;
+//! [`run_streaming`] serves the C ABI and Python with the layers a JSON
+//! [`RunOptions`] can describe.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use codex_arg0::Arg0PathEntryGuard;
+use codex_arg0::prepend_path_entry_for_codex_aliases;
+use codex_common::ApprovalModeCliArg;
+use codex_common::CliConfigOverrides;
+use codex_common::SandboxModeCliArg;
+use codex_core::ExternalInterceptorRegistration;
+use codex_core::ExternalToolFilter;
+use codex_core::ExternalToolLayers;
+use codex_core::ExternalToolLimit;
+use codex_core::ExternalToolRegistration;
+use codex_core::config::types::ExecSessionMode;
+use codex_exec::Cli;
+use codex_exec::Color;
+use codex_exec::Command;
+use codex_exec::EscalationHandler;
+use codex_exec::PersonalityCliArg;
+use codex_exec::ResumeArgs;
+use codex_exec::RunEscalationHandler;
+use codex_exec::exec_events::ThreadEvent;
+use codex_exec::run_with_thread_event_callback;
+use codex_protocol::config_types::Personality;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::WebSearchMode;
+use codex_protocol::dynamic_tools::DynamicToolSpec;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use serde_json::Map as JsonMap;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use crate::Error;
+use crate::Result;
+use crate::events::event_to_json;
+use crate::redaction::RedactionOptions;
+use crate::redaction::SecretRedactor;
+use crate::redaction::redact_thread_event;
+use crate::redaction::secret_redaction_interceptors;
+use crate::tools::tool_filter;
+use crate::tools::tool_limits;
+
+static APPLY_PATCH_TEMP_DIR: OnceLock<Mutex<Arg0PathEntryGuard>> = OnceLock::new();
+
+/// Puts the `apply_patch` alias on PATH for the commands runs execute.
+pub fn ensure_apply_patch_aliases() -> Result<()> {
+  if APPLY_PATCH_TEMP_DIR.get().is_some() {
+    return Ok(());
+  }
+  let temp_dir = prepend_path_entry_for_codex_aliases()
+    .map_err(|err| Error::new(format!("Failed to prepare apply_patch helper: {err}")))?;
+  // Another thread may have initialized it first; that's fine.
+  let _ = APPLY_PATCH_TEMP_DIR.set(Mutex::new(temp_dir));
+  Ok(())
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceWrite {
+  pub network_access: Option<bool>,
+  pub writable_roots: Vec<String>,
+  pub exclude_tmpdir_env_var: Option<bool>,
+  pub exclude_slash_tmp: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShellPty {
+  pub cols: Option<u32>,
+  pub rows: Option<u32>,
+  pub term: Option<String>,
+}
+
+/// Everything codex-exec needs to start a turn, however the host received the request.
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+  pub prompt: String,
+  pub input_items: Option<Vec<UserInput>>,
+  /// Resume this thread instead of starting a new one.
+  pub thread_id: Option<String>,
+  pub images: Vec<PathBuf>,
+  pub model: Option<String>,
+  pub model_provider: Option<String>,
+  pub config_profile: Option<String>,
+  pub oss: bool,
+  pub sandbox_mode: Option<SandboxModeCliArg>,
+  pub approval_mode: Option<ApprovalModeCliArg>,
+  pub workspace_write: Option<WorkspaceWrite>,
+  pub working_directory: Option<PathBuf>,
+  pub skip_git_repo_check: bool,
+  /// File holding the JSON schema the final message must match.
+  pub output_schema: Option<PathBuf>,
+  /// Compact the thread instead of running a turn.
+  pub force_compact: bool,
+  pub auth_home: Option<PathBuf>,
+  pub api_key: Option<String>,
+  pub base_url: Option<String>,
+  pub reasoning_effort: Option<ReasoningEffort>,
+  pub reasoning_summary: Option<ReasoningSummary>,
+  pub personality: Option<Personality>,
+  pub turn_personality: Option<Personality>,
+  pub ephemeral: Option<bool>,
+  pub web_search_mode: Option<WebSearchMode>,
+  pub dynamic_tools: Option<Vec<DynamicToolSpec>>,
+  /// MCP servers to register, keyed by server name, in their JSON config shape.
+  pub mcp: Option<JsonValue>,
+  /// Ignore the MCP servers registered in config.toml.
+  pub clear_mcp_servers: bool,
+  pub base_instructions: Option<String>,
+  pub developer_instructions: Option<String>,
+  pub compact_prompt: Option<String>,
+  pub include_raw_events: bool,
+  pub exec_session: Option<ExecSessionMode>,
+  pub shell_pty: Option<ShellPty>,
+  pub max_tool_output_tokens: Option<u32>,
+  pub tool_output_head_tail_split: Option<f64>,
+  /// Further `-c key=value` overrides from the host, e.g. providers it registered.
+  pub config_overrides: Vec<String>,
+}
+
+/// Convert a camelCase string to snake_case
+pub fn camel_to_snake(s: &str) -> String {
+  let mut result = String::with_capacity(s.len() + 4);
+  for (i, ch) in s.chars().enumerate() {
+    if ch.is_uppercase() {
+      if i > 0 {
+        result.push('_');
+      }
+      result.push(ch.to_ascii_lowercase());
+    } else {
+      result.push(ch);
+    }
+  }
+  result
+}
+
+/// Convert a JSON value to TOML inline format string, converting camelCase keys to snake_case
+pub fn json_to_toml_inline(value: &JsonValue) -> String {
+  match value {
+    JsonValue::Null => "null".to_string(),
+    JsonValue::Bool(b) => b.to_string(),
+    JsonValue::Number(n) => n.to_string(),
+    JsonValue::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+    JsonValue::Array(arr) => {
+      let items: Vec<String> = arr.iter().map(json_to_toml_inline).collect();
+      format!("[{}]", items.join(", "))
+    }
+    JsonValue::Object(obj) => {
+      let pairs: Vec<String> = obj
+        .iter()
+        .map(|(k, v)| format!("{} = {}", camel_to_snake(k), json_to_toml_inline(v)))
+        .collect();
+      format!("{{ {} }}", pairs.join(", "))
+    }
+  }
+}
+
+pub fn build_cli(options: &ExecOptions) -> Cli {
+  let sandbox_mode = options.sandbox_mode;
+  let wants_danger = matches!(sandbox_mode, Some(SandboxModeCliArg::DangerFullAccess));
+  let add_dir: Vec<PathBuf> = options
+    .workspace_write
+    .iter()
+    .flat_map(|opts| opts.writable_roots.iter().map(PathBuf::from))
+    .collect();
+
+  let command = options.thread_id.as_ref().map(|id| {
+    Command::Resume(ResumeArgs {
+      session_id: Some(id.clone()),
+      last: false,
+      all: false,
+      images: options.images.clone(),
+      prompt: Some(options.prompt.clone()),
+    })
+  });
+
+  let mut raw_overrides = Vec::new();
+  if options.force_compact {
+    raw_overrides.push("native.force_compact=true".to_string());
+  }
+
+  if let Some(approval_mode) = options.approval_mode {
+    let approval_str = match approval_mode {
+      ApprovalModeCliArg::Never => "never",
+      ApprovalModeCliArg::OnRequest => "on-request",
+      ApprovalModeCliArg::OnFailure => "on-failure",
+      ApprovalModeCliArg::Untrusted => "untrusted",
+    };
+    raw_overrides.push(format!("approval_policy={approval_str}"));
+  }
+
+  if let Some(effort) = options.reasoning_effort.as_ref() {
+    let effort = effort.to_string().to_lowercase();
+    raw_overrides.push(format!("model_reasoning_effort=\"{effort}\""));
+  }
+
+  if let Some(summary) = options.reasoning_summary.as_ref() {
+    let summary = summary.to_string().to_lowercase();
+    raw_overrides.push(format!("model_reasoning_summary=\"{summary}\""));
+  }
+
+  if let Some(personality) = options.personality {
+    raw_overrides.push(format!("personality=\"{personality}\""));
+  }
+
+  if let Some(ephemeral) = options.ephemeral {
+    raw_overrides.push(format!("ephemeral={ephemeral}"));
+  }
+
+  if let Some(mode) = options.web_search_mode {
+    raw_overrides.push(format!("web_search=\"{mode}\""));
+  }
+
+  if let Some(mode) = options.exec_session {
+    let mode = match mode {
+      ExecSessionMode::PerCall => "per-call",
+      ExecSessionMode::Persistent => "persistent",
+    };
+    raw_overrides.push(format!("exec_session=\"{mode}\""));
+  }
+
+  if let Some(pty) = &options.shell_pty {
+    let mut table = JsonMap::new();
+    if let Some(cols) = pty.cols {
+      table.insert("cols".to_string(), json!(cols));
+    }
+    if let Some(rows) = pty.rows {
+      table.insert("rows".to_string(), json!(rows));
+    }
+    if let Some(term) = &pty.term {
+      table.insert("term".to_string(), json!(term));
+    }
+    let table = json_to_toml_inline(&JsonValue::Object(table));
+    raw_overrides.push(format!("shell_pty={table}"));
+  }
+
+  if let Some(tokens) = options.max_tool_output_tokens {
+    raw_overrides.push(format!("tool_output_token_limit={tokens}"));
+  }
+  if let Some(split) = options.tool_output_head_tail_split {
+    raw_overrides.push(format!("tool_output_head_tail_split={split:?}"));
+  }
+
+  // Forward model provider selection for non-OSS runs via config overrides.
+  //
+  // `codex-rs/exec` currently only populates `ConfigOverrides.model_provider` in OSS mode.
+  // For remote providers, the provider is resolved from the layered config (including CLI -c
+  // overrides). Without this, a requested `modelProvider` can be ignored and codex falls back
+  // to ~/.codex/config.toml, which is surprising and can hit the wrong backend.
+  if !options.oss
+    && let Some(provider) = options
+      .model_provider
+      .as_deref()
+      .map(str::trim)
+      .filter(|provider| !provider.is_empty())
+  {
+    raw_overrides.push(format!("model_provider={provider}"));
+  }
+
+  if let Some(ws_opts) = &options.workspace_write {
+    if let Some(network_access) = ws_opts.network_access {
+      raw_overrides.push(format!(
+        "sandbox_workspace_write.network_access={network_access}"
+      ));
+    }
+    if !ws_opts.writable_roots.is_empty()
+      && let Ok(roots_json) = serde_json::to_string(&ws_opts.writable_roots)
+    {
+      raw_overrides.push(format!(
+        "sandbox_workspace_write.writable_roots={roots_json}"
+      ));
+    }
+    if let Some(exclude_tmpdir) = ws_opts.exclude_tmpdir_env_var {
+      raw_overrides.push(format!(
+        "sandbox_workspace_write.exclude_tmpdir_env_var={exclude_tmpdir}"
+      ));
+    }
+    if let Some(exclude_slash_tmp) = ws_opts.exclude_slash_tmp {
+      raw_overrides.push(format!(
+        "sandbox_workspace_write.exclude_slash_tmp={exclude_slash_tmp}"
+      ));
+    }
+  }
+
+  // Clearing the registered MCP servers comes first so the run's own servers survive it.
+  if options.clear_mcp_servers {
+    raw_overrides.push("mcp_servers={}".to_string());
+  }
+  if let Some(JsonValue::Object(servers)) = &options.mcp {
+    for (server_name, server_config) in servers {
+      let toml_value = json_to_toml_inline(server_config);
+      raw_overrides.push(format!("mcp_servers.{server_name}={toml_value}"));
+    }
+  }
+
+  raw_overrides.extend(options.config_overrides.iter().cloned());
+
+  let turn_personality = options
+    .turn_personality
+    .and_then(|personality| match personality {
+      Personality::Friendly => Some(PersonalityCliArg::Friendly),
+      Personality::Pragmatic => Some(PersonalityCliArg::Pragmatic),
+      Personality::None => None,
+    });
+
+  Cli {
+    command,
+    images: options.images.clone(),
+    model: options.model.clone(),
+    oss: options.oss,
+    oss_provider: options.model_provider.clone(),
+    sandbox_mode,
+    config_profile: options.config_profile.clone(),
+    full_auto: false,
+    dangerously_bypass_approvals_and_sandbox: wants_danger,
+    cwd: options.working_directory.clone(),
+    skip_git_repo_check: options.skip_git_repo_check,
+    add_dir,
+    ephemeral: options.ephemeral.unwrap_or(false),
+    output_schema: options.output_schema.clone(),
+    config_overrides: CliConfigOverrides { raw_overrides },
+    input_items: options.input_items.clone(),
+    input_items_path: None,
+    input_items_json: None,
+    dynamic_tools: options.dynamic_tools.clone(),
+    dynamic_tools_path: None,
+    dynamic_tools_json: None,
+    auth_home: options.auth_home.clone(),
+    api_key: options.api_key.clone(),
+    openai_base_url: options.base_url.clone(),
+    base_instructions: options.base_instructions.clone(),
+    developer_instructions: options.developer_instructions.clone(),
+    compact_prompt: options.compact_prompt.clone(),
+    include_raw_events: options.include_raw_events,
+    tool_layers: ExternalToolLayers::default(),
+    escalation_handler: RunEscalationHandler::default(),
+    turn_personality,
+    color: Color::Never,
+    json: false,
+    json_stream_output: false,
+    last_message_file: None,
+    prompt: if options.thread_id.is_some() {
+      None
+    } else {
+      Some(options.prompt.clone())
+    },
+  }
+}
+
+/// What a run's tool registry is built with. The layers travel with the run's `Cli`, so runs in
+/// the same process never see each other's.
+#[derive(Default)]
+pub struct ExecLayers {
+  pub external_tools: Vec<ExternalToolRegistration>,
+  /// Tools exposed to the model; `None` exposes all of them.
+  pub tool_filter: Option<ExternalToolFilter>,
+  pub tool_limits: HashMap<String, ExternalToolLimit>,
+  /// Interceptors in wrapping order: the first one sees each call first.
+  pub interceptors: Vec<ExternalInterceptorRegistration>,
+  /// Decides approval requests; without one they are denied.
+  pub escalation_handler: Option<EscalationHandler>,
+}
+
+impl ExecLayers {
+  /// The layers the session builds every tool router with.
+  pub fn into_tool_layers(self) -> ExternalToolLayers {
+    let mut layers = ExternalToolLayers::default();
+    layers.set_tool_limits(self.tool_limits);
+    layers.tools = self.external_tools;
+    layers.tool_filter = self.tool_filter;
+    layers.interceptors = self.interceptors;
+    layers
+  }
+}
+
+/// Run a turn with `layers`, passing each event to `on_event` as it happens.
+pub async fn run_exec<F>(
+  mut cli: Cli,
+  linux_sandbox_path: Option<PathBuf>,
+  mut layers: ExecLayers,
+  on_event: F,
+) -> Result<()>
+where
+  F: FnMut(ThreadEvent) + Send + 'static,
+{
+  ensure_apply_patch_aliases()?;
+  cli.escalation_handler = RunEscalationHandler(layers.escalation_handler.take());
+  cli.tool_layers = layers.into_tool_layers();
+  run_with_thread_event_callback(cli, linux_sandbox_path, on_event)
+    .await
+    .map_err(|e| Error::new(e.to_string()))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolLimitOptions {
+  /// Calls of the tool allowed to run at once. Must be at least 1.
+  pub max_concurrent: Option<u32>,
+  /// Minimum milliseconds between the starts of two calls of the tool.
+  pub min_interval_ms: Option<u32>,
+}
+
+/// A turn to run, in the JSON shape of the SDK's `RunRequest`. Options that need a
+/// host callback (hooks, guardrails, registered tools, escalation handlers) or a
+/// cassette are specific to the Node bindings.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOptions {
+  pub prompt: String,
+  /// Resume this thread instead of starting a new one.
+  pub thread_id: Option<String>,
+  #[serde(default)]
+  pub images: Vec<PathBuf>,
+  pub model: Option<String>,
+  pub working_directory: Option<PathBuf>,
+  /// `read-only`, `workspace-write` or `danger-full-access`.
+  pub sandbox_mode: Option<String>,
+  /// `never`, `on-request`, `on-failure` or `untrusted`.
+  pub approval_mode: Option<String>,
+  #[serde(default)]
+  pub skip_git_repo_check: bool,
+  pub api_key: Option<String>,
+  pub base_url: Option<String>,
+  /// The codex-linux-sandbox binary, required for sandboxed runs on Linux.
+  pub linux_sandbox_path: Option<PathBuf>,
+  /// When set, only these tools are exposed to the model.
+  pub allowed_tools: Option<Vec<String>>,
+  /// Tools never exposed to the model. Wins over `allowedTools`.
+  #[serde(default)]
+  pub denied_tools: Vec<String>,
+  /// Concurrency and start-interval limits per tool name.
+  #[serde(default)]
+  pub tool_limits: HashMap<String, ToolLimitOptions>,
+  /// Mask secrets in tool output and thread events.
+  pub redaction: Option<RedactionOptions>,
+}
+
+impl RunOptions {
+  fn exec_options(&self) -> Result<ExecOptions> {
+    let sandbox_mode = self
+      .sandbox_mode
+      .as_deref()
+      .map(|mode| {
+        SandboxModeCliArg::from_str(mode, false)
+          .map_err(|_| Error::new(format!("Unknown sandboxMode {mode:?}")))
+      })
+      .transpose()?;
+    let approval_mode = self
+      .approval_mode
+      .as_deref()
+      .map(|mode| {
+        ApprovalModeCliArg::from_str(mode, false)
+          .map_err(|_| Error::new(format!("Unknown approvalMode {mode:?}")))
+      })
+      .transpose()?;
+    Ok(ExecOptions {
+      prompt: self.prompt.clone(),
+      thread_id: self.thread_id.clone(),
+      images: self.images.clone(),
+      model: self.model.clone(),
+      sandbox_mode,
+      approval_mode,
+      working_directory: self.working_directory.clone(),
+      skip_git_repo_check: self.skip_git_repo_check,
+      api_key: self.api_key.clone(),
+      base_url: self.base_url.clone(),
+      ..ExecOptions::default()
+    })
+  }
+
+  fn exec_layers(&self, redactor: Option<&Arc<SecretRedactor>>) -> Result<ExecLayers> {
+    let tool_limits = tool_limits(
+      self
+        .tool_limits
+        .iter()
+        .map(|(name, limit)| (name.as_str(), limit.max_concurrent, limit.min_interval_ms)),
+    )?;
+    Ok(ExecLayers {
+      tool_filter: tool_filter(&[self.allowed_tools.as_deref()], &[&self.denied_tools]),
+      tool_limits,
+      interceptors: secret_redaction_interceptors(redactor),
+      ..ExecLayers::default()
+    })
+  }
+}
+
+/// Run a turn, passing each event to `on_event` as it happens. Blocks until the turn ends.
+pub fn run_streaming<F>(options: RunOptions, mut on_event: F) -> Result<()>
+where
+  F: FnMut(JsonValue) + Send + 'static,
+{
+  let cli = build_cli(&options.exec_options()?);
+  let redactor = SecretRedactor::compile(options.redaction.as_ref())?.map(Arc::new);
+  let layers = options.exec_layers(redactor.as_ref())?;
+  let runtime = tokio::runtime::Runtime::new()
+    .map_err(|e| Error::new(format!("Failed to create runtime: {e}")))?;
+  runtime.block_on(run_exec(
+    cli,
+    options.linux_sandbox_path,
+    layers,
+    move |event| {
+      let event = match redactor.as_deref() {
+        Some(redactor) => redact_thread_event(redactor, event),
+        None => event,
+      };
+      match event_to_json(&event) {
+        Ok(value) => on_event(value),
+        Err(err) => eprintln!("Failed to serialize thread event: {err}"),
+      }
+    },
+  ))
+}
+
+/// Run a turn and return all of its events.
+pub fn run(options: RunOptions) -> Result<Vec<JsonValue>> {
+  let events = Arc::new(Mutex::new(Vec::new()));
+  let events_for_run = Arc::clone(&events);
+  run_streaming(options, move |event| {
+    if let Ok(mut events) = events_for_run.lock() {
+      events.push(event);
+    }
+  })?;
+  let events = events
+    .lock()
+    .map_err(|e| Error::new(format!("event buffer poisoned: {e}")))?;
+  Ok(events.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn builds_exec_options_from_run_json() {
+    let options: RunOptions = serde_json::from_str(
+      r#"{"prompt":"fix it","threadId":"t1","sandboxMode":"danger-full-access","approvalMode":"never"}"#,
+    )
+    .unwrap();
+    let cli = build_cli(&options.exec_options().unwrap());
+    assert!(cli.dangerously_bypass_approvals_and_sandbox);
+    assert_eq!(cli.prompt, None);
+    assert_eq!(
+      cli.config_overrides.raw_overrides,
+      vec!["approval_policy=never".to_string()]
+    );
+    let Some(Command::Resume(resume)) = cli.command else {
+      panic!("expected a resume command");
+    };
+    assert_eq!(resume.session_id.as_deref(), Some("t1"));
+    assert_eq!(resume.prompt.as_deref(), Some("fix it"));
+
+    let bad = RunOptions {
+      approval_mode: Some("sometimes".to_string()),
+      ..RunOptions::default()
+    };
+    assert_eq!(
+      bad.exec_options().unwrap_err(),
+      Error::new("Unknown approvalMode \"sometimes\"")
+    );
+  }
+
+  #[test]
+  fn run_json_layers_filter_limit_and_redact_tools() {
+    let options: RunOptions = serde_json::from_str(
+      r#"{"prompt":"go","allowedTools":["shell","read_file"],"deniedTools":["read_file"],"toolLimits":{"web_search":{"maxConcurrent":1}},"redaction":{}}"#,
+    )
+    .unwrap();
+    let redactor = SecretRedactor::compile(options.redaction.as_ref())
+      .unwrap()
+      .map(Arc::new);
+    let layers = options.exec_layers(redactor.as_ref()).unwrap();
+    let filter = layers.tool_filter.unwrap();
+    assert!(filter.permits("exec_command"));
+    assert!(!filter.permits("read_file"));
+    assert_eq!(
+      layers.tool_limits.get("web_search"),
+      Some(&ExternalToolLimit {
+        max_concurrent: Some(1),
+        min_interval: None,
+      })
+    );
+    assert_eq!(
+      layers
+        .interceptors
+        .iter()
+        .map(|interceptor| interceptor.name.as_str())
+        .collect::<Vec<_>>(),
+      vec![codex_core::ALL_TOOLS_INTERCEPTOR]
+    );
+  }
+
+  #[test]
+  fn exec_options_become_config_overrides() {
+    let options = ExecOptions {
+      prompt: "hi".to_string(),
+      model_provider: Some(" azure ".to_string()),
+      workspace_write: Some(WorkspaceWrite {
+        writable_roots: vec!["/data".to_string()],
+        ..WorkspaceWrite::default()
+      }),
+      mcp: Some(json!({ "docs": { "url": "https://example.com/mcp" } })),
+      clear_mcp_servers: true,
+      max_tool_output_tokens: Some(4000),
+      config_overrides: vec!["model_providers.azure={ name = \"Azure\" }".to_string()],
+      ..ExecOptions::default()
+    };
+    let cli = build_cli(&options);
+    assert_eq!(cli.add_dir, vec![PathBuf::from("/data")]);
+    assert_eq!(
+      cli.config_overrides.raw_overrides,
+      vec![
+        "tool_output_token_limit=4000".to_string(),
+        "model_provider=azure".to_string(),
+        "sandbox_workspace_write.writable_roots=[\"/data\"]".to_string(),
+        "mcp_servers={}".to_string(),
+        "mcp_servers.docs={ url = \"https://example.com/mcp\" }".to_string(),
+        "model_providers.azure={ name = \"Azure\" }".to_string(),
+      ]
+    );
+  }
+}
@@ -0,0 +1,16 @@
+//! tiktoken tokenizers selected by model or encoding name, from codex-utils-tokenizer.
+
+pub use codex_utils_tokenizer::CoreBPE;
+pub use codex_utils_tokenizer::encode;
+
+use crate::Error;
+use crate::Result;
+
+/// The tokenizer for `encoding`, else for `model`, else `cl100k_base`.
+pub fn build_tokenizer(model: Option<&str>, encoding: Option<&str>) -> Result<CoreBPE> {
+  codex_utils_tokenizer::build_tokenizer(model, encoding).map_err(|err| Error::new(err.to_string()))
+}
+
+pub fn decode(tokenizer: &CoreBPE, tokens: Vec<u32>) -> Result<String> {
+  codex_utils_tokenizer::decode(tokenizer, tokens).map_err(|err| Error::new(err.to_string()))
+}
@@ -0,0 +1,94 @@
+//! Tool allow/deny lists and per-tool limits, as every binding applies them to a run.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codex_core::ExternalToolFilter;
+use codex_core::ExternalToolLimit;
+
+use crate::Error;
+use crate::Result;
+
+/// The model is offered one shell tool, named after its family; naming any of them in a tool
+/// list stands for all of them.
+pub const SHELL_TOOL_NAMES: &[&str] = &[
+  "shell",
+  "shell_command",
+  "local_shell",
+  "container.exec",
+  "exec_command",
+  "write_stdin",
+];
+
+pub fn expand_tool_names(names: &[String]) -> Vec<String> {
+  let mut expanded = Vec::new();
+  for name in names {
+    if SHELL_TOOL_NAMES.contains(&name.as_str()) {
+      expanded.extend(SHELL_TOOL_NAMES.iter().map(ToString::to_string));
+    } else {
+      expanded.push(name.clone());
+    }
+  }
+  expanded.sort_unstable();
+  expanded.dedup();
+  expanded
+}
+
+/// The registry filter for the given allow/deny lists, or `None` when none of them restricts
+/// anything. A tool must appear in every allow list and in no deny list.
+pub fn tool_filter(
+  allowed: &[Option<&[String]>],
+  denied: &[&[String]],
+) -> Option<ExternalToolFilter> {
+  let allowed = allowed
+    .iter()
+    .flatten()
+    .map(|names| expand_tool_names(names))
+    .reduce(|kept, names| {
+      kept
+        .into_iter()
+        .filter(|name| names.contains(name))
+        .collect()
+    });
+  let denied = expand_tool_names(&denied.concat());
+  if allowed.is_none() && denied.is_empty() {
+    return None;
+  }
+  Some(ExternalToolFilter { allowed, denied })
+}
+
+fn tool_limit(
+  tool_name: &str,
+  max_concurrent: Option<u32>,
+  min_interval_ms: Option<u32>,
+) -> Result<Option<ExternalToolLimit>> {
+  if max_concurrent == Some(0) {
+    return Err(Error::new(format!(
+      "maxConcurrent for tool {tool_name} must be at least 1"
+    )));
+  }
+  if max_concurrent.is_none() && min_interval_ms.is_none() {
+    return Ok(None);
+  }
+  Ok(Some(ExternalToolLimit {
+    max_concurrent: max_concurrent.map(|max| max as usize),
+    min_interval: min_interval_ms.map(|ms| Duration::from_millis(u64::from(ms))),
+  }))
+}
+
+/// Limits keyed by registry tool name. Shell tool names stand for every shell tool, as in
+/// tool allow/deny lists.
+pub fn tool_limits<'a>(
+  entries: impl IntoIterator<Item = (&'a str, Option<u32>, Option<u32>)>,
+) -> Result<HashMap<String, ExternalToolLimit>> {
+  let mut limits = HashMap::new();
+  for (name, max_concurrent, min_interval_ms) in entries {
+    let Some(limit) = tool_limit(name, max_concurrent, min_interval_ms)? else {
+      continue;
+    };
+    for tool_name in expand_tool_names(&[name.to_string()]) {
+      limits.insert(tool_name, limit);
+    }
+  }
+  Ok(limits)
+}
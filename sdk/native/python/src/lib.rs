@@ -0,0 +1,216 @@
+//! Python bindings (`import codex_native`) over codex-native-core.
+//!
+//! Requests are the SDK's JSON request objects, given as a dict or a JSON
+//! string, and events come back as dicts in the exec event shape. Blocking
+//! calls release the GIL; `run_thread_stream` is an async iterator for
+//! asyncio code such as notebooks.
+
+#![deny(clippy::all)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_native_core::embed::Embedder;
+use codex_native_core::embed::EmbedderOptions;
+use codex_native_core::run::RunOptions;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+fn core_err(err: codex_native_core::Error) -> PyErr {
+  PyRuntimeError::new_err(err.to_string())
+}
+
+fn json_loads(py: Python<'_>, text: &str) -> PyResult<PyObject> {
+  Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+fn run_options(request: &Bound<'_, PyAny>) -> PyResult<RunOptions> {
+  let text: String = match request.extract() {
+    Ok(text) => text,
+    Err(_) => request
+      .py()
+      .import("json")?
+      .call_method1("dumps", (request,))?
+      .extract()?,
+  };
+  serde_json::from_str(&text)
+    .map_err(|e| PyValueError::new_err(format!("invalid run request: {e}")))
+}
+
+/// Run a turn to completion and return its events.
+#[pyfunction]
+fn run_thread(py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+  let options = run_options(request)?;
+  let events = py
+    .allow_threads(|| codex_native_core::run::run(options))
+    .map_err(core_err)?;
+  json_loads(py, &serde_json::Value::Array(events).to_string())
+}
+
+/// Events of a running turn: `async for event in run_thread_stream(request)`.
+#[pyclass(module = "codex_native")]
+struct ThreadEventStream {
+  events: Arc<AsyncMutex<UnboundedReceiver<Result<String, String>>>>,
+}
+
+#[pymethods]
+impl ThreadEventStream {
+  fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+    slf
+  }
+
+  fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let events = Arc::clone(&self.events);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+      match events.lock().await.recv().await {
+        Some(Ok(text)) => Python::with_gil(|py| json_loads(py, &text)),
+        Some(Err(message)) => Err(PyRuntimeError::new_err(message)),
+        None => Err(PyStopAsyncIteration::new_err(())),
+      }
+    })
+  }
+}
+
+/// Start a turn and stream its events. A failed run raises from the iterator.
+#[pyfunction]
+fn run_thread_stream(request: &Bound<'_, PyAny>) -> PyResult<ThreadEventStream> {
+  let options = run_options(request)?;
+  let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+  std::thread::Builder::new()
+    .name("codex-native-run".to_string())
+    .spawn(move || {
+      let events_tx = tx.clone();
+      let result = codex_native_core::run::run_streaming(options, move |event| {
+        let _ = events_tx.send(Ok(event.to_string()));
+      });
+      if let Err(err) = result {
+        let _ = tx.send(Err(err.to_string()));
+      }
+    })
+    .map_err(|e| PyRuntimeError::new_err(format!("failed to start run: {e}")))?;
+  Ok(ThreadEventStream {
+    events: Arc::new(AsyncMutex::new(rx)),
+  })
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, model=None, encoding=None))]
+fn tokenizer_count(text: &str, model: Option<&str>, encoding: Option<&str>) -> PyResult<usize> {
+  let tokenizer =
+    codex_native_core::tokenizer::build_tokenizer(model, encoding).map_err(core_err)?;
+  Ok(codex_native_core::tokenizer::encode(&tokenizer, text, false).len())
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, model=None, encoding=None, with_special_tokens=false))]
+fn tokenizer_encode(
+  text: &str,
+  model: Option<&str>,
+  encoding: Option<&str>,
+  with_special_tokens: bool,
+) -> PyResult<Vec<u32>> {
+  let tokenizer =
+    codex_native_core::tokenizer::build_tokenizer(model, encoding).map_err(core_err)?;
+  Ok(codex_native_core::tokenizer::encode(
+    &tokenizer,
+    text,
+    with_special_tokens,
+  ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (tokens, model=None, encoding=None))]
+fn tokenizer_decode(
+  tokens: Vec<u32>,
+  model: Option<&str>,
+  encoding: Option<&str>,
+) -> PyResult<String> {
+  let tokenizer =
+    codex_native_core::tokenizer::build_tokenizer(model, encoding).map_err(core_err)?;
+  codex_native_core::tokenizer::decode(&tokenizer, tokens).map_err(core_err)
+}
+
+/// A local FastEmbed model. Loading downloads the model on first use.
+#[pyclass(name = "FastEmbed", module = "codex_native")]
+struct PyFastEmbed {
+  embedder: Arc<Embedder>,
+}
+
+#[pymethods]
+impl PyFastEmbed {
+  #[new]
+  #[pyo3(signature = (model=None, cache_dir=None, max_length=None, show_download_progress=None))]
+  fn new(
+    py: Python<'_>,
+    model: Option<String>,
+    cache_dir: Option<PathBuf>,
+    max_length: Option<usize>,
+    show_download_progress: Option<bool>,
+  ) -> PyResult<Self> {
+    let options = EmbedderOptions {
+      model,
+      cache_dir,
+      max_length,
+      show_download_progress,
+    };
+    let embedder = py
+      .allow_threads(|| Embedder::new(&options))
+      .map_err(core_err)?;
+    Ok(Self {
+      embedder: Arc::new(embedder),
+    })
+  }
+
+  /// One vector per input, unit length when `normalize` is set.
+  #[pyo3(signature = (inputs, batch_size=None, normalize=false))]
+  fn embed(
+    &self,
+    py: Python<'_>,
+    inputs: Vec<String>,
+    batch_size: Option<usize>,
+    normalize: bool,
+  ) -> PyResult<Vec<Vec<f32>>> {
+    let embedder = Arc::clone(&self.embedder);
+    py.allow_threads(|| embedder.embed(inputs, batch_size, normalize))
+      .map_err(core_err)
+  }
+}
+
+/// Keyword search over the conversations under `codex_home`, best match first.
+#[pyfunction]
+#[pyo3(signature = (codex_home, query, limit=20))]
+fn reverie_search(
+  py: Python<'_>,
+  codex_home: PathBuf,
+  query: String,
+  limit: usize,
+) -> PyResult<PyObject> {
+  let matches = py
+    .allow_threads(|| {
+      pyo3_async_runtimes::tokio::get_runtime().block_on(
+        codex_native_core::reverie::search_rollouts(&codex_home, &query, limit),
+      )
+    })
+    .map_err(core_err)?;
+  let text = serde_json::to_string(&matches)
+    .map_err(|e| PyRuntimeError::new_err(format!("failed to serialize matches: {e}")))?;
+  json_loads(py, &text)
+}
+
+#[pymodule]
+#[pyo3(name = "codex_native")]
+fn codex_native_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  m.add_function(wrap_pyfunction!(run_thread, m)?)?;
+  m.add_function(wrap_pyfunction!(run_thread_stream, m)?)?;
+  m.add_function(wrap_pyfunction!(tokenizer_count, m)?)?;
+  m.add_function(wrap_pyfunction!(tokenizer_encode, m)?)?;
+  m.add_function(wrap_pyfunction!(tokenizer_decode, m)?)?;
+  m.add_function(wrap_pyfunction!(reverie_search, m)?)?;
+  m.add_class::<ThreadEventStream>()?;
+  m.add_class::<PyFastEmbed>()?;
+  Ok(())
+}
@@ -0,0 +1,29 @@
+import asyncio
+
+import pytest
+
+import codex_native
+
+
+def test_tokenizer_round_trips():
+    tokens = codex_native.tokenizer_encode("hello world", encoding="o200k_base")
+    assert codex_native.tokenizer_count("hello world", encoding="o200k_base") == len(tokens)
+    assert codex_native.tokenizer_decode(tokens, encoding="o200k_base") == "hello world"
+    with pytest.raises(RuntimeError, match="Unknown tokenizer encoding"):
+        codex_native.tokenizer_count("hello", encoding="p50k")
+
+
+def test_reverie_search_of_an_empty_home(tmp_path):
+    assert codex_native.reverie_search(str(tmp_path), "cache") == []
+
+
+def test_invalid_run_requests_fail_before_starting():
+    with pytest.raises(ValueError, match="invalid run request"):
+        codex_native.run_thread({"model": "gpt-5"})
+
+    async def drain():
+        stream = codex_native.run_thread_stream('{"prompt": "hi", "sandboxMode": "nope"}')
+        return [event async for event in stream]
+
+    with pytest.raises(RuntimeError, match="Unknown sandboxMode"):
+        asyncio.run(drain())
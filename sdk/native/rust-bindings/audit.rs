@@ -0,0 +1,484 @@
+// ============================================================================
+// Section: Audit Log
+// ============================================================================
+//
+// `enableAuditLog(path)` appends a JSONL record for everything an agent does
+// that a reviewer may need to account for, across every run in the process:
+//   - approval: each decision returned by the registered approval callback
+//   - tool_execution: every tool call, with its arguments, outcome and duration
+//   - file_write: each file added, updated or deleted by a patch
+//   - network_request: each web search issued by the agent. Commands and MCP
+//     servers that reach the network are only logged as tool_execution records;
+//     their traffic is not inspected.
+//
+// With `hashChain` (the default) every record carries the SHA-256 of the
+// record before it and of itself, so editing, reordering or removing an
+// earlier record breaks the chain. Re-enabling an existing log continues its
+// sequence and chain; a log whose last record has no hash is refused rather
+// than chained from genesis mid-file. verify_audit_log() walks the chain and
+// reports the first record that does not check out. Arguments are masked with
+// the run's `redaction` settings when those are enabled.
+//
+// Key exports:
+//   - enable_audit_log()
+//   - disable_audit_log()
+//   - verify_audit_log()
+//
+// ============================================================================
+
+/// `prev_hash` of the first record in a chain.
+const AUDIT_GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct AuditLogOptions {
+  /// Chain records by hash so tampering is detectable (default true).
+  #[napi(js_name = "hashChain")]
+  pub hash_chain: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct AuditLogVerification {
+  pub valid: bool,
+  /// Records verified before the first invalid one (all of them when `valid`).
+  pub records: u32,
+  /// 1-based line of the first record that failed verification.
+  #[napi(js_name = "invalidLine")]
+  pub invalid_line: Option<u32>,
+  pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct AuditRecord {
+  seq: u64,
+  timestamp: String,
+  kind: String,
+  thread_id: Option<String>,
+  data: JsonValue,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  prev_hash: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  hash: Option<String>,
+}
+
+/// SHA-256 of the record serialized without its own `hash`.
+fn audit_record_hash(record: &AuditRecord) -> napi::Result<String> {
+  let unhashed = AuditRecord {
+    hash: None,
+    ..record.clone()
+  };
+  let line = serde_json::to_string(&unhashed)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize audit record: {e}")))?;
+  Ok(format!("{:x}", sha2::Sha256::digest(line.as_bytes())))
+}
+
+struct AuditLog {
+  path: PathBuf,
+  file: std::fs::File,
+  hash_chain: bool,
+  next_seq: u64,
+  last_hash: Option<String>,
+}
+
+impl AuditLog {
+  /// Opens `path` for appending, continuing the sequence and chain of any records already there.
+  /// Chaining onto records written without `hashChain` is refused: the chain would restart from
+  /// genesis mid-file and the log could never verify.
+  fn open(path: PathBuf, hash_chain: bool) -> napi::Result<Self> {
+    let last = match std::fs::read_to_string(&path) {
+      Ok(contents) => contents
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+          serde_json::from_str::<AuditRecord>(line).map_err(|e| {
+            napi::Error::from_reason(format!("{} is not an audit log: {e}", path.display()))
+          })
+        })
+        .transpose()?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+      Err(err) => {
+        return Err(napi::Error::from_reason(format!(
+          "Failed to read audit log {}: {err}",
+          path.display()
+        )));
+      }
+    };
+    if hash_chain && last.as_ref().is_some_and(|record| record.hash.is_none()) {
+      return Err(napi::Error::from_reason(format!(
+        "{} was written without hashChain; use a new path or pass hashChain: false",
+        path.display()
+      )));
+    }
+    if let Some(parent) = path.parent()
+      && !parent.as_os_str().is_empty()
+    {
+      std::fs::create_dir_all(parent).map_err(|e| {
+        napi::Error::from_reason(format!("Failed to create {}: {e}", parent.display()))
+      })?;
+    }
+    let file = std::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&path)
+      .map_err(|e| {
+        napi::Error::from_reason(format!("Failed to open audit log {}: {e}", path.display()))
+      })?;
+    Ok(Self {
+      path,
+      file,
+      hash_chain,
+      next_seq: last.as_ref().map_or(0, |record| record.seq + 1),
+      last_hash: last.and_then(|record| record.hash),
+    })
+  }
+
+  fn append(&mut self, kind: &str, thread_id: Option<&str>, data: JsonValue) -> napi::Result<()> {
+    let mut record = AuditRecord {
+      seq: self.next_seq,
+      timestamp: chrono::Utc::now().to_rfc3339(),
+      kind: kind.to_string(),
+      thread_id: thread_id.map(str::to_string),
+      data,
+      prev_hash: None,
+      hash: None,
+    };
+    if self.hash_chain {
+      record.prev_hash = Some(
+        self
+          .last_hash
+          .clone()
+          .unwrap_or_else(|| AUDIT_GENESIS_HASH.to_string()),
+      );
+      record.hash = Some(audit_record_hash(&record)?);
+    }
+    let line = serde_json::to_string(&record)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to serialize audit record: {e}")))?;
+    writeln!(self.file, "{line}")
+      .and_then(|()| self.file.sync_data())
+      .map_err(|e| {
+        napi::Error::from_reason(format!(
+          "Failed to write audit log {}: {e}",
+          self.path.display()
+        ))
+      })?;
+    self.next_seq += 1;
+    self.last_hash = record.hash;
+    Ok(())
+  }
+}
+
+fn active_audit_log() -> &'static Mutex<Option<AuditLog>> {
+  static AUDIT_LOG: OnceLock<Mutex<Option<AuditLog>>> = OnceLock::new();
+  AUDIT_LOG.get_or_init(|| Mutex::new(None))
+}
+
+fn audit_log_enabled() -> bool {
+  active_audit_log()
+    .lock()
+    .map(|guard| guard.is_some())
+    .unwrap_or(false)
+}
+
+/// Appends a record to the active audit log, if one is enabled. Write failures are reported on
+/// stderr rather than failing the agent's work.
+fn audit_record(kind: &str, thread_id: Option<&str>, data: JsonValue) {
+  let mut guard = match active_audit_log().lock() {
+    Ok(guard) => guard,
+    Err(poisoned) => poisoned.into_inner(),
+  };
+  if let Some(log) = guard.as_mut()
+    && let Err(err) = log.append(kind, thread_id, data)
+  {
+    eprintln!("codex-native: {}", err.reason);
+  }
+}
+
+#[napi]
+pub fn enable_audit_log(path: String, options: Option<AuditLogOptions>) -> napi::Result<()> {
+  let hash_chain = options
+    .and_then(|options| options.hash_chain)
+    .unwrap_or(true);
+  let log = AuditLog::open(PathBuf::from(path), hash_chain)?;
+  let mut guard = active_audit_log()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("audit log mutex poisoned: {e}")))?;
+  *guard = Some(log);
+  Ok(())
+}
+
+#[napi]
+pub fn disable_audit_log() -> napi::Result<()> {
+  let mut guard = active_audit_log()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("audit log mutex poisoned: {e}")))?;
+  *guard = None;
+  Ok(())
+}
+
+fn verify_audit_lines(contents: &str) -> AuditLogVerification {
+  let mut records = 0;
+  let mut expected_seq = 0;
+  let mut expected_prev = AUDIT_GENESIS_HASH.to_string();
+  for (index, line) in contents.lines().enumerate() {
+    if line.trim().is_empty() {
+      continue;
+    }
+    let invalid = |error: String| AuditLogVerification {
+      valid: false,
+      records,
+      invalid_line: Some(index as u32 + 1),
+      error: Some(error),
+    };
+    let record = match serde_json::from_str::<AuditRecord>(line) {
+      Ok(record) => record,
+      Err(err) => return invalid(format!("record is not valid JSON: {err}")),
+    };
+    if record.seq != expected_seq {
+      return invalid(format!(
+        "expected sequence {expected_seq}, found {}",
+        record.seq
+      ));
+    }
+    let Some(hash) = record.hash.clone() else {
+      return invalid("record has no hash; it was not written with hashChain".to_string());
+    };
+    if record.prev_hash.as_deref() != Some(expected_prev.as_str()) {
+      return invalid("record does not link to the previous record's hash".to_string());
+    }
+    match audit_record_hash(&record) {
+      Ok(actual) if actual == hash => {}
+      Ok(_) => return invalid("record hash does not match its contents".to_string()),
+      Err(err) => return invalid(err.reason),
+    }
+    records += 1;
+    expected_seq += 1;
+    expected_prev = hash;
+  }
+  AuditLogVerification {
+    valid: true,
+    records,
+    invalid_line: None,
+    error: None,
+  }
+}
+
+#[napi]
+pub fn verify_audit_log(path: String) -> napi::Result<AuditLogVerification> {
+  let contents = std::fs::read_to_string(&path)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read audit log {path}: {e}")))?;
+  Ok(verify_audit_lines(&contents))
+}
+
+/// Records the file writes and web searches carried by a completed thread item.
+fn audit_thread_event(thread_id: Option<&str>, event: &ExecThreadEvent) {
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  let item = match event {
+    ExecThreadEvent::ItemCompleted(ev) => &ev.item,
+    ExecThreadEvent::ThreadStarted(_)
+    | ExecThreadEvent::TurnStarted(_)
+    | ExecThreadEvent::TurnCompleted(_)
+    | ExecThreadEvent::TurnFailed(_)
+    | ExecThreadEvent::ItemStarted(_)
+    | ExecThreadEvent::ItemUpdated(_)
+    | ExecThreadEvent::Error(_)
+    | ExecThreadEvent::ExitedReviewMode(_)
+    | ExecThreadEvent::BackgroundEvent(_)
+    | ExecThreadEvent::ToolOutputChunk(_)
+    | ExecThreadEvent::BackgroundTaskStarted(_)
+    | ExecThreadEvent::BackgroundTaskCompleted(_)
+    | ExecThreadEvent::PlanUpdated(_)
+    | ExecThreadEvent::ThreadStatus(_)
+    | ExecThreadEvent::TestResults(_)
+    | ExecThreadEvent::VerificationFailed(_)
+    | ExecThreadEvent::FilesTouched(_)
+    | ExecThreadEvent::CommandStarted(_)
+    | ExecThreadEvent::CommandCompleted(_)
+    | ExecThreadEvent::Raw(_) => return,
+  };
+  match &item.details {
+    ThreadItemDetails::FileChange(file_change) => {
+      for change in &file_change.changes {
+        audit_record(
+          "file_write",
+          thread_id,
+          json!({
+            "item_id": item.id,
+            "path": change.path,
+            "change": change.kind,
+            "status": file_change.status,
+          }),
+        );
+      }
+    }
+    ThreadItemDetails::WebSearch(search) => audit_record(
+      "network_request",
+      thread_id,
+      json!({
+        "item_id": item.id,
+        "kind": "web_search",
+        "query": search.query,
+        "action": search.action,
+      }),
+    ),
+    ThreadItemDetails::AgentMessage(_)
+    | ThreadItemDetails::Reasoning(_)
+    | ThreadItemDetails::CommandExecution(_)
+    | ThreadItemDetails::McpToolCall(_)
+    | ThreadItemDetails::CollabToolCall(_)
+    | ThreadItemDetails::TodoList(_)
+    | ThreadItemDetails::Error(_) => {}
+  }
+}
+
+struct AuditToolInterceptor {
+  redactor: Option<Arc<SecretRedactor>>,
+}
+
+#[async_trait]
+impl ToolInterceptor for AuditToolInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    let thread_id = invocation.thread_id();
+    let call_id = invocation.call_id.clone();
+    let tool = invocation.tool_name.clone();
+    let arguments = invocation.payload.log_payload().into_owned();
+    let arguments = match self.redactor.as_deref() {
+      Some(redactor) => redactor.mask(&arguments).0,
+      None => arguments,
+    };
+    let started = std::time::Instant::now();
+    let result = next(invocation).await;
+    let (success, error) = match &result {
+      Ok(output) => (output.success_for_logging(), None),
+      Err(err) => (false, Some(err.to_string())),
+    };
+    audit_record(
+      "tool_execution",
+      Some(&thread_id),
+      json!({
+        "call_id": call_id,
+        "tool": tool,
+        "arguments": arguments,
+        "success": success,
+        "error": error,
+        "duration_ms": started.elapsed().as_millis() as u64,
+      }),
+    );
+    result
+  }
+}
+
+/// The interceptor recording every tool call of this run, if an audit log is enabled.
+fn audit_tool_interceptors(
+  redactor: Option<&Arc<SecretRedactor>>,
+) -> Vec<ExternalInterceptorRegistration> {
+  if !audit_log_enabled() {
+    return Vec::new();
+  }
+  vec![ExternalInterceptorRegistration {
+    name: codex_core::ALL_TOOLS_INTERCEPTOR.to_string(),
+    handler: Arc::new(AuditToolInterceptor {
+      redactor: redactor.cloned(),
+    }),
+  }]
+}
+
+#[cfg(test)]
+mod tests_audit {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn write_records(path: &Path, count: usize) {
+    let mut log = AuditLog::open(path.to_path_buf(), true).unwrap();
+    for index in 0..count {
+      log
+        .append(
+          "tool_execution",
+          Some("thread-1"),
+          json!({ "call_id": format!("call-{index}") }),
+        )
+        .unwrap();
+    }
+  }
+
+  #[test]
+  fn chain_verifies_and_continues_across_reopens() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit").join("log.jsonl");
+    write_records(&path, 2);
+    write_records(&path, 1);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+      verify_audit_lines(&contents),
+      AuditLogVerification {
+        valid: true,
+        records: 3,
+        invalid_line: None,
+        error: None,
+      }
+    );
+  }
+
+  #[test]
+  fn refuses_to_chain_onto_an_unchained_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("log.jsonl");
+    let mut log = AuditLog::open(path.clone(), false).unwrap();
+    log
+      .append("tool_execution", Some("thread-1"), json!({}))
+      .unwrap();
+
+    let err = AuditLog::open(path.clone(), true).err().unwrap();
+    assert_eq!(
+      err.reason,
+      format!(
+        "{} was written without hashChain; use a new path or pass hashChain: false",
+        path.display()
+      )
+    );
+    assert!(AuditLog::open(path, false).is_ok());
+  }
+
+  #[test]
+  fn edits_and_deletions_break_the_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("log.jsonl");
+    write_records(&path, 3);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let edited = contents.replace("call-1", "call-9");
+    let verification = verify_audit_lines(&edited);
+    assert_eq!(
+      (verification.valid, verification.invalid_line),
+      (false, Some(2))
+    );
+    assert_eq!(
+      verification.error.as_deref(),
+      Some("record hash does not match its contents")
+    );
+
+    let deleted = format!("{}\n{}\n", lines[0], lines[2]);
+    let verification = verify_audit_lines(&deleted);
+    assert_eq!(
+      (verification.records, verification.invalid_line),
+      (1, Some(2))
+    );
+    assert_eq!(
+      verification.error.as_deref(),
+      Some("expected sequence 1, found 2")
+    );
+  }
+}
@@ -0,0 +1,494 @@
+// Section 15: Authentication
+// ============================================================================
+//
+// Manages the credentials stored in codex home the same way `codex login`
+// does, so Node apps are not limited to `CODEX_API_KEY`. The ChatGPT flow is
+// driven natively: device-code login by default, falling back to the local
+// browser callback server when device codes are unavailable. The URL (and
+// code) the user has to visit are handed to the caller instead of printed.
+//
+// Named profiles keep further accounts under `<codex home>/auth-profiles/<name>`,
+// each laid out like codex home itself. A run that sets `authProfile` reads
+// (and refreshes) that profile's credentials instead of the default ones,
+// without touching process-wide environment variables.
+//
+// Key exports:
+//   - auth_status(): Whether credentials are stored, and of which kind
+//   - auth_login_api_key(): Store an API key
+//   - auth_login_chat_gpt(): Sign in with ChatGPT
+//   - auth_logout(): Remove stored credentials
+//   - auth_profiles(): Status of every named profile
+//
+// ============================================================================
+
+use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthMode;
+use codex_protocol::config_types::ForcedLoginMethod;
+
+const AUTH_CHATGPT_LOGIN_DISABLED: &str = "ChatGPT login is disabled. Use API key login instead.";
+const AUTH_API_KEY_LOGIN_DISABLED: &str = "API key login is disabled. Use ChatGPT login instead.";
+const AUTH_PROFILES_DIR: &str = "auth-profiles";
+
+#[napi(object)]
+pub struct AuthOptions {
+  /// Defaults to `$CODEX_HOME` or `~/.codex`.
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  /// Named credential profile to act on instead of the default credentials.
+  pub profile: Option<String>,
+}
+
+#[napi(object)]
+pub struct AuthStatus {
+  #[napi(js_name = "loggedIn")]
+  pub logged_in: bool,
+  #[napi(ts_type = "\"apiKey\" | \"chatgpt\"")]
+  pub mode: Option<String>,
+  /// The stored API key with all but its first 8 and last 5 characters masked.
+  #[napi(js_name = "apiKey")]
+  pub api_key: Option<String>,
+  pub email: Option<String>,
+  #[napi(js_name = "planType")]
+  pub plan_type: Option<String>,
+  #[napi(js_name = "accountId")]
+  pub account_id: Option<String>,
+  #[napi(js_name = "codexHome")]
+  pub codex_home: String,
+  pub profile: Option<String>,
+  /// `CODEX_API_KEY` is set, which runs use instead of stored credentials.
+  #[napi(js_name = "envApiKey")]
+  pub env_api_key: bool,
+}
+
+#[napi(object)]
+pub struct AuthChatGptLoginOptions {
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  pub profile: Option<String>,
+  /// Defaults to `"deviceCode"`, which falls back to `"browser"` when device codes are disabled.
+  #[napi(ts_type = "\"deviceCode\" | \"browser\"")]
+  pub method: Option<String>,
+  /// Open the browser login page automatically (browser method only). Default: true.
+  #[napi(js_name = "openBrowser")]
+  pub open_browser: Option<bool>,
+  /// Give up waiting for the user after this long. Default: 15 minutes.
+  #[napi(js_name = "timeoutMs")]
+  pub timeout_ms: Option<u32>,
+}
+
+/// What the user has to do to finish a ChatGPT login.
+#[napi(object)]
+pub struct AuthLoginPrompt {
+  #[napi(ts_type = "\"deviceCode\" | \"browser\"")]
+  pub method: String,
+  pub url: String,
+  /// One-time code to enter at `url` (device-code method only).
+  #[napi(js_name = "userCode")]
+  pub user_code: Option<String>,
+}
+
+const DEFAULT_AUTH_LOGIN_TIMEOUT_MS: u32 = 15 * 60 * 1_000;
+
+struct AuthSettings {
+  codex_home: PathBuf,
+  profile: Option<String>,
+  /// Where the credentials live: codex home, or the profile's directory.
+  auth_home: PathBuf,
+  store_mode: AuthCredentialsStoreMode,
+  forced_login_method: Option<ForcedLoginMethod>,
+  forced_chatgpt_workspace_id: Option<String>,
+}
+
+fn auth_profile_dir(codex_home: &Path, name: &str) -> napi::Result<PathBuf> {
+  let valid = !name.is_empty()
+    && name
+      .chars()
+      .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+    && !name.starts_with('.');
+  if !valid {
+    return Err(napi::Error::from_reason(format!(
+      "Invalid auth profile name {name:?}: use letters, digits, '-', '_' and '.'"
+    )));
+  }
+  Ok(codex_home.join(AUTH_PROFILES_DIR).join(name))
+}
+
+async fn load_auth_settings(
+  codex_home: Option<String>,
+  profile: Option<String>,
+) -> napi::Result<AuthSettings> {
+  let mut builder = codex_core::config::ConfigBuilder::default();
+  if let Some(codex_home) = codex_home {
+    builder = builder.codex_home(PathBuf::from(codex_home));
+  }
+  let config = builder
+    .build()
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to load config: {e}")))?;
+  let auth_home = match profile.as_deref() {
+    Some(profile) => auth_profile_dir(&config.codex_home, profile)?,
+    None => config.codex_home.clone(),
+  };
+  Ok(AuthSettings {
+    codex_home: config.codex_home,
+    profile,
+    auth_home,
+    store_mode: config.cli_auth_credentials_store_mode,
+    forced_login_method: config.forced_login_method,
+    forced_chatgpt_workspace_id: config.forced_chatgpt_workspace_id,
+  })
+}
+
+/// Profiles exist once logged into; the directory is created up front since keyring-backed
+/// credentials leave no file behind.
+fn ensure_auth_home(settings: &AuthSettings) -> napi::Result<()> {
+  std::fs::create_dir_all(&settings.auth_home)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create auth profile: {e}")))
+}
+
+/// The profile directory for a run's `authProfile`, which must already have been logged into.
+fn resolve_run_auth_home(
+  profile: Option<&str>,
+  has_api_key: bool,
+) -> napi::Result<Option<PathBuf>> {
+  let Some(profile) = profile else {
+    return Ok(None);
+  };
+  if has_api_key {
+    return Err(napi::Error::from_reason(
+      "apiKey and authProfile cannot be combined",
+    ));
+  }
+  let codex_home = find_codex_home()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?;
+  let dir = auth_profile_dir(&codex_home, profile)?;
+  if !dir.is_dir() {
+    return Err(napi::Error::from_reason(format!(
+      "Unknown auth profile: {profile}"
+    )));
+  }
+  Ok(Some(dir))
+}
+
+/// Credentials for a run, as `codex exec` resolves them: the run's `apiKey`, then its profile
+/// when it names one, otherwise codex home, where `CODEX_API_KEY` takes precedence.
+fn run_auth_manager(options: &InternalRunRequest, config: &Config) -> Arc<AuthManager> {
+  match options.api_key.as_deref() {
+    Some(api_key) => AuthManager::shared_with_api_key(
+      config.codex_home.clone(),
+      api_key,
+      config.cli_auth_credentials_store_mode,
+    ),
+    None => AuthManager::shared(
+      options
+        .auth_home
+        .clone()
+        .unwrap_or_else(|| config.codex_home.clone()),
+      options.auth_home.is_none(),
+      config.cli_auth_credentials_store_mode,
+    ),
+  }
+}
+
+fn redact_api_key(key: &str) -> String {
+  if key.len() <= 13 || !key.is_char_boundary(8) || !key.is_char_boundary(key.len() - 5) {
+    return "***".to_string();
+  }
+  let (prefix, suffix) = (&key[..8], &key[key.len() - 5..]);
+  format!("{prefix}***{suffix}")
+}
+
+fn read_auth_status(settings: &AuthSettings) -> AuthStatus {
+  // Stored credentials only; the env key is reported separately.
+  let manager = AuthManager::new(settings.auth_home.clone(), false, settings.store_mode);
+  let auth = manager.auth_cached();
+  AuthStatus {
+    logged_in: auth.is_some(),
+    mode: manager.auth_mode().map(|mode| {
+      match mode {
+        AuthMode::ApiKey => "apiKey",
+        AuthMode::Chatgpt => "chatgpt",
+      }
+      .to_string()
+    }),
+    api_key: auth
+      .as_ref()
+      .and_then(|auth| auth.api_key())
+      .map(redact_api_key),
+    email: auth
+      .as_ref()
+      .and_then(codex_core::CodexAuth::get_account_email),
+    plan_type: auth
+      .as_ref()
+      .and_then(codex_core::CodexAuth::account_plan_type)
+      .and_then(|plan| serde_json::to_value(plan).ok())
+      .and_then(|plan| plan.as_str().map(str::to_string)),
+    account_id: auth
+      .as_ref()
+      .and_then(codex_core::CodexAuth::get_account_id),
+    codex_home: settings.codex_home.to_string_lossy().into_owned(),
+    profile: settings.profile.clone(),
+    env_api_key: codex_core::auth::read_codex_api_key_from_env().is_some(),
+  }
+}
+
+fn list_auth_profiles(settings: &AuthSettings) -> Vec<AuthStatus> {
+  let Ok(entries) = std::fs::read_dir(settings.codex_home.join(AUTH_PROFILES_DIR)) else {
+    return Vec::new();
+  };
+  let mut names: Vec<String> = entries
+    .filter_map(Result::ok)
+    .filter(|entry| entry.path().is_dir())
+    .filter_map(|entry| entry.file_name().into_string().ok())
+    .collect();
+  names.sort();
+  names
+    .into_iter()
+    .filter_map(|name| {
+      let auth_home = auth_profile_dir(&settings.codex_home, &name).ok()?;
+      Some(read_auth_status(&AuthSettings {
+        codex_home: settings.codex_home.clone(),
+        profile: Some(name),
+        auth_home,
+        store_mode: settings.store_mode,
+        forced_login_method: None,
+        forced_chatgpt_workspace_id: None,
+      }))
+    })
+    .collect()
+}
+
+#[napi(js_name = "authStatus")]
+pub async fn auth_status(options: Option<AuthOptions>) -> napi::Result<AuthStatus> {
+  let (codex_home, profile) = options.map_or((None, None), |o| (o.codex_home, o.profile));
+  let settings = load_auth_settings(codex_home, profile).await?;
+  Ok(read_auth_status(&settings))
+}
+
+/// Every named profile, logged in or not, sorted by name.
+#[napi(js_name = "authProfiles")]
+pub async fn auth_profiles(options: Option<AuthOptions>) -> napi::Result<Vec<AuthStatus>> {
+  let settings = load_auth_settings(options.and_then(|options| options.codex_home), None).await?;
+  Ok(list_auth_profiles(&settings))
+}
+
+#[napi(js_name = "authLoginApiKey")]
+pub async fn auth_login_api_key(
+  api_key: String,
+  options: Option<AuthOptions>,
+) -> napi::Result<AuthStatus> {
+  let (codex_home, profile) = options.map_or((None, None), |o| (o.codex_home, o.profile));
+  let settings = load_auth_settings(codex_home, profile).await?;
+  if matches!(
+    settings.forced_login_method,
+    Some(ForcedLoginMethod::Chatgpt)
+  ) {
+    return Err(napi::Error::from_reason(AUTH_API_KEY_LOGIN_DISABLED));
+  }
+  let api_key = api_key.trim();
+  if api_key.is_empty() {
+    return Err(napi::Error::from_reason("API key must not be empty"));
+  }
+  ensure_auth_home(&settings)?;
+  codex_core::auth::login_with_api_key(&settings.auth_home, api_key, settings.store_mode)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to store API key: {e}")))?;
+  Ok(read_auth_status(&settings))
+}
+
+/// Sign in with ChatGPT. `on_prompt` receives the URL (and device code) to show the user;
+/// the promise resolves once the login completes and the tokens are stored.
+#[napi(js_name = "authLoginChatGpt")]
+pub async fn auth_login_chat_gpt(
+  options: Option<AuthChatGptLoginOptions>,
+  #[napi(ts_arg_type = "(err: unknown, prompt?: AuthLoginPrompt) => void")] on_prompt: Option<
+    ThreadsafeFunction<AuthLoginPrompt>,
+  >,
+) -> napi::Result<AuthStatus> {
+  let options = options.unwrap_or(AuthChatGptLoginOptions {
+    codex_home: None,
+    profile: None,
+    method: None,
+    open_browser: None,
+    timeout_ms: None,
+  });
+  let settings = load_auth_settings(options.codex_home, options.profile).await?;
+  if matches!(settings.forced_login_method, Some(ForcedLoginMethod::Api)) {
+    return Err(napi::Error::from_reason(AUTH_CHATGPT_LOGIN_DISABLED));
+  }
+  let browser = match options.method.as_deref() {
+    None | Some("deviceCode") => false,
+    Some("browser") => true,
+    Some(other) => {
+      return Err(napi::Error::from_reason(format!(
+        "Unknown login method: {other}"
+      )));
+    }
+  };
+  let timeout = std::time::Duration::from_millis(u64::from(
+    options.timeout_ms.unwrap_or(DEFAULT_AUTH_LOGIN_TIMEOUT_MS),
+  ));
+  ensure_auth_home(&settings)?;
+  let mut server_options = codex_login::ServerOptions::new(
+    settings.auth_home.clone(),
+    codex_core::auth::CLIENT_ID.to_string(),
+    settings.forced_chatgpt_workspace_id.clone(),
+    settings.store_mode,
+  );
+  server_options.open_browser = browser && options.open_browser.unwrap_or(true);
+  let prompt = |prompt: AuthLoginPrompt| {
+    if let Some(on_prompt) = on_prompt.as_ref() {
+      on_prompt.call(Ok(prompt), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  };
+
+  let device_code = if browser {
+    None
+  } else {
+    match codex_login::request_device_code(&server_options).await {
+      Ok(device_code) => Some(device_code),
+      // Device codes are feature-gated server side; `codex login` falls back the same way.
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+      Err(err) => {
+        return Err(napi::Error::from_reason(format!(
+          "Failed to request device code: {err}"
+        )));
+      }
+    }
+  };
+
+  let result = match device_code {
+    Some(device_code) => {
+      prompt(AuthLoginPrompt {
+        method: "deviceCode".to_string(),
+        url: device_code.verification_url.clone(),
+        user_code: Some(device_code.user_code.clone()),
+      });
+      tokio::time::timeout(
+        timeout,
+        codex_login::complete_device_code_login(server_options, device_code),
+      )
+      .await
+    }
+    None => {
+      let server = codex_login::run_login_server(server_options)
+        .map_err(|e| napi::Error::from_reason(format!("Failed to start login server: {e}")))?;
+      prompt(AuthLoginPrompt {
+        method: "browser".to_string(),
+        url: server.auth_url.clone(),
+        user_code: None,
+      });
+      let cancel = server.cancel_handle();
+      let result = tokio::time::timeout(timeout, server.block_until_done()).await;
+      if result.is_err() {
+        cancel.shutdown();
+      }
+      result
+    }
+  };
+  match result {
+    Ok(Ok(())) => Ok(read_auth_status(&settings)),
+    Ok(Err(err)) => Err(napi::Error::from_reason(format!(
+      "ChatGPT login failed: {err}"
+    ))),
+    Err(_) => Err(napi::Error::from_reason(format!(
+      "ChatGPT login timed out after {}ms",
+      timeout.as_millis()
+    ))),
+  }
+}
+
+/// Remove stored credentials, and the profile itself when one is named. Resolves to false when
+/// there were no credentials.
+#[napi(js_name = "authLogout")]
+pub async fn auth_logout(options: Option<AuthOptions>) -> napi::Result<bool> {
+  let (codex_home, profile) = options.map_or((None, None), |o| (o.codex_home, o.profile));
+  let settings = load_auth_settings(codex_home, profile).await?;
+  let removed = AuthManager::new(settings.auth_home.clone(), false, settings.store_mode)
+    .logout()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to log out: {e}")))?;
+  if settings.profile.is_some() {
+    // Only removes the directory once nothing else was left in it.
+    let _ = std::fs::remove_dir(&settings.auth_home);
+  }
+  Ok(removed)
+}
+
+#[cfg(test)]
+mod tests_auth {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn settings(codex_home: &Path, profile: Option<&str>) -> AuthSettings {
+    AuthSettings {
+      codex_home: codex_home.to_path_buf(),
+      profile: profile.map(str::to_string),
+      auth_home: match profile {
+        Some(profile) => auth_profile_dir(codex_home, profile).unwrap(),
+        None => codex_home.to_path_buf(),
+      },
+      store_mode: AuthCredentialsStoreMode::File,
+      forced_login_method: None,
+      forced_chatgpt_workspace_id: None,
+    }
+  }
+
+  #[test]
+  fn api_key_login_round_trips_through_codex_home() {
+    let home = tempfile::tempdir().unwrap();
+    let default = settings(home.path(), None);
+    assert!(!read_auth_status(&default).logged_in);
+
+    codex_core::auth::login_with_api_key(
+      home.path(),
+      "sk-proj-1234567890ABCDE",
+      default.store_mode,
+    )
+    .unwrap();
+    let status = read_auth_status(&default);
+    assert!(status.logged_in);
+    assert_eq!(status.mode.as_deref(), Some("apiKey"));
+    assert_eq!(status.api_key.as_deref(), Some("sk-proj-***ABCDE"));
+
+    let manager = AuthManager::new(home.path().to_path_buf(), false, default.store_mode);
+    assert!(manager.logout().unwrap());
+    assert!(!read_auth_status(&default).logged_in);
+    assert_eq!(redact_api_key("sk-short"), "***");
+  }
+
+  #[test]
+  fn profiles_keep_separate_credentials() {
+    let home = tempfile::tempdir().unwrap();
+    for (profile, key) in [
+      ("work", "sk-work-1234567890AAAAA"),
+      ("personal", "sk-home-1234567890BBBBB"),
+    ] {
+      let profile = settings(home.path(), Some(profile));
+      ensure_auth_home(&profile).unwrap();
+      codex_core::auth::login_with_api_key(&profile.auth_home, key, profile.store_mode).unwrap();
+    }
+
+    let profiles: Vec<(Option<String>, Option<String>)> =
+      list_auth_profiles(&settings(home.path(), None))
+        .into_iter()
+        .map(|status| (status.profile, status.api_key))
+        .collect();
+    assert_eq!(
+      profiles,
+      vec![
+        (
+          Some("personal".to_string()),
+          Some("sk-home-***BBBBB".to_string())
+        ),
+        (
+          Some("work".to_string()),
+          Some("sk-work-***AAAAA".to_string())
+        ),
+      ]
+    );
+    assert!(!read_auth_status(&settings(home.path(), None)).logged_in);
+    assert_eq!(
+      auth_profile_dir(home.path(), "../work").unwrap_err().reason,
+      "Invalid auth profile name \"../work\": use letters, digits, '-', '_' and '.'"
+    );
+  }
+}
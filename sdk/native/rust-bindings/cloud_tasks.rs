@@ -1,19 +1,327 @@
+#[napi(string_enum = "lowercase")]
+pub enum CloudTaskStatus {
+  Pending,
+  Ready,
+  Applied,
+  Error,
+}
+
+impl From<cloud::TaskStatus> for CloudTaskStatus {
+  fn from(value: cloud::TaskStatus) -> Self {
+    match value {
+      cloud::TaskStatus::Pending => Self::Pending,
+      cloud::TaskStatus::Ready => Self::Ready,
+      cloud::TaskStatus::Applied => Self::Applied,
+      cloud::TaskStatus::Error => Self::Error,
+    }
+  }
+}
+
+#[napi(string_enum = "lowercase")]
+pub enum CloudAttemptStatus {
+  Pending,
+  Running,
+  Completed,
+  Failed,
+  Cancelled,
+  Unknown,
+}
+
+impl From<cloud::AttemptStatus> for CloudAttemptStatus {
+  fn from(value: cloud::AttemptStatus) -> Self {
+    match value {
+      cloud::AttemptStatus::Pending => Self::Pending,
+      cloud::AttemptStatus::InProgress => Self::Running,
+      cloud::AttemptStatus::Completed => Self::Completed,
+      cloud::AttemptStatus::Failed => Self::Failed,
+      cloud::AttemptStatus::Cancelled => Self::Cancelled,
+      cloud::AttemptStatus::Unknown => Self::Unknown,
+    }
+  }
+}
+
+#[napi(string_enum = "lowercase")]
+pub enum CloudApplyStatus {
+  Success,
+  Partial,
+  Error,
+}
+
+impl From<cloud::ApplyStatus> for CloudApplyStatus {
+  fn from(value: cloud::ApplyStatus) -> Self {
+    match value {
+      cloud::ApplyStatus::Success => Self::Success,
+      cloud::ApplyStatus::Partial => Self::Partial,
+      cloud::ApplyStatus::Error => Self::Error,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct CloudTaskDiffSummary {
+  #[napi(js_name = "filesChanged")]
+  pub files_changed: u32,
+  #[napi(js_name = "linesAdded")]
+  pub lines_added: u32,
+  #[napi(js_name = "linesRemoved")]
+  pub lines_removed: u32,
+}
+
+impl From<cloud::DiffSummary> for CloudTaskDiffSummary {
+  fn from(value: cloud::DiffSummary) -> Self {
+    Self {
+      files_changed: value.files_changed.min(u32::MAX as usize) as u32,
+      lines_added: value.lines_added.min(u32::MAX as usize) as u32,
+      lines_removed: value.lines_removed.min(u32::MAX as usize) as u32,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct CloudTaskSummary {
+  pub id: String,
+  pub title: String,
+  pub status: CloudTaskStatus,
+  /// RFC 3339 timestamp of the last backend update.
+  #[napi(js_name = "updatedAt")]
+  pub updated_at: String,
+  #[napi(js_name = "environmentId")]
+  pub environment_id: Option<String>,
+  #[napi(js_name = "environmentLabel")]
+  pub environment_label: Option<String>,
+  pub summary: CloudTaskDiffSummary,
+  #[napi(js_name = "isReview")]
+  pub is_review: bool,
+  #[napi(js_name = "attemptTotal")]
+  pub attempt_total: Option<u32>,
+}
+
+impl From<cloud::TaskSummary> for CloudTaskSummary {
+  fn from(value: cloud::TaskSummary) -> Self {
+    Self {
+      id: value.id.0,
+      title: value.title,
+      status: value.status.into(),
+      updated_at: value.updated_at.to_rfc3339(),
+      environment_id: value.environment_id,
+      environment_label: value.environment_label,
+      summary: value.summary.into(),
+      is_review: value.is_review,
+      attempt_total: value
+        .attempt_total
+        .map(|total| total.min(u32::MAX as usize) as u32),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct CloudTaskListPage {
+  pub tasks: Vec<CloudTaskSummary>,
+  #[napi(js_name = "nextCursor")]
+  pub next_cursor: Option<String>,
+}
+
+#[napi(object)]
+pub struct CloudTaskCreated {
+  pub id: String,
+}
+
+#[napi(object)]
+pub struct CloudTaskDetails {
+  pub task: CloudTaskSummary,
+  /// The prompt the task was created with.
+  pub prompt: Option<String>,
+  /// Assistant messages of the latest attempt.
+  pub messages: Vec<String>,
+  #[napi(js_name = "turnId")]
+  pub turn_id: Option<String>,
+  #[napi(js_name = "attemptStatus")]
+  pub attempt_status: CloudAttemptStatus,
+  /// Unified diff of the latest attempt, once it has produced one.
+  pub diff: Option<String>,
+}
+
+#[napi(object)]
+pub struct CloudTaskAwaitOptions {
+  /// Default: 5000.
+  #[napi(js_name = "pollIntervalMs")]
+  pub poll_interval_ms: Option<u32>,
+  /// Default: 30 minutes.
+  #[napi(js_name = "timeoutMs")]
+  pub timeout_ms: Option<u32>,
+}
+
+const DEFAULT_CLOUD_TASK_POLL_INTERVAL_MS: u32 = 5_000;
+const DEFAULT_CLOUD_TASK_AWAIT_TIMEOUT_MS: u32 = 30 * 60 * 1_000;
+
+#[napi(object)]
+pub struct CloudApplyOutcome {
+  pub applied: bool,
+  pub status: CloudApplyStatus,
+  pub message: String,
+  #[napi(js_name = "skippedPaths")]
+  pub skipped_paths: Vec<String>,
+  #[napi(js_name = "conflictPaths")]
+  pub conflict_paths: Vec<String>,
+  /// True when files touched by the task had uncommitted local edits.
+  pub diverged: bool,
+  /// Per-file three-way merge conflicts; empty unless `diverged`.
+  pub conflicts: Vec<GitMergeConflict>,
+  /// Branch created for the result by `cloudTasksApplyResult`.
+  pub branch: Option<String>,
+}
+
+impl From<cloud::ApplyOutcome> for CloudApplyOutcome {
+  fn from(value: cloud::ApplyOutcome) -> Self {
+    Self {
+      applied: value.applied,
+      status: value.status.into(),
+      message: value.message,
+      skipped_paths: value.skipped_paths,
+      conflict_paths: value.conflict_paths,
+      diverged: false,
+      conflicts: Vec::new(),
+      branch: None,
+    }
+  }
+}
+
+/// `status` filters the fetched page, so a filtered page can hold fewer than `limit` tasks.
 #[napi(js_name = "cloudTasksList")]
 pub async fn cloud_tasks_list(
   env_filter: Option<String>,
+  limit: Option<i64>,
+  cursor: Option<String>,
   base_url: Option<String>,
   api_key: Option<String>,
-) -> napi::Result<String> {
+  status: Option<CloudTaskStatus>,
+) -> napi::Result<CloudTaskListPage> {
+  let client =
+    build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  let page = cloud::CloudBackend::list_tasks(
+    &client,
+    env_filter.as_deref(),
+    limit.map(|value| value.max(1)),
+    cursor.as_deref(),
+  )
+  .await
+  .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  Ok(CloudTaskListPage {
+    tasks: page
+      .tasks
+      .into_iter()
+      .map(CloudTaskSummary::from)
+      .filter(|task| {
+        status.as_ref().is_none_or(|status| {
+          std::mem::discriminant(status) == std::mem::discriminant(&task.status)
+        })
+      })
+      .collect(),
+    next_cursor: page.cursor,
+  })
+}
+
+#[napi(js_name = "cloudTasksGet")]
+pub async fn cloud_tasks_get(
+  task_id: String,
+  base_url: Option<String>,
+  api_key: Option<String>,
+) -> napi::Result<CloudTaskDetails> {
   let client =
     build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  let tasks = cloud::CloudBackend::list_tasks(&client, env_filter.as_deref(), None, None)
+  let id = cloud::TaskId(task_id);
+  let summary = cloud::CloudBackend::get_task_summary(&client, id.clone())
     .await
     .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  let payload = serde_json_json!({
-    "tasks": tasks.tasks,
-    "cursor": tasks.cursor,
-  });
-  serde_json::to_string(&payload).map_err(|e| napi::Error::from_reason(e.to_string()))
+  let text = cloud::CloudBackend::get_task_text(&client, id.clone())
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  let diff = cloud::CloudBackend::get_task_diff(&client, id)
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  Ok(CloudTaskDetails {
+    task: summary.into(),
+    prompt: text.prompt,
+    messages: text.messages,
+    turn_id: text.turn_id,
+    attempt_status: text.attempt_status.into(),
+    diff,
+  })
+}
+
+/// Cancel the task's in-flight attempt and return its summary afterwards.
+#[napi(js_name = "cloudTasksCancel")]
+pub async fn cloud_tasks_cancel(
+  task_id: String,
+  base_url: Option<String>,
+  api_key: Option<String>,
+) -> napi::Result<CloudTaskSummary> {
+  let client =
+    build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  let id = cloud::TaskId(task_id);
+  cloud::CloudBackend::cancel_task(&client, id.clone())
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  let summary = cloud::CloudBackend::get_task_summary(&client, id)
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  Ok(summary.into())
+}
+
+/// Poll until the task is no longer pending and return its final summary.
+#[napi(js_name = "cloudTasksAwait")]
+pub async fn cloud_tasks_await(
+  task_id: String,
+  options: Option<CloudTaskAwaitOptions>,
+  base_url: Option<String>,
+  api_key: Option<String>,
+) -> napi::Result<CloudTaskSummary> {
+  let client =
+    build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+  let (poll_interval_ms, timeout_ms) = options
+    .map(|options| (options.poll_interval_ms, options.timeout_ms))
+    .unwrap_or_default();
+  let poll_interval = std::time::Duration::from_millis(u64::from(
+    poll_interval_ms.unwrap_or(DEFAULT_CLOUD_TASK_POLL_INTERVAL_MS).max(1),
+  ));
+  let timeout = std::time::Duration::from_millis(u64::from(
+    timeout_ms.unwrap_or(DEFAULT_CLOUD_TASK_AWAIT_TIMEOUT_MS),
+  ));
+  let summary = await_cloud_task(&task_id, poll_interval, timeout, || {
+    cloud::CloudBackend::get_task_summary(&client, cloud::TaskId(task_id.clone()))
+  })
+  .await?;
+  Ok(summary.into())
+}
+
+/// Call `fetch` every `poll_interval` until the task leaves `pending`, failing once `timeout` passes.
+async fn await_cloud_task<F, Fut>(
+  task_id: &str,
+  poll_interval: std::time::Duration,
+  timeout: std::time::Duration,
+  mut fetch: F,
+) -> napi::Result<cloud::TaskSummary>
+where
+  F: FnMut() -> Fut,
+  Fut: std::future::Future<Output = cloud::Result<cloud::TaskSummary>>,
+{
+  let deadline = tokio::time::Instant::now() + timeout;
+  loop {
+    let summary = fetch()
+      .await
+      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    if summary.status != cloud::TaskStatus::Pending {
+      return Ok(summary);
+    }
+    let now = tokio::time::Instant::now();
+    if now >= deadline {
+      return Err(napi::Error::from_reason(format!(
+        "Timed out after {}ms waiting for cloud task {task_id}",
+        timeout.as_millis()
+      )));
+    }
+    tokio::time::sleep(poll_interval.min(deadline - now)).await;
+  }
 }
 
 #[napi(js_name = "cloudTasksGetDiff")]
@@ -37,14 +345,9 @@ pub async fn cloud_tasks_apply_preflight(
   diff_override: Option<String>,
   base_url: Option<String>,
   api_key: Option<String>,
-) -> napi::Result<String> {
-  let client =
-    build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  let outcome =
-    cloud::CloudBackend::apply_task_preflight(&client, cloud::TaskId(task_id), diff_override)
-      .await
-      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  serde_json::to_string(&outcome).map_err(|e| napi::Error::from_reason(e.to_string()))
+) -> napi::Result<CloudApplyOutcome> {
+  let diff = fetch_cloud_task_diff(&task_id, diff_override, base_url, api_key).await?;
+  cloud_tasks_apply_internal(&task_id, &diff, &process_cwd()?, true)
 }
 
 #[napi(js_name = "cloudTasksApply")]
@@ -53,13 +356,236 @@ pub async fn cloud_tasks_apply(
   diff_override: Option<String>,
   base_url: Option<String>,
   api_key: Option<String>,
+) -> napi::Result<CloudApplyOutcome> {
+  let diff = fetch_cloud_task_diff(&task_id, diff_override, base_url, api_key).await?;
+  cloud_tasks_apply_internal(&task_id, &diff, &process_cwd()?, false)
+}
+
+#[napi(object)]
+pub struct CloudTaskApplyResultOptions {
+  /// Workspace to apply into; defaults to the process working directory.
+  pub cwd: Option<String>,
+  /// Switch to a new branch of this name before applying.
+  #[napi(js_name = "createBranch")]
+  pub create_branch: Option<String>,
+}
+
+/// Download a task's diff and apply it to a local checkout, optionally on a fresh branch.
+///
+/// The diff is preflighted first, so a patch that won't apply leaves the workspace, including
+/// the current branch, untouched.
+#[napi(js_name = "cloudTasksApplyResult")]
+pub async fn cloud_tasks_apply_result(
+  task_id: String,
+  options: Option<CloudTaskApplyResultOptions>,
+  base_url: Option<String>,
+  api_key: Option<String>,
+) -> napi::Result<CloudApplyOutcome> {
+  let CloudTaskApplyResultOptions { cwd, create_branch } =
+    options.unwrap_or(CloudTaskApplyResultOptions {
+      cwd: None,
+      create_branch: None,
+    });
+  let cwd = match cwd {
+    Some(cwd) => PathBuf::from(cwd),
+    None => process_cwd()?,
+  };
+  let diff = fetch_cloud_task_diff(&task_id, None, base_url, api_key).await?;
+  let Some(branch) = create_branch else {
+    return cloud_tasks_apply_internal(&task_id, &diff, &cwd, false);
+  };
+
+  let preflight = cloud_tasks_apply_internal(&task_id, &diff, &cwd, true)?;
+  if !matches!(preflight.status, CloudApplyStatus::Success) {
+    return Ok(preflight);
+  }
+  create_git_branch(&cwd, &branch)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create branch {branch}: {e}")))?;
+  let outcome = cloud_tasks_apply_internal(&task_id, &diff, &cwd, false)?;
+  Ok(CloudApplyOutcome {
+    branch: Some(branch),
+    ..outcome
+  })
+}
+
+fn process_cwd() -> napi::Result<PathBuf> {
+  std::env::current_dir()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve working directory: {e}")))
+}
+
+async fn fetch_cloud_task_diff(
+  task_id: &str,
+  diff_override: Option<String>,
+  base_url: Option<String>,
+  api_key: Option<String>,
 ) -> napi::Result<String> {
+  if let Some(diff) = diff_override {
+    return Ok(diff);
+  }
   let client =
     build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  let outcome = cloud::CloudBackend::apply_task(&client, cloud::TaskId(task_id), diff_override)
+  cloud::CloudBackend::get_task_diff(&client, cloud::TaskId(task_id.to_string()))
     .await
-    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  serde_json::to_string(&outcome).map_err(|e| napi::Error::from_reason(e.to_string()))
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?
+    .ok_or_else(|| napi::Error::from_reason(format!("No diff available for task {task_id}")))
+}
+
+/// Apply (or preflight) a task diff in `cwd`, three-way merging files that carry local edits.
+///
+/// When none of the touched files are dirty this is a plain `git apply --3way`. Otherwise the
+/// dirty files are merged against the task's base blob and nothing is written unless every file
+/// merges cleanly and the rest of the patch applies. The merged files are restored to their local
+/// contents if applying the rest of the patch fails anyway.
+fn cloud_tasks_apply_internal(
+  task_id: &str,
+  diff: &str,
+  cwd: &Path,
+  preflight: bool,
+) -> napi::Result<CloudApplyOutcome> {
+  let touched = codex_git::extract_paths_from_patch(diff);
+  let dirty = locally_modified_paths(cwd, &touched)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to inspect workspace: {e}")))?;
+
+  if dirty.is_empty() {
+    return git_apply_task_diff(task_id, diff.to_string(), cwd, preflight);
+  }
+
+  let merge = three_way_merge_patch(cwd, diff, &dirty)
+    .map_err(|e| napi::Error::from_reason(format!("Three-way merge failed: {e}")))?;
+  if !merge.conflicts.is_empty() {
+    let conflict_paths = merge
+      .conflicts
+      .iter()
+      .map(|conflict| conflict.path.clone())
+      .collect::<Vec<_>>();
+    return Ok(CloudApplyOutcome {
+      applied: false,
+      status: CloudApplyStatus::Partial,
+      message: format!(
+        "Local changes conflict with task {task_id} in {} file(s); workspace left untouched",
+        conflict_paths.len()
+      ),
+      skipped_paths: Vec::new(),
+      conflict_paths,
+      diverged: true,
+      conflicts: merge.conflicts,
+      branch: None,
+    });
+  }
+
+  let mut outcome = if merge.remaining_patch.is_empty() {
+    CloudApplyOutcome {
+      applied: false,
+      status: CloudApplyStatus::Success,
+      message: String::new(),
+      skipped_paths: Vec::new(),
+      conflict_paths: Vec::new(),
+      diverged: true,
+      conflicts: Vec::new(),
+      branch: None,
+    }
+  } else {
+    CloudApplyOutcome {
+      diverged: true,
+      ..git_apply_task_diff(task_id, merge.remaining_patch.clone(), cwd, true)?
+    }
+  };
+  if !matches!(outcome.status, CloudApplyStatus::Success) {
+    return Ok(outcome);
+  }
+
+  if !preflight {
+    let backup = write_merged_files(cwd, &merge.merged_files)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to write merged files: {e}")))?;
+    if !merge.remaining_patch.is_empty() {
+      let applied = git_apply_task_diff(task_id, merge.remaining_patch, cwd, false);
+      let applied_cleanly = applied
+        .as_ref()
+        .is_ok_and(|applied| matches!(applied.status, CloudApplyStatus::Success));
+      if !applied_cleanly {
+        backup
+          .restore()
+          .map_err(|e| napi::Error::from_reason(format!("Failed to restore local changes: {e}")))?;
+      }
+      outcome = CloudApplyOutcome {
+        diverged: true,
+        ..applied?
+      };
+      if !applied_cleanly {
+        return Ok(outcome);
+      }
+    }
+  }
+  outcome.applied = !preflight;
+  outcome.message = if preflight {
+    format!(
+      "Preflight passed for task {task_id} (merges cleanly with local changes in {} file(s))",
+      merge.merged_files.len()
+    )
+  } else {
+    format!(
+      "Applied task {task_id} locally, merging local changes in {} file(s)",
+      merge.merged_files.len()
+    )
+  };
+  Ok(outcome)
+}
+
+/// `git apply --3way` in `cwd`, reported the way the cloud client reports its own applies.
+fn git_apply_task_diff(
+  task_id: &str,
+  diff: String,
+  cwd: &Path,
+  preflight: bool,
+) -> napi::Result<CloudApplyOutcome> {
+  let request = codex_git::ApplyGitRequest {
+    cwd: cwd.to_path_buf(),
+    diff,
+    revert: false,
+    preflight,
+  };
+  let result = codex_git::apply_git_patch(&request)
+    .map_err(|e| napi::Error::from_reason(format!("git apply failed to run: {e}")))?;
+  let status = if result.exit_code == 0 {
+    cloud::ApplyStatus::Success
+  } else if !result.applied_paths.is_empty() || !result.conflicted_paths.is_empty() {
+    cloud::ApplyStatus::Partial
+  } else {
+    cloud::ApplyStatus::Error
+  };
+  let counts = format!(
+    "applied={}, skipped={}, conflicts={}",
+    result.applied_paths.len(),
+    result.skipped_paths.len(),
+    result.conflicted_paths.len()
+  );
+  let message = match (&status, preflight) {
+    (cloud::ApplyStatus::Success, true) => {
+      format!("Preflight passed for task {task_id} (applies cleanly)")
+    }
+    (cloud::ApplyStatus::Success, false) => format!(
+      "Applied task {task_id} locally ({} files)",
+      result.applied_paths.len()
+    ),
+    (cloud::ApplyStatus::Partial, true) => {
+      format!("Preflight: patch does not fully apply for task {task_id} ({counts})")
+    }
+    (cloud::ApplyStatus::Partial, false) => {
+      format!("Apply partially succeeded for task {task_id} ({counts})")
+    }
+    (cloud::ApplyStatus::Error, true) => format!("Preflight failed for task {task_id} ({counts})"),
+    (cloud::ApplyStatus::Error, false) => format!("Apply failed for task {task_id} ({counts})"),
+  };
+  Ok(
+    cloud::ApplyOutcome {
+      applied: matches!(status, cloud::ApplyStatus::Success) && !preflight,
+      status,
+      message,
+      skipped_paths: result.skipped_paths,
+      conflict_paths: result.conflicted_paths,
+    }
+    .into(),
+  )
 }
 
 #[napi(js_name = "cloudTasksCreate")]
@@ -71,7 +597,7 @@ pub async fn cloud_tasks_create(
   best_of_n: Option<i32>,
   base_url: Option<String>,
   api_key: Option<String>,
-) -> napi::Result<String> {
+) -> napi::Result<CloudTaskCreated> {
   let client =
     build_cloud_client(base_url, api_key).map_err(|e| napi::Error::from_reason(e.to_string()))?;
   let resolved_git_ref = if let Some(g) = git_ref {
@@ -97,8 +623,111 @@ pub async fn cloud_tasks_create(
   )
   .await
   .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-  let payload = serde_json_json!({ "id": created.id.0 });
-  serde_json::to_string(&payload).map_err(|e| napi::Error::from_reason(e.to_string()))
+  Ok(CloudTaskCreated { id: created.id.0 })
 }
 
 // ============================================================================
+
+#[cfg(test)]
+mod tests_cloud_tasks {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn task_summary_converts_to_typed_object() {
+    let updated_at = DateTime::parse_from_rfc3339("2025-01-02T03:04:05Z")
+      .expect("timestamp")
+      .with_timezone(&Utc);
+    let summary = CloudTaskSummary::from(cloud::TaskSummary {
+      id: cloud::TaskId("task_123".to_string()),
+      title: "Fix flaky test".to_string(),
+      status: cloud::TaskStatus::Ready,
+      updated_at,
+      environment_id: Some("env_1".to_string()),
+      environment_label: Some("main".to_string()),
+      summary: cloud::DiffSummary {
+        files_changed: 2,
+        lines_added: 10,
+        lines_removed: 3,
+      },
+      is_review: false,
+      attempt_total: Some(2),
+    });
+
+    assert_eq!(summary.id, "task_123");
+    assert!(matches!(summary.status, CloudTaskStatus::Ready));
+    assert_eq!(summary.updated_at, "2025-01-02T03:04:05+00:00");
+    assert_eq!(summary.environment_label.as_deref(), Some("main"));
+    assert_eq!(summary.summary.files_changed, 2);
+    assert_eq!(summary.attempt_total, Some(2));
+  }
+
+  #[tokio::test]
+  async fn await_polls_until_the_task_leaves_pending() {
+    let task = |status: cloud::TaskStatus| cloud::TaskSummary {
+      id: cloud::TaskId("task_123".to_string()),
+      title: "Fix flaky test".to_string(),
+      status,
+      updated_at: Utc::now(),
+      environment_id: None,
+      environment_label: None,
+      summary: cloud::DiffSummary::default(),
+      is_review: false,
+      attempt_total: None,
+    };
+    let poll = std::time::Duration::from_millis(1);
+
+    let mut statuses = vec![
+      cloud::TaskStatus::Ready,
+      cloud::TaskStatus::Pending,
+      cloud::TaskStatus::Pending,
+    ];
+    let mut polls = 0;
+    let done = await_cloud_task("task_123", poll, std::time::Duration::from_secs(5), || {
+      polls += 1;
+      let status = statuses.pop().unwrap_or(cloud::TaskStatus::Ready);
+      std::future::ready(Ok(task(status)))
+    })
+    .await
+    .unwrap();
+    assert_eq!(done.status, cloud::TaskStatus::Ready);
+    assert_eq!(polls, 3);
+
+    let err = await_cloud_task("task_123", poll, std::time::Duration::from_millis(5), || {
+      std::future::ready(Ok(task(cloud::TaskStatus::Pending)))
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(err.reason, "Timed out after 5ms waiting for cloud task task_123");
+  }
+
+  #[test]
+  fn applies_into_the_given_workspace_on_a_new_branch() {
+    let repo = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+      let output = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+      assert!(output.status.success(), "git {args:?} failed");
+      String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["init", "-q", "-b", "main"]);
+    std::fs::write(repo.path().join("README.md"), "old\n").unwrap();
+    git(&["add", "README.md"]);
+    git(&["commit", "-q", "-m", "init"]);
+    let diff = "diff --git a/README.md b/README.md\n--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-old\n+new\n";
+
+    let preflight = cloud_tasks_apply_internal("task_1", diff, repo.path(), true).unwrap();
+    assert!(matches!(preflight.status, CloudApplyStatus::Success));
+    create_git_branch(repo.path(), "codex/task_1").unwrap();
+    let outcome = cloud_tasks_apply_internal("task_1", diff, repo.path(), false).unwrap();
+
+    assert!(outcome.applied);
+    assert!(outcome.message.starts_with("Applied task task_1 locally"));
+    assert_eq!(std::fs::read_to_string(repo.path().join("README.md")).unwrap(), "new\n");
+    assert_eq!(git(&["branch", "--show-current"]), "codex/task_1");
+  }
+}
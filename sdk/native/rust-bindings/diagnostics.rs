@@ -0,0 +1,546 @@
+// Section 9: Diagnostics and Environment Reports
+// ============================================================================
+//
+// Collects a self-contained snapshot of the host, addon, sandbox and config
+// state for bug reports. Everything is gathered natively so the report stays
+// available even when the JavaScript wrapper is misbehaving.
+//
+// The config doctor goes a step further: it resolves the config a run would
+// get and lists what would make that run fail (or behave unexpectedly), each
+// with a suggested fix, so apps can check their setup once at startup.
+//
+// Key exports:
+//   - capture_environment_report(): JSON blob suitable for attaching to issues
+//   - config_doctor(): Problems with the effective config, auth and binaries
+//
+// ============================================================================
+
+const MAX_RECORDED_RUN_ERRORS: usize = 20;
+
+/// Environment variables whose presence (never value) is reported.
+const REPORTED_SECRET_ENV_VARS: &[&str] = &["OPENAI_API_KEY", "CODEX_API_KEY"];
+
+#[derive(Clone, Debug)]
+struct RecordedRunError {
+  timestamp: String,
+  thread_id: Option<String>,
+  message: String,
+}
+
+fn recorded_run_errors() -> &'static Mutex<VecDeque<RecordedRunError>> {
+  static ERRORS: OnceLock<Mutex<VecDeque<RecordedRunError>>> = OnceLock::new();
+  ERRORS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn push_run_error(
+  errors: &mut VecDeque<RecordedRunError>,
+  thread_id: Option<String>,
+  message: &str,
+) {
+  if errors.len() == MAX_RECORDED_RUN_ERRORS {
+    errors.pop_front();
+  }
+  errors.push_back(RecordedRunError {
+    timestamp: Utc::now().to_rfc3339(),
+    thread_id,
+    message: message.to_string(),
+  });
+}
+
+/// Remember a run failure so it can be surfaced by `capture_environment_report`.
+fn record_run_error(thread_id: Option<String>, message: &str) {
+  if let Ok(mut errors) = recorded_run_errors().lock() {
+    push_run_error(&mut errors, thread_id, message);
+  }
+}
+
+/// The helper a run would use when it passes no `linuxSandboxPath`: the `CODEX_LINUX_SANDBOX_EXE`
+/// override, else the copy embedded in the addon. Nothing is extracted to report on it.
+fn linux_sandbox_report(override_path: Option<PathBuf>, embedded_path: &Path) -> JsonValue {
+  match override_path {
+    Some(path) => json!({
+      "backend": "landlock",
+      "available": path.exists(),
+      "helperPath": path.to_string_lossy(),
+      "overridden": true,
+    }),
+    None => json!({
+      "backend": "landlock",
+      "available": true,
+      "helperPath": embedded_path.to_string_lossy(),
+      "overridden": false,
+      "extracted": embedded_path.exists(),
+    }),
+  }
+}
+
+fn sandbox_backend_report() -> JsonValue {
+  if cfg!(target_os = "linux") {
+    linux_sandbox_report(
+      std::env::var_os("CODEX_LINUX_SANDBOX_EXE").map(PathBuf::from),
+      &embedded_linux_sandbox_path(),
+    )
+  } else if cfg!(target_os = "macos") {
+    json!({
+      "backend": "seatbelt",
+      "available": Path::new("/usr/bin/sandbox-exec").exists(),
+    })
+  } else if cfg!(target_os = "windows") {
+    json!({ "backend": "windows-restricted-token", "available": true })
+  } else {
+    json!({ "backend": "none", "available": false })
+  }
+}
+
+fn to_report_value<T: serde::Serialize>(value: &T) -> JsonValue {
+  serde_json::to_value(value).unwrap_or(JsonValue::Null)
+}
+
+/// Summarise the resolved config without leaking credentials or header values.
+fn config_summary_report(config: &Config) -> JsonValue {
+  let provider = &config.model_provider;
+  let mut mcp_servers: Vec<&String> = config.mcp_servers.get().keys().collect();
+  mcp_servers.sort();
+  json!({
+    "codexHome": config.codex_home.to_string_lossy(),
+    "cwd": config.cwd.to_string_lossy(),
+    "activeProfile": config.active_profile,
+    "model": config.model,
+    "modelReasoningEffort": config.model_reasoning_effort.as_ref().map(to_report_value),
+    "modelProvider": {
+      "id": config.model_provider_id,
+      "name": provider.name,
+      "baseUrl": provider.base_url,
+      "envKey": provider.env_key,
+      "envKeySet": provider
+        .env_key
+        .as_deref()
+        .is_some_and(|key| std::env::var(key).is_ok()),
+      "hasBearerToken": provider.experimental_bearer_token.is_some(),
+      "httpHeaderNames": provider
+        .http_headers
+        .as_ref()
+        .map(|headers| headers.keys().cloned().collect::<Vec<_>>()),
+    },
+    "approvalPolicy": to_report_value(config.approval_policy.get()),
+    "sandboxPolicy": to_report_value(config.sandbox_policy.get()),
+    "mcpServers": mcp_servers,
+  })
+}
+
+fn load_default_config_summary() -> JsonValue {
+  let runtime = match tokio::runtime::Runtime::new() {
+    Ok(runtime) => runtime,
+    Err(err) => return json!({ "error": format!("Failed to create runtime: {err}") }),
+  };
+  match runtime.block_on(Config::load_with_cli_overrides_and_harness_overrides(
+    Vec::new(),
+    ConfigOverrides::default(),
+  )) {
+    Ok(config) => config_summary_report(&config),
+    Err(err) => json!({ "error": err.to_string() }),
+  }
+}
+
+#[napi]
+pub fn capture_environment_report(env: Env) -> napi::Result<JsonValue> {
+  let node = env.get_node_version()?;
+  let secrets: JsonMap<String, JsonValue> = REPORTED_SECRET_ENV_VARS
+    .iter()
+    .map(|name| {
+      let state = if std::env::var(name).is_ok() { "set (redacted)" } else { "unset" };
+      ((*name).to_string(), JsonValue::String(state.to_string()))
+    })
+    .collect();
+  let recent_errors: Vec<JsonValue> = recorded_run_errors()
+    .lock()
+    .map(|errors| {
+      errors
+        .iter()
+        .map(|error| {
+          json!({
+            "timestamp": error.timestamp,
+            "threadId": error.thread_id,
+            "message": error.message,
+          })
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+
+  Ok(json!({
+    "generatedAt": Utc::now().to_rfc3339(),
+    "os": {
+      "platform": std::env::consts::OS,
+      "family": std::env::consts::FAMILY,
+      "arch": std::env::consts::ARCH,
+    },
+    "node": {
+      "version": format!("v{}.{}.{}", node.major, node.minor, node.patch),
+      "napiVersion": env.get_napi_version()?,
+    },
+    "addon": {
+      "name": env!("CARGO_PKG_NAME"),
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "codex": {
+      "userAgent": default_client::get_codex_user_agent(),
+      "originator": NATIVE_ORIGINATOR,
+    },
+    "sandbox": sandbox_backend_report(),
+    "config": load_default_config_summary(),
+    "environment": secrets,
+    "registeredTools": registered_native_tools().lock().map(|tools| tools.len()).unwrap_or(0),
+    "recentRunErrors": recent_errors,
+  }))
+}
+
+#[napi(object)]
+pub struct ConfigDoctorOptions {
+  /// Thread options to check, as a run would receive them.
+  pub config: Option<ConversationConfigRequest>,
+  /// `key=value` overrides, as passed to `codex -c`.
+  #[napi(js_name = "configOverrides")]
+  pub config_overrides: Option<Vec<String>>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigProblem {
+  #[napi(ts_type = "\"error\" | \"warning\"")]
+  pub severity: String,
+  /// Stable identifier such as `auth.missing`.
+  pub code: String,
+  pub message: String,
+  pub fix: Option<String>,
+}
+
+#[napi(object)]
+pub struct ConfigDoctorReport {
+  /// No problem is an error.
+  pub ok: bool,
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  pub cwd: Option<String>,
+  pub model: Option<String>,
+  #[napi(js_name = "modelProvider")]
+  pub model_provider: Option<String>,
+  pub problems: Vec<ConfigProblem>,
+}
+
+impl ConfigProblem {
+  fn error(code: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+    Self {
+      severity: "error".to_string(),
+      code: code.to_string(),
+      message: message.into(),
+      fix: Some(fix.into()),
+    }
+  }
+
+  fn warning(code: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+    Self {
+      severity: "warning".to_string(),
+      ..Self::error(code, message, fix)
+    }
+  }
+}
+
+/// What the doctor found on the host, gathered separately so the checks stay pure.
+struct DoctorProbe {
+  git_available: bool,
+  /// The Linux sandbox helper a run would use.
+  sandbox_helper: Result<Option<PathBuf>, String>,
+  auth_mode: Option<AuthMode>,
+}
+
+fn uses_sandbox(policy: &codex_protocol::protocol::SandboxPolicy) -> bool {
+  use codex_protocol::protocol::SandboxPolicy;
+  match policy {
+    SandboxPolicy::ReadOnly | SandboxPolicy::WorkspaceWrite { .. } => true,
+    SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => false,
+  }
+}
+
+fn config_problems(
+  options: &InternalRunRequest,
+  config: &Config,
+  probe: &DoctorProbe,
+) -> Vec<ConfigProblem> {
+  let mut problems = Vec::new();
+
+  if let Err(err) = validate_model_name(
+    options.model.as_deref(),
+    options.oss,
+    options.model_provider.as_deref(),
+  ) {
+    problems.push(ConfigProblem::error(
+      "model.unsupported",
+      err.reason,
+      "Pick a supported model, or set modelProvider when the model is served elsewhere.",
+    ));
+  }
+
+  let provider = &config.model_provider;
+  if let Some(key) = provider.env_key.as_deref()
+    && std::env::var(key).is_err()
+  {
+    problems.push(ConfigProblem::error(
+      "provider.env_key_missing",
+      format!(
+        "Model provider \"{}\" reads its API key from {key}, which is not set.",
+        config.model_provider_id
+      ),
+      format!("Export {key} before starting the app."),
+    ));
+  }
+  if options.base_url.is_some() && !provider.is_openai() {
+    problems.push(ConfigProblem::warning(
+      "provider.base_url_ignored",
+      format!(
+        "baseUrl only applies to the OpenAI provider, not \"{}\".",
+        config.model_provider_id
+      ),
+      "Set base_url on the provider in config.toml instead.",
+    ));
+  }
+
+  if provider.requires_openai_auth {
+    match (probe.auth_mode, config.forced_login_method) {
+      (None, _) => problems.push(ConfigProblem::error(
+        "auth.missing",
+        "No credentials found for the OpenAI provider.",
+        "Call authLoginApiKey() or authLoginChatGpt(), pass apiKey, or set CODEX_API_KEY.",
+      )),
+      (Some(AuthMode::Chatgpt), Some(ForcedLoginMethod::Api)) => {
+        problems.push(ConfigProblem::error(
+          "auth.login_method",
+          "config.toml requires API key login, but ChatGPT credentials are in use.",
+          "Log in with authLoginApiKey().",
+        ))
+      }
+      (Some(AuthMode::ApiKey), Some(ForcedLoginMethod::Chatgpt)) => {
+        problems.push(ConfigProblem::error(
+          "auth.login_method",
+          "config.toml requires ChatGPT login, but an API key is in use.",
+          "Log in with authLoginChatGpt().",
+        ))
+      }
+      (Some(_), _) => {}
+    }
+  }
+
+  let sandbox_policy = config.sandbox_policy.get();
+  if uses_sandbox(sandbox_policy) {
+    if cfg!(target_os = "linux") {
+      let missing = match &probe.sandbox_helper {
+        Ok(Some(path)) if path.exists() => None,
+        Ok(Some(path)) => Some(format!(
+          "The Linux sandbox helper {} does not exist.",
+          path.display()
+        )),
+        Ok(None) => Some("No Linux sandbox helper is available.".to_string()),
+        Err(reason) => Some(format!(
+          "The Linux sandbox helper could not be prepared: {reason}"
+        )),
+      };
+      if let Some(message) = missing {
+        problems.push(ConfigProblem::error(
+          "sandbox.helper_missing",
+          message,
+          "Pass linuxSandboxPath or set CODEX_LINUX_SANDBOX_EXE to a codex-linux-sandbox binary.",
+        ));
+      }
+    } else if cfg!(target_os = "macos") && !Path::new("/usr/bin/sandbox-exec").exists() {
+      problems.push(ConfigProblem::error(
+        "sandbox.helper_missing",
+        "/usr/bin/sandbox-exec is not available, so commands cannot be sandboxed.",
+        "Run on a standard macOS install, or use sandboxMode \"danger-full-access\" inside an external sandbox.",
+      ));
+    }
+  } else if matches!(config.approval_policy.get(), AskForApproval::Never) {
+    problems.push(ConfigProblem::warning(
+      "sandbox.unrestricted",
+      "Commands run without a sandbox and without asking for approval.",
+      "Use sandboxMode \"workspace-write\", or only run this way inside an external sandbox.",
+    ));
+  }
+
+  if !probe.git_available {
+    problems.push(ConfigProblem::warning(
+      "git.missing",
+      "git is not on PATH; diffs, cloud task apply and the trusted-directory check need it.",
+      "Install git.",
+    ));
+  } else if !options.skip_git_repo_check && get_git_repo_root(&config.cwd).is_none() {
+    problems.push(ConfigProblem::error(
+      "git.untrusted_directory",
+      format!(
+        "{} is not inside a git repository, so runs will refuse to start.",
+        config.cwd.display()
+      ),
+      "Run inside a git repository or set skipGitRepoCheck.",
+    ));
+  }
+
+  problems
+}
+
+fn git_available() -> bool {
+  std::process::Command::new("git")
+    .arg("--version")
+    .output()
+    .is_ok_and(|output| output.status.success())
+}
+
+fn doctor_report(problems: Vec<ConfigProblem>, config: Option<&Config>) -> ConfigDoctorReport {
+  ConfigDoctorReport {
+    ok: problems.iter().all(|problem| problem.severity != "error"),
+    codex_home: config.map(|config| config.codex_home.to_string_lossy().to_string()),
+    cwd: config.map(|config| config.cwd.to_string_lossy().to_string()),
+    model: config.and_then(|config| config.model.clone()),
+    model_provider: config.map(|config| config.model_provider_id.clone()),
+    problems,
+  }
+}
+
+#[napi(js_name = "configDoctor")]
+pub async fn config_doctor(
+  options: Option<ConfigDoctorOptions>,
+) -> napi::Result<ConfigDoctorReport> {
+  let ConfigDoctorOptions {
+    config: config_request,
+    config_overrides,
+  } = options.unwrap_or(ConfigDoctorOptions {
+    config: None,
+    config_overrides: None,
+  });
+  let options = match config_request.unwrap_or_default().into_internal_request() {
+    Ok(options) => options,
+    Err(err) => {
+      let problem = ConfigProblem::error("options.invalid", err.reason, "Fix the thread options.");
+      return Ok(doctor_report(vec![problem], None));
+    }
+  };
+  let extra_overrides = match (CliConfigOverrides {
+    raw_overrides: config_overrides.unwrap_or_default(),
+  })
+  .parse_overrides()
+  {
+    Ok(overrides) => overrides,
+    Err(err) => {
+      let problem = ConfigProblem::error(
+        "config.invalid_override",
+        format!("Failed to parse config overrides: {err}"),
+        "Pass overrides as key=value, with TOML values.",
+      );
+      return Ok(doctor_report(vec![problem], None));
+    }
+  };
+
+  let (overrides, mut cli_kv_overrides) =
+    build_config_inputs(&options, options.linux_sandbox_path.clone())?;
+  cli_kv_overrides.extend(extra_overrides);
+  let config = match Config::load_with_cli_overrides_and_harness_overrides(
+    cli_kv_overrides,
+    overrides,
+  )
+  .await
+  {
+    Ok(config) => config,
+    Err(err) => {
+      let problem = ConfigProblem::error(
+        "config.load_failed",
+        format!("Failed to load config: {err}"),
+        "Fix config.toml in codex home (or the override that broke it).",
+      );
+      return Ok(doctor_report(vec![problem], None));
+    }
+  };
+
+  let probe = DoctorProbe {
+    git_available: git_available(),
+    sandbox_helper: resolve_linux_sandbox_path(options.linux_sandbox_path.clone())
+      .map_err(|err| err.reason),
+    auth_mode: run_auth_manager(&options, &config).auth_mode(),
+  };
+  let problems = config_problems(&options, &config, &probe);
+  Ok(doctor_report(problems, Some(&config)))
+}
+
+#[cfg(test)]
+mod tests_diagnostics {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[tokio::test]
+  async fn doctor_lists_problems_with_fixes() {
+    let cwd = tempfile::tempdir().expect("tempdir");
+    let options = ConversationConfigRequest {
+      model: Some("gpt-4.1".to_string()),
+      sandbox_mode: Some("read-only".to_string()),
+      working_directory: Some(cwd.path().to_string_lossy().to_string()),
+      ..Default::default()
+    }
+    .into_internal_request()
+    .expect("options parse");
+    let config = load_config_from_internal(&options)
+      .await
+      .expect("config should load");
+    let probe = DoctorProbe {
+      git_available: true,
+      sandbox_helper: Ok(Some(PathBuf::from("/nonexistent/codex-linux-sandbox"))),
+      auth_mode: None,
+    };
+
+    let report = doctor_report(config_problems(&options, &config, &probe), Some(&config));
+    let codes: Vec<&str> = report.problems.iter().map(|problem| problem.code.as_str()).collect();
+    let mut expected = vec!["model.unsupported", "auth.missing"];
+    if cfg!(target_os = "linux") {
+      expected.push("sandbox.helper_missing");
+    }
+    expected.push("git.untrusted_directory");
+    assert_eq!(codes, expected);
+    assert!(!report.ok);
+    assert!(report.problems.iter().all(|problem| problem.fix.is_some()));
+  }
+
+  #[test]
+  fn run_error_buffer_keeps_most_recent_entries() {
+    let mut errors = VecDeque::new();
+    for idx in 0..(MAX_RECORDED_RUN_ERRORS + 3) {
+      push_run_error(
+        &mut errors,
+        Some("thread-1".to_string()),
+        &format!("failure {idx}"),
+      );
+    }
+    assert_eq!(errors.len(), MAX_RECORDED_RUN_ERRORS);
+    assert_eq!(
+      errors.front().map(|error| error.message.as_str()),
+      Some("failure 3")
+    );
+    assert_eq!(
+      errors.back().map(|error| error.message.as_str()),
+      Some(format!("failure {}", MAX_RECORDED_RUN_ERRORS + 2).as_str())
+    );
+  }
+
+  #[test]
+  fn linux_sandbox_report_resolves_without_extracting() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let embedded = dir.path().join("codex-linux-sandbox");
+
+    let report = linux_sandbox_report(None, &embedded);
+    assert_eq!(report["available"], true);
+    assert_eq!(report["overridden"], false);
+    assert_eq!(report["extracted"], false);
+    assert!(!embedded.exists());
+
+    let missing = dir.path().join("missing-helper");
+    let report = linux_sandbox_report(Some(missing.clone()), &embedded);
+    assert_eq!(report["available"], false);
+    assert_eq!(report["overridden"], true);
+    assert_eq!(report["helperPath"], missing.to_string_lossy().as_ref());
+  }
+}
@@ -0,0 +1,315 @@
+// ============================================================================
+// WebSocket event bus (startEventBus / stopEventBus)
+// ============================================================================
+//
+// Broadcasts the events of every run in this process to WebSocket clients on
+// 127.0.0.1, so a separate UI process (an Electron window, a web dashboard)
+// can watch runs executing elsewhere. Clients authenticate with `?token=` or
+// `Authorization: Bearer`, and receive
+// `{ "type": "thread_event", "thread_id", "event" }` messages for the threads
+// and event types they subscribe to: everything by default, `?threadId=` and
+// `?eventType=` (repeatable) on the URL, or a
+// `{ "type": "subscribe", "threadIds"?, "eventTypes"? }` message that replaces
+// the filter at any time. Event types ending in `*` match by prefix, e.g.
+// `item.*`. A client too slow to keep up is sent `{ "type": "lagged",
+// "skipped" }` in place of the events it missed.
+
+/// Events buffered per client before a slow one starts missing them.
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct EventBusOptions {
+  /// Loopback port to listen on; any free port when unset.
+  pub port: Option<u32>,
+  /// Token clients must send. Generated when unset.
+  #[napi(js_name = "authToken")]
+  pub auth_token: Option<String>,
+}
+
+#[napi(object)]
+pub struct EventBusInfo {
+  pub id: String,
+  /// `ws://127.0.0.1:<port>`.
+  pub url: String,
+  pub port: u32,
+  #[napi(js_name = "authToken")]
+  pub auth_token: String,
+}
+
+struct BusEvent {
+  thread_id: Option<String>,
+  event_type: String,
+  message: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BusFilter {
+  thread_ids: Option<Vec<String>>,
+  event_types: Option<Vec<String>>,
+}
+
+impl BusFilter {
+  fn matches(&self, thread_id: Option<&str>, event_type: &str) -> bool {
+    let thread_matches = match (&self.thread_ids, thread_id) {
+      (None, _) => true,
+      (Some(ids), Some(thread_id)) => ids.iter().any(|id| id == thread_id),
+      (Some(_), None) => false,
+    };
+    let type_matches = self.event_types.as_ref().is_none_or(|types| {
+      types.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => event_type.starts_with(prefix),
+        None => pattern == event_type,
+      })
+    });
+    thread_matches && type_matches
+  }
+
+  /// The filter and token from a connection URL's query string.
+  fn from_query(query: &str) -> (Self, Option<String>) {
+    let mut filter = Self::default();
+    let mut token = None;
+    let url = reqwest::Url::parse(&format!("ws://localhost/?{query}"));
+    for (key, value) in url.iter().flat_map(reqwest::Url::query_pairs) {
+      match key.as_ref() {
+        "token" => token = Some(value.into_owned()),
+        "threadId" => filter
+          .thread_ids
+          .get_or_insert_with(Vec::new)
+          .push(value.into_owned()),
+        "eventType" => filter
+          .event_types
+          .get_or_insert_with(Vec::new)
+          .push(value.into_owned()),
+        _ => {}
+      }
+    }
+    (filter, token)
+  }
+}
+
+fn event_bus() -> &'static tokio::sync::broadcast::Sender<Arc<BusEvent>> {
+  static BUS: OnceLock<tokio::sync::broadcast::Sender<Arc<BusEvent>>> = OnceLock::new();
+  BUS.get_or_init(|| tokio::sync::broadcast::channel(EVENT_BUS_CAPACITY).0)
+}
+
+fn event_bus_servers() -> &'static Mutex<HashMap<String, CancellationToken>> {
+  static SERVERS: OnceLock<Mutex<HashMap<String, CancellationToken>>> = OnceLock::new();
+  SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Send `event` to connected bus clients. Free when nobody is listening.
+fn publish_bus_event(thread_id: Option<String>, event: &ExecThreadEvent) {
+  let bus = event_bus();
+  if bus.receiver_count() == 0 {
+    return;
+  }
+  let Ok(value) = event_to_json(event) else {
+    return;
+  };
+  let event_type = value
+    .get("type")
+    .and_then(JsonValue::as_str)
+    .unwrap_or_default()
+    .to_string();
+  let message =
+    json!({ "type": "thread_event", "thread_id": thread_id, "event": value }).to_string();
+  let _ = bus.send(Arc::new(BusEvent {
+    thread_id,
+    event_type,
+    message,
+  }));
+}
+
+async fn serve_bus_client(
+  stream: tokio::net::TcpStream,
+  token: Arc<str>,
+  mut events: tokio::sync::broadcast::Receiver<Arc<BusEvent>>,
+  stopped: CancellationToken,
+) {
+  use futures::SinkExt;
+  use futures::StreamExt;
+  use tokio_tungstenite::tungstenite::Message;
+  use tokio_tungstenite::tungstenite::handshake::server::ErrorResponse;
+  use tokio_tungstenite::tungstenite::handshake::server::Request;
+  use tokio_tungstenite::tungstenite::handshake::server::Response;
+
+  let mut filter = BusFilter::default();
+  let authorize = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+    let (query_filter, query_token) =
+      BusFilter::from_query(request.uri().query().unwrap_or_default());
+    let authorization = request
+      .headers()
+      .get("authorization")
+      .and_then(|value| value.to_str().ok());
+    let authorized = match query_token.as_deref() {
+      Some(query_token) => token_matches(Some(query_token), &token),
+      None => bearer_token_matches(authorization, &token),
+    };
+    if !authorized {
+      let mut error = ErrorResponse::new(Some("missing or invalid token".to_string()));
+      *error.status_mut() = tokio_tungstenite::tungstenite::http::StatusCode::UNAUTHORIZED;
+      return Err(error);
+    }
+    filter = query_filter;
+    Ok(response)
+  };
+  let Ok(mut socket) = tokio_tungstenite::accept_hdr_async(stream, authorize).await else {
+    return;
+  };
+
+  loop {
+    let outgoing = tokio::select! {
+      _ = stopped.cancelled() => break,
+      incoming = socket.next() => match incoming {
+        Some(Ok(Message::Text(text))) => {
+          match serde_json::from_str::<BusFilter>(text.as_str()) {
+            Ok(subscription) => {
+              filter = subscription;
+              json!({ "type": "subscribed" })
+            }
+            Err(err) => json!({ "type": "error", "message": format!("invalid subscription: {err}") }),
+          }
+        }
+        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+        Some(Ok(_)) => continue,
+      },
+      event = events.recv() => match event {
+        Ok(event) if filter.matches(event.thread_id.as_deref(), &event.event_type) => {
+          if socket.send(Message::Text(event.message.clone().into())).await.is_err() {
+            break;
+          }
+          continue;
+        }
+        Ok(_) => continue,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+          json!({ "type": "lagged", "skipped": skipped })
+        }
+        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+      },
+    };
+    if socket
+      .send(Message::Text(outgoing.to_string().into()))
+      .await
+      .is_err()
+    {
+      break;
+    }
+  }
+  let _ = socket.close(None).await;
+}
+
+/// Broadcast this process's thread events over WebSocket on 127.0.0.1 until stopEventBus().
+#[napi]
+pub fn start_event_bus(options: Option<EventBusOptions>) -> napi::Result<EventBusInfo> {
+  let options = options.unwrap_or_default();
+  let port = u16::try_from(options.port.unwrap_or(0))
+    .map_err(|_| napi::Error::from_reason("port must be between 0 and 65535"))?;
+  let token = match options.auth_token {
+    Some(token) if token.trim().is_empty() => {
+      return Err(napi::Error::from_reason("authToken must not be empty"));
+    }
+    Some(token) => token,
+    None => Uuid::new_v4().simple().to_string(),
+  };
+  let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+    .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to bind event bus: {e}")))?;
+  let addr = listener
+    .local_addr()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read event bus address: {e}")))?;
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+
+  let id = Uuid::new_v4().to_string();
+  let stopped = CancellationToken::new();
+  let stopped_for_server = stopped.clone();
+  let token_for_server: Arc<str> = Arc::from(token.as_str());
+  std::thread::Builder::new()
+    .name("codex-event-bus".to_string())
+    .spawn(move || {
+      runtime.block_on(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+          Ok(listener) => listener,
+          Err(err) => {
+            eprintln!("Failed to start event bus: {err}");
+            return;
+          }
+        };
+        loop {
+          tokio::select! {
+            _ = stopped_for_server.cancelled() => break,
+            accepted = listener.accept() => {
+              let Ok((stream, _)) = accepted else {
+                continue;
+              };
+              tokio::spawn(serve_bus_client(
+                stream,
+                Arc::clone(&token_for_server),
+                event_bus().subscribe(),
+                stopped_for_server.clone(),
+              ));
+            }
+          }
+        }
+      });
+    })
+    .map_err(|e| napi::Error::from_reason(format!("Failed to spawn event bus: {e}")))?;
+
+  event_bus_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("event bus mutex poisoned: {e}")))?
+    .insert(id.clone(), stopped);
+
+  Ok(EventBusInfo {
+    id,
+    url: format!("ws://{addr}"),
+    port: u32::from(addr.port()),
+    auth_token: token,
+  })
+}
+
+/// Disconnect every client of an event bus and stop listening. Returns `false` for unknown ids.
+#[napi]
+pub fn stop_event_bus(id: String) -> napi::Result<bool> {
+  let stopped = event_bus_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("event bus mutex poisoned: {e}")))?
+    .remove(&id);
+  if let Some(stopped) = &stopped {
+    stopped.cancel();
+  }
+  Ok(stopped.is_some())
+}
+
+#[cfg(test)]
+mod tests_event_bus {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn filters_route_by_thread_and_event_type() {
+    let (filter, token) = BusFilter::from_query("token=secret&threadId=t1&eventType=item.*");
+    assert_eq!(token.as_deref(), Some("secret"));
+    assert_eq!(
+      filter,
+      BusFilter {
+        thread_ids: Some(vec!["t1".to_string()]),
+        event_types: Some(vec!["item.*".to_string()]),
+      }
+    );
+    assert!(filter.matches(Some("t1"), "item.completed"));
+    assert!(!filter.matches(Some("t2"), "item.completed"));
+    assert!(!filter.matches(Some("t1"), "turn.completed"));
+    assert!(!filter.matches(None, "item.completed"));
+
+    let everything = BusFilter::default();
+    assert!(everything.matches(None, "thread.started"));
+    let exact: BusFilter = serde_json::from_str(r#"{"eventTypes":["turn.failed"]}"#).unwrap();
+    assert!(exact.matches(Some("t3"), "turn.failed"));
+    assert!(!exact.matches(Some("t3"), "turn.completed"));
+  }
+}
@@ -158,7 +158,7 @@ pub async fn fast_embed_embed(req: FastEmbedEmbedRequest) -> napi::Result<Vec<Ve
     let mut vector = maybe_vector
       .ok_or_else(|| napi::Error::from_reason("Missing embedding after FastEmbed inference"))?;
     if req.normalize.unwrap_or(false) {
-      normalize_vector(&mut vector);
+      codex_native_core::embed::normalize_vector(&mut vector);
     }
     outputs.push(vector);
   }
@@ -167,50 +167,8 @@ pub async fn fast_embed_embed(req: FastEmbedEmbedRequest) -> napi::Result<Vec<Ve
 }
 
 fn resolve_fastembed_model(model: Option<String>) -> napi::Result<EmbeddingModel> {
-  match model {
-    None => Ok(EmbeddingModel::default()),
-    Some(name) => {
-      let trimmed = name.trim();
-      let sanitized = sanitize_model_identifier(trimmed);
-      if let Ok(parsed) = sanitized.parse::<EmbeddingModel>() {
-        return Ok(parsed);
-      }
-      if let Some(matched) = match_supported_model(&sanitized) {
-        return Ok(matched);
-      }
-      Err(napi::Error::from_reason(format!(
-        "Unknown FastEmbed model '{trimmed}'. Run fastembed::TextEmbedding::list_supported_models() to inspect supported identifiers."
-      )))
-    }
-  }
-}
-
-fn sanitize_model_identifier(input: &str) -> String {
-  let lowercase = input.trim();
-  if lowercase
-    .to_ascii_lowercase()
-    .starts_with("baai/bge-")
-  {
-    let suffix = lowercase
-      .split_once('/')
-      .map(|(_, right)| right)
-      .unwrap_or(lowercase);
-    format!("Xenova/{suffix}")
-  } else {
-    lowercase.to_string()
-  }
-}
-
-fn match_supported_model(identifier: &str) -> Option<EmbeddingModel> {
-  let id_lower = identifier.to_ascii_lowercase();
-  let supported = TextEmbedding::list_supported_models();
-  for info in supported {
-    let code = info.model_code.to_ascii_lowercase();
-    if code == id_lower || code.ends_with(&id_lower) || id_lower.ends_with(&code) {
-      return Some(info.model);
-    }
-  }
-  None
+  codex_native_core::embed::resolve_model(model.as_deref())
+    .map_err(|err| napi::Error::from_reason(err.to_string()))
 }
 
 struct EmbeddingCache {
@@ -336,18 +294,6 @@ fn derive_fastembed_namespace(opts: &TextInitOptions) -> String {
   hash_string(&descriptor)
 }
 
-fn normalize_vector(vec: &mut [f32]) {
-  let norm = vec
-    .iter()
-    .fold(0f64, |sum, value| sum + (*value as f64) * (*value as f64))
-    .sqrt();
-  if norm > 0.0 {
-    for value in vec {
-      *value = (*value as f64 / norm) as f32;
-    }
-  }
-}
-
 fn default_model_cache_dir(kind: &str) -> Option<PathBuf> {
   resolve_codex_home_for_cache().map(|home| home.join("fastembed").join(kind))
 }
@@ -116,3 +116,634 @@ pub async fn collect_repo_diff_summary(
   .map_err(|err| napi::Error::from_reason(format!("Failed to collect repo diff summary: {err}")))?;
   Ok(summary.into())
 }
+
+// ============================================================================
+// Three-way merge of patches onto a diverged workspace
+// ============================================================================
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GitConflictRegion {
+  /// 1-based line of the `<<<<<<<` marker in the merged output.
+  #[napi(js_name = "startLine")]
+  pub start_line: u32,
+  /// 1-based line of the `>>>>>>>` marker in the merged output.
+  #[napi(js_name = "endLine")]
+  pub end_line: u32,
+  pub local: String,
+  pub base: String,
+  pub incoming: String,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GitMergeConflict {
+  pub path: String,
+  /// Set when the file could not be merged line-by-line (e.g. deleted by the patch).
+  pub reason: Option<String>,
+  pub regions: Vec<GitConflictRegion>,
+  /// Merged file contents including conflict markers, when a textual merge ran.
+  pub merged: Option<String>,
+}
+
+/// Outcome of merging a patch into files that carry uncommitted local edits.
+#[derive(Debug, Default)]
+pub(crate) struct WorkspaceMergeResult {
+  /// Cleanly merged contents to write back, keyed by repo-relative path.
+  pub merged_files: Vec<(String, String)>,
+  pub conflicts: Vec<GitMergeConflict>,
+  /// The portion of the patch touching files without local edits.
+  pub remaining_patch: String,
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> io::Result<std::process::Output> {
+  std::process::Command::new("git")
+    .args(args)
+    .current_dir(cwd)
+    .output()
+}
+
+/// `git switch -c`: a new branch at HEAD, keeping uncommitted changes in the working tree.
+pub(crate) fn create_git_branch(cwd: &Path, name: &str) -> io::Result<()> {
+  let output = run_git(cwd, &["switch", "-c", name])?;
+  if !output.status.success() {
+    return Err(io::Error::other(format!(
+      "git switch failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  Ok(())
+}
+
+fn run_git_stdout(cwd: &Path, args: &[&str]) -> io::Result<String> {
+  let output = run_git(cwd, args)?;
+  if !output.status.success() {
+    return Err(io::Error::other(format!(
+      "git {} failed: {}",
+      args.first().copied().unwrap_or_default(),
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The changes `head_ref` makes since its merge base with `base_ref`, or the working tree's
+/// changes since HEAD's merge base with `base_ref` when `head_ref` is unset.
+pub(crate) fn git_diff_since_merge_base(
+  cwd: &Path,
+  base_ref: &str,
+  head_ref: Option<&str>,
+) -> io::Result<String> {
+  let merge_base = run_git_stdout(cwd, &["merge-base", base_ref, head_ref.unwrap_or("HEAD")])?;
+  let mut args = vec!["diff", "--no-color", "--no-ext-diff", merge_base.trim()];
+  args.extend(head_ref);
+  run_git_stdout(cwd, &args)
+}
+
+/// The staged changes, or every uncommitted change to tracked files when `staged` is false.
+pub(crate) fn git_uncommitted_diff(cwd: &Path, staged: bool) -> io::Result<String> {
+  let target = if staged { "--cached" } else { "HEAD" };
+  run_git_stdout(cwd, &["diff", "--no-color", "--no-ext-diff", target])
+}
+
+/// Subjects of the commits `head_ref` has and `base_ref` lacks, oldest first.
+pub(crate) fn git_commit_subjects(
+  cwd: &Path,
+  base_ref: &str,
+  head_ref: &str,
+) -> io::Result<Vec<String>> {
+  let range = format!("{base_ref}..{head_ref}");
+  let log = run_git_stdout(cwd, &["log", "--reverse", "--format=%s", &range])?;
+  Ok(
+    log
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(str::to_string)
+      .collect(),
+  )
+}
+
+/// Paths from `paths` that have staged or unstaged changes relative to HEAD.
+pub(crate) fn locally_modified_paths(cwd: &Path, paths: &[String]) -> io::Result<Vec<String>> {
+  if paths.is_empty() {
+    return Ok(Vec::new());
+  }
+  let mut args = vec!["status", "--porcelain=v1", "-z", "--untracked-files=all", "--"];
+  args.extend(paths.iter().map(String::as_str));
+  let output = run_git(cwd, &args)?;
+  if !output.status.success() {
+    return Err(io::Error::other(format!(
+      "git status failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let mut dirty = Vec::new();
+  let mut entries = stdout.split('\0').filter(|entry| !entry.is_empty());
+  while let Some(entry) = entries.next() {
+    let (Some(status), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+      continue;
+    };
+    dirty.push(path.to_string());
+    // Renames and copies are followed by their origin path as a separate entry.
+    if status.contains(['R', 'C'])
+      && let Some(origin) = entries.next()
+    {
+      dirty.push(origin.to_string());
+    }
+  }
+  dirty.retain(|path| paths.contains(path));
+  dirty.sort();
+  dirty.dedup();
+  Ok(dirty)
+}
+
+/// Files overwritten by [`write_merged_files`], with the contents they had before.
+pub(crate) struct MergedFilesBackup {
+  originals: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl MergedFilesBackup {
+  /// Put back what every written file held before the merge.
+  pub(crate) fn restore(&self) -> io::Result<()> {
+    for (path, contents) in self.originals.iter().rev() {
+      std::fs::write(path, contents)?;
+    }
+    Ok(())
+  }
+}
+
+/// Write every merged file or none of them: a failed write restores the files already written.
+pub(crate) fn write_merged_files(
+  cwd: &Path,
+  files: &[(String, String)],
+) -> io::Result<MergedFilesBackup> {
+  let mut backup = MergedFilesBackup {
+    originals: Vec::new(),
+  };
+  for (path, contents) in files {
+    let target = cwd.join(path);
+    let written = std::fs::read(&target).and_then(|original| {
+      backup.originals.push((target.clone(), original));
+      std::fs::write(&target, contents)
+    });
+    if let Err(err) = written {
+      let restored = backup.restore().map_or_else(
+        |err| format!("; restoring local changes failed: {err}"),
+        |()| String::new(),
+      );
+      return Err(io::Error::other(format!(
+        "failed to write merged {path}: {err}{restored}"
+      )));
+    }
+  }
+  Ok(backup)
+}
+
+#[derive(Debug, PartialEq)]
+struct FilePatch {
+  path: String,
+  chunk: String,
+  preimage_blob: Option<String>,
+  is_new: bool,
+  is_deleted: bool,
+}
+
+fn split_patch_by_file(diff: &str) -> Vec<FilePatch> {
+  let mut files = Vec::new();
+  let mut current: Option<FilePatch> = None;
+  let mut in_hunks = false;
+  for line in diff.split_inclusive('\n') {
+    if let Some(rest) = line.strip_prefix("diff --git ") {
+      files.extend(current.take());
+      in_hunks = false;
+      let path = rest
+        .trim_end()
+        .rsplit_once(" b/")
+        .map(|(_, b)| b.to_string())
+        .unwrap_or_default();
+      current = Some(FilePatch {
+        path,
+        chunk: String::new(),
+        preimage_blob: None,
+        is_new: false,
+        is_deleted: false,
+      });
+    }
+    let Some(file) = current.as_mut() else {
+      continue;
+    };
+    if line.starts_with("@@") {
+      in_hunks = true;
+    } else if !in_hunks {
+      if let Some(index) = line.strip_prefix("index ") {
+        file.preimage_blob = index
+          .split("..")
+          .next()
+          .map(str::trim)
+          .filter(|blob| !blob.chars().all(|ch| ch == '0'))
+          .map(str::to_string);
+      } else if line.starts_with("new file mode") {
+        file.is_new = true;
+      } else if line.starts_with("deleted file mode") {
+        file.is_deleted = true;
+      }
+    }
+    file.chunk.push_str(line);
+  }
+  files.extend(current);
+  files
+}
+
+fn read_merge_base_contents(cwd: &Path, file: &FilePatch) -> io::Result<String> {
+  if let Some(blob) = file.preimage_blob.as_deref() {
+    let output = run_git(cwd, &["cat-file", "-p", blob])?;
+    if output.status.success() {
+      return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+  }
+  let spec = format!("HEAD:{}", file.path);
+  let output = run_git(cwd, &["show", &spec])?;
+  if !output.status.success() {
+    return Err(io::Error::other(format!(
+      "unable to resolve merge base for {}",
+      file.path
+    )));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Apply a single-file patch to `base` in an isolated directory and return the result.
+fn apply_file_patch_to(base: &str, file: &FilePatch) -> io::Result<String> {
+  let scratch = tempfile::tempdir()?;
+  let target = scratch.path().join(&file.path);
+  if let Some(parent) = target.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(&target, base)?;
+  let patch_path = scratch.path().join(".codex-merge.patch");
+  std::fs::write(&patch_path, &file.chunk)?;
+
+  let output = std::process::Command::new("git")
+    .arg("apply")
+    .arg("--whitespace=nowarn")
+    .arg(&patch_path)
+    .current_dir(scratch.path())
+    .env("GIT_CEILING_DIRECTORIES", scratch.path())
+    .env_remove("GIT_DIR")
+    .env_remove("GIT_WORK_TREE")
+    .output()?;
+  if !output.status.success() {
+    return Err(io::Error::other(format!(
+      "patch for {} does not apply to its base: {}",
+      file.path,
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  std::fs::read_to_string(&target)
+}
+
+/// Run `git merge-file --diff3` and return the merged text plus whether it conflicted.
+fn merge_file_contents(local: &str, base: &str, incoming: &str) -> io::Result<(String, bool)> {
+  let scratch = tempfile::tempdir()?;
+  let local_path = scratch.path().join("local");
+  let base_path = scratch.path().join("base");
+  let incoming_path = scratch.path().join("incoming");
+  std::fs::write(&local_path, local)?;
+  std::fs::write(&base_path, base)?;
+  std::fs::write(&incoming_path, incoming)?;
+
+  let output = std::process::Command::new("git")
+    .args(["merge-file", "-p", "--diff3", "-L", "local", "-L", "base", "-L", "incoming"])
+    .arg(&local_path)
+    .arg(&base_path)
+    .arg(&incoming_path)
+    .output()?;
+  match output.status.code() {
+    Some(code) if code >= 0 => Ok((String::from_utf8_lossy(&output.stdout).into_owned(), code > 0)),
+    _ => Err(io::Error::other(format!(
+      "git merge-file failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ))),
+  }
+}
+
+/// Extract the conflict hunks written by `git merge-file --diff3`.
+fn parse_conflict_regions(merged: &str) -> Vec<GitConflictRegion> {
+  enum Section {
+    Local,
+    Base,
+    Incoming,
+  }
+
+  let mut regions = Vec::new();
+  let mut current: Option<(GitConflictRegion, Section)> = None;
+  for (index, line) in merged.lines().enumerate() {
+    let line_no = index as u32 + 1;
+    if line.starts_with("<<<<<<< ") {
+      current = Some((
+        GitConflictRegion {
+          start_line: line_no,
+          end_line: line_no,
+          local: String::new(),
+          base: String::new(),
+          incoming: String::new(),
+        },
+        Section::Local,
+      ));
+      continue;
+    }
+    let Some((region, section)) = current.as_mut() else {
+      continue;
+    };
+    if line.starts_with("||||||| ") {
+      *section = Section::Base;
+    } else if line == "=======" {
+      *section = Section::Incoming;
+    } else if line.starts_with(">>>>>>> ") {
+      region.end_line = line_no;
+      if let Some((region, _)) = current.take() {
+        regions.push(region);
+      }
+    } else {
+      let target = match section {
+        Section::Local => &mut region.local,
+        Section::Base => &mut region.base,
+        Section::Incoming => &mut region.incoming,
+      };
+      target.push_str(line);
+      target.push('\n');
+    }
+  }
+  regions
+}
+
+/// Merge `diff` into the files listed in `dirty_paths`, leaving the rest of the patch untouched.
+///
+/// Nothing is written to disk; callers persist `merged_files` with [`write_merged_files`].
+pub(crate) fn three_way_merge_patch(
+  cwd: &Path,
+  diff: &str,
+  dirty_paths: &[String],
+) -> io::Result<WorkspaceMergeResult> {
+  let mut result = WorkspaceMergeResult::default();
+  for file in split_patch_by_file(diff) {
+    if !dirty_paths.contains(&file.path) {
+      result.remaining_patch.push_str(&file.chunk);
+      continue;
+    }
+
+    let conflict = |reason: &str| GitMergeConflict {
+      path: file.path.clone(),
+      reason: Some(reason.to_string()),
+      regions: Vec::new(),
+      merged: None,
+    };
+    if file.is_new {
+      result.conflicts.push(conflict("added by task but already exists locally"));
+      continue;
+    }
+    if file.is_deleted {
+      result.conflicts.push(conflict("deleted by task but modified locally"));
+      continue;
+    }
+
+    let local = match std::fs::read_to_string(cwd.join(&file.path)) {
+      Ok(contents) => contents,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => {
+        result.conflicts.push(conflict("modified by task but deleted locally"));
+        continue;
+      }
+      Err(err) => return Err(err),
+    };
+    let base = read_merge_base_contents(cwd, &file)?;
+    let incoming = match apply_file_patch_to(&base, &file) {
+      Ok(contents) => contents,
+      Err(err) => {
+        result.conflicts.push(conflict(&err.to_string()));
+        continue;
+      }
+    };
+
+    let (merged, conflicted) = merge_file_contents(&local, &base, &incoming)?;
+    if conflicted {
+      result.conflicts.push(GitMergeConflict {
+        path: file.path.clone(),
+        reason: None,
+        regions: parse_conflict_regions(&merged),
+        merged: Some(merged),
+      });
+    } else {
+      result.merged_files.push((file.path.clone(), merged));
+    }
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests_git_merge {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn git(repo: &Path, args: &[&str]) -> String {
+    let output = run_git(repo, args).unwrap();
+    assert!(
+      output.status.success(),
+      "{}",
+      String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+  }
+
+  /// A repo with `files` committed on `main`.
+  fn init_repo(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init", "-q", "-b", "main"]);
+    git(repo.path(), &["config", "user.email", "dev@example.com"]);
+    git(repo.path(), &["config", "user.name", "dev"]);
+    for (path, contents) in files {
+      std::fs::write(repo.path().join(path), contents).unwrap();
+    }
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "-q", "-m", "base"]);
+    repo
+  }
+
+  /// The diff of writing `files`, with the working tree reset to HEAD afterwards.
+  fn task_patch(repo: &Path, files: &[(&str, &str)]) -> String {
+    for (path, contents) in files {
+      std::fs::write(repo.join(path), contents).unwrap();
+    }
+    let patch = git(repo, &["diff"]);
+    git(repo, &["checkout", "--", "."]);
+    patch
+  }
+
+  const NOTES: &str = "one\ntwo\nthree\nfour\nfive\n";
+
+  const PATCH: &str = "diff --git a/src/lib.rs b/src/lib.rs\nindex 1234567..89abcde 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n-fn a() {}\n+fn a() { println!(\"a\"); }\n fn b() {}\ndiff --git a/NEW.md b/NEW.md\nnew file mode 100644\nindex 0000000..1111111\n--- /dev/null\n+++ b/NEW.md\n@@ -0,0 +1 @@\n+hello\n";
+
+  #[test]
+  fn splits_patch_into_file_chunks() {
+    let files = split_patch_by_file(PATCH);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "src/lib.rs");
+    assert_eq!(files[0].preimage_blob.as_deref(), Some("1234567"));
+    assert!(!files[0].is_new);
+    assert_eq!(files[1].path, "NEW.md");
+    assert_eq!(files[1].preimage_blob, None);
+    assert!(files[1].is_new);
+    assert_eq!(format!("{}{}", files[0].chunk, files[1].chunk), PATCH);
+  }
+
+  #[test]
+  fn parses_diff3_conflict_regions() {
+    let merged = "keep\n<<<<<<< local\nmine\n||||||| base\norig\n=======\ntheirs\n>>>>>>> incoming\ntail\n";
+    assert_eq!(
+      parse_conflict_regions(merged),
+      vec![GitConflictRegion {
+        start_line: 2,
+        end_line: 8,
+        local: "mine\n".to_string(),
+        base: "orig\n".to_string(),
+        incoming: "theirs\n".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn merges_patch_into_locally_edited_file() {
+    let repo = init_repo(&[("notes.txt", NOTES), ("other.txt", "x\n")]);
+    let patch = task_patch(
+      repo.path(),
+      &[
+        ("notes.txt", "ONE\ntwo\nthree\nfour\nfive\n"),
+        ("other.txt", "y\n"),
+      ],
+    );
+    std::fs::write(
+      repo.path().join("notes.txt"),
+      "one\ntwo\nthree\nfour\nFIVE\n",
+    )
+    .unwrap();
+
+    let dirty = locally_modified_paths(
+      repo.path(),
+      &["notes.txt".to_string(), "other.txt".to_string()],
+    )
+    .unwrap();
+    assert_eq!(dirty, vec!["notes.txt".to_string()]);
+    let merge = three_way_merge_patch(repo.path(), &patch, &dirty).unwrap();
+    assert_eq!(merge.conflicts, Vec::new());
+    assert_eq!(
+      merge.merged_files,
+      vec![(
+        "notes.txt".to_string(),
+        "ONE\ntwo\nthree\nfour\nFIVE\n".to_string()
+      )]
+    );
+    assert!(
+      merge
+        .remaining_patch
+        .starts_with("diff --git a/other.txt b/other.txt")
+    );
+    assert!(!merge.remaining_patch.contains("notes.txt"));
+  }
+
+  #[test]
+  fn reports_conflicting_local_edits() {
+    let repo = init_repo(&[("notes.txt", NOTES)]);
+    let patch = task_patch(
+      repo.path(),
+      &[("notes.txt", "ONE\ntwo\nthree\nfour\nfive\n")],
+    );
+    std::fs::write(
+      repo.path().join("notes.txt"),
+      "uno\ntwo\nthree\nfour\nfive\n",
+    )
+    .unwrap();
+
+    let merge = three_way_merge_patch(repo.path(), &patch, &["notes.txt".to_string()]).unwrap();
+    assert_eq!(merge.merged_files, Vec::new());
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].path, "notes.txt");
+    assert_eq!(merge.conflicts[0].reason, None);
+    assert_eq!(
+      merge.conflicts[0].regions,
+      vec![GitConflictRegion {
+        start_line: 1,
+        end_line: 7,
+        local: "uno\n".to_string(),
+        base: "one\n".to_string(),
+        incoming: "ONE\n".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn renamed_paths_count_as_modified_under_both_names() {
+    let repo = init_repo(&[("old.txt", "a\n"), ("kept.txt", "b\n")]);
+    git(repo.path(), &["mv", "old.txt", "new.txt"]);
+
+    let paths = ["kept.txt", "new.txt", "old.txt"].map(str::to_string);
+    assert_eq!(
+      locally_modified_paths(repo.path(), &paths).unwrap(),
+      vec!["new.txt".to_string(), "old.txt".to_string()]
+    );
+  }
+
+  #[test]
+  fn failed_merged_write_restores_earlier_files() {
+    let repo = init_repo(&[("notes.txt", NOTES)]);
+    let files = vec![
+      ("notes.txt".to_string(), "merged\n".to_string()),
+      ("missing/file.txt".to_string(), "merged\n".to_string()),
+    ];
+
+    assert!(write_merged_files(repo.path(), &files).is_err());
+    assert_eq!(
+      std::fs::read_to_string(repo.path().join("notes.txt")).unwrap(),
+      NOTES
+    );
+    let backup = write_merged_files(repo.path(), &files[..1]).unwrap();
+    assert_eq!(
+      std::fs::read_to_string(repo.path().join("notes.txt")).unwrap(),
+      "merged\n"
+    );
+    backup.restore().unwrap();
+    assert_eq!(
+      std::fs::read_to_string(repo.path().join("notes.txt")).unwrap(),
+      NOTES
+    );
+  }
+
+  #[test]
+  fn diffs_head_and_working_tree_against_merge_base() {
+    let repo = init_repo(&[("lib.rs", "fn a() {}\n")]);
+    let git = |args: &[&str]| git(repo.path(), args);
+    git(&["switch", "-q", "-c", "feature"]);
+    std::fs::write(repo.path().join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+    git(&["commit", "-q", "-am", "add b"]);
+    std::fs::write(
+      repo.path().join("lib.rs"),
+      "fn a() {}\nfn b() {}\nfn c() {}\n",
+    )
+    .unwrap();
+
+    let committed = git_diff_since_merge_base(repo.path(), "main", Some("feature")).unwrap();
+    assert!(committed.contains("+fn b() {}"));
+    assert!(!committed.contains("fn c()"));
+    let working = git_diff_since_merge_base(repo.path(), "main", None).unwrap();
+    assert!(working.contains("+fn b() {}") && working.contains("+fn c() {}"));
+    assert!(git_diff_since_merge_base(repo.path(), "missing-ref", None).is_err());
+
+    assert_eq!(
+      git_commit_subjects(repo.path(), "main", "feature").unwrap(),
+      vec!["add b".to_string()]
+    );
+    assert!(git_uncommitted_diff(repo.path(), true).unwrap().is_empty());
+    git(&["add", "lib.rs"]);
+    let staged = git_uncommitted_diff(repo.path(), true).unwrap();
+    assert!(staged.contains("+fn c() {}") && !staged.contains("+fn b() {}"));
+  }
+}
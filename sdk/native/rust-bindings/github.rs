@@ -0,0 +1,648 @@
+// ============================================================================
+// GitHub pull requests (fetchPrContext, postReviewComments)
+// ============================================================================
+//
+// Review bots need the same three things from GitHub: the pull request's
+// diff, the discussion so far and the CI state, then a way to publish what
+// the review found. fetchPrContext() gathers the first part in one call;
+// its `diff` feeds reviewDiff({ patchText }). postReviewComments() posts
+// ReviewFindings as one pull request review, with an inline comment per
+// finding. GitHub rejects a whole review when any comment points outside the
+// diff, so findings on lines the diff doesn't show are listed in the review
+// body instead. The token comes from `token`, then GITHUB_TOKEN, then
+// GH_TOKEN.
+
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_JSON: &str = "application/vnd.github+json";
+const GITHUB_PAGE_SIZE: usize = 100;
+/// Pages fetched per listing, so at most 1000 comments or checks of each kind.
+const GITHUB_MAX_PAGES: usize = 10;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct FetchPrContextRequest {
+  /// `owner/name`.
+  pub repo: String,
+  pub number: u32,
+  pub token: Option<String>,
+  /// API root for GitHub Enterprise Server, e.g. `https://github.example.com/api/v3`.
+  #[napi(js_name = "apiUrl")]
+  pub api_url: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GithubPrComment {
+  pub id: i64,
+  /// `issue` for conversation comments, `review` for inline comments on the diff.
+  pub kind: String,
+  pub author: String,
+  pub body: String,
+  /// File an inline comment is attached to.
+  pub path: Option<String>,
+  /// Line an inline comment is attached to; unset once the line is outdated.
+  pub line: Option<u32>,
+  #[napi(js_name = "createdAt")]
+  pub created_at: String,
+  pub url: String,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GithubCheck {
+  pub name: String,
+  /// `check_run` for GitHub Actions and apps, `status` for commit statuses.
+  pub source: String,
+  /// `queued`, `in_progress` or `completed`.
+  pub status: String,
+  /// Outcome of a completed check, e.g. `success`, `failure` or `skipped`.
+  pub conclusion: Option<String>,
+  pub url: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GithubCiStatus {
+  /// `failure` when any check failed, else `pending` while any is running, else `success`;
+  /// `none` without checks.
+  pub state: String,
+  pub checks: Vec<GithubCheck>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct GithubPrContext {
+  pub repo: String,
+  pub number: u32,
+  pub title: String,
+  pub body: String,
+  pub author: String,
+  /// `open` or `closed`.
+  pub state: String,
+  pub draft: bool,
+  pub url: String,
+  #[napi(js_name = "baseRef")]
+  pub base_ref: String,
+  #[napi(js_name = "baseSha")]
+  pub base_sha: String,
+  #[napi(js_name = "headRef")]
+  pub head_ref: String,
+  #[napi(js_name = "headSha")]
+  pub head_sha: String,
+  /// Unified diff of the pull request.
+  pub diff: String,
+  /// Conversation and inline comments, oldest first.
+  pub comments: Vec<GithubPrComment>,
+  /// Checks and statuses on the head commit.
+  pub ci: GithubCiStatus,
+}
+
+#[napi(object)]
+pub struct PostReviewCommentsRequest {
+  /// `owner/name`.
+  pub repo: String,
+  pub number: u32,
+  pub token: Option<String>,
+  #[napi(js_name = "apiUrl")]
+  pub api_url: Option<String>,
+  pub findings: ReviewFindings,
+  /// Checkout root that absolute finding paths are made relative to. Defaults to the process cwd.
+  pub root: Option<String>,
+  /// `COMMENT` (default), `APPROVE` or `REQUEST_CHANGES`.
+  pub event: Option<String>,
+  /// Review summary; defaults to the findings' overall explanation.
+  pub body: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostReviewCommentsResult {
+  #[napi(js_name = "reviewId")]
+  pub review_id: i64,
+  pub url: String,
+  /// Findings posted as inline comments.
+  pub posted: u32,
+  /// `path:line` of findings outside the diff, listed in the review body instead.
+  pub skipped: Vec<String>,
+}
+
+struct GithubClient {
+  client: reqwest::Client,
+  api_url: String,
+  token: Option<String>,
+}
+
+impl GithubClient {
+  fn new(token: Option<String>, api_url: Option<String>) -> napi::Result<Self> {
+    let client = reqwest::Client::builder()
+      .user_agent("codex-native")
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to build GitHub client: {e}")))?;
+    let token = token
+      .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+      .or_else(|| std::env::var("GH_TOKEN").ok())
+      .filter(|token| !token.trim().is_empty());
+    let api_url = api_url.unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string());
+    Ok(Self {
+      client,
+      api_url: api_url.trim_end_matches('/').to_string(),
+      token,
+    })
+  }
+
+  fn request(&self, method: reqwest::Method, path: &str, accept: &str) -> reqwest::RequestBuilder {
+    let request = self
+      .client
+      .request(method, format!("{}{path}", self.api_url))
+      .header("Accept", accept)
+      .header("X-GitHub-Api-Version", "2022-11-28");
+    match &self.token {
+      Some(token) => request.bearer_auth(token),
+      None => request,
+    }
+  }
+
+  async fn send(&self, request: reqwest::RequestBuilder, path: &str) -> napi::Result<String> {
+    let response = request
+      .send()
+      .await
+      .map_err(|e| napi::Error::from_reason(format!("GitHub request {path} failed: {e}")))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| {
+      napi::Error::from_reason(format!("Failed to read GitHub response for {path}: {e}"))
+    })?;
+    if !status.is_success() {
+      let message = serde_json::from_str::<JsonValue>(&text)
+        .ok()
+        .and_then(|body| body.get("message")?.as_str().map(str::to_string))
+        .unwrap_or(text);
+      return Err(napi::Error::from_reason(format!(
+        "GitHub request {path} failed ({status}): {message}"
+      )));
+    }
+    Ok(text)
+  }
+
+  async fn get_json(&self, path: &str) -> napi::Result<JsonValue> {
+    let text = self
+      .send(self.request(reqwest::Method::GET, path, GITHUB_JSON), path)
+      .await?;
+    serde_json::from_str(&text)
+      .map_err(|e| napi::Error::from_reason(format!("Invalid GitHub response for {path}: {e}")))
+  }
+
+  async fn get_diff(&self, path: &str) -> napi::Result<String> {
+    let request = self.request(reqwest::Method::GET, path, "application/vnd.github.diff");
+    self.send(request, path).await
+  }
+
+  /// Every item of a paginated listing; `key` names the array in object responses.
+  async fn get_pages(&self, path: &str, key: Option<&str>) -> napi::Result<Vec<JsonValue>> {
+    let mut items = Vec::new();
+    for page in 1..=GITHUB_MAX_PAGES {
+      let separator = if path.contains('?') { '&' } else { '?' };
+      let mut body = self
+        .get_json(&format!(
+          "{path}{separator}per_page={GITHUB_PAGE_SIZE}&page={page}"
+        ))
+        .await?;
+      let page_items = match key {
+        Some(key) => body.get_mut(key).map(JsonValue::take),
+        None => Some(body),
+      };
+      let Some(JsonValue::Array(page_items)) = page_items else {
+        break;
+      };
+      let full = page_items.len() == GITHUB_PAGE_SIZE;
+      items.extend(page_items);
+      if !full {
+        break;
+      }
+    }
+    Ok(items)
+  }
+
+  async fn post_json(&self, path: &str, body: &JsonValue) -> napi::Result<JsonValue> {
+    let text = self
+      .send(
+        self
+          .request(reqwest::Method::POST, path, GITHUB_JSON)
+          .json(body),
+        path,
+      )
+      .await?;
+    serde_json::from_str(&text)
+      .map_err(|e| napi::Error::from_reason(format!("Invalid GitHub response for {path}: {e}")))
+  }
+}
+
+fn validate_github_repo(repo: &str) -> napi::Result<&str> {
+  let repo = repo.trim();
+  match repo.split_once('/') {
+    Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok(repo),
+    _ => Err(napi::Error::from_reason(format!(
+      "repo must be `owner/name`, got `{repo}`"
+    ))),
+  }
+}
+
+fn json_str(value: &JsonValue, pointer: &str) -> String {
+  value
+    .pointer(pointer)
+    .and_then(JsonValue::as_str)
+    .unwrap_or_default()
+    .to_string()
+}
+
+fn github_comment(value: &JsonValue, kind: &str) -> GithubPrComment {
+  GithubPrComment {
+    id: value["id"].as_i64().unwrap_or_default(),
+    kind: kind.to_string(),
+    author: json_str(value, "/user/login"),
+    body: json_str(value, "/body"),
+    path: value["path"].as_str().map(str::to_string),
+    line: value["line"]
+      .as_u64()
+      .and_then(|line| u32::try_from(line).ok()),
+    created_at: json_str(value, "/created_at"),
+    url: json_str(value, "/html_url"),
+  }
+}
+
+fn github_check_run(value: &JsonValue) -> GithubCheck {
+  GithubCheck {
+    name: json_str(value, "/name"),
+    source: "check_run".to_string(),
+    status: json_str(value, "/status"),
+    conclusion: value["conclusion"].as_str().map(str::to_string),
+    url: value["html_url"].as_str().map(str::to_string),
+  }
+}
+
+fn github_commit_status(value: &JsonValue) -> GithubCheck {
+  let state = json_str(value, "/state");
+  let completed = state != "pending";
+  GithubCheck {
+    name: json_str(value, "/context"),
+    source: "status".to_string(),
+    status: if completed { "completed" } else { "queued" }.to_string(),
+    conclusion: completed.then_some(state),
+    url: value["target_url"].as_str().map(str::to_string),
+  }
+}
+
+fn github_ci_state(checks: &[GithubCheck]) -> &'static str {
+  let failed = checks.iter().any(|check| {
+    matches!(
+      check.conclusion.as_deref(),
+      Some("failure" | "error" | "timed_out" | "cancelled" | "action_required" | "startup_failure")
+    )
+  });
+  if checks.is_empty() {
+    "none"
+  } else if failed {
+    "failure"
+  } else if checks.iter().any(|check| check.status != "completed") {
+    "pending"
+  } else {
+    "success"
+  }
+}
+
+/// The diff, discussion and CI state of a pull request.
+#[napi]
+pub async fn fetch_pr_context(req: FetchPrContextRequest) -> napi::Result<GithubPrContext> {
+  let repo = validate_github_repo(&req.repo)?.to_string();
+  let github = GithubClient::new(req.token, req.api_url)?;
+  let pull_path = format!("/repos/{repo}/pulls/{}", req.number);
+  let pull = github.get_json(&pull_path).await?;
+  let head_sha = json_str(&pull, "/head/sha");
+
+  let issue_comments_path = format!("/repos/{repo}/issues/{}/comments", req.number);
+  let review_comments_path = format!("{pull_path}/comments");
+  let check_runs_path = format!("/repos/{repo}/commits/{head_sha}/check-runs");
+  let statuses_path = format!("/repos/{repo}/commits/{head_sha}/status");
+  let (diff, issue_comments, review_comments, check_runs, statuses) = futures::try_join!(
+    github.get_diff(&pull_path),
+    github.get_pages(&issue_comments_path, None),
+    github.get_pages(&review_comments_path, None),
+    github.get_pages(&check_runs_path, Some("check_runs")),
+    github.get_pages(&statuses_path, Some("statuses")),
+  )?;
+
+  let mut comments: Vec<GithubPrComment> = issue_comments
+    .iter()
+    .map(|comment| github_comment(comment, "issue"))
+    .chain(
+      review_comments
+        .iter()
+        .map(|comment| github_comment(comment, "review")),
+    )
+    .collect();
+  comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+  let checks: Vec<GithubCheck> = check_runs
+    .iter()
+    .map(github_check_run)
+    .chain(statuses.iter().map(github_commit_status))
+    .collect();
+
+  Ok(GithubPrContext {
+    number: req.number,
+    title: json_str(&pull, "/title"),
+    body: json_str(&pull, "/body"),
+    author: json_str(&pull, "/user/login"),
+    state: json_str(&pull, "/state"),
+    draft: pull["draft"].as_bool().unwrap_or(false),
+    url: json_str(&pull, "/html_url"),
+    base_ref: json_str(&pull, "/base/ref"),
+    base_sha: json_str(&pull, "/base/sha"),
+    head_ref: json_str(&pull, "/head/ref"),
+    head_sha,
+    diff,
+    comments,
+    ci: GithubCiStatus {
+      state: github_ci_state(&checks).to_string(),
+      checks,
+    },
+    repo,
+  })
+}
+
+/// Lines of each file's new side that the diff shows, so inline comments on them are accepted.
+fn diff_commentable_lines(diff: &str) -> HashMap<String, std::collections::HashSet<u32>> {
+  let mut files: HashMap<String, std::collections::HashSet<u32>> = HashMap::new();
+  let mut path: Option<String> = None;
+  let mut next_line = 0u32;
+  let mut in_hunk = false;
+  for line in diff.lines() {
+    if line.starts_with("diff --git ") {
+      in_hunk = false;
+      path = None;
+    } else if let Some(hunk) = line.strip_prefix("@@ ") {
+      in_hunk = true;
+      next_line = hunk
+        .split_whitespace()
+        .find_map(|range| range.strip_prefix('+'))
+        .and_then(|range| range.split(',').next()?.parse().ok())
+        .unwrap_or(0);
+    } else if !in_hunk {
+      if let Some(target) = line.strip_prefix("+++ ") {
+        path = target.strip_prefix("b/").map(str::to_string);
+      }
+    } else if let Some(path) = &path
+      && (line.starts_with('+') || line.starts_with(' '))
+    {
+      files.entry(path.clone()).or_default().insert(next_line);
+      next_line += 1;
+    }
+  }
+  files
+}
+
+/// `path` relative to `root`, with `/` separators.
+fn github_relative_path(path: &str, root: &Path) -> String {
+  let relative = Path::new(path)
+    .strip_prefix(root)
+    .unwrap_or(Path::new(path));
+  relative
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .filter(|component| component != "/" && component != ".")
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn github_comment_body(comment: &ReviewComment) -> String {
+  format!(
+    "**[P{}] {}**\n\n{}",
+    comment.priority, comment.title, comment.body
+  )
+}
+
+/// Inline review comments for the findings the diff shows, and `path:line` of the rest.
+fn github_review_comments(
+  findings: &ReviewFindings,
+  root: &Path,
+  diff: &str,
+) -> (Vec<JsonValue>, Vec<(String, ReviewComment)>) {
+  let commentable = diff_commentable_lines(diff);
+  let mut inline = Vec::new();
+  let mut skipped = Vec::new();
+  for file in &findings.files {
+    let path = github_relative_path(&file.path, root);
+    let lines = commentable.get(&path);
+    let shown = |line: i32| {
+      u32::try_from(line)
+        .ok()
+        .is_some_and(|line| lines.is_some_and(|lines| lines.contains(&line)))
+    };
+    for comment in &file.comments {
+      let (start, end) = (comment.start_line, comment.end_line.max(comment.start_line));
+      let body = github_comment_body(comment);
+      let placed = if start < end && (start..=end).all(shown) {
+        Some(json!({
+          "path": path,
+          "start_line": start,
+          "start_side": "RIGHT",
+          "line": end,
+          "side": "RIGHT",
+          "body": body,
+        }))
+      } else {
+        [end, start]
+          .into_iter()
+          .find(|line| shown(*line))
+          .map(|line| json!({ "path": path, "line": line, "side": "RIGHT", "body": body }))
+      };
+      match placed {
+        Some(placed) => inline.push(placed),
+        None => skipped.push((format!("{path}:{start}"), comment.clone())),
+      }
+    }
+  }
+  (inline, skipped)
+}
+
+fn github_review_body(summary: &str, skipped: &[(String, ReviewComment)]) -> String {
+  let mut body = summary.trim().to_string();
+  if !skipped.is_empty() {
+    if !body.is_empty() {
+      body.push_str("\n\n");
+    }
+    body.push_str("Findings outside the diff:\n");
+    for (location, comment) in skipped {
+      body.push_str(&format!(
+        "\n- `{location}` **[P{}] {}**: {}",
+        comment.priority,
+        comment.title,
+        comment.body.lines().next().unwrap_or_default()
+      ));
+    }
+  }
+  body
+}
+
+/// Post review findings to a pull request as one review with inline comments.
+#[napi]
+pub async fn post_review_comments(
+  req: PostReviewCommentsRequest,
+) -> napi::Result<PostReviewCommentsResult> {
+  let repo = validate_github_repo(&req.repo)?;
+  let event = req
+    .event
+    .as_deref()
+    .unwrap_or("COMMENT")
+    .trim()
+    .to_ascii_uppercase();
+  if !matches!(event.as_str(), "COMMENT" | "APPROVE" | "REQUEST_CHANGES") {
+    return Err(napi::Error::from_reason(format!(
+      "event must be COMMENT, APPROVE or REQUEST_CHANGES, got {event}"
+    )));
+  }
+  let github = GithubClient::new(req.token, req.api_url)?;
+  if github.token.is_none() {
+    return Err(napi::Error::from_reason(
+      "postReviewComments requires a token, GITHUB_TOKEN or GH_TOKEN",
+    ));
+  }
+  let root = req
+    .root
+    .map(PathBuf::from)
+    .map_or_else(std::env::current_dir, Ok)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve root: {e}")))?;
+
+  let pull_path = format!("/repos/{repo}/pulls/{}", req.number);
+  let (pull, diff) = futures::try_join!(github.get_json(&pull_path), github.get_diff(&pull_path))?;
+  let (comments, skipped) = github_review_comments(&req.findings, &root, &diff);
+  let summary = req
+    .body
+    .unwrap_or_else(|| req.findings.overall_explanation.clone());
+  let posted = comments.len() as u32;
+  let review = github
+    .post_json(
+      &format!("{pull_path}/reviews"),
+      &json!({
+        "commit_id": json_str(&pull, "/head/sha"),
+        "event": event,
+        "body": github_review_body(&summary, &skipped),
+        "comments": comments,
+      }),
+    )
+    .await?;
+  Ok(PostReviewCommentsResult {
+    review_id: review["id"].as_i64().unwrap_or_default(),
+    url: json_str(&review, "/html_url"),
+    posted,
+    skipped: skipped.into_iter().map(|(location, _)| location).collect(),
+  })
+}
+
+#[cfg(test)]
+mod tests_github {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn comment(title: &str, start_line: i32, end_line: i32) -> ReviewComment {
+    ReviewComment {
+      title: title.to_string(),
+      body: "Details.".to_string(),
+      severity: "high".to_string(),
+      priority: 1,
+      confidence: 0.8,
+      start_line,
+      end_line,
+      suggested_patch: None,
+    }
+  }
+
+  #[test]
+  fn places_findings_on_lines_the_diff_shows() {
+    let diff = concat!(
+      "diff --git a/src/lib.rs b/src/lib.rs\n",
+      "--- a/src/lib.rs\n",
+      "+++ b/src/lib.rs\n",
+      "@@ -10,3 +10,4 @@ fn main() {\n",
+      " let a = 1;\n",
+      "-let b = 2;\n",
+      "+let b = 3;\n",
+      "+++c;\n",
+      " a + b\n",
+    );
+    let lines = diff_commentable_lines(diff);
+    let mut shown: Vec<u32> = lines["src/lib.rs"].iter().copied().collect();
+    shown.sort_unstable();
+    assert_eq!(shown, vec![10, 11, 12, 13]);
+
+    let findings = ReviewFindings {
+      files: vec![ReviewFileComments {
+        path: "/repo/src/lib.rs".to_string(),
+        comments: vec![
+          comment("Range", 11, 12),
+          comment("Single", 13, 13),
+          comment("Outside", 40, 41),
+        ],
+      }],
+      overall_correctness: "patch is incorrect".to_string(),
+      overall_explanation: "One bug.".to_string(),
+      overall_confidence: 0.7,
+    };
+    let (inline, skipped) = github_review_comments(&findings, Path::new("/repo"), diff);
+    assert_eq!(inline.len(), 2);
+    assert_eq!(inline[0]["start_line"], 11);
+    assert_eq!(inline[0]["line"], 12);
+    assert_eq!(inline[0]["body"], "**[P1] Range**\n\nDetails.");
+    assert_eq!(inline[1]["line"], 13);
+    assert_eq!(inline[1].get("start_line"), None);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(
+      github_review_body("One bug.", &skipped),
+      "One bug.\n\nFindings outside the diff:\n\n- `src/lib.rs:40` **[P1] Outside**: Details."
+    );
+  }
+
+  #[test]
+  fn ci_state_prefers_failures_then_pending_checks() {
+    let check = |status: &str, conclusion: Option<&str>| GithubCheck {
+      name: "build".to_string(),
+      source: "check_run".to_string(),
+      status: status.to_string(),
+      conclusion: conclusion.map(str::to_string),
+      url: None,
+    };
+    assert_eq!(github_ci_state(&[]), "none");
+    assert_eq!(
+      github_ci_state(&[
+        check("completed", Some("success")),
+        check("completed", Some("skipped"))
+      ]),
+      "success"
+    );
+    assert_eq!(
+      github_ci_state(&[
+        check("completed", Some("success")),
+        check("in_progress", None)
+      ]),
+      "pending"
+    );
+    assert_eq!(
+      github_ci_state(&[
+        check("in_progress", None),
+        check("completed", Some("failure"))
+      ]),
+      "failure"
+    );
+    assert_eq!(
+      github_commit_status(&json!({"context": "ci/lint", "state": "pending"})),
+      GithubCheck {
+        name: "ci/lint".to_string(),
+        source: "status".to_string(),
+        status: "queued".to_string(),
+        conclusion: None,
+        url: None,
+      }
+    );
+    assert!(validate_github_repo("owner/name").is_ok());
+    assert!(validate_github_repo("owner").is_err());
+    assert!(validate_github_repo("owner/name/extra").is_err());
+  }
+}
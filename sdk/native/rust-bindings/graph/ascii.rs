@@ -0,0 +1,298 @@
+#[derive(Clone, Debug, Default)]
+struct AsciiGraphOptions {
+  /// Cut longer lines to this many columns, ending in an ellipsis.
+  width: Option<usize>,
+  /// ANSI colors for each agent's glyph and state.
+  color: bool,
+  /// Keep the last rows, behind a marker row counting the ones left out.
+  max_rows: Option<usize>,
+  /// Leave out agents that have not changed since then.
+  since: Option<DateTime<Utc>>,
+}
+
+/// One line per layout row; agent rows carry the agent's summary after the lanes.
+fn render_agent_graph_ascii(graph: &AgentGraph, options: &AsciiGraphOptions) -> Vec<String> {
+  let recent;
+  let graph = match options.since {
+    Some(since) => {
+      recent = agent_graph_since(graph, since);
+      &recent
+    }
+    None => graph,
+  };
+  let rows = layout_agent_graph(graph);
+  let lane_width = rows
+    .iter()
+    .map(|row| row.lanes.chars().count())
+    .max()
+    .unwrap_or(0);
+  let mut lines: Vec<String> = rows
+    .iter()
+    .map(|row| render_agent_graph_row(row, lane_width, options))
+    .collect();
+  if let Some(max_rows) = options.max_rows
+    && lines.len() > max_rows
+  {
+    if max_rows == 0 {
+      return Vec::new();
+    }
+    let hidden = lines.len() + 1 - max_rows;
+    lines.drain(..hidden);
+    let marker = format!("⋮ {hidden} earlier rows");
+    lines.insert(0, truncate_agent_graph_line(&marker, options.width));
+  }
+  lines
+}
+
+/// Agents changed at or after `since`; children of agents left out become roots.
+fn agent_graph_since(graph: &AgentGraph, since: DateTime<Utc>) -> AgentGraph {
+  let nodes = graph
+    .nodes
+    .iter()
+    .filter(|node| {
+      node
+        .updated_at
+        .or(node.started_at)
+        .is_some_and(|changed| changed >= since)
+    })
+    .cloned()
+    .collect();
+  AgentGraph {
+    nodes,
+    next_seq: graph.next_seq,
+  }
+}
+
+fn render_agent_graph_row(
+  row: &AgentGraphRow<'_>,
+  lane_width: usize,
+  options: &AsciiGraphOptions,
+) -> String {
+  let Some(node) = row.node else {
+    return truncate_agent_graph_line(row.lanes.trim_end(), options.width);
+  };
+  let lanes = &row.lanes;
+  let summary = agent_node_summary(node);
+  let line = truncate_agent_graph_line(&format!("{lanes:<lane_width$} {summary}"), options.width);
+  if !options.color {
+    return line;
+  }
+  // Color after truncating so escape codes never count toward the width.
+  let color = agent_state_ansi(node.state);
+  let split = line
+    .char_indices()
+    .nth(lane_width)
+    .map_or(line.len(), |(index, _)| index);
+  let (lanes, summary) = line.split_at(split);
+  let state = format!("[{}]", node.state.as_str());
+  format!(
+    "{}{}",
+    lanes.replacen('*', &format!("{color}*\x1b[0m"), 1),
+    summary.replacen(&state, &format!("{color}{state}\x1b[0m"), 1)
+  )
+}
+
+fn agent_state_ansi(state: AgentNodeState) -> &'static str {
+  match state {
+    AgentNodeState::Pending => "\x1b[2m",
+    AgentNodeState::Running => "\x1b[33m",
+    AgentNodeState::Completed => "\x1b[32m",
+    AgentNodeState::Failed => "\x1b[31m",
+  }
+}
+
+fn truncate_agent_graph_line(line: &str, width: Option<usize>) -> String {
+  match width {
+    Some(width) if line.chars().count() > width => {
+      let kept: String = line.chars().take(width.saturating_sub(1)).collect();
+      format!("{kept}…")
+    }
+    _ => line.to_string(),
+  }
+}
+
+#[napi(object)]
+pub struct AgentGraphAsciiOptions {
+  /// Terminal width; longer lines are cut with an ellipsis.
+  pub width: Option<u32>,
+  /// Color each agent's glyph and state with ANSI escapes. Default: false.
+  pub color: Option<bool>,
+  /// Show only the last rows, under a row counting the ones left out.
+  #[napi(js_name = "maxRows")]
+  pub max_rows: Option<u32>,
+  /// RFC 3339 timestamp; agents that have not changed since then are left out.
+  pub since: Option<String>,
+}
+
+impl AgentGraphAsciiOptions {
+  fn into_options(self) -> napi::Result<AsciiGraphOptions> {
+    Ok(AsciiGraphOptions {
+      width: self.width.map(|width| width as usize),
+      color: self.color.unwrap_or(false),
+      max_rows: self.max_rows.map(|rows| rows as usize),
+      since: self
+        .since
+        .as_deref()
+        .map(|since| parse_agent_graph_timestamp("since", since))
+        .transpose()?,
+    })
+  }
+}
+
+fn agent_node_summary(node: &AgentNode) -> String {
+  let mut summary = format!("{} [{}]", node.label, node.state.as_str());
+  match node.turns {
+    0 => {}
+    1 => summary.push_str(" (1 turn)"),
+    turns => summary.push_str(&format!(" ({turns} turns)")),
+  }
+  if let Some(activity) = node.activity.as_deref() {
+    summary.push_str("  ");
+    summary.push_str(activity);
+  }
+  summary
+}
+
+#[cfg(test)]
+mod tests_agent_graph_ascii {
+  use super::*;
+  use chrono::TimeZone;
+  use pretty_assertions::assert_eq;
+
+  fn node(id: &str, parent_id: Option<&str>, state: AgentNodeState) -> AgentNode {
+    AgentNode {
+      state,
+      ..AgentNode::new(id.to_string(), parent_id.map(str::to_string))
+    }
+  }
+
+  #[test]
+  fn children_fork_into_their_own_lanes() {
+    let mut graph = AgentGraph::default();
+    graph.upsert(AgentNode {
+      turns: 2,
+      activity: Some("shell: cargo test".to_string()),
+      ..node("root", None, AgentNodeState::Running)
+    });
+    graph.upsert(node("a", Some("root"), AgentNodeState::Completed));
+    graph.upsert(node("b", Some("root"), AgentNodeState::Failed));
+    graph.upsert(node("a1", Some("a"), AgentNodeState::Pending));
+    graph.upsert(node("orphan", Some("missing"), AgentNodeState::Pending));
+
+    assert_eq!(
+      render_agent_graph_ascii(&graph, &AsciiGraphOptions::default()),
+      vec![
+        "*     root [running] (2 turns)  shell: cargo test",
+        "├─┐",
+        "│ *   a [completed]",
+        "├─┼─┐",
+        "│ │ * b [failed]",
+        "│ └─┐",
+        "│   * a1 [pending]",
+        "│ * │ orphan [pending]",
+      ]
+    );
+  }
+
+  #[test]
+  fn parallel_siblings_hold_distinct_columns() {
+    let mut graph = AgentGraph::default();
+    graph.upsert(node("root", None, AgentNodeState::Completed));
+    for id in ["a", "b", "c"] {
+      graph.upsert(node(id, Some("root"), AgentNodeState::Running));
+    }
+    graph.upsert(node("b1", Some("b"), AgentNodeState::Pending));
+    graph.upsert(node("c1", Some("c"), AgentNodeState::Pending));
+
+    // root's lane ends at its last fork, so b1 reuses the free column on the left.
+    assert_eq!(
+      render_agent_graph_ascii(&graph, &AsciiGraphOptions::default()),
+      vec![
+        "*         root [completed]",
+        "├─┐",
+        "│ *       a [running]",
+        "├─┼─┐",
+        "│ │ *     b [running]",
+        "└─┼─┼─┐",
+        "  │ │ *   c [running]",
+        "┌─┼─┤ │",
+        "* │ │ │   b1 [pending]",
+        "│ │ │ ├─┐",
+        "│ │ │ │ * c1 [pending]",
+      ]
+    );
+  }
+
+  #[test]
+  fn merged_agents_rejoin_the_target_lane() {
+    let mut graph = AgentGraph::default();
+    graph.upsert(node("root", None, AgentNodeState::Running));
+    for id in ["a", "b", "c"] {
+      graph.upsert(node(id, Some("root"), AgentNodeState::Completed));
+    }
+    assert!(graph.merge("b", "root"));
+    assert!(graph.merge("a", "root"));
+    assert!(!graph.merge("c", "missing"));
+
+    assert_eq!(
+      render_agent_graph_ascii(&graph, &AsciiGraphOptions::default()),
+      vec![
+        "*       root [running]",
+        "├─┐",
+        "│ *     a [completed]",
+        "├─┼─┐",
+        "│ │ *   b [completed]",
+        "├─┼─┼─┐",
+        "│ │ │ * c [completed]",
+        "├─┼─┘",
+        "├─┘",
+      ]
+    );
+  }
+
+  #[test]
+  fn options_truncate_color_and_window_rows() {
+    let at = |minute: u32| Utc.with_ymd_and_hms(2026, 1, 1, 10, minute, 0).unwrap();
+    let mut graph = AgentGraph::default();
+    graph.upsert(AgentNode {
+      activity: Some("shell: cargo test --workspace".to_string()),
+      updated_at: Some(at(30)),
+      ..node("root", None, AgentNodeState::Running)
+    });
+    graph.upsert(AgentNode {
+      updated_at: Some(at(10)),
+      ..node("a", Some("root"), AgentNodeState::Completed)
+    });
+
+    let render = |options: AsciiGraphOptions| render_agent_graph_ascii(&graph, &options);
+    assert_eq!(
+      render(AsciiGraphOptions {
+        width: Some(24),
+        ..AsciiGraphOptions::default()
+      }),
+      vec!["*   root [running]  she…", "├─┐", "│ * a [completed]"]
+    );
+    assert_eq!(
+      render(AsciiGraphOptions {
+        width: Some(24),
+        color: true,
+        ..AsciiGraphOptions::default()
+      })[0],
+      "\x1b[33m*\x1b[0m   root \x1b[33m[running]\x1b[0m  she…"
+    );
+    assert_eq!(
+      render(AsciiGraphOptions {
+        max_rows: Some(2),
+        ..AsciiGraphOptions::default()
+      }),
+      vec!["⋮ 2 earlier rows", "│ * a [completed]"]
+    );
+    assert_eq!(
+      render(AsciiGraphOptions {
+        since: Some(at(20)),
+        ..AsciiGraphOptions::default()
+      }),
+      vec!["* root [running]  shell: cargo test --workspace"]
+    );
+  }
+}
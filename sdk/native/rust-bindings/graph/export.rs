@@ -0,0 +1,223 @@
+// Graph export
+// ----------------------------------------------------------------------------
+//
+// DOT and Mermaid for docs and PR descriptions, JSON for web UIs. Fork edges
+// point from parent to child; merge edges are dashed and point at the agent
+// the work was merged into.
+
+fn agent_state_fill(state: AgentNodeState) -> &'static str {
+  match state {
+    AgentNodeState::Pending => "#e5e7eb",
+    AgentNodeState::Running => "#bfdbfe",
+    AgentNodeState::Completed => "#bbf7d0",
+    AgentNodeState::Failed => "#fecaca",
+  }
+}
+
+/// Second label line shared by DOT and Mermaid: "running · 2 turns".
+fn agent_node_caption(node: &AgentNode) -> String {
+  let state = node.state.as_str();
+  match node.turns {
+    0 => state.to_string(),
+    1 => format!("{state} · 1 turn"),
+    turns => format!("{state} · {turns} turns"),
+  }
+}
+
+fn agent_graph_fork_edges(graph: &AgentGraph) -> impl Iterator<Item = (&AgentNode, &AgentNode)> {
+  graph.nodes.iter().filter_map(move |node| {
+    let parent = graph.node(node.parent_id.as_deref()?)?;
+    Some((parent, node))
+  })
+}
+
+fn agent_graph_merge_edges(graph: &AgentGraph) -> impl Iterator<Item = (&AgentNode, &AgentNode)> {
+  graph.nodes.iter().filter_map(move |node| {
+    let (into, _) = node.merged_into.as_ref()?;
+    Some((node, graph.node(into)?))
+  })
+}
+
+fn dot_quote(value: &str) -> String {
+  let escaped = value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n");
+  format!("\"{escaped}\"")
+}
+
+fn render_agent_graph_dot(graph: &AgentGraph) -> String {
+  let mut dot = String::from("digraph agents {\n  rankdir=TB;\n");
+  dot.push_str("  node [shape=box, style=\"rounded,filled\"];\n");
+  for node in &graph.nodes {
+    let id = dot_quote(&node.id);
+    let label = dot_quote(&format!("{}\n{}", node.label, agent_node_caption(node)));
+    let fill = agent_state_fill(node.state);
+    dot.push_str(&format!("  {id} [label={label}, fillcolor=\"{fill}\"];\n"));
+  }
+  for (parent, child) in agent_graph_fork_edges(graph) {
+    let (from, to) = (dot_quote(&parent.id), dot_quote(&child.id));
+    dot.push_str(&format!("  {from} -> {to};\n"));
+  }
+  for (node, into) in agent_graph_merge_edges(graph) {
+    let (from, to) = (dot_quote(&node.id), dot_quote(&into.id));
+    dot.push_str(&format!("  {from} -> {to} [style=dashed, label=\"merge\"];\n"));
+  }
+  dot.push('}');
+  dot
+}
+
+/// Mermaid ids can't hold arbitrary thread ids, so nodes are numbered in insertion order.
+fn render_agent_graph_mermaid(graph: &AgentGraph) -> String {
+  let ids: HashMap<&str, String> = graph
+    .nodes
+    .iter()
+    .enumerate()
+    .map(|(index, node)| (node.id.as_str(), format!("n{index}")))
+    .collect();
+  let node_label = |node: &AgentNode| {
+    format!("{}<br/>{}", node.label, agent_node_caption(node)).replace('"', "#quot;")
+  };
+
+  let mut mermaid = String::from("flowchart TD\n");
+  for node in &graph.nodes {
+    let id = &ids[node.id.as_str()];
+    let label = node_label(node);
+    mermaid.push_str(&format!("  {id}[\"{label}\"]\n"));
+  }
+  for (parent, child) in agent_graph_fork_edges(graph) {
+    let (from, to) = (&ids[parent.id.as_str()], &ids[child.id.as_str()]);
+    mermaid.push_str(&format!("  {from} --> {to}\n"));
+  }
+  for (node, into) in agent_graph_merge_edges(graph) {
+    let (from, to) = (&ids[node.id.as_str()], &ids[into.id.as_str()]);
+    mermaid.push_str(&format!("  {from} -.->|merge| {to}\n"));
+  }
+  for state in [
+    AgentNodeState::Pending,
+    AgentNodeState::Running,
+    AgentNodeState::Completed,
+    AgentNodeState::Failed,
+  ] {
+    let members: Vec<&str> = graph
+      .nodes
+      .iter()
+      .filter(|node| node.state == state)
+      .map(|node| ids[node.id.as_str()].as_str())
+      .collect();
+    if members.is_empty() {
+      continue;
+    }
+    let (name, fill) = (state.as_str(), agent_state_fill(state));
+    let members = members.join(",");
+    mermaid.push_str(&format!("  classDef {name} fill:{fill}\n  class {members} {name}\n"));
+  }
+  mermaid.trim_end().to_string()
+}
+
+fn agent_graph_json(graph: &AgentGraph) -> JsonValue {
+  let nodes: Vec<JsonValue> = graph
+    .nodes
+    .iter()
+    .map(|node| {
+      json!({
+        "id": node.id,
+        "parentId": node.parent_id,
+        "label": node.label,
+        "state": node.state.as_str(),
+        "activity": node.activity,
+        "turns": node.turns,
+        "startedAt": node.started_at.map(|started_at| started_at.to_rfc3339()),
+        "updatedAt": node.updated_at.map(|updated_at| updated_at.to_rfc3339()),
+        "mergedInto": node.merged_into.as_ref().map(|(into, _)| into),
+      })
+    })
+    .collect();
+  let forks = agent_graph_fork_edges(graph)
+    .map(|(parent, child)| json!({ "from": parent.id, "to": child.id, "kind": "fork" }));
+  let merges = agent_graph_merge_edges(graph)
+    .map(|(node, into)| json!({ "from": node.id, "to": into.id, "kind": "merge" }));
+  json!({
+    "nodes": nodes,
+    "edges": forks.chain(merges).collect::<Vec<_>>(),
+  })
+}
+
+#[cfg(test)]
+mod tests_agent_graph_export {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn sample_graph() -> AgentGraph {
+    let mut graph = AgentGraph::default();
+    graph.upsert(AgentNode {
+      label: "lead \"a\"".to_string(),
+      state: AgentNodeState::Running,
+      turns: 2,
+      ..AgentNode::new("root".to_string(), None)
+    });
+    graph.upsert(AgentNode {
+      state: AgentNodeState::Completed,
+      turns: 1,
+      ..AgentNode::new("0199-b".to_string(), Some("root".to_string()))
+    });
+    graph.upsert(AgentNode::new("0199-c".to_string(), Some("root".to_string())));
+    assert!(graph.merge("0199-b", "root"));
+    graph
+  }
+
+  #[test]
+  fn dot_and_mermaid_include_fork_and_merge_edges() {
+    let graph = sample_graph();
+    assert_eq!(
+      render_agent_graph_dot(&graph),
+      [
+        "digraph agents {",
+        "  rankdir=TB;",
+        "  node [shape=box, style=\"rounded,filled\"];",
+        "  \"root\" [label=\"lead \\\"a\\\"\\nrunning · 2 turns\", fillcolor=\"#bfdbfe\"];",
+        "  \"0199-b\" [label=\"0199\\ncompleted · 1 turn\", fillcolor=\"#bbf7d0\"];",
+        "  \"0199-c\" [label=\"0199\\npending\", fillcolor=\"#e5e7eb\"];",
+        "  \"root\" -> \"0199-b\";",
+        "  \"root\" -> \"0199-c\";",
+        "  \"0199-b\" -> \"root\" [style=dashed, label=\"merge\"];",
+        "}",
+      ]
+      .join("\n")
+    );
+    assert_eq!(
+      render_agent_graph_mermaid(&graph),
+      [
+        "flowchart TD",
+        "  n0[\"lead #quot;a#quot;<br/>running · 2 turns\"]",
+        "  n1[\"0199<br/>completed · 1 turn\"]",
+        "  n2[\"0199<br/>pending\"]",
+        "  n0 --> n1",
+        "  n0 --> n2",
+        "  n1 -.->|merge| n0",
+        "  classDef pending fill:#e5e7eb",
+        "  class n2 pending",
+        "  classDef running fill:#bfdbfe",
+        "  class n0 running",
+        "  classDef completed fill:#bbf7d0",
+        "  class n1 completed",
+      ]
+      .join("\n")
+    );
+  }
+
+  #[test]
+  fn json_lists_nodes_and_edges() {
+    let json = agent_graph_json(&sample_graph());
+    assert_eq!(json["nodes"][1]["mergedInto"], json!("root"));
+    assert_eq!(json["nodes"][2]["mergedInto"], JsonValue::Null);
+    assert_eq!(
+      json["edges"],
+      json!([
+        { "from": "root", "to": "0199-b", "kind": "fork" },
+        { "from": "root", "to": "0199-c", "kind": "fork" },
+        { "from": "0199-b", "to": "root", "kind": "merge" },
+      ])
+    );
+  }
+}
@@ -0,0 +1,254 @@
+// Rollout history
+// ----------------------------------------------------------------------------
+//
+// Rebuilds the fork tree of past sessions from rollouts under codex home. Each
+// rollout's first session_meta names the thread and where it came from: the
+// forked_from_id of a fork, or the parent of a spawned sub-agent. A fork's
+// rollout starts with a copy of its parent's history, so only the turns after
+// that shared prefix count toward the fork.
+
+#[derive(Debug, Default, PartialEq)]
+struct RolloutAgentSummary {
+  id: String,
+  parent_id: Option<String>,
+  started_at: Option<DateTime<Utc>>,
+  /// Timestamp of the last record.
+  updated_at: Option<DateTime<Utc>>,
+  /// Every user message in the file, including those copied from a fork's parent.
+  user_messages: Vec<String>,
+  last_agent_message: Option<String>,
+  /// The last turn was aborted or ended in an error.
+  last_turn_failed: bool,
+}
+
+fn summarize_agent_rollout(records: &[JsonValue]) -> Option<RolloutAgentSummary> {
+  use codex_protocol::protocol::SessionMeta;
+  use codex_protocol::protocol::SessionSource;
+  use codex_protocol::protocol::SubAgentSource;
+
+  let mut summary: Option<RolloutAgentSummary> = None;
+  for record in records {
+    let payload = record.get("payload");
+    if let Some(summary) = summary.as_mut()
+      && let Some(timestamp) = record
+        .get("timestamp")
+        .and_then(JsonValue::as_str)
+        .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+    {
+      summary.updated_at = Some(timestamp.with_timezone(&Utc));
+    }
+    match record.get("type").and_then(JsonValue::as_str) {
+      Some("session_meta") if summary.is_none() => {
+        let meta: SessionMeta = serde_json::from_value(payload?.clone()).ok()?;
+        let parent_id = match (&meta.forked_from_id, &meta.source) {
+          (Some(forked_from), _) => Some(forked_from.to_string()),
+          (None, SessionSource::SubAgent(SubAgentSource::ThreadSpawn { parent_thread_id, .. })) => {
+            Some(parent_thread_id.to_string())
+          }
+          (None, _) => None,
+        };
+        summary = Some(RolloutAgentSummary {
+          id: meta.id.to_string(),
+          parent_id,
+          started_at: DateTime::parse_from_rfc3339(&meta.timestamp)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+          ..RolloutAgentSummary::default()
+        });
+      }
+      Some("event_msg") => {
+        let Some(summary) = summary.as_mut() else {
+          continue;
+        };
+        let message = payload.and_then(|payload| payload.get("message")).and_then(JsonValue::as_str);
+        match payload.and_then(|payload| payload.get("type")).and_then(JsonValue::as_str) {
+          Some("user_message") => {
+            summary.user_messages.push(message.unwrap_or_default().to_string());
+            summary.last_turn_failed = false;
+          }
+          Some("agent_message") => {
+            summary.last_agent_message = message
+              .and_then(|text| text.lines().find(|line| !line.trim().is_empty()))
+              .map(|line| line.trim().to_string());
+          }
+          Some("turn_aborted" | "error") => summary.last_turn_failed = true,
+          _ => {}
+        }
+      }
+      _ => {}
+    }
+  }
+  summary
+}
+
+/// The graph of `summaries`, limited to `root_thread_id` and its descendants when given.
+fn agent_graph_from_rollouts(
+  mut summaries: Vec<RolloutAgentSummary>,
+  root_thread_id: Option<&str>,
+) -> napi::Result<AgentGraph> {
+  if let Some(root) = root_thread_id {
+    if !summaries.iter().any(|summary| summary.id == root) {
+      return Err(napi::Error::from_reason(format!("No rollout found for thread {root}")));
+    }
+    let mut included = std::collections::HashSet::from([root.to_string()]);
+    loop {
+      let before = included.len();
+      for summary in &summaries {
+        if summary
+          .parent_id
+          .as_ref()
+          .is_some_and(|parent| included.contains(parent))
+        {
+          included.insert(summary.id.clone());
+        }
+      }
+      if included.len() == before {
+        break;
+      }
+    }
+    summaries.retain(|summary| included.contains(&summary.id));
+  }
+  // Insertion order is the lane order, so add threads in the order they started.
+  summaries.sort_by_key(|summary| (summary.started_at.is_none(), summary.started_at));
+
+  let user_messages: HashMap<&str, &[String]> = summaries
+    .iter()
+    .map(|summary| (summary.id.as_str(), summary.user_messages.as_slice()))
+    .collect();
+  let mut graph = AgentGraph::default();
+  for summary in &summaries {
+    let inherited = summary
+      .parent_id
+      .as_deref()
+      .and_then(|parent| user_messages.get(parent))
+      .map(|parent| {
+        parent
+          .iter()
+          .zip(&summary.user_messages)
+          .take_while(|(a, b)| a == b)
+          .count()
+      })
+      .unwrap_or(0);
+    let turns = summary.user_messages.len().saturating_sub(inherited);
+    let state = match (turns, summary.last_turn_failed) {
+      (0, _) => AgentNodeState::Pending,
+      (_, true) => AgentNodeState::Failed,
+      (_, false) => AgentNodeState::Completed,
+    };
+    graph.upsert(AgentNode {
+      state,
+      activity: summary.last_agent_message.clone(),
+      turns: u32::try_from(turns).unwrap_or(u32::MAX),
+      started_at: summary.started_at,
+      updated_at: summary.updated_at.or(summary.started_at),
+      ..AgentNode::new(summary.id.clone(), summary.parent_id.clone())
+    });
+  }
+  Ok(graph)
+}
+
+fn load_agent_graph_from_codex_home(
+  codex_home: &Path,
+  root_thread_id: Option<&str>,
+) -> napi::Result<AgentGraph> {
+  let mut rollouts = Vec::new();
+  for dir in USAGE_ROLLOUT_DIRS {
+    collect_usage_rollouts(&codex_home.join(dir), &mut rollouts);
+  }
+  rollouts.sort();
+  let summaries = rollouts
+    .iter()
+    .filter_map(|path| load_cached_rollout(path).ok())
+    .filter_map(|parsed| summarize_agent_rollout(&parsed.records))
+    .collect();
+  agent_graph_from_rollouts(summaries, root_thread_id)
+}
+
+#[napi(object)]
+pub struct AgentGraphHistoryOptions {
+  /// Only this thread and the threads forked or spawned from it, transitively.
+  #[napi(js_name = "rootThreadId")]
+  pub root_thread_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests_agent_graph_history {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn meta(id: &str, timestamp: &str, extra: JsonValue) -> JsonValue {
+    let mut payload = json!({
+      "id": id,
+      "timestamp": timestamp,
+      "cwd": "/repo",
+      "originator": "codex_exec",
+      "cli_version": "0.0.0",
+      "source": "exec",
+      "model_provider": null,
+    });
+    if let (Some(payload), Some(extra)) = (payload.as_object_mut(), extra.as_object()) {
+      payload.extend(extra.clone());
+    }
+    json!({ "timestamp": timestamp, "type": "session_meta", "payload": payload })
+  }
+
+  fn event(kind: &str, message: &str) -> JsonValue {
+    json!({ "timestamp": "t", "type": "event_msg", "payload": { "type": kind, "message": message } })
+  }
+
+  #[test]
+  fn forks_and_spawned_agents_rebuild_the_tree() {
+    let root = "00000000-0000-0000-0000-000000000001";
+    let fork = "00000000-0000-0000-0000-000000000002";
+    let spawned = "00000000-0000-0000-0000-000000000003";
+    let unrelated = "00000000-0000-0000-0000-000000000004";
+    let rollouts = [
+      vec![
+        meta(root, "2026-01-01T10:00:00Z", json!({})),
+        event("user_message", "plan"),
+        event("agent_message", "\nSplitting the work"),
+        event("user_message", "go"),
+      ],
+      // Forked after the first turn: the copied history, then one turn of its own that is aborted.
+      vec![
+        meta(fork, "2026-01-01T10:05:00Z", json!({ "forked_from_id": root })),
+        meta(root, "2026-01-01T10:00:00Z", json!({})),
+        event("user_message", "plan"),
+        event("user_message", "review"),
+        event("turn_aborted", ""),
+      ],
+      vec![
+        meta(
+          spawned,
+          "2026-01-01T10:01:00Z",
+          json!({ "source": { "subagent": { "thread_spawn": { "parent_thread_id": root, "depth": 1 } } } }),
+        ),
+      ],
+      vec![meta(unrelated, "2026-01-01T09:00:00Z", json!({})), event("user_message", "hi")],
+    ];
+    let summaries = rollouts
+      .iter()
+      .filter_map(|records| summarize_agent_rollout(records))
+      .collect();
+
+    let graph = agent_graph_from_rollouts(summaries, Some(root)).unwrap();
+    let nodes: Vec<(&str, Option<&str>, AgentNodeState, u32)> = graph
+      .nodes
+      .iter()
+      .map(|node| (node.id.as_str(), node.parent_id.as_deref(), node.state, node.turns))
+      .collect();
+    assert_eq!(
+      nodes,
+      vec![
+        (root, None, AgentNodeState::Completed, 2),
+        (spawned, Some(root), AgentNodeState::Pending, 0),
+        (fork, Some(root), AgentNodeState::Failed, 1),
+      ]
+    );
+    assert_eq!(graph.nodes[0].activity.as_deref(), Some("Splitting the work"));
+    assert_eq!(
+      agent_graph_from_rollouts(Vec::new(), Some(root)).unwrap_err().reason,
+      format!("No rollout found for thread {root}")
+    );
+  }
+}
@@ -0,0 +1,282 @@
+// Lane layout
+// ----------------------------------------------------------------------------
+//
+// Follows git-graph's branch layout: every agent is a branch that owns one
+// column from the row where it forks off its parent until its last fork or
+// merge, or to the bottom of the graph while it is still pending or running.
+// Agents appear in the order they were added (parents always before their
+// children), so siblings that run in parallel hold distinct columns at the
+// same time. A new lane takes the lowest free column, which may sit left
+// of its parent once earlier lanes have ended. Fork and merge rows join two
+// columns (┌ ┐ └ ┘ ├ ┤) and cross any lane in between (┼).
+
+enum AgentGraphSlot<'a> {
+  Fork {
+    parent: &'a AgentNode,
+    child: &'a AgentNode,
+  },
+  Node(&'a AgentNode),
+  Merge {
+    node: &'a AgentNode,
+    into: &'a AgentNode,
+  },
+}
+
+/// One rendered line: the lane glyphs, and the agent it shows (fork and merge rows have none).
+struct AgentGraphRow<'a> {
+  lanes: String,
+  node: Option<&'a AgentNode>,
+}
+
+/// Nodes with every parent before its children, otherwise in insertion order.
+/// Nodes caught in a parent cycle come last and are laid out as roots.
+fn agent_graph_topological_order(graph: &AgentGraph) -> (Vec<&AgentNode>, Vec<&AgentNode>) {
+  let mut ordered: Vec<&AgentNode> = Vec::new();
+  let mut placed = std::collections::HashSet::new();
+  loop {
+    let before = ordered.len();
+    for node in &graph.nodes {
+      if placed.contains(node.id.as_str()) {
+        continue;
+      }
+      let ready = node
+        .parent_id
+        .as_deref()
+        .is_none_or(|parent| graph.node(parent).is_none() || placed.contains(parent));
+      if ready {
+        placed.insert(node.id.as_str());
+        ordered.push(node);
+      }
+    }
+    if ordered.len() == before {
+      break;
+    }
+  }
+  let cyclic = graph
+    .nodes
+    .iter()
+    .filter(|node| !placed.contains(node.id.as_str()))
+    .collect();
+  (ordered, cyclic)
+}
+
+fn agent_graph_slots(graph: &AgentGraph) -> Vec<AgentGraphSlot<'_>> {
+  let (ordered, cyclic) = agent_graph_topological_order(graph);
+  let mut base = Vec::new();
+  let mut node_slot: HashMap<&str, usize> = HashMap::new();
+  for &node in &ordered {
+    if let Some(parent) = node.parent_id.as_deref().and_then(|parent| graph.node(parent)) {
+      base.push(AgentGraphSlot::Fork { parent, child: node });
+    }
+    node_slot.insert(node.id.as_str(), base.len());
+    base.push(AgentGraphSlot::Node(node));
+  }
+  for node in cyclic {
+    node_slot.insert(node.id.as_str(), base.len());
+    base.push(AgentGraphSlot::Node(node));
+  }
+
+  // Last slot of each node's subtree, so a merge never cuts a lane that still has forks below.
+  let mut subtree_end = node_slot.clone();
+  for node in &ordered {
+    let end = node_slot[node.id.as_str()];
+    let mut ancestor = node.parent_id.as_deref();
+    // Bounded by the node count in case parent links form a cycle.
+    for _ in 0..ordered.len() {
+      let Some(entry) = ancestor.and_then(|id| subtree_end.get_mut(id)) else {
+        break;
+      };
+      *entry = (*entry).max(end);
+      ancestor = ancestor
+        .and_then(|id| graph.node(id))
+        .and_then(|parent| parent.parent_id.as_deref());
+    }
+  }
+
+  // A merge lands after every agent added before it, after the merged agent's
+  // subtree, and after its target appears.
+  let mut merges: Vec<(usize, u64, &AgentNode, &AgentNode)> = graph
+    .nodes
+    .iter()
+    .filter_map(|node| {
+      let (into_id, merge_seq) = node.merged_into.as_ref()?;
+      let into = graph.node(into_id).filter(|into| into.id != node.id)?;
+      let added_before = graph
+        .nodes
+        .iter()
+        .filter(|other| other.seq < *merge_seq)
+        .filter_map(|other| node_slot.get(other.id.as_str()).copied())
+        .max()
+        .unwrap_or(0);
+      let position = added_before
+        .max(subtree_end[node.id.as_str()])
+        .max(node_slot[into.id.as_str()]);
+      Some((position, *merge_seq, node, into))
+    })
+    .collect();
+  merges.sort_by_key(|(position, merge_seq, _, _)| (*position, *merge_seq));
+
+  let mut slots = Vec::with_capacity(base.len() + merges.len());
+  let mut pending = merges.into_iter().peekable();
+  for (index, slot) in base.into_iter().enumerate() {
+    slots.push(slot);
+    while let Some((_, _, node, into)) = pending.next_if(|(position, ..)| *position == index) {
+      slots.push(AgentGraphSlot::Merge { node, into });
+    }
+  }
+  slots
+}
+
+/// A row joining lane `from` to lane `to`, crossing the lanes in between.
+fn agent_graph_connector(
+  columns: &[Option<&str>],
+  from: usize,
+  to: usize,
+  from_glyph: char,
+  to_glyph: char,
+) -> String {
+  let (low, high) = (from.min(to), from.max(to));
+  let mut lanes = String::new();
+  for column in 0..columns.len() {
+    if column > 0 {
+      lanes.push(if column > low && column <= high { '─' } else { ' ' });
+    }
+    let glyph = if column == from {
+      from_glyph
+    } else if column == to {
+      to_glyph
+    } else if column > low && column < high {
+      if columns[column].is_some() { '┼' } else { '─' }
+    } else if columns[column].is_some() {
+      '│'
+    } else {
+      ' '
+    };
+    lanes.push(glyph);
+  }
+  lanes
+}
+
+fn agent_lane_column(columns: &[Option<&str>], id: &str) -> Option<usize> {
+  columns.iter().position(|owner| *owner == Some(id))
+}
+
+fn assign_agent_lane<'a>(columns: &mut Vec<Option<&'a str>>, id: &'a str) -> usize {
+  match columns.iter().position(Option::is_none) {
+    Some(free) => {
+      columns[free] = Some(id);
+      free
+    }
+    None => {
+      columns.push(Some(id));
+      columns.len() - 1
+    }
+  }
+}
+
+fn layout_agent_graph(graph: &AgentGraph) -> Vec<AgentGraphRow<'_>> {
+  let slots = agent_graph_slots(graph);
+  let mut last_use: HashMap<&str, usize> = HashMap::new();
+  for (index, slot) in slots.iter().enumerate() {
+    match slot {
+      AgentGraphSlot::Fork { parent, child } => {
+        last_use.insert(parent.id.as_str(), index);
+        last_use.insert(child.id.as_str(), index);
+      }
+      AgentGraphSlot::Node(node) => {
+        last_use.insert(node.id.as_str(), index);
+      }
+      AgentGraphSlot::Merge { node, into } => {
+        last_use.insert(node.id.as_str(), index);
+        last_use.insert(into.id.as_str(), index);
+      }
+    }
+  }
+  // Unfinished agents are still working: like a branch tip, their lane runs to the bottom.
+  for node in &graph.nodes {
+    if matches!(node.state, AgentNodeState::Pending | AgentNodeState::Running)
+      && node.merged_into.is_none()
+      && let Some(last) = last_use.get_mut(node.id.as_str())
+    {
+      *last = slots.len();
+    }
+  }
+  let continues = |id: &str, index: usize| last_use.get(id).is_some_and(|last| *last > index);
+
+  let mut columns: Vec<Option<&str>> = Vec::new();
+  let mut rows = Vec::new();
+  for (index, slot) in slots.iter().enumerate() {
+    match *slot {
+      AgentGraphSlot::Fork { parent, child } => {
+        let Some(from) = agent_lane_column(&columns, &parent.id) else {
+          continue;
+        };
+        let to = assign_agent_lane(&mut columns, child.id.as_str());
+        let right = to > from;
+        let from_glyph = match (continues(parent.id.as_str(), index), right) {
+          (true, true) => '├',
+          (true, false) => '┤',
+          (false, true) => '└',
+          (false, false) => '┘',
+        };
+        let to_glyph = if right { '┐' } else { '┌' };
+        rows.push(AgentGraphRow {
+          lanes: agent_graph_connector(&columns, from, to, from_glyph, to_glyph),
+          node: None,
+        });
+      }
+      AgentGraphSlot::Node(node) => {
+        let column = match agent_lane_column(&columns, &node.id) {
+          Some(column) => column,
+          None => assign_agent_lane(&mut columns, node.id.as_str()),
+        };
+        let mut lanes = String::new();
+        for (position, owner) in columns.iter().enumerate() {
+          if position > 0 {
+            lanes.push(' ');
+          }
+          lanes.push(match (position == column, owner.is_some()) {
+            (true, _) => '*',
+            (false, true) => '│',
+            (false, false) => ' ',
+          });
+        }
+        rows.push(AgentGraphRow {
+          lanes,
+          node: Some(node),
+        });
+      }
+      AgentGraphSlot::Merge { node, into } => {
+        let (Some(from), Some(to)) = (
+          agent_lane_column(&columns, &node.id),
+          agent_lane_column(&columns, &into.id),
+        ) else {
+          continue;
+        };
+        let right = to > from;
+        let from_glyph = match (continues(node.id.as_str(), index), right) {
+          (true, true) => '├',
+          (true, false) => '┤',
+          (false, true) => '└',
+          (false, false) => '┘',
+        };
+        let to_glyph = match (continues(into.id.as_str(), index), right) {
+          (true, true) => '┤',
+          (true, false) => '├',
+          (false, true) => '┘',
+          (false, false) => '└',
+        };
+        rows.push(AgentGraphRow {
+          lanes: agent_graph_connector(&columns, from, to, from_glyph, to_glyph),
+          node: None,
+        });
+      }
+    }
+    for owner in &mut columns {
+      if owner.is_some_and(|id| !continues(id, index)) {
+        *owner = None;
+      }
+    }
+  }
+  rows
+}
@@ -0,0 +1,25 @@
+// Section 14: Agent Graph
+// ============================================================================
+//
+// Orchestrators that fork threads or spawn sub-agents get a git-style view of
+// the topology: one node per thread, with an edge from each child to the
+// thread it came from. Graphs subscribe to the native event stream, so every
+// run in this process updates the state and latest activity of the threads
+// a graph contains, and forks or spawned agents of those threads join the
+// graph on their own.
+//
+// Key exports:
+//   - AgentGraphRenderer: Build a graph by hand or from live thread events
+//   - AgentGraphRenderer.merge_agent(): Draw a sub-agent merging back into a lane
+//   - AgentGraphRenderer.from_codex_home(): Fork tree of past sessions
+//   - AgentGraphRenderer.render_ascii(): git-graph style lanes for the terminal
+//   - AgentGraphRenderer.render_dot() / render_mermaid() / to_json(): Exports
+//   - TuiApp.mount_agent_graph(): Live graph widget for TuiApp dashboards
+//
+// ============================================================================
+
+include!("model.rs");
+include!("layout.rs");
+include!("ascii.rs");
+include!("export.rs");
+include!("history.rs");
@@ -0,0 +1,506 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AgentNodeState {
+  Pending,
+  Running,
+  Completed,
+  Failed,
+}
+
+impl AgentNodeState {
+  fn as_str(self) -> &'static str {
+    match self {
+      AgentNodeState::Pending => "pending",
+      AgentNodeState::Running => "running",
+      AgentNodeState::Completed => "completed",
+      AgentNodeState::Failed => "failed",
+    }
+  }
+
+  fn parse(value: &str) -> napi::Result<Self> {
+    match value {
+      "pending" => Ok(AgentNodeState::Pending),
+      "running" => Ok(AgentNodeState::Running),
+      "completed" => Ok(AgentNodeState::Completed),
+      "failed" => Ok(AgentNodeState::Failed),
+      other => Err(napi::Error::from_reason(format!(
+        "Unknown agent state \"{other}\" (expected pending, running, completed or failed)"
+      ))),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct AgentNode {
+  id: String,
+  parent_id: Option<String>,
+  label: String,
+  state: AgentNodeState,
+  activity: Option<String>,
+  turns: u32,
+  started_at: Option<DateTime<Utc>>,
+  /// Last time an event, update or merge touched the node.
+  updated_at: Option<DateTime<Utc>>,
+  /// Position in the order agents joined the graph; assigned by `AgentGraph`.
+  seq: u64,
+  /// The agent this one's work was merged back into, with the sequence number of the merge.
+  merged_into: Option<(String, u64)>,
+}
+
+impl AgentNode {
+  fn new(id: String, parent_id: Option<String>) -> Self {
+    // Thread ids are UUIDs; their first group is distinctive enough for a label.
+    let label = id.split('-').next().unwrap_or(&id).to_string();
+    Self {
+      id,
+      parent_id,
+      label,
+      state: AgentNodeState::Pending,
+      activity: None,
+      turns: 0,
+      started_at: None,
+      updated_at: None,
+      seq: 0,
+      merged_into: None,
+    }
+  }
+}
+
+/// Nodes in insertion order; children render after their parent.
+#[derive(Debug, Default)]
+struct AgentGraph {
+  nodes: Vec<AgentNode>,
+  next_seq: u64,
+}
+
+impl AgentGraph {
+  fn node(&self, id: &str) -> Option<&AgentNode> {
+    self.nodes.iter().find(|node| node.id == id)
+  }
+
+  fn node_mut(&mut self, id: &str) -> Option<&mut AgentNode> {
+    self.nodes.iter_mut().find(|node| node.id == id)
+  }
+
+  fn take_seq(&mut self) -> u64 {
+    let seq = self.next_seq;
+    self.next_seq += 1;
+    seq
+  }
+
+  /// Insert `node`, or replace the node with the same id in place, keeping its position.
+  fn upsert(&mut self, mut node: AgentNode) {
+    match self.nodes.iter().position(|existing| existing.id == node.id) {
+      Some(index) => {
+        node.seq = self.nodes[index].seq;
+        self.nodes[index] = node;
+      }
+      None => {
+        node.seq = self.take_seq();
+        self.nodes.push(node);
+      }
+    }
+  }
+
+  /// Add a child seen live, e.g. a fork made by a native run.
+  fn add_child(&mut self, parent_id: &str, child_id: &str) {
+    if self.node(child_id).is_none() {
+      let now = Utc::now();
+      self.upsert(AgentNode {
+        started_at: Some(now),
+        updated_at: Some(now),
+        ..AgentNode::new(child_id.to_string(), Some(parent_id.to_string()))
+      });
+    }
+  }
+
+  /// Record that `id`'s work was merged back into `into_id`. Returns false if either is missing.
+  fn merge(&mut self, id: &str, into_id: &str) -> bool {
+    if id == into_id || self.node(into_id).is_none() {
+      return false;
+    }
+    let seq = self.next_seq;
+    let Some(node) = self.node_mut(id) else {
+      return false;
+    };
+    node.merged_into = Some((into_id.to_string(), seq));
+    node.updated_at = Some(Utc::now());
+    self.next_seq += 1;
+    true
+  }
+
+  /// Top-level nodes: no parent, or a parent that is not in the graph.
+  fn roots(&self) -> impl Iterator<Item = &AgentNode> {
+    self.nodes.iter().filter(|node| {
+      node
+        .parent_id
+        .as_deref()
+        .is_none_or(|parent| self.node(parent).is_none())
+    })
+  }
+
+  fn children<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a AgentNode> {
+    self
+      .nodes
+      .iter()
+      .filter(move |node| node.parent_id.as_deref() == Some(id))
+  }
+
+  /// Apply one event from `thread_id`'s stream; threads not in the graph are ignored.
+  fn observe(&mut self, thread_id: &str, event: &ExecThreadEvent) {
+    use codex_exec::exec_events::CollabTool;
+    use codex_exec::exec_events::ThreadItemDetails;
+
+    let mut spawned = Vec::new();
+    let Some(node) = self.node_mut(thread_id) else {
+      return;
+    };
+    node.updated_at = Some(Utc::now());
+    match event {
+      ExecThreadEvent::ThreadStarted(_) | ExecThreadEvent::TurnStarted(_) => {
+        node.state = AgentNodeState::Running;
+      }
+      ExecThreadEvent::TurnCompleted(_) => {
+        node.state = AgentNodeState::Completed;
+        node.turns += 1;
+      }
+      ExecThreadEvent::TurnFailed(failed) => {
+        node.state = AgentNodeState::Failed;
+        node.activity = Some(failed.error.message.clone());
+      }
+      ExecThreadEvent::Error(error) => {
+        node.state = AgentNodeState::Failed;
+        node.activity = Some(error.message.clone());
+      }
+      ExecThreadEvent::ItemStarted(started) => {
+        if let (_, Some(tool)) = item_transition(&started.item) {
+          node.activity = Some(tool);
+        }
+      }
+      ExecThreadEvent::ItemUpdated(updated) => {
+        if let (_, Some(tool)) = item_transition(&updated.item) {
+          node.activity = Some(tool);
+        }
+      }
+      ExecThreadEvent::ItemCompleted(completed) => match &completed.item.details {
+        ThreadItemDetails::AgentMessage(message) => {
+          node.activity = message
+            .text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string());
+        }
+        ThreadItemDetails::CollabToolCall(call) if call.tool == CollabTool::SpawnAgent => {
+          spawned.clone_from(&call.receiver_thread_ids);
+        }
+        ThreadItemDetails::Reasoning(_)
+        | ThreadItemDetails::CommandExecution(_)
+        | ThreadItemDetails::FileChange(_)
+        | ThreadItemDetails::McpToolCall(_)
+        | ThreadItemDetails::CollabToolCall(_)
+        | ThreadItemDetails::WebSearch(_)
+        | ThreadItemDetails::TodoList(_)
+        | ThreadItemDetails::Error(_) => {}
+      },
+      ExecThreadEvent::ExitedReviewMode(_)
+      | ExecThreadEvent::BackgroundEvent(_)
+      | ExecThreadEvent::ToolOutputChunk(_)
+      | ExecThreadEvent::BackgroundTaskStarted(_)
+      | ExecThreadEvent::BackgroundTaskCompleted(_)
+      | ExecThreadEvent::PlanUpdated(_)
+      | ExecThreadEvent::ThreadStatus(_)
+      | ExecThreadEvent::TestResults(_)
+      | ExecThreadEvent::VerificationFailed(_)
+      | ExecThreadEvent::FilesTouched(_)
+      | ExecThreadEvent::CommandStarted(_)
+      | ExecThreadEvent::CommandCompleted(_)
+      | ExecThreadEvent::Raw(_) => {}
+    }
+    for child in spawned {
+      self.add_child(thread_id, &child);
+    }
+  }
+}
+
+/// Live graphs held by `AgentGraphRenderer`s; dropped renderers leave dead entries behind.
+fn agent_graph_subscribers() -> &'static Mutex<Vec<std::sync::Weak<Mutex<AgentGraph>>>> {
+  static SUBSCRIBERS: OnceLock<Mutex<Vec<std::sync::Weak<Mutex<AgentGraph>>>>> = OnceLock::new();
+  SUBSCRIBERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn for_each_agent_graph(mut apply: impl FnMut(&mut AgentGraph)) {
+  let Ok(mut subscribers) = agent_graph_subscribers().lock() else {
+    return;
+  };
+  subscribers.retain(|graph| graph.strong_count() > 0);
+  for graph in subscribers.iter().filter_map(std::sync::Weak::upgrade) {
+    if let Ok(mut graph) = graph.lock() {
+      apply(&mut graph);
+    }
+  }
+}
+
+/// Feed a thread event from a native run to every graph containing the thread.
+fn notify_agent_graphs(thread_id: &str, event: &ExecThreadEvent) {
+  for_each_agent_graph(|graph| graph.observe(thread_id, event));
+}
+
+/// Add a fork to every graph containing its parent.
+fn record_agent_graph_fork(parent_id: &str, child_id: &str) {
+  for_each_agent_graph(|graph| {
+    if graph.node(parent_id).is_some() {
+      graph.add_child(parent_id, child_id);
+    }
+  });
+}
+
+fn parse_agent_graph_timestamp(field: &str, value: &str) -> napi::Result<DateTime<Utc>> {
+  DateTime::parse_from_rfc3339(value)
+    .map(|timestamp| timestamp.with_timezone(&Utc))
+    .map_err(|e| napi::Error::from_reason(format!("Invalid {field} \"{value}\": {e}")))
+}
+
+#[napi(object)]
+pub struct AgentGraphNode {
+  /// Thread id; events from native runs of this thread update the node.
+  pub id: String,
+  #[napi(js_name = "parentId")]
+  pub parent_id: Option<String>,
+  /// Default: the first group of the id.
+  pub label: Option<String>,
+  #[napi(ts_type = "\"pending\" | \"running\" | \"completed\" | \"failed\"")]
+  pub state: Option<String>,
+  pub activity: Option<String>,
+  pub turns: Option<u32>,
+  /// RFC 3339 timestamp of the thread's first turn.
+  #[napi(js_name = "startedAt")]
+  pub started_at: Option<String>,
+  /// RFC 3339 timestamp of the last change; `renderAscii({ since })` filters on it. Default: now.
+  #[napi(js_name = "updatedAt")]
+  pub updated_at: Option<String>,
+  /// Agent this one's work was merged back into; drawn as a merge line into that lane.
+  #[napi(js_name = "mergedInto")]
+  pub merged_into: Option<String>,
+}
+
+#[napi(object)]
+pub struct AgentGraphUpdate {
+  pub label: Option<String>,
+  #[napi(ts_type = "\"pending\" | \"running\" | \"completed\" | \"failed\"")]
+  pub state: Option<String>,
+  pub activity: Option<String>,
+}
+
+fn agent_graph_node_snapshot(node: &AgentNode) -> AgentGraphNode {
+  AgentGraphNode {
+    id: node.id.clone(),
+    parent_id: node.parent_id.clone(),
+    label: Some(node.label.clone()),
+    state: Some(node.state.as_str().to_string()),
+    activity: node.activity.clone(),
+    turns: Some(node.turns),
+    started_at: node.started_at.map(|started_at| started_at.to_rfc3339()),
+    updated_at: node.updated_at.map(|updated_at| updated_at.to_rfc3339()),
+    merged_into: node.merged_into.as_ref().map(|(into, _)| into.clone()),
+  }
+}
+
+#[napi]
+pub struct AgentGraphRenderer {
+  graph: Arc<Mutex<AgentGraph>>,
+}
+
+#[napi]
+impl AgentGraphRenderer {
+  #[napi(constructor)]
+  pub fn new() -> napi::Result<Self> {
+    Self::subscribed(AgentGraph::default())
+  }
+
+  /// The fork tree of past sessions, rebuilt from the rollouts under `codexHomePath`.
+  /// Threads in it keep updating from native runs like any other graph.
+  #[napi(factory)]
+  pub fn from_codex_home(
+    codex_home_path: String,
+    options: Option<AgentGraphHistoryOptions>,
+  ) -> napi::Result<Self> {
+    let root_thread_id = options.and_then(|options| options.root_thread_id);
+    Self::subscribed(load_agent_graph_from_codex_home(
+      Path::new(&codex_home_path),
+      root_thread_id.as_deref(),
+    )?)
+  }
+
+  fn subscribed(graph: AgentGraph) -> napi::Result<Self> {
+    let graph = Arc::new(Mutex::new(graph));
+    agent_graph_subscribers()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("agent graph mutex poisoned: {e}")))?
+      .push(Arc::downgrade(&graph));
+    Ok(Self { graph })
+  }
+
+  fn lock_graph(&self) -> napi::Result<std::sync::MutexGuard<'_, AgentGraph>> {
+    self
+      .graph
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("agent graph mutex poisoned: {e}")))
+  }
+
+  /// Add a thread to the graph, or replace the node with the same id.
+  #[napi]
+  pub fn add_agent(&self, agent: AgentGraphNode) -> napi::Result<()> {
+    let mut node = AgentNode::new(agent.id, agent.parent_id);
+    if let Some(label) = agent.label {
+      node.label = label;
+    }
+    if let Some(state) = agent.state.as_deref() {
+      node.state = AgentNodeState::parse(state)?;
+    }
+    node.activity = agent.activity;
+    node.turns = agent.turns.unwrap_or(0);
+    node.started_at = agent
+      .started_at
+      .as_deref()
+      .map(|started_at| parse_agent_graph_timestamp("startedAt", started_at))
+      .transpose()?;
+    node.updated_at = match agent.updated_at.as_deref() {
+      Some(updated_at) => Some(parse_agent_graph_timestamp("updatedAt", updated_at)?),
+      None => Some(Utc::now()),
+    };
+    let id = node.id.clone();
+    let mut graph = self.lock_graph()?;
+    graph.upsert(node);
+    if let Some(into) = agent.merged_into.as_deref()
+      && !graph.merge(&id, into)
+    {
+      return Err(napi::Error::from_reason(format!("Unknown agent: {into}")));
+    }
+    Ok(())
+  }
+
+  #[napi]
+  pub fn update_agent(&self, id: String, update: AgentGraphUpdate) -> napi::Result<()> {
+    let state = update.state.as_deref().map(AgentNodeState::parse).transpose()?;
+    let mut graph = self.lock_graph()?;
+    let node = graph
+      .node_mut(&id)
+      .ok_or_else(|| napi::Error::from_reason(format!("Unknown agent: {id}")))?;
+    if let Some(label) = update.label {
+      node.label = label;
+    }
+    if let Some(state) = state {
+      node.state = state;
+    }
+    if update.activity.is_some() {
+      node.activity = update.activity;
+    }
+    node.updated_at = Some(Utc::now());
+    Ok(())
+  }
+
+  /// Draw `id`'s lane merging back into `intoId`'s lane, e.g. once a sub-agent's result is collected.
+  #[napi]
+  pub fn merge_agent(&self, id: String, into_id: String) -> napi::Result<()> {
+    let mut graph = self.lock_graph()?;
+    if graph.node(&id).is_none() {
+      return Err(napi::Error::from_reason(format!("Unknown agent: {id}")));
+    }
+    if !graph.merge(&id, &into_id) {
+      return Err(napi::Error::from_reason(format!("Unknown agent: {into_id}")));
+    }
+    Ok(())
+  }
+
+  /// Remove a thread; its children become roots.
+  #[napi]
+  pub fn remove_agent(&self, id: String) -> napi::Result<()> {
+    self.lock_graph()?.nodes.retain(|node| node.id != id);
+    Ok(())
+  }
+
+  #[napi(getter)]
+  pub fn agents(&self) -> napi::Result<Vec<AgentGraphNode>> {
+    Ok(self.lock_graph()?.nodes.iter().map(agent_graph_node_snapshot).collect())
+  }
+
+  #[napi]
+  pub fn render_ascii(&self, options: Option<AgentGraphAsciiOptions>) -> napi::Result<String> {
+    let options = match options {
+      Some(options) => options.into_options()?,
+      None => AsciiGraphOptions::default(),
+    };
+    Ok(render_agent_graph_ascii(&self.lock_graph()?, &options).join("\n"))
+  }
+
+  /// Graphviz source; fork edges are solid, merge edges dashed.
+  #[napi]
+  pub fn render_dot(&self) -> napi::Result<String> {
+    Ok(render_agent_graph_dot(&self.lock_graph()?))
+  }
+
+  /// Mermaid `flowchart`, e.g. for a PR description.
+  #[napi]
+  pub fn render_mermaid(&self) -> napi::Result<String> {
+    Ok(render_agent_graph_mermaid(&self.lock_graph()?))
+  }
+
+  /// `{ nodes, edges }` with `fork` and `merge` edges, for web UIs.
+  #[napi]
+  pub fn to_json(&self) -> napi::Result<JsonValue> {
+    Ok(agent_graph_json(&self.lock_graph()?))
+  }
+}
+
+#[cfg(test)]
+mod tests_agent_graph_model {
+  use super::*;
+  use codex_exec::exec_events::AgentMessageItem;
+  use codex_exec::exec_events::ItemCompletedEvent;
+  use codex_exec::exec_events::ThreadItem;
+  use codex_exec::exec_events::ThreadItemDetails;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn thread_events_update_subscribed_graphs() {
+    let renderer = AgentGraphRenderer::new().unwrap();
+    renderer
+      .add_agent(AgentGraphNode {
+        id: "graph-model-root".to_string(),
+        parent_id: None,
+        label: Some("root".to_string()),
+        state: None,
+        activity: None,
+        turns: None,
+        started_at: None,
+        updated_at: None,
+        merged_into: None,
+      })
+      .unwrap();
+
+    notify_agent_graphs(
+      "graph-model-root",
+      &ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent::default()),
+    );
+    notify_agent_graphs(
+      "graph-model-root",
+      &ExecThreadEvent::ItemCompleted(ItemCompletedEvent {
+        item: ThreadItem {
+          id: "item_0".to_string(),
+          details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+            text: "\nForking a reviewer\nmore".to_string(),
+          }),
+        },
+      }),
+    );
+    record_agent_graph_fork("graph-model-root", "graph-model-fork");
+    record_agent_graph_fork("graph-model-elsewhere", "graph-model-ignored");
+
+    let agents = renderer.agents().unwrap();
+    assert_eq!(agents.len(), 2);
+    assert_eq!(agents[0].state.as_deref(), Some("running"));
+    assert_eq!(agents[0].activity.as_deref(), Some("Forking a reviewer"));
+    assert_eq!(agents[1].id, "graph-model-fork");
+    assert_eq!(agents[1].parent_id.as_deref(), Some("graph-model-root"));
+    assert_eq!(agents[1].state.as_deref(), Some("pending"));
+  }
+}
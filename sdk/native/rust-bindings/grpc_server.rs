@@ -0,0 +1,1068 @@
+// ============================================================================
+// gRPC server (startGrpcServer / stopGrpcServer), behind the `grpc` feature
+// ============================================================================
+//
+// Serves the `codex_native.v1.CodexNative` service from proto/codex_native.proto
+// on 127.0.0.1 so Go, Python and other non-Node services can drive the native
+// runtime. The RPCs mirror runThread, runThreadStream, fork and
+// listConversations. Requests and events are typed messages that mirror the
+// SDK's RunRequest/ForkRequest and the exec event types field for field; the
+// conversions below match exhaustively, so a new event kind or item type fails
+// to compile until the proto covers it. A client that hangs up stops its run.
+// Every call must send `authorization: Bearer <token>`.
+
+mod grpc_proto {
+  tonic::include_proto!("codex_native.v1");
+}
+
+type GrpcEventStream =
+  Pin<Box<dyn futures::Stream<Item = Result<grpc_proto::ThreadEvent, tonic::Status>> + Send>>;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct GrpcServerOptions {
+  /// Loopback port to listen on; any free port when unset.
+  pub port: Option<u32>,
+  /// Token callers must send as `authorization: Bearer <token>`. Generated when unset.
+  #[napi(js_name = "authToken")]
+  pub auth_token: Option<String>,
+}
+
+#[napi(object)]
+pub struct GrpcServerInfo {
+  pub id: String,
+  /// `127.0.0.1:<port>`, ready for a gRPC channel.
+  pub address: String,
+  pub port: u32,
+  #[napi(js_name = "authToken")]
+  pub auth_token: String,
+}
+
+fn grpc_servers() -> &'static Mutex<HashMap<String, CancellationToken>> {
+  static SERVERS: OnceLock<Mutex<HashMap<String, CancellationToken>>> = OnceLock::new();
+  SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn json_to_grpc_value(value: JsonValue) -> prost_types::Value {
+  use prost_types::value::Kind;
+
+  let kind = match value {
+    JsonValue::Null => Kind::NullValue(0),
+    JsonValue::Bool(value) => Kind::BoolValue(value),
+    JsonValue::Number(number) => Kind::NumberValue(number.as_f64().unwrap_or_default()),
+    JsonValue::String(value) => Kind::StringValue(value),
+    JsonValue::Array(values) => Kind::ListValue(prost_types::ListValue {
+      values: values.into_iter().map(json_to_grpc_value).collect(),
+    }),
+    JsonValue::Object(fields) => Kind::StructValue(prost_types::Struct {
+      fields: fields
+        .into_iter()
+        .map(|(key, value)| (key, json_to_grpc_value(value)))
+        .collect(),
+    }),
+  };
+  prost_types::Value { kind: Some(kind) }
+}
+
+fn grpc_value_to_json(value: prost_types::Value) -> JsonValue {
+  use prost_types::value::Kind;
+
+  match value.kind {
+    None | Some(Kind::NullValue(_)) => JsonValue::Null,
+    Some(Kind::BoolValue(value)) => JsonValue::Bool(value),
+    // Whole numbers stay integers, so counts and limits keep their JSON type.
+    Some(Kind::NumberValue(number)) if number.fract() == 0.0 && number.abs() < 9.0e15 => {
+      JsonValue::from(number as i64)
+    }
+    Some(Kind::NumberValue(number)) => {
+      serde_json::Number::from_f64(number).map_or(JsonValue::Null, JsonValue::Number)
+    }
+    Some(Kind::StringValue(value)) => JsonValue::String(value),
+    Some(Kind::ListValue(list)) => {
+      JsonValue::Array(list.values.into_iter().map(grpc_value_to_json).collect())
+    }
+    Some(Kind::StructValue(fields)) => grpc_struct_to_json(fields),
+  }
+}
+
+fn grpc_struct_to_json(value: prost_types::Struct) -> JsonValue {
+  JsonValue::Object(
+    value
+      .fields
+      .into_iter()
+      .map(|(key, value)| (key, grpc_value_to_json(value)))
+      .collect(),
+  )
+}
+
+fn grpc_string_list(list: Option<grpc_proto::StringList>) -> Option<Vec<String>> {
+  list.map(|list| list.values)
+}
+
+/// `None` for an empty repeated field, which proto3 cannot tell apart from an unset one.
+fn grpc_non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+  (!values.is_empty()).then_some(values)
+}
+
+fn grpc_non_empty_map<K, V>(values: HashMap<K, V>) -> Option<HashMap<K, V>> {
+  (!values.is_empty()).then_some(values)
+}
+
+fn grpc_workspace_write_options(
+  options: grpc_proto::WorkspaceWriteOptions,
+) -> WorkspaceWriteOptions {
+  WorkspaceWriteOptions {
+    network_access: options.network_access,
+    writable_roots: grpc_non_empty(options.writable_roots),
+    exclude_tmpdir_env_var: options.exclude_tmpdir_env_var,
+    exclude_slash_tmp: options.exclude_slash_tmp,
+  }
+}
+
+fn grpc_run_request(request: grpc_proto::RunRequest) -> RunRequest {
+  RunRequest {
+    prompt: request.prompt,
+    prompt_template: request
+      .prompt_template
+      .map(|template| PromptTemplateRequest {
+        name: template.name,
+        vars: template.vars.map(grpc_struct_to_json),
+        max_tokens: template.max_tokens,
+      }),
+    input_items: request.input_items.map(grpc_value_to_json),
+    thread_id: request.thread_id,
+    images: grpc_non_empty(request.images),
+    model: request.model,
+    model_provider: request.model_provider,
+    profile: request.profile,
+    oss: request.oss,
+    sandbox_mode: request.sandbox_mode,
+    approval_mode: request.approval_mode,
+    workspace_write_options: request
+      .workspace_write_options
+      .map(grpc_workspace_write_options),
+    permission_profile: request.permission_profile,
+    allowed_tools: grpc_string_list(request.allowed_tools),
+    denied_tools: grpc_non_empty(request.denied_tools),
+    tool_limits: grpc_non_empty_map(
+      request
+        .tool_limits
+        .into_iter()
+        .map(|(name, limit)| {
+          (
+            name,
+            ToolLimitOptions {
+              max_concurrent: limit.max_concurrent,
+              min_interval_ms: limit.min_interval_ms,
+            },
+          )
+        })
+        .collect(),
+    ),
+    cache_ttl_seconds: grpc_non_empty_map(request.cache_ttl_seconds),
+    review_mode: request.review_mode,
+    review_hint: request.review_hint,
+    working_directory: request.working_directory,
+    skip_git_repo_check: request.skip_git_repo_check,
+    output_schema: request.output_schema.map(grpc_struct_to_json),
+    tool_choice: request.tool_choice.map(grpc_value_to_json),
+    base_url: request.base_url,
+    api_key: request.api_key,
+    auth_profile: request.auth_profile,
+    linux_sandbox_path: request.linux_sandbox_path,
+    reasoning_effort: request.reasoning_effort,
+    reasoning_summary: request.reasoning_summary,
+    personality: request.personality,
+    turn_personality: request.turn_personality,
+    ephemeral: request.ephemeral,
+    web_search_mode: request.web_search_mode,
+    web_search: request.web_search,
+    dynamic_tools: request.dynamic_tools.map(grpc_value_to_json),
+    mcp: request.mcp.map(grpc_struct_to_json),
+    inherit_mcp: request.inherit_mcp,
+    record: request.record,
+    replay: request.replay,
+    patch_path_policy: request.patch_path_policy.map(|policy| PatchPathPolicy {
+      allow: grpc_string_list(policy.allow),
+      deny: grpc_string_list(policy.deny),
+    }),
+    file_skeleton: request.file_skeleton.map(|skeleton| FileSkeletonOptions {
+      max_bytes: skeleton.max_bytes,
+      context_lines: skeleton.context_lines,
+    }),
+    redaction: request.redaction.map(|redaction| RedactionOptions {
+      enabled: redaction.enabled,
+      patterns: grpc_string_list(redaction.patterns),
+    }),
+    post_turn_checks: grpc_non_empty(
+      request
+        .post_turn_checks
+        .into_iter()
+        .map(|check| PostTurnCheck {
+          command: check.command,
+          name: check.name,
+          on_fail: check.on_fail,
+          timeout_ms: check.timeout_ms,
+        })
+        .collect(),
+    ),
+    max_check_feedback_turns: request.max_check_feedback_turns,
+    base_instructions: request.base_instructions,
+    developer_instructions: request.developer_instructions,
+    compact_prompt: request.compact_prompt,
+    event_delivery: request.event_delivery.map(|delivery| EventDeliveryOptions {
+      capacity: delivery.capacity,
+      overflow: delivery.overflow,
+    }),
+    include_raw_events: request.include_raw_events,
+    exec_session: request.exec_session,
+    shell_pty: request.shell_pty.map(|pty| ShellPtyOptions {
+      cols: pty.cols,
+      rows: pty.rows,
+      term: pty.term,
+    }),
+    tool_env: request.tool_env.map(|env| ToolEnvOptions {
+      inherit: env.inherit,
+      allow: grpc_string_list(env.allow),
+      set: grpc_non_empty_map(env.set),
+    }),
+    verify: request.verify.map(|verify| VerifyOptions {
+      commands: verify.commands,
+      timeout_ms: verify.timeout_ms,
+    }),
+    memory: request.memory.map(|memory| RunMemoryOptions {
+      limit: memory.limit,
+      tags: grpc_string_list(memory.tags),
+      min_score: memory.min_score,
+    }),
+    escalation_handler: request.escalation_handler,
+    output_limits: request.output_limits.map(|limits| OutputLimits {
+      max_tool_output_tokens: limits.max_tool_output_tokens,
+      head_tail_split: limits.head_tail_split,
+    }),
+  }
+}
+
+fn grpc_fork_request(request: grpc_proto::ForkRequest) -> ForkRequest {
+  ForkRequest {
+    thread_id: request.thread_id,
+    nth_user_message: request.nth_user_message,
+    model: request.model,
+    model_provider: request.model_provider,
+    profile: request.profile,
+    oss: request.oss,
+    sandbox_mode: request.sandbox_mode,
+    approval_mode: request.approval_mode,
+    workspace_write_options: request
+      .workspace_write_options
+      .map(grpc_workspace_write_options),
+    working_directory: request.working_directory,
+    skip_git_repo_check: request.skip_git_repo_check,
+    base_url: request.base_url,
+    api_key: request.api_key,
+    auth_profile: request.auth_profile,
+    linux_sandbox_path: request.linux_sandbox_path,
+    reasoning_effort: request.reasoning_effort,
+    reasoning_summary: request.reasoning_summary,
+    personality: request.personality,
+    turn_personality: request.turn_personality,
+    ephemeral: request.ephemeral,
+    web_search_mode: request.web_search_mode,
+    dynamic_tools: request.dynamic_tools.map(grpc_value_to_json),
+  }
+}
+
+fn grpc_internal_run_request(
+  request: grpc_proto::RunRequest,
+) -> Result<InternalRunRequest, tonic::Status> {
+  grpc_run_request(request)
+    .into_internal()
+    .map_err(|err| tonic::Status::invalid_argument(err.reason))
+}
+
+fn grpc_thread_item(item: codex_exec::exec_events::ThreadItem) -> grpc_proto::ThreadItem {
+  use codex_exec::exec_events as exec;
+  use grpc_proto::thread_item::Details;
+
+  let details = match item.details {
+    exec::ThreadItemDetails::AgentMessage(message) => {
+      Details::AgentMessage(grpc_proto::AgentMessageItem { text: message.text })
+    }
+    exec::ThreadItemDetails::Reasoning(reasoning) => {
+      Details::Reasoning(grpc_proto::ReasoningItem {
+        text: reasoning.text,
+      })
+    }
+    exec::ThreadItemDetails::CommandExecution(command) => {
+      Details::CommandExecution(grpc_proto::CommandExecutionItem {
+        command: command.command,
+        aggregated_output: command.aggregated_output,
+        exit_code: command.exit_code,
+        status: match command.status {
+          exec::CommandExecutionStatus::InProgress => {
+            grpc_proto::CommandExecutionStatus::InProgress
+          }
+          exec::CommandExecutionStatus::Completed => grpc_proto::CommandExecutionStatus::Completed,
+          exec::CommandExecutionStatus::Failed => grpc_proto::CommandExecutionStatus::Failed,
+          exec::CommandExecutionStatus::Declined => grpc_proto::CommandExecutionStatus::Declined,
+        } as i32,
+        cache_hit: command.cache_hit,
+        is_tty: command.is_tty,
+      })
+    }
+    exec::ThreadItemDetails::FileChange(change) => {
+      Details::FileChange(grpc_proto::FileChangeItem {
+        changes: change
+          .changes
+          .into_iter()
+          .map(|change| grpc_proto::FileUpdateChange {
+            path: change.path,
+            kind: match change.kind {
+              exec::PatchChangeKind::Add => grpc_proto::PatchChangeKind::Add,
+              exec::PatchChangeKind::Delete => grpc_proto::PatchChangeKind::Delete,
+              exec::PatchChangeKind::Update => grpc_proto::PatchChangeKind::Update,
+            } as i32,
+          })
+          .collect(),
+        status: match change.status {
+          exec::PatchApplyStatus::InProgress => grpc_proto::PatchApplyStatus::InProgress,
+          exec::PatchApplyStatus::Completed => grpc_proto::PatchApplyStatus::Completed,
+          exec::PatchApplyStatus::Failed => grpc_proto::PatchApplyStatus::Failed,
+        } as i32,
+      })
+    }
+    exec::ThreadItemDetails::McpToolCall(call) => {
+      Details::McpToolCall(grpc_proto::McpToolCallItem {
+        server: call.server,
+        tool: call.tool,
+        arguments: Some(json_to_grpc_value(call.arguments)),
+        result: call.result.map(|result| grpc_proto::McpToolCallResult {
+          content: result.content.into_iter().map(json_to_grpc_value).collect(),
+          structured_content: result.structured_content.map(json_to_grpc_value),
+        }),
+        error: call.error.map(|error| error.message),
+        status: match call.status {
+          exec::McpToolCallStatus::InProgress => grpc_proto::ToolCallStatus::InProgress,
+          exec::McpToolCallStatus::Completed => grpc_proto::ToolCallStatus::Completed,
+          exec::McpToolCallStatus::Failed => grpc_proto::ToolCallStatus::Failed,
+        } as i32,
+        cache_hit: call.cache_hit,
+      })
+    }
+    exec::ThreadItemDetails::CollabToolCall(call) => {
+      Details::CollabToolCall(grpc_proto::CollabToolCallItem {
+        tool: match call.tool {
+          exec::CollabTool::SpawnAgent => grpc_proto::CollabTool::SpawnAgent,
+          exec::CollabTool::SendInput => grpc_proto::CollabTool::SendInput,
+          exec::CollabTool::Wait => grpc_proto::CollabTool::Wait,
+          exec::CollabTool::CloseAgent => grpc_proto::CollabTool::CloseAgent,
+        } as i32,
+        sender_thread_id: call.sender_thread_id,
+        receiver_thread_ids: call.receiver_thread_ids,
+        prompt: call.prompt,
+        agents_states: call
+          .agents_states
+          .into_iter()
+          .map(|(thread_id, state)| {
+            let status = match state.status {
+              exec::CollabAgentStatus::PendingInit => grpc_proto::CollabAgentStatus::PendingInit,
+              exec::CollabAgentStatus::Running => grpc_proto::CollabAgentStatus::Running,
+              exec::CollabAgentStatus::Completed => grpc_proto::CollabAgentStatus::Completed,
+              exec::CollabAgentStatus::Errored => grpc_proto::CollabAgentStatus::Errored,
+              exec::CollabAgentStatus::Shutdown => grpc_proto::CollabAgentStatus::Shutdown,
+              exec::CollabAgentStatus::NotFound => grpc_proto::CollabAgentStatus::NotFound,
+            };
+            (
+              thread_id,
+              grpc_proto::CollabAgentState {
+                status: status as i32,
+                message: state.message,
+              },
+            )
+          })
+          .collect(),
+        status: match call.status {
+          exec::CollabToolCallStatus::InProgress => grpc_proto::ToolCallStatus::InProgress,
+          exec::CollabToolCallStatus::Completed => grpc_proto::ToolCallStatus::Completed,
+          exec::CollabToolCallStatus::Failed => grpc_proto::ToolCallStatus::Failed,
+        } as i32,
+      })
+    }
+    exec::ThreadItemDetails::WebSearch(search) => {
+      use codex_protocol::models::WebSearchAction;
+
+      let action = match search.action {
+        WebSearchAction::Search { query, queries } => grpc_proto::WebSearchAction {
+          r#type: "search".to_string(),
+          query,
+          queries: queries.unwrap_or_default(),
+          ..Default::default()
+        },
+        WebSearchAction::OpenPage { url } => grpc_proto::WebSearchAction {
+          r#type: "open_page".to_string(),
+          url,
+          ..Default::default()
+        },
+        WebSearchAction::FindInPage { url, pattern } => grpc_proto::WebSearchAction {
+          r#type: "find_in_page".to_string(),
+          url,
+          pattern,
+          ..Default::default()
+        },
+        WebSearchAction::Other => grpc_proto::WebSearchAction {
+          r#type: "other".to_string(),
+          ..Default::default()
+        },
+      };
+      Details::WebSearch(grpc_proto::WebSearchItem {
+        id: search.id,
+        query: search.query,
+        action: Some(action),
+      })
+    }
+    exec::ThreadItemDetails::TodoList(list) => Details::TodoList(grpc_proto::TodoListItem {
+      items: list
+        .items
+        .into_iter()
+        .map(|item| grpc_proto::TodoItem {
+          text: item.text,
+          completed: item.completed,
+        })
+        .collect(),
+    }),
+    exec::ThreadItemDetails::Error(error) => Details::Error(grpc_proto::ErrorItem {
+      message: error.message,
+    }),
+  };
+  grpc_proto::ThreadItem {
+    id: item.id,
+    details: Some(details),
+  }
+}
+
+fn grpc_review_output(
+  output: codex_exec::exec_events::ReviewOutputEvent,
+) -> grpc_proto::ReviewOutput {
+  grpc_proto::ReviewOutput {
+    findings: output
+      .findings
+      .into_iter()
+      .map(|finding| grpc_proto::ReviewFinding {
+        title: finding.title,
+        body: finding.body,
+        confidence_score: finding.confidence_score,
+        priority: finding.priority,
+        code_location: Some(grpc_proto::ReviewCodeLocation {
+          absolute_file_path: finding.code_location.absolute_file_path,
+          line_range: Some(grpc_proto::ReviewLineRange {
+            start: finding.code_location.line_range.start,
+            end: finding.code_location.line_range.end,
+          }),
+        }),
+      })
+      .collect(),
+    overall_correctness: output.overall_correctness,
+    overall_explanation: output.overall_explanation,
+    overall_confidence_score: output.overall_confidence_score,
+  }
+}
+
+fn grpc_background_task(
+  task: codex_exec::exec_events::BackgroundTaskEvent,
+) -> grpc_proto::BackgroundTaskEvent {
+  use codex_exec::exec_events::BackgroundTaskStatus;
+
+  grpc_proto::BackgroundTaskEvent {
+    task_id: task.task_id,
+    call_id: task.call_id,
+    tool_name: task.tool_name,
+    status: match task.status {
+      BackgroundTaskStatus::Running => grpc_proto::BackgroundTaskStatus::Running,
+      BackgroundTaskStatus::Completed => grpc_proto::BackgroundTaskStatus::Completed,
+      BackgroundTaskStatus::Failed => grpc_proto::BackgroundTaskStatus::Failed,
+    } as i32,
+    output: task.output,
+    error: task.error,
+  }
+}
+
+fn grpc_thread_event_payload(event: ExecThreadEvent) -> grpc_proto::thread_event::Event {
+  use codex_exec::exec_events as exec;
+  use codex_protocol::protocol::ExecCommandSource;
+  use grpc_proto::thread_event::Event;
+
+  let item = |item: exec::ThreadItem| grpc_proto::ItemEvent {
+    item: Some(grpc_thread_item(item)),
+  };
+  match event {
+    ExecThreadEvent::ThreadStarted(started) => {
+      Event::ThreadStarted(grpc_proto::ThreadStartedEvent {
+        thread_id: started.thread_id,
+        model: started.model,
+        model_provider: started.model_provider,
+        sandbox_policy: started.sandbox_policy,
+        cwd: started.cwd,
+        rollout_path: started.rollout_path,
+        config_profile: started.config_profile,
+      })
+    }
+    ExecThreadEvent::TurnStarted(exec::TurnStartedEvent {}) => {
+      Event::TurnStarted(grpc_proto::TurnStartedEvent {})
+    }
+    ExecThreadEvent::TurnCompleted(completed) => {
+      Event::TurnCompleted(grpc_proto::TurnCompletedEvent {
+        usage: Some(grpc_proto::Usage {
+          input_tokens: completed.usage.input_tokens,
+          cached_input_tokens: completed.usage.cached_input_tokens,
+          output_tokens: completed.usage.output_tokens,
+        }),
+        redaction_applied: completed.redaction_applied,
+        dropped_events: completed.dropped_events,
+      })
+    }
+    ExecThreadEvent::TurnFailed(failed) => Event::TurnFailed(grpc_proto::TurnFailedEvent {
+      error: Some(grpc_proto::ThreadErrorEvent {
+        message: failed.error.message,
+      }),
+    }),
+    ExecThreadEvent::ItemStarted(started) => Event::ItemStarted(item(started.item)),
+    ExecThreadEvent::ItemUpdated(updated) => Event::ItemUpdated(item(updated.item)),
+    ExecThreadEvent::ItemCompleted(completed) => Event::ItemCompleted(item(completed.item)),
+    ExecThreadEvent::Error(error) => Event::Error(grpc_proto::ThreadErrorEvent {
+      message: error.message,
+    }),
+    ExecThreadEvent::ExitedReviewMode(exited) => {
+      Event::ExitedReviewMode(grpc_proto::ExitedReviewModeEvent {
+        review_output: exited.review_output.map(grpc_review_output),
+      })
+    }
+    ExecThreadEvent::BackgroundEvent(background) => {
+      Event::BackgroundEvent(grpc_proto::BackgroundEventEvent {
+        message: background.message,
+      })
+    }
+    ExecThreadEvent::ToolOutputChunk(chunk) => {
+      Event::ToolOutputChunk(grpc_proto::ToolOutputChunkEvent {
+        call_id: chunk.call_id,
+        item_id: chunk.item_id,
+        chunk: chunk.chunk,
+      })
+    }
+    ExecThreadEvent::BackgroundTaskStarted(task) => {
+      Event::BackgroundTaskStarted(grpc_background_task(task))
+    }
+    ExecThreadEvent::BackgroundTaskCompleted(task) => {
+      Event::BackgroundTaskCompleted(grpc_background_task(task))
+    }
+    ExecThreadEvent::PlanUpdated(plan) => Event::PlanUpdated(grpc_proto::PlanUpdatedEvent {
+      explanation: plan.explanation,
+      plan: plan
+        .plan
+        .into_iter()
+        .map(|step| grpc_proto::PlanStep {
+          step: step.step,
+          status: match step.status {
+            exec::PlanStepStatus::Pending => grpc_proto::PlanStepStatus::Pending,
+            exec::PlanStepStatus::InProgress => grpc_proto::PlanStepStatus::InProgress,
+            exec::PlanStepStatus::Completed => grpc_proto::PlanStepStatus::Completed,
+          } as i32,
+        })
+        .collect(),
+    }),
+    ExecThreadEvent::ThreadStatus(status) => Event::ThreadStatus(grpc_proto::ThreadStatusEvent {
+      text: status.text,
+      progress_pct: status.progress_pct,
+    }),
+    ExecThreadEvent::TestResults(results) => Event::TestResults(grpc_proto::TestResultsEvent {
+      framework: results.framework,
+      command: results.command,
+      call_id: results.call_id,
+      exit_code: results.exit_code,
+      success: results.success,
+      passed: results.passed,
+      failed: results.failed,
+      skipped: results.skipped,
+      tests: results
+        .tests
+        .into_iter()
+        .map(|test| grpc_proto::TestCaseResult {
+          name: test.name,
+          status: match test.status {
+            exec::TestCaseStatus::Passed => grpc_proto::TestCaseStatus::Passed,
+            exec::TestCaseStatus::Failed => grpc_proto::TestCaseStatus::Failed,
+            exec::TestCaseStatus::Skipped => grpc_proto::TestCaseStatus::Skipped,
+          } as i32,
+          file: test.file,
+          message: test.message,
+          duration_ms: test.duration_ms,
+        })
+        .collect(),
+    }),
+    ExecThreadEvent::VerificationFailed(failed) => {
+      Event::VerificationFailed(grpc_proto::VerificationFailedEvent {
+        call_id: failed.call_id,
+        failures: failed
+          .failures
+          .into_iter()
+          .map(|failure| grpc_proto::VerificationFailure {
+            command: failure.command,
+            exit_code: failure.exit_code,
+            timed_out: failure.timed_out,
+            output: failure.output,
+          })
+          .collect(),
+      })
+    }
+    ExecThreadEvent::FilesTouched(touched) => Event::FilesTouched(grpc_proto::FilesTouchedEvent {
+      read: touched.read,
+      modified: touched.modified,
+    }),
+    ExecThreadEvent::CommandStarted(started) => {
+      Event::CommandStarted(grpc_proto::CommandStartedEvent {
+        call_id: started.call_id,
+        item_id: started.item_id,
+        command: started.command,
+        cwd: started.cwd,
+        source: match started.source {
+          ExecCommandSource::Agent => grpc_proto::ExecCommandSource::Agent,
+          ExecCommandSource::UserShell => grpc_proto::ExecCommandSource::UserShell,
+          ExecCommandSource::UnifiedExecStartup => {
+            grpc_proto::ExecCommandSource::UnifiedExecStartup
+          }
+          ExecCommandSource::UnifiedExecInteraction => {
+            grpc_proto::ExecCommandSource::UnifiedExecInteraction
+          }
+        } as i32,
+        process_id: started.process_id,
+        sandboxed: started.sandboxed,
+      })
+    }
+    ExecThreadEvent::CommandCompleted(completed) => {
+      Event::CommandCompleted(grpc_proto::CommandCompletedEvent {
+        call_id: completed.call_id,
+        item_id: completed.item_id,
+        command: completed.command,
+        exit_code: completed.exit_code,
+        duration_ms: completed.duration_ms,
+        output: completed.output,
+        output_truncated: completed.output_truncated,
+        sandbox_decision: match completed.sandbox_decision {
+          exec::CommandSandboxDecision::Sandboxed => grpc_proto::CommandSandboxDecision::Sandboxed,
+          exec::CommandSandboxDecision::Unsandboxed => {
+            grpc_proto::CommandSandboxDecision::Unsandboxed
+          }
+          exec::CommandSandboxDecision::Approved => grpc_proto::CommandSandboxDecision::Approved,
+          exec::CommandSandboxDecision::Denied => grpc_proto::CommandSandboxDecision::Denied,
+        } as i32,
+        approval_reason: completed.approval_reason,
+        model_output: completed.model_output,
+      })
+    }
+    ExecThreadEvent::Raw(raw) => Event::RawEvent(grpc_proto::RawEvent {
+      raw: Some(json_to_grpc_value(raw.raw)),
+    }),
+  }
+}
+
+/// `event` as its protobuf message, stamped with the thread's next sequence number.
+fn grpc_thread_event(
+  sequencer: &mut ThreadEventSequencer,
+  event: ExecThreadEvent,
+) -> grpc_proto::ThreadEvent {
+  let stamp = sequencer.stamp(&event, JsonValue::Object(JsonMap::new()));
+  grpc_proto::ThreadEvent {
+    sequence: stamp["sequence"].as_u64().unwrap_or_default(),
+    event_id: stamp["event_id"].as_str().unwrap_or_default().to_string(),
+    event: Some(grpc_thread_event_payload(event)),
+  }
+}
+
+/// The thread id and last agent message of a finished run.
+fn grpc_run_outcome(events: &[grpc_proto::ThreadEvent]) -> (Option<String>, Option<String>) {
+  use grpc_proto::thread_event::Event;
+  use grpc_proto::thread_item::Details;
+
+  let thread_id = events.iter().find_map(|event| match &event.event {
+    Some(Event::ThreadStarted(started)) => Some(started.thread_id.clone()),
+    _ => None,
+  });
+  let final_response = events.iter().rev().find_map(|event| match &event.event {
+    Some(Event::ItemCompleted(grpc_proto::ItemEvent {
+      item:
+        Some(grpc_proto::ThreadItem {
+          details: Some(Details::AgentMessage(message)),
+          ..
+        }),
+    })) => Some(message.text.clone()),
+    _ => None,
+  });
+  (thread_id, final_response)
+}
+
+/// Run `options`, passing each event to `on_event`, until the run ends or `cancel` fires.
+fn grpc_run(
+  options: InternalRunRequest,
+  cancel: CancellationToken,
+  mut on_event: impl FnMut(grpc_proto::ThreadEvent) + Send + 'static,
+) -> napi::Result<()> {
+  let mut sequencer = ThreadEventSequencer::new(options.thread_id.clone());
+  with_run_cancellation(cancel, || {
+    run_with_session_hooks(options, move |event| {
+      on_event(grpc_thread_event(&mut sequencer, event));
+    })
+  })
+}
+
+struct GrpcService {
+  token: String,
+}
+
+impl GrpcService {
+  fn authorize<T>(&self, request: &tonic::Request<T>) -> Result<(), tonic::Status> {
+    let authorization = request
+      .metadata()
+      .get("authorization")
+      .and_then(|value| value.to_str().ok());
+    if !bearer_token_matches(authorization, &self.token) {
+      return Err(tonic::Status::unauthenticated(
+        "missing or invalid bearer token",
+      ));
+    }
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl grpc_proto::codex_native_server::CodexNative for GrpcService {
+  type RunStreamStream = GrpcEventStream;
+
+  async fn run(
+    &self,
+    request: tonic::Request<grpc_proto::RunRequest>,
+  ) -> Result<tonic::Response<grpc_proto::RunResponse>, tonic::Status> {
+    self.authorize(&request)?;
+    let options = grpc_internal_run_request(request.into_inner())?;
+    // Tonic drops this future when the client goes away; the guard then stops the run.
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_for_run = Arc::clone(&events);
+    tokio::task::spawn_blocking(move || {
+      grpc_run(options, cancel, move |event| {
+        if let Ok(mut events) = events_for_run.lock() {
+          events.push(event);
+        }
+      })
+    })
+    .await
+    .map_err(|e| tonic::Status::internal(format!("run task failed: {e}")))?
+    .map_err(|err| tonic::Status::internal(err.reason))?;
+    let events = std::mem::take(
+      &mut *events
+        .lock()
+        .map_err(|e| tonic::Status::internal(format!("run events mutex poisoned: {e}")))?,
+    );
+    let (thread_id, final_response) = grpc_run_outcome(&events);
+    Ok(tonic::Response::new(grpc_proto::RunResponse {
+      events,
+      thread_id,
+      final_response,
+    }))
+  }
+
+  async fn run_stream(
+    &self,
+    request: tonic::Request<grpc_proto::RunRequest>,
+  ) -> Result<tonic::Response<Self::RunStreamStream>, tonic::Status> {
+    self.authorize(&request)?;
+    let options = grpc_internal_run_request(request.into_inner())?;
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let cancel = CancellationToken::new();
+    // The receiver goes away with the response stream, so a closed channel means the client
+    // cancelled the call or hung up.
+    cancel_when_closed(tx.clone(), cancel.clone());
+    tokio::task::spawn_blocking(move || {
+      let events_tx = tx.clone();
+      let result = grpc_run(options, cancel.clone(), move |event| {
+        let _ = events_tx.send(Ok(event));
+      });
+      cancel.cancel();
+      if let Err(err) = result {
+        let _ = tx.send(Err(tonic::Status::internal(err.reason)));
+      }
+    });
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+      rx.recv().await.map(|item| (item, rx))
+    });
+    Ok(tonic::Response::new(Box::pin(stream)))
+  }
+
+  async fn fork(
+    &self,
+    request: tonic::Request<grpc_proto::ForkRequest>,
+  ) -> Result<tonic::Response<grpc_proto::ForkResponse>, tonic::Status> {
+    self.authorize(&request)?;
+    let forked = fork_thread(grpc_fork_request(request.into_inner()))
+      .await
+      .map_err(|err| tonic::Status::internal(err.reason))?;
+    Ok(tonic::Response::new(grpc_proto::ForkResponse {
+      thread_id: forked.thread_id,
+      rollout_path: forked.rollout_path,
+    }))
+  }
+
+  async fn list_threads(
+    &self,
+    request: tonic::Request<grpc_proto::ListThreadsRequest>,
+  ) -> Result<tonic::Response<grpc_proto::ListThreadsResponse>, tonic::Status> {
+    self.authorize(&request)?;
+    let request = request.into_inner();
+    let page = list_conversations(ListConversationsRequest {
+      config: None,
+      page_size: request.page_size,
+      cursor: request.cursor,
+      model_providers: None,
+      cwd: request.cwd,
+    })
+    .await
+    .map_err(|err| tonic::Status::internal(err.reason))?;
+    Ok(tonic::Response::new(grpc_proto::ListThreadsResponse {
+      threads: page
+        .conversations
+        .into_iter()
+        .map(|summary| grpc_proto::ThreadSummary {
+          id: summary.id,
+          path: summary.path,
+          created_at: summary.created_at,
+          updated_at: summary.updated_at,
+          title: summary.title,
+          summary: summary.summary,
+        })
+        .collect(),
+      next_cursor: page.next_cursor,
+    }))
+  }
+}
+
+/// Serve the CodexNative gRPC service on 127.0.0.1 until stopGrpcServer().
+#[napi]
+pub fn start_grpc_server(options: Option<GrpcServerOptions>) -> napi::Result<GrpcServerInfo> {
+  let options = options.unwrap_or_default();
+  let port = u16::try_from(options.port.unwrap_or(0))
+    .map_err(|_| napi::Error::from_reason("port must be between 0 and 65535"))?;
+  let token = match options.auth_token {
+    Some(token) if token.trim().is_empty() => {
+      return Err(napi::Error::from_reason("authToken must not be empty"));
+    }
+    Some(token) => token,
+    None => Uuid::new_v4().simple().to_string(),
+  };
+  let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+    .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to bind gRPC server: {e}")))?;
+  let addr = listener
+    .local_addr()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read gRPC server address: {e}")))?;
+  let runtime = tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+
+  let id = Uuid::new_v4().to_string();
+  let stopped = CancellationToken::new();
+  let stopped_for_server = stopped.clone();
+  let service = GrpcService {
+    token: token.clone(),
+  };
+  std::thread::Builder::new()
+    .name("codex-grpc-server".to_string())
+    .spawn(move || {
+      runtime.block_on(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+          Ok(listener) => listener,
+          Err(err) => {
+            eprintln!("Failed to start gRPC server: {err}");
+            return;
+          }
+        };
+        let served = tonic::transport::Server::builder()
+          .add_service(grpc_proto::codex_native_server::CodexNativeServer::new(
+            service,
+          ))
+          .serve_with_incoming_shutdown(
+            tonic::transport::server::TcpIncoming::from(listener),
+            stopped_for_server.cancelled_owned(),
+          )
+          .await;
+        if let Err(err) = served {
+          eprintln!("gRPC server stopped: {err}");
+        }
+      });
+    })
+    .map_err(|e| napi::Error::from_reason(format!("Failed to spawn gRPC server: {e}")))?;
+
+  grpc_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("gRPC server mutex poisoned: {e}")))?
+    .insert(id.clone(), stopped);
+
+  Ok(GrpcServerInfo {
+    id,
+    address: addr.to_string(),
+    port: u32::from(addr.port()),
+    auth_token: token,
+  })
+}
+
+/// Stop a gRPC server started by startGrpcServer(). Returns `false` for unknown ids.
+#[napi]
+pub fn stop_grpc_server(id: String) -> napi::Result<bool> {
+  let stopped = grpc_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("gRPC server mutex poisoned: {e}")))?
+    .remove(&id);
+  if let Some(stopped) = &stopped {
+    stopped.cancel();
+  }
+  Ok(stopped.is_some())
+}
+
+#[cfg(test)]
+mod tests_grpc_server {
+  use super::*;
+  use codex_exec::exec_events as exec;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn converts_exec_events_to_typed_messages() {
+    let mut sequencer = ThreadEventSequencer::ephemeral();
+    let started = grpc_thread_event(
+      &mut sequencer,
+      ExecThreadEvent::ThreadStarted(exec::ThreadStartedEvent {
+        thread_id: "t1".to_string(),
+        model: Some("gpt-5".to_string()),
+        model_provider: None,
+        sandbox_policy: None,
+        cwd: None,
+        rollout_path: None,
+        config_profile: None,
+      }),
+    );
+    let completed = grpc_thread_event(
+      &mut sequencer,
+      ExecThreadEvent::ItemCompleted(exec::ItemCompletedEvent {
+        item: exec::ThreadItem {
+          id: "item_0".to_string(),
+          details: exec::ThreadItemDetails::AgentMessage(exec::AgentMessageItem {
+            text: "done".to_string(),
+          }),
+        },
+      }),
+    );
+    assert_eq!((started.sequence, started.event_id.as_str()), (0, "t1:0"));
+    assert_eq!(
+      completed,
+      grpc_proto::ThreadEvent {
+        sequence: 1,
+        event_id: "t1:1".to_string(),
+        event: Some(grpc_proto::thread_event::Event::ItemCompleted(
+          grpc_proto::ItemEvent {
+            item: Some(grpc_proto::ThreadItem {
+              id: "item_0".to_string(),
+              details: Some(grpc_proto::thread_item::Details::AgentMessage(
+                grpc_proto::AgentMessageItem {
+                  text: "done".to_string(),
+                }
+              )),
+            }),
+          }
+        )),
+      }
+    );
+    assert_eq!(
+      grpc_run_outcome(&[started, completed]),
+      (Some("t1".to_string()), Some("done".to_string()))
+    );
+  }
+
+  #[test]
+  fn converts_mcp_arguments_to_protobuf_values() {
+    let item = grpc_thread_item(exec::ThreadItem {
+      id: "item_1".to_string(),
+      details: exec::ThreadItemDetails::McpToolCall(exec::McpToolCallItem {
+        server: "docs".to_string(),
+        tool: "search".to_string(),
+        arguments: json!({ "query": "tonic", "limit": 3, "exact": true }),
+        result: None,
+        error: Some(exec::McpToolCallItemError {
+          message: "timed out".to_string(),
+        }),
+        status: exec::McpToolCallStatus::Failed,
+        cache_hit: None,
+      }),
+    });
+    let Some(grpc_proto::thread_item::Details::McpToolCall(call)) = item.details else {
+      panic!("expected an MCP tool call, got {item:?}");
+    };
+    assert_eq!(call.status, grpc_proto::ToolCallStatus::Failed as i32);
+    assert_eq!(call.error.as_deref(), Some("timed out"));
+    assert_eq!(
+      call.arguments.map(grpc_value_to_json),
+      Some(json!({ "query": "tonic", "limit": 3, "exact": true }))
+    );
+  }
+
+  #[test]
+  fn converts_typed_run_requests() {
+    let request = grpc_run_request(grpc_proto::RunRequest {
+      prompt: "hi".to_string(),
+      allowed_tools: Some(grpc_proto::StringList { values: Vec::new() }),
+      tool_choice: Some(json_to_grpc_value(
+        json!({ "type": "function", "name": "search" }),
+      )),
+      cache_ttl_seconds: HashMap::from([("search".to_string(), 60)]),
+      ..Default::default()
+    });
+    assert_eq!(request.prompt, "hi");
+    // An empty allow list still means "no tools", unlike an unset one.
+    assert_eq!(request.allowed_tools, Some(Vec::new()));
+    assert_eq!(request.denied_tools, None);
+    assert_eq!(
+      request.tool_choice,
+      Some(json!({ "type": "function", "name": "search" }))
+    );
+    assert_eq!(
+      request.cache_ttl_seconds,
+      Some(HashMap::from([("search".to_string(), 60)]))
+    );
+  }
+
+  #[test]
+  fn authorize_requires_the_server_token() {
+    let service = GrpcService {
+      token: "secret".to_string(),
+    };
+    let with_token = |token: &str| {
+      let mut request = tonic::Request::new(());
+      request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {token}").parse().expect("metadata value"),
+      );
+      request
+    };
+    assert!(service.authorize(&with_token("secret")).is_ok());
+    assert!(service.authorize(&with_token("secre")).is_err());
+    assert!(service.authorize(&tonic::Request::new(())).is_err());
+  }
+
+  #[tokio::test]
+  async fn cancelled_runs_stop_waiting() {
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let result = run_until_cancelled(Some(cancel), std::future::pending()).await;
+    assert_eq!(
+      result.map_err(|err| err.reason),
+      Err("Run cancelled".to_string())
+    );
+  }
+}
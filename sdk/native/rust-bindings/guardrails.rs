@@ -0,0 +1,397 @@
+// ============================================================================
+// Section: Guardrails
+// ============================================================================
+//
+// `registerGuardrail(direction, handler)` installs a content policy that every
+// run enforces natively, whichever wrapper started it:
+//   - input: each turn's prompt and text input items, after pre_turn hooks
+//   - output: assistant messages, before their events reach any JS handler
+//
+// A guardrail allows, redacts, rewrites or blocks the content. Blocked input
+// fails the turn before anything is sent to the model; a blocked assistant
+// message is replaced by an error item. Both carry the guardrail's reason. A
+// guardrail that throws or returns an unknown decision blocks the content, so
+// a broken policy never lets unchecked content through.
+//
+// Guardrails are not removed by clearRegisteredTools(); only clear_guardrails()
+// removes them.
+//
+// Key exports:
+//   - register_guardrail()
+//   - clear_guardrails()
+//
+// ============================================================================
+
+const GUARDRAIL_REDACTION: &str = "[REDACTED]";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GuardrailDirection {
+  Input,
+  Output,
+}
+
+impl GuardrailDirection {
+  fn parse(direction: &str) -> napi::Result<Self> {
+    match direction {
+      "input" => Ok(Self::Input),
+      "output" => Ok(Self::Output),
+      other => Err(napi::Error::from_reason(format!(
+        "Unsupported guardrail direction: {other}. Expected input or output"
+      ))),
+    }
+  }
+
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Input => "input",
+      Self::Output => "output",
+    }
+  }
+}
+
+#[derive(Clone)]
+#[napi(object)]
+pub struct JsGuardrailRequest {
+  #[napi(ts_type = "\"input\" | \"output\"")]
+  pub direction: String,
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  pub content: String,
+}
+
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct JsGuardrailDecision {
+  /// Defaults to `allow`.
+  #[napi(ts_type = "\"allow\" | \"redact\" | \"rewrite\" | \"block\"")]
+  pub action: Option<String>,
+  /// `rewrite`: replacement content.
+  pub content: Option<String>,
+  /// `redact`: substrings replaced with `[REDACTED]`.
+  pub redact: Option<Vec<String>>,
+  /// `block`: why the content was blocked.
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum GuardrailOutcome {
+  Pass(String),
+  Blocked(String),
+}
+
+type GuardrailReturn = napi::Either<
+  napi::bindgen_prelude::Promise<Option<JsGuardrailDecision>>,
+  Option<JsGuardrailDecision>,
+>;
+type GuardrailTsfn = Arc<
+  ThreadsafeFunction<JsGuardrailRequest, GuardrailReturn, JsGuardrailRequest, napi::Status, false>,
+>;
+
+#[derive(Clone)]
+struct NativeGuardrail {
+  direction: GuardrailDirection,
+  callback: GuardrailTsfn,
+}
+
+fn registered_guardrails() -> &'static Mutex<Vec<NativeGuardrail>> {
+  static GUARDRAILS: OnceLock<Mutex<Vec<NativeGuardrail>>> = OnceLock::new();
+  GUARDRAILS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn guardrails_for(direction: GuardrailDirection) -> Vec<GuardrailTsfn> {
+  // A poisoned registry must not silently disable enforcement.
+  let guardrails = match registered_guardrails().lock() {
+    Ok(guardrails) => guardrails,
+    Err(poisoned) => poisoned.into_inner(),
+  };
+  guardrails
+    .iter()
+    .filter(|guardrail| guardrail.direction == direction)
+    .map(|guardrail| Arc::clone(&guardrail.callback))
+    .collect()
+}
+
+fn has_guardrails(direction: GuardrailDirection) -> bool {
+  !guardrails_for(direction).is_empty()
+}
+
+#[napi(
+  ts_args_type = "direction: \"input\" | \"output\", handler: (request: JsGuardrailRequest) => JsGuardrailDecision | void | Promise<JsGuardrailDecision | void>"
+)]
+pub fn register_guardrail(
+  env: Env,
+  direction: String,
+  handler: Function<JsGuardrailRequest, GuardrailReturn>,
+) -> napi::Result<()> {
+  let direction = GuardrailDirection::parse(&direction)?;
+  let mut tsfn = handler
+    .build_threadsafe_function::<JsGuardrailRequest>()
+    .callee_handled::<false>()
+    .build()?;
+  #[allow(deprecated)]
+  let _ = tsfn.unref(&env);
+
+  registered_guardrails()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("guardrails mutex poisoned: {e}")))?
+    .push(NativeGuardrail {
+      direction,
+      callback: Arc::new(tsfn),
+    });
+  Ok(())
+}
+
+#[napi]
+pub fn clear_guardrails() -> napi::Result<()> {
+  registered_guardrails()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("guardrails mutex poisoned: {e}")))?
+    .clear();
+  Ok(())
+}
+
+fn apply_guardrail_decision(content: String, decision: JsGuardrailDecision) -> GuardrailOutcome {
+  match decision.action.as_deref().unwrap_or("allow") {
+    "allow" => GuardrailOutcome::Pass(content),
+    "redact" => GuardrailOutcome::Pass(
+      decision
+        .redact
+        .unwrap_or_default()
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .fold(content, |content, secret| {
+          content.replace(secret.as_str(), GUARDRAIL_REDACTION)
+        }),
+    ),
+    "rewrite" => match decision.content {
+      Some(content) => GuardrailOutcome::Pass(content),
+      None => GuardrailOutcome::Blocked("guardrail returned a rewrite without content".to_string()),
+    },
+    "block" => GuardrailOutcome::Blocked(
+      decision
+        .reason
+        .unwrap_or_else(|| "content policy violation".to_string()),
+    ),
+    other => GuardrailOutcome::Blocked(format!("guardrail returned unknown action `{other}`")),
+  }
+}
+
+/// Runs every guardrail for `direction` over `content`, in registration order.
+async fn run_guardrails(
+  direction: GuardrailDirection,
+  thread_id: Option<String>,
+  content: String,
+) -> GuardrailOutcome {
+  let mut content = content;
+  for guardrail in guardrails_for(direction) {
+    let request = JsGuardrailRequest {
+      direction: direction.as_str().to_string(),
+      thread_id: thread_id.clone(),
+      content: content.clone(),
+    };
+    let decision = match guardrail.call_async(request).await {
+      Ok(napi::Either::A(promise)) => promise.await,
+      Ok(napi::Either::B(decision)) => Ok(decision),
+      Err(err) => Err(err),
+    };
+    let decision = match decision {
+      Ok(decision) => decision.unwrap_or_default(),
+      Err(err) => return GuardrailOutcome::Blocked(format!("guardrail failed: {}", err.reason)),
+    };
+    match apply_guardrail_decision(content, decision) {
+      GuardrailOutcome::Pass(next) => content = next,
+      blocked @ GuardrailOutcome::Blocked(_) => return blocked,
+    }
+  }
+  GuardrailOutcome::Pass(content)
+}
+
+fn guardrail_block_message(reason: &str) -> String {
+  format!("Blocked by guardrail: {reason}")
+}
+
+/// Applies the input guardrails to the turn's prompt and text input items. Returns the reason
+/// when the turn must not be sent.
+async fn guard_run_input(options: &mut InternalRunRequest) -> Option<String> {
+  let thread_id = options.thread_id.clone();
+  if !options.prompt.is_empty() {
+    let prompt = std::mem::take(&mut options.prompt);
+    match run_guardrails(GuardrailDirection::Input, thread_id.clone(), prompt).await {
+      GuardrailOutcome::Pass(prompt) => options.prompt = prompt,
+      GuardrailOutcome::Blocked(reason) => return Some(reason),
+    }
+  }
+  for item in options.input_items.iter_mut().flatten() {
+    if let UserInput::Text {
+      text,
+      text_elements,
+    } = item
+    {
+      let original = std::mem::take(text);
+      match run_guardrails(
+        GuardrailDirection::Input,
+        thread_id.clone(),
+        original.clone(),
+      )
+      .await
+      {
+        GuardrailOutcome::Pass(guarded) => {
+          // Element byte ranges only describe the text they were created for.
+          if guarded != original {
+            text_elements.clear();
+          }
+          *text = guarded;
+        }
+        GuardrailOutcome::Blocked(reason) => return Some(reason),
+      }
+    }
+  }
+  None
+}
+
+fn guard_output_item(
+  item: codex_exec::exec_events::ThreadItem,
+  thread_id: Option<String>,
+) -> codex_exec::exec_events::ThreadItem {
+  use codex_exec::exec_events::AgentMessageItem;
+  use codex_exec::exec_events::ErrorItem;
+  use codex_exec::exec_events::ThreadItem;
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  let ThreadItem { id, details } = item;
+  let details = match details {
+    ThreadItemDetails::AgentMessage(message) => {
+      // Thread events are delivered synchronously from inside the run's runtime.
+      let outcome = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(run_guardrails(
+          GuardrailDirection::Output,
+          thread_id,
+          message.text,
+        ))
+      });
+      match outcome {
+        GuardrailOutcome::Pass(text) => ThreadItemDetails::AgentMessage(AgentMessageItem { text }),
+        GuardrailOutcome::Blocked(reason) => ThreadItemDetails::Error(ErrorItem {
+          message: guardrail_block_message(&reason),
+        }),
+      }
+    }
+    other @ (ThreadItemDetails::Reasoning(_)
+    | ThreadItemDetails::CommandExecution(_)
+    | ThreadItemDetails::FileChange(_)
+    | ThreadItemDetails::McpToolCall(_)
+    | ThreadItemDetails::CollabToolCall(_)
+    | ThreadItemDetails::WebSearch(_)
+    | ThreadItemDetails::TodoList(_)
+    | ThreadItemDetails::Error(_)) => other,
+  };
+  ThreadItem { id, details }
+}
+
+/// Applies the output guardrails to assistant messages carried by `event`.
+fn guard_output_event(event: ExecThreadEvent, thread_id: Option<String>) -> ExecThreadEvent {
+  use codex_exec::exec_events::ItemCompletedEvent;
+  use codex_exec::exec_events::ItemStartedEvent;
+  use codex_exec::exec_events::ItemUpdatedEvent;
+
+  if !has_guardrails(GuardrailDirection::Output) {
+    return event;
+  }
+  match event {
+    ExecThreadEvent::ItemStarted(ev) => ExecThreadEvent::ItemStarted(ItemStartedEvent {
+      item: guard_output_item(ev.item, thread_id),
+    }),
+    ExecThreadEvent::ItemUpdated(ev) => ExecThreadEvent::ItemUpdated(ItemUpdatedEvent {
+      item: guard_output_item(ev.item, thread_id),
+    }),
+    ExecThreadEvent::ItemCompleted(ev) => ExecThreadEvent::ItemCompleted(ItemCompletedEvent {
+      item: guard_output_item(ev.item, thread_id),
+    }),
+    other @ (ExecThreadEvent::ThreadStarted(_)
+    | ExecThreadEvent::TurnStarted(_)
+    | ExecThreadEvent::TurnCompleted(_)
+    | ExecThreadEvent::TurnFailed(_)
+    | ExecThreadEvent::Error(_)
+    | ExecThreadEvent::ExitedReviewMode(_)
+    | ExecThreadEvent::BackgroundEvent(_)
+    | ExecThreadEvent::ToolOutputChunk(_)
+    | ExecThreadEvent::BackgroundTaskStarted(_)
+    | ExecThreadEvent::BackgroundTaskCompleted(_)
+    | ExecThreadEvent::PlanUpdated(_)
+    | ExecThreadEvent::ThreadStatus(_)
+    | ExecThreadEvent::TestResults(_)
+    | ExecThreadEvent::VerificationFailed(_)
+    | ExecThreadEvent::FilesTouched(_)
+    | ExecThreadEvent::CommandStarted(_)
+    | ExecThreadEvent::CommandCompleted(_)
+    | ExecThreadEvent::Raw(_)) => other,
+  }
+}
+
+#[cfg(test)]
+mod tests_guardrails {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn decision(action: &str) -> JsGuardrailDecision {
+    JsGuardrailDecision {
+      action: Some(action.to_string()),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn decisions_redact_rewrite_and_block() {
+    let content = "token sk-123 and sk-456".to_string();
+    assert_eq!(
+      apply_guardrail_decision(content.clone(), JsGuardrailDecision::default()),
+      GuardrailOutcome::Pass(content.clone())
+    );
+    assert_eq!(
+      apply_guardrail_decision(
+        content.clone(),
+        JsGuardrailDecision {
+          redact: Some(vec![
+            "sk-123".to_string(),
+            "sk-456".to_string(),
+            String::new()
+          ]),
+          ..decision("redact")
+        }
+      ),
+      GuardrailOutcome::Pass("token [REDACTED] and [REDACTED]".to_string())
+    );
+    assert_eq!(
+      apply_guardrail_decision(
+        content.clone(),
+        JsGuardrailDecision {
+          content: Some("safe".to_string()),
+          ..decision("rewrite")
+        }
+      ),
+      GuardrailOutcome::Pass("safe".to_string())
+    );
+    assert_eq!(
+      apply_guardrail_decision(
+        content.clone(),
+        JsGuardrailDecision {
+          reason: Some("contains credentials".to_string()),
+          ..decision("block")
+        }
+      ),
+      GuardrailOutcome::Blocked("contains credentials".to_string())
+    );
+  }
+
+  #[test]
+  fn malformed_decisions_fail_closed() {
+    assert_eq!(
+      apply_guardrail_decision("text".to_string(), decision("rewrite")),
+      GuardrailOutcome::Blocked("guardrail returned a rewrite without content".to_string())
+    );
+    assert_eq!(
+      apply_guardrail_decision("text".to_string(), decision("allow-all")),
+      GuardrailOutcome::Blocked("guardrail returned unknown action `allow-all`".to_string())
+    );
+  }
+}
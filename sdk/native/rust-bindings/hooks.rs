@@ -0,0 +1,506 @@
+// ============================================================================
+// Section: Lifecycle Hooks
+// ============================================================================
+//
+// `registerHook(event, callback)` runs JS callbacks at fixed points of every
+// run, generalizing per-tool interceptors into one extension surface:
+//   - pre_turn: before each turn; may replace the prompt
+//   - post_turn: after each turn; may annotate it with a background event
+//   - pre_tool_call: before every tool call; may rewrite the arguments or
+//     reject the call with a message for the model
+//   - post_tool_call: after every tool call; may replace or annotate the output
+//   - session_end: once a run, including post-turn-check follow-ups, is over
+//
+// Hooks for the same event run in registration order, each seeing the changes
+// made by the previous one. Tool-call hooks wrap every tool outside its own
+// interceptors, so a rewritten `apply_patch` is still checked by patchPathPolicy.
+//
+// Key exports:
+//   - register_hook()
+//   - clear_hooks()
+//
+// ============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HookEvent {
+  PreTurn,
+  PostTurn,
+  PreToolCall,
+  PostToolCall,
+  SessionEnd,
+}
+
+impl HookEvent {
+  fn parse(event: &str) -> napi::Result<Self> {
+    match event {
+      "pre_turn" => Ok(Self::PreTurn),
+      "post_turn" => Ok(Self::PostTurn),
+      "pre_tool_call" => Ok(Self::PreToolCall),
+      "post_tool_call" => Ok(Self::PostToolCall),
+      "session_end" => Ok(Self::SessionEnd),
+      other => Err(napi::Error::from_reason(format!(
+        "Unsupported hook event: {other}. Expected pre_turn, post_turn, pre_tool_call, post_tool_call or session_end"
+      ))),
+    }
+  }
+
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::PreTurn => "pre_turn",
+      Self::PostTurn => "post_turn",
+      Self::PreToolCall => "pre_tool_call",
+      Self::PostToolCall => "post_tool_call",
+      Self::SessionEnd => "session_end",
+    }
+  }
+}
+
+/// What a hook is told about the point in the run it was called at. Fields that don't apply to
+/// the event are omitted.
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct JsHookContext {
+  #[napi(
+    ts_type = "\"pre_turn\" | \"post_turn\" | \"pre_tool_call\" | \"post_tool_call\" | \"session_end\""
+  )]
+  pub event: String,
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  /// Turn prompt (`pre_turn`, `post_turn`).
+  pub prompt: Option<String>,
+  /// Tool call being made (`pre_tool_call`, `post_tool_call`).
+  pub invocation: Option<JsToolInvocation>,
+  /// Tool output (`post_tool_call`) or the turn's final response (`post_turn`).
+  pub output: Option<String>,
+  pub success: Option<bool>,
+  /// Why the tool call, turn or run failed.
+  pub error: Option<String>,
+}
+
+/// Changes a hook asks for. Fields that don't apply to the event are ignored.
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct JsHookResult {
+  /// `pre_turn`: replacement prompt.
+  pub prompt: Option<String>,
+  /// `pre_tool_call`: replacement arguments for function and MCP tools.
+  pub arguments: Option<String>,
+  /// `pre_tool_call`: replacement input for freeform tools.
+  pub input: Option<String>,
+  /// `pre_tool_call`: reject the call, returning this message to the model.
+  pub error: Option<String>,
+  /// `post_tool_call`: replacement output.
+  pub output: Option<String>,
+  /// `post_tool_call`: note appended to the output. `post_turn`: background event emitted after
+  /// the turn.
+  pub annotation: Option<String>,
+}
+
+type HookReturn =
+  napi::Either<napi::bindgen_prelude::Promise<Option<JsHookResult>>, Option<JsHookResult>>;
+type HookTsfn =
+  Arc<ThreadsafeFunction<JsHookContext, HookReturn, JsHookContext, napi::Status, false>>;
+
+#[derive(Clone)]
+struct NativeHook {
+  event: HookEvent,
+  callback: HookTsfn,
+}
+
+fn registered_hooks() -> &'static Mutex<Vec<NativeHook>> {
+  static HOOKS: OnceLock<Mutex<Vec<NativeHook>>> = OnceLock::new();
+  HOOKS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn hooks_for(event: HookEvent) -> Vec<HookTsfn> {
+  registered_hooks()
+    .lock()
+    .map(|hooks| {
+      hooks
+        .iter()
+        .filter(|hook| hook.event == event)
+        .map(|hook| Arc::clone(&hook.callback))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn has_hooks(event: HookEvent) -> bool {
+  registered_hooks()
+    .lock()
+    .map(|hooks| hooks.iter().any(|hook| hook.event == event))
+    .unwrap_or(false)
+}
+
+async fn call_hook(callback: &HookTsfn, context: JsHookContext) -> Result<JsHookResult, String> {
+  let event = context.event.clone();
+  let result = match callback.call_async(context).await {
+    Ok(napi::Either::A(promise)) => promise.await,
+    Ok(napi::Either::B(result)) => Ok(result),
+    Err(err) => Err(err),
+  };
+  result
+    .map(Option::unwrap_or_default)
+    .map_err(|err| format!("{event} hook failed: {}", err.reason))
+}
+
+#[napi(
+  ts_args_type = "event: \"pre_turn\" | \"post_turn\" | \"pre_tool_call\" | \"post_tool_call\" | \"session_end\", handler: (context: JsHookContext) => JsHookResult | void | Promise<JsHookResult | void>"
+)]
+pub fn register_hook(
+  env: Env,
+  event: String,
+  handler: Function<JsHookContext, HookReturn>,
+) -> napi::Result<()> {
+  let event = HookEvent::parse(&event)?;
+  let mut tsfn = handler
+    .build_threadsafe_function::<JsHookContext>()
+    .callee_handled::<false>()
+    .build()?;
+  #[allow(deprecated)]
+  let _ = tsfn.unref(&env);
+
+  registered_hooks()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("hooks mutex poisoned: {e}")))?
+    .push(NativeHook {
+      event,
+      callback: Arc::new(tsfn),
+    });
+  Ok(())
+}
+
+#[napi]
+pub fn clear_hooks() -> napi::Result<()> {
+  registered_hooks()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("hooks mutex poisoned: {e}")))?
+    .clear();
+  Ok(())
+}
+
+/// Runs the `pre_turn` hooks, returning the prompt the turn should be sent with.
+async fn run_pre_turn_hooks(thread_id: Option<String>, prompt: String) -> napi::Result<String> {
+  let mut context = JsHookContext {
+    event: HookEvent::PreTurn.as_str().to_string(),
+    thread_id,
+    prompt: Some(prompt),
+    ..Default::default()
+  };
+  for hook in hooks_for(HookEvent::PreTurn) {
+    let result = call_hook(&hook, context.clone())
+      .await
+      .map_err(napi::Error::from_reason)?;
+    if let Some(prompt) = result.prompt {
+      context.prompt = Some(prompt);
+    }
+  }
+  Ok(context.prompt.unwrap_or_default())
+}
+
+/// Runs the `post_turn` hooks, returning the annotations to emit as background events.
+async fn run_post_turn_hooks(
+  thread_id: Option<String>,
+  prompt: String,
+  final_response: Option<String>,
+  error: Option<String>,
+) -> napi::Result<Vec<String>> {
+  let context = JsHookContext {
+    event: HookEvent::PostTurn.as_str().to_string(),
+    thread_id,
+    prompt: Some(prompt),
+    output: final_response,
+    success: Some(error.is_none()),
+    error,
+    ..Default::default()
+  };
+  let mut annotations = Vec::new();
+  for hook in hooks_for(HookEvent::PostTurn) {
+    let result = call_hook(&hook, context.clone())
+      .await
+      .map_err(napi::Error::from_reason)?;
+    annotations.extend(result.annotation);
+  }
+  Ok(annotations)
+}
+
+/// `run_with_post_turn_checks`, followed by the `session_end` hooks. A failing hook only fails
+/// the run if the run itself succeeded.
+fn run_with_session_hooks<F>(options: InternalRunRequest, mut handler: F) -> napi::Result<()>
+where
+  F: FnMut(ExecThreadEvent) + Send + 'static,
+{
+  if !has_hooks(HookEvent::SessionEnd) {
+    return run_with_post_turn_checks(options, handler);
+  }
+  let thread_id = Arc::new(Mutex::new(options.thread_id.clone()));
+  let thread_id_for_events = Arc::clone(&thread_id);
+  let result = run_with_post_turn_checks(options, move |event| {
+    if let ExecThreadEvent::ThreadStarted(ev) = &event
+      && let Ok(mut slot) = thread_id_for_events.lock()
+    {
+      *slot = Some(ev.thread_id.clone());
+    }
+    handler(event);
+  });
+
+  let context = JsHookContext {
+    event: HookEvent::SessionEnd.as_str().to_string(),
+    thread_id: thread_id.lock().ok().and_then(|slot| slot.clone()),
+    success: Some(result.is_ok()),
+    error: result.as_ref().err().map(|err| err.reason.clone()),
+    ..Default::default()
+  };
+  let runtime = tokio::runtime::Runtime::new()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+  let hook_result = runtime.block_on(async {
+    for hook in hooks_for(HookEvent::SessionEnd) {
+      call_hook(&hook, context.clone()).await?;
+    }
+    Ok::<(), String>(())
+  });
+  result?;
+  hook_result.map_err(napi::Error::from_reason)
+}
+
+fn hook_invocation(invocation: &ToolInvocation) -> JsToolInvocation {
+  let (arguments, input) = match &invocation.payload {
+    ToolPayload::Function { arguments } => (Some(arguments.clone()), None),
+    ToolPayload::Custom { input } => (None, Some(input.clone())),
+    ToolPayload::Mcp { raw_arguments, .. } => (Some(raw_arguments.clone()), None),
+    ToolPayload::LocalShell { params } => (
+      Some(
+        json!({
+          "command": params.command,
+          "workdir": params.workdir,
+          "timeout_ms": params.timeout_ms,
+        })
+        .to_string(),
+      ),
+      None,
+    ),
+  };
+  JsToolInvocation {
+    call_id: invocation.call_id.clone(),
+    tool_name: invocation.tool_name.clone(),
+    arguments,
+    input,
+  }
+}
+
+/// Applies a `pre_tool_call` rewrite to the invocation's payload.
+fn rewrite_tool_payload(
+  payload: &mut ToolPayload,
+  arguments: Option<String>,
+  input: Option<String>,
+  tool_name: &str,
+) -> Result<(), FunctionCallError> {
+  match (payload, arguments, input) {
+    (_, None, None) => Ok(()),
+    (ToolPayload::Function { arguments }, Some(replacement), _) => {
+      *arguments = replacement;
+      Ok(())
+    }
+    (ToolPayload::Mcp { raw_arguments, .. }, Some(replacement), _) => {
+      *raw_arguments = replacement;
+      Ok(())
+    }
+    (ToolPayload::Custom { input }, _, Some(replacement)) => {
+      *input = replacement;
+      Ok(())
+    }
+    (ToolPayload::Function { .. } | ToolPayload::Mcp { .. }, None, Some(_))
+    | (ToolPayload::Custom { .. }, Some(_), None)
+    | (ToolPayload::LocalShell { .. }, _, _) => Err(FunctionCallError::Fatal(format!(
+      "pre_tool_call hook returned a rewrite that does not fit tool `{tool_name}`"
+    ))),
+  }
+}
+
+/// Wraps every tool with the `pre_tool_call` and `post_tool_call` hooks.
+struct HookToolInterceptor;
+
+#[async_trait]
+impl ToolInterceptor for HookToolInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    use codex_protocol::models::FunctionCallOutputBody;
+
+    let mut invocation = invocation;
+    let thread_id = Some(invocation.thread_id());
+    for hook in hooks_for(HookEvent::PreToolCall) {
+      let context = JsHookContext {
+        event: HookEvent::PreToolCall.as_str().to_string(),
+        thread_id: thread_id.clone(),
+        invocation: Some(hook_invocation(&invocation)),
+        ..Default::default()
+      };
+      let result = call_hook(&hook, context)
+        .await
+        .map_err(FunctionCallError::Fatal)?;
+      if let Some(error) = result.error {
+        return Err(FunctionCallError::RespondToModel(error));
+      }
+      let tool_name = invocation.tool_name.clone();
+      rewrite_tool_payload(
+        &mut invocation.payload,
+        result.arguments,
+        result.input,
+        &tool_name,
+      )?;
+    }
+
+    let post_hooks = hooks_for(HookEvent::PostToolCall);
+    let js_invocation = hook_invocation(&invocation);
+    let result = next(invocation).await;
+    if post_hooks.is_empty() {
+      return result;
+    }
+
+    let mut context = JsHookContext {
+      event: HookEvent::PostToolCall.as_str().to_string(),
+      thread_id,
+      invocation: Some(js_invocation),
+      ..Default::default()
+    };
+    match &result {
+      Ok(output) => {
+        context.output = match output {
+          ToolOutput::Function { body, .. } => body.to_text(),
+          ToolOutput::Mcp { .. } => Some(output.log_preview()),
+          ToolOutput::Stream { .. } => None,
+        };
+        context.success = Some(output.success_for_logging());
+      }
+      Err(err) => {
+        context.success = Some(false);
+        context.error = Some(err.to_string());
+      }
+    }
+
+    let mut replacement = None;
+    let mut annotations = Vec::new();
+    for hook in post_hooks {
+      let hook_result = call_hook(&hook, context.clone())
+        .await
+        .map_err(FunctionCallError::Fatal)?;
+      if let Some(output) = hook_result.output {
+        context.output = Some(output.clone());
+        replacement = Some(output);
+      }
+      annotations.extend(hook_result.annotation);
+    }
+    if replacement.is_none() && annotations.is_empty() {
+      return result;
+    }
+
+    // Replacements and annotations only apply to text outputs; MCP results and failures that
+    // end the turn pass through untouched.
+    match result {
+      Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(text),
+        success,
+      }) => {
+        let mut text = replacement.unwrap_or(text);
+        for annotation in annotations {
+          text.push_str("\n\n");
+          text.push_str(&annotation);
+        }
+        Ok(ToolOutput::Function {
+          body: FunctionCallOutputBody::Text(text),
+          success,
+        })
+      }
+      Err(FunctionCallError::RespondToModel(message)) => {
+        let mut message = replacement.unwrap_or(message);
+        for annotation in annotations {
+          message.push_str("\n\n");
+          message.push_str(&annotation);
+        }
+        Err(FunctionCallError::RespondToModel(message))
+      }
+      other @ (Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::ContentItems(_),
+        ..
+      })
+      | Ok(ToolOutput::Mcp { .. } | ToolOutput::Stream { .. })
+      | Err(FunctionCallError::MissingLocalShellCallId | FunctionCallError::Fatal(_))) => other,
+    }
+  }
+}
+
+/// The interceptor running the tool-call hooks for this run, if any are registered.
+fn hook_tool_interceptors() -> Vec<ExternalInterceptorRegistration> {
+  if !has_hooks(HookEvent::PreToolCall) && !has_hooks(HookEvent::PostToolCall) {
+    return Vec::new();
+  }
+  vec![ExternalInterceptorRegistration {
+    name: codex_core::ALL_TOOLS_INTERCEPTOR.to_string(),
+    handler: Arc::new(HookToolInterceptor),
+  }]
+}
+
+#[cfg(test)]
+mod tests_hooks {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parses_hook_events() {
+    for name in [
+      "pre_turn",
+      "post_turn",
+      "pre_tool_call",
+      "post_tool_call",
+      "session_end",
+    ] {
+      assert_eq!(HookEvent::parse(name).expect("known event").as_str(), name);
+    }
+    let err = HookEvent::parse("before_turn").expect_err("unknown event");
+    assert!(
+      err.reason.contains("Unsupported hook event: before_turn"),
+      "{}",
+      err.reason
+    );
+  }
+
+  #[test]
+  fn rewrites_fit_the_payload_kind() {
+    let mut payload = ToolPayload::Function {
+      arguments: "{}".to_string(),
+    };
+    rewrite_tool_payload(
+      &mut payload,
+      Some("{\"path\":\"a\"}".to_string()),
+      None,
+      "read_file",
+    )
+    .expect("function rewrite");
+    assert_eq!(payload.log_payload(), "{\"path\":\"a\"}");
+
+    let mut payload = ToolPayload::Custom {
+      input: "old".to_string(),
+    };
+    rewrite_tool_payload(&mut payload, None, Some("new".to_string()), "apply_patch")
+      .expect("custom rewrite");
+    assert_eq!(payload.log_payload(), "new");
+
+    let err = rewrite_tool_payload(&mut payload, Some("{}".to_string()), None, "apply_patch")
+      .expect_err("arguments don't fit a freeform tool");
+    assert_eq!(
+      err,
+      FunctionCallError::Fatal(
+        "pre_tool_call hook returned a rewrite that does not fit tool `apply_patch`".to_string()
+      )
+    );
+  }
+}
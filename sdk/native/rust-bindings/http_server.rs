@@ -0,0 +1,519 @@
+// ============================================================================
+// HTTP server mode (startHttpServer / stopHttpServer)
+// ============================================================================
+//
+// Exposes the SDK as a loopback REST/SSE service, served with axum, so
+// non-Node consumers can drive it without the NAPI layer. Every request needs
+// `Authorization: Bearer <authToken>`, compared in constant time; a random
+// token is generated when none is given. Bodies are the JSON forms of the
+// matching SDK requests. A client that disconnects mid-run cancels the run.
+//
+//   GET  /v1/health
+//   POST /v1/runs            RunRequest -> { events }
+//   POST /v1/runs/stream     RunRequest -> text/event-stream of thread events
+//   POST /v1/threads/fork    ForkRequest -> ForkResult
+//   GET  /v1/threads         ?pageSize=&cursor=&cwd= -> ConversationListPage
+//   POST /v1/reverie/search  { query, limit? } -> ReverieSearchResult[]
+//   GET  /v1/tools           -> NativeToolInfo[]
+//   POST /v1/tools           NativeToolInfo & { webhookUrl, headers? }
+//
+// A tool registered over HTTP is answered by POSTing each call to its
+// webhook, which replies with a NativeToolResponse.
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct HttpServerOptions {
+  /// Loopback port to listen on; any free port when unset.
+  pub port: Option<u32>,
+  /// Bearer token clients must send. Generated when unset.
+  #[napi(js_name = "authToken")]
+  pub auth_token: Option<String>,
+}
+
+#[napi(object)]
+pub struct HttpServerInfo {
+  pub id: String,
+  pub url: String,
+  pub port: u32,
+  #[napi(js_name = "authToken")]
+  pub auth_token: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookToolRegistration {
+  #[serde(flatten)]
+  info: NativeToolInfo,
+  webhook_url: String,
+  #[serde(default)]
+  headers: HashMap<String, String>,
+}
+
+#[derive(serde::Deserialize)]
+struct ReverieSearchRequest {
+  query: String,
+  limit: Option<i32>,
+}
+
+struct WebhookToolHandler {
+  url: String,
+  headers: HashMap<String, String>,
+  client: reqwest::Client,
+}
+
+#[async_trait]
+impl ToolHandler for WebhookToolHandler {
+  fn kind(&self) -> ToolKind {
+    ToolKind::Function
+  }
+
+  async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let (arguments, input) = match &invocation.payload {
+      ToolPayload::Function { arguments } => (Some(arguments.clone()), None),
+      ToolPayload::Custom { input } => (None, Some(input.clone())),
+      _ => {
+        return Err(FunctionCallError::Fatal(format!(
+          "tool `{}` received unsupported payload",
+          invocation.tool_name
+        )));
+      }
+    };
+    let body = json!({
+      "callId": invocation.call_id,
+      "toolName": invocation.tool_name,
+      "threadId": invocation.thread_id(),
+      "arguments": arguments,
+      "input": input,
+    });
+    let mut request = self.client.post(&self.url).json(&body);
+    for (name, value) in &self.headers {
+      request = request.header(name, value);
+    }
+    let failed = |err: String| {
+      FunctionCallError::RespondToModel(format!(
+        "tool `{}` webhook failed: {err}",
+        invocation.tool_name
+      ))
+    };
+    let response = request
+      .send()
+      .await
+      .and_then(reqwest::Response::error_for_status)
+      .map_err(|err| failed(err.to_string()))?;
+    let text = response
+      .text()
+      .await
+      .map_err(|err| failed(err.to_string()))?;
+    let response = serde_json::from_str::<NativeToolResponse>(&text)
+      .map_err(|err| failed(format!("invalid response: {err}")))?;
+    native_response_to_tool_output(response)
+  }
+}
+
+fn http_servers() -> &'static Mutex<HashMap<String, CancellationToken>> {
+  static SERVERS: OnceLock<Mutex<HashMap<String, CancellationToken>>> = OnceLock::new();
+  SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+type HttpResult = Result<JsonValue, (StatusCode, String)>;
+
+fn parse_http_body<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, (StatusCode, String)> {
+  serde_json::from_str(body).map_err(|e| {
+    (
+      StatusCode::BAD_REQUEST,
+      format!("invalid request body: {e}"),
+    )
+  })
+}
+
+fn http_json<T: serde::Serialize>(result: napi::Result<T>) -> HttpResult {
+  let value = result.map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.reason))?;
+  serde_json::to_value(value).map_err(|e| {
+    (
+      StatusCode::INTERNAL_SERVER_ERROR,
+      format!("failed to serialize response: {e}"),
+    )
+  })
+}
+
+fn http_response(result: HttpResult) -> Response {
+  match result {
+    Ok(value) => axum::Json(value).into_response(),
+    Err((status, message)) => (
+      status,
+      axum::Json(json!({ "error": { "message": message } })),
+    )
+      .into_response(),
+  }
+}
+
+/// Whether an `authorization` header value carries `token`, compared in constant time.
+fn bearer_token_matches(authorization: Option<&str>, token: &str) -> bool {
+  token_matches(
+    authorization.and_then(|value| value.strip_prefix("Bearer ")),
+    token,
+  )
+}
+
+/// Whether `candidate` is `token`, compared in constant time.
+fn token_matches(candidate: Option<&str>, token: &str) -> bool {
+  use subtle::ConstantTimeEq;
+
+  candidate.is_some_and(|candidate| bool::from(candidate.as_bytes().ct_eq(token.as_bytes())))
+}
+
+async fn require_http_bearer(
+  axum::extract::State(token): axum::extract::State<Arc<str>>,
+  request: axum::extract::Request,
+  next: axum::middleware::Next,
+) -> Response {
+  let authorization = request
+    .headers()
+    .get(axum::http::header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok());
+  if !bearer_token_matches(authorization, &token) {
+    return http_response(Err((
+      StatusCode::UNAUTHORIZED,
+      "missing or invalid bearer token".to_string(),
+    )));
+  }
+  next.run(request).await
+}
+
+fn http_run_request(body: &str) -> Result<InternalRunRequest, (StatusCode, String)> {
+  parse_http_body::<RunRequest>(body)?
+    .into_internal()
+    .map_err(|err| (StatusCode::BAD_REQUEST, err.reason))
+}
+
+/// Run `options`, passing each stamped event to `on_event`, until the run ends or `cancel` fires.
+fn http_run_blocking(
+  options: InternalRunRequest,
+  cancel: CancellationToken,
+  mut on_event: impl FnMut(JsonValue) + Send + 'static,
+) -> napi::Result<()> {
+  let mut sequencer = ThreadEventSequencer::new(options.thread_id.clone());
+  with_run_cancellation(cancel, || {
+    run_with_session_hooks(options, move |event| match event_to_json(&event) {
+      Ok(value) => on_event(sequencer.stamp(&event, value)),
+      Err(err) => eprintln!("Failed to serialize streamed event: {err}"),
+    })
+  })
+}
+
+async fn http_run(body: String) -> Response {
+  let result = async {
+    let options = http_run_request(&body)?;
+    // Axum drops this future when the client disconnects; the guard then stops the run.
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_for_run = Arc::clone(&events);
+    tokio::task::spawn_blocking(move || {
+      http_run_blocking(options, cancel, move |event| {
+        if let Ok(mut events) = events_for_run.lock() {
+          events.push(event);
+        }
+      })
+    })
+    .await
+    .map_err(|e| {
+      (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("run task failed: {e}"),
+      )
+    })?
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.reason))?;
+    let events = events
+      .lock()
+      .map(|mut events| std::mem::take(&mut *events))
+      .unwrap_or_default();
+    Ok(json!({ "events": events }))
+  };
+  http_response(result.await)
+}
+
+/// Stream a run's events as SSE, ending with a `done` (or `error`) event. The run stops if the
+/// client disconnects first.
+async fn http_run_stream(body: String) -> Response {
+  use axum::response::sse::Event;
+
+  let options = match http_run_request(&body) {
+    Ok(options) => options,
+    Err(err) => return http_response(Err(err)),
+  };
+  let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+  let cancel = CancellationToken::new();
+  cancel_when_closed(tx.clone(), cancel.clone());
+  tokio::task::spawn_blocking(move || {
+    let events_tx = tx.clone();
+    let result = http_run_blocking(options, cancel.clone(), move |event| {
+      let _ = events_tx.send(Event::default().data(event.to_string()));
+    });
+    cancel.cancel();
+    let last = match result {
+      Ok(()) => Event::default().event("done").data("{}"),
+      Err(err) => Event::default()
+        .event("error")
+        .data(json!({ "message": err.reason }).to_string()),
+    };
+    let _ = tx.send(last);
+  });
+  let stream = futures::stream::unfold(rx, |mut rx| async move {
+    rx.recv()
+      .await
+      .map(|event| (Ok::<_, std::convert::Infallible>(event), rx))
+  });
+  axum::response::sse::Sse::new(stream).into_response()
+}
+
+async fn http_fork(body: String) -> Response {
+  let result = match parse_http_body::<ForkRequest>(&body) {
+    Ok(fork) => http_json(fork_thread(fork).await),
+    Err(err) => Err(err),
+  };
+  http_response(result)
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpListThreadsQuery {
+  page_size: Option<u32>,
+  cursor: Option<String>,
+  cwd: Option<String>,
+}
+
+async fn http_list_threads(
+  query: Result<
+    axum::extract::Query<HttpListThreadsQuery>,
+    axum::extract::rejection::QueryRejection,
+  >,
+) -> Response {
+  let query = match query {
+    Ok(axum::extract::Query(query)) => query,
+    Err(rejection) => {
+      return http_response(Err((StatusCode::BAD_REQUEST, rejection.body_text())));
+    }
+  };
+  http_response(http_json(
+    list_conversations(ListConversationsRequest {
+      config: None,
+      page_size: query.page_size,
+      cursor: query.cursor,
+      model_providers: None,
+      cwd: query.cwd,
+    })
+    .await,
+  ))
+}
+
+async fn http_reverie_search(body: String) -> Response {
+  let result = async {
+    let search = parse_http_body::<ReverieSearchRequest>(&body)?;
+    let codex_home = find_codex_home().map_err(|e| {
+      (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("codex home: {e}"),
+      )
+    })?;
+    http_json(
+      reverie_search_conversations(
+        codex_home.to_string_lossy().into_owned(),
+        search.query,
+        search.limit,
+      )
+      .await,
+    )
+  };
+  http_response(result.await)
+}
+
+async fn http_list_tools() -> Response {
+  http_response(http_json(list_registered_tools()))
+}
+
+fn http_register_webhook_tool(body: &str) -> HttpResult {
+  let registration = parse_http_body::<WebhookToolRegistration>(body)?;
+  let client = reqwest::Client::builder().build().map_err(|e| {
+    (
+      StatusCode::INTERNAL_SERVER_ERROR,
+      format!("failed to build webhook client: {e}"),
+    )
+  })?;
+  let info = registration.info.clone();
+  push_registered_tool(
+    registration.info,
+    Arc::new(WebhookToolHandler {
+      url: registration.webhook_url,
+      headers: registration.headers,
+      client,
+    }),
+  )
+  .map_err(|err| (StatusCode::BAD_REQUEST, err.reason))?;
+  http_json(Ok(info))
+}
+
+async fn http_register_tool(body: String) -> Response {
+  http_response(http_register_webhook_tool(&body))
+}
+
+async fn http_no_route(method: axum::http::Method, uri: axum::http::Uri) -> Response {
+  http_response(Err((
+    StatusCode::NOT_FOUND,
+    format!("no route for {method} {}", uri.path()),
+  )))
+}
+
+fn http_router(token: &str) -> axum::Router {
+  use axum::routing::get;
+  use axum::routing::post;
+
+  axum::Router::new()
+    .route(
+      "/v1/health",
+      get(|| async { http_response(Ok(json!({ "ok": true }))) }),
+    )
+    .route("/v1/runs", post(http_run))
+    .route("/v1/runs/stream", post(http_run_stream))
+    .route("/v1/threads/fork", post(http_fork))
+    .route("/v1/threads", get(http_list_threads))
+    .route("/v1/reverie/search", post(http_reverie_search))
+    .route("/v1/tools", get(http_list_tools).post(http_register_tool))
+    .fallback(http_no_route)
+    .method_not_allowed_fallback(http_no_route)
+    .layer(axum::middleware::from_fn_with_state(
+      Arc::<str>::from(token),
+      require_http_bearer,
+    ))
+}
+
+/// Serve the SDK over HTTP on 127.0.0.1 until stopHttpServer().
+#[napi]
+pub fn start_http_server(options: Option<HttpServerOptions>) -> napi::Result<HttpServerInfo> {
+  let options = options.unwrap_or_default();
+  let port = u16::try_from(options.port.unwrap_or(0))
+    .map_err(|_| napi::Error::from_reason("port must be between 0 and 65535"))?;
+  let token = match options.auth_token {
+    Some(token) if token.trim().is_empty() => {
+      return Err(napi::Error::from_reason("authToken must not be empty"));
+    }
+    Some(token) => token,
+    None => Uuid::new_v4().simple().to_string(),
+  };
+  let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+    .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to bind HTTP server: {e}")))?;
+  let addr = listener
+    .local_addr()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read HTTP server address: {e}")))?;
+  let runtime = tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+
+  let id = Uuid::new_v4().to_string();
+  let stopped = CancellationToken::new();
+  let stopped_for_server = stopped.clone();
+  let router = http_router(&token);
+  std::thread::Builder::new()
+    .name("codex-http-server".to_string())
+    .spawn(move || {
+      runtime.block_on(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+          Ok(listener) => listener,
+          Err(err) => {
+            eprintln!("Failed to start HTTP server: {err}");
+            return;
+          }
+        };
+        let served = axum::serve(listener, router)
+          .with_graceful_shutdown(stopped_for_server.cancelled_owned())
+          .await;
+        if let Err(err) = served {
+          eprintln!("HTTP server stopped: {err}");
+        }
+      });
+    })
+    .map_err(|e| napi::Error::from_reason(format!("Failed to spawn HTTP server: {e}")))?;
+
+  http_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("HTTP servers mutex poisoned: {e}")))?
+    .insert(id.clone(), stopped);
+
+  Ok(HttpServerInfo {
+    id,
+    url: format!("http://{addr}"),
+    port: u32::from(addr.port()),
+    auth_token: token,
+  })
+}
+
+/// Stop accepting requests. Runs in progress finish. Returns `false` for unknown ids.
+#[napi]
+pub fn stop_http_server(id: String) -> napi::Result<bool> {
+  let stopped = http_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("HTTP servers mutex poisoned: {e}")))?
+    .remove(&id);
+  if let Some(stopped) = &stopped {
+    stopped.cancel();
+  }
+  Ok(stopped.is_some())
+}
+
+#[cfg(test)]
+mod tests_http_server {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn requires_the_bearer_token_and_rejects_bad_requests() {
+    let info = start_http_server(Some(HttpServerOptions {
+      port: None,
+      auth_token: Some("secret".to_string()),
+    }))
+    .unwrap();
+    let client = reqwest::blocking::Client::new();
+    let status =
+      |request: reqwest::blocking::RequestBuilder| request.send().unwrap().status().as_u16();
+
+    assert_eq!(status(client.get(format!("{}/v1/health", info.url))), 401);
+    assert_eq!(
+      status(
+        client
+          .get(format!("{}/v1/health", info.url))
+          .bearer_auth("wrong")
+      ),
+      401
+    );
+    let health = client
+      .get(format!("{}/v1/health", info.url))
+      .bearer_auth("secret")
+      .send()
+      .unwrap();
+    assert_eq!(health.status().as_u16(), 200);
+    assert_eq!(health.json::<JsonValue>().unwrap(), json!({ "ok": true }));
+
+    assert_eq!(
+      status(
+        client
+          .post(format!("{}/v1/runs", info.url))
+          .bearer_auth("secret")
+          .body("{")
+      ),
+      400
+    );
+    assert_eq!(
+      status(
+        client
+          .delete(format!("{}/v1/runs", info.url))
+          .bearer_auth("secret")
+      ),
+      404
+    );
+
+    assert!(stop_http_server(info.id.clone()).unwrap());
+    assert!(!stop_http_server(info.id).unwrap());
+  }
+}
@@ -0,0 +1,308 @@
+// ============================================================================
+// Image attachments (prepareImage)
+// ============================================================================
+//
+// Screenshots straight off a retina display are easily several megabytes, and
+// an oversized attachment used to fail deep inside the request as an opaque
+// API error. prepareImage() reads an image from a file or the clipboard,
+// checks its format up front, scales it to fit `maxDimension` and re-encodes
+// it. The prepared file goes in a run's `images` (or a `local_image` input)
+// like any other.
+
+const DEFAULT_IMAGE_MAX_DIMENSION: u32 = 2048;
+const PREPARED_JPEG_QUALITY: u8 = 85;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct PrepareImageOptions {
+  /// Longest side of the prepared image in pixels (default 2048). Smaller images are not
+  /// scaled up.
+  #[napi(js_name = "maxDimension")]
+  pub max_dimension: Option<u32>,
+  /// `png` or `jpeg`. Defaults to `jpeg` for JPEG sources and `png` otherwise.
+  pub format: Option<String>,
+  /// Where to write the prepared image; defaults to a new file in the temp directory.
+  #[napi(js_name = "outputPath")]
+  pub output_path: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedImage {
+  pub path: String,
+  /// `png` or `jpeg`.
+  pub format: String,
+  pub width: u32,
+  pub height: u32,
+  #[napi(js_name = "originalWidth")]
+  pub original_width: u32,
+  #[napi(js_name = "originalHeight")]
+  pub original_height: u32,
+  /// Size of the prepared file in bytes.
+  pub bytes: u32,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct ClipboardImageSource {
+  pub clipboard: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum ImageSource {
+  Path(PathBuf),
+  Clipboard,
+}
+
+/// Formats the model accepts as input; anything else is rejected before it is attached.
+fn supported_image_format(bytes: &[u8], origin: &str) -> napi::Result<image::ImageFormat> {
+  let format = image::guess_format(bytes).map_err(|_| {
+    napi::Error::from_reason(format!(
+      "{origin} is not a recognized image; expected PNG, JPEG, GIF or WebP"
+    ))
+  })?;
+  match format {
+    image::ImageFormat::Png
+    | image::ImageFormat::Jpeg
+    | image::ImageFormat::Gif
+    | image::ImageFormat::WebP => Ok(format),
+    other => Err(napi::Error::from_reason(format!(
+      "{origin} is a {other:?} image, which cannot be attached; expected PNG, JPEG, GIF or WebP"
+    ))),
+  }
+}
+
+fn prepared_image_format(
+  requested: Option<&str>,
+  source_format: Option<image::ImageFormat>,
+) -> napi::Result<image::ImageFormat> {
+  match requested.map(str::trim) {
+    None | Some("") => Ok(match source_format {
+      Some(image::ImageFormat::Jpeg) => image::ImageFormat::Jpeg,
+      _ => image::ImageFormat::Png,
+    }),
+    Some("png") => Ok(image::ImageFormat::Png),
+    Some("jpeg" | "jpg") => Ok(image::ImageFormat::Jpeg),
+    Some(other) => Err(napi::Error::from_reason(format!(
+      "Unsupported prepareImage format `{other}`; expected `png` or `jpeg`"
+    ))),
+  }
+}
+
+fn read_image_file(path: &Path) -> napi::Result<(image::DynamicImage, image::ImageFormat)> {
+  let origin = path.display().to_string();
+  let bytes = std::fs::read(path)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read {origin}: {e}")))?;
+  let format = supported_image_format(&bytes, &origin)?;
+  let image = image::load_from_memory_with_format(&bytes, format)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to decode {origin}: {e}")))?;
+  Ok((image, format))
+}
+
+/// The clipboard image, or the image file copied to the clipboard.
+#[cfg(not(target_os = "android"))]
+fn read_clipboard_image() -> napi::Result<(image::DynamicImage, Option<image::ImageFormat>)> {
+  let mut clipboard = arboard::Clipboard::new()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to open the clipboard: {e}")))?;
+  if let Ok(files) = clipboard.get().file_list()
+    && let Some(path) = files.into_iter().next()
+  {
+    let (image, format) = read_image_file(&path)?;
+    return Ok((image, Some(format)));
+  }
+  let data = clipboard
+    .get_image()
+    .map_err(|e| napi::Error::from_reason(format!("The clipboard holds no image: {e}")))?;
+  let (width, height) = (data.width as u32, data.height as u32);
+  let rgba = image::RgbaImage::from_raw(width, height, data.bytes.into_owned())
+    .ok_or_else(|| napi::Error::from_reason("The clipboard image is malformed"))?;
+  Ok((image::DynamicImage::ImageRgba8(rgba), None))
+}
+
+#[cfg(target_os = "android")]
+fn read_clipboard_image() -> napi::Result<(image::DynamicImage, Option<image::ImageFormat>)> {
+  Err(napi::Error::from_reason(
+    "Clipboard images are not supported on Android",
+  ))
+}
+
+/// `image` scaled to fit `max_dimension` and encoded as `format`.
+fn encode_prepared_image(
+  image: &image::DynamicImage,
+  max_dimension: u32,
+  format: image::ImageFormat,
+) -> napi::Result<(Vec<u8>, u32, u32)> {
+  let resized;
+  let image = if image.width() > max_dimension || image.height() > max_dimension {
+    resized = image.resize(
+      max_dimension,
+      max_dimension,
+      image::imageops::FilterType::Triangle,
+    );
+    &resized
+  } else {
+    image
+  };
+
+  let mut buffer = Vec::new();
+  match format {
+    image::ImageFormat::Jpeg => {
+      // JPEG has no alpha channel.
+      let rgb = image::DynamicImage::ImageRgb8(image.to_rgb8());
+      image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, PREPARED_JPEG_QUALITY)
+        .encode_image(&rgb)
+    }
+    _ => image::DynamicImage::ImageRgba8(image.to_rgba8()).write_to(
+      &mut std::io::Cursor::new(&mut buffer),
+      image::ImageFormat::Png,
+    ),
+  }
+  .map_err(|e| napi::Error::from_reason(format!("Failed to encode image: {e}")))?;
+  Ok((buffer, image.width(), image.height()))
+}
+
+fn prepare_image_sync(
+  source: ImageSource,
+  options: PrepareImageOptions,
+) -> napi::Result<PreparedImage> {
+  let max_dimension = options.max_dimension.unwrap_or(DEFAULT_IMAGE_MAX_DIMENSION);
+  if max_dimension == 0 {
+    return Err(napi::Error::from_reason(
+      "prepareImage maxDimension must be greater than 0",
+    ));
+  }
+  let (image, source_format) = match source {
+    ImageSource::Path(path) => {
+      let (image, format) = read_image_file(&path)?;
+      (image, Some(format))
+    }
+    ImageSource::Clipboard => read_clipboard_image()?,
+  };
+  let format = prepared_image_format(options.format.as_deref(), source_format)?;
+  let (encoded, width, height) = encode_prepared_image(&image, max_dimension, format)?;
+
+  let extension = match format {
+    image::ImageFormat::Jpeg => "jpeg",
+    _ => "png",
+  };
+  let path = match options.output_path {
+    Some(path) => PathBuf::from(path),
+    None => {
+      let (_, path) = tempfile::Builder::new()
+        .prefix("codex-image-")
+        .suffix(&format!(".{extension}"))
+        .tempfile()
+        .and_then(|file| file.keep().map_err(|e| e.error))
+        .map_err(|e| napi::Error::from_reason(format!("Failed to create image file: {e}")))?;
+      path
+    }
+  };
+  std::fs::write(&path, &encoded)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to write {}: {e}", path.display())))?;
+
+  Ok(PreparedImage {
+    path: path.to_string_lossy().into_owned(),
+    format: extension.to_string(),
+    width,
+    height,
+    original_width: image.width(),
+    original_height: image.height(),
+    bytes: encoded.len() as u32,
+  })
+}
+
+/// Read an image from a file or the clipboard, validate it, scale it to fit and re-encode it for
+/// attaching to a run.
+#[napi]
+pub async fn prepare_image(
+  source: napi::Either<String, ClipboardImageSource>,
+  options: Option<PrepareImageOptions>,
+) -> napi::Result<PreparedImage> {
+  let source = match source {
+    napi::Either::A(path) => ImageSource::Path(PathBuf::from(path)),
+    napi::Either::B(ClipboardImageSource { clipboard: true }) => ImageSource::Clipboard,
+    napi::Either::B(ClipboardImageSource { clipboard: false }) => {
+      return Err(napi::Error::from_reason(
+        "prepareImage needs a path or { clipboard: true }",
+      ));
+    }
+  };
+  let options = options.unwrap_or_default();
+  tokio::task::spawn_blocking(move || prepare_image_sync(source, options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))?
+}
+
+#[cfg(test)]
+mod tests_images {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn prepared_images_are_scaled_reencoded_and_validated() {
+    let dir = tempfile::tempdir().unwrap();
+    let screenshot = dir.path().join("screenshot.png");
+    image::RgbaImage::from_pixel(4000, 1000, image::Rgba([10, 20, 30, 255]))
+      .save(&screenshot)
+      .unwrap();
+
+    let output = dir.path().join("prepared.jpeg");
+    let prepared = prepare_image_sync(
+      ImageSource::Path(screenshot.clone()),
+      PrepareImageOptions {
+        max_dimension: Some(1000),
+        format: Some("jpeg".to_string()),
+        output_path: Some(output.to_string_lossy().into_owned()),
+      },
+    )
+    .unwrap();
+    let bytes = std::fs::read(&output).unwrap();
+    assert_eq!(
+      prepared,
+      PreparedImage {
+        path: output.to_string_lossy().into_owned(),
+        format: "jpeg".to_string(),
+        width: 1000,
+        height: 250,
+        original_width: 4000,
+        original_height: 1000,
+        bytes: bytes.len() as u32,
+      }
+    );
+    assert_eq!(
+      image::guess_format(&bytes).unwrap(),
+      image::ImageFormat::Jpeg
+    );
+
+    // By default the image fits 2048 pixels and stays PNG, in a temp file.
+    let prepared = prepare_image_sync(
+      ImageSource::Path(screenshot),
+      PrepareImageOptions::default(),
+    )
+    .unwrap();
+    assert_eq!((prepared.width, prepared.format.as_str()), (2048, "png"));
+    assert!(prepared.path.ends_with(".png"));
+    std::fs::remove_file(&prepared.path).unwrap();
+
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, "not an image").unwrap();
+    assert_eq!(
+      prepare_image_sync(
+        ImageSource::Path(notes.clone()),
+        PrepareImageOptions::default()
+      )
+      .err()
+      .unwrap()
+      .reason,
+      format!(
+        "{} is not a recognized image; expected PNG, JPEG, GIF or WebP",
+        notes.display()
+      )
+    );
+    assert!(prepared_image_format(Some("tiff"), None).is_err());
+    assert_eq!(
+      prepared_image_format(None, Some(image::ImageFormat::Jpeg)).unwrap(),
+      image::ImageFormat::Jpeg
+    );
+  }
+}
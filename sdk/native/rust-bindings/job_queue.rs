@@ -0,0 +1,588 @@
+// ============================================================================
+// Job queue (enqueueRun / queueStatus / cancelJob / configureJobQueue)
+// ============================================================================
+//
+// enqueueRun() queues a RunRequest instead of starting it, so backends that
+// embed the SDK get ordered, bounded agent work without a global mutex of
+// their own. Jobs run highest `priority` first, then in enqueue order, at most
+// `concurrency` at a time (configureJobQueue(), default 1). A run that fails,
+// or ends its turn with `turn.failed`, is retried until it has been attempted
+// `maxAttempts` times.
+//
+// Every change to a job is appended to `$CODEX_HOME/job_queue.jsonl` (without
+// the request's `apiKey`). The first queue call in a process replays and
+// compacts the journal and resumes its work: queued jobs run again, and jobs
+// that were running when the previous process exited are queued for another
+// attempt. Use the queue from one process per codex home.
+
+const JOB_JOURNAL_FILE: &str = "job_queue.jsonl";
+const DEFAULT_JOB_MAX_ATTEMPTS: u32 = 3;
+/// Finished jobs kept when the journal is compacted; older ones are dropped.
+const MAX_FINISHED_JOBS: usize = 200;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct EnqueueRunOptions {
+  /// Higher runs first (default 0).
+  pub priority: Option<i32>,
+  /// Attempts before a failing job is given up on (default 3).
+  #[napi(js_name = "maxAttempts")]
+  pub max_attempts: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobInfo {
+  pub id: String,
+  pub prompt: String,
+  pub priority: i32,
+  /// `queued`, `running`, `succeeded`, `failed` or `cancelled`.
+  pub status: String,
+  pub attempts: u32,
+  #[napi(js_name = "maxAttempts")]
+  pub max_attempts: u32,
+  #[napi(js_name = "enqueuedAt")]
+  pub enqueued_at: String,
+  /// When the latest attempt started.
+  #[napi(js_name = "startedAt")]
+  pub started_at: Option<String>,
+  #[napi(js_name = "finishedAt")]
+  pub finished_at: Option<String>,
+  /// Thread of the latest attempt.
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  /// Error of the latest failed attempt.
+  pub error: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueueStatus {
+  pub queued: u32,
+  pub running: u32,
+  pub concurrency: u32,
+  /// Queued, running and recently finished jobs, in enqueue order.
+  pub jobs: Vec<JobInfo>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+  Queued,
+  Running,
+  Succeeded,
+  Failed,
+  Cancelled,
+}
+
+impl JobStatus {
+  fn as_str(self) -> &'static str {
+    match self {
+      JobStatus::Queued => "queued",
+      JobStatus::Running => "running",
+      JobStatus::Succeeded => "succeeded",
+      JobStatus::Failed => "failed",
+      JobStatus::Cancelled => "cancelled",
+    }
+  }
+
+  fn is_finished(self) -> bool {
+    match self {
+      JobStatus::Queued | JobStatus::Running => false,
+      JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled => true,
+    }
+  }
+}
+
+/// One journal line: the whole job after a change.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct StoredJob {
+  id: String,
+  /// Enqueue order.
+  seq: u64,
+  /// The RunRequest as JSON, without `apiKey`.
+  request: JsonValue,
+  priority: i32,
+  max_attempts: u32,
+  attempts: u32,
+  status: JobStatus,
+  enqueued_at: String,
+  #[serde(default)]
+  started_at: Option<String>,
+  #[serde(default)]
+  finished_at: Option<String>,
+  #[serde(default)]
+  thread_id: Option<String>,
+  #[serde(default)]
+  error: Option<String>,
+}
+
+impl StoredJob {
+  fn info(&self) -> JobInfo {
+    JobInfo {
+      id: self.id.clone(),
+      prompt: self
+        .request
+        .get("prompt")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string(),
+      priority: self.priority,
+      status: self.status.as_str().to_string(),
+      attempts: self.attempts,
+      max_attempts: self.max_attempts,
+      enqueued_at: self.enqueued_at.clone(),
+      started_at: self.started_at.clone(),
+      finished_at: self.finished_at.clone(),
+      thread_id: self.thread_id.clone(),
+      error: self.error.clone(),
+    }
+  }
+}
+
+struct JobQueue {
+  /// The journal every change to a job is appended to.
+  journal: PathBuf,
+  jobs: HashMap<String, StoredJob>,
+  /// Requests with their `apiKey`, for unfinished jobs enqueued by this process.
+  requests: HashMap<String, JsonValue>,
+  /// Stops the current attempt of each running job.
+  cancels: HashMap<String, CancellationToken>,
+  running: u32,
+  concurrency: u32,
+  next_seq: u64,
+}
+
+fn job_journal_path() -> io::Result<PathBuf> {
+  Ok(find_codex_home()?.join(JOB_JOURNAL_FILE))
+}
+
+/// The latest state of each job in `journal`. A torn last line from a crash is skipped.
+fn replay_job_journal(journal: &str) -> HashMap<String, StoredJob> {
+  let mut jobs = HashMap::new();
+  for line in journal.lines().filter(|line| !line.trim().is_empty()) {
+    match serde_json::from_str::<StoredJob>(line) {
+      Ok(job) => {
+        jobs.insert(job.id.clone(), job);
+      }
+      Err(err) => eprintln!("Skipping unreadable job journal entry: {err}"),
+    }
+  }
+  jobs
+}
+
+/// Requeue the jobs a previous process was running, and drop the oldest finished jobs.
+fn recover_jobs(jobs: &mut HashMap<String, StoredJob>) {
+  for job in jobs.values_mut() {
+    if job.status != JobStatus::Running {
+      continue;
+    }
+    job.error = Some("the host process exited during the run".to_string());
+    if job.attempts < job.max_attempts {
+      job.status = JobStatus::Queued;
+    } else {
+      job.status = JobStatus::Failed;
+      job.finished_at = Some(format_rollout_timestamp(SystemTime::now()));
+    }
+  }
+  let mut finished: Vec<(u64, String)> = jobs
+    .values()
+    .filter(|job| job.status.is_finished())
+    .map(|job| (job.seq, job.id.clone()))
+    .collect();
+  if finished.len() > MAX_FINISHED_JOBS {
+    finished.sort();
+    for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+      jobs.remove(id);
+    }
+  }
+}
+
+/// The queued job to run next: highest priority, then first enqueued.
+fn next_queued_job(jobs: &HashMap<String, StoredJob>) -> Option<String> {
+  jobs
+    .values()
+    .filter(|job| job.status == JobStatus::Queued)
+    .max_by_key(|job| (job.priority, std::cmp::Reverse(job.seq)))
+    .map(|job| job.id.clone())
+}
+
+/// Replay and compact the journal at `path` into a queue that appends to it.
+fn load_job_queue(path: PathBuf) -> io::Result<JobQueue> {
+  let journal = match std::fs::read_to_string(&path) {
+    Ok(journal) => journal,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+    Err(err) => return Err(err),
+  };
+  let mut jobs = replay_job_journal(&journal);
+  recover_jobs(&mut jobs);
+
+  let mut compacted: Vec<&StoredJob> = jobs.values().collect();
+  compacted.sort_by_key(|job| job.seq);
+  let mut contents = String::new();
+  for job in &compacted {
+    contents.push_str(&serde_json::to_string(job).map_err(io::Error::other)?);
+    contents.push('\n');
+  }
+  let temp = path.with_extension("jsonl.tmp");
+  std::fs::write(&temp, contents)?;
+  std::fs::rename(&temp, &path)?;
+
+  let next_seq = compacted.last().map_or(0, |job| job.seq + 1);
+  Ok(JobQueue {
+    journal: path,
+    jobs,
+    requests: HashMap::new(),
+    cancels: HashMap::new(),
+    running: 0,
+    concurrency: 1,
+    next_seq,
+  })
+}
+
+fn journal_job(journal: &Path, job: &StoredJob) -> io::Result<()> {
+  let mut line = serde_json::to_string(job).map_err(io::Error::other)?;
+  line.push('\n');
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(journal)?
+    .write_all(line.as_bytes())
+}
+
+/// Run `change` on the queue, loading it on first use, then start whatever may run now.
+fn with_job_queue<T>(change: impl FnOnce(&mut JobQueue) -> napi::Result<T>) -> napi::Result<T> {
+  static QUEUE: Mutex<Option<JobQueue>> = Mutex::new(None);
+  let mut slot = QUEUE
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("job queue mutex poisoned: {e}")))?;
+  if slot.is_none() {
+    let queue = job_journal_path()
+      .and_then(load_job_queue)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to load job queue: {e}")))?;
+    *slot = Some(queue);
+  }
+  let Some(queue) = slot.as_mut() else {
+    return Err(napi::Error::from_reason("job queue not loaded"));
+  };
+  let result = change(queue);
+  dispatch_jobs(queue);
+  result
+}
+
+fn dispatch_jobs(queue: &mut JobQueue) {
+  while queue.running < queue.concurrency {
+    let Some(id) = next_queued_job(&queue.jobs) else {
+      return;
+    };
+    let Some(job) = queue.jobs.get_mut(&id) else {
+      return;
+    };
+    job.status = JobStatus::Running;
+    job.attempts += 1;
+    job.started_at = Some(format_rollout_timestamp(SystemTime::now()));
+    if let Err(err) = journal_job(&queue.journal, job) {
+      eprintln!("Failed to journal job {id}: {err}");
+    }
+    let request = queue
+      .requests
+      .get(&id)
+      .cloned()
+      .unwrap_or_else(|| job.request.clone());
+    queue.running += 1;
+    let cancel = CancellationToken::new();
+    queue.cancels.insert(id.clone(), cancel.clone());
+    let job_id = id.clone();
+    let spawned = std::thread::Builder::new()
+      .name("codex-job".to_string())
+      .spawn(move || run_job(job_id, request, cancel));
+    if let Err(err) = spawned {
+      finish_job(queue, &id, None, Err(format!("Failed to start run: {err}")));
+    }
+  }
+}
+
+fn run_job(id: String, request: JsonValue, cancel: CancellationToken) {
+  let thread_id = Arc::new(Mutex::new(None));
+  let turn_error = Arc::new(Mutex::new(None));
+  let result = run_request_from_json(&request).and_then(|options| {
+    let thread_id = Arc::clone(&thread_id);
+    let turn_error = Arc::clone(&turn_error);
+    with_run_cancellation(cancel, || {
+      run_with_session_hooks(options, move |event| match &event {
+        ExecThreadEvent::ThreadStarted(started) => {
+          if let Ok(mut slot) = thread_id.lock() {
+            *slot = Some(started.thread_id.clone());
+          }
+        }
+        ExecThreadEvent::TurnFailed(failed) => {
+          if let Ok(mut slot) = turn_error.lock() {
+            *slot = Some(failed.error.message.clone());
+          }
+        }
+        _ => {}
+      })
+    })
+  });
+  let turn_error = turn_error.lock().ok().and_then(|slot| slot.clone());
+  let outcome = match (result, turn_error) {
+    (Err(err), _) => Err(err.reason),
+    (Ok(()), Some(message)) => Err(message),
+    (Ok(()), None) => Ok(()),
+  };
+  let thread_id = thread_id.lock().ok().and_then(|slot| slot.clone());
+  if let Err(err) = with_job_queue(|queue| {
+    finish_job(queue, &id, thread_id, outcome);
+    Ok(())
+  }) {
+    eprintln!("Failed to record job {id}: {}", err.reason);
+  }
+}
+
+/// Record the end of an attempt: success, another attempt, or failure.
+fn finish_job(
+  queue: &mut JobQueue,
+  id: &str,
+  thread_id: Option<String>,
+  outcome: Result<(), String>,
+) {
+  queue.running = queue.running.saturating_sub(1);
+  queue.cancels.remove(id);
+  let Some(job) = queue.jobs.get_mut(id) else {
+    return;
+  };
+  if thread_id.is_some() {
+    job.thread_id = thread_id;
+  }
+  match outcome {
+    Ok(()) => {
+      job.error = None;
+      // A run that ends as cancelJob() stops it is neither retried nor counted as a success.
+      if job.status != JobStatus::Cancelled {
+        job.status = JobStatus::Succeeded;
+      }
+    }
+    Err(error) => {
+      job.error = Some(error);
+      if job.status != JobStatus::Cancelled {
+        job.status = if job.attempts < job.max_attempts {
+          JobStatus::Queued
+        } else {
+          JobStatus::Failed
+        };
+      }
+    }
+  }
+  if job.status.is_finished() {
+    job.finished_at = Some(format_rollout_timestamp(SystemTime::now()));
+    queue.requests.remove(id);
+  }
+  if let Err(err) = journal_job(&queue.journal, job) {
+    eprintln!("Failed to journal job {id}: {err}");
+  }
+}
+
+/// Queue `request` to run once the jobs ahead of it finish.
+#[napi]
+pub fn enqueue_run(
+  request: RunRequest,
+  options: Option<EnqueueRunOptions>,
+) -> napi::Result<JobInfo> {
+  let options = options.unwrap_or_default();
+  let max_attempts = options.max_attempts.unwrap_or(DEFAULT_JOB_MAX_ATTEMPTS);
+  if max_attempts == 0 {
+    return Err(napi::Error::from_reason(
+      "maxAttempts must be greater than zero",
+    ));
+  }
+  let (request, stored_request) = persistable_run_request(&request)?;
+  with_job_queue(|queue| {
+    let job = StoredJob {
+      id: Uuid::new_v4().to_string(),
+      seq: queue.next_seq,
+      request: stored_request,
+      priority: options.priority.unwrap_or(0),
+      max_attempts,
+      attempts: 0,
+      status: JobStatus::Queued,
+      enqueued_at: format_rollout_timestamp(SystemTime::now()),
+      started_at: None,
+      finished_at: None,
+      thread_id: None,
+      error: None,
+    };
+    journal_job(&queue.journal, &job)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to journal job: {e}")))?;
+    queue.next_seq += 1;
+    queue.requests.insert(job.id.clone(), request);
+    let info = job.info();
+    queue.jobs.insert(job.id.clone(), job);
+    Ok(info)
+  })
+}
+
+#[napi]
+pub fn queue_status() -> napi::Result<QueueStatus> {
+  with_job_queue(|queue| {
+    let mut jobs: Vec<&StoredJob> = queue.jobs.values().collect();
+    jobs.sort_by_key(|job| job.seq);
+    let count = |status: JobStatus| jobs.iter().filter(|job| job.status == status).count() as u32;
+    Ok(QueueStatus {
+      queued: count(JobStatus::Queued),
+      running: queue.running,
+      concurrency: queue.concurrency,
+      jobs: jobs.iter().map(|job| job.info()).collect(),
+    })
+  })
+}
+
+/// Cancel a queued job, or stop a running one without retrying it. Returns `false` for unknown
+/// or finished jobs.
+#[napi]
+pub fn cancel_job(id: String) -> napi::Result<bool> {
+  with_job_queue(|queue| cancel_queued_job(queue, &id))
+}
+
+fn cancel_queued_job(queue: &mut JobQueue, id: &str) -> napi::Result<bool> {
+  let Some(job) = queue.jobs.get_mut(id) else {
+    return Ok(false);
+  };
+  match job.status {
+    JobStatus::Queued => {
+      job.finished_at = Some(format_rollout_timestamp(SystemTime::now()));
+      queue.requests.remove(id);
+    }
+    // The attempt ends with a "Run cancelled" error, which finish_job() records.
+    JobStatus::Running => {
+      if let Some(cancel) = queue.cancels.get(id) {
+        cancel.cancel();
+      }
+    }
+    JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled => return Ok(false),
+  }
+  job.status = JobStatus::Cancelled;
+  journal_job(&queue.journal, job)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to journal job: {e}")))?;
+  Ok(true)
+}
+
+/// Set how many jobs run at once (default 1).
+#[napi]
+pub fn configure_job_queue(concurrency: u32) -> napi::Result<()> {
+  if concurrency == 0 {
+    return Err(napi::Error::from_reason(
+      "concurrency must be greater than zero",
+    ));
+  }
+  with_job_queue(|queue| {
+    queue.concurrency = concurrency;
+    Ok(())
+  })
+}
+
+#[cfg(test)]
+mod tests_job_queue {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn job(id: &str, seq: u64, priority: i32, status: JobStatus, attempts: u32) -> StoredJob {
+    StoredJob {
+      id: id.to_string(),
+      seq,
+      request: json!({ "prompt": format!("job {id}") }),
+      priority,
+      max_attempts: 2,
+      attempts,
+      status,
+      enqueued_at: "2026-10-14T09:00:00Z".to_string(),
+      started_at: None,
+      finished_at: None,
+      thread_id: None,
+      error: None,
+    }
+  }
+
+  #[test]
+  fn journal_replay_recovers_interrupted_jobs() {
+    let line = |job: &StoredJob| serde_json::to_string(job).unwrap();
+    let journal = [
+      line(&job("a", 0, 0, JobStatus::Queued, 0)),
+      line(&job("b", 1, 0, JobStatus::Queued, 0)),
+      line(&job("c", 2, 5, JobStatus::Queued, 0)),
+      line(&job("a", 0, 0, JobStatus::Running, 1)),
+      line(&job("b", 1, 0, JobStatus::Running, 2)),
+      line(&job("d", 3, 9, JobStatus::Succeeded, 1)),
+      "{\"id\":\"e\",\"se".to_string(),
+    ]
+    .join("\n");
+    let mut jobs = replay_job_journal(&journal);
+    recover_jobs(&mut jobs);
+
+    let state = |id: &str| (jobs[id].status, jobs[id].attempts);
+    assert_eq!(jobs.len(), 4);
+    assert_eq!(state("a"), (JobStatus::Queued, 1));
+    assert_eq!(state("b"), (JobStatus::Failed, 2));
+    assert_eq!(state("d"), (JobStatus::Succeeded, 1));
+    assert_eq!(
+      jobs["a"].error.as_deref(),
+      Some("the host process exited during the run")
+    );
+    assert!(jobs["b"].finished_at.is_some());
+
+    assert_eq!(next_queued_job(&jobs), Some("c".to_string()));
+    jobs.remove("c");
+    assert_eq!(next_queued_job(&jobs), Some("a".to_string()));
+  }
+
+  #[test]
+  fn loading_compacts_the_journal_and_appends_to_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(JOB_JOURNAL_FILE);
+    let line = |job: &StoredJob| serde_json::to_string(job).unwrap();
+    std::fs::write(
+      &path,
+      [
+        line(&job("a", 0, 0, JobStatus::Queued, 0)),
+        line(&job("a", 0, 0, JobStatus::Running, 1)),
+        line(&job("b", 1, 0, JobStatus::Queued, 0)),
+      ]
+      .join("\n"),
+    )
+    .unwrap();
+
+    let queue = load_job_queue(path.clone()).unwrap();
+    assert_eq!(queue.next_seq, 2);
+    assert_eq!(queue.jobs["a"].status, JobStatus::Queued);
+    let compacted = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(compacted.lines().count(), 2);
+
+    journal_job(&queue.journal, &job("c", 2, 0, JobStatus::Queued, 0)).unwrap();
+    let replayed = replay_job_journal(&std::fs::read_to_string(&path).unwrap());
+    assert_eq!(replayed.len(), 3);
+    assert_eq!(replayed["a"], queue.jobs["a"]);
+  }
+
+  #[test]
+  fn cancelling_a_running_job_stops_its_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut queue = load_job_queue(dir.path().join(JOB_JOURNAL_FILE)).unwrap();
+    queue
+      .jobs
+      .insert("a".to_string(), job("a", 0, 0, JobStatus::Running, 1));
+    let cancel = CancellationToken::new();
+    queue.cancels.insert("a".to_string(), cancel.clone());
+    queue.running = 1;
+
+    assert!(cancel_queued_job(&mut queue, "a").unwrap());
+    assert!(cancel.is_cancelled());
+    assert_eq!(queue.jobs["a"].status, JobStatus::Cancelled);
+
+    finish_job(&mut queue, "a", None, Err("Run cancelled".to_string()));
+    assert_eq!(queue.jobs["a"].status, JobStatus::Cancelled);
+    assert_eq!(queue.jobs["a"].attempts, 1);
+    assert!(queue.jobs["a"].finished_at.is_some());
+    assert!(queue.cancels.is_empty());
+    assert_eq!(queue.running, 0);
+    assert!(!cancel_queued_job(&mut queue, "a").unwrap());
+  }
+}
@@ -23,8 +23,20 @@ use std::thread::JoinHandle;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
-use codex_arg0::Arg0PathEntryGuard;
-use codex_arg0::prepend_path_entry_for_codex_aliases;
+use codex_native_core::redaction::SecretRedactor;
+use codex_native_core::redaction::redact_rollout_lines;
+use codex_native_core::redaction::redact_thread_event;
+use codex_native_core::redaction::redact_thread_item;
+use codex_native_core::redaction::secret_redaction_interceptors;
+use codex_native_core::run::ExecLayers;
+use codex_native_core::run::ExecOptions;
+use codex_native_core::run::ShellPty;
+use codex_native_core::run::WorkspaceWrite;
+use codex_native_core::run::json_to_toml_inline;
+use codex_native_core::run::run_exec;
+use codex_native_core::tools::SHELL_TOOL_NAMES;
+use codex_native_core::tools::expand_tool_names;
+use codex_native_core::tools::tool_filter;
 use fastembed::EmbeddingModel;
 use fastembed::RerankInitOptions;
 use fastembed::RerankResult;
@@ -36,12 +48,18 @@ use sha1::Digest;
 use sha1::Sha1;
 
 use async_trait::async_trait;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
 use codex_cloud_tasks_client as cloud;
 use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
 use codex_common::SandboxModeCliArg;
 use codex_core::AuthManager;
 use codex_core::ExternalInterceptorRegistration;
+use codex_core::ExternalToolFilter;
+use codex_core::ExternalToolLayers;
+use codex_core::ExternalToolLimit;
 use codex_core::ExternalToolRegistration;
 use codex_core::FunctionCallError;
 use codex_core::RolloutRecorder;
@@ -51,10 +69,13 @@ use codex_core::ToolInterceptor;
 use codex_core::ToolInvocation;
 use codex_core::ToolKind;
 use codex_core::ToolOutput;
+use codex_core::ToolOutputSender;
+use codex_core::ToolOutputStream;
 use codex_core::ToolPayload;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
+use codex_core::config::types::ExecSessionMode;
 use codex_core::create_function_tool_spec_from_schema;
 use codex_core::default_client;
 use codex_core::find_thread_path_by_id_str;
@@ -62,11 +83,9 @@ use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
-use codex_core::set_pending_external_interceptors;
-use codex_core::set_pending_external_tools;
 use codex_exec::exec_events::BackgroundEventEvent;
 use codex_exec::exec_events::ThreadEvent as ExecThreadEvent;
-use codex_exec::{Cli, Color, Command, ResumeArgs, run_with_thread_event_callback};
+use codex_exec::Cli;
 use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -111,6 +130,15 @@ fn io_to_napi(err: std::io::Error) -> napi::Error {
   napi::Error::from_reason(err.to_string())
 }
 
+fn embedded_linux_sandbox_dir() -> PathBuf {
+  std::env::temp_dir().join("codex-native")
+}
+
+/// Where runs extract the embedded Linux sandbox helper the first time they need it.
+fn embedded_linux_sandbox_path() -> PathBuf {
+  embedded_linux_sandbox_dir().join("codex-linux-sandbox")
+}
+
 #[cfg(target_os = "linux")]
 fn ensure_embedded_linux_sandbox() -> napi::Result<PathBuf> {
   use std::fs;
@@ -118,9 +146,9 @@ fn ensure_embedded_linux_sandbox() -> napi::Result<PathBuf> {
 
   // Simplified: just create the sandbox each time if it doesn't exist
   // The filesystem acts as our "cache" - if the file exists, we don't recreate it
-  let root = std::env::temp_dir().join("codex-native");
+  let root = embedded_linux_sandbox_dir();
   fs::create_dir_all(&root).map_err(io_to_napi)?;
-  let target_path = root.join("codex-linux-sandbox");
+  let target_path = embedded_linux_sandbox_path();
 
   // Only create if it doesn't exist
   if !target_path.exists() {
@@ -158,11 +186,8 @@ fn default_linux_sandbox_path() -> napi::Result<Option<PathBuf>> {
 #[cfg(target_os = "linux")]
 const EMBEDDED_LINUX_SANDBOX_BYTES: &[u8] = include_bytes!(env!("CODEX_LINUX_SANDBOX_BIN"));
 
-const ORIGINATOR_ENV: &str = "CODEX_INTERNAL_ORIGINATOR_OVERRIDE";
 const NATIVE_ORIGINATOR: &str = "codex_sdk_native";
 
-static APPLY_PATCH_TEMP_DIR: OnceLock<Mutex<Arg0PathEntryGuard>> = OnceLock::new();
-
 #[napi]
 pub fn ensure_tokio_runtime() {
   // Re-create the shared Tokio runtime if it was previously shut down.
@@ -191,19 +216,8 @@ pub fn is_tokio_runtime_available() -> bool {
 }
 
 fn ensure_apply_patch_aliases() -> napi::Result<()> {
-  if APPLY_PATCH_TEMP_DIR.get().is_some() {
-    return Ok(());
-  }
-
-  let temp_dir = prepend_path_entry_for_codex_aliases().map_err(|err| {
-    napi::Error::from_reason(format!("Failed to prepare apply_patch helper: {err}"))
-  })?;
-
-  if APPLY_PATCH_TEMP_DIR.set(Mutex::new(temp_dir)).is_err() {
-    // Another thread initialized it first; that's fine.
-  }
-
-  Ok(())
+  codex_native_core::run::ensure_apply_patch_aliases()
+    .map_err(|err| napi::Error::from_reason(err.to_string()))
 }
 
 // ============================================================================
@@ -219,9 +233,35 @@ include!("run/mod.rs");
 include!("tui.rs");
 include!("tui_components.rs");
 include!("git.rs");
+include!("github.rs");
 include!("cloud_tasks.rs");
 include!("events.rs");
 include!("reverie/mod.rs");
 include!("fast_embed.rs");
 include!("tokenizer.rs");
 include!("toon.rs");
+include!("provider_adapters.rs");
+include!("diagnostics.rs");
+include!("mock_model_server.rs");
+include!("rollout_cache.rs");
+include!("rollout_reader.rs");
+include!("rollout_archive.rs");
+include!("rollout_encryption.rs");
+include!("tui_test_session.rs");
+include!("usage_report.rs");
+include!("graph/mod.rs");
+include!("auth.rs");
+include!("hooks.rs");
+include!("guardrails.rs");
+include!("audit.rs");
+include!("workspace_search.rs");
+include!("workspace_index.rs");
+include!("memory.rs");
+include!("watch.rs");
+include!("scheduler.rs");
+include!("job_queue.rs");
+include!("http_server.rs");
+include!("event_bus.rs");
+include!("images.rs");
+#[cfg(feature = "grpc")]
+include!("grpc_server.rs");
@@ -0,0 +1,524 @@
+// ============================================================================
+// Project memory (memoryPromote / memoryQuery)
+// ============================================================================
+//
+// Reverie searches past conversations passively. This store holds facts
+// someone chose to keep: memoryPromote() saves a piece of text, or the
+// text of given items from a thread, under the thread's project, and
+// memoryQuery() ranks a project's memories against a query. A project is
+// the git repository containing a directory, or the directory itself outside
+// one. Memories live in `$CODEX_HOME/memory/<project hash>.json`. Each
+// memory is embedded when promoted if fastEmbedInit() has run; queries
+// combine cosine similarity with reverie's keyword relevance score, and fall
+// back to keywords alone without embeddings. Runs that set `memory` get the
+// top memories for their prompt added to their developer instructions when
+// they start a new thread.
+
+const MEMORY_STORE_VERSION: u32 = 1;
+const DEFAULT_MEMORY_QUERY_LIMIT: u32 = 5;
+/// Keyword relevance scores at or above this count as a full match.
+const MEMORY_KEYWORD_SCORE_CAP: f64 = 200.0;
+const MEMORY_SEMANTIC_WEIGHT: f64 = 0.7;
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryPromoteOptions {
+  pub tags: Option<Vec<String>>,
+  /// Directory whose project stores the memory. Defaults to the thread's working directory.
+  pub project: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryQueryOptions {
+  /// Directory whose project is searched. Defaults to the current directory.
+  pub project: Option<String>,
+  /// Defaults to 5.
+  pub limit: Option<u32>,
+  /// Only memories carrying every one of these tags.
+  pub tags: Option<Vec<String>>,
+  /// Memories scoring below this (0 to 1) are dropped.
+  #[napi(js_name = "minScore")]
+  pub min_score: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEntry {
+  pub id: String,
+  pub text: String,
+  pub tags: Vec<String>,
+  /// Thread the memory was promoted from.
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  #[napi(js_name = "createdAt")]
+  pub created_at: String,
+}
+
+#[napi(object)]
+pub struct MemoryMatch {
+  pub memory: MemoryEntry,
+  pub score: f64,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredMemory {
+  #[serde(flatten)]
+  entry: MemoryEntry,
+  /// Embedding from the model named by the store's `embedding_namespace`.
+  vector: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryStore {
+  version: u32,
+  project: String,
+  embedding_namespace: Option<String>,
+  memories: Vec<StoredMemory>,
+}
+
+/// The git repository containing `dir`, or `dir` itself outside of one.
+fn memory_project_root(dir: &Path) -> String {
+  canonicalize_to_string(&get_git_repo_root(dir).unwrap_or_else(|| dir.to_path_buf()))
+}
+
+fn memory_project_for(project: Option<&str>) -> napi::Result<String> {
+  match project {
+    Some(dir) => Ok(memory_project_root(Path::new(dir))),
+    None => std::env::current_dir()
+      .map(|dir| memory_project_root(&dir))
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve current directory: {e}"))),
+  }
+}
+
+fn memory_store_path(project: &str) -> napi::Result<PathBuf> {
+  let codex_home = resolve_codex_home_for_cache()
+    .ok_or_else(|| napi::Error::from_reason("Unable to resolve CODEX_HOME for memories"))?;
+  Ok(
+    codex_home
+      .join("memory")
+      .join(format!("{}.json", hash_string(project))),
+  )
+}
+
+fn load_memory_store(path: &Path, project: &str) -> napi::Result<MemoryStore> {
+  let contents = match std::fs::read_to_string(path) {
+    Ok(contents) => contents,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+      return Ok(MemoryStore {
+        version: MEMORY_STORE_VERSION,
+        project: project.to_string(),
+        ..Default::default()
+      });
+    }
+    Err(err) => {
+      return Err(napi::Error::from_reason(format!(
+        "Failed to read {}: {err}",
+        path.display()
+      )));
+    }
+  };
+  serde_json::from_str(&contents)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to parse {}: {e}", path.display())))
+}
+
+fn save_memory_store(path: &Path, store: &MemoryStore) -> napi::Result<()> {
+  let contents = serde_json::to_string_pretty(store)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize memories: {e}")))?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent).map_err(|e| {
+      napi::Error::from_reason(format!("Failed to create {}: {e}", parent.display()))
+    })?;
+  }
+  let temp = path.with_extension("json.tmp");
+  std::fs::write(&temp, contents)
+    .and_then(|()| std::fs::rename(&temp, path))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to store memories: {e}")))
+}
+
+/// Serializes read-modify-write cycles on memory stores within this process.
+static MEMORY_STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_memory_stores() -> napi::Result<std::sync::MutexGuard<'static, ()>> {
+  MEMORY_STORE_LOCK
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("memory store mutex poisoned: {e}")))
+}
+
+/// `score_query_relevance` without the query-independent importance bonus it adds.
+fn memory_keyword_relevance(text: &str, query: &str) -> usize {
+  score_query_relevance(text, query).saturating_sub(score_message_importance(text) / 3)
+}
+
+/// Unit vectors for `texts` and the model's namespace, or `None` before fastEmbedInit().
+async fn embed_memory_texts(
+  texts: Vec<String>,
+  project: &str,
+) -> napi::Result<Option<(String, Vec<Vec<f32>>)>> {
+  let Ok(namespace) = fast_embed_namespace() else {
+    return Ok(None);
+  };
+  let vectors = fast_embed_embed(FastEmbedEmbedRequest {
+    inputs: texts,
+    batch_size: None,
+    normalize: Some(true),
+    project_root: Some(project.to_string()),
+    cache: Some(true),
+  })
+  .await?;
+  Ok(Some((namespace, vectors)))
+}
+
+/// The text a thread item contributes as a memory.
+fn thread_item_memory_text(item: &ThreadHistoryItem) -> Option<String> {
+  let field = |name: &str| item.item.get(name).and_then(JsonValue::as_str);
+  let text = match item.item_type.as_str() {
+    "user_message" | "agent_message" | "reasoning" => field("text")?.to_string(),
+    "command_execution" => match field("aggregated_output").filter(|out| !out.trim().is_empty()) {
+      Some(output) => format!("$ {}\n{output}", field("command")?),
+      None => format!("$ {}", field("command")?),
+    },
+    _ => item.item.to_string(),
+  };
+  let text = text.trim();
+  (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Store a fact, or the text of the given thread items, as project memories.
+#[napi]
+pub async fn memory_promote(
+  thread_id: String,
+  source: napi::Either<String, Vec<String>>,
+  options: Option<MemoryPromoteOptions>,
+) -> napi::Result<Vec<MemoryEntry>> {
+  let options = options.unwrap_or_default();
+  let codex_home = find_codex_home()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve CODEX_HOME: {e}")))?;
+  let rollout_path = find_thread_path_by_id_str(&codex_home, &thread_id)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?;
+  let texts = match source {
+    napi::Either::A(text) => {
+      let text = text.trim().to_string();
+      if text.is_empty() {
+        return Err(napi::Error::from_reason("Memory text must not be empty"));
+      }
+      vec![text]
+    }
+    napi::Either::B(item_ids) => {
+      let path = rollout_path.as_deref().ok_or_else(|| {
+        napi::Error::from_reason(format!("No rollout found for thread {thread_id}"))
+      })?;
+      let rollout = load_cached_rollout(path).map_err(|e| {
+        napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+      })?;
+      let items = collect_thread_history(&rollout);
+      item_ids
+        .iter()
+        .map(|id| {
+          items
+            .iter()
+            .find(|item| &item.id == id)
+            .and_then(thread_item_memory_text)
+            .ok_or_else(|| {
+              napi::Error::from_reason(format!("Thread {thread_id} has no item {id} with text"))
+            })
+        })
+        .collect::<napi::Result<Vec<_>>>()?
+    }
+  };
+  let project = match options.project.as_deref() {
+    Some(dir) => memory_project_root(Path::new(dir)),
+    None => match rollout_path
+      .as_deref()
+      .and_then(|path| rollout_session_meta(path).0)
+    {
+      Some(cwd) => memory_project_root(Path::new(&cwd)),
+      None => memory_project_for(None)?,
+    },
+  };
+  let tags = options.tags.unwrap_or_default();
+  let embedded = embed_memory_texts(texts.clone(), &project).await?;
+
+  let path = memory_store_path(&project)?;
+  let _guard = lock_memory_stores()?;
+  let mut store = load_memory_store(&path, &project)?;
+  let (namespace, vectors) = match embedded {
+    Some((namespace, vectors)) => (Some(namespace), vectors.into_iter().map(Some).collect()),
+    None => (None, vec![None; texts.len()]),
+  };
+  if namespace.is_some() && store.embedding_namespace != namespace {
+    // Vectors from another model can't be compared with the new ones.
+    for memory in &mut store.memories {
+      memory.vector = None;
+    }
+    store.embedding_namespace = namespace;
+  }
+  let mut promoted = Vec::with_capacity(texts.len());
+  for (text, vector) in texts.into_iter().zip(vectors) {
+    // Promoting the same text again only adds its new tags.
+    if let Some(existing) = store.memories.iter_mut().find(|m| m.entry.text == text) {
+      for tag in &tags {
+        if !existing.entry.tags.contains(tag) {
+          existing.entry.tags.push(tag.clone());
+        }
+      }
+      if existing.vector.is_none() {
+        existing.vector = vector;
+      }
+      promoted.push(existing.entry.clone());
+      continue;
+    }
+    let entry = MemoryEntry {
+      id: Uuid::new_v4().to_string(),
+      text,
+      tags: tags.clone(),
+      thread_id: Some(thread_id.clone()),
+      created_at: format_rollout_timestamp(SystemTime::now()),
+    };
+    promoted.push(entry.clone());
+    store.memories.push(StoredMemory { entry, vector });
+  }
+  save_memory_store(&path, &store)?;
+  Ok(promoted)
+}
+
+/// Scores in `0..=1`, best first: the weighted mix of cosine similarity and keyword relevance
+/// when both sides have vectors, keyword relevance alone otherwise.
+fn rank_memories(
+  memories: Vec<StoredMemory>,
+  query: &str,
+  query_vector: Option<&[f32]>,
+  tags: &[String],
+  limit: usize,
+  min_score: Option<f64>,
+) -> Vec<MemoryMatch> {
+  let mut ranked: Vec<MemoryMatch> = memories
+    .into_iter()
+    .filter(|memory| tags.iter().all(|tag| memory.entry.tags.contains(tag)))
+    .filter_map(|memory| {
+      let haystack = format!("{} {}", memory.entry.text, memory.entry.tags.join(" "));
+      let keyword =
+        (memory_keyword_relevance(&haystack, query) as f64 / MEMORY_KEYWORD_SCORE_CAP).min(1.0);
+      let score = match (query_vector, memory.vector.as_deref()) {
+        (Some(query_vector), Some(vector)) => {
+          let semantic = cosine_similarity(query_vector, vector).max(0.0);
+          MEMORY_SEMANTIC_WEIGHT * semantic + (1.0 - MEMORY_SEMANTIC_WEIGHT) * keyword
+        }
+        _ => keyword,
+      };
+      (score > 0.0 && min_score.is_none_or(|min| score >= min)).then_some(MemoryMatch {
+        memory: memory.entry,
+        score,
+      })
+    })
+    .collect();
+  ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+  ranked.truncate(limit);
+  ranked
+}
+
+async fn query_project_memories(
+  project: &str,
+  query: &str,
+  tags: &[String],
+  limit: usize,
+  min_score: Option<f64>,
+) -> napi::Result<Vec<MemoryMatch>> {
+  let store = load_memory_store(&memory_store_path(project)?, project)?;
+  if store.memories.is_empty() {
+    return Ok(Vec::new());
+  }
+  let query_vector = match embed_memory_texts(vec![query.to_string()], project).await? {
+    Some((namespace, mut vectors)) if store.embedding_namespace.as_ref() == Some(&namespace) => {
+      vectors.pop()
+    }
+    Some(_) | None => None,
+  };
+  Ok(rank_memories(
+    store.memories,
+    query,
+    query_vector.as_deref(),
+    tags,
+    limit,
+    min_score,
+  ))
+}
+
+/// A project's memories ranked against `query`, best first.
+#[napi]
+pub async fn memory_query(
+  query: String,
+  options: Option<MemoryQueryOptions>,
+) -> napi::Result<Vec<MemoryMatch>> {
+  let query = query.trim();
+  if query.is_empty() {
+    return Ok(Vec::new());
+  }
+  let options = options.unwrap_or_default();
+  let project = memory_project_for(options.project.as_deref())?;
+  query_project_memories(
+    &project,
+    query,
+    &options.tags.unwrap_or_default(),
+    options.limit.unwrap_or(DEFAULT_MEMORY_QUERY_LIMIT).max(1) as usize,
+    options.min_score,
+  )
+  .await
+}
+
+/// Remove a memory. Returns false when the project has no memory with that id.
+#[napi]
+pub async fn memory_forget(id: String, project: Option<String>) -> napi::Result<bool> {
+  let project = memory_project_for(project.as_deref())?;
+  let path = memory_store_path(&project)?;
+  let _guard = lock_memory_stores()?;
+  let mut store = load_memory_store(&path, &project)?;
+  let before = store.memories.len();
+  store.memories.retain(|memory| memory.entry.id != id);
+  if store.memories.len() == before {
+    return Ok(false);
+  }
+  save_memory_store(&path, &store)?;
+  Ok(true)
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMemoryOptions {
+  /// Memories added to a new thread's developer instructions. Defaults to 5.
+  pub limit: Option<u32>,
+  pub tags: Option<Vec<String>>,
+  #[napi(js_name = "minScore")]
+  pub min_score: Option<f64>,
+}
+
+fn project_memories_block(matches: &[MemoryMatch]) -> String {
+  let body = matches
+    .iter()
+    .map(|found| format!("- {}", found.memory.text.replace('\n', "\n  ")))
+    .collect::<Vec<_>>()
+    .join("\n");
+  format!("<project_memories>\n{body}\n</project_memories>")
+}
+
+/// Adds the project's top memories for the prompt to a new thread's developer instructions.
+async fn inject_project_memories(options: &mut InternalRunRequest) -> napi::Result<()> {
+  let Some(memory) = options.memory.clone() else {
+    return Ok(());
+  };
+  if options.thread_id.is_some() || options.prompt.trim().is_empty() {
+    return Ok(());
+  }
+  let project = match options.working_directory.as_deref() {
+    Some(dir) => memory_project_root(dir),
+    None => memory_project_for(None)?,
+  };
+  let matches = query_project_memories(
+    &project,
+    options.prompt.trim(),
+    &memory.tags.unwrap_or_default(),
+    memory.limit.unwrap_or(DEFAULT_MEMORY_QUERY_LIMIT).max(1) as usize,
+    memory.min_score,
+  )
+  .await?;
+  if matches.is_empty() {
+    return Ok(());
+  }
+  // The request's developer instructions replace config.toml's, so keep whichever applies.
+  let base = match options.developer_instructions.take() {
+    Some(instructions) => Some(instructions),
+    None => {
+      load_config_from_internal(options)
+        .await?
+        .developer_instructions
+    }
+  };
+  let block = project_memories_block(&matches);
+  options.developer_instructions = Some(match base.filter(|base| !base.trim().is_empty()) {
+    Some(base) => format!("{base}\n\n{block}"),
+    None => block,
+  });
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests_memory {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn stored(text: &str, tags: &[&str], vector: Option<Vec<f32>>) -> StoredMemory {
+    StoredMemory {
+      entry: MemoryEntry {
+        id: text.to_string(),
+        text: text.to_string(),
+        tags: tags.iter().map(ToString::to_string).collect(),
+        thread_id: None,
+        created_at: String::new(),
+      },
+      vector,
+    }
+  }
+
+  #[test]
+  fn ranks_by_keywords_and_vectors_and_filters_tags() {
+    let memories = vec![
+      stored(
+        "Run tests with cargo nextest",
+        &["testing"],
+        Some(vec![1.0, 0.0]),
+      ),
+      stored(
+        "Releases are cut from the main branch",
+        &["release"],
+        Some(vec![0.0, 1.0]),
+      ),
+      stored("Use pnpm, not npm", &[], None),
+    ];
+    let ranked = rank_memories(memories.clone(), "cargo nextest", None, &[], 5, None);
+    let ids: Vec<&str> = ranked
+      .iter()
+      .map(|found| found.memory.id.as_str())
+      .collect();
+    assert_eq!(ids, vec!["Run tests with cargo nextest"]);
+
+    let ranked = rank_memories(
+      memories.clone(),
+      "which branch do releases come from",
+      Some(&[0.0, 1.0]),
+      &[],
+      5,
+      Some(0.5),
+    );
+    let ids: Vec<&str> = ranked
+      .iter()
+      .map(|found| found.memory.id.as_str())
+      .collect();
+    assert_eq!(ids, vec!["Releases are cut from the main branch"]);
+
+    let ranked = rank_memories(
+      memories,
+      "cargo nextest",
+      None,
+      &["release".to_string()],
+      5,
+      None,
+    );
+    assert!(ranked.is_empty());
+
+    let block = project_memories_block(&[MemoryMatch {
+      memory: stored("line one\nline two", &[], None).entry,
+      score: 1.0,
+    }]);
+    assert_eq!(
+      block,
+      "<project_memories>\n- line one\n  line two\n</project_memories>"
+    );
+  }
+}
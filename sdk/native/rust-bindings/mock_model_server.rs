@@ -0,0 +1,210 @@
+// Section 10: Mock Model Server for SDK Tests
+// ============================================================================
+//
+// A loopback Responses API mock that TypeScript suites can start without
+// writing Rust. Each POST to `<url>/responses` consumes the next fixture and
+// streams it back as SSE; requests are recorded for later assertions.
+//
+// Key exports:
+//   - start_mock_model_server(): Serve fixtures on 127.0.0.1 and return the base URL
+//   - stop_mock_model_server(): Shut a server down and return the recorded requests
+//
+// ============================================================================
+
+#[derive(Clone, Debug)]
+#[napi(object)]
+pub struct MockModelFixture {
+  /// Event payloads (e.g. from `evAssistantMessage`) streamed as one SSE response.
+  pub events: Option<Vec<String>>,
+  /// Raw response body, sent verbatim instead of `events`.
+  pub body: Option<String>,
+  #[napi(js_name = "statusCode")]
+  pub status_code: Option<u16>,
+}
+
+#[napi(object)]
+pub struct MockModelServerInfo {
+  pub id: String,
+  /// Base URL to pass as `baseUrl`; already includes the `/v1` prefix.
+  pub url: String,
+  pub port: u32,
+}
+
+#[napi(object)]
+pub struct MockModelRequest {
+  pub path: String,
+  pub body: String,
+  pub json: Option<JsonValue>,
+  pub headers: HashMap<String, String>,
+}
+
+struct MockModelServerState {
+  fixtures: VecDeque<MockModelFixture>,
+  requests: Vec<MockModelRequest>,
+}
+
+struct RunningMockModelServer {
+  server: Arc<tiny_http::Server>,
+  state: Arc<Mutex<MockModelServerState>>,
+}
+
+fn mock_model_servers() -> &'static Mutex<HashMap<String, RunningMockModelServer>> {
+  static SERVERS: OnceLock<Mutex<HashMap<String, RunningMockModelServer>>> = OnceLock::new();
+  SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn mock_fixture_body(fixture: &MockModelFixture) -> String {
+  if let Some(body) = fixture.body.clone() {
+    return body;
+  }
+  let mut events = fixture.events.clone().unwrap_or_default();
+  let has_completion = events.iter().any(|event| {
+    serde_json::from_str::<JsonValue>(event)
+      .is_ok_and(|value| value.get("type").and_then(JsonValue::as_str) == Some("response.completed"))
+  });
+  // Codex treats a stream without `response.completed` as an error; close it for the caller.
+  if !has_completion {
+    events.push(ev_completed("resp_mock".to_string()));
+  }
+  sse(events)
+}
+
+fn handle_mock_model_request(state: &Mutex<MockModelServerState>, mut request: tiny_http::Request) {
+  let path = request.url().to_string();
+  let mut body = String::new();
+  let _ = std::io::Read::read_to_string(request.as_reader(), &mut body);
+  let headers = request
+    .headers()
+    .iter()
+    .map(|header| (header.field.to_string().to_ascii_lowercase(), header.value.to_string()))
+    .collect();
+
+  let is_responses = request.method() == &tiny_http::Method::Post
+    && path.split('?').next().is_some_and(|route| route.ends_with("/responses"));
+  if !is_responses {
+    let _ = request.respond(tiny_http::Response::from_string("not found").with_status_code(404));
+    return;
+  }
+
+  let fixture = match state.lock() {
+    Ok(mut guard) => {
+      guard.requests.push(MockModelRequest {
+        path,
+        json: serde_json::from_str(&body).ok(),
+        body,
+        headers,
+      });
+      guard.fixtures.pop_front()
+    }
+    Err(_) => None,
+  };
+
+  let Some(fixture) = fixture else {
+    let body = json!({ "error": { "message": "mock model server has no fixtures left" } }).to_string();
+    let _ = request.respond(tiny_http::Response::from_string(body).with_status_code(500));
+    return;
+  };
+  let header = tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..])
+    .expect("static header");
+  let _ = request.respond(
+    tiny_http::Response::from_string(mock_fixture_body(&fixture))
+      .with_status_code(fixture.status_code.unwrap_or(200))
+      .with_header(header),
+  );
+}
+
+/// Start a loopback Responses API mock that serves `fixtures` in order.
+#[napi]
+pub fn start_mock_model_server(fixtures: Vec<MockModelFixture>) -> napi::Result<MockModelServerInfo> {
+  let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to bind mock model server: {e}")))?;
+  let addr = listener
+    .local_addr()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read mock server address: {e}")))?;
+  let server = Arc::new(
+    tiny_http::Server::from_listener(listener, None)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to start mock model server: {e}")))?,
+  );
+  let state = Arc::new(Mutex::new(MockModelServerState {
+    fixtures: fixtures.into(),
+    requests: Vec::new(),
+  }));
+
+  let id = Uuid::new_v4().to_string();
+  let worker_server = Arc::clone(&server);
+  let worker_state = Arc::clone(&state);
+  std::thread::Builder::new()
+    .name("codex-mock-model-server".to_string())
+    .spawn(move || {
+      for request in worker_server.incoming_requests() {
+        handle_mock_model_request(&worker_state, request);
+      }
+    })
+    .map_err(|e| napi::Error::from_reason(format!("Failed to spawn mock model server: {e}")))?;
+
+  mock_model_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("mock server mutex poisoned: {e}")))?
+    .insert(id.clone(), RunningMockModelServer { server, state });
+
+  Ok(MockModelServerInfo {
+    id,
+    url: format!("http://{addr}/v1"),
+    port: u32::from(addr.port()),
+  })
+}
+
+/// Stop a mock server and return every request it received, in arrival order.
+#[napi]
+pub fn stop_mock_model_server(id: String) -> napi::Result<Vec<MockModelRequest>> {
+  let running = mock_model_servers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("mock server mutex poisoned: {e}")))?
+    .remove(&id)
+    .ok_or_else(|| napi::Error::from_reason(format!("Unknown mock model server {id}")))?;
+  running.server.unblock();
+  let mut state = running
+    .state
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("mock server state poisoned: {e}")))?;
+  Ok(std::mem::take(&mut state.requests))
+}
+
+#[cfg(test)]
+mod tests_mock_model_server {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn serves_fixtures_in_order_and_records_requests() {
+    let info = start_mock_model_server(vec![MockModelFixture {
+      events: Some(vec![ev_assistant_message("msg_1".to_string(), "hi".to_string())]),
+      body: None,
+      status_code: None,
+    }])
+    .unwrap();
+    let client = reqwest::blocking::Client::new();
+
+    let first = client
+      .post(format!("{}/responses", info.url))
+      .body(r#"{"model":"gpt-5"}"#)
+      .send()
+      .unwrap();
+    assert_eq!(first.status().as_u16(), 200);
+    let text = first.text().unwrap();
+    assert!(text.contains("\"text\":\"hi\""));
+    assert!(text.contains("response.completed"));
+
+    let exhausted = client
+      .post(format!("{}/responses", info.url))
+      .body("{}")
+      .send()
+      .unwrap();
+    assert_eq!(exhausted.status().as_u16(), 500);
+
+    let requests = stop_mock_model_server(info.id).unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/v1/responses");
+    assert_eq!(requests[0].json, Some(json!({ "model": "gpt-5" })));
+  }
+}
@@ -0,0 +1,983 @@
+// Section 8: Model Provider Registry and Wire-Protocol Adapters
+// ============================================================================
+//
+// Providers registered from JavaScript are forwarded to codex-rs as
+// `model_providers.<id>` config overrides. OpenAI-compatible providers are
+// passed through directly; Anthropic Messages and Gemini generateContent
+// providers are served by an in-process loopback adapter that speaks the
+// Responses API to codex-rs and translates each turn to the upstream format.
+//
+// Key exports:
+//   - register_model_provider(): Register or replace a provider by id
+//   - list_registered_model_providers(): Inspect registrations (keys redacted)
+//   - clear_registered_model_providers(): Drop all registrations and stop the
+//     adapter
+//
+// ============================================================================
+
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+const DEFAULT_ADAPTER_MAX_OUTPUT_TOKENS: u32 = 8192;
+
+#[derive(Clone, Debug)]
+#[napi(object)]
+pub struct ModelProviderRegistration {
+  pub id: String,
+  pub name: Option<String>,
+  #[napi(js_name = "baseUrl")]
+  pub base_url: String,
+  #[napi(js_name = "apiKey")]
+  pub api_key: Option<String>,
+  /// Environment variable holding the upstream API key.
+  #[napi(js_name = "envKey")]
+  pub env_key: Option<String>,
+  #[napi(
+    js_name = "wireApi",
+    ts_type = "\"responses\" | \"anthropic\" | \"gemini\""
+  )]
+  pub wire_api: Option<String>,
+  #[napi(js_name = "httpHeaders")]
+  pub http_headers: Option<HashMap<String, String>>,
+  /// Upper bound applied to adapter requests (Anthropic requires one).
+  #[napi(js_name = "maxOutputTokens")]
+  pub max_output_tokens: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProviderWireApi {
+  Responses,
+  AnthropicMessages,
+  GeminiGenerateContent,
+}
+
+#[derive(Clone, Debug)]
+struct RegisteredModelProvider {
+  registration: ModelProviderRegistration,
+  wire_api: ProviderWireApi,
+}
+
+impl RegisteredModelProvider {
+  fn upstream_api_key(&self) -> Option<String> {
+    self
+      .registration
+      .api_key
+      .clone()
+      .or_else(|| {
+        self
+          .registration
+          .env_key
+          .as_deref()
+          .and_then(|key| std::env::var(key).ok())
+      })
+      .filter(|key| !key.trim().is_empty())
+  }
+}
+
+fn parse_provider_wire_api(input: Option<&str>) -> napi::Result<Option<ProviderWireApi>> {
+  parse_enum_arg!(input, "wire api",
+    "responses" => ProviderWireApi::Responses,
+    "anthropic" => ProviderWireApi::AnthropicMessages,
+    "gemini" => ProviderWireApi::GeminiGenerateContent,
+  )
+}
+
+fn registered_model_providers() -> &'static Mutex<HashMap<String, RegisteredModelProvider>> {
+  static PROVIDERS: OnceLock<Mutex<HashMap<String, RegisteredModelProvider>>> = OnceLock::new();
+  PROVIDERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The running loopback adapter. Stopping it unblocks the accept loop and joins its thread;
+/// requests already being served finish on their own threads.
+struct ProviderAdapterServer {
+  addr: std::net::SocketAddr,
+  server: Arc<tiny_http::Server>,
+  accept_thread: std::thread::JoinHandle<()>,
+}
+
+impl ProviderAdapterServer {
+  fn start() -> napi::Result<Self> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+      .map_err(|e| napi::Error::from_reason(format!("Failed to bind provider adapter: {e}")))?;
+    let addr = listener
+      .local_addr()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to read adapter address: {e}")))?;
+    let server = tiny_http::Server::from_listener(listener, None)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to start provider adapter: {e}")))?;
+    let server = Arc::new(server);
+    let client = reqwest::blocking::Client::builder()
+      .timeout(None::<std::time::Duration>)
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to build adapter client: {e}")))?;
+
+    let accept_server = Arc::clone(&server);
+    let accept_thread = std::thread::Builder::new()
+      .name("codex-provider-adapter".to_string())
+      .spawn(move || {
+        for request in accept_server.incoming_requests() {
+          let client = client.clone();
+          std::thread::spawn(move || handle_provider_adapter_request(&client, request));
+        }
+      })
+      .map_err(|e| napi::Error::from_reason(format!("Failed to spawn provider adapter: {e}")))?;
+
+    Ok(Self {
+      addr,
+      server,
+      accept_thread,
+    })
+  }
+
+  fn stop(self) {
+    self.server.unblock();
+    let _ = self.accept_thread.join();
+  }
+}
+
+fn provider_adapter_server() -> &'static Mutex<Option<ProviderAdapterServer>> {
+  static SERVER: OnceLock<Mutex<Option<ProviderAdapterServer>>> = OnceLock::new();
+  SERVER.get_or_init(|| Mutex::new(None))
+}
+
+fn lookup_model_provider(id: &str) -> Option<RegisteredModelProvider> {
+  registered_model_providers()
+    .lock()
+    .ok()
+    .and_then(|guard| guard.get(id).cloned())
+}
+
+#[napi]
+pub fn register_model_provider(registration: ModelProviderRegistration) -> napi::Result<()> {
+  let id = registration.id.trim().to_string();
+  if id.is_empty()
+    || !id
+      .chars()
+      .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+  {
+    return Err(napi::Error::from_reason(format!(
+      "Invalid model provider id \"{id}\"; use letters, digits, '-' or '_'"
+    )));
+  }
+  if registration.base_url.trim().is_empty() {
+    return Err(napi::Error::from_reason(format!(
+      "Model provider \"{id}\" requires a baseUrl"
+    )));
+  }
+
+  let wire_api = parse_provider_wire_api(registration.wire_api.as_deref())?
+    .unwrap_or(ProviderWireApi::Responses);
+  if wire_api != ProviderWireApi::Responses {
+    ensure_provider_adapter_server()?;
+  }
+
+  let mut registration = registration;
+  registration.id = id.clone();
+  registration.base_url = registration
+    .base_url
+    .trim()
+    .trim_end_matches('/')
+    .to_string();
+
+  registered_model_providers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("model providers mutex poisoned: {e}")))?
+    .insert(
+      id,
+      RegisteredModelProvider {
+        registration,
+        wire_api,
+      },
+    );
+  Ok(())
+}
+
+#[napi]
+pub fn list_registered_model_providers() -> napi::Result<Vec<ModelProviderRegistration>> {
+  let guard = registered_model_providers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("model providers mutex poisoned: {e}")))?;
+  let mut providers: Vec<ModelProviderRegistration> = guard
+    .values()
+    .map(|provider| ModelProviderRegistration {
+      api_key: None,
+      ..provider.registration.clone()
+    })
+    .collect();
+  providers.sort_by(|a, b| a.id.cmp(&b.id));
+  Ok(providers)
+}
+
+#[napi]
+pub fn clear_registered_model_providers() -> napi::Result<()> {
+  registered_model_providers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("model providers mutex poisoned: {e}")))?
+    .clear();
+  stop_provider_adapter_server()
+}
+
+/// Build the `model_providers.<id>=...` override for a registered provider, if any.
+fn registered_model_provider_override(provider_id: &str) -> Option<String> {
+  let provider = lookup_model_provider(provider_id)?;
+  let registration = &provider.registration;
+  let quote = |value: &str| json_to_toml_inline(&JsonValue::String(value.to_string()));
+  let name = registration.name.as_deref().unwrap_or(&registration.id);
+  let mut pairs = vec![
+    format!("name = {}", quote(name)),
+    "wire_api = \"responses\"".to_string(),
+  ];
+
+  match provider.wire_api {
+    ProviderWireApi::Responses => {
+      pairs.push(format!("base_url = {}", quote(&registration.base_url)));
+      if let Some(env_key) = registration.env_key.as_deref() {
+        pairs.push(format!("env_key = {}", quote(env_key)));
+      } else if let Some(api_key) = registration.api_key.as_deref() {
+        pairs.push(format!("experimental_bearer_token = {}", quote(api_key)));
+      }
+      if let Some(headers) = registration.http_headers.as_ref()
+        && !headers.is_empty()
+      {
+        let mut headers: Vec<String> = headers
+          .iter()
+          .map(|(key, value)| format!("{} = {}", quote(key), quote(value)))
+          .collect();
+        headers.sort();
+        pairs.push(format!("http_headers = {{ {} }}", headers.join(", ")));
+      }
+    }
+    ProviderWireApi::AnthropicMessages | ProviderWireApi::GeminiGenerateContent => {
+      let addr = provider_adapter_server()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|server| server.addr))?;
+      pairs.push(format!(
+        "base_url = {}",
+        quote(&format!("http://{addr}/adapters/{}/v1", registration.id))
+      ));
+    }
+  }
+
+  Some(format!(
+    "model_providers.{}={{ {} }}",
+    registration.id,
+    pairs.join(", ")
+  ))
+}
+
+fn ensure_provider_adapter_server() -> napi::Result<std::net::SocketAddr> {
+  let mut guard = provider_adapter_server()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("provider adapter mutex poisoned: {e}")))?;
+  if let Some(server) = guard.as_ref() {
+    return Ok(server.addr);
+  }
+  let server = ProviderAdapterServer::start()?;
+  let addr = server.addr;
+  *guard = Some(server);
+  Ok(addr)
+}
+
+fn stop_provider_adapter_server() -> napi::Result<()> {
+  let server = provider_adapter_server()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("provider adapter mutex poisoned: {e}")))?
+    .take();
+  if let Some(server) = server {
+    server.stop();
+  }
+  Ok(())
+}
+
+fn respond_adapter_error(request: tiny_http::Request, status: u16, message: &str) {
+  let body = json!({ "error": { "message": message } }).to_string();
+  let _ = request.respond(tiny_http::Response::from_string(body).with_status_code(status));
+}
+
+fn handle_provider_adapter_request(
+  client: &reqwest::blocking::Client,
+  mut request: tiny_http::Request,
+) {
+  let url = request.url().to_string();
+  let provider_id = url
+    .strip_prefix("/adapters/")
+    .and_then(|rest| rest.strip_suffix("/v1/responses"))
+    .map(str::to_string);
+  let Some(provider_id) = provider_id.filter(|_| request.method() == &tiny_http::Method::Post)
+  else {
+    respond_adapter_error(request, 404, "unsupported adapter route");
+    return;
+  };
+  let Some(provider) = lookup_model_provider(&provider_id) else {
+    respond_adapter_error(
+      request,
+      404,
+      &format!("unknown model provider {provider_id}"),
+    );
+    return;
+  };
+
+  let mut body = String::new();
+  if let Err(err) = std::io::Read::read_to_string(request.as_reader(), &mut body) {
+    respond_adapter_error(request, 400, &format!("failed to read request: {err}"));
+    return;
+  }
+  let responses_request: JsonValue = match serde_json::from_str(&body) {
+    Ok(value) => value,
+    Err(err) => {
+      respond_adapter_error(request, 400, &format!("invalid request body: {err}"));
+      return;
+    }
+  };
+
+  match forward_adapter_request(client, &provider, &responses_request) {
+    Ok(output) => {
+      let header = tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..])
+        .expect("static header");
+      let _ = request
+        .respond(tiny_http::Response::from_string(responses_sse_body(&output)).with_header(header));
+    }
+    Err((status, message)) => respond_adapter_error(request, status, &message),
+  }
+}
+
+/// Normalised result of a single upstream turn, expressed as Responses API items.
+#[derive(Debug, PartialEq)]
+struct AdapterTurnOutput {
+  items: Vec<JsonValue>,
+  input_tokens: i64,
+  output_tokens: i64,
+}
+
+fn forward_adapter_request(
+  client: &reqwest::blocking::Client,
+  provider: &RegisteredModelProvider,
+  responses_request: &JsonValue,
+) -> Result<AdapterTurnOutput, (u16, String)> {
+  let registration = &provider.registration;
+  let model = responses_request
+    .get("model")
+    .and_then(JsonValue::as_str)
+    .unwrap_or_default();
+  let max_output_tokens = registration
+    .max_output_tokens
+    .unwrap_or(DEFAULT_ADAPTER_MAX_OUTPUT_TOKENS);
+  let api_key = provider.upstream_api_key();
+
+  let builder = match provider.wire_api {
+    ProviderWireApi::AnthropicMessages => {
+      let body = responses_to_anthropic_request(responses_request, max_output_tokens);
+      let mut builder = client
+        .post(format!("{}/messages", registration.base_url))
+        .header("anthropic-version", ANTHROPIC_API_VERSION)
+        .json(&body);
+      if let Some(key) = api_key {
+        builder = builder.header("x-api-key", key);
+      }
+      builder
+    }
+    ProviderWireApi::GeminiGenerateContent => {
+      let body = responses_to_gemini_request(responses_request, max_output_tokens);
+      let mut builder = client
+        .post(format!(
+          "{}/models/{model}:generateContent",
+          registration.base_url
+        ))
+        .json(&body);
+      if let Some(key) = api_key {
+        builder = builder.header("x-goog-api-key", key);
+      }
+      builder
+    }
+    ProviderWireApi::Responses => {
+      return Err((
+        400,
+        "responses providers are not served by the adapter".to_string(),
+      ));
+    }
+  };
+  let builder = registration
+    .http_headers
+    .iter()
+    .flatten()
+    .fold(builder, |builder, (key, value)| builder.header(key, value));
+
+  let response = builder
+    .send()
+    .map_err(|err| (502, format!("upstream request failed: {err}")))?;
+  let status = response.status();
+  let text = response
+    .text()
+    .map_err(|err| (502, format!("failed to read upstream response: {err}")))?;
+  if !status.is_success() {
+    return Err((status.as_u16(), text));
+  }
+  let value: JsonValue =
+    serde_json::from_str(&text).map_err(|err| (502, format!("invalid upstream JSON: {err}")))?;
+
+  Ok(match provider.wire_api {
+    ProviderWireApi::AnthropicMessages => anthropic_response_to_output(&value),
+    ProviderWireApi::GeminiGenerateContent => gemini_response_to_output(&value),
+    ProviderWireApi::Responses => unreachable!("rejected above"),
+  })
+}
+
+fn responses_sse_body(output: &AdapterTurnOutput) -> String {
+  let response_id = format!("resp_{}", Uuid::new_v4().simple());
+  let mut events = vec![json!({
+    "type": "response.created",
+    "response": { "id": response_id },
+  })];
+  for item in &output.items {
+    events.push(json!({ "type": "response.output_item.done", "item": item }));
+  }
+  events.push(json!({
+    "type": "response.completed",
+    "response": {
+      "id": response_id,
+      "usage": {
+        "input_tokens": output.input_tokens,
+        "input_tokens_details": null,
+        "output_tokens": output.output_tokens,
+        "output_tokens_details": null,
+        "total_tokens": output.input_tokens + output.output_tokens,
+      },
+    },
+  }));
+
+  events
+    .into_iter()
+    .map(|event| {
+      let kind = event
+        .get("type")
+        .and_then(JsonValue::as_str)
+        .unwrap_or("response")
+        .to_string();
+      format!("event: {kind}\ndata: {event}\n\n")
+    })
+    .collect()
+}
+
+/// Collect the plain text of a Responses API message item.
+fn responses_message_text(item: &JsonValue) -> String {
+  match item.get("content") {
+    Some(JsonValue::String(text)) => text.clone(),
+    Some(JsonValue::Array(parts)) => parts
+      .iter()
+      .filter_map(|part| part.get("text").and_then(JsonValue::as_str))
+      .collect::<Vec<_>>()
+      .join("\n"),
+    _ => String::new(),
+  }
+}
+
+fn responses_function_output_text(item: &JsonValue) -> String {
+  match item.get("output") {
+    Some(JsonValue::String(text)) => text.clone(),
+    Some(JsonValue::Array(parts)) => parts
+      .iter()
+      .filter_map(|part| part.get("text").and_then(JsonValue::as_str))
+      .collect::<Vec<_>>()
+      .join("\n"),
+    Some(other) => other.to_string(),
+    None => String::new(),
+  }
+}
+
+fn parse_function_arguments(item: &JsonValue) -> JsonValue {
+  item
+    .get("arguments")
+    .and_then(JsonValue::as_str)
+    .and_then(|raw| serde_json::from_str(raw).ok())
+    .unwrap_or_else(|| json!({}))
+}
+
+fn responses_function_tools(request: &JsonValue) -> Vec<&JsonValue> {
+  request
+    .get("tools")
+    .and_then(JsonValue::as_array)
+    .map(|tools| {
+      tools
+        .iter()
+        .filter(|tool| tool.get("type").and_then(JsonValue::as_str) == Some("function"))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn push_anthropic_block(messages: &mut Vec<JsonValue>, role: &str, block: JsonValue) {
+  if let Some(last) = messages.last_mut()
+    && last.get("role").and_then(JsonValue::as_str) == Some(role)
+    && let Some(content) = last.get_mut("content").and_then(JsonValue::as_array_mut)
+  {
+    content.push(block);
+    return;
+  }
+  messages.push(json!({ "role": role, "content": [block] }));
+}
+
+fn responses_to_anthropic_request(request: &JsonValue, max_output_tokens: u32) -> JsonValue {
+  let mut system_parts = Vec::new();
+  if let Some(instructions) = request.get("instructions").and_then(JsonValue::as_str)
+    && !instructions.trim().is_empty()
+  {
+    system_parts.push(instructions.to_string());
+  }
+
+  let mut messages: Vec<JsonValue> = Vec::new();
+  for item in request
+    .get("input")
+    .and_then(JsonValue::as_array)
+    .into_iter()
+    .flatten()
+  {
+    match item
+      .get("type")
+      .and_then(JsonValue::as_str)
+      .unwrap_or("message")
+    {
+      "message" => {
+        let text = responses_message_text(item);
+        if text.is_empty() {
+          continue;
+        }
+        match item.get("role").and_then(JsonValue::as_str) {
+          Some("system") | Some("developer") => system_parts.push(text),
+          Some("assistant") => push_anthropic_block(
+            &mut messages,
+            "assistant",
+            json!({ "type": "text", "text": text }),
+          ),
+          _ => push_anthropic_block(
+            &mut messages,
+            "user",
+            json!({ "type": "text", "text": text }),
+          ),
+        }
+      }
+      "function_call" => push_anthropic_block(
+        &mut messages,
+        "assistant",
+        json!({
+          "type": "tool_use",
+          "id": item.get("call_id").cloned().unwrap_or(JsonValue::Null),
+          "name": item.get("name").cloned().unwrap_or(JsonValue::Null),
+          "input": parse_function_arguments(item),
+        }),
+      ),
+      "function_call_output" => push_anthropic_block(
+        &mut messages,
+        "user",
+        json!({
+          "type": "tool_result",
+          "tool_use_id": item.get("call_id").cloned().unwrap_or(JsonValue::Null),
+          "content": responses_function_output_text(item),
+        }),
+      ),
+      _ => {}
+    }
+  }
+
+  let mut body = json!({
+    "model": request.get("model").cloned().unwrap_or(JsonValue::Null),
+    "max_tokens": max_output_tokens,
+    "messages": messages,
+  });
+  if !system_parts.is_empty() {
+    body["system"] = JsonValue::String(system_parts.join("\n\n"));
+  }
+  let tools: Vec<JsonValue> = responses_function_tools(request)
+    .into_iter()
+    .map(|tool| {
+      let mut definition = json!({
+        "name": tool.get("name").cloned().unwrap_or(JsonValue::Null),
+        "input_schema": tool
+          .get("parameters")
+          .cloned()
+          .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+      });
+      if let Some(description) = tool.get("description").filter(|value| !value.is_null()) {
+        definition["description"] = description.clone();
+      }
+      definition
+    })
+    .collect();
+  if !tools.is_empty() {
+    body["tools"] = JsonValue::Array(tools);
+  }
+  body
+}
+
+fn anthropic_response_to_output(response: &JsonValue) -> AdapterTurnOutput {
+  let mut items = Vec::new();
+  let mut text_parts = Vec::new();
+  for block in response
+    .get("content")
+    .and_then(JsonValue::as_array)
+    .into_iter()
+    .flatten()
+  {
+    match block.get("type").and_then(JsonValue::as_str) {
+      Some("text") => {
+        if let Some(text) = block.get("text").and_then(JsonValue::as_str) {
+          text_parts.push(text.to_string());
+        }
+      }
+      Some("tool_use") => items.push(json!({
+        "type": "function_call",
+        "id": format!("fc_{}", Uuid::new_v4().simple()),
+        "call_id": block.get("id").cloned().unwrap_or(JsonValue::Null),
+        "name": block.get("name").cloned().unwrap_or(JsonValue::Null),
+        "arguments": block.get("input").map(JsonValue::to_string).unwrap_or_else(|| "{}".to_string()),
+      })),
+      _ => {}
+    }
+  }
+  if !text_parts.is_empty() {
+    items.insert(0, assistant_output_message(text_parts.join("\n")));
+  }
+
+  let usage = response.get("usage");
+  AdapterTurnOutput {
+    items,
+    input_tokens: usage
+      .and_then(|u| u.get("input_tokens"))
+      .and_then(JsonValue::as_i64)
+      .unwrap_or(0),
+    output_tokens: usage
+      .and_then(|u| u.get("output_tokens"))
+      .and_then(JsonValue::as_i64)
+      .unwrap_or(0),
+  }
+}
+
+/// Gemini rejects several JSON-schema keywords that OpenAI tool schemas commonly use.
+fn sanitize_gemini_schema(schema: &JsonValue) -> JsonValue {
+  match schema {
+    JsonValue::Object(map) => JsonValue::Object(
+      map
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "additionalProperties" | "$schema" | "strict"))
+        .map(|(key, value)| (key.clone(), sanitize_gemini_schema(value)))
+        .collect(),
+    ),
+    JsonValue::Array(values) => {
+      JsonValue::Array(values.iter().map(sanitize_gemini_schema).collect())
+    }
+    other => other.clone(),
+  }
+}
+
+fn push_gemini_part(contents: &mut Vec<JsonValue>, role: &str, part: JsonValue) {
+  if let Some(last) = contents.last_mut()
+    && last.get("role").and_then(JsonValue::as_str) == Some(role)
+    && let Some(parts) = last.get_mut("parts").and_then(JsonValue::as_array_mut)
+  {
+    parts.push(part);
+    return;
+  }
+  contents.push(json!({ "role": role, "parts": [part] }));
+}
+
+fn responses_to_gemini_request(request: &JsonValue, max_output_tokens: u32) -> JsonValue {
+  let mut system_parts = Vec::new();
+  if let Some(instructions) = request.get("instructions").and_then(JsonValue::as_str)
+    && !instructions.trim().is_empty()
+  {
+    system_parts.push(json!({ "text": instructions }));
+  }
+
+  // Gemini correlates tool results by function name rather than call id.
+  let mut call_names: HashMap<String, String> = HashMap::new();
+  let mut contents: Vec<JsonValue> = Vec::new();
+  for item in request
+    .get("input")
+    .and_then(JsonValue::as_array)
+    .into_iter()
+    .flatten()
+  {
+    match item
+      .get("type")
+      .and_then(JsonValue::as_str)
+      .unwrap_or("message")
+    {
+      "message" => {
+        let text = responses_message_text(item);
+        if text.is_empty() {
+          continue;
+        }
+        match item.get("role").and_then(JsonValue::as_str) {
+          Some("system") | Some("developer") => system_parts.push(json!({ "text": text })),
+          Some("assistant") => push_gemini_part(&mut contents, "model", json!({ "text": text })),
+          _ => push_gemini_part(&mut contents, "user", json!({ "text": text })),
+        }
+      }
+      "function_call" => {
+        let name = item
+          .get("name")
+          .and_then(JsonValue::as_str)
+          .unwrap_or_default()
+          .to_string();
+        if let Some(call_id) = item.get("call_id").and_then(JsonValue::as_str) {
+          call_names.insert(call_id.to_string(), name.clone());
+        }
+        push_gemini_part(
+          &mut contents,
+          "model",
+          json!({ "functionCall": { "name": name, "args": parse_function_arguments(item) } }),
+        );
+      }
+      "function_call_output" => {
+        let name = item
+          .get("call_id")
+          .and_then(JsonValue::as_str)
+          .and_then(|call_id| call_names.get(call_id))
+          .cloned()
+          .unwrap_or_default();
+        push_gemini_part(
+          &mut contents,
+          "user",
+          json!({
+            "functionResponse": {
+              "name": name,
+              "response": { "content": responses_function_output_text(item) },
+            }
+          }),
+        );
+      }
+      _ => {}
+    }
+  }
+
+  let mut body = json!({
+    "contents": contents,
+    "generationConfig": { "maxOutputTokens": max_output_tokens },
+  });
+  if !system_parts.is_empty() {
+    body["systemInstruction"] = json!({ "parts": system_parts });
+  }
+  let declarations: Vec<JsonValue> = responses_function_tools(request)
+    .into_iter()
+    .map(|tool| {
+      let mut declaration = json!({
+        "name": tool.get("name").cloned().unwrap_or(JsonValue::Null),
+      });
+      if let Some(description) = tool.get("description").filter(|value| !value.is_null()) {
+        declaration["description"] = description.clone();
+      }
+      if let Some(parameters) = tool.get("parameters") {
+        declaration["parameters"] = sanitize_gemini_schema(parameters);
+      }
+      declaration
+    })
+    .collect();
+  if !declarations.is_empty() {
+    body["tools"] = json!([{ "functionDeclarations": declarations }]);
+  }
+  body
+}
+
+fn gemini_response_to_output(response: &JsonValue) -> AdapterTurnOutput {
+  let mut items = Vec::new();
+  let mut text_parts = Vec::new();
+  let parts = response
+    .get("candidates")
+    .and_then(JsonValue::as_array)
+    .and_then(|candidates| candidates.first())
+    .and_then(|candidate| candidate.get("content"))
+    .and_then(|content| content.get("parts"))
+    .and_then(JsonValue::as_array);
+  for part in parts.into_iter().flatten() {
+    if let Some(text) = part.get("text").and_then(JsonValue::as_str) {
+      text_parts.push(text.to_string());
+    } else if let Some(call) = part.get("functionCall") {
+      items.push(json!({
+        "type": "function_call",
+        "id": format!("fc_{}", Uuid::new_v4().simple()),
+        "call_id": format!("call_{}", Uuid::new_v4().simple()),
+        "name": call.get("name").cloned().unwrap_or(JsonValue::Null),
+        "arguments": call.get("args").map(JsonValue::to_string).unwrap_or_else(|| "{}".to_string()),
+      }));
+    }
+  }
+  if !text_parts.is_empty() {
+    items.insert(0, assistant_output_message(text_parts.join("")));
+  }
+
+  let usage = response.get("usageMetadata");
+  AdapterTurnOutput {
+    items,
+    input_tokens: usage
+      .and_then(|u| u.get("promptTokenCount"))
+      .and_then(JsonValue::as_i64)
+      .unwrap_or(0),
+    output_tokens: usage
+      .and_then(|u| u.get("candidatesTokenCount"))
+      .and_then(JsonValue::as_i64)
+      .unwrap_or(0),
+  }
+}
+
+fn assistant_output_message(text: String) -> JsonValue {
+  json!({
+    "type": "message",
+    "role": "assistant",
+    "id": format!("msg_{}", Uuid::new_v4().simple()),
+    "content": [{ "type": "output_text", "text": text, "annotations": [] }],
+  })
+}
+
+#[cfg(test)]
+mod tests_provider_adapters {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn sample_responses_request() -> JsonValue {
+    json!({
+      "model": "claude-sonnet-4-5",
+      "instructions": "You are a coding agent.",
+      "input": [
+        { "type": "message", "role": "developer", "content": [{ "type": "input_text", "text": "Be brief." }] },
+        { "type": "message", "role": "user", "content": [{ "type": "input_text", "text": "List files" }] },
+        { "type": "function_call", "name": "shell", "call_id": "call_1", "arguments": "{\"command\":[\"ls\"]}" },
+        { "type": "function_call_output", "call_id": "call_1", "output": "README.md" },
+      ],
+      "tools": [
+        {
+          "type": "function",
+          "name": "shell",
+          "description": "Run a command",
+          "strict": false,
+          "parameters": { "type": "object", "properties": {}, "additionalProperties": false },
+        },
+        { "type": "web_search" },
+      ],
+    })
+  }
+
+  #[test]
+  fn converts_responses_request_to_anthropic_messages() {
+    let body = responses_to_anthropic_request(&sample_responses_request(), 1024);
+    assert_eq!(
+      body,
+      json!({
+        "model": "claude-sonnet-4-5",
+        "max_tokens": 1024,
+        "system": "You are a coding agent.\n\nBe brief.",
+        "messages": [
+          { "role": "user", "content": [{ "type": "text", "text": "List files" }] },
+          { "role": "assistant", "content": [{
+            "type": "tool_use", "id": "call_1", "name": "shell", "input": { "command": ["ls"] },
+          }] },
+          { "role": "user", "content": [{
+            "type": "tool_result", "tool_use_id": "call_1", "content": "README.md",
+          }] },
+        ],
+        "tools": [{
+          "name": "shell",
+          "description": "Run a command",
+          "input_schema": { "type": "object", "properties": {}, "additionalProperties": false },
+        }],
+      })
+    );
+  }
+
+  #[test]
+  fn converts_responses_request_to_gemini_contents() {
+    let body = responses_to_gemini_request(&sample_responses_request(), 512);
+    assert_eq!(
+      body,
+      json!({
+        "systemInstruction": { "parts": [{ "text": "You are a coding agent." }, { "text": "Be brief." }] },
+        "contents": [
+          { "role": "user", "parts": [{ "text": "List files" }] },
+          { "role": "model", "parts": [{ "functionCall": { "name": "shell", "args": { "command": ["ls"] } } }] },
+          { "role": "user", "parts": [{
+            "functionResponse": { "name": "shell", "response": { "content": "README.md" } },
+          }] },
+        ],
+        "generationConfig": { "maxOutputTokens": 512 },
+        "tools": [{ "functionDeclarations": [{
+          "name": "shell",
+          "description": "Run a command",
+          "parameters": { "type": "object", "properties": {} },
+        }] }],
+      })
+    );
+  }
+
+  #[test]
+  fn omits_missing_tool_descriptions() {
+    let request = json!({
+      "model": "claude-sonnet-4-5",
+      "input": [],
+      "tools": [{ "type": "function", "name": "noop" }],
+    });
+
+    let anthropic = responses_to_anthropic_request(&request, 1024);
+    assert_eq!(
+      anthropic["tools"],
+      json!([{ "name": "noop", "input_schema": { "type": "object", "properties": {} } }])
+    );
+    let gemini = responses_to_gemini_request(&request, 1024);
+    assert_eq!(
+      gemini["tools"],
+      json!([{ "functionDeclarations": [{ "name": "noop" }] }])
+    );
+  }
+
+  #[test]
+  fn stopping_the_adapter_ends_its_accept_loop() {
+    let server = ProviderAdapterServer::start().expect("start adapter");
+    std::net::TcpStream::connect(server.addr).expect("adapter accepts connections");
+
+    // `stop` joins the accept thread, so this returns only once the loop has exited.
+    server.stop();
+  }
+
+  #[test]
+  fn anthropic_tool_use_maps_to_function_call_items() {
+    let output = anthropic_response_to_output(&json!({
+      "content": [
+        { "type": "text", "text": "Running ls" },
+        { "type": "tool_use", "id": "toolu_1", "name": "shell", "input": { "command": ["ls"] } },
+      ],
+      "usage": { "input_tokens": 12, "output_tokens": 7 },
+    }));
+
+    assert_eq!(output.items.len(), 2);
+    assert_eq!(output.items[0]["content"][0]["text"], "Running ls");
+    assert_eq!(output.items[1]["call_id"], "toolu_1");
+    assert_eq!(output.items[1]["arguments"], "{\"command\":[\"ls\"]}");
+    assert_eq!((output.input_tokens, output.output_tokens), (12, 7));
+  }
+
+  #[test]
+  fn provider_override_uses_adapter_for_anthropic() {
+    register_model_provider(ModelProviderRegistration {
+      id: "claude-test".to_string(),
+      name: Some("Claude".to_string()),
+      base_url: "https://api.anthropic.com/v1/".to_string(),
+      api_key: Some("sk-test".to_string()),
+      env_key: None,
+      wire_api: Some("anthropic".to_string()),
+      http_headers: None,
+      max_output_tokens: None,
+    })
+    .expect("register provider");
+
+    let override_value =
+      registered_model_provider_override("claude-test").expect("override should exist");
+    let addr = ensure_provider_adapter_server().expect("adapter address");
+    assert_eq!(
+      override_value,
+      format!(
+        "model_providers.claude-test={{ name = \"Claude\", wire_api = \"responses\", base_url = \"http://{addr}/adapters/claude-test/v1\" }}"
+      )
+    );
+    let listed = list_registered_model_providers().expect("list providers");
+    assert!(listed.iter().all(|provider| provider.api_key.is_none()));
+    registered_model_providers()
+      .lock()
+      .unwrap()
+      .remove("claude-test");
+  }
+}
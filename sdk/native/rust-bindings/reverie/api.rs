@@ -56,7 +56,7 @@ pub async fn reverie_list_conversations(
   }
 
   let codex_home = Path::new(&codex_home_path);
-  let conversations = load_reverie_conversations(codex_home, max_conversations, skip_count)
+  let conversations = load_reverie_conversations(codex_home, max_conversations, skip_count, None)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to load conversations: {e}")))?;
 
@@ -77,32 +77,23 @@ pub async fn reverie_search_conversations(
   let max_results = limit.unwrap_or(20).max(1) as usize;
   let search_window = max_results.saturating_mul(5).min(500);
   let codex_home = Path::new(&codex_home_path);
-  let conversations = load_reverie_conversations(codex_home, search_window, 0)
+  let conversations = load_reverie_conversations(codex_home, search_window, 0, None)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to load conversations: {e}")))?;
 
-  let regex = regex::RegexBuilder::new(&regex::escape(trimmed_query))
-    .case_insensitive(true)
-    .unicode(true)
-    .build()
-    .map_err(|e| napi::Error::from_reason(format!("Invalid search query: {e}")))?;
+  let regex = codex_native_core::reverie::query_regex(trimmed_query)
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
 
   let mut results = Vec::new();
 
   for conv in conversations {
-    let mut relevance_score = 0.0;
-    let mut matching_excerpts = Vec::new();
     let mut insights = Vec::new();
 
     // Use JSON records for regex matching (excerpts)
-    for record in conv.head_records.iter().chain(conv.tail_records.iter()) {
-      for mat in regex.find_iter(record) {
-        relevance_score += 1.0;
-        let excerpt_start = mat.start().saturating_sub(50);
-        let excerpt_end = (mat.end() + 50).min(record.len());
-        matching_excerpts.push(format!("...{}...", &record[excerpt_start..excerpt_end]));
-      }
-    }
+    let (relevance_score, matching_excerpts) = codex_native_core::reverie::lexical_matches(
+      &regex,
+      conv.head_records.iter().chain(conv.tail_records.iter()),
+    );
 
     // Use TOON records for insights (LLM-friendly format)
     for record in conv.head_records_toon.iter().chain(conv.tail_records_toon.iter()) {
@@ -167,14 +158,51 @@ pub async fn reverie_search_semantic(
   context_text: String,
   options: Option<ReverieSemanticSearchOptions>,
 ) -> napi::Result<Vec<ReverieSearchResult>> {
+  reverie_search_semantic_with_stats(codex_home_path, context_text, options)
+    .await
+    .map(|response| response.results)
+}
+
+/// `reverieSearchSemantic` that also reports what the search read and how long it took.
+#[napi]
+pub async fn reverie_search_semantic_with_stats(
+  codex_home_path: String,
+  context_text: String,
+  options: Option<ReverieSemanticSearchOptions>,
+) -> napi::Result<ReverieSemanticSearchResponse> {
+  let started = std::time::Instant::now();
+  let scan = Arc::new(ReverieScan::new(
+    options
+      .as_ref()
+      .and_then(|opts| opts.max_resident_bytes)
+      .map_or(DEFAULT_REVERIE_MAX_RESIDENT_BYTES, u64::from),
+  ));
+  let respond = |results: Vec<ReverieSearchResult>, candidates: usize, early_terminated: bool| ReverieSemanticSearchResponse {
+    results,
+    stats: scan.stats(started, candidates, early_terminated),
+  };
   let trimmed = context_text.trim();
   if trimmed.is_empty() {
-    return Ok(Vec::new());
+    return Ok(respond(Vec::new(), 0, false));
   }
 
-  let query_context = SearchQueryContext::new(trimmed);
-
   let opts = options.unwrap_or_default();
+  let weights = ScoreWeights::from_options(&opts)?;
+  let cache_ttl = std::time::Duration::from_millis(u64::from(
+    opts.result_cache_ttl_ms.unwrap_or(DEFAULT_REVERIE_RESULT_CACHE_TTL_MS),
+  ));
+  let cache_key = (!cache_ttl.is_zero()).then(|| reverie_result_cache_key(&codex_home_path, trimmed, &opts));
+  let corpus_version = reverie_corpus_version();
+  if let Some(key) = &cache_key
+    && let Some((results, candidates)) = cached_search_results(key, cache_ttl)
+  {
+    let mut response = respond(results, candidates as usize, false);
+    response.stats.cache_hit = true;
+    return Ok(response);
+  }
+  let language = opts.language.as_deref().map(KeywordLanguage::parse).transpose()?;
+  let query_context = SearchQueryContext::new(trimmed, language);
+
   let limit = opts.limit.unwrap_or(10).max(1) as usize;
   let max_candidates = opts
     .max_candidates
@@ -188,60 +216,31 @@ pub async fn reverie_search_semantic(
     .map(normalize_path);
 
   let codex_home = Path::new(&codex_home_path);
-  let raw_conversations = load_reverie_conversations(codex_home, max_candidates.saturating_mul(2), 0)
+  let raw_conversations = load_reverie_conversations(codex_home, max_candidates.saturating_mul(2), 0, opts.cpu_threads)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to load conversations: {e}")))?;
 
-  let mut scored_conversations: Vec<(usize, ReverieConversation)> = Vec::new();
-  for conversation in raw_conversations {
-    if !conversation_matches_project(
-      conversation.cwd.as_deref(),
-      &conversation.head_records,
-      normalized_project_root.as_deref(),
-    ) {
-      continue;
-    }
-
-    let lex_score = conversation_lexical_score(&conversation, query_context.keyword_text());
-    scored_conversations.push((lex_score, conversation));
-  }
-
-  if scored_conversations.is_empty() {
-    return Ok(Vec::new());
-  }
-
-  scored_conversations.sort_by(|a, b| b.0.cmp(&a.0));
-
-  let lexical_budget = max_candidates.saturating_mul(2);
-  let mut candidates = Vec::<SemanticCandidate>::new();
-  let mut total_documents = 0usize;
-  for (_lex_score, conversation) in scored_conversations.into_iter().take(lexical_budget) {
-    let insights = derive_insights_for_semantic(&conversation.head_records_toon, &conversation.tail_records_toon);
-    let message_chunks = build_compact_document(&conversation, &insights, Some(query_context.keyword_text()));
-
-    if message_chunks.is_empty() {
-      continue;
-    }
-
-    total_documents += message_chunks.len();
-    candidates.push(SemanticCandidate {
-      conversation,
-      insights,
-      message_chunks,
-    });
-
-    if candidates.len() >= max_candidates {
-      break;
-    }
-  }
+  let keyword_text = query_context.keyword_text().to_string();
+  let language = query_context.language();
+  // Enough strong lexical matches make the weaker rest unlikely to reach the top `limit`.
+  let strong_enough = opts.early_stop.unwrap_or(true).then_some(limit.saturating_mul(EARLY_STOP_FACTOR));
+  let pool_scan = Arc::clone(&scan);
+  let (candidates, early_terminated) = run_on_reverie_pool(opts.cpu_threads, move || {
+    let ranked = rank_conversations_lexically(raw_conversations, &keyword_text, language, normalized_project_root.as_deref());
+    build_semantic_candidates(ranked, max_candidates, Some((&keyword_text, language)), &pool_scan, strong_enough)
+  })
+  .await
+  .map_err(|e| napi::Error::from_reason(format!("Failed to prepare conversations: {e}")))?;
+  let total_documents: usize = candidates.iter().map(|candidate| candidate.message_chunks.len()).sum();
 
+  let candidate_count = candidates.len();
   if candidates.is_empty() || total_documents == 0 {
-    return Ok(Vec::new());
+    return Ok(respond(Vec::new(), candidate_count, early_terminated));
   }
 
   let embedding_queries = build_embedding_queries(&query_context);
   if embedding_queries.is_empty() {
-    return Ok(Vec::new());
+    return Ok(respond(Vec::new(), candidate_count, early_terminated));
   }
 
   let mut inputs = Vec::with_capacity(total_documents.saturating_add(embedding_queries.len()));
@@ -255,13 +254,13 @@ pub async fn reverie_search_semantic(
       doc_refs.push(MessageDocRef {
         candidate_idx,
         message_idx,
-        keyword_score: score_query_relevance(chunk, query_context.keyword_text()),
+        keyword_score: score_query_relevance_in(chunk, query_context.keyword_text(), query_context.language()),
       });
     }
   }
 
   if doc_refs.is_empty() {
-    return Ok(Vec::new());
+    return Ok(respond(Vec::new(), candidate_count, early_terminated));
   }
 
   let embed_request = FastEmbedEmbedRequest {
@@ -300,7 +299,7 @@ pub async fn reverie_search_semantic(
   let mut matches: Vec<RankedMatch> = candidates
     .into_iter()
     .zip(per_candidate_matches.into_iter())
-    .filter_map(|(candidate, message_matches)| RankedMatch::new(candidate, message_matches))
+    .filter_map(|(candidate, message_matches)| RankedMatch::new(candidate, message_matches, &weights))
     .collect();
 
   if let Err(err) = maybe_rerank_matches(&mut matches, query_context.original(), &opts).await {
@@ -312,9 +311,17 @@ pub async fn reverie_search_semantic(
     .relevance_score
     .partial_cmp(&a.result.relevance_score)
     .unwrap_or(std::cmp::Ordering::Equal));
-  matches.truncate(limit);
+  if opts.diversify.unwrap_or(false) {
+    matches = diversify_matches(matches, limit, opts.diversity_lambda.unwrap_or(DEFAULT_DIVERSITY_LAMBDA));
+  } else {
+    matches.truncate(limit);
+  }
 
-  Ok(matches.into_iter().map(|entry| entry.result).collect())
+  let results: Vec<ReverieSearchResult> = matches.into_iter().map(|entry| entry.result).collect();
+  if let Some(key) = cache_key {
+    store_search_results(key, corpus_version, &results, candidate_count as u32);
+  }
+  Ok(respond(results, candidate_count, early_terminated))
 }
 
 #[napi]
@@ -334,31 +341,28 @@ pub async fn reverie_index_semantic(
     .map(normalize_path);
 
   let codex_home = Path::new(&codex_home_path);
-  let conversations = load_reverie_conversations(codex_home, max_candidates, 0)
+  let conversations = load_reverie_conversations(codex_home, max_candidates, 0, opts.cpu_threads)
     .await
     .map_err(|e| napi::Error::from_reason(format!("Failed to load conversations: {e}")))?;
 
-  let mut documents = Vec::new();
-  let mut conversations_indexed = 0i32;
-  for conversation in conversations {
-    if conversations_indexed as usize >= conversation_limit {
-      break;
-    }
-    if !conversation_matches_project(
-      conversation.cwd.as_deref(),
-      &conversation.head_records,
-      project_root.as_deref(),
-    ) {
-      continue;
-    }
-    let insights = derive_insights_for_semantic(&conversation.head_records_toon, &conversation.tail_records_toon);
-    let doc_chunks = build_compact_document(&conversation, &insights, None); // No query during indexing
-    if doc_chunks.is_empty() {
-      continue;
-    }
-    conversations_indexed += 1;
-    documents.extend(doc_chunks);
-  }
+  let candidates = run_on_reverie_pool(opts.cpu_threads, move || {
+    let in_project: Vec<ReverieConversation> = conversations
+      .into_iter()
+      .filter(|conversation| {
+        conversation_matches_project(conversation.cwd.as_deref(), &conversation.head_records, project_root.as_deref())
+      })
+      .collect();
+    let scored = in_project.into_iter().map(|conversation| (0, conversation)).collect();
+    let scan = ReverieScan::unbounded();
+    build_semantic_candidates(scored, conversation_limit, None, &scan, None).0 // No query during indexing
+  })
+  .await
+  .map_err(|e| napi::Error::from_reason(format!("Failed to prepare conversations: {e}")))?;
+  let conversations_indexed = candidates.len() as i32;
+  let documents: Vec<String> = candidates
+    .into_iter()
+    .flat_map(|candidate| candidate.message_chunks)
+    .collect();
 
   if documents.is_empty() {
     return Ok(ReverieSemanticIndexStats {
@@ -0,0 +1,186 @@
+// Search budgets and statistics.
+//
+// Candidate preparation streams each rollout line by line and keeps only the
+// records it needs. All reads in one search share a byte budget
+// (`maxResidentBytes`, 64 MiB by default). Once it is spent, later candidates
+// are built from the head and tail previews already in memory. Preparation
+// also stops early once `limit * EARLY_STOP_FACTOR` candidates with a strong
+// lexical match are ready, since the lexically weaker rest rarely outranks
+// them. ReverieSearchStats reports what a query read and how long it took.
+
+const DEFAULT_REVERIE_MAX_RESIDENT_BYTES: u64 = 64 * 1024 * 1024;
+const EARLY_STOP_FACTOR: usize = 2;
+/// Lexical scores at or above this come from an exact technical term or phrase match.
+const STRONG_LEXICAL_SCORE: usize = 100;
+
+/// Byte budget and counters shared by the reads of one search.
+struct ReverieScan {
+  max_bytes: u64,
+  bytes_read: std::sync::atomic::AtomicU64,
+  files_scanned: std::sync::atomic::AtomicU32,
+  budget_exhausted: std::sync::atomic::AtomicBool,
+}
+
+impl ReverieScan {
+  fn new(max_bytes: u64) -> Self {
+    Self {
+      max_bytes,
+      bytes_read: std::sync::atomic::AtomicU64::new(0),
+      files_scanned: std::sync::atomic::AtomicU32::new(0),
+      budget_exhausted: std::sync::atomic::AtomicBool::new(false),
+    }
+  }
+
+  fn unbounded() -> Self {
+    Self::new(u64::MAX)
+  }
+
+  /// Counts a rollout about to be read; false once the budget is spent.
+  fn begin_file(&self) -> bool {
+    use std::sync::atomic::Ordering;
+    if self.budget_exhausted.load(Ordering::Relaxed) {
+      return false;
+    }
+    self.files_scanned.fetch_add(1, Ordering::Relaxed);
+    true
+  }
+
+  /// Counts `bytes` just read; false when they overran the budget and reading should stop.
+  fn record_bytes(&self, bytes: usize) -> bool {
+    use std::sync::atomic::Ordering;
+    let bytes = bytes as u64;
+    let total = self.bytes_read.fetch_add(bytes, Ordering::Relaxed).saturating_add(bytes);
+    if total > self.max_bytes {
+      self.budget_exhausted.store(true, Ordering::Relaxed);
+      return false;
+    }
+    true
+  }
+
+  fn stats(&self, started: std::time::Instant, candidates: usize, early_terminated: bool) -> ReverieSearchStats {
+    use std::sync::atomic::Ordering;
+    ReverieSearchStats {
+      files_scanned: self.files_scanned.load(Ordering::Relaxed),
+      bytes_read: i64::try_from(self.bytes_read.load(Ordering::Relaxed)).unwrap_or(i64::MAX),
+      elapsed_ms: u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX),
+      candidates: candidates as u32,
+      early_terminated,
+      budget_exhausted: self.budget_exhausted.load(Ordering::Relaxed),
+      cache_hit: false,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverieSearchStats {
+  /// Rollouts streamed while preparing candidates.
+  #[napi(js_name = "filesScanned")]
+  pub files_scanned: u32,
+  /// Bytes of rollout text those reads consumed.
+  #[napi(js_name = "bytesRead")]
+  pub bytes_read: i64,
+  #[napi(js_name = "elapsedMs")]
+  pub elapsed_ms: u32,
+  /// Conversations embedded and scored.
+  pub candidates: u32,
+  /// Preparation stopped once enough strong lexical matches were found.
+  #[napi(js_name = "earlyTerminated")]
+  pub early_terminated: bool,
+  /// `maxResidentBytes` ran out and later candidates used their previews only.
+  #[napi(js_name = "budgetExhausted")]
+  pub budget_exhausted: bool,
+  /// Served from the result cache without reading anything.
+  #[napi(js_name = "cacheHit")]
+  pub cache_hit: bool,
+}
+
+#[napi(object)]
+pub struct ReverieSemanticSearchResponse {
+  pub results: Vec<ReverieSearchResult>,
+  pub stats: ReverieSearchStats,
+}
+
+/// Up to `max_records` non-metadata records from record `skip_records` on, read line by line so
+/// only the records kept are held. Returns what it has once `scan`'s budget runs out.
+fn stream_conversation_json_segments(
+  path: &str,
+  skip_records: usize,
+  max_records: usize,
+  scan: &ReverieScan,
+) -> Vec<serde_json::Value> {
+  use std::io::BufRead;
+
+  if max_records == 0 || !scan.begin_file() {
+    return Vec::new();
+  }
+  let Ok(mut lines) = open_rollout_lines(Path::new(path)) else {
+    return Vec::new();
+  };
+  let mut segments = Vec::new();
+  let mut records = 0usize;
+  let mut line = String::new();
+  while segments.len() < max_records {
+    line.clear();
+    match lines.read_line(&mut line) {
+      Ok(0) | Err(_) => break,
+      Ok(read) => {
+        if !scan.record_bytes(read) {
+          break;
+        }
+      }
+    }
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) else {
+      continue;
+    };
+    records += 1;
+    if records <= skip_records || is_metadata_record(&value) {
+      continue;
+    }
+    segments.push(value);
+  }
+  segments
+}
+
+#[cfg(test)]
+mod budget_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn streams_segments_within_the_byte_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rollout.jsonl");
+    let mut contents = String::new();
+    for turn in 0..10 {
+      contents.push_str(&format!(
+        r#"{{"type":"event_msg","payload":{{"type":"user_message","message":"step {turn}"}}}}"#
+      ));
+      contents.push('\n');
+    }
+    std::fs::write(&path, &contents).unwrap();
+    let path = path.to_string_lossy().to_string();
+    let message = |value: &serde_json::Value| value["payload"]["message"].as_str().unwrap().to_string();
+
+    let scan = ReverieScan::unbounded();
+    let segments = stream_conversation_json_segments(&path, 7, 200, &scan);
+    assert_eq!(segments.iter().map(message).collect::<Vec<_>>(), vec!["step 7", "step 8", "step 9"]);
+    let stats = scan.stats(std::time::Instant::now(), 1, false);
+    assert_eq!((stats.files_scanned, stats.bytes_read), (1, contents.len() as i64));
+    assert!(!stats.budget_exhausted);
+
+    // The budget covers two lines; the next file isn't opened at all.
+    let line_len = contents.lines().next().unwrap().len() + 1;
+    let scan = ReverieScan::new((line_len * 2) as u64);
+    assert_eq!(stream_conversation_json_segments(&path, 0, 200, &scan).len(), 2);
+    assert!(stream_conversation_json_segments(&path, 0, 200, &scan).is_empty());
+    let stats = scan.stats(std::time::Instant::now(), 0, false);
+    assert_eq!(stats.files_scanned, 1);
+    assert!(stats.budget_exhausted);
+  }
+}
@@ -0,0 +1,168 @@
+// Result diversification for semantic search.
+//
+// Forked threads and retried sessions repeat most of their messages, so
+// without diversification they fill the top results with one story. Each
+// match carries a MinHash signature of its chunks' word shingles. With
+// `diversify`, matches whose estimated Jaccard similarity to a better match
+// reaches NEAR_DUPLICATE_JACCARD are dropped, and the rest are picked by
+// maximal marginal relevance: relevance traded against similarity to what
+// was already picked.
+
+const MINHASH_PERMUTATIONS: u64 = 64;
+const MINHASH_SHINGLE_WORDS: usize = 3;
+const NEAR_DUPLICATE_JACCARD: f64 = 0.8;
+const DEFAULT_DIVERSITY_LAMBDA: f64 = 0.7;
+
+fn splitmix64(mut value: u64) -> u64 {
+  value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+  value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  value ^ (value >> 31)
+}
+
+/// MinHash signature over the word shingles of `chunks`; empty when they have no words.
+fn minhash_signature(chunks: &[String]) -> Vec<u64> {
+  use std::hash::Hash;
+  use std::hash::Hasher;
+
+  let mut shingles = HashSet::new();
+  for chunk in chunks {
+    let words: Vec<String> = chunk
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .map(str::to_lowercase)
+      .collect();
+    for shingle in words.windows(MINHASH_SHINGLE_WORDS.min(words.len()).max(1)) {
+      let mut hasher = std::collections::hash_map::DefaultHasher::new();
+      shingle.hash(&mut hasher);
+      shingles.insert(hasher.finish());
+    }
+  }
+  if shingles.is_empty() {
+    return Vec::new();
+  }
+  (0..MINHASH_PERMUTATIONS)
+    .map(|seed| {
+      let seed = splitmix64(seed);
+      shingles
+        .iter()
+        .map(|shingle| splitmix64(shingle ^ seed))
+        .min()
+        .unwrap_or(u64::MAX)
+    })
+    .collect()
+}
+
+/// Estimated Jaccard similarity of the shingle sets behind two signatures.
+fn estimated_jaccard(a: &[u64], b: &[u64]) -> f64 {
+  if a.is_empty() || a.len() != b.len() {
+    return 0.0;
+  }
+  let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+  equal as f64 / a.len() as f64
+}
+
+/// `matches` sorted best first, without near-duplicates and reordered by MMR, cut to `limit`.
+fn diversify_matches(matches: Vec<RankedMatch>, limit: usize, lambda: f64) -> Vec<RankedMatch> {
+  let mut distinct: Vec<RankedMatch> = Vec::new();
+  for candidate in matches {
+    let duplicate = distinct.iter().any(|kept| {
+      estimated_jaccard(&kept.signature, &candidate.signature) >= NEAR_DUPLICATE_JACCARD
+    });
+    if !duplicate {
+      distinct.push(candidate);
+    }
+  }
+
+  // Reranker scores aren't bounded, so relevance is rescaled to 0..=1 before mixing.
+  let (min, max) = distinct.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), entry| {
+    let score = entry.result.relevance_score;
+    (min.min(score), max.max(score))
+  });
+  let relevance = |entry: &RankedMatch| {
+    if max > min {
+      (entry.result.relevance_score - min) / (max - min)
+    } else {
+      1.0
+    }
+  };
+
+  let lambda = lambda.clamp(0.0, 1.0);
+  let mut selected: Vec<RankedMatch> = Vec::with_capacity(limit.min(distinct.len()));
+  while selected.len() < limit && !distinct.is_empty() {
+    let mut best_idx = 0;
+    let mut best_score = f64::NEG_INFINITY;
+    for (idx, candidate) in distinct.iter().enumerate() {
+      let redundancy = selected
+        .iter()
+        .map(|picked| estimated_jaccard(&picked.signature, &candidate.signature))
+        .fold(0.0, f64::max);
+      let score = lambda * relevance(candidate) - (1.0 - lambda) * redundancy;
+      if score > best_score {
+        best_score = score;
+        best_idx = idx;
+      }
+    }
+    selected.push(distinct.remove(best_idx));
+  }
+  selected
+}
+
+#[cfg(test)]
+mod diversity_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn ranked(id: &str, score: f64, chunks: &[&str]) -> RankedMatch {
+    let chunks: Vec<String> = chunks.iter().map(ToString::to_string).collect();
+    RankedMatch {
+      doc_text: chunks.first().cloned().unwrap_or_default(),
+      signature: minhash_signature(&chunks),
+      result: ReverieSearchResult {
+        conversation: ReverieConversation {
+          id: id.to_string(),
+          path: format!("/tmp/{id}.jsonl"),
+          cwd: None,
+          created_at: None,
+          updated_at: None,
+          head_records: Vec::new(),
+          tail_records: Vec::new(),
+          head_records_toon: Vec::new(),
+          tail_records_toon: Vec::new(),
+        },
+        relevance_score: score,
+        matching_excerpts: Vec::new(),
+        insights: Vec::new(),
+        reranker_score: None,
+      },
+    }
+  }
+
+  #[test]
+  fn drops_forks_and_keeps_distinct_sessions() {
+    let retry = [
+      "the auth middleware rejects refresh tokens after the session store migration",
+      "fixed by reading the refresh token expiry from the new sessions table",
+    ];
+    let matches = vec![
+      ranked("original", 0.9, &retry),
+      ranked("fork", 0.89, &retry),
+      ranked(
+        "other",
+        0.5,
+        &["flaky snapshot test caused by unsorted hash map iteration in the exporter"],
+      ),
+    ];
+    assert_eq!(
+      estimated_jaccard(&matches[0].signature, &matches[1].signature),
+      1.0
+    );
+    assert!(estimated_jaccard(&matches[0].signature, &matches[2].signature) < 0.2);
+
+    let ids: Vec<String> = diversify_matches(matches, 2, DEFAULT_DIVERSITY_LAMBDA)
+      .into_iter()
+      .map(|entry| entry.result.conversation.id)
+      .collect();
+    assert_eq!(ids, vec!["original".to_string(), "other".to_string()]);
+  }
+}
@@ -1,34 +1,3 @@
-fn load_full_conversation_json_segments(path: &str, max_records: usize) -> Vec<serde_json::Value> {
-  if max_records == 0 {
-    return Vec::new();
-  }
-  let file = match File::open(path) {
-    Ok(file) => file,
-    Err(_) => return Vec::new(),
-  };
-  let reader = BufReader::new(file);
-  let mut records = Vec::new();
-  for line in reader.lines() {
-    if records.len() >= max_records {
-      break;
-    }
-    let line = match line {
-      Ok(line) => line,
-      Err(_) => continue,
-    };
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-      continue;
-    }
-    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed)
-      && !is_metadata_record(&value)
-    {
-      records.push(value);
-    }
-  }
-  records
-}
-
 #[allow(dead_code)]
 fn parse_json_strings(records: &[String], limit: usize) -> Vec<serde_json::Value> {
   if limit == 0 {
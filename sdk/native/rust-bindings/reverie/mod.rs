@@ -2,6 +2,12 @@ include!("types.rs");
 include!("api.rs");
 include!("storage.rs");
 include!("ranking.rs");
+include!("diversity.rs");
+include!("parallel.rs");
+include!("budget.rs");
+include!("result_cache.rs");
+include!("related.rs");
+include!("weights.rs");
 include!("scoring.rs");
 include!("text_analysis.rs");
 include!("json_utils.rs");
@@ -0,0 +1,152 @@
+// Parallel candidate preparation.
+//
+// Reading rollouts, TOON encoding, lexical scoring and chunk extraction are
+// independent per conversation, so they run on a rayon pool instead of one
+// conversation at a time. `cpuThreads` bounds the pool; omitted, rayon sizes
+// it to the machine. Pools are built once per size and reused. Work is
+// handed to the pool from a blocking task so the tokio workers stay free.
+
+fn reverie_thread_pool(cpu_threads: Option<u32>) -> std::io::Result<Arc<rayon::ThreadPool>> {
+  static POOLS: OnceLock<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> = OnceLock::new();
+  // Zero asks rayon for its default size.
+  let threads = cpu_threads.unwrap_or(0) as usize;
+  let mut pools = POOLS
+    .get_or_init(|| Mutex::new(HashMap::new()))
+    .lock()
+    .map_err(|e| std::io::Error::other(format!("reverie pool mutex poisoned: {e}")))?;
+  if let Some(pool) = pools.get(&threads) {
+    return Ok(Arc::clone(pool));
+  }
+  let pool = rayon::ThreadPoolBuilder::new()
+    .num_threads(threads)
+    .thread_name(|index| format!("reverie-{index}"))
+    .build()
+    .map(Arc::new)
+    .map_err(|e| std::io::Error::other(format!("Failed to start reverie thread pool: {e}")))?;
+  pools.insert(threads, Arc::clone(&pool));
+  Ok(pool)
+}
+
+async fn run_on_reverie_pool<T, F>(cpu_threads: Option<u32>, work: F) -> std::io::Result<T>
+where
+  F: FnOnce() -> T + Send + 'static,
+  T: Send + 'static,
+{
+  let pool = reverie_thread_pool(cpu_threads)?;
+  tokio::task::spawn_blocking(move || pool.install(work))
+    .await
+    .map_err(|e| std::io::Error::other(format!("Reverie worker failed: {e}")))
+}
+
+/// Conversations in the project with their lexical scores against `keyword_text`, best first.
+fn rank_conversations_lexically(
+  conversations: Vec<ReverieConversation>,
+  keyword_text: &str,
+  language: KeywordLanguage,
+  project_root: Option<&Path>,
+) -> Vec<(usize, ReverieConversation)> {
+  use rayon::prelude::*;
+
+  let mut scored: Vec<(usize, ReverieConversation)> = conversations
+    .into_par_iter()
+    .filter(|conversation| {
+      conversation_matches_project(conversation.cwd.as_deref(), &conversation.head_records, project_root)
+    })
+    .map(|conversation| (conversation_lexical_score(&conversation, keyword_text, language), conversation))
+    .collect();
+  scored.sort_by(|a, b| b.0.cmp(&a.0));
+  scored
+}
+
+const CANDIDATE_BATCH: usize = 32;
+
+/// The first `wanted` of the scored conversations, in order, that yield any chunks, and whether
+/// preparation stopped early because `strong_enough` candidates scored
+/// STRONG_LEXICAL_SCORE or more. Conversations are built a batch at a time, each batch no larger
+/// than the shortfall, so few more rollouts are read than a sequential pass would.
+fn build_semantic_candidates(
+  conversations: Vec<(usize, ReverieConversation)>,
+  wanted: usize,
+  query: Option<(&str, KeywordLanguage)>,
+  scan: &ReverieScan,
+  strong_enough: Option<usize>,
+) -> (Vec<SemanticCandidate>, bool) {
+  use rayon::prelude::*;
+
+  let mut remaining = conversations.into_iter();
+  let mut candidates = Vec::new();
+  let mut strong = 0usize;
+  while candidates.len() < wanted {
+    if strong_enough.is_some_and(|enough| strong >= enough) {
+      return (candidates, !remaining.as_slice().is_empty());
+    }
+    let batch_size = (wanted - candidates.len()).min(CANDIDATE_BATCH);
+    let batch: Vec<(usize, ReverieConversation)> = remaining.by_ref().take(batch_size).collect();
+    if batch.is_empty() {
+      break;
+    }
+    let built: Vec<(usize, SemanticCandidate)> = batch
+      .into_par_iter()
+      .filter_map(|(lexical_score, conversation)| {
+        let insights = derive_insights_for_semantic(&conversation.head_records_toon, &conversation.tail_records_toon);
+        let message_chunks = build_compact_document(&conversation, &insights, query, scan);
+        (!message_chunks.is_empty()).then_some((
+          lexical_score,
+          SemanticCandidate {
+            conversation,
+            insights,
+            message_chunks,
+          },
+        ))
+      })
+      .collect();
+    for (lexical_score, candidate) in built {
+      if lexical_score >= STRONG_LEXICAL_SCORE {
+        strong += 1;
+      }
+      candidates.push(candidate);
+    }
+  }
+  (candidates, false)
+}
+
+#[cfg(test)]
+mod parallel_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn conversation(id: &str, toon: &str) -> ReverieConversation {
+    ReverieConversation {
+      id: id.to_string(),
+      path: format!("/nonexistent/{id}.jsonl"),
+      cwd: Some("/tmp/workspace".to_string()),
+      created_at: None,
+      updated_at: None,
+      head_records: Vec::new(),
+      tail_records: Vec::new(),
+      head_records_toon: vec![toon.to_string()],
+      tail_records_toon: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn prepares_the_best_lexical_candidates_in_order() {
+    let conversations = vec![
+      conversation("empty", " "),
+      conversation("other", "updated the changelog"),
+      conversation("best", "fixed the websocket reconnect timeout in the websocket client"),
+      conversation("good", "websocket handshake"),
+    ];
+    let pool = reverie_thread_pool(Some(2)).unwrap();
+    let scan = ReverieScan::unbounded();
+    let (ids, early): (Vec<String>, bool) = pool.install(|| {
+      let ranked = rank_conversations_lexically(conversations, "websocket timeout", KeywordLanguage::English, None);
+      let (candidates, early) =
+        build_semantic_candidates(ranked, 2, Some(("websocket timeout", KeywordLanguage::English)), &scan, None);
+      (candidates.into_iter().map(|candidate| candidate.conversation.id).collect(), early)
+    });
+    assert_eq!(ids, vec!["best".to_string(), "good".to_string()]);
+    assert!(!early);
+    assert!(Arc::ptr_eq(&pool, &reverie_thread_pool(Some(2)).unwrap()));
+  }
+}
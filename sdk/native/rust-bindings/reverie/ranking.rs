@@ -20,11 +20,13 @@ struct MessageMatch {
 #[derive(Clone)]
 struct RankedMatch {
   doc_text: String,
+  /// MinHash of the conversation's chunks, for near-duplicate detection.
+  signature: Vec<u64>,
   result: ReverieSearchResult,
 }
 
 impl RankedMatch {
-  fn new(candidate: SemanticCandidate, mut message_matches: Vec<MessageMatch>) -> Option<Self> {
+  fn new(candidate: SemanticCandidate, mut message_matches: Vec<MessageMatch>, weights: &ScoreWeights) -> Option<Self> {
     if message_matches.is_empty() {
       return None;
     }
@@ -42,6 +44,7 @@ impl RankedMatch {
       insights,
       message_chunks,
     } = candidate;
+    let signature = minhash_signature(&message_chunks);
 
     let best_match = message_matches.first()?;
     let doc_text = message_chunks.get(best_match.message_idx)?.clone();
@@ -59,9 +62,9 @@ impl RankedMatch {
 
     let semantic_component = normalize_semantic_score(avg_semantic);
     let keyword_component = normalize_keyword_score(best_keyword_raw);
-    let recency_component = recency_score(&conversation.updated_at);
+    let recency_component = weights.recency_score(&conversation.updated_at);
     let importance_component = compute_conversation_importance(&message_matches, &message_chunks);
-    let blended_score = blend_similarity_scores(
+    let blended_score = weights.blend(
       semantic_component,
       keyword_component,
       recency_component,
@@ -84,6 +87,7 @@ impl RankedMatch {
 
     Some(Self {
       doc_text,
+      signature,
       result: ReverieSearchResult {
         conversation,
         relevance_score: blended_score,
@@ -0,0 +1,118 @@
+// Related sessions (reverieRelatedThreads)
+//
+// "You solved something like this before" panels need a query that stands
+// for a whole thread. reverieRelatedThreads() builds one from the thread's
+// rollups (summarizeAndStore), falling back to its stored abstract
+// (generateThreadSummary) and then to a transcript of its history, and runs
+// a semantic search with it, leaving the thread itself out of the results.
+
+const DEFAULT_RELATED_THREADS_LIMIT: u32 = 5;
+const RELATED_THREADS_TRANSCRIPT_CHARS: usize = 4_000;
+
+/// Text standing for the thread in the search, best source first.
+fn related_threads_query(codex_home: &Path, thread_id: &str, rollout_path: &Path) -> napi::Result<String> {
+  let rollups = read_thread_rollups(rollout_path);
+  if !rollups.is_empty() {
+    return Ok(rollups.into_iter().map(|rollup| rollup.summary).collect::<Vec<_>>().join("\n\n"));
+  }
+  if let Some(summary) = read_thread_summaries(codex_home).remove(thread_id) {
+    return Ok(format!("{}\n\n{}", summary.title, summary.summary));
+  }
+  let rollout = load_cached_rollout(rollout_path)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read rollout {}: {e}", rollout_path.display())))?;
+  Ok(thread_summary_transcript(&collect_thread_history(&rollout), RELATED_THREADS_TRANSCRIPT_CHARS))
+}
+
+/// Other sessions most similar to the thread, best first.
+#[napi]
+pub async fn reverie_related_threads(
+  thread_id: String,
+  limit: Option<u32>,
+  options: Option<ReverieSemanticSearchOptions>,
+) -> napi::Result<Vec<ReverieSearchResult>> {
+  let codex_home = find_codex_home()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve CODEX_HOME: {e}")))?;
+  let rollout_path = find_thread_path_by_id_str(&codex_home, &thread_id)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?
+    .ok_or_else(|| napi::Error::from_reason(format!("No rollout found for thread {thread_id}")))?;
+  let query = related_threads_query(&codex_home, &thread_id, &rollout_path)?;
+  if query.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let mut opts = options.unwrap_or_default();
+  let limit = limit
+    .or(opts.limit.map(|value| value.max(1) as u32))
+    .unwrap_or(DEFAULT_RELATED_THREADS_LIMIT)
+    .max(1) as usize;
+  // One extra, since the thread usually matches itself best.
+  opts.limit = Some(limit as i32 + 1);
+  let own_path = canonicalize_to_string(&rollout_path);
+  let mut results = reverie_search_semantic(codex_home.to_string_lossy().into_owned(), query, Some(opts)).await?;
+  results.retain(|result| {
+    canonicalize_to_string(Path::new(&result.conversation.path)) != own_path
+      && !result.conversation.id.ends_with(&thread_id)
+  });
+  results.truncate(limit);
+  Ok(results)
+}
+
+#[cfg(test)]
+mod related_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn query_prefers_rollups_then_summaries_then_history() {
+    let home = tempfile::tempdir().unwrap();
+    let rollout = home.path().join("rollout-2025-01-01T00-00-00-thread.jsonl");
+    std::fs::write(
+      &rollout,
+      concat!(
+        r#"{"type":"event_msg","payload":{"type":"user_message","message":"the parser drops trailing commas"}}"#,
+        "\n"
+      ),
+    )
+    .unwrap();
+    let query = related_threads_query(home.path(), "thread", &rollout).unwrap();
+    assert!(query.contains("the parser drops trailing commas"));
+
+    append_thread_summary(
+      home.path(),
+      &ThreadSummary {
+        thread_id: "thread".to_string(),
+        title: "Parser fix".to_string(),
+        summary: "Kept trailing commas in arrays".to_string(),
+        model: None,
+        generated_at: "2025-01-01T00:00:00Z".to_string(),
+      },
+    )
+    .unwrap();
+    assert_eq!(
+      related_threads_query(home.path(), "thread", &rollout).unwrap(),
+      "Parser fix\n\nKept trailing commas in arrays"
+    );
+
+    let rollup = |summary: &str| ThreadRollup {
+      start_turn: 0,
+      end_turn: 1,
+      end_record: 1,
+      summary: summary.to_string(),
+      model: None,
+      generated_at: "2025-01-01T00:00:00Z".to_string(),
+    };
+    write_thread_rollups(
+      &rollout,
+      &StoredThreadRollups {
+        thread_id: "thread".to_string(),
+        rollups: vec![rollup("Found the comma bug"), rollup("Fixed the tokenizer")],
+      },
+    )
+    .unwrap();
+    assert_eq!(
+      related_threads_query(home.path(), "thread", &rollout).unwrap(),
+      "Found the comma bug\n\nFixed the tokenizer"
+    );
+  }
+}
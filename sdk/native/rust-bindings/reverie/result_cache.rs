@@ -0,0 +1,158 @@
+// Semantic search result cache.
+//
+// Chat UIs often re-run the same search on every pause in typing. Results are
+// kept for `resultCacheTtlMs` (30 s by default) under the codex home, query,
+// the options that change results, and a corpus version. The version is
+// bumped whenever this process starts or finishes a turn, or archives,
+// encrypts or deletes rollouts, so new sessions are never hidden by a stale
+// entry. Rollouts written by other processes show up once the TTL expires.
+
+const DEFAULT_REVERIE_RESULT_CACHE_TTL_MS: u32 = 30_000;
+const REVERIE_RESULT_CACHE_CAPACITY: usize = 32;
+
+static REVERIE_CORPUS_VERSION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+fn reverie_corpus_version() -> u64 {
+  REVERIE_CORPUS_VERSION.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Invalidates every cached search result.
+fn bump_reverie_corpus_version() {
+  REVERIE_CORPUS_VERSION.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Bumps the corpus version for run events that create or extend a rollout.
+fn note_reverie_corpus_event(event: &ExecThreadEvent) {
+  if matches!(
+    event,
+    ExecThreadEvent::ThreadStarted(_) | ExecThreadEvent::TurnCompleted(_) | ExecThreadEvent::TurnFailed(_)
+  ) {
+    bump_reverie_corpus_version();
+  }
+}
+
+struct CachedSearch {
+  corpus_version: u64,
+  stored_at: std::time::Instant,
+  results: Vec<ReverieSearchResult>,
+  candidates: u32,
+}
+
+fn reverie_result_cache() -> &'static Mutex<HashMap<String, CachedSearch>> {
+  static CACHE: OnceLock<Mutex<HashMap<String, CachedSearch>>> = OnceLock::new();
+  CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Everything that changes a search's results besides the corpus.
+fn reverie_result_cache_key(codex_home: &str, query: &str, opts: &ReverieSemanticSearchOptions) -> String {
+  format!(
+    "{codex_home}\u{0}{query}\u{0}{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+    opts.limit,
+    opts.max_candidates,
+    opts.project_root,
+    opts.normalize,
+    opts.reranker_model,
+    opts.reranker_max_length,
+    opts.reranker_top_k,
+    opts.diversify,
+    opts.diversity_lambda,
+    opts.language,
+    opts.max_resident_bytes,
+    opts.early_stop,
+    opts.semantic_weight,
+    opts.keyword_weight,
+    opts.recency_weight,
+    opts.importance_weight,
+    opts.recency_half_life_days,
+  )
+}
+
+fn cached_search_results(key: &str, ttl: std::time::Duration) -> Option<(Vec<ReverieSearchResult>, u32)> {
+  let mut cache = reverie_result_cache().lock().ok()?;
+  let entry = cache.get(key)?;
+  if entry.corpus_version == reverie_corpus_version() && entry.stored_at.elapsed() < ttl {
+    return Some((entry.results.clone(), entry.candidates));
+  }
+  cache.remove(key);
+  None
+}
+
+fn store_search_results(key: String, corpus_version: u64, results: &[ReverieSearchResult], candidates: u32) {
+  let Ok(mut cache) = reverie_result_cache().lock() else {
+    return;
+  };
+  let current = reverie_corpus_version();
+  cache.retain(|_, entry| entry.corpus_version == current);
+  if corpus_version != current {
+    // The corpus changed while this search ran.
+    return;
+  }
+  if cache.len() >= REVERIE_RESULT_CACHE_CAPACITY
+    && let Some(oldest) = cache
+      .iter()
+      .min_by_key(|(_, entry)| entry.stored_at)
+      .map(|(key, _)| key.clone())
+  {
+    cache.remove(&oldest);
+  }
+  cache.insert(
+    key,
+    CachedSearch {
+      corpus_version,
+      stored_at: std::time::Instant::now(),
+      results: results.to_vec(),
+      candidates,
+    },
+  );
+}
+
+#[cfg(test)]
+mod result_cache_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use pretty_assertions::assert_ne;
+
+  fn result(id: &str) -> ReverieSearchResult {
+    ReverieSearchResult {
+      conversation: ReverieConversation {
+        id: id.to_string(),
+        path: format!("/tmp/{id}.jsonl"),
+        cwd: None,
+        created_at: None,
+        updated_at: None,
+        head_records: Vec::new(),
+        tail_records: Vec::new(),
+        head_records_toon: Vec::new(),
+        tail_records_toon: Vec::new(),
+      },
+      relevance_score: 0.5,
+      matching_excerpts: Vec::new(),
+      insights: Vec::new(),
+      reranker_score: None,
+    }
+  }
+
+  #[test]
+  fn hits_until_the_corpus_changes_or_the_ttl_expires() {
+    let ttl = std::time::Duration::from_secs(60);
+    let opts = ReverieSemanticSearchOptions {
+      limit: Some(3),
+      ..Default::default()
+    };
+    let key = reverie_result_cache_key("/tmp/cache-test-home", "flaky test", &opts);
+    assert_ne!(
+      key,
+      reverie_result_cache_key("/tmp/cache-test-home", "flaky test", &ReverieSemanticSearchOptions::default())
+    );
+
+    store_search_results(key.clone(), reverie_corpus_version(), &[result("a")], 4);
+    let (results, candidates) = cached_search_results(&key, ttl).expect("cache hit");
+    assert_eq!(results.len(), 1);
+    assert_eq!(candidates, 4);
+    assert!(cached_search_results(&key, std::time::Duration::ZERO).is_none());
+
+    store_search_results(key.clone(), reverie_corpus_version(), &[result("a")], 4);
+    bump_reverie_corpus_version();
+    assert!(cached_search_results(&key, ttl).is_none());
+  }
+}
@@ -10,42 +10,17 @@ fn normalize_keyword_score(value: usize) -> f64 {
   }
 }
 
-fn blend_similarity_scores(
-  semantic_component: f64,
-  keyword_component: f64,
-  recency_component: f64,
-  importance_component: f64,
-) -> f64 {
-  (semantic_component * SEMANTIC_SCORE_WEIGHT)
-    + (keyword_component * KEYWORD_SCORE_WEIGHT)
-    + (recency_component.clamp(0.0, 1.0) * RECENCY_SCORE_WEIGHT)
-    + (importance_component.clamp(0.0, 1.0) * IMPORTANCE_SCORE_WEIGHT)
-}
-
-fn conversation_lexical_score(conversation: &ReverieConversation, keyword_text: &str) -> usize {
+fn conversation_lexical_score(conversation: &ReverieConversation, keyword_text: &str, language: KeywordLanguage) -> usize {
   conversation
     .head_records_toon
     .iter()
     .chain(conversation.tail_records_toon.iter())
     .take(20)
-    .map(|line| score_query_relevance(line, keyword_text))
+    .map(|line| score_query_relevance_in(line, keyword_text, language))
     .max()
     .unwrap_or(0)
 }
 
-fn recency_score(updated_at: &Option<String>) -> f64 {
-  if let Some(ts) = updated_at
-    && let Ok(dt) = DateTime::parse_from_rfc3339(ts)
-  {
-    let utc: DateTime<Utc> = dt.with_timezone(&Utc);
-    let age_seconds = (Utc::now() - utc).num_seconds().max(0) as f64;
-    let age_days = age_seconds / 86_400.0;
-    let lambda = 0.05_f64; // ~half-life of ~14 days
-    return (-lambda * age_days).exp().clamp(0.0, 1.0);
-  }
-  0.5
-}
-
 fn compute_conversation_importance(message_matches: &[MessageMatch], message_chunks: &[String]) -> f64 {
   if message_matches.is_empty() {
     return 0.0;
@@ -155,12 +130,16 @@ fn derive_insights_for_semantic(head_records_toon: &[String], tail_records_toon:
 fn build_compact_document(
   conversation: &ReverieConversation,
   insights: &[String],
-  query: Option<&str>,
+  query: Option<(&str, KeywordLanguage)>,
+  scan: &ReverieScan,
 ) -> Vec<String> {
   const MAX_CHARS: usize = 6000; // Increased from 4000 to preserve more technical details
   const MAX_MESSAGES: usize = 50; // Increased from 32 to sample more of conversation
 
-  let segments = load_full_conversation_json_segments(&conversation.path, 200); // Load more segments
+  // Rollups from summarizeAndStore() stand in for the turns they cover.
+  let rollups = read_thread_rollups(Path::new(&conversation.path));
+  let covered_records = rollups.last().map_or(0, |rollup| rollup.end_record as usize);
+  let segments = stream_conversation_json_segments(&conversation.path, covered_records, 200, scan); // Load more segments
 
   // Filter and score messages by relevance to query
   let mut scored_messages: Vec<(String, usize)> = segments
@@ -183,8 +162,8 @@ fn build_compact_document(
       }
 
       // Score by query relevance if query provided, otherwise by general importance
-      let score = if let Some(q) = query {
-        score_query_relevance(&text, q)
+      let score = if let Some((q, language)) = query {
+        score_query_relevance_in(&text, q, language)
       } else {
         score_message_importance(&text)
       };
@@ -203,7 +182,7 @@ fn build_compact_document(
     .collect();
 
   // Fallback: if no valid messages found, use TOON records (LLM-friendly format)
-  if message_chunks.is_empty() {
+  if message_chunks.is_empty() && rollups.is_empty() {
     message_chunks = conversation
       .head_records_toon
       .iter()
@@ -216,6 +195,7 @@ fn build_compact_document(
 
   // Add insights at the beginning (they're high-value summaries)
   let mut final_chunks = insights.to_vec();
+  final_chunks.extend(rollups.into_iter().map(|rollup| rollup.summary));
   final_chunks.extend(message_chunks);
 
   if final_chunks.is_empty() {
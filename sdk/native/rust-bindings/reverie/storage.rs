@@ -2,6 +2,7 @@ async fn load_reverie_conversations(
   codex_home: &Path,
   limit: usize,
   offset: usize,
+  cpu_threads: Option<u32>,
 ) -> std::io::Result<Vec<ReverieConversation>> {
   if limit == 0 {
     return Ok(Vec::new());
@@ -19,15 +20,80 @@ async fn load_reverie_conversations(
   )
   .await?;
 
-  let mut conversations = Vec::new();
-  for item in page.items.into_iter().skip(offset).take(limit) {
-    conversations.push(conversation_item_to_reverie(item).await);
-  }
+  // Rollouts moved by archiveRollouts() sort in with the live ones by update time.
+  let mut candidates: Vec<(Option<String>, ReverieCandidate)> = page
+    .items
+    .into_iter()
+    .map(|item| (item.updated_at.clone(), ReverieCandidate::Live(item)))
+    .chain(
+      read_rollout_archive_index(&codex_home.join(ROLLOUT_ARCHIVE_DIR))
+        .into_iter()
+        .map(|entry| (entry.updated_at.clone(), ReverieCandidate::Archived(entry))),
+    )
+    .collect();
+  candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+  let selected: Vec<ReverieCandidate> = candidates
+    .into_iter()
+    .skip(offset)
+    .take(limit)
+    .map(|(_, candidate)| candidate)
+    .collect();
+  // Summary heads are read concurrently; tails and TOON encoding run on the pool.
+  let heads = futures::future::join_all(selected.iter().map(|candidate| async move {
+    match candidate {
+      ReverieCandidate::Live(item) => codex_core::read_head_for_summary(&item.path)
+        .await
+        .unwrap_or_default(),
+      ReverieCandidate::Archived(_) => Vec::new(),
+    }
+  }))
+  .await;
+
+  run_on_reverie_pool(cpu_threads, move || {
+    use rayon::prelude::*;
+    selected
+      .into_par_iter()
+      .zip(heads)
+      .map(|(candidate, head_values)| match candidate {
+        ReverieCandidate::Live(item) => conversation_item_to_reverie(item, head_values),
+        ReverieCandidate::Archived(entry) => archived_rollout_to_reverie(entry),
+      })
+      .collect()
+  })
+  .await
+}
+
+enum ReverieCandidate {
+  Live(codex_core::ThreadItem),
+  Archived(ArchivedRollout),
+}
 
-  Ok(conversations)
+fn archived_rollout_to_reverie(entry: ArchivedRollout) -> ReverieConversation {
+  const HEAD_RECORD_LIMIT: usize = 10;
+  const TAIL_RECORD_LIMIT: usize = 10;
+  let path = PathBuf::from(&entry.archive_path);
+  let (head_records, head_records_toon) =
+    serialize_records(&read_head_records_fallback(&path, HEAD_RECORD_LIMIT));
+  let (tail_records, tail_records_toon) =
+    serialize_records(&read_tail_records(&path, TAIL_RECORD_LIMIT));
+  ReverieConversation {
+    id: entry.id,
+    path: entry.archive_path,
+    cwd: entry.cwd,
+    created_at: entry.created_at,
+    updated_at: entry.updated_at,
+    head_records,
+    tail_records,
+    head_records_toon,
+    tail_records_toon,
+  }
 }
 
-async fn conversation_item_to_reverie(item: codex_core::ThreadItem) -> ReverieConversation {
+fn conversation_item_to_reverie(
+  item: codex_core::ThreadItem,
+  mut head_values: Vec<serde_json::Value>,
+) -> ReverieConversation {
   let id = item
     .path
     .file_stem()
@@ -37,9 +103,6 @@ async fn conversation_item_to_reverie(item: codex_core::ThreadItem) -> ReverieCo
 
   const HEAD_RECORD_LIMIT: usize = 10;
   const TAIL_RECORD_LIMIT: usize = 10;
-  let mut head_values = codex_core::read_head_for_summary(&item.path)
-    .await
-    .unwrap_or_default();
   if head_values.is_empty() || !head_values.iter().any(record_has_cwd) {
     head_values = read_head_records_fallback(&item.path, HEAD_RECORD_LIMIT);
   }
@@ -73,62 +136,28 @@ fn read_head_records_fallback(path: &Path, limit: usize) -> Vec<serde_json::Valu
   if limit == 0 {
     return Vec::new();
   }
-
-  let file = match File::open(path) {
-    Ok(file) => file,
-    Err(_) => return Vec::new(),
+  let Ok(rollout) = load_cached_rollout(path) else {
+    return Vec::new();
   };
-
-  let reader = BufReader::new(file);
-  let mut values = Vec::with_capacity(limit);
-
-  for line in reader.lines().map_while(Result::ok) {
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-      continue;
-    }
-
-    let Ok(val) = serde_json::from_str::<serde_json::Value>(trimmed) else {
-      continue;
-    };
-
-    let record_val = val.get("item").cloned().unwrap_or(val);
-    values.push(record_val);
-    if values.len() >= limit {
-      break;
-    }
-  }
-
-  values
+  rollout
+    .records
+    .iter()
+    .take(limit)
+    .map(|val| val.get("item").cloned().unwrap_or_else(|| val.clone()))
+    .collect()
 }
 
 fn read_tail_records(path: &Path, limit: usize) -> Vec<serde_json::Value> {
-  let file = match File::open(path) {
-    Ok(file) => file,
-    Err(_) => return Vec::new(),
+  let Ok(rollout) = load_cached_rollout(path) else {
+    return Vec::new();
   };
-
-  let reader = BufReader::new(file);
-  let mut deque: VecDeque<serde_json::Value> = VecDeque::with_capacity(limit);
-
-  for line in reader.lines().map_while(Result::ok) {
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-      continue;
-    }
-
-    let Ok(val) = serde_json::from_str::<serde_json::Value>(trimmed) else {
-      continue;
-    };
-
-    let record_val = val.get("item").cloned().unwrap_or(val);
-    deque.push_back(record_val);
-    if deque.len() > limit {
-      deque.pop_front();
-    }
-  }
-
-  deque.into_iter().collect()
+  let skip = rollout.records.len().saturating_sub(limit);
+  rollout
+    .records
+    .iter()
+    .skip(skip)
+    .map(|val| val.get("item").cloned().unwrap_or_else(|| val.clone()))
+    .collect()
 }
 
 fn serialize_records(values: &[serde_json::Value]) -> (Vec<String>, Vec<String>) {
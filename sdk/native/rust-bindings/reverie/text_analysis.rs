@@ -19,15 +19,281 @@ fn is_technical_term(term: &str) -> bool {
 fn extract_technical_terms(query: &str) -> Vec<String> {
   query
     .split_whitespace()
-    .filter(|term| is_technical_term(term))
+    .map(trim_code_token)
+    .filter(|term| !term.is_empty() && is_technical_term(term))
     .map(|s| s.to_string())
     .collect()
 }
 
+/// Strip prose punctuation and quoting around a code token (`` `Foo::bar`, `` -> `Foo::bar`).
+fn trim_code_token(token: &str) -> &str {
+  token
+    .trim_matches(|c: char| matches!(c, '`' | '"' | '\'' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | ',' | ';' | '!' | '?'))
+    .trim_end_matches(['.', ':'])
+}
+
+/// Lowercased words of an identifier or path, split at separators and case changes, so
+/// `ToolRegistryBuilder`, `tool_registry_builder` and `tool-registry-builder` all give
+/// `[tool, registry, builder]`, `HTTPServer` gives `[http, server]` and `src/registry.rs` gives
+/// `[src, registry, rs]`.
+fn split_code_identifier(token: &str) -> Vec<String> {
+  let chars: Vec<char> = token.chars().collect();
+  let mut words = Vec::new();
+  let mut current = String::new();
+  for (i, &c) in chars.iter().enumerate() {
+    if !c.is_alphanumeric() {
+      if !current.is_empty() {
+        words.push(std::mem::take(&mut current));
+      }
+      continue;
+    }
+    if c.is_uppercase() && !current.is_empty() {
+      let prev = chars[i - 1];
+      let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+      // camelCase boundary, or the last capital of an acronym followed by a word (HTTPServer).
+      if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+        words.push(std::mem::take(&mut current));
+      }
+    }
+    current.extend(c.to_lowercase());
+  }
+  if !current.is_empty() {
+    words.push(current);
+  }
+  words
+}
+
+/// The code-like tokens of a text, normalized across naming conventions.
+struct CodeTokens {
+  /// Multi-word identifiers as their words joined by `_`.
+  identifiers: HashSet<String>,
+  /// Every word of every identifier and path.
+  words: HashSet<String>,
+}
+
+impl CodeTokens {
+  fn new(text: &str) -> Self {
+    let mut identifiers = HashSet::new();
+    let mut words = HashSet::new();
+    for token in text.split_whitespace().map(trim_code_token) {
+      if !is_technical_term(token) {
+        continue;
+      }
+      // Paths and qualified names contribute their segments as identifiers too.
+      for segment in token.split(['/', '\\', ':', '.']) {
+        let parts = split_code_identifier(segment);
+        if parts.len() > 1 {
+          identifiers.insert(parts.join("_"));
+        }
+      }
+      words.extend(split_code_identifier(token));
+    }
+    Self { identifiers, words }
+  }
+}
+
+/// Score for technical query terms the text spells differently (`tool_registry_builder` for
+/// `ToolRegistryBuilder`) or only names in part (`registry.rs`).
+fn score_code_term_matches(text: &str, text_lower: &str, technical_terms: &[String]) -> usize {
+  let code_tokens = CodeTokens::new(text);
+  let mut score = 0;
+  for term in technical_terms {
+    if text_lower.contains(&term.to_lowercase()) {
+      // Already scored as an exact match.
+      continue;
+    }
+    let words = split_code_identifier(term);
+    if words.len() > 1 && code_tokens.identifiers.contains(&words.join("_")) {
+      score += 80;
+      continue;
+    }
+    let matched = words
+      .iter()
+      .filter(|word| word.chars().count() > 2 && code_tokens.words.contains(*word))
+      .count();
+    score += matched.min(3) * 10;
+  }
+  score
+}
+
+/// The words of the technical terms in `query`, for matching prose that describes them.
+fn code_query_words(query: &str) -> Vec<String> {
+  let mut seen = HashSet::new();
+  extract_technical_terms(query)
+    .iter()
+    .flat_map(|term| split_code_identifier(term))
+    .filter(|word| word.chars().count() > 2 && seen.insert(word.clone()))
+    .collect()
+}
+
+/// Languages with both a Snowball stemmer and a stop-word list, for keyword scoring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum KeywordLanguage {
+  English,
+  French,
+  German,
+  Spanish,
+  Italian,
+  Portuguese,
+  Dutch,
+  Swedish,
+  Norwegian,
+  Danish,
+  Finnish,
+  Hungarian,
+  Romanian,
+  Turkish,
+  Russian,
+  Greek,
+  Arabic,
+}
+
+/// Candidates for detection among Latin-script queries, English first so it wins ties.
+const LATIN_KEYWORD_LANGUAGES: [KeywordLanguage; 14] = [
+  KeywordLanguage::English,
+  KeywordLanguage::French,
+  KeywordLanguage::German,
+  KeywordLanguage::Spanish,
+  KeywordLanguage::Italian,
+  KeywordLanguage::Portuguese,
+  KeywordLanguage::Dutch,
+  KeywordLanguage::Swedish,
+  KeywordLanguage::Norwegian,
+  KeywordLanguage::Danish,
+  KeywordLanguage::Finnish,
+  KeywordLanguage::Hungarian,
+  KeywordLanguage::Romanian,
+  KeywordLanguage::Turkish,
+];
+
+impl KeywordLanguage {
+  /// Accepts ISO 639-1 codes (`de`) and English names (`german`), case-insensitively.
+  fn parse(value: &str) -> napi::Result<Self> {
+    let language = match value.trim().to_lowercase().as_str() {
+      "en" | "english" => Self::English,
+      "fr" | "french" => Self::French,
+      "de" | "german" => Self::German,
+      "es" | "spanish" => Self::Spanish,
+      "it" | "italian" => Self::Italian,
+      "pt" | "portuguese" => Self::Portuguese,
+      "nl" | "dutch" => Self::Dutch,
+      "sv" | "swedish" => Self::Swedish,
+      "no" | "nb" | "norwegian" => Self::Norwegian,
+      "da" | "danish" => Self::Danish,
+      "fi" | "finnish" => Self::Finnish,
+      "hu" | "hungarian" => Self::Hungarian,
+      "ro" | "romanian" => Self::Romanian,
+      "tr" | "turkish" => Self::Turkish,
+      "ru" | "russian" => Self::Russian,
+      "el" | "greek" => Self::Greek,
+      "ar" | "arabic" => Self::Arabic,
+      other => {
+        return Err(napi::Error::from_reason(format!(
+          "Unsupported reverie language: {other}"
+        )));
+      }
+    };
+    Ok(language)
+  }
+
+  fn stemmer_algorithm(self) -> rust_stemmers::Algorithm {
+    use rust_stemmers::Algorithm;
+    match self {
+      Self::English => Algorithm::English,
+      Self::French => Algorithm::French,
+      Self::German => Algorithm::German,
+      Self::Spanish => Algorithm::Spanish,
+      Self::Italian => Algorithm::Italian,
+      Self::Portuguese => Algorithm::Portuguese,
+      Self::Dutch => Algorithm::Dutch,
+      Self::Swedish => Algorithm::Swedish,
+      Self::Norwegian => Algorithm::Norwegian,
+      Self::Danish => Algorithm::Danish,
+      Self::Finnish => Algorithm::Finnish,
+      Self::Hungarian => Algorithm::Hungarian,
+      Self::Romanian => Algorithm::Romanian,
+      Self::Turkish => Algorithm::Turkish,
+      Self::Russian => Algorithm::Russian,
+      Self::Greek => Algorithm::Greek,
+      Self::Arabic => Algorithm::Arabic,
+    }
+  }
+
+  fn stop_word_list(self) -> stop_words::LANGUAGE {
+    use stop_words::LANGUAGE;
+    match self {
+      Self::English => LANGUAGE::English,
+      Self::French => LANGUAGE::French,
+      Self::German => LANGUAGE::German,
+      Self::Spanish => LANGUAGE::Spanish,
+      Self::Italian => LANGUAGE::Italian,
+      Self::Portuguese => LANGUAGE::Portuguese,
+      Self::Dutch => LANGUAGE::Dutch,
+      Self::Swedish => LANGUAGE::Swedish,
+      Self::Norwegian => LANGUAGE::Norwegian,
+      Self::Danish => LANGUAGE::Danish,
+      Self::Finnish => LANGUAGE::Finnish,
+      Self::Hungarian => LANGUAGE::Hungarian,
+      Self::Romanian => LANGUAGE::Romanian,
+      Self::Turkish => LANGUAGE::Turkish,
+      Self::Russian => LANGUAGE::Russian,
+      Self::Greek => LANGUAGE::Greek,
+      Self::Arabic => LANGUAGE::Arabic,
+    }
+  }
+
+  /// The language's stop words. Every list is loaded on first use.
+  fn stop_words(self) -> &'static HashSet<String> {
+    static STOP_WORDS: OnceLock<HashMap<KeywordLanguage, HashSet<String>>> = OnceLock::new();
+    let lists = STOP_WORDS.get_or_init(|| {
+      LATIN_KEYWORD_LANGUAGES
+        .into_iter()
+        .chain([KeywordLanguage::Russian, KeywordLanguage::Greek, KeywordLanguage::Arabic])
+        .map(|language| {
+          let words = stop_words::get(language.stop_word_list()).into_iter().collect();
+          (language, words)
+        })
+        .collect()
+    });
+    &lists[&self]
+  }
+}
+
+/// Guess the query's language: by script for non-Latin alphabets, otherwise the language whose
+/// stop words the query uses most. Falls back to English.
+fn detect_query_language(query: &str) -> KeywordLanguage {
+  for c in query.chars() {
+    match c {
+      '\u{0400}'..='\u{04FF}' => return KeywordLanguage::Russian,
+      '\u{0370}'..='\u{03FF}' => return KeywordLanguage::Greek,
+      '\u{0600}'..='\u{06FF}' => return KeywordLanguage::Arabic,
+      _ => {}
+    }
+  }
+  let words: Vec<String> = query
+    .split(|c: char| !c.is_alphabetic())
+    .filter(|word| !word.is_empty())
+    .map(str::to_lowercase)
+    .collect();
+  let mut best = (KeywordLanguage::English, 0);
+  for language in LATIN_KEYWORD_LANGUAGES {
+    let stop_words = language.stop_words();
+    let hits = words.iter().filter(|word| stop_words.contains(*word)).count();
+    if hits > best.1 {
+      best = (language, hits);
+    }
+  }
+  best.0
+}
+
 /// Score message relevance to search query (enhanced RAG with stemming and n-grams)
 fn score_query_relevance(text: &str, query: &str) -> usize {
-  use stop_words::{get, LANGUAGE};
-  use rust_stemmers::{Algorithm, Stemmer};
+  score_query_relevance_in(text, query, detect_query_language(query))
+}
+
+/// `score_query_relevance` with the stemmer and stop words of `language`.
+fn score_query_relevance_in(text: &str, query: &str, language: KeywordLanguage) -> usize {
+  use rust_stemmers::Stemmer;
 
   let text_lower = text.to_lowercase();
   let query_lower = query.to_lowercase();
@@ -36,12 +302,12 @@ fn score_query_relevance(text: &str, query: &str) -> usize {
   let technical_terms = extract_technical_terms(query);
 
   // Extract meaningful query terms (filter out common words)
-  let stop_words_set = get(LANGUAGE::English);
+  let stop_words_set = language.stop_words();
   let query_terms: Vec<&str> = query_lower
     .split_whitespace()
     .filter(|term| {
       // Keep if: technical term, longer than 2 chars and not a stop word
-      is_technical_term(term) || (term.len() > 2 && !stop_words_set.contains(&term.to_string()))
+      is_technical_term(term) || (term.chars().count() > 2 && !stop_words_set.contains(*term))
     })
     .collect();
 
@@ -50,7 +316,7 @@ fn score_query_relevance(text: &str, query: &str) -> usize {
   }
 
   let mut score = 0;
-  let stemmer = Stemmer::create(Algorithm::English);
+  let stemmer = Stemmer::create(language.stemmer_algorithm());
 
   // CRITICAL: Exact technical term matching (structural detection, not content assumptions)
   // Technical terms are identified by structure (CamelCase, kebab-case, etc.), not by domain knowledge
@@ -67,6 +333,9 @@ fn score_query_relevance(text: &str, query: &str) -> usize {
     }
   }
 
+  // The same identifiers under other naming conventions, and files named after their parts
+  score += score_code_term_matches(text, &text_lower, &technical_terms);
+
   // Exact multi-word phrase match (query appears verbatim in text)
   if text_lower.contains(&query_lower) {
     score += 150;
@@ -307,3 +576,50 @@ fn lookup_query_synonyms(term: &str) -> &'static [&'static str] {
     _ => &[],
   }
 }
+
+#[cfg(test)]
+mod text_analysis_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn detects_query_language_by_script_and_stop_words() {
+    assert_eq!(detect_query_language("why does the build fail on CI"), KeywordLanguage::English);
+    assert_eq!(detect_query_language("wie kann ich die Datenbank migrieren"), KeywordLanguage::German);
+    assert_eq!(detect_query_language("pourquoi le test échoue avec une erreur"), KeywordLanguage::French);
+    assert_eq!(detect_query_language("почему падает тест"), KeywordLanguage::Russian);
+    assert_eq!(detect_query_language("FastEmbed"), KeywordLanguage::English);
+    assert_eq!(KeywordLanguage::parse("DE").unwrap(), KeywordLanguage::German);
+    assert_eq!(KeywordLanguage::parse("spanish").unwrap(), KeywordLanguage::Spanish);
+    assert!(KeywordLanguage::parse("klingon").is_err());
+  }
+
+  #[test]
+  fn stems_with_the_query_language() {
+    // German stemming maps the plural "Tabellen" to "Tabelle"; English stemming doesn't.
+    let text = "Die Migration der Tabelle ist fertig";
+    let german = score_query_relevance_in(text, "Tabellen", KeywordLanguage::German);
+    let english = score_query_relevance_in(text, "Tabellen", KeywordLanguage::English);
+    assert!(german > english);
+  }
+
+  #[test]
+  fn splits_identifiers_across_naming_conventions() {
+    let words = |token: &str| split_code_identifier(token);
+    assert_eq!(words("ToolRegistryBuilder"), vec!["tool", "registry", "builder"]);
+    assert_eq!(words("tool_registry_builder"), vec!["tool", "registry", "builder"]);
+    assert_eq!(words("HTTPServer"), vec!["http", "server"]);
+    assert_eq!(words("core/src/tools/registry.rs"), vec!["core", "src", "tools", "registry", "rs"]);
+    assert_eq!(trim_code_token("`ToolRegistryBuilder::new()`,"), "ToolRegistryBuilder::new");
+  }
+
+  #[test]
+  fn matches_identifiers_spelled_differently() {
+    let query = "where is ToolRegistryBuilder defined";
+    let snake = score_query_relevance_in("added tool_registry_builder to the spec", query, KeywordLanguage::English);
+    let path = score_query_relevance_in("see core/src/tools/registry.rs for details", query, KeywordLanguage::English);
+    let unrelated = score_query_relevance_in("updated the changelog for details", query, KeywordLanguage::English);
+    assert!(snake > path);
+    assert!(path > unrelated);
+  }
+}
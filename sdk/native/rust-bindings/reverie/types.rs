@@ -1,13 +1,12 @@
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use chrono::{DateTime, Utc};
 use codex_core::OLLAMA_OSS_PROVIDER_ID;
 
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
 pub struct ReverieConversation {
   pub id: String,
   pub path: String,
@@ -26,8 +25,9 @@ pub struct ReverieConversation {
   pub tail_records_toon: Vec<String>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
 pub struct ReverieSearchResult {
   pub conversation: ReverieConversation,
   #[napi(js_name = "relevanceScore")]
@@ -40,10 +40,6 @@ pub struct ReverieSearchResult {
 }
 
 const MAX_INSIGHTS_PER_CONVERSATION: usize = 4;
-const SEMANTIC_SCORE_WEIGHT: f64 = 0.55;
-const KEYWORD_SCORE_WEIGHT: f64 = 0.15;
-const RECENCY_SCORE_WEIGHT: f64 = 0.15;
-const IMPORTANCE_SCORE_WEIGHT: f64 = 0.15;
 const KEYWORD_SCORE_SMOOTHING: f64 = 100.0;
 
 #[derive(Default)]
@@ -70,6 +66,41 @@ pub struct ReverieSemanticSearchOptions {
   pub reranker_batch_size: Option<u32>,
   #[napi(js_name = "rerankerTopK")]
   pub reranker_top_k: Option<u32>,
+  /// Drop near-duplicate conversations and order the rest by maximal marginal relevance.
+  pub diversify: Option<bool>,
+  /// Language for keyword stemming and stop words, as an ISO 639-1 code or English name.
+  /// Detected from the query when unset.
+  pub language: Option<String>,
+  /// Threads preparing candidates in parallel. Defaults to one per CPU.
+  #[napi(js_name = "cpuThreads")]
+  pub cpu_threads: Option<u32>,
+  /// Bytes of rollout text one search may read while preparing candidates. Defaults to 64 MiB.
+  #[napi(js_name = "maxResidentBytes")]
+  pub max_resident_bytes: Option<u32>,
+  /// Stop preparing candidates once twice `limit` strong lexical matches are ready (default true).
+  #[napi(js_name = "earlyStop")]
+  pub early_stop: Option<bool>,
+  /// How long identical searches reuse results, in milliseconds. Defaults to 30000; 0 disables.
+  #[napi(js_name = "resultCacheTtlMs")]
+  pub result_cache_ttl_ms: Option<u32>,
+  /// MMR trade-off between relevance (1.0) and novelty (0.0). Defaults to 0.7.
+  #[napi(js_name = "diversityLambda")]
+  pub diversity_lambda: Option<f64>,
+  /// Share of the blended score from embedding similarity. Defaults to 0.55.
+  #[napi(js_name = "semanticWeight")]
+  pub semantic_weight: Option<f64>,
+  /// Share from keyword matches. Defaults to 0.15.
+  #[napi(js_name = "keywordWeight")]
+  pub keyword_weight: Option<f64>,
+  /// Share from how recently the conversation was updated. Defaults to 0.15.
+  #[napi(js_name = "recencyWeight")]
+  pub recency_weight: Option<f64>,
+  /// Share from how substantial the matched messages are. Defaults to 0.15.
+  #[napi(js_name = "importanceWeight")]
+  pub importance_weight: Option<f64>,
+  /// Age in days at which the recency component halves. Defaults to 14.
+  #[napi(js_name = "recencyHalfLifeDays")]
+  pub recency_half_life_days: Option<f64>,
 }
 
 #[napi(object)]
@@ -84,12 +115,20 @@ pub struct ReverieSemanticIndexStats {
 struct SearchQueryContext {
   original: String,
   expanded: String,
+  language: KeywordLanguage,
 }
 
 impl SearchQueryContext {
-  fn new(input: &str) -> Self {
+  fn new(input: &str, language: Option<KeywordLanguage>) -> Self {
     let original = input.trim().to_string();
-    let mut extra_terms = expand_query_terms(&original);
+    let language = language.unwrap_or_else(|| detect_query_language(&original));
+    // The synonym table is English.
+    let mut extra_terms = if language == KeywordLanguage::English {
+      expand_query_terms(&original)
+    } else {
+      Vec::new()
+    };
+    extra_terms.extend(code_query_words(&original));
     extra_terms.retain(|term| !term.is_empty());
 
     let expanded = if extra_terms.is_empty() {
@@ -98,7 +137,11 @@ impl SearchQueryContext {
       format!("{}\n\n{}", original, extra_terms.join(" "))
     };
 
-    Self { original, expanded }
+    Self {
+      original,
+      expanded,
+      language,
+    }
   }
 
   fn original(&self) -> &str {
@@ -108,4 +151,8 @@ impl SearchQueryContext {
   fn keyword_text(&self) -> &str {
     &self.expanded
   }
+
+  fn language(&self) -> KeywordLanguage {
+    self.language
+  }
 }
@@ -0,0 +1,136 @@
+// Score blend weights.
+//
+// A match's relevance blends embedding similarity, keyword matches, recency
+// and importance. The shares default to 0.55 / 0.15 / 0.15 / 0.15 with a
+// 14-day recency half-life. Archival research can lower `recencyWeight`;
+// recent-work recall can raise it or shorten `recencyHalfLifeDays`. The
+// weights must be non-negative and sum to 1 within WEIGHT_SUM_TOLERANCE, so
+// relevance scores stay in 0..=1.
+
+const DEFAULT_SEMANTIC_SCORE_WEIGHT: f64 = 0.55;
+const DEFAULT_KEYWORD_SCORE_WEIGHT: f64 = 0.15;
+const DEFAULT_RECENCY_SCORE_WEIGHT: f64 = 0.15;
+const DEFAULT_IMPORTANCE_SCORE_WEIGHT: f64 = 0.15;
+const DEFAULT_RECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+const WEIGHT_SUM_TOLERANCE: f64 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ScoreWeights {
+  semantic: f64,
+  keyword: f64,
+  recency: f64,
+  importance: f64,
+  recency_half_life_days: f64,
+}
+
+impl Default for ScoreWeights {
+  fn default() -> Self {
+    Self {
+      semantic: DEFAULT_SEMANTIC_SCORE_WEIGHT,
+      keyword: DEFAULT_KEYWORD_SCORE_WEIGHT,
+      recency: DEFAULT_RECENCY_SCORE_WEIGHT,
+      importance: DEFAULT_IMPORTANCE_SCORE_WEIGHT,
+      recency_half_life_days: DEFAULT_RECENCY_HALF_LIFE_DAYS,
+    }
+  }
+}
+
+impl ScoreWeights {
+  /// The weights `opts` asks for, unset ones at their defaults.
+  fn from_options(opts: &ReverieSemanticSearchOptions) -> napi::Result<Self> {
+    let defaults = Self::default();
+    let weights = Self {
+      semantic: opts.semantic_weight.unwrap_or(defaults.semantic),
+      keyword: opts.keyword_weight.unwrap_or(defaults.keyword),
+      recency: opts.recency_weight.unwrap_or(defaults.recency),
+      importance: opts.importance_weight.unwrap_or(defaults.importance),
+      recency_half_life_days: opts.recency_half_life_days.unwrap_or(defaults.recency_half_life_days),
+    };
+    let shares = [
+      ("semanticWeight", weights.semantic),
+      ("keywordWeight", weights.keyword),
+      ("recencyWeight", weights.recency),
+      ("importanceWeight", weights.importance),
+    ];
+    if let Some((name, value)) = shares.iter().find(|(_, value)| !value.is_finite() || *value < 0.0) {
+      return Err(napi::Error::from_reason(format!("{name} must be a non-negative number, got {value}")));
+    }
+    let sum: f64 = shares.iter().map(|(_, value)| value).sum();
+    if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+      return Err(napi::Error::from_reason(format!(
+        "semanticWeight, keywordWeight, recencyWeight and importanceWeight must sum to 1.0, got {sum}"
+      )));
+    }
+    if !weights.recency_half_life_days.is_finite() || weights.recency_half_life_days <= 0.0 {
+      return Err(napi::Error::from_reason(format!(
+        "recencyHalfLifeDays must be positive, got {}",
+        weights.recency_half_life_days
+      )));
+    }
+    Ok(weights)
+  }
+
+  fn blend(&self, semantic_component: f64, keyword_component: f64, recency_component: f64, importance_component: f64) -> f64 {
+    (semantic_component * self.semantic)
+      + (keyword_component * self.keyword)
+      + (recency_component.clamp(0.0, 1.0) * self.recency)
+      + (importance_component.clamp(0.0, 1.0) * self.importance)
+  }
+
+  /// 1.0 for a conversation updated now, halving every `recency_half_life_days`; 0.5 when undated.
+  fn recency_score(&self, updated_at: &Option<String>) -> f64 {
+    if let Some(ts) = updated_at
+      && let Ok(dt) = DateTime::parse_from_rfc3339(ts)
+    {
+      let utc: DateTime<Utc> = dt.with_timezone(&Utc);
+      let age_days = (Utc::now() - utc).num_seconds().max(0) as f64 / 86_400.0;
+      return 0.5_f64.powf(age_days / self.recency_half_life_days).clamp(0.0, 1.0);
+    }
+    0.5
+  }
+}
+
+#[cfg(test)]
+mod weights_tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn validates_weights_and_applies_the_half_life() {
+    assert_eq!(
+      ScoreWeights::from_options(&ReverieSemanticSearchOptions::default()).unwrap(),
+      ScoreWeights::default()
+    );
+
+    let archival = ReverieSemanticSearchOptions {
+      semantic_weight: Some(0.7),
+      recency_weight: Some(0.0),
+      recency_half_life_days: Some(1.0),
+      ..Default::default()
+    };
+    let weights = ScoreWeights::from_options(&archival).unwrap();
+    assert_eq!(weights.blend(1.0, 1.0, 1.0, 1.0), 1.0);
+    let day_old = Some((Utc::now() - chrono::Duration::days(1)).to_rfc3339());
+    assert!((weights.recency_score(&day_old) - 0.5).abs() < 0.01);
+    assert_eq!(weights.recency_score(&None), 0.5);
+
+    let unbalanced = ReverieSemanticSearchOptions {
+      semantic_weight: Some(0.9),
+      ..Default::default()
+    };
+    assert!(ScoreWeights::from_options(&unbalanced).is_err());
+    let negative = ReverieSemanticSearchOptions {
+      semantic_weight: Some(0.85),
+      keyword_weight: Some(-0.15),
+      recency_weight: Some(0.15),
+      importance_weight: Some(0.15),
+      ..Default::default()
+    };
+    assert!(ScoreWeights::from_options(&negative).is_err());
+    let no_half_life = ReverieSemanticSearchOptions {
+      recency_half_life_days: Some(0.0),
+      ..Default::default()
+    };
+    assert!(ScoreWeights::from_options(&no_half_life).is_err());
+  }
+}
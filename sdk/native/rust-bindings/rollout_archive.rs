@@ -0,0 +1,359 @@
+// ============================================================================
+// Rollout archival (archiveRollouts)
+// ============================================================================
+//
+// Long-lived installs accumulate gigabytes under `$CODEX_HOME/sessions`.
+// archiveRollouts() moves rollouts that haven't been written for a while into
+// an archive directory, zstd-compressed by default and optionally encrypted,
+// and records each one in the archive's `index.jsonl`. Archived rollouts stay
+// readable: the rollout cache and openRolloutReader() decrypt `.age` and
+// decompress `.zst` files transparently, and
+// reverie listing and search merge the default archive's index into their
+// results.
+
+const ROLLOUT_ARCHIVE_DIR: &str = "rollout-archives";
+const ROLLOUT_ARCHIVE_INDEX: &str = "index.jsonl";
+const ROLLOUT_ARCHIVE_ZSTD_LEVEL: i32 = 9;
+
+#[napi(object)]
+pub struct ArchiveRolloutsOptions {
+  /// Rollouts last written at least this many days ago are archived.
+  #[napi(js_name = "olderThanDays")]
+  pub older_than_days: u32,
+  /// `"zstd"` (default) or `"none"` to move files uncompressed.
+  pub compress: Option<String>,
+  /// Defaults to `$CODEX_HOME/rollout-archives`, the only archive reverie reads.
+  pub destination: Option<String>,
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  /// Report what would be archived without moving anything.
+  #[napi(js_name = "dryRun")]
+  pub dry_run: Option<bool>,
+  /// Encrypt with the key set by configureArchiveEncryption(), adding an `.age` extension.
+  pub encrypt: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+pub struct ArchivedRollout {
+  /// Rollout file name without extensions, as reverie reports it.
+  pub id: String,
+  #[napi(js_name = "originalPath")]
+  pub original_path: String,
+  #[napi(js_name = "archivePath")]
+  pub archive_path: String,
+  pub cwd: Option<String>,
+  #[napi(js_name = "createdAt")]
+  pub created_at: Option<String>,
+  #[napi(js_name = "updatedAt")]
+  pub updated_at: Option<String>,
+  #[napi(js_name = "originalBytes")]
+  pub original_bytes: i64,
+  #[napi(js_name = "archivedBytes")]
+  pub archived_bytes: i64,
+}
+
+#[napi(object)]
+pub struct ArchiveRolloutsResult {
+  pub archived: Vec<ArchivedRollout>,
+  #[napi(js_name = "indexPath")]
+  pub index_path: String,
+  #[napi(js_name = "bytesBefore")]
+  pub bytes_before: i64,
+  #[napi(js_name = "bytesAfter")]
+  pub bytes_after: i64,
+}
+
+/// Byte stream over a rollout, undoing the `.age` encryption and `.zst` compression archives add.
+fn open_rollout_stream(path: &Path) -> io::Result<Box<dyn io::Read + Send>> {
+  let mut reader: Box<dyn io::Read + Send> =
+    Box::new(io::BufReader::new(std::fs::File::open(path)?));
+  let mut name = path.to_path_buf();
+  if name
+    .extension()
+    .is_some_and(|ext| ext == ROLLOUT_ENCRYPTED_EXTENSION)
+  {
+    reader = Box::new(rollout_encryption_key()?.decrypt_from(reader)?);
+    name.set_extension("");
+  }
+  if name.extension().is_some_and(|ext| ext == "zst") {
+    reader = Box::new(zstd::Decoder::new(reader)?);
+  }
+  Ok(reader)
+}
+
+/// Read a rollout, decrypting and decompressing archived files.
+fn read_rollout_text(path: &Path) -> io::Result<String> {
+  use std::io::Read;
+
+  let mut text = String::new();
+  open_rollout_stream(path)?.read_to_string(&mut text)?;
+  Ok(text)
+}
+
+/// Line reader over a rollout, decrypting and decompressing archived files.
+fn open_rollout_lines(path: &Path) -> io::Result<Box<dyn io::BufRead + Send>> {
+  Ok(Box::new(io::BufReader::new(open_rollout_stream(path)?)))
+}
+
+fn format_rollout_timestamp(time: SystemTime) -> String {
+  DateTime::<Utc>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// The cwd and start time from a rollout's `session_meta` record.
+fn rollout_session_meta(path: &Path) -> (Option<String>, Option<String>) {
+  use std::io::BufRead;
+
+  let Ok(lines) = open_rollout_lines(path) else {
+    return (None, None);
+  };
+  for line in lines.lines().map_while(Result::ok).take(5) {
+    let Ok(value) = serde_json::from_str::<JsonValue>(&line) else {
+      continue;
+    };
+    if value.get("type").and_then(JsonValue::as_str) == Some("session_meta") {
+      let payload = value.get("payload").unwrap_or(&value);
+      let field = |name: &str| {
+        payload
+          .get(name)
+          .and_then(JsonValue::as_str)
+          .map(str::to_string)
+      };
+      return (field("cwd"), field("timestamp"));
+    }
+  }
+  (None, None)
+}
+
+/// Entries recorded in an archive's index. A missing index means an empty archive.
+fn read_rollout_archive_index(archive_dir: &Path) -> Vec<ArchivedRollout> {
+  let Ok(contents) = std::fs::read_to_string(archive_dir.join(ROLLOUT_ARCHIVE_INDEX)) else {
+    return Vec::new();
+  };
+  contents
+    .lines()
+    .filter_map(|line| serde_json::from_str(line).ok())
+    .collect()
+}
+
+fn archive_rollout_file(
+  source: &Path,
+  target: &Path,
+  compress: bool,
+  key: Option<&RolloutEncryptionKey>,
+) -> io::Result<u64> {
+  if let Some(parent) = target.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  if !compress && key.is_none() {
+    if std::fs::rename(source, target).is_err() {
+      // Different filesystems: copy, then drop the original.
+      std::fs::copy(source, target)?;
+      std::fs::remove_file(source)?;
+    }
+    return Ok(std::fs::metadata(target)?.len());
+  }
+  let mut temp = target.as_os_str().to_owned();
+  temp.push(format!(".tmp-{}", Uuid::new_v4()));
+  let temp = PathBuf::from(temp);
+  let result = (|| {
+    let mut input = std::fs::File::open(source)?;
+    write_rollout_layers(&mut input, std::fs::File::create(&temp)?, compress, key)?;
+    std::fs::rename(&temp, target)
+  })();
+  if let Err(err) = result {
+    let _ = std::fs::remove_file(&temp);
+    return Err(err);
+  }
+  std::fs::remove_file(source)?;
+  Ok(std::fs::metadata(target)?.len())
+}
+
+fn archive_rollouts_blocking(
+  options: ArchiveRolloutsOptions,
+) -> napi::Result<ArchiveRolloutsResult> {
+  let compress = match options.compress.as_deref() {
+    None | Some("zstd") => true,
+    Some("none") => false,
+    Some(other) => {
+      return Err(napi::Error::from_reason(format!(
+        "Unsupported compress value {other:?}; expected \"zstd\" or \"none\""
+      )));
+    }
+  };
+  let codex_home = match options.codex_home.as_deref() {
+    Some(path) => PathBuf::from(path),
+    None => find_codex_home()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?,
+  };
+  let key = if options.encrypt.unwrap_or(false) {
+    Some(rollout_encryption_key().map_err(|err| napi::Error::from_reason(err.to_string()))?)
+  } else {
+    None
+  };
+  let destination = options
+    .destination
+    .map_or_else(|| codex_home.join(ROLLOUT_ARCHIVE_DIR), PathBuf::from);
+  let cutoff = SystemTime::now()
+    - std::time::Duration::from_secs(u64::from(options.older_than_days) * 24 * 60 * 60);
+  let sessions_dir = codex_home.join(codex_core::SESSIONS_SUBDIR);
+  let mut rollouts = Vec::new();
+  collect_usage_rollouts(&sessions_dir, &mut rollouts);
+  rollouts.sort();
+
+  let mut archived = Vec::new();
+  let (mut bytes_before, mut bytes_after) = (0i64, 0i64);
+  for source in rollouts {
+    let Ok(metadata) = std::fs::metadata(&source) else {
+      continue;
+    };
+    let Some(modified) = metadata
+      .modified()
+      .ok()
+      .filter(|modified| *modified <= cutoff)
+    else {
+      continue;
+    };
+    let relative = source.strip_prefix(&sessions_dir).unwrap_or(&source);
+    let mut target = destination.join(relative);
+    if compress {
+      target.as_mut_os_string().push(".zst");
+    }
+    if key.is_some() {
+      target
+        .as_mut_os_string()
+        .push(format!(".{ROLLOUT_ENCRYPTED_EXTENSION}"));
+    }
+    let (cwd, created_at) = rollout_session_meta(&source);
+    let archived_bytes = if options.dry_run.unwrap_or(false) {
+      0
+    } else {
+      archive_rollout_file(&source, &target, compress, key.as_deref()).map_err(|err| {
+        napi::Error::from_reason(format!("Failed to archive {}: {err}", source.display()))
+      })?
+    };
+    let entry = ArchivedRollout {
+      id: source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("unknown")
+        .to_string(),
+      original_path: source.to_string_lossy().into_owned(),
+      archive_path: target.to_string_lossy().into_owned(),
+      cwd,
+      created_at,
+      updated_at: Some(format_rollout_timestamp(modified)),
+      original_bytes: metadata.len() as i64,
+      archived_bytes: archived_bytes as i64,
+    };
+    bytes_before += entry.original_bytes;
+    bytes_after += entry.archived_bytes;
+    if !options.dry_run.unwrap_or(false) {
+      append_rollout_archive_index(&destination, &entry)?;
+      if let Ok(mut cache) = rollout_cache().lock() {
+        cache.remove(&source);
+      }
+    }
+    archived.push(entry);
+  }
+
+  Ok(ArchiveRolloutsResult {
+    archived,
+    index_path: destination
+      .join(ROLLOUT_ARCHIVE_INDEX)
+      .to_string_lossy()
+      .into_owned(),
+    bytes_before,
+    bytes_after,
+  })
+}
+
+/// Replace an archive's index with `entries`.
+fn write_rollout_archive_index(
+  archive_dir: &Path,
+  entries: &[ArchivedRollout],
+) -> napi::Result<()> {
+  let mut contents = String::new();
+  for entry in entries {
+    let line = serde_json::to_string(entry).map_err(|err| {
+      napi::Error::from_reason(format!("Failed to serialize archive entry: {err}"))
+    })?;
+    contents.push_str(&line);
+    contents.push('\n');
+  }
+  let index = archive_dir.join(ROLLOUT_ARCHIVE_INDEX);
+  let temp = archive_dir.join(format!("{ROLLOUT_ARCHIVE_INDEX}.tmp-{}", Uuid::new_v4()));
+  std::fs::write(&temp, contents)
+    .and_then(|()| std::fs::rename(&temp, &index))
+    .map_err(|err| {
+      let _ = std::fs::remove_file(&temp);
+      napi::Error::from_reason(format!("Failed to update archive index: {err}"))
+    })
+}
+
+fn append_rollout_archive_index(destination: &Path, entry: &ArchivedRollout) -> napi::Result<()> {
+  let line = serde_json::to_string(entry)
+    .map_err(|err| napi::Error::from_reason(format!("Failed to serialize archive entry: {err}")))?;
+  std::fs::create_dir_all(destination)
+    .and_then(|()| {
+      let mut index = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(destination.join(ROLLOUT_ARCHIVE_INDEX))?;
+      writeln!(index, "{line}")
+    })
+    .map_err(|err| napi::Error::from_reason(format!("Failed to update archive index: {err}")))
+}
+
+#[napi]
+pub async fn archive_rollouts(
+  options: ArchiveRolloutsOptions,
+) -> napi::Result<ArchiveRolloutsResult> {
+  let result = tokio::task::spawn_blocking(move || archive_rollouts_blocking(options))
+    .await
+    .map_err(|err| napi::Error::from_reason(format!("archive task failed: {err}")))?;
+  bump_reverie_corpus_version();
+  result
+}
+
+#[cfg(test)]
+mod tests_rollout_archive {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn archived_rollouts_are_compressed_indexed_and_still_readable() {
+    let home = tempfile::tempdir().unwrap();
+    let day_dir = home.path().join("sessions/2024/01/02");
+    std::fs::create_dir_all(&day_dir).unwrap();
+    let rollout = day_dir.join("rollout-2024-01-02T03-04-05-abc.jsonl");
+    let contents = concat!(
+      r#"{"type":"session_meta","payload":{"id":"abc","timestamp":"2024-01-02T03:04:05Z","cwd":"/repo"}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"hello"}}"#,
+      "\n",
+    );
+    std::fs::write(&rollout, contents).unwrap();
+
+    let result = archive_rollouts_blocking(ArchiveRolloutsOptions {
+      older_than_days: 0,
+      compress: None,
+      destination: None,
+      codex_home: Some(home.path().to_string_lossy().into_owned()),
+      dry_run: None,
+      encrypt: None,
+    })
+    .unwrap();
+
+    let archive_dir = home.path().join(ROLLOUT_ARCHIVE_DIR);
+    let archive_path = archive_dir.join("2024/01/02/rollout-2024-01-02T03-04-05-abc.jsonl.zst");
+    assert!(!rollout.exists());
+    assert_eq!(read_rollout_text(&archive_path).unwrap(), contents);
+    assert_eq!(read_rollout_archive_index(&archive_dir), result.archived);
+    let entry = &result.archived[0];
+    assert_eq!(entry.id, "rollout-2024-01-02T03-04-05-abc");
+    assert_eq!(entry.archive_path, archive_path.to_string_lossy());
+    assert_eq!(entry.cwd.as_deref(), Some("/repo"));
+    assert_eq!(entry.created_at.as_deref(), Some("2024-01-02T03:04:05Z"));
+  }
+}
@@ -0,0 +1,233 @@
+// Section 11: Read-through Rollout Cache
+// ============================================================================
+//
+// Replay, reverie search and insight extraction all parse the same rollout
+// JSONL files. Parsed records are kept in a small LRU keyed by path and
+// invalidated automatically whenever the file's mtime or length changes, so
+// active sessions that keep appending are re-read only when they grow.
+//
+// Key exports:
+//   - configure_rollout_cache(): Resize the cache (0 disables it)
+//   - invalidate_rollout_cache(): Drop one path or every entry
+//   - rollout_cache_stats(): Inspect size and hit/miss counters
+//
+// ============================================================================
+
+const DEFAULT_ROLLOUT_CACHE_CAPACITY: usize = 32;
+
+/// Every non-empty line of a rollout, parsed as JSON.
+#[derive(Debug, Default)]
+struct ParsedRollout {
+  records: Vec<JsonValue>,
+  /// 1-based line numbers that failed to parse, with the parser error.
+  invalid_lines: Vec<(usize, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RolloutFingerprint {
+  modified: Option<SystemTime>,
+  len: u64,
+}
+
+struct RolloutCache {
+  capacity: usize,
+  entries: HashMap<PathBuf, (RolloutFingerprint, Arc<ParsedRollout>)>,
+  /// Least recently used path at the front.
+  order: VecDeque<PathBuf>,
+  hits: u64,
+  misses: u64,
+}
+
+impl RolloutCache {
+  fn get(&mut self, path: &Path, fingerprint: &RolloutFingerprint) -> Option<Arc<ParsedRollout>> {
+    let parsed = match self.entries.get(path) {
+      Some((cached, parsed)) if cached == fingerprint => Arc::clone(parsed),
+      _ => {
+        self.misses += 1;
+        return None;
+      }
+    };
+    self.hits += 1;
+    self.touch(path);
+    Some(parsed)
+  }
+
+  fn insert(&mut self, path: PathBuf, fingerprint: RolloutFingerprint, parsed: Arc<ParsedRollout>) {
+    if self.capacity == 0 {
+      return;
+    }
+    self.touch(&path);
+    self.entries.insert(path, (fingerprint, parsed));
+    self.evict_to_capacity();
+  }
+
+  fn touch(&mut self, path: &Path) {
+    self.order.retain(|entry| entry != path);
+    self.order.push_back(path.to_path_buf());
+  }
+
+  fn remove(&mut self, path: &Path) {
+    self.order.retain(|entry| entry != path);
+    self.entries.remove(path);
+  }
+
+  fn evict_to_capacity(&mut self) {
+    while self.order.len() > self.capacity {
+      if let Some(oldest) = self.order.pop_front() {
+        self.entries.remove(&oldest);
+      }
+    }
+  }
+}
+
+fn rollout_cache() -> &'static Mutex<RolloutCache> {
+  static CACHE: OnceLock<Mutex<RolloutCache>> = OnceLock::new();
+  CACHE.get_or_init(|| {
+    Mutex::new(RolloutCache {
+      capacity: DEFAULT_ROLLOUT_CACHE_CAPACITY,
+      entries: HashMap::new(),
+      order: VecDeque::new(),
+      hits: 0,
+      misses: 0,
+    })
+  })
+}
+
+fn parse_rollout_contents(contents: &str) -> ParsedRollout {
+  let mut parsed = ParsedRollout::default();
+  for (index, line) in contents.lines().enumerate() {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+    match serde_json::from_str::<JsonValue>(trimmed) {
+      Ok(value) => parsed.records.push(value),
+      Err(err) => parsed.invalid_lines.push((index + 1, err.to_string())),
+    }
+  }
+  parsed
+}
+
+/// Parse `path`, reusing the cached records when the file is unchanged since the last read.
+fn load_cached_rollout(path: &Path) -> std::io::Result<Arc<ParsedRollout>> {
+  let metadata = std::fs::metadata(path)?;
+  let fingerprint = RolloutFingerprint {
+    modified: metadata.modified().ok(),
+    len: metadata.len(),
+  };
+  if let Ok(mut cache) = rollout_cache().lock()
+    && let Some(parsed) = cache.get(path, &fingerprint)
+  {
+    return Ok(parsed);
+  }
+
+  let parsed = Arc::new(parse_rollout_contents(&read_rollout_text(path)?));
+  if let Ok(mut cache) = rollout_cache().lock() {
+    cache.insert(path.to_path_buf(), fingerprint, Arc::clone(&parsed));
+  }
+  Ok(parsed)
+}
+
+#[napi(object)]
+pub struct RolloutCacheStats {
+  pub entries: u32,
+  pub capacity: u32,
+  pub hits: i64,
+  pub misses: i64,
+}
+
+/// Set the maximum number of parsed rollouts kept in memory. `0` disables caching.
+#[napi]
+pub fn configure_rollout_cache(max_entries: u32) -> napi::Result<()> {
+  let mut cache = rollout_cache()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("rollout cache mutex poisoned: {e}")))?;
+  cache.capacity = max_entries as usize;
+  cache.evict_to_capacity();
+  Ok(())
+}
+
+/// Drop the cached records for `path`, or the whole cache when no path is given.
+#[napi]
+pub fn invalidate_rollout_cache(path: Option<String>) -> napi::Result<()> {
+  let mut cache = rollout_cache()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("rollout cache mutex poisoned: {e}")))?;
+  match path {
+    Some(path) => cache.remove(Path::new(&path)),
+    None => {
+      cache.entries.clear();
+      cache.order.clear();
+    }
+  }
+  Ok(())
+}
+
+#[napi]
+pub fn rollout_cache_stats() -> napi::Result<RolloutCacheStats> {
+  let cache = rollout_cache()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("rollout cache mutex poisoned: {e}")))?;
+  Ok(RolloutCacheStats {
+    entries: cache.entries.len() as u32,
+    capacity: cache.capacity as u32,
+    hits: cache.hits as i64,
+    misses: cache.misses as i64,
+  })
+}
+
+#[cfg(test)]
+mod tests_rollout_cache {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn test_cache(capacity: usize) -> RolloutCache {
+    RolloutCache {
+      capacity,
+      entries: HashMap::new(),
+      order: VecDeque::new(),
+      hits: 0,
+      misses: 0,
+    }
+  }
+
+  fn fingerprint(len: u64) -> RolloutFingerprint {
+    RolloutFingerprint { modified: None, len }
+  }
+
+  #[test]
+  fn parse_collects_records_and_invalid_lines() {
+    let parsed = parse_rollout_contents("{\"a\":1}\n\nnot json\n{\"b\":2}\n");
+    assert_eq!(parsed.records, vec![json!({ "a": 1 }), json!({ "b": 2 })]);
+    assert_eq!(
+      parsed.invalid_lines.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+      vec![3]
+    );
+  }
+
+  #[test]
+  fn evicts_least_recently_used_and_misses_on_changed_fingerprint() {
+    let mut cache = test_cache(2);
+    let parsed = Arc::new(ParsedRollout::default());
+    cache.insert(PathBuf::from("a"), fingerprint(1), Arc::clone(&parsed));
+    cache.insert(PathBuf::from("b"), fingerprint(1), Arc::clone(&parsed));
+    assert!(cache.get(Path::new("a"), &fingerprint(1)).is_some());
+
+    cache.insert(PathBuf::from("c"), fingerprint(1), Arc::clone(&parsed));
+    assert!(cache.get(Path::new("b"), &fingerprint(1)).is_none());
+    assert!(cache.get(Path::new("a"), &fingerprint(2)).is_none());
+    assert_eq!((cache.hits, cache.misses), (1, 2));
+    assert_eq!(cache.order, VecDeque::from([PathBuf::from("a"), PathBuf::from("c")]));
+  }
+
+  #[test]
+  fn load_rereads_file_after_append() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rollout.jsonl");
+    std::fs::write(&path, "{\"n\":1}\n").unwrap();
+    assert_eq!(load_cached_rollout(&path).unwrap().records.len(), 1);
+
+    std::fs::write(&path, "{\"n\":1}\n{\"n\":2}\n").unwrap();
+    assert_eq!(load_cached_rollout(&path).unwrap().records.len(), 2);
+  }
+}
@@ -0,0 +1,397 @@
+// ============================================================================
+// Archive encryption (configureArchiveEncryption, encryptRolloutArchive)
+// ============================================================================
+//
+// Transcripts often contain proprietary code. configureArchiveEncryption()
+// sets a process-wide age key, either scrypt-derived from a passphrase or an
+// X25519 identity kept in the OS keychain, for the rollout archive only: the
+// archive readers (the rollout cache, openRolloutReader() and reverie over
+// archives) decrypt `.age` rollouts transparently. Live sessions are not
+// encrypted; the recorder writes them in plaintext, and resume, fork and
+// listConversations only read them. encryptRolloutArchive() encrypts what is
+// already archived, and archives closed sessions only when asked to with
+// olderThanDays.
+
+const ROLLOUT_ENCRYPTED_EXTENSION: &str = "age";
+const ROLLOUT_KEYCHAIN_SERVICE: &str = "Codex Rollout Encryption";
+
+static ROLLOUT_ENCRYPTION_KEY: Mutex<Option<Arc<RolloutEncryptionKey>>> = Mutex::new(None);
+
+enum RolloutEncryptionKey {
+  Passphrase(age::secrecy::SecretString),
+  Keychain(age::x25519::Identity),
+}
+
+impl RolloutEncryptionKey {
+  fn encrypt_to<W: io::Write>(&self, output: W) -> io::Result<age::stream::StreamWriter<W>> {
+    let encryptor = match self {
+      Self::Passphrase(passphrase) => age::Encryptor::with_user_passphrase(passphrase.clone()),
+      Self::Keychain(identity) => {
+        let recipient = identity.to_public();
+        age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))
+          .map_err(io::Error::other)?
+      }
+    };
+    encryptor.wrap_output(output)
+  }
+
+  fn decrypt_from<R: io::Read>(&self, input: R) -> io::Result<age::stream::StreamReader<R>> {
+    let decryptor = age::Decryptor::new(input).map_err(io::Error::other)?;
+    let reader = match self {
+      Self::Passphrase(passphrase) => {
+        let identity = age::scrypt::Identity::new(passphrase.clone());
+        decryptor.decrypt(std::iter::once(&identity as &dyn age::Identity))
+      }
+      Self::Keychain(identity) => {
+        decryptor.decrypt(std::iter::once(identity as &dyn age::Identity))
+      }
+    };
+    reader.map_err(|err| {
+      io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("Failed to decrypt rollout: {err}"),
+      )
+    })
+  }
+}
+
+fn set_rollout_encryption_key(key: Option<RolloutEncryptionKey>) -> napi::Result<()> {
+  let mut guard = ROLLOUT_ENCRYPTION_KEY
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("rollout encryption mutex poisoned: {e}")))?;
+  *guard = key.map(Arc::new);
+  // Decrypted contents cached under the previous key must not outlive it.
+  if let Ok(mut cache) = rollout_cache().lock() {
+    cache.entries.clear();
+    cache.order.clear();
+  }
+  Ok(())
+}
+
+/// The configured key, or an error explaining how to set one.
+fn rollout_encryption_key() -> io::Result<Arc<RolloutEncryptionKey>> {
+  ROLLOUT_ENCRYPTION_KEY
+    .lock()
+    .ok()
+    .and_then(|guard| guard.clone())
+    .ok_or_else(|| {
+      io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "rollout is encrypted; call configureArchiveEncryption() first",
+      )
+    })
+}
+
+/// Load the keychain identity for `codex_home`, generating and saving one on first use.
+fn load_or_create_keychain_identity(
+  store: &dyn codex_keyring_store::KeyringStore,
+  codex_home: &Path,
+) -> napi::Result<age::x25519::Identity> {
+  use age::secrecy::ExposeSecret;
+
+  let account = format!(
+    "rollouts|{}",
+    hash_string(&canonicalize_to_string(codex_home))
+  );
+  let stored = store
+    .load(ROLLOUT_KEYCHAIN_SERVICE, &account)
+    .map_err(|err| {
+      napi::Error::from_reason(format!("Failed to read rollout key from keychain: {err}"))
+    })?;
+  if let Some(stored) = stored {
+    return stored
+      .trim()
+      .parse::<age::x25519::Identity>()
+      .map_err(|err| napi::Error::from_reason(format!("Invalid rollout key in keychain: {err}")));
+  }
+  let identity = age::x25519::Identity::generate();
+  store
+    .save(
+      ROLLOUT_KEYCHAIN_SERVICE,
+      &account,
+      identity.to_string().expose_secret(),
+    )
+    .map_err(|err| {
+      napi::Error::from_reason(format!("Failed to save rollout key to keychain: {err}"))
+    })?;
+  Ok(identity)
+}
+
+/// Copy `input` into `output`, zstd-compressing and/or age-encrypting on the way.
+fn write_rollout_layers(
+  input: &mut impl io::Read,
+  output: std::fs::File,
+  compress: bool,
+  key: Option<&RolloutEncryptionKey>,
+) -> io::Result<()> {
+  match key {
+    Some(key) => {
+      let mut encrypted = key.encrypt_to(output)?;
+      if compress {
+        let mut encoder = zstd::Encoder::new(&mut encrypted, ROLLOUT_ARCHIVE_ZSTD_LEVEL)?;
+        io::copy(input, &mut encoder)?;
+        encoder.finish()?;
+      } else {
+        io::copy(input, &mut encrypted)?;
+      }
+      encrypted.finish()?.sync_all()
+    }
+    None if compress => {
+      let mut encoder = zstd::Encoder::new(output, ROLLOUT_ARCHIVE_ZSTD_LEVEL)?;
+      io::copy(input, &mut encoder)?;
+      encoder.finish()?.sync_all()
+    }
+    None => {
+      let mut output = output;
+      io::copy(input, &mut output)?;
+      output.sync_all()
+    }
+  }
+}
+
+#[napi(object)]
+pub struct ArchiveEncryptionOptions {
+  /// Derive the key from this passphrase (age scrypt). Every process reading the archive
+  /// needs the same passphrase.
+  pub passphrase: Option<String>,
+  /// Use an X25519 key kept in the OS keychain, created on first use.
+  #[napi(js_name = "useKeychain")]
+  pub use_keychain: Option<bool>,
+  /// Keychain keys are per codex home. Defaults to the resolved `$CODEX_HOME`.
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+}
+
+/// Set the key used to encrypt and read the rollout archive. Live sessions stay plaintext.
+/// Pass nothing to forget the key.
+#[napi]
+pub fn configure_archive_encryption(options: Option<ArchiveEncryptionOptions>) -> napi::Result<()> {
+  let Some(options) = options else {
+    return set_rollout_encryption_key(None);
+  };
+  let key = match (options.passphrase, options.use_keychain.unwrap_or(false)) {
+    (Some(_), true) => {
+      return Err(napi::Error::from_reason(
+        "Pass either passphrase or useKeychain, not both",
+      ));
+    }
+    (Some(passphrase), false) => {
+      if passphrase.is_empty() {
+        return Err(napi::Error::from_reason(
+          "Archive passphrase must not be empty",
+        ));
+      }
+      RolloutEncryptionKey::Passphrase(passphrase.into())
+    }
+    (None, true) => {
+      let codex_home = match options.codex_home.as_deref() {
+        Some(path) => PathBuf::from(path),
+        None => find_codex_home()
+          .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?,
+      };
+      RolloutEncryptionKey::Keychain(load_or_create_keychain_identity(
+        &codex_keyring_store::DefaultKeyringStore,
+        &codex_home,
+      )?)
+    }
+    (None, false) => {
+      return Err(napi::Error::from_reason(
+        "Archive encryption needs a passphrase or useKeychain: true",
+      ));
+    }
+  };
+  set_rollout_encryption_key(Some(key))
+}
+
+#[napi(object)]
+pub struct EncryptRolloutArchiveOptions {
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  /// Also archive, encrypted, live rollouts last written at least this many days ago. Without
+  /// it, live sessions are left alone.
+  #[napi(js_name = "olderThanDays")]
+  pub older_than_days: Option<u32>,
+}
+
+#[napi(object)]
+pub struct EncryptRolloutArchiveResult {
+  /// Archive entries that were encrypted in place, then newly archived rollouts.
+  pub encrypted: Vec<ArchivedRollout>,
+  #[napi(js_name = "indexPath")]
+  pub index_path: String,
+}
+
+/// Encrypt every plaintext rollout in the archive, rewriting its index entry.
+fn encrypt_archive_entries(
+  archive_dir: &Path,
+  key: &RolloutEncryptionKey,
+) -> napi::Result<Vec<ArchivedRollout>> {
+  let mut entries = read_rollout_archive_index(archive_dir);
+  let mut encrypted = Vec::new();
+  for entry in &mut entries {
+    let source = PathBuf::from(&entry.archive_path);
+    if source
+      .extension()
+      .is_some_and(|ext| ext == ROLLOUT_ENCRYPTED_EXTENSION)
+      || !source.exists()
+    {
+      continue;
+    }
+    let mut target = source.clone().into_os_string();
+    target.push(format!(".{ROLLOUT_ENCRYPTED_EXTENSION}"));
+    let target = PathBuf::from(target);
+    let archived_bytes =
+      archive_rollout_file(&source, &target, false, Some(key)).map_err(|err| {
+        napi::Error::from_reason(format!("Failed to encrypt {}: {err}", source.display()))
+      })?;
+    if let Ok(mut cache) = rollout_cache().lock() {
+      cache.remove(&source);
+    }
+    entry.archive_path = target.to_string_lossy().into_owned();
+    entry.archived_bytes = archived_bytes as i64;
+    encrypted.push(entry.clone());
+  }
+  if !encrypted.is_empty() {
+    write_rollout_archive_index(archive_dir, &entries)?;
+  }
+  Ok(encrypted)
+}
+
+fn encrypt_rollout_archive_blocking(
+  options: EncryptRolloutArchiveOptions,
+) -> napi::Result<EncryptRolloutArchiveResult> {
+  let key = rollout_encryption_key().map_err(|err| napi::Error::from_reason(err.to_string()))?;
+  let codex_home = match options.codex_home.as_deref() {
+    Some(path) => PathBuf::from(path),
+    None => find_codex_home()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?,
+  };
+  let archive_dir = codex_home.join(ROLLOUT_ARCHIVE_DIR);
+  let mut encrypted = encrypt_archive_entries(&archive_dir, &key)?;
+  if let Some(older_than_days) = options.older_than_days {
+    let archived = archive_rollouts_blocking(ArchiveRolloutsOptions {
+      older_than_days,
+      compress: None,
+      destination: None,
+      codex_home: Some(codex_home.to_string_lossy().into_owned()),
+      dry_run: None,
+      encrypt: Some(true),
+    })?;
+    encrypted.extend(archived.archived);
+  }
+  Ok(EncryptRolloutArchiveResult {
+    encrypted,
+    index_path: archive_dir
+      .join(ROLLOUT_ARCHIVE_INDEX)
+      .to_string_lossy()
+      .into_owned(),
+  })
+}
+
+#[napi]
+pub async fn encrypt_rollout_archive(
+  options: Option<EncryptRolloutArchiveOptions>,
+) -> napi::Result<EncryptRolloutArchiveResult> {
+  let options = options.unwrap_or(EncryptRolloutArchiveOptions {
+    codex_home: None,
+    older_than_days: None,
+  });
+  let result = tokio::task::spawn_blocking(move || encrypt_rollout_archive_blocking(options))
+    .await
+    .map_err(|err| napi::Error::from_reason(format!("archive encryption task failed: {err}")))?;
+  bump_reverie_corpus_version();
+  result
+}
+
+#[cfg(test)]
+mod tests_rollout_encryption {
+  use super::*;
+  use codex_keyring_store::tests::MockKeyringStore;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn encrypts_the_archive_and_only_requested_sessions_readable_only_with_key() {
+    let home = tempfile::tempdir().unwrap();
+    let contents = concat!(
+      r#"{"type":"session_meta","payload":{"id":"abc","timestamp":"2024-01-02T03:04:05Z","cwd":"/repo"}}"#,
+      "\n",
+    );
+    let archive_dir = home.path().join(ROLLOUT_ARCHIVE_DIR);
+    let archived_plain = archive_dir.join("2024/01/01/rollout-2024-01-01T00-00-00-old.jsonl");
+    std::fs::create_dir_all(archived_plain.parent().unwrap()).unwrap();
+    std::fs::write(&archived_plain, contents).unwrap();
+    append_rollout_archive_index(
+      &archive_dir,
+      &ArchivedRollout {
+        id: "rollout-2024-01-01T00-00-00-old".to_string(),
+        original_path: "unused".to_string(),
+        archive_path: archived_plain.to_string_lossy().into_owned(),
+        cwd: Some("/repo".to_string()),
+        created_at: None,
+        updated_at: None,
+        original_bytes: contents.len() as i64,
+        archived_bytes: contents.len() as i64,
+      },
+    )
+    .unwrap();
+    let live = home
+      .path()
+      .join("sessions/2024/01/02/rollout-2024-01-02T03-04-05-abc.jsonl");
+    std::fs::create_dir_all(live.parent().unwrap()).unwrap();
+    std::fs::write(&live, contents).unwrap();
+
+    let store = MockKeyringStore::default();
+    let identity = load_or_create_keychain_identity(&store, home.path()).unwrap();
+    let reloaded = load_or_create_keychain_identity(&store, home.path()).unwrap();
+    assert_eq!(
+      reloaded.to_public().to_string(),
+      identity.to_public().to_string()
+    );
+    set_rollout_encryption_key(Some(RolloutEncryptionKey::Keychain(identity))).unwrap();
+
+    let archive_only = encrypt_rollout_archive_blocking(EncryptRolloutArchiveOptions {
+      codex_home: Some(home.path().to_string_lossy().into_owned()),
+      older_than_days: None,
+    })
+    .unwrap();
+    assert_eq!(archive_only.encrypted.len(), 1);
+    assert!(live.exists());
+
+    let result = encrypt_rollout_archive_blocking(EncryptRolloutArchiveOptions {
+      codex_home: Some(home.path().to_string_lossy().into_owned()),
+      older_than_days: Some(0),
+    })
+    .unwrap();
+
+    let paths: Vec<String> = archive_only
+      .encrypted
+      .iter()
+      .chain(&result.encrypted)
+      .map(|entry| entry.archive_path.clone())
+      .collect();
+    let expected = vec![
+      archive_dir.join("2024/01/01/rollout-2024-01-01T00-00-00-old.jsonl.age"),
+      archive_dir.join("2024/01/02/rollout-2024-01-02T03-04-05-abc.jsonl.zst.age"),
+    ];
+    assert_eq!(
+      paths,
+      expected
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+    );
+    assert!(!archived_plain.exists() && !live.exists());
+    assert_eq!(
+      read_rollout_archive_index(&archive_dir),
+      [archive_only.encrypted, result.encrypted].concat()
+    );
+    for path in &expected {
+      assert!(!std::fs::read(path).unwrap().starts_with(b"{"));
+      assert_eq!(read_rollout_text(path).unwrap(), contents);
+    }
+
+    set_rollout_encryption_key(None).unwrap();
+    let err = read_rollout_text(&expected[0]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+  }
+}
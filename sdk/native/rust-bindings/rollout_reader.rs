@@ -0,0 +1,191 @@
+// ============================================================================
+// Streaming rollout reader (openRolloutReader)
+// ============================================================================
+//
+// The rollout cache and reverie_get_conversation_insights() parse a whole
+// rollout at once, which is wasteful for dashboards that show a long session
+// progressively. openRolloutReader() returns a cursor that reads the JSONL
+// file lazily: each nextBatch(n) call parses only the next n records and
+// attaches the same TOON preview and message classification reverie uses.
+
+const DEFAULT_ROLLOUT_READER_BATCH: u32 = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct RolloutReaderRecord {
+  /// 1-based line number in the rollout file.
+  #[napi(js_name = "lineNumber")]
+  pub line_number: u32,
+  /// The parsed record, or `None` when the line isn't valid JSON.
+  pub record: Option<JsonValue>,
+  /// TOON encoding of the record, as in reverie's `headRecordsToon`.
+  pub toon: Option<String>,
+  /// `user`, `agent`, `reasoning`, `tool` or `system`.
+  #[napi(js_name = "messageType")]
+  pub message_type: Option<String>,
+  /// Session metadata or injected instructions rather than conversation content.
+  #[napi(js_name = "isMetadata")]
+  pub is_metadata: bool,
+  #[napi(js_name = "parseError")]
+  pub parse_error: Option<String>,
+}
+
+struct RolloutReaderState {
+  lines: Box<dyn io::BufRead + Send>,
+  line_number: u32,
+  done: bool,
+}
+
+impl RolloutReaderState {
+  fn next_batch(&mut self, limit: usize) -> io::Result<Vec<RolloutReaderRecord>> {
+    use std::io::BufRead;
+
+    let mut batch = Vec::new();
+    let mut line = String::new();
+    while batch.len() < limit && !self.done {
+      line.clear();
+      if self.lines.read_line(&mut line)? == 0 {
+        self.done = true;
+        break;
+      }
+      self.line_number += 1;
+      let trimmed = line.trim();
+      if trimmed.is_empty() {
+        continue;
+      }
+      batch.push(rollout_reader_record(self.line_number, trimmed));
+    }
+    Ok(batch)
+  }
+}
+
+fn rollout_reader_record(line_number: u32, line: &str) -> RolloutReaderRecord {
+  let value = match serde_json::from_str::<JsonValue>(line) {
+    Ok(value) => value,
+    Err(err) => {
+      return RolloutReaderRecord {
+        line_number,
+        record: None,
+        toon: None,
+        message_type: None,
+        is_metadata: false,
+        parse_error: Some(err.to_string()),
+      };
+    }
+  };
+  let message_type = match classify_message_type(&value) {
+    MessageType::User => "user",
+    MessageType::Agent => "agent",
+    MessageType::Reasoning => "reasoning",
+    MessageType::Tool => "tool",
+    MessageType::System => "system",
+  };
+  RolloutReaderRecord {
+    line_number,
+    toon: Some(encode_json_value_to_toon(&value).unwrap_or_else(|| fallback_toon_snippet(line))),
+    message_type: Some(message_type.to_string()),
+    is_metadata: is_metadata_record(&value),
+    record: Some(value),
+    parse_error: None,
+  }
+}
+
+#[napi]
+pub struct RolloutReader {
+  state: Mutex<RolloutReaderState>,
+}
+
+#[napi]
+impl RolloutReader {
+  /// The next `n` records (default 100). Returns fewer, down to none, at the end of the file.
+  /// Lines appended after the end was reached are not picked up.
+  #[napi]
+  pub fn next_batch(&self, n: Option<u32>) -> napi::Result<Vec<RolloutReaderRecord>> {
+    let limit = n.unwrap_or(DEFAULT_ROLLOUT_READER_BATCH).max(1) as usize;
+    self
+      .lock_state()?
+      .next_batch(limit)
+      .map_err(|err| napi::Error::from_reason(format!("Failed to read rollout: {err}")))
+  }
+
+  /// Whether the whole file has been read.
+  #[napi(getter)]
+  pub fn done(&self) -> napi::Result<bool> {
+    Ok(self.lock_state()?.done)
+  }
+
+  /// Lines consumed so far, including blank and invalid ones.
+  #[napi(getter)]
+  pub fn lines_read(&self) -> napi::Result<u32> {
+    Ok(self.lock_state()?.line_number)
+  }
+
+  fn lock_state(&self) -> napi::Result<std::sync::MutexGuard<'_, RolloutReaderState>> {
+    self
+      .state
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("rollout reader mutex poisoned: {e}")))
+  }
+}
+
+#[napi]
+pub fn open_rollout_reader(path: String) -> napi::Result<RolloutReader> {
+  let lines = open_rollout_lines(Path::new(&path))
+    .map_err(|err| napi::Error::from_reason(format!("Failed to open rollout {path}: {err}")))?;
+  Ok(RolloutReader {
+    state: Mutex::new(RolloutReaderState {
+      lines,
+      line_number: 0,
+      done: false,
+    }),
+  })
+}
+
+#[cfg(test)]
+mod tests_rollout_reader {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn batches_parse_lazily_and_classify_records() {
+    let contents = concat!(
+      r#"{"type":"session_meta","payload":{"id":"t1"}}"#,
+      "\n\n",
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"fix the build"}}"#,
+      "\nnot json\n",
+    );
+    let mut state = RolloutReaderState {
+      lines: Box::new(io::Cursor::new(contents.as_bytes().to_vec())),
+      line_number: 0,
+      done: false,
+    };
+
+    let first = state.next_batch(2).unwrap();
+    let summary: Vec<(u32, Option<String>, bool)> = first
+      .iter()
+      .map(|record| {
+        (
+          record.line_number,
+          record.message_type.clone(),
+          record.is_metadata,
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        (1, Some("system".to_string()), true),
+        (3, Some("user".to_string()), false),
+      ]
+    );
+    assert!(first.iter().all(|record| record.toon.is_some()));
+    assert!(!state.done);
+
+    let rest = state.next_batch(2).unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].line_number, 4);
+    assert!(rest[0].parse_error.is_some());
+    assert!(state.done);
+    assert_eq!(state.next_batch(2).unwrap(), Vec::new());
+  }
+}
@@ -0,0 +1,250 @@
+// ============================================================================
+// Thread activity tracking (getThreadActivity)
+// ============================================================================
+//
+// Every event that flows through `run_internal_sync` updates a per-thread
+// activity record so supervisors can poll for the current phase and how long
+// the agent has been silent, without subscribing to the event stream.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ThreadPhase {
+  Starting,
+  Thinking,
+  ExecutingTool,
+  WaitingForApproval,
+  Idle,
+  Failed,
+}
+
+impl ThreadPhase {
+  fn as_str(self) -> &'static str {
+    match self {
+      ThreadPhase::Starting => "starting",
+      ThreadPhase::Thinking => "thinking",
+      ThreadPhase::ExecutingTool => "executing_tool",
+      ThreadPhase::WaitingForApproval => "waiting_for_approval",
+      ThreadPhase::Idle => "idle",
+      ThreadPhase::Failed => "failed",
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+struct ThreadActivityState {
+  phase: ThreadPhase,
+  tool: Option<String>,
+  /// Tool awaiting a JS approval decision; overrides `phase` while set.
+  pending_approval: Option<String>,
+  last_event_at: DateTime<Utc>,
+  last_event_type: Option<&'static str>,
+  event_count: u64,
+  running: bool,
+}
+
+impl ThreadActivityState {
+  fn new() -> Self {
+    Self {
+      phase: ThreadPhase::Starting,
+      tool: None,
+      pending_approval: None,
+      last_event_at: Utc::now(),
+      last_event_type: None,
+      event_count: 0,
+      running: true,
+    }
+  }
+
+  fn observe(&mut self, event: &ExecThreadEvent) {
+    self.last_event_at = Utc::now();
+    self.event_count += 1;
+    let (event_type, transition) = match event {
+      ExecThreadEvent::ThreadStarted(_) => ("thread.started", Some((ThreadPhase::Starting, None))),
+      ExecThreadEvent::TurnStarted(_) => ("turn.started", Some((ThreadPhase::Thinking, None))),
+      ExecThreadEvent::TurnCompleted(_) => ("turn.completed", Some((ThreadPhase::Idle, None))),
+      ExecThreadEvent::TurnFailed(_) => ("turn.failed", Some((ThreadPhase::Failed, None))),
+      ExecThreadEvent::Error(_) => ("error", Some((ThreadPhase::Failed, None))),
+      ExecThreadEvent::ItemCompleted(_) => ("item.completed", Some((ThreadPhase::Thinking, None))),
+      ExecThreadEvent::ItemStarted(started) => ("item.started", Some(item_transition(&started.item))),
+      ExecThreadEvent::ItemUpdated(updated) => ("item.updated", Some(item_transition(&updated.item))),
+      ExecThreadEvent::ExitedReviewMode(_) => ("exited_review_mode", None),
+      ExecThreadEvent::BackgroundEvent(_) => ("background_event", None),
+      ExecThreadEvent::ToolOutputChunk(_) => ("tool.output_chunk", None),
+      ExecThreadEvent::BackgroundTaskStarted(_) => ("background_task_started", None),
+      ExecThreadEvent::BackgroundTaskCompleted(_) => ("background_task_completed", None),
+      ExecThreadEvent::PlanUpdated(_) => ("plan_updated", None),
+      ExecThreadEvent::ThreadStatus(_) => ("thread_status", None),
+      ExecThreadEvent::TestResults(_) => ("test_results", None),
+      ExecThreadEvent::VerificationFailed(_) => ("verification_failed", None),
+      ExecThreadEvent::FilesTouched(_) => ("files_touched", None),
+      ExecThreadEvent::CommandStarted(_) => ("command_started", None),
+      ExecThreadEvent::CommandCompleted(_) => ("command_completed", None),
+      ExecThreadEvent::Raw(_) => ("raw_event", None),
+    };
+    self.last_event_type = Some(event_type);
+    if let Some((phase, tool)) = transition {
+      self.phase = phase;
+      self.tool = tool;
+    }
+  }
+}
+
+fn item_transition(item: &codex_exec::exec_events::ThreadItem) -> (ThreadPhase, Option<String>) {
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  let tool = match &item.details {
+    ThreadItemDetails::CommandExecution(command) => Some(format!("shell: {}", command.command)),
+    ThreadItemDetails::FileChange(_) => Some("apply_patch".to_string()),
+    ThreadItemDetails::McpToolCall(call) => Some(format!("{}.{}", call.server, call.tool)),
+    ThreadItemDetails::CollabToolCall(_) => Some("collab".to_string()),
+    ThreadItemDetails::WebSearch(_) => Some("web_search".to_string()),
+    ThreadItemDetails::AgentMessage(_)
+    | ThreadItemDetails::Reasoning(_)
+    | ThreadItemDetails::TodoList(_)
+    | ThreadItemDetails::Error(_) => None,
+  };
+  match tool {
+    Some(tool) => (ThreadPhase::ExecutingTool, Some(tool)),
+    None => (ThreadPhase::Thinking, None),
+  }
+}
+
+fn thread_activity() -> &'static Mutex<HashMap<String, ThreadActivityState>> {
+  static ACTIVITY: OnceLock<Mutex<HashMap<String, ThreadActivityState>>> = OnceLock::new();
+  ACTIVITY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_thread_activity(thread_id: &str, event: &ExecThreadEvent) {
+  if let Ok(mut activity) = thread_activity().lock() {
+    let state = activity
+      .entry(thread_id.to_string())
+      .or_insert_with(ThreadActivityState::new);
+    state.running = true;
+    state.observe(event);
+  }
+}
+
+fn mark_thread_run_finished(thread_id: &str) {
+  if let Ok(mut activity) = thread_activity().lock()
+    && let Some(state) = activity.get_mut(thread_id)
+  {
+    state.running = false;
+    state.pending_approval = None;
+    if state.phase != ThreadPhase::Failed {
+      state.phase = ThreadPhase::Idle;
+      state.tool = None;
+    }
+  }
+}
+
+/// Mark `thread_id` as blocked on an approval decision for `tool` (or clear it with `None`).
+fn set_thread_pending_approval(thread_id: &str, tool: Option<&str>) {
+  if let Ok(mut activity) = thread_activity().lock() {
+    let state = activity
+      .entry(thread_id.to_string())
+      .or_insert_with(ThreadActivityState::new);
+    state.pending_approval = tool.map(str::to_string);
+    state.last_event_at = Utc::now();
+  }
+}
+
+#[napi(object)]
+pub struct ThreadActivity {
+  #[napi(js_name = "threadId")]
+  pub thread_id: String,
+  #[napi(ts_type = "\"starting\" | \"thinking\" | \"executing_tool\" | \"waiting_for_approval\" | \"idle\" | \"failed\"")]
+  pub phase: String,
+  /// Tool currently executing or awaiting approval.
+  pub tool: Option<String>,
+  /// Whether a run for this thread is in progress in this process.
+  pub running: bool,
+  #[napi(js_name = "lastEventAt")]
+  pub last_event_at: String,
+  #[napi(js_name = "lastEventType")]
+  pub last_event_type: Option<String>,
+  /// Milliseconds since the last event (or approval transition).
+  #[napi(js_name = "idleMs")]
+  pub idle_ms: i64,
+  #[napi(js_name = "eventCount")]
+  pub event_count: i64,
+}
+
+fn thread_activity_snapshot(thread_id: &str, state: &ThreadActivityState) -> ThreadActivity {
+  let (phase, tool) = match &state.pending_approval {
+    Some(tool) => (ThreadPhase::WaitingForApproval, Some(tool.clone())),
+    None => (state.phase, state.tool.clone()),
+  };
+  ThreadActivity {
+    thread_id: thread_id.to_string(),
+    phase: phase.as_str().to_string(),
+    tool,
+    running: state.running,
+    last_event_at: state.last_event_at.to_rfc3339(),
+    last_event_type: state.last_event_type.map(str::to_string),
+    idle_ms: (Utc::now() - state.last_event_at).num_milliseconds().max(0),
+    event_count: state.event_count as i64,
+  }
+}
+
+/// Current phase and idle time for a thread seen by this process, if any.
+#[napi]
+pub fn get_thread_activity(thread_id: String) -> napi::Result<Option<ThreadActivity>> {
+  let activity = thread_activity()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("thread activity mutex poisoned: {e}")))?;
+  Ok(
+    activity
+      .get(&thread_id)
+      .map(|state| thread_activity_snapshot(&thread_id, state)),
+  )
+}
+
+#[cfg(test)]
+mod tests_activity {
+  use super::*;
+  use codex_exec::exec_events::CommandExecutionItem;
+  use codex_exec::exec_events::CommandExecutionStatus;
+  use codex_exec::exec_events::ItemStartedEvent;
+  use codex_exec::exec_events::ThreadItem;
+  use codex_exec::exec_events::ThreadItemDetails;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn phases_follow_the_event_stream_and_approvals() {
+    let thread_id = "activity-test-thread";
+    record_thread_activity(
+      thread_id,
+      &ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent::default()),
+    );
+    record_thread_activity(
+      thread_id,
+      &ExecThreadEvent::ItemStarted(ItemStartedEvent {
+        item: ThreadItem {
+          id: "item_0".to_string(),
+          details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
+            command: "ls".to_string(),
+            aggregated_output: String::new(),
+            exit_code: None,
+            status: CommandExecutionStatus::InProgress,
+            cache_hit: None,
+            is_tty: None,
+          }),
+        },
+      }),
+    );
+    let activity = get_thread_activity(thread_id.to_string()).unwrap().unwrap();
+    assert_eq!(activity.phase, "executing_tool");
+    assert_eq!(activity.tool.as_deref(), Some("shell: ls"));
+    assert_eq!(activity.event_count, 2);
+
+    set_thread_pending_approval(thread_id, Some("apply_patch"));
+    let activity = get_thread_activity(thread_id.to_string()).unwrap().unwrap();
+    assert_eq!(activity.phase, "waiting_for_approval");
+    assert_eq!(activity.tool.as_deref(), Some("apply_patch"));
+
+    set_thread_pending_approval(thread_id, None);
+    mark_thread_run_finished(thread_id);
+    let activity = get_thread_activity(thread_id.to_string()).unwrap().unwrap();
+    assert_eq!(activity.phase, "idle");
+    assert!(!activity.running);
+  }
+}
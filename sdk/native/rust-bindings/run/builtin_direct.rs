@@ -0,0 +1,104 @@
+// ============================================================================
+// Direct builtin tool calls (callBuiltinTool)
+// ============================================================================
+//
+// callBuiltin() only works inside an interceptor, on a call the model made.
+// callBuiltinTool() runs a builtin (shell, apply_patch, read_file) from
+// orchestration code with no model turn at all, under the same sandbox and
+// patch checks a run would apply. Nothing can be approved interactively, so
+// anything the sandbox mode doesn't allow outright is rejected. The default
+// sandbox mode is read-only.
+
+#[derive(Clone, Debug)]
+#[napi(object)]
+pub struct BuiltinToolCallRequest {
+  #[napi(js_name = "toolName")]
+  pub tool_name: String,
+  /// The tool's arguments, as an object or a JSON string, in the shape the model would send.
+  pub arguments: Option<JsonValue>,
+  /// Directory commands run in and relative paths resolve against. Defaults to the process cwd.
+  pub cwd: Option<String>,
+  #[napi(js_name = "sandboxMode")]
+  pub sandbox_mode: Option<String>,
+  #[napi(js_name = "linuxSandboxPath")]
+  pub linux_sandbox_path: Option<String>,
+}
+
+fn direct_sandbox_policy(
+  sandbox_mode: Option<&str>,
+) -> napi::Result<codex_protocol::protocol::SandboxPolicy> {
+  use codex_protocol::protocol::SandboxPolicy;
+  Ok(match parse_sandbox_mode(sandbox_mode)? {
+    None | Some(SandboxModeCliArg::ReadOnly) => SandboxPolicy::new_read_only_policy(),
+    Some(SandboxModeCliArg::WorkspaceWrite) => SandboxPolicy::new_workspace_write_policy(),
+    Some(SandboxModeCliArg::DangerFullAccess) => SandboxPolicy::DangerFullAccess,
+  })
+}
+
+impl BuiltinToolCallRequest {
+  fn into_direct_call(self) -> napi::Result<codex_core::DirectToolCall> {
+    let cwd = match self.cwd {
+      Some(cwd) => PathBuf::from(cwd),
+      None => std::env::current_dir()
+        .map_err(|err| napi::Error::from_reason(format!("failed to read current dir: {err}")))?,
+    };
+    let arguments = match self.arguments {
+      Some(JsonValue::String(arguments)) => arguments,
+      Some(arguments) => arguments.to_string(),
+      None => "{}".to_string(),
+    };
+    Ok(codex_core::DirectToolCall {
+      tool_name: self.tool_name,
+      arguments,
+      cwd,
+      sandbox_policy: direct_sandbox_policy(self.sandbox_mode.as_deref())?,
+      codex_linux_sandbox_exe: resolve_linux_sandbox_path(
+        self.linux_sandbox_path.map(PathBuf::from),
+      )?,
+    })
+  }
+}
+
+#[napi]
+pub async fn call_builtin_tool(req: BuiltinToolCallRequest) -> napi::Result<NativeToolResponse> {
+  let call = req.into_direct_call()?;
+  match codex_core::call_builtin_tool(call).await {
+    Ok(output) => Ok(NativeToolResponse {
+      output: Some(output.output),
+      success: Some(output.success),
+      error: None,
+    }),
+    Err(FunctionCallError::RespondToModel(message)) => Ok(NativeToolResponse {
+      output: None,
+      success: Some(false),
+      error: Some(message),
+    }),
+    Err(err) => Err(napi::Error::from_reason(err.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests_builtin_direct {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn direct_calls_default_to_read_only_and_accept_object_arguments() {
+    let call = BuiltinToolCallRequest {
+      tool_name: "read_file".to_string(),
+      arguments: Some(json!({ "file_path": "README.md" })),
+      cwd: Some("/workspace".to_string()),
+      sandbox_mode: None,
+      linux_sandbox_path: Some("/opt/codex-linux-sandbox".to_string()),
+    }
+    .into_direct_call()
+    .unwrap();
+    assert_eq!(call.arguments, r#"{"file_path":"README.md"}"#);
+    assert_eq!(call.cwd, PathBuf::from("/workspace"));
+    assert_eq!(
+      call.sandbox_policy,
+      codex_protocol::protocol::SandboxPolicy::ReadOnly
+    );
+    assert!(direct_sandbox_policy(Some("everything")).is_err());
+  }
+}
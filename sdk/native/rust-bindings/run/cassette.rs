@@ -0,0 +1,527 @@
+// ============================================================================
+// Session cassettes (RunRequest.record / RunRequest.replay)
+// ============================================================================
+//
+// Recording routes the run's model traffic through a loopback proxy that
+// forwards to the real provider and captures every exchange, along with the
+// tool outputs the agent sent back. Replaying serves the same exchanges from
+// the cassette and answers recorded tool calls from tool interceptors, so no
+// network access, sandboxed process, or file write happens on playback.
+
+const CASSETTE_VERSION: u32 = 1;
+const DEFAULT_CASSETTE_UPSTREAM: &str = "https://api.openai.com/v1";
+const CASSETTE_REPLAY_API_KEY: &str = "cassette-replay";
+/// Request headers that describe the proxy hop rather than the upstream request.
+const CASSETTE_HOP_HEADERS: &[&str] = &["host", "content-length", "connection", "accept-encoding"];
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cassette {
+  version: u32,
+  interactions: Vec<CassetteInteraction>,
+  tool_outputs: Vec<CassetteToolOutput>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CassetteInteraction {
+  method: String,
+  path: String,
+  request: Option<JsonValue>,
+  status: u16,
+  content_type: Option<String>,
+  body: String,
+  /// Milliseconds since the recording started when the request arrived.
+  offset_ms: u64,
+  /// Milliseconds the upstream took to answer.
+  elapsed_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CassetteToolOutput {
+  call_id: String,
+  tool: String,
+  /// The `output` field of the recorded `function_call_output` / `custom_tool_call_output`.
+  output: JsonValue,
+  /// Milliseconds between the model requesting the call and its output being sent back.
+  elapsed_ms: u64,
+}
+
+fn millis_since(instant: std::time::Instant) -> u64 {
+  u64::try_from(instant.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Tool calls requested in a recorded Responses SSE body, as `(call_id, tool name)`.
+fn cassette_tool_calls(body: &str) -> Vec<(String, String)> {
+  body
+    .lines()
+    .filter_map(|line| line.strip_prefix("data:"))
+    .filter_map(|data| serde_json::from_str::<JsonValue>(data.trim()).ok())
+    .filter(|event| event.get("type").and_then(JsonValue::as_str) == Some("response.output_item.done"))
+    .filter_map(|event| {
+      let item = event.get("item")?;
+      let call_id = item
+        .get("call_id")
+        .or_else(|| item.get("id"))
+        .and_then(JsonValue::as_str)?;
+      let tool = match item.get("type").and_then(JsonValue::as_str)? {
+        "function_call" | "custom_tool_call" => item.get("name").and_then(JsonValue::as_str)?,
+        "local_shell_call" => "local_shell",
+        _ => return None,
+      };
+      Some((call_id.to_string(), tool.to_string()))
+    })
+    .collect()
+}
+
+struct CassetteRecorder {
+  upstream: String,
+  client: reqwest::blocking::Client,
+  started: std::time::Instant,
+  cassette: Cassette,
+  /// Calls requested by the model whose outputs have not been sent back yet.
+  pending_calls: HashMap<String, (String, std::time::Instant)>,
+}
+
+impl CassetteRecorder {
+  fn capture_tool_outputs(&mut self, request: &JsonValue) {
+    let Some(items) = request.get("input").and_then(JsonValue::as_array) else {
+      return;
+    };
+    for item in items {
+      let is_output = matches!(
+        item.get("type").and_then(JsonValue::as_str),
+        Some("function_call_output" | "custom_tool_call_output")
+      );
+      let call_id = item.get("call_id").and_then(JsonValue::as_str);
+      if let Some(call_id) = call_id.filter(|_| is_output)
+        && let Some((tool, requested_at)) = self.pending_calls.remove(call_id)
+      {
+        self.cassette.tool_outputs.push(CassetteToolOutput {
+          call_id: call_id.to_string(),
+          tool,
+          output: item.get("output").cloned().unwrap_or(JsonValue::Null),
+          elapsed_ms: millis_since(requested_at),
+        });
+      }
+    }
+  }
+
+  fn forward(&mut self, request: &mut tiny_http::Request) -> Result<CassetteInteraction, String> {
+    let method = request.method().as_str().to_string();
+    let path = request.url().to_string();
+    let mut body = String::new();
+    std::io::Read::read_to_string(request.as_reader(), &mut body)
+      .map_err(|e| format!("failed to read request: {e}"))?;
+    let request_json = serde_json::from_str::<JsonValue>(&body).ok();
+    if let Some(request_json) = request_json.as_ref() {
+      self.capture_tool_outputs(request_json);
+    }
+
+    let route = path.strip_prefix("/v1").unwrap_or(&path);
+    let url = format!("{}{route}", self.upstream.trim_end_matches('/'));
+    let method_value = reqwest::Method::from_bytes(method.as_bytes())
+      .map_err(|e| format!("unsupported method {method}: {e}"))?;
+    let mut upstream_request = self.client.request(method_value, url);
+    for header in request.headers() {
+      let name = header.field.as_str().as_str();
+      if !CASSETTE_HOP_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        upstream_request = upstream_request.header(name, header.value.as_str());
+      }
+    }
+
+    let offset_ms = millis_since(self.started);
+    let sent_at = std::time::Instant::now();
+    let response = upstream_request
+      .body(body)
+      .send()
+      .map_err(|e| format!("upstream request failed: {e}"))?;
+    let status = response.status().as_u16();
+    let content_type = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .map(str::to_string);
+    let response_body = response
+      .text()
+      .map_err(|e| format!("failed to read upstream response: {e}"))?;
+
+    let now = std::time::Instant::now();
+    for (call_id, tool) in cassette_tool_calls(&response_body) {
+      self.pending_calls.insert(call_id, (tool, now));
+    }
+    let interaction = CassetteInteraction {
+      method,
+      path,
+      request: request_json,
+      status,
+      content_type,
+      body: response_body,
+      offset_ms,
+      elapsed_ms: millis_since(sent_at),
+    };
+    self.cassette.interactions.push(interaction.clone());
+    Ok(interaction)
+  }
+}
+
+fn respond_cassette_interaction(request: tiny_http::Request, interaction: &CassetteInteraction) {
+  let mut response =
+    tiny_http::Response::from_string(interaction.body.clone()).with_status_code(interaction.status);
+  if let Some(header) = interaction
+    .content_type
+    .as_deref()
+    .and_then(|value| tiny_http::Header::from_bytes(&b"Content-Type"[..], value.as_bytes()).ok())
+  {
+    response = response.with_header(header);
+  }
+  let _ = request.respond(response);
+}
+
+fn respond_cassette_error(request: tiny_http::Request, status: u16, message: &str) {
+  let body = json!({ "error": { "message": message } }).to_string();
+  let _ = request.respond(tiny_http::Response::from_string(body).with_status_code(status));
+}
+
+/// Serve recorded exchanges in order, matching on method and path.
+fn replay_cassette_request(remaining: &Mutex<Vec<CassetteInteraction>>, mut request: tiny_http::Request) {
+  let mut drained = String::new();
+  let _ = std::io::Read::read_to_string(request.as_reader(), &mut drained);
+  let method = request.method().as_str().to_string();
+  let path = request.url().to_string();
+  let interaction = remaining.lock().ok().and_then(|mut remaining| {
+    let index = remaining
+      .iter()
+      .position(|interaction| interaction.method == method && interaction.path == path)?;
+    Some(remaining.remove(index))
+  });
+  match interaction {
+    Some(interaction) => respond_cassette_interaction(request, &interaction),
+    None => respond_cassette_error(
+      request,
+      500,
+      &format!("cassette has no recorded response left for {method} {path}"),
+    ),
+  }
+}
+
+/// Answers recorded tool calls from the cassette without running the real tool.
+struct CassetteToolInterceptor {
+  outputs: Arc<HashMap<String, CassetteToolOutput>>,
+}
+
+#[async_trait]
+impl ToolInterceptor for CassetteToolInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    _next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    let recorded = self.outputs.get(&invocation.call_id).ok_or_else(|| {
+      FunctionCallError::RespondToModel(format!(
+        "cassette has no recorded output for {} call {}",
+        invocation.tool_name, invocation.call_id
+      ))
+    })?;
+    let payload: codex_protocol::models::FunctionCallOutputPayload =
+      serde_json::from_value(recorded.output.clone()).map_err(|e| {
+        FunctionCallError::Fatal(format!("invalid recorded output for {}: {e}", recorded.call_id))
+      })?;
+    Ok(ToolOutput::Function {
+      body: payload.body,
+      success: payload.success,
+    })
+  }
+}
+
+fn cassette_tool_interceptors(cassette: &Cassette) -> Vec<ExternalInterceptorRegistration> {
+  let outputs: Arc<HashMap<String, CassetteToolOutput>> = Arc::new(
+    cassette
+      .tool_outputs
+      .iter()
+      .map(|output| (output.call_id.clone(), output.clone()))
+      .collect(),
+  );
+  let mut tools: Vec<&str> = cassette
+    .tool_outputs
+    .iter()
+    .map(|output| output.tool.as_str())
+    .collect();
+  tools.sort_unstable();
+  tools.dedup();
+  tools
+    .into_iter()
+    .map(|tool| ExternalInterceptorRegistration {
+      name: tool.to_string(),
+      handler: Arc::new(CassetteToolInterceptor {
+        outputs: Arc::clone(&outputs),
+      }),
+    })
+    .collect()
+}
+
+/// Loopback server backing one recording or replaying run.
+struct CassetteSession {
+  base_url: String,
+  server: Arc<tiny_http::Server>,
+  worker: Option<std::thread::JoinHandle<()>>,
+  recording: Option<(PathBuf, Arc<Mutex<CassetteRecorder>>)>,
+  interceptors: Vec<ExternalInterceptorRegistration>,
+}
+
+impl CassetteSession {
+  fn start(options: &InternalRunRequest) -> napi::Result<Option<Self>> {
+    if let Some(path) = options.replay.as_deref() {
+      return Self::start_replay(path).map(Some);
+    }
+    let Some(path) = options.record.clone() else {
+      return Ok(None);
+    };
+    let upstream = options
+      .base_url
+      .clone()
+      .or_else(|| std::env::var("OPENAI_BASE_URL").ok())
+      .unwrap_or_else(|| DEFAULT_CASSETTE_UPSTREAM.to_string());
+    Self::start_record(path, upstream).map(Some)
+  }
+
+  fn bind() -> napi::Result<(Arc<tiny_http::Server>, String)> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+      .map_err(|e| napi::Error::from_reason(format!("Failed to bind cassette server: {e}")))?;
+    let addr = listener
+      .local_addr()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to read cassette address: {e}")))?;
+    let server = tiny_http::Server::from_listener(listener, None)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to start cassette server: {e}")))?;
+    Ok((Arc::new(server), format!("http://{addr}/v1")))
+  }
+
+  fn start_replay(path: &Path) -> napi::Result<Self> {
+    let cassette = load_cassette(path)?;
+    let interceptors = cassette_tool_interceptors(&cassette);
+    let remaining = Mutex::new(cassette.interactions);
+    let (server, base_url) = Self::bind()?;
+    let worker_server = Arc::clone(&server);
+    let worker = std::thread::Builder::new()
+      .name("codex-cassette-replay".to_string())
+      .spawn(move || {
+        for request in worker_server.incoming_requests() {
+          replay_cassette_request(&remaining, request);
+        }
+      })
+      .map_err(|e| napi::Error::from_reason(format!("Failed to spawn cassette server: {e}")))?;
+    Ok(Self {
+      base_url,
+      server,
+      worker: Some(worker),
+      recording: None,
+      interceptors,
+    })
+  }
+
+  fn start_record(path: PathBuf, upstream: String) -> napi::Result<Self> {
+    let client = reqwest::blocking::Client::builder()
+      .timeout(None::<std::time::Duration>)
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to build cassette client: {e}")))?;
+    let recorder = Arc::new(Mutex::new(CassetteRecorder {
+      upstream,
+      client,
+      started: std::time::Instant::now(),
+      cassette: Cassette {
+        version: CASSETTE_VERSION,
+        ..Cassette::default()
+      },
+      pending_calls: HashMap::new(),
+    }));
+    let (server, base_url) = Self::bind()?;
+    let worker_server = Arc::clone(&server);
+    let worker_recorder = Arc::clone(&recorder);
+    let worker = std::thread::Builder::new()
+      .name("codex-cassette-record".to_string())
+      .spawn(move || {
+        for mut request in worker_server.incoming_requests() {
+          let forwarded = match worker_recorder.lock() {
+            Ok(mut recorder) => recorder.forward(&mut request),
+            Err(err) => Err(format!("cassette recorder poisoned: {err}")),
+          };
+          match forwarded {
+            Ok(interaction) => respond_cassette_interaction(request, &interaction),
+            Err(message) => respond_cassette_error(request, 502, &message),
+          }
+        }
+      })
+      .map_err(|e| napi::Error::from_reason(format!("Failed to spawn cassette server: {e}")))?;
+    Ok(Self {
+      base_url,
+      server,
+      worker: Some(worker),
+      recording: Some((path, recorder)),
+      interceptors: Vec::new(),
+    })
+  }
+
+  fn is_replay(&self) -> bool {
+    self.recording.is_none()
+  }
+
+  /// Stop serving and, when recording, write the cassette to disk.
+  fn finish(mut self) -> napi::Result<()> {
+    self.server.unblock();
+    if let Some(worker) = self.worker.take() {
+      let _ = worker.join();
+    }
+    let Some((path, recorder)) = self.recording.take() else {
+      return Ok(());
+    };
+    let recorder = recorder
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("cassette recorder poisoned: {e}")))?;
+    let encoded = serde_json::to_string_pretty(&recorder.cassette)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to encode cassette: {e}")))?;
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+      std::fs::create_dir_all(parent).map_err(|e| {
+        napi::Error::from_reason(format!("Failed to create {}: {e}", parent.display()))
+      })?;
+    }
+    std::fs::write(&path, encoded).map_err(|e| {
+      napi::Error::from_reason(format!("Failed to write cassette {}: {e}", path.display()))
+    })
+  }
+}
+
+impl Drop for CassetteSession {
+  fn drop(&mut self) {
+    self.server.unblock();
+  }
+}
+
+fn load_cassette(path: &Path) -> napi::Result<Cassette> {
+  let contents = std::fs::read_to_string(path).map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read cassette {}: {e}", path.display()))
+  })?;
+  let cassette: Cassette = serde_json::from_str(&contents).map_err(|e| {
+    napi::Error::from_reason(format!("Invalid cassette {}: {e}", path.display()))
+  })?;
+  if cassette.version != CASSETTE_VERSION {
+    return Err(napi::Error::from_reason(format!(
+      "Unsupported cassette version {} in {}",
+      cassette.version,
+      path.display()
+    )));
+  }
+  Ok(cassette)
+}
+
+#[cfg(test)]
+mod tests_cassette {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn extracts_tool_calls_from_recorded_stream() {
+    let body = sse(vec![
+      ev_function_call("call_1".to_string(), "shell".to_string(), "{}".to_string()),
+      ev_assistant_message("msg_1".to_string(), "done".to_string()),
+      ev_completed("resp_1".to_string()),
+    ]);
+    assert_eq!(
+      cassette_tool_calls(&body),
+      vec![("call_1".to_string(), "shell".to_string())]
+    );
+  }
+
+  #[test]
+  fn replay_serves_interactions_in_order_per_route() {
+    let interaction = |body: &str| CassetteInteraction {
+      method: "POST".to_string(),
+      path: "/v1/responses".to_string(),
+      request: None,
+      status: 200,
+      content_type: Some("text/event-stream".to_string()),
+      body: body.to_string(),
+      offset_ms: 0,
+      elapsed_ms: 0,
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cassette.json");
+    let cassette = Cassette {
+      version: CASSETTE_VERSION,
+      interactions: vec![interaction("first"), interaction("second")],
+      tool_outputs: Vec::new(),
+    };
+    std::fs::write(&path, serde_json::to_string(&cassette).unwrap()).unwrap();
+
+    let session = CassetteSession::start_replay(&path).unwrap();
+    assert!(session.is_replay());
+
+    let client = reqwest::blocking::Client::new();
+    let url = format!("{}/responses", session.base_url);
+    let bodies: Vec<String> = (0..3)
+      .map(|_| client.post(&url).body("{}").send().unwrap().text().unwrap())
+      .collect();
+    assert_eq!(bodies[0], "first");
+    assert_eq!(bodies[1], "second");
+    assert!(bodies[2].contains("no recorded response left"));
+    session.finish().unwrap();
+  }
+
+  #[test]
+  fn interceptor_returns_recorded_output() {
+    let cassette = Cassette {
+      version: CASSETTE_VERSION,
+      interactions: Vec::new(),
+      tool_outputs: vec![
+        CassetteToolOutput {
+          call_id: "call_1".to_string(),
+          tool: "shell".to_string(),
+          output: json!("README.md"),
+          elapsed_ms: 5,
+        },
+        CassetteToolOutput {
+          call_id: "call_2".to_string(),
+          tool: "shell".to_string(),
+          output: json!("src"),
+          elapsed_ms: 5,
+        },
+      ],
+    };
+    let interceptors = cassette_tool_interceptors(&cassette);
+    assert_eq!(
+      interceptors.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>(),
+      vec!["shell"]
+    );
+  }
+
+  #[test]
+  fn recorder_pairs_tool_outputs_with_requested_calls() {
+    let mut recorder = CassetteRecorder {
+      upstream: DEFAULT_CASSETTE_UPSTREAM.to_string(),
+      client: reqwest::blocking::Client::new(),
+      started: std::time::Instant::now(),
+      cassette: Cassette::default(),
+      pending_calls: HashMap::from([(
+        "call_1".to_string(),
+        ("shell".to_string(), std::time::Instant::now()),
+      )]),
+    };
+    recorder.capture_tool_outputs(&json!({
+      "input": [
+        { "type": "message", "role": "user", "content": [] },
+        { "type": "function_call_output", "call_id": "call_1", "output": "README.md" },
+        { "type": "function_call_output", "call_id": "call_unknown", "output": "ignored" },
+      ]
+    }));
+    assert_eq!(recorder.cassette.tool_outputs.len(), 1);
+    assert_eq!(recorder.cassette.tool_outputs[0].tool, "shell");
+    assert_eq!(recorder.cassette.tool_outputs[0].output, json!("README.md"));
+    assert!(recorder.pending_calls.is_empty());
+  }
+}
@@ -0,0 +1,313 @@
+// ============================================================================
+// Commit messages and PR descriptions (generateCommitMessage, generatePrDescription)
+// ============================================================================
+//
+// Every CLI built on the SDK ends up writing its own "describe my diff"
+// prompt. generateCommitMessage() describes the staged (or all uncommitted)
+// changes and generatePrDescription() the commits since a base ref's merge
+// base. Both read the diff natively, ask for a structured answer in one
+// ephemeral, tool-less turn and return it typed. Nothing is committed or
+// pushed.
+
+const DEFAULT_DESCRIPTION_DIFF_CHARS: u32 = 60_000;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct GenerateCommitMessageOptions {
+  /// Repository to describe; defaults to `config.workingDirectory`, then the process cwd.
+  pub cwd: Option<String>,
+  /// Describe only the staged changes (default true). When false, unstaged changes to tracked
+  /// files are included too.
+  pub staged: Option<bool>,
+  /// Model for the turn. Defaults to the configured model.
+  pub model: Option<String>,
+  /// Extra instructions, e.g. a commit message convention to follow.
+  pub instructions: Option<String>,
+  /// Diff characters sent to the model (default 60000); longer diffs are cut.
+  #[napi(js_name = "maxDiffChars")]
+  pub max_diff_chars: Option<u32>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedCommitMessage {
+  pub subject: String,
+  /// Empty when the subject says enough.
+  pub body: String,
+  /// `subject` and `body` joined the way `git commit -F` expects.
+  pub message: String,
+  /// Whether the diff was cut to `maxDiffChars` before it was sent.
+  #[napi(js_name = "diffTruncated")]
+  pub diff_truncated: bool,
+}
+
+#[napi(object)]
+pub struct GeneratePrDescriptionOptions {
+  /// Repository to describe; defaults to `config.workingDirectory`, then the process cwd.
+  pub cwd: Option<String>,
+  /// Branch the pull request targets, e.g. `origin/main`.
+  #[napi(js_name = "baseRef")]
+  pub base_ref: String,
+  /// Branch or commit to describe (default HEAD).
+  #[napi(js_name = "headRef")]
+  pub head_ref: Option<String>,
+  /// Model for the turn. Defaults to the configured model.
+  pub model: Option<String>,
+  /// Extra instructions, e.g. sections the description must have.
+  pub instructions: Option<String>,
+  /// Diff characters sent to the model (default 60000); longer diffs are cut.
+  #[napi(js_name = "maxDiffChars")]
+  pub max_diff_chars: Option<u32>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedPrDescription {
+  pub title: String,
+  /// Markdown body.
+  pub body: String,
+  /// Whether the diff was cut to `maxDiffChars` before it was sent.
+  #[napi(js_name = "diffTruncated")]
+  pub diff_truncated: bool,
+}
+
+/// `diff` cut to whole lines within `max_chars` characters, and whether it was cut.
+fn truncate_description_diff(diff: &str, max_chars: usize) -> (String, bool) {
+  let Some((cut, _)) = diff.char_indices().nth(max_chars) else {
+    return (diff.trim_end().to_string(), false);
+  };
+  let end = diff[..cut].rfind('\n').map_or(cut, |index| index + 1);
+  (
+    format!("{}\n[diff truncated]", diff[..end].trim_end()),
+    true,
+  )
+}
+
+fn with_description_instructions(rules: &str, instructions: Option<&str>) -> String {
+  match instructions.map(str::trim) {
+    Some(instructions) if !instructions.is_empty() => {
+      format!("{rules}\nAdditional instructions:\n{instructions}\n")
+    }
+    _ => rules.to_string(),
+  }
+}
+
+fn commit_message_prompt(diff: &str, instructions: Option<&str>) -> String {
+  let rules = with_description_instructions(
+    "Write a git commit message for the diff below.\n\
+     - subject: imperative mood, at most 72 characters, with no trailing period.\n\
+     - body: why the change was made and anything a reviewer should know, wrapped at 72 \
+     columns; empty when the subject says enough.\n\
+     Do not run any tools.\n",
+    instructions,
+  );
+  format!("{rules}\n<diff>\n{diff}\n</diff>")
+}
+
+fn pr_description_prompt(commits: &[String], diff: &str, instructions: Option<&str>) -> String {
+  let rules = with_description_instructions(
+    "Write a pull request title and description for the changes below.\n\
+     - title: at most 72 characters, summarizing the change.\n\
+     - body: Markdown. Open with what the change does and why, then list the notable \
+     changes.\n\
+     Do not run any tools.\n",
+    instructions,
+  );
+  let commits: String = commits
+    .iter()
+    .map(|subject| format!("- {subject}\n"))
+    .collect();
+  format!("{rules}\n<commits>\n{commits}</commits>\n\n<diff>\n{diff}\n</diff>")
+}
+
+fn titled_description_schema(title_field: &str) -> JsonValue {
+  json!({
+    "type": "object",
+    "properties": {
+      title_field: { "type": "string" },
+      "body": { "type": "string" }
+    },
+    "required": [title_field, "body"],
+    "additionalProperties": false
+  })
+}
+
+/// The `(title_field, body)` pair from the model's final message.
+fn parse_titled_description(message: &str, title_field: &str) -> napi::Result<(String, String)> {
+  let output: JsonValue = serde_json::from_str(message.trim())
+    .map_err(|e| napi::Error::from_reason(format!("Model returned invalid JSON: {e}")))?;
+  let field = |name: &str| {
+    output
+      .get(name)
+      .and_then(JsonValue::as_str)
+      .map(|value| value.trim().to_string())
+      .ok_or_else(|| napi::Error::from_reason(format!("Model output is missing `{name}`")))
+  };
+  let title = field(title_field)?;
+  if title.is_empty() {
+    return Err(napi::Error::from_reason(format!(
+      "Model returned an empty `{title_field}`"
+    )));
+  }
+  Ok((title, field("body")?))
+}
+
+/// Runs `prompt` as a tool-less turn constrained to `schema` and returns the final message.
+async fn run_description_turn(
+  prompt: String,
+  schema: JsonValue,
+  model: Option<String>,
+  cwd: PathBuf,
+  config: Option<ConversationConfigRequest>,
+) -> napi::Result<String> {
+  let mut run_options = config.unwrap_or_default().into_internal_request()?;
+  // Keeps project instructions (AGENTS.md) for the repository in view.
+  run_options.working_directory.get_or_insert(cwd);
+  run_options.model = model.or(run_options.model.take());
+  run_options.prompt = prompt;
+  make_summary_turn(&mut run_options);
+  run_options.output_schema = Some(schema);
+  tokio::task::spawn_blocking(move || run_summary_turn(run_options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))?
+}
+
+/// Write a commit message for the staged (or all uncommitted) changes.
+#[napi]
+pub async fn generate_commit_message(
+  options: Option<GenerateCommitMessageOptions>,
+) -> napi::Result<GeneratedCommitMessage> {
+  let options = options.unwrap_or_default();
+  let cwd = repository_dir(options.cwd.as_deref(), options.config.as_ref())?;
+  let staged = options.staged.unwrap_or(true);
+  let diff = git_uncommitted_diff(&cwd, staged)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read changes: {e}")))?;
+  if diff.trim().is_empty() {
+    let what = if staged { "staged changes" } else { "changes" };
+    return Err(napi::Error::from_reason(format!(
+      "No {what} to describe in {}",
+      cwd.display()
+    )));
+  }
+  let max_chars = options
+    .max_diff_chars
+    .unwrap_or(DEFAULT_DESCRIPTION_DIFF_CHARS)
+    .max(1) as usize;
+  let (diff, diff_truncated) = truncate_description_diff(&diff, max_chars);
+
+  let message = run_description_turn(
+    commit_message_prompt(&diff, options.instructions.as_deref()),
+    titled_description_schema("subject"),
+    options.model,
+    cwd,
+    options.config,
+  )
+  .await?;
+  let (subject, body) = parse_titled_description(&message, "subject")?;
+  let message = if body.is_empty() {
+    subject.clone()
+  } else {
+    format!("{subject}\n\n{body}")
+  };
+  Ok(GeneratedCommitMessage {
+    subject,
+    body,
+    message,
+    diff_truncated,
+  })
+}
+
+/// Write a pull request title and description for the commits since `baseRef`.
+#[napi]
+pub async fn generate_pr_description(
+  options: GeneratePrDescriptionOptions,
+) -> napi::Result<GeneratedPrDescription> {
+  let cwd = repository_dir(options.cwd.as_deref(), options.config.as_ref())?;
+  let base_ref = options.base_ref.trim();
+  let head_ref = options
+    .head_ref
+    .as_deref()
+    .map(str::trim)
+    .filter(|head_ref| !head_ref.is_empty())
+    .unwrap_or("HEAD");
+  if base_ref.is_empty() {
+    return Err(napi::Error::from_reason(
+      "generatePrDescription requires a baseRef",
+    ));
+  }
+  let diff = git_diff_since_merge_base(&cwd, base_ref, Some(head_ref))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to compute diff: {e}")))?;
+  if diff.trim().is_empty() {
+    return Err(napi::Error::from_reason(format!(
+      "'{head_ref}' has no changes since its merge base with '{base_ref}'"
+    )));
+  }
+  let commits = git_commit_subjects(&cwd, base_ref, head_ref)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to list commits: {e}")))?;
+  let max_chars = options
+    .max_diff_chars
+    .unwrap_or(DEFAULT_DESCRIPTION_DIFF_CHARS)
+    .max(1) as usize;
+  let (diff, diff_truncated) = truncate_description_diff(&diff, max_chars);
+
+  let message = run_description_turn(
+    pr_description_prompt(&commits, &diff, options.instructions.as_deref()),
+    titled_description_schema("title"),
+    options.model,
+    cwd,
+    options.config,
+  )
+  .await?;
+  let (title, body) = parse_titled_description(&message, "title")?;
+  Ok(GeneratedPrDescription {
+    title,
+    body,
+    diff_truncated,
+  })
+}
+
+#[cfg(test)]
+mod tests_change_descriptions {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn prompts_truncate_the_diff_and_parse_structured_output() {
+    let diff = "--- a/lib.rs\n+++ b/lib.rs\n+fn a() {}\n+fn b() {}\n";
+    assert_eq!(
+      truncate_description_diff(diff, 1000),
+      (diff.trim_end().to_string(), false)
+    );
+    assert_eq!(
+      truncate_description_diff(diff, 30),
+      (
+        "--- a/lib.rs\n+++ b/lib.rs\n[diff truncated]".to_string(),
+        true
+      )
+    );
+
+    let prompt = commit_message_prompt("+fn a() {}", Some("Use Conventional Commits."));
+    assert!(prompt.contains("Additional instructions:\nUse Conventional Commits.\n"));
+    assert!(prompt.ends_with("<diff>\n+fn a() {}\n</diff>"));
+    let prompt = pr_description_prompt(
+      &["Add a".to_string(), "Add b".to_string()],
+      "+fn b() {}",
+      None,
+    );
+    assert!(!prompt.contains("Additional instructions"));
+    assert!(prompt.contains("<commits>\n- Add a\n- Add b\n</commits>"));
+
+    assert_eq!(
+      parse_titled_description(
+        r#"{"subject":" Add b ","body":"Needed by the parser."}"#,
+        "subject"
+      )
+      .unwrap(),
+      ("Add b".to_string(), "Needed by the parser.".to_string())
+    );
+    assert!(parse_titled_description(r#"{"subject":"","body":""}"#, "subject").is_err());
+    assert!(parse_titled_description(r#"{"title":"Add b"}"#, "title").is_err());
+  }
+}
@@ -1,60 +0,0 @@
-struct TempSchemaFile {
-  path: PathBuf,
-  _guard: tempfile::TempPath,
-}
-
-fn prepare_schema(schema: Option<JsonValue>) -> napi::Result<Option<TempSchemaFile>> {
-  if let Some(schema_value) = schema {
-    let mut file = NamedTempFile::new().map_err(|e| napi::Error::from_reason(e.to_string()))?;
-    serde_json::to_writer(&mut file, &schema_value)
-      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-    let path = file.path().to_path_buf();
-    let temp_path = file.into_temp_path();
-    Ok(Some(TempSchemaFile {
-      path,
-      _guard: temp_path,
-    }))
-  } else {
-    Ok(None)
-  }
-}
-
-struct EnvOverride {
-  key: &'static str,
-  previous: Option<String>,
-}
-
-struct EnvOverrides {
-  entries: Vec<EnvOverride>,
-}
-
-impl EnvOverrides {
-  fn apply(pairs: Vec<(&'static str, Option<String>, bool)>) -> Self {
-    let mut entries = Vec::new();
-    for (key, value, force) in pairs {
-      if !force && value.is_none() {
-        continue;
-      }
-      let previous = std::env::var(key).ok();
-      match value {
-        Some(val) => unsafe { std::env::set_var(key, val) },
-        None if force => unsafe { std::env::remove_var(key) },
-        None => {}
-      }
-      entries.push(EnvOverride { key, previous });
-    }
-    Self { entries }
-  }
-}
-
-impl Drop for EnvOverrides {
-  fn drop(&mut self) {
-    for entry in self.entries.iter().rev() {
-      if let Some(prev) = &entry.previous {
-        unsafe { std::env::set_var(entry.key, prev) };
-      } else {
-        unsafe { std::env::remove_var(entry.key) };
-      }
-    }
-  }
-}
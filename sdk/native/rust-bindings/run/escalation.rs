@@ -0,0 +1,210 @@
+// ============================================================================
+// Sandbox escalations (registerEscalationHandler, escalationHandler)
+// ============================================================================
+//
+// Under `approvalMode: "on-failure"`, a command the sandbox denies can run
+// again outside it once somebody approves. The TUI asks the user; headless
+// runs used to have nobody to ask. A run that names a handler registered with
+// registerEscalationHandler() sends each escalation to it: the failed
+// command, why approval is needed and what the command printed in the
+// sandbox. Returning true grants a one-time retry. Runs without a handler deny
+// every escalation.
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsEscalationRequest {
+  /// `command` for a command to retry outside the sandbox, `patch` for a patch that writes
+  /// outside the writable roots.
+  #[napi(js_name = "type")]
+  pub type_: String,
+  #[napi(js_name = "threadId")]
+  pub thread_id: String,
+  #[napi(js_name = "callId")]
+  pub call_id: String,
+  /// Command awaiting approval; empty for patches.
+  pub command: Vec<String>,
+  /// Working directory of the command, or the root a patch asks to write under.
+  pub cwd: Option<String>,
+  /// Files a patch touches; empty for commands.
+  pub files: Vec<String>,
+  /// Why approval is needed, e.g. `command failed; retry without sandbox?`.
+  pub reason: Option<String>,
+  /// The end of what the command printed in the sandbox before it was denied.
+  #[napi(js_name = "sandboxError")]
+  pub sandbox_error: Option<String>,
+}
+
+type EscalationHandlerReturn = napi::Either<napi::bindgen_prelude::Promise<bool>, bool>;
+type EscalationCallback = ThreadsafeFunction<
+  JsEscalationRequest,
+  EscalationHandlerReturn,
+  JsEscalationRequest,
+  Status,
+  true,
+>;
+
+fn registered_escalation_handlers() -> &'static Mutex<HashMap<String, Arc<EscalationCallback>>> {
+  static HANDLERS: OnceLock<Mutex<HashMap<String, Arc<EscalationCallback>>>> = OnceLock::new();
+  HANDLERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register a handler for sandbox escalations. Pass the returned id as a run's
+/// `escalationHandler`.
+#[napi]
+pub fn register_escalation_handler(
+  env: Env,
+  #[napi(ts_arg_type = "(request: JsEscalationRequest) => boolean | Promise<boolean>")]
+  handler: Function<JsEscalationRequest, EscalationHandlerReturn>,
+) -> napi::Result<String> {
+  let mut tsfn = handler
+    .build_threadsafe_function::<JsEscalationRequest>()
+    .callee_handled::<true>()
+    .build()?;
+  #[allow(deprecated)]
+  let _ = tsfn.unref(&env);
+
+  let id = format!("escalation_{}", Uuid::new_v4().simple());
+  registered_escalation_handlers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("escalation handlers mutex poisoned: {e}")))?
+    .insert(id.clone(), Arc::new(tsfn));
+  Ok(id)
+}
+
+/// Drop a handler from `registerEscalationHandler`. Returns false when the id is unknown.
+#[napi]
+pub fn unregister_escalation_handler(id: String) -> napi::Result<bool> {
+  Ok(
+    registered_escalation_handlers()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("escalation handlers mutex poisoned: {e}")))?
+      .remove(&id)
+      .is_some(),
+  )
+}
+
+fn js_escalation_request(request: codex_exec::EscalationRequest) -> JsEscalationRequest {
+  let type_ = match request.kind {
+    codex_exec::EscalationKind::Command => "command",
+    codex_exec::EscalationKind::Patch => "patch",
+  };
+  JsEscalationRequest {
+    type_: type_.to_string(),
+    thread_id: request.thread_id,
+    call_id: request.call_id,
+    command: request.command,
+    cwd: request.cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+    files: request
+      .files
+      .iter()
+      .map(|file| file.to_string_lossy().into_owned())
+      .collect(),
+    reason: request.reason,
+    sandbox_error: request.sandbox_output,
+  }
+}
+
+async fn decide_escalation(
+  callback: &EscalationCallback,
+  request: JsEscalationRequest,
+) -> napi::Result<bool> {
+  match callback.call_async(Ok(request)).await? {
+    napi::Either::A(promise) => promise.await,
+    napi::Either::B(approved) => Ok(approved),
+  }
+}
+
+/// The escalation handler `options` names, adapted for codex-exec.
+fn run_escalation_handler(
+  options: &InternalRunRequest,
+) -> napi::Result<Option<codex_exec::EscalationHandler>> {
+  let Some(id) = options.escalation_handler.as_deref() else {
+    return Ok(None);
+  };
+  let callback = registered_escalation_handlers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("escalation handlers mutex poisoned: {e}")))?
+    .get(id)
+    .cloned()
+    .ok_or_else(|| {
+      napi::Error::from_reason(format!(
+        "Unknown escalation handler `{id}`; register it with registerEscalationHandler"
+      ))
+    })?;
+  let handler: codex_exec::EscalationHandler = Arc::new(move |request| {
+    let callback = Arc::clone(&callback);
+    Box::pin(async move {
+      let request = js_escalation_request(request);
+      let thread_id = request.thread_id.clone();
+      set_thread_pending_approval(&thread_id, Some(&request.type_));
+      let approved = match decide_escalation(&callback, request.clone()).await {
+        Ok(approved) => approved,
+        Err(err) => {
+          eprintln!("Escalation handler failed; denying: {err}");
+          false
+        }
+      };
+      set_thread_pending_approval(&thread_id, None);
+      audit_record(
+        "escalation",
+        Some(&thread_id),
+        json!({
+          "call_id": request.call_id,
+          "type": request.type_,
+          "command": request.command,
+          "files": request.files,
+          "approved": approved,
+        }),
+      );
+      if approved {
+        codex_core::protocol::ReviewDecision::Approved
+      } else {
+        codex_core::protocol::ReviewDecision::Denied
+      }
+    }) as codex_exec::EscalationFuture
+  });
+  Ok(Some(handler))
+}
+
+#[cfg(test)]
+mod tests_escalation {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn escalation_requests_carry_the_command_and_sandbox_output() {
+    let request = js_escalation_request(codex_exec::EscalationRequest {
+      kind: codex_exec::EscalationKind::Command,
+      thread_id: "thread-1".to_string(),
+      call_id: "call-1".to_string(),
+      command: vec!["touch".to_string(), "/etc/hosts".to_string()],
+      cwd: Some(PathBuf::from("/repo")),
+      files: Vec::new(),
+      reason: Some("command failed; retry without sandbox?".to_string()),
+      sandbox_output: Some("touch: /etc/hosts: Operation not permitted".to_string()),
+    });
+    assert_eq!(
+      request,
+      JsEscalationRequest {
+        type_: "command".to_string(),
+        thread_id: "thread-1".to_string(),
+        call_id: "call-1".to_string(),
+        command: vec!["touch".to_string(), "/etc/hosts".to_string()],
+        cwd: Some("/repo".to_string()),
+        files: Vec::new(),
+        reason: Some("command failed; retry without sandbox?".to_string()),
+        sandbox_error: Some("touch: /etc/hosts: Operation not permitted".to_string()),
+      }
+    );
+
+    let mut options = ConversationConfigRequest::default()
+      .into_internal_request()
+      .unwrap();
+    assert!(run_escalation_handler(&options).unwrap().is_none());
+    options.escalation_handler = Some("escalation_missing".to_string());
+    assert_eq!(
+      run_escalation_handler(&options).err().unwrap().reason,
+      "Unknown escalation handler `escalation_missing`; register it with registerEscalationHandler"
+    );
+  }
+}
@@ -0,0 +1,141 @@
+// ============================================================================
+// Event delivery (RunRequest.eventDelivery)
+// ============================================================================
+//
+// runThreadStream() hands each event to JS with a non-blocking threadsafe
+// call, so a busy JS thread lets events pile up without limit. With
+// `eventDelivery` set, at most `capacity` events wait for the JS callback at
+// once; past that the run either blocks until the callback catches up or
+// drops the event. Events dropped during a turn are reported as
+// `dropped_events` on its `turn.completed` event, which is never dropped.
+
+const DEFAULT_EVENT_DELIVERY_CAPACITY: u32 = 256;
+
+#[napi(object)]
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDeliveryOptions {
+  /// Events waiting for the JS callback before `overflow` applies (default 256).
+  pub capacity: Option<u32>,
+  /// `block` (default) pauses the run until the callback catches up; `drop` discards the event.
+  pub overflow: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EventOverflow {
+  Block,
+  Drop,
+}
+
+struct EventDeliveryQueue {
+  capacity: usize,
+  overflow: EventOverflow,
+  in_flight: Mutex<usize>,
+  drained: std::sync::Condvar,
+  dropped: Mutex<u32>,
+}
+
+impl EventDeliveryQueue {
+  fn new(options: EventDeliveryOptions) -> napi::Result<Self> {
+    let capacity = options.capacity.unwrap_or(DEFAULT_EVENT_DELIVERY_CAPACITY);
+    if capacity == 0 {
+      return Err(napi::Error::from_reason(
+        "eventDelivery.capacity must be at least 1",
+      ));
+    }
+    let overflow = match options.overflow.as_deref() {
+      None | Some("block") => EventOverflow::Block,
+      Some("drop") => EventOverflow::Drop,
+      Some(other) => {
+        return Err(napi::Error::from_reason(format!(
+          "Invalid eventDelivery.overflow {other:?}; expected \"block\" or \"drop\""
+        )));
+      }
+    };
+    Ok(Self {
+      capacity: capacity as usize,
+      overflow,
+      in_flight: Mutex::new(0),
+      drained: std::sync::Condvar::new(),
+      dropped: Mutex::new(0),
+    })
+  }
+
+  /// Reserve a slot for `event`. Returns false, counting the drop, when it should be discarded.
+  fn acquire(&self, event: &ExecThreadEvent) -> bool {
+    let Ok(mut in_flight) = self.in_flight.lock() else {
+      return true;
+    };
+    let must_deliver = matches!(event, ExecThreadEvent::TurnCompleted(_));
+    if *in_flight >= self.capacity && self.overflow == EventOverflow::Drop && !must_deliver {
+      drop(in_flight);
+      self.record_drop();
+      return false;
+    }
+    while *in_flight >= self.capacity {
+      in_flight = match self.drained.wait(in_flight) {
+        Ok(guard) => guard,
+        Err(_) => return true,
+      };
+    }
+    *in_flight += 1;
+    true
+  }
+
+  /// Free a slot once the JS callback has run (or the call was refused).
+  fn release(&self) {
+    if let Ok(mut in_flight) = self.in_flight.lock() {
+      *in_flight = in_flight.saturating_sub(1);
+    }
+    self.drained.notify_one();
+  }
+
+  fn record_drop(&self) {
+    if let Ok(mut dropped) = self.dropped.lock() {
+      *dropped += 1;
+    }
+  }
+
+  /// Events dropped since the last call.
+  fn take_dropped(&self) -> u32 {
+    self
+      .dropped
+      .lock()
+      .map(|mut dropped| std::mem::take(&mut *dropped))
+      .unwrap_or(0)
+  }
+}
+
+#[cfg(test)]
+mod tests_event_delivery {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn drop_overflow_counts_discarded_events_but_keeps_turn_completion() {
+    let queue = EventDeliveryQueue::new(EventDeliveryOptions {
+      capacity: Some(1),
+      overflow: Some("drop".to_string()),
+    })
+    .unwrap();
+    let started = ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent {});
+    assert!(queue.acquire(&started));
+    assert!(!queue.acquire(&started));
+    assert!(!queue.acquire(&started));
+    queue.release();
+    assert!(queue.acquire(&started));
+    assert_eq!(queue.take_dropped(), 2);
+    assert_eq!(queue.take_dropped(), 0);
+
+    let err = EventDeliveryQueue::new(EventDeliveryOptions {
+      capacity: None,
+      overflow: Some("wait".to_string()),
+    })
+    .err()
+    .unwrap();
+    assert_eq!(
+      err.reason,
+      "Invalid eventDelivery.overflow \"wait\"; expected \"block\" or \"drop\""
+    );
+  }
+}
@@ -0,0 +1,147 @@
+// ============================================================================
+// Event sequencing
+// ============================================================================
+//
+// Every serialized ThreadEvent carries a per-thread `sequence` (monotonic,
+// gap-free within a process, continued across runs of the same thread) and an
+// `event_id` of the form `<thread_id>:<sequence>`. The next sequence for each
+// thread is persisted under `$CODEX_HOME/native/event-sequences/` so resumed
+// threads never reuse an id.
+
+const UNSTARTED_THREAD_EVENT_PREFIX: &str = "pending";
+
+struct ThreadEventSequencer {
+  thread_id: Option<String>,
+  next_sequence: u64,
+  persist: bool,
+}
+
+impl ThreadEventSequencer {
+  fn new(thread_id: Option<String>) -> Self {
+    let next_sequence = thread_id
+      .as_deref()
+      .map(load_thread_event_sequence)
+      .unwrap_or(0);
+    Self {
+      thread_id,
+      next_sequence,
+      persist: true,
+    }
+  }
+
+  /// A sequencer that starts at zero and never touches disk (used by replay).
+  fn ephemeral() -> Self {
+    Self {
+      thread_id: None,
+      next_sequence: 0,
+      persist: false,
+    }
+  }
+
+  fn stamp(&mut self, event: &ExecThreadEvent, mut value: JsonValue) -> JsonValue {
+    if let ExecThreadEvent::ThreadStarted(started) = event
+      && self.thread_id.as_deref() != Some(started.thread_id.as_str())
+    {
+      self.thread_id = Some(started.thread_id.clone());
+      if self.persist {
+        self.next_sequence = self
+          .next_sequence
+          .max(load_thread_event_sequence(&started.thread_id));
+      }
+    }
+    let JsonValue::Object(map) = &mut value else {
+      return value;
+    };
+
+    let sequence = self.next_sequence;
+    self.next_sequence += 1;
+    let prefix = self
+      .thread_id
+      .as_deref()
+      .unwrap_or(UNSTARTED_THREAD_EVENT_PREFIX);
+    map.insert("sequence".to_string(), JsonValue::from(sequence));
+    map.insert(
+      "event_id".to_string(),
+      JsonValue::String(format!("{prefix}:{sequence}")),
+    );
+    value
+  }
+}
+
+impl Drop for ThreadEventSequencer {
+  fn drop(&mut self) {
+    if self.persist
+      && let Some(thread_id) = self.thread_id.as_deref()
+    {
+      store_thread_event_sequence(thread_id, self.next_sequence);
+    }
+  }
+}
+
+fn thread_event_sequence_path(thread_id: &str) -> Option<PathBuf> {
+  if thread_id.is_empty()
+    || !thread_id
+      .chars()
+      .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+  {
+    return None;
+  }
+  let codex_home = find_codex_home().ok()?;
+  Some(
+    codex_home
+      .join("native")
+      .join("event-sequences")
+      .join(thread_id),
+  )
+}
+
+fn load_thread_event_sequence(thread_id: &str) -> u64 {
+  thread_event_sequence_path(thread_id)
+    .and_then(|path| std::fs::read_to_string(path).ok())
+    .and_then(|text| text.trim().parse().ok())
+    .unwrap_or(0)
+}
+
+fn store_thread_event_sequence(thread_id: &str, next_sequence: u64) {
+  let Some(path) = thread_event_sequence_path(thread_id) else {
+    return;
+  };
+  if let Some(parent) = path.parent()
+    && std::fs::create_dir_all(parent).is_err()
+  {
+    return;
+  }
+  let _ = std::fs::write(path, next_sequence.to_string());
+}
+
+#[cfg(test)]
+mod tests_event_sequence {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn stamps_monotonic_sequence_and_thread_scoped_ids() {
+    let mut sequencer = ThreadEventSequencer::ephemeral();
+    let started = ExecThreadEvent::ThreadStarted(codex_exec::exec_events::ThreadStartedEvent {
+      thread_id: "thread-a".to_string(),
+      ..Default::default()
+    });
+    let turn = ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent::default());
+
+    let first = sequencer.stamp(&started, event_to_json(&started).unwrap());
+    let second = sequencer.stamp(&turn, event_to_json(&turn).unwrap());
+
+    assert_eq!(first["sequence"], 0);
+    assert_eq!(first["event_id"], "thread-a:0");
+    assert_eq!(second["sequence"], 1);
+    assert_eq!(second["event_id"], "thread-a:1");
+  }
+
+  #[test]
+  fn null_events_are_not_sequenced() {
+    let mut sequencer = ThreadEventSequencer::ephemeral();
+    let raw = ExecThreadEvent::Raw(codex_exec::exec_events::RawEvent { raw: json!({}) });
+    assert_eq!(sequencer.stamp(&raw, JsonValue::Null), JsonValue::Null);
+    assert_eq!(sequencer.next_sequence, 0);
+  }
+}
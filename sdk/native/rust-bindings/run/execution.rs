@@ -1,29 +1,98 @@
-fn run_internal_sync<F>(options: InternalRunRequest, handler: F) -> napi::Result<()>
+thread_local! {
+  /// Stops the runs this thread starts when cancelled. Set with `with_run_cancellation` by hosts
+  /// whose callers can go away mid-run, like the gRPC server.
+  static RUN_CANCELLATION: std::cell::RefCell<Option<CancellationToken>> =
+    const { std::cell::RefCell::new(None) };
+}
+
+/// Call `f`, stopping every run it starts on this thread once `token` is cancelled.
+fn with_run_cancellation<T>(token: CancellationToken, f: impl FnOnce() -> T) -> T {
+  let previous = RUN_CANCELLATION.with(|slot| slot.replace(Some(token)));
+  let result = f();
+  RUN_CANCELLATION.with(|slot| *slot.borrow_mut() = previous);
+  result
+}
+
+/// `run` to completion, or a "Run cancelled" error as soon as `cancellation` fires. Dropping the
+/// run stops its turn; the run's runtime then shuts down whatever it had spawned.
+async fn run_until_cancelled(
+  cancellation: Option<CancellationToken>,
+  run: impl Future<Output = codex_native_core::Result<()>>,
+) -> napi::Result<()> {
+  let result = match cancellation {
+    Some(token) => tokio::select! {
+      result = run => result,
+      () = token.cancelled() => return Err(napi::Error::from_reason("Run cancelled")),
+    },
+    None => run.await,
+  };
+  result.map_err(|err| napi::Error::from_reason(err.to_string()))
+}
+
+/// Cancel `cancel` once every receiver of `tx` is gone, as when a streaming client hangs up.
+/// Cancelling `cancel` first releases this clone of `tx`, so the stream can end.
+fn cancel_when_closed<T: Send + 'static>(
+  tx: tokio::sync::mpsc::UnboundedSender<T>,
+  cancel: CancellationToken,
+) {
+  tokio::spawn(async move {
+    tokio::select! {
+      () = tx.closed() => cancel.cancel(),
+      () = cancel.cancelled() => {}
+    }
+  });
+}
+
+fn run_internal_sync<F>(mut options: InternalRunRequest, handler: F) -> napi::Result<()>
 where
   F: FnMut(ExecThreadEvent) + Send + 'static,
 {
-  ensure_apply_patch_aliases()?;
+  let runtime = tokio::runtime::Runtime::new()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+  if has_hooks(HookEvent::PreTurn) {
+    let prompt = std::mem::take(&mut options.prompt);
+    options.prompt = runtime.block_on(run_pre_turn_hooks(options.thread_id.clone(), prompt))?;
+  }
+  if has_guardrails(GuardrailDirection::Input)
+    && let Some(reason) = runtime.block_on(guard_run_input(&mut options))
+  {
+    let mut handler = handler;
+    handler(ExecThreadEvent::TurnFailed(codex_exec::exec_events::TurnFailedEvent {
+      error: codex_exec::exec_events::ThreadErrorEvent {
+        message: guardrail_block_message(&reason),
+      },
+    }));
+    return Ok(());
+  }
+  if options.memory.is_some() {
+    runtime.block_on(inject_project_memories(&mut options))?;
+  }
   // Check for pending plan updates and inject them as early events
-  let pending_plan = if let Some(thread_id) = &options.thread_id {
-    let mut updates = pending_plan_updates()
-      .lock()
-      .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?;
-    updates.remove(thread_id)
-  } else {
-    None
+  let thread_plans = thread_plans_path()?;
+  let pending_plan = match &options.thread_id {
+    Some(thread_id) => take_pending_plan(&thread_plans, thread_id)?,
+    None => None,
   };
 
-  let handler_arc: ThreadEventHandler = Arc::new(Mutex::new(Box::new(handler)));
-  let handler_error: Arc<Mutex<Option<napi::Error>>> = Arc::new(Mutex::new(None));
-
   let initial_thread_id = options.thread_id.clone();
   let thread_id_slot = Arc::new(Mutex::new(initial_thread_id.clone()));
 
+  // Every event, including those tools emit mid-turn, also goes to event bus clients.
+  let bus_thread_id = Arc::clone(&thread_id_slot);
+  let mut handler = handler;
+  let handler_arc: ThreadEventHandler = Arc::new(Mutex::new(Box::new(move |event: ExecThreadEvent| {
+    note_reverie_corpus_event(&event);
+    publish_bus_event(bus_thread_id.lock().ok().and_then(|slot| slot.clone()), &event);
+    handler(event);
+  })));
+  let handler_error: Arc<Mutex<Option<napi::Error>>> = Arc::new(Mutex::new(None));
+
   if let Some(id) = initial_thread_id {
     register_thread_handler(&id, &handler_arc);
   }
 
   if let Some(plan_args) = pending_plan {
+    let plan_updated = codex_exec::exec_events::PlanUpdatedEvent::from(&plan_args);
     let todo_items: Vec<codex_exec::exec_events::TodoItem> = plan_args
       .plan
       .into_iter()
@@ -50,7 +119,13 @@ where
     let plan_event = ExecThreadEvent::ItemCompleted(codex_exec::exec_events::ItemCompletedEvent {
       item: thread_item,
     });
-    if let Err(err) = dispatch_thread_event(&handler_arc, plan_event) {
+    let plan_updated = ExecThreadEvent::PlanUpdated(plan_updated);
+    if let Some(thread_id) = options.thread_id.as_deref() {
+      record_thread_plan(&thread_plans, thread_id, &plan_updated);
+    }
+    if let Err(err) = dispatch_thread_event(&handler_arc, plan_event)
+      .and_then(|()| dispatch_thread_event(&handler_arc, plan_updated))
+    {
       cleanup_thread_handler(&thread_id_slot);
       return Err(err);
     }
@@ -58,16 +133,10 @@ where
 
   let schema_file = prepare_schema(options.output_schema.clone())?;
   let schema_path = schema_file.as_ref().map(|file| file.path.clone());
-  let cli = build_cli(&options, schema_path, false);
+  let mut run_journal = RunJournal::start(&options, schema_path.iter().cloned().collect());
+  let mut cli = build_cli(&options, schema_path, false);
 
-  let pending_tools = {
-    let guard = registered_native_tools()
-      .lock()
-      .map_err(|e| napi::Error::from_reason(format!("tools mutex poisoned: {e}")))?;
-    guard.clone()
-  };
-  set_pending_external_tools(pending_tools);
-  let pending_interceptors = {
+  let registered_interceptors = {
     let guard = registered_native_interceptors()
       .lock()
       .map_err(|e| napi::Error::from_reason(format!("interceptors mutex poisoned: {e}")))?;
@@ -79,70 +148,167 @@ where
       })
       .collect::<Vec<_>>()
   };
-  set_pending_external_interceptors(pending_interceptors);
-
-  let mut env_pairs: Vec<(&'static str, Option<String>, bool)> = Vec::new();
-  if std::env::var(ORIGINATOR_ENV).is_err() {
-    env_pairs.push((ORIGINATOR_ENV, Some(NATIVE_ORIGINATOR.to_string()), true));
-  }
-  if let Some(base_url) = options.base_url.clone() {
-    env_pairs.push(("OPENAI_BASE_URL", Some(base_url), true));
-  }
-  if let Some(api_key) = options.api_key.clone() {
-    env_pairs.push(("CODEX_API_KEY", Some(api_key), true));
-  }
-  if let Some(tool_choice) = options.tool_choice.clone() {
-    let encoded = serde_json::to_string(&tool_choice)
-      .map_err(|e| napi::Error::from_reason(format!("Failed to encode toolChoice: {e}")))?;
-    env_pairs.push(("CODEX_TOOL_CHOICE", Some(encoded), true));
-  } else {
-    env_pairs.push(("CODEX_TOOL_CHOICE", None, true));
-  }
-
-  let linux_sandbox_path = if let Some(path) = options.linux_sandbox_path.clone() {
-    Some(path)
-  } else if let Ok(path) = std::env::var("CODEX_LINUX_SANDBOX_EXE") {
-    Some(PathBuf::from(path))
-  } else {
-    default_linux_sandbox_path()?
+  // Interceptors wrap each call in this order, outermost first: tool-call hooks, redaction, the
+  // audit log, the file access ledger, the result cache, the patch policy, patch verification,
+  // file skeletons, JS interceptors, then the cassette.
+  // Hooks see every call, so patches they rewrite are still checked, and redaction just inside
+  // them means hooks and the model only ever see masked output. Cache hits are audited and still
+  // count as file reads, but skip everything inside the cache, approvals included. The patch
+  // policy comes before verification, JS interceptors and approvals, so none of them sees a
+  // denied patch and only allowed patches are verified.
+  let redactor = run_secret_redactor(options.redaction.as_ref())?.map(Arc::new);
+  let patch_policy_interceptors = patch_path_policy_interceptors(&options)?;
+  let verification_interceptors = patch_verification_interceptors(&options, redactor.as_ref());
+  let file_skeleton_interceptors = file_skeleton_interceptors(&options)?;
+  let tool_cache_interceptors = tool_result_cache_interceptors(&options, redactor.as_ref())?;
+  let cassette = CassetteSession::start(&options)?;
+  let cassette_interceptors = cassette
+    .as_ref()
+    .map(|cassette| cassette.interceptors.clone())
+    .unwrap_or_default();
+  let pending_interceptors = hook_tool_interceptors()
+    .into_iter()
+    .chain(secret_redaction_interceptors(redactor.as_ref()))
+    .chain(audit_tool_interceptors(redactor.as_ref()))
+    .chain(file_access_interceptors())
+    .chain(tool_cache_interceptors)
+    .chain(patch_policy_interceptors)
+    .chain(verification_interceptors)
+    .chain(file_skeleton_interceptors)
+    .chain(registered_interceptors)
+    .chain(cassette_interceptors)
+    .collect();
+  let layers = ExecLayers {
+    external_tools: run_external_tools()?,
+    tool_filter: options.tool_filter.clone(),
+    tool_limits: run_tool_limits(&options)?,
+    interceptors: pending_interceptors,
+    escalation_handler: run_escalation_handler(&options)?,
   };
 
-  if let Some(path) = linux_sandbox_path.as_ref() {
-    env_pairs.push((
-      "CODEX_LINUX_SANDBOX_EXE",
-      Some(path.to_string_lossy().to_string()),
-      false,
-    ));
+  ensure_native_originator();
+  let mut overrides = RunOverrides::resolve(&options)?;
+  if let Some(cassette) = cassette.as_ref() {
+    overrides.base_url = Some(cassette.base_url.clone());
+    if cassette.is_replay() {
+      overrides
+        .api_key
+        .get_or_insert_with(|| CASSETTE_REPLAY_API_KEY.to_string());
+    }
   }
-
-  let _env_guard = EnvOverrides::apply(env_pairs);
+  overrides.apply_to_cli(&mut cli);
+  let linux_sandbox_path = overrides.linux_sandbox_path;
 
   let handler_for_callback = Arc::clone(&handler_arc);
   let handler_error_for_callback = Arc::clone(&handler_error);
   let thread_id_for_callback = Arc::clone(&thread_id_slot);
+  let final_response: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+  let final_response_for_callback = Arc::clone(&final_response);
+  let redactor_for_callback = redactor.clone();
 
-  let runtime = tokio::runtime::Runtime::new()
-    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
-
-  runtime.block_on(async {
-    run_with_thread_event_callback(cli, linux_sandbox_path, move |event| {
-      if let ExecThreadEvent::ThreadStarted(ev) = &event {
-        if let Ok(mut slot) = thread_id_for_callback.lock() {
-          *slot = Some(ev.thread_id.clone());
-        }
-        register_thread_handler(&ev.thread_id, &handler_for_callback);
+  let cancellation = RUN_CANCELLATION.with(|slot| slot.borrow().clone());
+  let run = run_exec(cli, linux_sandbox_path, layers, move |event| {
+    // Guard assistant messages before anything else observes them.
+    let event = guard_output_event(
+      event,
+      thread_id_for_callback.lock().ok().and_then(|slot| slot.clone()),
+    );
+    let event = match redactor_for_callback.as_deref() {
+      Some(redactor) => redact_thread_event(redactor, event),
+      None => event,
+    };
+    if let Some(journal) = run_journal.as_mut() {
+      journal.observe(&event);
+    }
+    if let ExecThreadEvent::ItemCompleted(ev) = &event
+      && let codex_exec::exec_events::ThreadItemDetails::AgentMessage(message) = &ev.item.details
+      && let Ok(mut slot) = final_response_for_callback.lock()
+    {
+      *slot = Some(message.text.clone());
+    }
+    if let ExecThreadEvent::ThreadStarted(ev) = &event {
+      if let Ok(mut slot) = thread_id_for_callback.lock() {
+        *slot = Some(ev.thread_id.clone());
       }
+      register_thread_handler(&ev.thread_id, &handler_for_callback);
+    }
+
+    let thread_id = thread_id_for_callback.lock().ok().and_then(|slot| slot.clone());
+    let files_touched = thread_id
+      .as_deref()
+      .and_then(|thread_id| files_touched_event(thread_id, &event));
+    if let Some(thread_id) = thread_id.as_deref() {
+      record_thread_activity(thread_id, &event);
+      record_thread_plan(&thread_plans, thread_id, &event);
+      audit_thread_event(Some(thread_id), &event);
+      notify_agent_graphs(thread_id, &event);
+    }
+
+    let failure = match &event {
+      ExecThreadEvent::TurnFailed(ev) => Some(ev.error.message.as_str()),
+      ExecThreadEvent::Error(ev) => Some(ev.message.as_str()),
+      _ => None,
+    };
+    if let Some(message) = failure {
+      record_run_error(thread_id, message);
+    }
 
+    // The turn's files_touched summary goes out just before the event that ends it.
+    for event in files_touched.into_iter().chain(std::iter::once(event)) {
       if let Err(err) = dispatch_thread_event(&handler_for_callback, event)
         && let Ok(mut guard) = handler_error_for_callback.lock() {
           *guard = Some(err);
       }
-    })
-    .await
-    .map_err(|e| napi::Error::from_reason(e.to_string()))
-  })?;
+    }
+  });
+  let run_result = runtime
+    .block_on(run_until_cancelled(cancellation, run))
+    .inspect_err(|err| {
+      let thread_id = thread_id_slot.lock().ok().and_then(|slot| slot.clone());
+      if let Some(thread_id) = thread_id.as_deref() {
+        mark_thread_run_finished(thread_id);
+      }
+      record_run_error(thread_id, &err.reason);
+    });
+  let cassette_result = cassette.map(CassetteSession::finish).transpose();
+  let redaction_result = match (
+    redactor.as_deref(),
+    thread_id_slot.lock().ok().and_then(|slot| slot.clone()),
+  ) {
+    (Some(redactor), Some(thread_id)) => {
+      runtime.block_on(redact_thread_rollout(&options, &thread_id, redactor))
+    }
+    _ => Ok(()),
+  };
+  let post_turn_result = if has_hooks(HookEvent::PostTurn) {
+    let thread_id = thread_id_slot.lock().ok().and_then(|slot| slot.clone());
+    let final_response = final_response.lock().ok().and_then(|mut slot| slot.take());
+    let error = run_result.as_ref().err().map(|err| err.reason.clone());
+    runtime
+      .block_on(run_post_turn_hooks(thread_id, options.prompt.clone(), final_response, error))
+      .and_then(|annotations| {
+        annotations.into_iter().try_for_each(|message| {
+          dispatch_thread_event(
+            &handler_arc,
+            ExecThreadEvent::BackgroundEvent(BackgroundEventEvent { message }),
+          )
+        })
+      })
+  } else {
+    Ok(())
+  };
+  run_result?;
+  cassette_result?;
+  redaction_result?;
+  post_turn_result?;
+
+  if let Some(thread_id) = thread_id_slot.lock().ok().and_then(|slot| slot.clone()) {
+    mark_thread_run_finished(&thread_id);
+  }
 
   if let Some(err) = handler_error.lock().unwrap().take() {
+    let thread_id = thread_id_slot.lock().ok().and_then(|slot| slot.clone());
+    record_run_error(thread_id, &err.reason);
     cleanup_thread_handler(&thread_id_slot);
     return Err(err);
   }
@@ -0,0 +1,284 @@
+// ============================================================================
+// File access ledger (getThreadFileAccess)
+// ============================================================================
+//
+// Every run records which files its tools read and modified: `read_file` and
+// `view_image` paths, files shell commands read (`cat`, `sed -n`, `head`, ...),
+// and the targets of patches that applied, whether through apply_patch or an
+// apply_patch heredoc in a shell tool. Calls that fail are not recorded.
+// getThreadFileAccess() returns a thread's ledger across all of its runs, and
+// each turn that touched files ends with a `files_touched` event listing them,
+// emitted just before `turn.completed` / `turn.failed`. The ledger is kept in
+// memory only, so it starts empty again after a restart.
+
+/// Tools whose calls can read or modify files.
+const FILE_ACCESS_TOOLS: &[&str] = &[
+  "read_file",
+  "view_image",
+  "apply_patch",
+  "shell",
+  "shell_command",
+  "exec_command",
+  "local_shell",
+];
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileAccessEntry {
+  /// Absolute path of the file.
+  pub path: String,
+  /// Tool calls that read the file.
+  pub reads: u32,
+  /// Applied patches that added, updated or deleted the file.
+  pub writes: u32,
+}
+
+#[derive(Debug, Default)]
+struct ThreadFileLedger {
+  files: std::collections::BTreeMap<String, FileAccessEntry>,
+  turn_read: std::collections::BTreeSet<String>,
+  turn_modified: std::collections::BTreeSet<String>,
+}
+
+impl ThreadFileLedger {
+  fn record(&mut self, path: &Path, write: bool) {
+    let path = normalize_patch_path(path).to_string_lossy().into_owned();
+    let entry = self
+      .files
+      .entry(path.clone())
+      .or_insert_with(|| FileAccessEntry {
+        path: path.clone(),
+        reads: 0,
+        writes: 0,
+      });
+    if write {
+      entry.writes += 1;
+      self.turn_modified.insert(path);
+    } else {
+      entry.reads += 1;
+      self.turn_read.insert(path);
+    }
+  }
+
+  /// The files touched since the turn started, which then starts over.
+  fn take_turn(&mut self) -> Option<codex_exec::exec_events::FilesTouchedEvent> {
+    let read = std::mem::take(&mut self.turn_read);
+    let modified = std::mem::take(&mut self.turn_modified);
+    if read.is_empty() && modified.is_empty() {
+      return None;
+    }
+    Some(codex_exec::exec_events::FilesTouchedEvent {
+      read: read.into_iter().collect(),
+      modified: modified.into_iter().collect(),
+    })
+  }
+}
+
+fn thread_file_ledgers() -> &'static Mutex<HashMap<String, ThreadFileLedger>> {
+  static LEDGERS: OnceLock<Mutex<HashMap<String, ThreadFileLedger>>> = OnceLock::new();
+  LEDGERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Files the tools read or modified on `thread_id`, sorted by path.
+#[napi]
+pub fn get_thread_file_access(thread_id: String) -> napi::Result<Vec<FileAccessEntry>> {
+  let ledgers = thread_file_ledgers()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("file access mutex poisoned: {e}")))?;
+  Ok(
+    ledgers
+      .get(&thread_id)
+      .map(|ledger| ledger.files.values().cloned().collect())
+      .unwrap_or_default(),
+  )
+}
+
+/// The `files_touched` event to emit before `event` ends a turn on `thread_id`, if any.
+fn files_touched_event(thread_id: &str, event: &ExecThreadEvent) -> Option<ExecThreadEvent> {
+  let mut ledgers = thread_file_ledgers().lock().ok()?;
+  let ledger = ledgers.get_mut(thread_id)?;
+  match event {
+    // Drop anything left over from a run that ended without finishing its turn.
+    ExecThreadEvent::TurnStarted(_) => {
+      ledger.take_turn();
+      None
+    }
+    ExecThreadEvent::TurnCompleted(_) | ExecThreadEvent::TurnFailed(_) => {
+      ledger.take_turn().map(ExecThreadEvent::FilesTouched)
+    }
+    _ => None,
+  }
+}
+
+/// Files a tool call reads, resolved against `root`.
+fn file_read_targets(tool_name: &str, payload: &ToolPayload, root: &Path) -> Vec<PathBuf> {
+  let path_argument = match tool_name {
+    "read_file" => Some("file_path"),
+    "view_image" => Some("path"),
+    _ => None,
+  };
+  if let Some(key) = path_argument {
+    let ToolPayload::Function { arguments } = payload else {
+      return Vec::new();
+    };
+    return serde_json::from_str::<JsonValue>(arguments)
+      .ok()
+      .and_then(|arguments| {
+        arguments
+          .get(key)
+          .and_then(JsonValue::as_str)
+          .map(|path| root.join(path))
+      })
+      .into_iter()
+      .collect();
+  }
+  let Some((argv, cwd)) = shell_call_command(tool_name, payload, root) else {
+    return Vec::new();
+  };
+  codex_core::parse_command::parse_command(&argv)
+    .into_iter()
+    .filter_map(|command| match command {
+      codex_protocol::parse_command::ParsedCommand::Read { path, .. } => Some(cwd.join(path)),
+      codex_protocol::parse_command::ParsedCommand::ListFiles { .. }
+      | codex_protocol::parse_command::ParsedCommand::Search { .. }
+      | codex_protocol::parse_command::ParsedCommand::Unknown { .. } => None,
+    })
+    .collect()
+}
+
+struct FileAccessInterceptor;
+
+#[async_trait]
+impl ToolInterceptor for FileAccessInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    let thread_id = invocation.thread_id();
+    let reads = file_read_targets(&invocation.tool_name, &invocation.payload, invocation.cwd());
+    let writes = patch_targets(&invocation.tool_name, &invocation.payload, invocation.cwd());
+    let output = next(invocation).await?;
+    let failed = matches!(
+      output,
+      ToolOutput::Function {
+        success: Some(false),
+        ..
+      }
+    );
+    if !failed
+      && (!reads.is_empty() || !writes.is_empty())
+      && let Ok(mut ledgers) = thread_file_ledgers().lock()
+    {
+      let ledger = ledgers.entry(thread_id).or_default();
+      for path in &reads {
+        ledger.record(path, false);
+      }
+      for path in &writes {
+        ledger.record(path, true);
+      }
+    }
+    Ok(output)
+  }
+}
+
+fn file_access_interceptors() -> Vec<ExternalInterceptorRegistration> {
+  let handler: Arc<dyn ToolInterceptor> = Arc::new(FileAccessInterceptor);
+  FILE_ACCESS_TOOLS
+    .iter()
+    .map(|tool| ExternalInterceptorRegistration {
+      name: (*tool).to_string(),
+      handler: Arc::clone(&handler),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests_file_access {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn ledger_counts_reads_and_writes_per_turn() {
+    let root = Path::new("/repo");
+    let function = |arguments: JsonValue| ToolPayload::Function {
+      arguments: arguments.to_string(),
+    };
+    assert_eq!(
+      file_read_targets(
+        "read_file",
+        &function(json!({ "file_path": "/repo/src/lib.rs" })),
+        root
+      ),
+      vec![PathBuf::from("/repo/src/lib.rs")]
+    );
+    assert_eq!(
+      file_read_targets(
+        "shell_command",
+        &function(json!({ "command": "sed -n '1,40p' src/main.rs", "workdir": "crates/cli" })),
+        root
+      ),
+      vec![PathBuf::from("/repo/crates/cli/src/main.rs")]
+    );
+    assert_eq!(
+      file_read_targets(
+        "shell_command",
+        &function(json!({ "command": "rg -n todo src" })),
+        root
+      ),
+      Vec::<PathBuf>::new()
+    );
+
+    let thread_id = "file-access-test-thread";
+    let turn_started = ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent {});
+    let turn_completed =
+      ExecThreadEvent::TurnCompleted(codex_exec::exec_events::TurnCompletedEvent {
+        usage: codex_exec::exec_events::Usage::default(),
+        redaction_applied: None,
+        dropped_events: None,
+      });
+    {
+      let mut ledgers = thread_file_ledgers().lock().unwrap();
+      let ledger = ledgers.entry(thread_id.to_string()).or_default();
+      ledger.record(Path::new("/repo/src/lib.rs"), false);
+      ledger.record(Path::new("/repo/./src/lib.rs"), false);
+      ledger.record(Path::new("/repo/src/lib.rs"), true);
+      ledger.record(Path::new("/repo/README.md"), false);
+    }
+    assert_eq!(
+      files_touched_event(thread_id, &turn_completed),
+      Some(ExecThreadEvent::FilesTouched(
+        codex_exec::exec_events::FilesTouchedEvent {
+          read: vec![
+            "/repo/README.md".to_string(),
+            "/repo/src/lib.rs".to_string()
+          ],
+          modified: vec!["/repo/src/lib.rs".to_string()],
+        }
+      ))
+    );
+    assert_eq!(files_touched_event(thread_id, &turn_started), None);
+    assert_eq!(files_touched_event(thread_id, &turn_completed), None);
+    assert_eq!(
+      get_thread_file_access(thread_id.to_string()).unwrap(),
+      vec![
+        FileAccessEntry {
+          path: "/repo/README.md".to_string(),
+          reads: 1,
+          writes: 0,
+        },
+        FileAccessEntry {
+          path: "/repo/src/lib.rs".to_string(),
+          reads: 2,
+          writes: 1,
+        },
+      ]
+    );
+  }
+}
@@ -0,0 +1,490 @@
+// ============================================================================
+// Large file skeletons (fileSkeleton)
+// ============================================================================
+//
+// When a whole-file read (`cat`, `nl`, `bat`, `less`, or `read_file` without a
+// range) targets a file larger than the configured limit, the truncated tool
+// output is replaced with a skeleton built natively from the file itself:
+// imports, declaration lines, and the lines around any `path:line` reference
+// seen earlier in the run (prompt, compiler errors, test failures). The
+// skeleton ends with instructions for requesting specific ranges, so the model
+// keeps a usable map of the file instead of its head and tail.
+
+/// Tools whose calls may read a whole file into the transcript.
+const FILE_SKELETON_TOOLS: &[&str] = &[
+  "shell",
+  "shell_command",
+  "exec_command",
+  "local_shell",
+  "read_file",
+];
+/// Commands that print an entire file when given a single path operand.
+const WHOLE_FILE_READERS: &[&str] = &["cat", "nl", "bat", "batcat", "less", "more"];
+const DEFAULT_FILE_SKELETON_MAX_BYTES: u32 = 32 * 1024;
+const DEFAULT_FILE_SKELETON_CONTEXT_LINES: u32 = 8;
+/// Referenced regions shown per file; later references win.
+const MAX_SKELETON_REGIONS: usize = 6;
+/// Longest line echoed into the skeleton before it is clipped.
+const MAX_SKELETON_LINE_CHARS: usize = 160;
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSkeletonOptions {
+  /// Files larger than this are returned as a skeleton (default 32 KiB).
+  #[napi(js_name = "maxBytes")]
+  pub max_bytes: Option<u32>,
+  /// Lines shown on each side of a referenced line (default 8).
+  #[napi(js_name = "contextLines")]
+  pub context_lines: Option<u32>,
+}
+
+fn import_line_regex() -> &'static regex::Regex {
+  static IMPORT: OnceLock<regex::Regex> = OnceLock::new();
+  IMPORT.get_or_init(|| {
+    regex::Regex::new(
+      r#"^\s*(use\s|pub\s+use\s|extern\s+crate\s|mod\s+\w+\s*;|pub\s+mod\s+\w+\s*;|import\s|from\s+\S+\s+import\s|#include\s|@import\s|package\s|require\s*\(|(const|let|var)\s+\S+\s*=\s*require\s*\()"#,
+    )
+    .expect("valid import regex")
+  })
+}
+
+fn declaration_line_regex() -> &'static regex::Regex {
+  static DECLARATION: OnceLock<regex::Regex> = OnceLock::new();
+  DECLARATION.get_or_init(|| {
+    regex::Regex::new(
+      r#"^\s*((pub(\([^)]*\))?|export|default|async|unsafe|const|static|abstract|public|private|protected|final|override)\s+)*(fn|def|class|struct|enum|trait|impl|interface|type|function\*?|func|module|namespace|macro_rules!)[\s<({:!]|^\s*(export\s+)?(const|let)\s+\w+\s*(:[^=]+)?=\s*(async\s*)?(\([^)]*\)|\w+)\s*=>"#,
+    )
+    .expect("valid declaration regex")
+  })
+}
+
+fn line_reference_regex() -> &'static regex::Regex {
+  static REFERENCE: OnceLock<regex::Regex> = OnceLock::new();
+  REFERENCE.get_or_init(|| {
+    regex::Regex::new(r"([A-Za-z0-9_@~./\\-]*[A-Za-z0-9_-]\.[A-Za-z0-9]+)(?::|\(|, line )(\d+)")
+      .expect("valid line reference regex")
+  })
+}
+
+/// `path:line` mentions collected from the prompt and earlier tool output.
+#[derive(Debug, Default)]
+struct LineReferences {
+  by_path: Vec<(PathBuf, usize)>,
+}
+
+impl LineReferences {
+  fn record(&mut self, text: &str) {
+    for capture in line_reference_regex().captures_iter(text) {
+      let Ok(line) = capture[2].parse::<usize>() else {
+        continue;
+      };
+      if line == 0 {
+        continue;
+      }
+      let path = PathBuf::from(capture[1].trim_start_matches("./").replace('\\', "/"));
+      self
+        .by_path
+        .retain(|(seen, seen_line)| !(seen == &path && *seen_line == line));
+      self.by_path.push((path, line));
+    }
+  }
+
+  /// Lines referenced for `target`, most recent last, matching on path suffix.
+  fn lines_for(&self, target: &Path) -> Vec<usize> {
+    let target = normalize_patch_path(target);
+    let mut lines: Vec<usize> = self
+      .by_path
+      .iter()
+      .filter(|(path, _)| target.ends_with(path))
+      .map(|(_, line)| *line)
+      .collect();
+    if lines.len() > MAX_SKELETON_REGIONS {
+      lines.drain(..lines.len() - MAX_SKELETON_REGIONS);
+    }
+    lines
+  }
+}
+
+/// Command line a shell tool call runs, and the directory it runs in, resolved against `root`.
+fn shell_call_command(
+  tool_name: &str,
+  payload: &ToolPayload,
+  root: &Path,
+) -> Option<(Vec<String>, PathBuf)> {
+  match payload {
+    ToolPayload::LocalShell { params } => Some((
+      params.command.clone(),
+      params
+        .workdir
+        .as_deref()
+        .map_or_else(|| root.to_path_buf(), |dir| root.join(dir)),
+    )),
+    ToolPayload::Custom { .. } | ToolPayload::Mcp { .. } => None,
+    ToolPayload::Function { arguments } => {
+      let arguments = serde_json::from_str::<JsonValue>(arguments).ok()?;
+      let cwd = arguments
+        .get("workdir")
+        .and_then(JsonValue::as_str)
+        .map_or_else(|| root.to_path_buf(), |dir| root.join(dir));
+      let argv = match tool_name {
+        "shell" => arguments
+          .get("command")
+          .and_then(|command| serde_json::from_value(command.clone()).ok())?,
+        _ => {
+          let script = arguments
+            .get("command")
+            .or_else(|| arguments.get("cmd"))
+            .and_then(JsonValue::as_str)?;
+          vec!["bash".to_string(), "-lc".to_string(), script.to_string()]
+        }
+      };
+      Some((argv, cwd))
+    }
+  }
+}
+
+/// File a call reads in full, resolved against `root`, if it is a whole-file read.
+fn whole_file_read_target(tool_name: &str, payload: &ToolPayload, root: &Path) -> Option<PathBuf> {
+  if tool_name == "read_file" {
+    let ToolPayload::Function { arguments } = payload else {
+      return None;
+    };
+    let arguments = serde_json::from_str::<JsonValue>(arguments).ok()?;
+    let ranged = ["offset", "limit", "mode", "indentation"]
+      .iter()
+      .any(|key| arguments.get(key).is_some());
+    if ranged {
+      return None;
+    }
+    return arguments
+      .get("file_path")
+      .and_then(JsonValue::as_str)
+      .map(PathBuf::from);
+  }
+  let (argv, cwd) = shell_call_command(tool_name, payload, root)?;
+  use codex_protocol::parse_command::ParsedCommand;
+  match codex_core::parse_command::parse_command(&argv).as_slice() {
+    [ParsedCommand::Read { cmd, path, .. }] => {
+      let program = cmd.split_whitespace().next().unwrap_or_default();
+      WHOLE_FILE_READERS
+        .contains(&program)
+        .then(|| cwd.join(path))
+    }
+    [] | [_] | [_, _, ..] => None,
+  }
+}
+
+fn clip_skeleton_line(line: &str) -> String {
+  let trimmed = line.trim_end();
+  match trimmed.char_indices().nth(MAX_SKELETON_LINE_CHARS) {
+    Some((end, _)) => format!("{}…", &trimmed[..end]),
+    None => trimmed.to_string(),
+  }
+}
+
+/// Outline of `contents` that fits roughly within `max_bytes`.
+fn build_file_skeleton(
+  display_path: &str,
+  contents: &str,
+  referenced: &[usize],
+  context_lines: usize,
+  max_bytes: usize,
+) -> String {
+  let lines: Vec<&str> = contents.lines().collect();
+  let average_line = (contents.len() / lines.len().max(1)).max(1);
+  let range_lines = (max_bytes / average_line).clamp(20, 2000);
+
+  let mut out = format!(
+    "[file skeleton] {display_path}: {} lines, {} bytes, which is more than the {max_bytes}-byte output limit.\n\
+     Showing imports, declarations and the lines around referenced locations instead of the full file. \
+     Line numbers are 1-based.\n",
+    lines.len(),
+    contents.len(),
+  );
+  let footer = format!(
+    "\nTo read a specific range, run `sed -n '<start>,<end>p' {display_path}` (or call read_file with offset and \
+     limit), keeping each range under about {range_lines} lines.\n"
+  );
+  let budget = max_bytes.saturating_sub(footer.len());
+
+  let mut referenced: Vec<usize> = referenced
+    .iter()
+    .copied()
+    .filter(|line| *line <= lines.len())
+    .collect();
+  referenced.sort_unstable();
+  let mut regions: Vec<(usize, usize)> = Vec::new();
+  for line in referenced {
+    let start = line.saturating_sub(context_lines).max(1);
+    let end = (line + context_lines).min(lines.len());
+    match regions.last_mut() {
+      Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+      Some(_) | None => regions.push((start, end)),
+    }
+  }
+
+  let mut sections: Vec<(&str, Vec<String>)> = Vec::new();
+  if !regions.is_empty() {
+    let mut entries = Vec::new();
+    for (start, end) in &regions {
+      entries.push(format!("  lines {start}-{end}:"));
+      for number in *start..=*end {
+        entries.push(format!(
+          "  {number:>6}| {}",
+          clip_skeleton_line(lines[number - 1])
+        ));
+      }
+    }
+    sections.push(("Referenced lines", entries));
+  }
+  let outline = |regex: &regex::Regex| -> Vec<String> {
+    lines
+      .iter()
+      .enumerate()
+      .filter(|(_, line)| regex.is_match(line))
+      .map(|(index, line)| format!("  {:>6}: {}", index + 1, clip_skeleton_line(line)))
+      .collect()
+  };
+  sections.push(("Imports", outline(import_line_regex())));
+  sections.push(("Declarations", outline(declaration_line_regex())));
+
+  for (title, entries) in sections {
+    if entries.is_empty() {
+      continue;
+    }
+    let heading = format!("\n{title}:\n");
+    if out.len() + heading.len() >= budget {
+      break;
+    }
+    out.push_str(&heading);
+    for (shown, entry) in entries.iter().enumerate() {
+      if out.len() + entry.len() + 1 > budget {
+        out.push_str(&format!("  … {} more omitted\n", entries.len() - shown));
+        break;
+      }
+      out.push_str(entry);
+      out.push('\n');
+    }
+  }
+  out.push_str(&footer);
+  out
+}
+
+/// Swap the file contents in a formatted tool output for `skeleton`, keeping
+/// exit codes, timings and other metadata the handler reported.
+fn replace_tool_output_contents(text: &str, skeleton: &str) -> String {
+  if let Ok(JsonValue::Object(mut object)) = serde_json::from_str::<JsonValue>(text)
+    && object.get("output").is_some_and(JsonValue::is_string)
+  {
+    object.insert(
+      "output".to_string(),
+      JsonValue::String(skeleton.to_string()),
+    );
+    return JsonValue::Object(object).to_string();
+  }
+  match text.find("Output:\n") {
+    Some(index) if index == 0 || text[..index].ends_with('\n') => {
+      format!("{}{skeleton}", &text[..index + "Output:\n".len()])
+    }
+    Some(_) | None => skeleton.to_string(),
+  }
+}
+
+struct FileSkeletonInterceptor {
+  root: PathBuf,
+  max_bytes: usize,
+  context_lines: usize,
+  references: Arc<Mutex<LineReferences>>,
+}
+
+impl FileSkeletonInterceptor {
+  fn skeleton_for(&self, target: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(target).ok()?;
+    if !metadata.is_file() || metadata.len() <= self.max_bytes as u64 {
+      return None;
+    }
+    let bytes = std::fs::read(target).ok()?;
+    let contents = String::from_utf8(bytes).ok()?;
+    let referenced = self.references.lock().ok()?.lines_for(target);
+    let display_path = target
+      .strip_prefix(&self.root)
+      .unwrap_or(target)
+      .to_string_lossy()
+      .replace('\\', "/");
+    Some(build_file_skeleton(
+      &display_path,
+      &contents,
+      &referenced,
+      self.context_lines,
+      self.max_bytes,
+    ))
+  }
+}
+
+#[async_trait]
+impl ToolInterceptor for FileSkeletonInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    use codex_protocol::models::FunctionCallOutputBody;
+
+    let target = whole_file_read_target(&invocation.tool_name, &invocation.payload, &self.root);
+    let output = next(invocation).await?;
+    match output {
+      ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(text),
+        success,
+      } => {
+        let skeleton = target
+          .filter(|_| success != Some(false))
+          .and_then(|target| self.skeleton_for(&target));
+        // Record after building so the file's own contents don't count as references.
+        if let Ok(mut references) = self.references.lock() {
+          references.record(&text);
+        }
+        let text = match skeleton {
+          Some(skeleton) => replace_tool_output_contents(&text, &skeleton),
+          None => text,
+        };
+        Ok(ToolOutput::Function {
+          body: FunctionCallOutputBody::Text(text),
+          success,
+        })
+      }
+      output @ (ToolOutput::Function {
+        body: FunctionCallOutputBody::ContentItems(_),
+        ..
+      }
+      | ToolOutput::Mcp { .. }
+      | ToolOutput::Stream { .. }) => Ok(output),
+    }
+  }
+}
+
+/// Interceptors applying `options.file_skeleton`, if configured.
+fn file_skeleton_interceptors(
+  options: &InternalRunRequest,
+) -> napi::Result<Vec<ExternalInterceptorRegistration>> {
+  let Some(skeleton) = options.file_skeleton.as_ref() else {
+    return Ok(Vec::new());
+  };
+  let root = match options.working_directory.clone() {
+    Some(dir) => dir,
+    None => std::env::current_dir()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve working directory: {e}")))?,
+  };
+  let max_bytes = skeleton
+    .max_bytes
+    .unwrap_or(DEFAULT_FILE_SKELETON_MAX_BYTES);
+  if max_bytes == 0 {
+    return Err(napi::Error::from_reason(
+      "fileSkeleton.maxBytes must be greater than zero",
+    ));
+  }
+  let mut references = LineReferences::default();
+  references.record(&options.prompt);
+  let handler = Arc::new(FileSkeletonInterceptor {
+    root,
+    max_bytes: max_bytes as usize,
+    context_lines: skeleton
+      .context_lines
+      .unwrap_or(DEFAULT_FILE_SKELETON_CONTEXT_LINES) as usize,
+    references: Arc::new(Mutex::new(references)),
+  });
+  Ok(
+    FILE_SKELETON_TOOLS
+      .iter()
+      .map(|tool| ExternalInterceptorRegistration {
+        name: (*tool).to_string(),
+        handler: Arc::clone(&handler) as Arc<dyn ToolInterceptor>,
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests_file_skeleton {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn only_whole_file_reads_are_targeted() {
+    let root = Path::new("/repo");
+    let shell = |command: &str| ToolPayload::Function {
+      arguments: json!({ "command": command }).to_string(),
+    };
+    assert_eq!(
+      whole_file_read_target("shell_command", &shell("cat src/lib.rs"), root),
+      Some(PathBuf::from("/repo/src/lib.rs"))
+    );
+    assert_eq!(
+      whole_file_read_target("shell_command", &shell("sed -n '10,20p' src/lib.rs"), root),
+      None
+    );
+    let ranged = ToolPayload::Function {
+      arguments: json!({ "file_path": "/repo/a.rs", "offset": 10 }).to_string(),
+    };
+    assert_eq!(whole_file_read_target("read_file", &ranged, root), None);
+  }
+
+  #[test]
+  fn skeleton_keeps_outline_and_referenced_regions() {
+    let mut contents = String::from("use std::fmt;\n\npub struct Widget {\n  size: u32,\n}\n\n");
+    for index in 0..200 {
+      contents.push_str(&format!("// filler {index}\n"));
+    }
+    contents.push_str("fn render(widget: &Widget) -> String {\n  todo!()\n}\n");
+
+    let mut references = LineReferences::default();
+    references.record("error[E0425]: cannot find value `x` in src/widget.rs:207:3");
+    let referenced = references.lines_for(Path::new("/repo/src/widget.rs"));
+    assert_eq!(referenced, vec![207]);
+
+    let skeleton = build_file_skeleton("src/widget.rs", &contents, &referenced, 1, 4096);
+    assert!(skeleton.contains("       1: use std::fmt;"), "{skeleton}");
+    assert!(
+      skeleton.contains("       3: pub struct Widget {"),
+      "{skeleton}"
+    );
+    assert!(
+      skeleton.contains("     207: fn render(widget: &Widget) -> String {"),
+      "{skeleton}"
+    );
+    assert!(
+      skeleton.contains("  lines 206-208:\n     206| // filler 199\n"),
+      "{skeleton}"
+    );
+    assert!(
+      skeleton.contains("sed -n '<start>,<end>p' src/widget.rs"),
+      "{skeleton}"
+    );
+    assert!(!skeleton.contains("filler 100"), "{skeleton}");
+  }
+
+  #[test]
+  fn replacement_preserves_output_metadata() {
+    assert_eq!(
+      replace_tool_output_contents(
+        "Exit code: 0\nWall time: 0.1 seconds\nOutput:\nlots",
+        "SKELETON"
+      ),
+      "Exit code: 0\nWall time: 0.1 seconds\nOutput:\nSKELETON"
+    );
+    let structured = json!({ "output": "lots", "metadata": { "exit_code": 0 } }).to_string();
+    let replaced: JsonValue =
+      serde_json::from_str(&replace_tool_output_contents(&structured, "SKELETON")).unwrap();
+    assert_eq!(
+      replaced,
+      json!({ "output": "SKELETON", "metadata": { "exit_code": 0 } })
+    );
+  }
+}
@@ -9,7 +9,19 @@
 // Key exports:
 //   - run_thread(): Execute agent with given configuration
 //   - run_thread_stream(): Stream events during execution
+//   - run_thread_collect(): Execute agent and return its folded, typed result
+//   - run_review(): Review a diff or base ref and return structured findings
+//   - review_diff(): Review a natively computed git diff between refs
 //   - compact_thread(): Compact conversation history
+//   - build_prompt(): Assemble the first-turn payload offline
+//   - register_prompt_template(): Named prompts rendered for `promptTemplate`
+//   - replay_thread(): Re-derive ThreadEvents from a recorded rollout
+//   - get_thread_activity(): Current phase and idle time of a thread
+//   - get_thread_file_access(): Files a thread's tools read and modified
+//   - recover_interrupted_runs(): Find and close runs cut off by a process exit
+//   - generate_commit_message(), generate_pr_description(): Describe local changes
+//   - run_from_task(): Run an issue or ticket payload and link the thread to it
+//   - register_escalation_handler(): Decide sandbox escalations for headless runs
 //
 // ============================================================================
 
@@ -18,9 +30,37 @@ include!("model_validation.rs");
 include!("parsing.rs");
 include!("cli_builder.rs");
 include!("schema.rs");
-include!("env_overrides.rs");
+include!("run_overrides.rs");
 include!("tui_snapshots.rs");
 include!("thread_ops.rs");
+include!("thread_collect.rs");
+include!("review.rs");
+include!("event_sequence.rs");
+include!("event_delivery.rs");
+include!("cassette.rs");
+include!("patch_policy.rs");
+include!("verification.rs");
+include!("permissions.rs");
+include!("prompt_templates.rs");
+include!("tool_limits.rs");
+include!("tool_env.rs");
+include!("tool_cache.rs");
+include!("builtin_direct.rs");
+include!("file_skeleton.rs");
+include!("file_access.rs");
+include!("redaction.rs");
+include!("post_turn_checks.rs");
+include!("run_recovery.rs");
 include!("execution.rs");
+include!("activity.rs");
+include!("prompt_preview.rs");
+include!("replay.rs");
+include!("thread_items.rs");
+include!("thread_edits.rs");
+include!("thread_summary.rs");
+include!("thread_rollups.rs");
+include!("change_descriptions.rs");
+include!("task_runs.rs");
+include!("escalation.rs");
 include!("cloud_client.rs");
 include!("tests.rs");
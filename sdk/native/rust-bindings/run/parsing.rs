@@ -6,6 +6,7 @@ impl ConversationConfigRequest {
     let reasoning_summary = parse_reasoning_summary(self.reasoning_summary.as_deref())?;
     let personality = parse_personality(self.personality.as_deref())?;
     let web_search_mode = parse_web_search_mode(self.web_search_mode.as_deref())?;
+    let auth_home = resolve_run_auth_home(self.auth_profile.as_deref(), self.api_key.is_some())?;
     Ok(InternalRunRequest {
       prompt: String::new(),
       input_items: None,
@@ -13,10 +14,14 @@ impl ConversationConfigRequest {
       images: Vec::new(),
       model: self.model,
       model_provider: self.model_provider,
+      config_profile: self.profile,
       oss: self.oss.unwrap_or(false),
       sandbox_mode,
       approval_mode,
       workspace_write_options: self.workspace_write_options,
+      tool_filter: None,
+      tool_limits: HashMap::new(),
+      tool_cache_ttls: HashMap::new(),
       review_request: None,
       working_directory: self.working_directory.map(PathBuf::from),
       skip_git_repo_check: self.skip_git_repo_check.unwrap_or(false),
@@ -24,6 +29,7 @@ impl ConversationConfigRequest {
       tool_choice: None,
       base_url: self.base_url,
       api_key: self.api_key,
+      auth_home,
       linux_sandbox_path: self.linux_sandbox_path.map(PathBuf::from),
       reasoning_effort,
       reasoning_summary,
@@ -34,19 +40,42 @@ impl ConversationConfigRequest {
       dynamic_tools: None,
       mcp: None,
       inherit_mcp: true,
+      record: None,
+      replay: None,
+      patch_path_policy: None,
+      file_skeleton: None,
+      redaction: None,
+      post_turn_checks: Vec::new(),
+      max_check_feedback_turns: DEFAULT_MAX_CHECK_FEEDBACK_TURNS,
+      base_instructions: None,
+      developer_instructions: None,
+      compact_prompt: None,
+      include_raw_events: false,
+      exec_session: None,
+      shell_pty: None,
+      tool_env: None,
+      verify: None,
+      memory: None,
+      escalation_handler: None,
+      output_limits: None,
     })
   }
 }
 
 impl RunRequest {
-  pub fn into_internal(self) -> napi::Result<InternalRunRequest> {
+  pub fn into_internal(mut self) -> napi::Result<InternalRunRequest> {
+    self.resolve_prompt_template()?;
+    let tool_filter = self.resolve_permissions()?;
+    let tool_limits = self.resolve_tool_limits()?;
+    let tool_cache_ttls = self.resolve_tool_cache_ttls();
     let sandbox_mode = parse_sandbox_mode(self.sandbox_mode.as_deref())?;
     let approval_mode = parse_approval_mode(self.approval_mode.as_deref())?;
     let reasoning_effort = parse_reasoning_effort(self.reasoning_effort.as_deref())?;
     let reasoning_summary = parse_reasoning_summary(self.reasoning_summary.as_deref())?;
     let personality = parse_personality(self.personality.as_deref())?;
     let turn_personality = parse_personality(self.turn_personality.as_deref())?;
-    let web_search_mode = parse_web_search_mode(self.web_search_mode.as_deref())?;
+    let web_search_mode = parse_web_search_mode(self.web_search_mode.as_deref())?
+      .or(self.web_search.map(web_search_toggle_mode));
 
     let review_request = if self.review_mode.unwrap_or(false) {
       let prompt_trimmed = self.prompt.trim().to_string();
@@ -86,6 +115,15 @@ impl RunRequest {
       None => None,
     };
 
+    let post_turn_checks = parse_post_turn_checks(self.post_turn_checks)?;
+    let auth_home = resolve_run_auth_home(self.auth_profile.as_deref(), self.api_key.is_some())?;
+
+    if self.record.is_some() && self.replay.is_some() {
+      return Err(napi::Error::from_reason(
+        "record and replay cannot be combined in one run".to_string(),
+      ));
+    }
+
     validate_model_name(
       self.model.as_deref(),
       self.oss.unwrap_or(false),
@@ -99,10 +137,14 @@ impl RunRequest {
       images,
       model: self.model,
       model_provider: self.model_provider,
+      config_profile: self.profile,
       oss: self.oss.unwrap_or(false),
       sandbox_mode,
       approval_mode,
       workspace_write_options: self.workspace_write_options,
+      tool_filter,
+      tool_limits,
+      tool_cache_ttls,
       review_request,
       working_directory,
       skip_git_repo_check: self.skip_git_repo_check.unwrap_or(false),
@@ -110,6 +152,7 @@ impl RunRequest {
       tool_choice: self.tool_choice,
       base_url: self.base_url,
       api_key: self.api_key,
+      auth_home,
       linux_sandbox_path: self.linux_sandbox_path.map(PathBuf::from),
       reasoning_effort,
       reasoning_summary,
@@ -120,6 +163,30 @@ impl RunRequest {
       dynamic_tools,
       mcp: self.mcp,
       inherit_mcp: self.inherit_mcp.unwrap_or(true),
+      record: self.record.map(PathBuf::from),
+      replay: self.replay.map(PathBuf::from),
+      patch_path_policy: self.patch_path_policy,
+      file_skeleton: self.file_skeleton,
+      redaction: self.redaction,
+      post_turn_checks,
+      max_check_feedback_turns: self
+        .max_check_feedback_turns
+        .unwrap_or(DEFAULT_MAX_CHECK_FEEDBACK_TURNS),
+      base_instructions: self.base_instructions,
+      developer_instructions: self.developer_instructions,
+      compact_prompt: self.compact_prompt,
+      include_raw_events: self.include_raw_events.unwrap_or(false),
+      exec_session: parse_exec_session_mode(self.exec_session.as_deref())?,
+      shell_pty: self.shell_pty,
+      tool_env: self.tool_env.map(ToolEnvPolicy::from_options).transpose()?,
+      verify: self
+        .verify
+        .map(PatchVerification::from_options)
+        .transpose()?
+        .flatten(),
+      memory: self.memory,
+      escalation_handler: self.escalation_handler,
+      output_limits: self.output_limits.map(validate_output_limits).transpose()?,
     })
   }
 }
@@ -140,21 +207,29 @@ impl ForkRequest {
 
     let run_request = RunRequest {
       prompt: String::new(),
+      prompt_template: None,
       input_items: None,
       thread_id: Some(thread_id.clone()),
       images: None,
       model: self.model,
       model_provider: self.model_provider,
+      profile: self.profile,
       oss: self.oss,
       sandbox_mode: self.sandbox_mode,
       approval_mode: self.approval_mode,
       workspace_write_options: self.workspace_write_options,
+      permission_profile: None,
+      allowed_tools: None,
+      denied_tools: None,
+      tool_limits: None,
+      cache_ttl_seconds: None,
       working_directory: self.working_directory,
       skip_git_repo_check: self.skip_git_repo_check,
       output_schema: None,
       tool_choice: None,
       base_url: self.base_url,
       api_key: self.api_key,
+      auth_profile: self.auth_profile,
       linux_sandbox_path: self.linux_sandbox_path,
       reasoning_effort: self.reasoning_effort,
       reasoning_summary: self.reasoning_summary,
@@ -166,7 +241,27 @@ impl ForkRequest {
       turn_personality: None,
       ephemeral: None,
       web_search_mode: None,
+      web_search: None,
       dynamic_tools: None,
+      record: None,
+      replay: None,
+      patch_path_policy: None,
+      file_skeleton: None,
+      redaction: None,
+      post_turn_checks: None,
+      max_check_feedback_turns: None,
+      base_instructions: None,
+      developer_instructions: None,
+      compact_prompt: None,
+      event_delivery: None,
+      include_raw_events: None,
+      exec_session: None,
+      shell_pty: None,
+      tool_env: None,
+      verify: None,
+      memory: None,
+      escalation_handler: None,
+      output_limits: None,
     };
 
     let run_options = run_request.into_internal()?;
@@ -245,6 +340,37 @@ fn parse_web_search_mode(input: Option<&str>) -> napi::Result<Option<WebSearchMo
   )
 }
 
+fn parse_exec_session_mode(input: Option<&str>) -> napi::Result<Option<ExecSessionMode>> {
+  parse_enum_arg!(input, "exec session mode",
+    "per-call" => ExecSessionMode::PerCall,
+    "persistent" => ExecSessionMode::Persistent,
+  )
+}
+
+fn validate_output_limits(limits: OutputLimits) -> napi::Result<OutputLimits> {
+  if limits.max_tool_output_tokens == Some(0) {
+    return Err(napi::Error::from_reason(
+      "outputLimits.maxToolOutputTokens must be at least 1",
+    ));
+  }
+  if let Some(split) = limits.head_tail_split
+    && !(0.0..=1.0).contains(&split)
+  {
+    return Err(napi::Error::from_reason(format!(
+      "outputLimits.headTailSplit must be between 0 and 1, got {split}"
+    )));
+  }
+  Ok(limits)
+}
+
+fn web_search_toggle_mode(enabled: bool) -> WebSearchMode {
+  if enabled {
+    WebSearchMode::Live
+  } else {
+    WebSearchMode::Disabled
+  }
+}
+
 fn approval_mode_cli_to_policy(mode: Option<ApprovalModeCliArg>) -> Option<AskForApproval> {
   mode.map(|m| match m {
     ApprovalModeCliArg::Never => AskForApproval::Never,
@@ -0,0 +1,306 @@
+// ============================================================================
+// Patch path policy (patchPathPolicy)
+// ============================================================================
+//
+// Allow/deny globs checked against every file an `apply_patch` call would
+// touch, whether it arrives through the apply_patch tool or as an
+// `apply_patch` heredoc run through one of the shell tools. The check runs in
+// an interceptor ahead of approvals and the handler, so protected files stay
+// untouched even in full-auto mode; violations are returned to the model as a
+// denied tool call that names the offending path and the policy.
+
+/// Tools whose calls may carry an `apply_patch` payload.
+const PATCH_POLICY_TOOLS: &[&str] = &["apply_patch", "shell", "shell_command", "exec_command", "local_shell"];
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchPathPolicy {
+  /// When non-empty, only paths matching one of these globs may be patched.
+  pub allow: Option<Vec<String>>,
+  /// Paths matching any of these globs may never be patched. Wins over `allow`.
+  pub deny: Option<Vec<String>>,
+}
+
+/// Compiled form of [`PatchPathPolicy`]; globs match paths relative to `root`.
+#[derive(Debug)]
+struct PatchPathRules {
+  root: PathBuf,
+  allow: Option<globset::GlobSet>,
+  deny: globset::GlobSet,
+  allow_patterns: Vec<String>,
+  deny_patterns: Vec<String>,
+}
+
+fn compile_patch_globs(patterns: &[String]) -> napi::Result<globset::GlobSet> {
+  let mut builder = globset::GlobSetBuilder::new();
+  for pattern in patterns {
+    let glob = globset::GlobBuilder::new(pattern)
+      .literal_separator(true)
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Invalid patchPathPolicy glob {pattern:?}: {e}")))?;
+    builder.add(glob);
+  }
+  builder
+    .build()
+    .map_err(|e| napi::Error::from_reason(format!("Invalid patchPathPolicy: {e}")))
+}
+
+impl PatchPathRules {
+  fn compile(policy: &PatchPathPolicy, root: PathBuf) -> napi::Result<Self> {
+    let allow_patterns = policy.allow.clone().unwrap_or_default();
+    let deny_patterns = policy.deny.clone().unwrap_or_default();
+    let allow = if allow_patterns.is_empty() {
+      None
+    } else {
+      Some(compile_patch_globs(&allow_patterns)?)
+    };
+    Ok(Self {
+      root: normalize_patch_path(&root),
+      allow,
+      deny: compile_patch_globs(&deny_patterns)?,
+      allow_patterns,
+      deny_patterns,
+    })
+  }
+
+  /// Why `path` may not be patched, or `None` when the policy permits it.
+  fn violation(&self, path: &Path) -> Option<String> {
+    let absolute = normalize_patch_path(&self.root.join(path));
+    let Ok(relative) = absolute.strip_prefix(&self.root) else {
+      return Some(format!(
+        "{} is outside the workspace {}",
+        absolute.display(),
+        self.root.display()
+      ));
+    };
+    let display = relative.to_string_lossy().replace('\\', "/");
+    if self.deny.is_match(relative) {
+      return Some(format!(
+        "{display} matches a denied pattern ({})",
+        self.deny_patterns.join(", ")
+      ));
+    }
+    match &self.allow {
+      Some(allow) if !allow.is_match(relative) => Some(format!(
+        "{display} is not covered by the allowed patterns ({})",
+        self.allow_patterns.join(", ")
+      )),
+      Some(_) | None => None,
+    }
+  }
+}
+
+/// Resolve `.` and `..` lexically; the target of an add need not exist yet.
+fn normalize_patch_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      std::path::Component::CurDir => {}
+      std::path::Component::ParentDir => {
+        normalized.pop();
+      }
+      std::path::Component::Prefix(_)
+      | std::path::Component::RootDir
+      | std::path::Component::Normal(_) => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+fn patch_hunk_paths(hunks: &[codex_apply_patch::Hunk], cwd: &Path) -> Vec<PathBuf> {
+  let mut paths = Vec::new();
+  for hunk in hunks {
+    paths.push(hunk.resolve_path(cwd));
+    if let codex_apply_patch::Hunk::UpdateFile {
+      move_path: Some(move_path),
+      ..
+    } = hunk
+    {
+      paths.push(cwd.join(move_path));
+    }
+  }
+  paths
+}
+
+fn shell_patch_paths(argv: &[String], cwd: &Path) -> Vec<PathBuf> {
+  use codex_apply_patch::ApplyPatchFileChange;
+  use codex_apply_patch::MaybeApplyPatchVerified;
+
+  match codex_apply_patch::maybe_parse_apply_patch_verified(argv, cwd) {
+    MaybeApplyPatchVerified::Body(action) => {
+      let mut paths = Vec::new();
+      for (path, change) in action.changes() {
+        paths.push(path.clone());
+        if let ApplyPatchFileChange::Update {
+          move_path: Some(move_path),
+          ..
+        } = change
+        {
+          paths.push(move_path.clone());
+        }
+      }
+      paths
+    }
+    MaybeApplyPatchVerified::ShellParseError(_)
+    | MaybeApplyPatchVerified::CorrectnessError(_)
+    | MaybeApplyPatchVerified::NotApplyPatch => Vec::new(),
+  }
+}
+
+/// Every path the call would create, modify, delete or move to.
+fn patch_targets(tool_name: &str, payload: &ToolPayload, root: &Path) -> Vec<PathBuf> {
+  let arguments = match payload {
+    ToolPayload::Custom { input } => {
+      return codex_apply_patch::parse_patch(input)
+        .map(|args| patch_hunk_paths(&args.hunks, root))
+        .unwrap_or_default();
+    }
+    ToolPayload::LocalShell { params } => {
+      let cwd = params.workdir.as_deref().map_or_else(|| root.to_path_buf(), |dir| root.join(dir));
+      return shell_patch_paths(&params.command, &cwd);
+    }
+    ToolPayload::Mcp { .. } => return Vec::new(),
+    ToolPayload::Function { arguments } => {
+      serde_json::from_str::<JsonValue>(arguments).unwrap_or(JsonValue::Null)
+    }
+  };
+  let cwd = arguments
+    .get("workdir")
+    .and_then(JsonValue::as_str)
+    .map_or_else(|| root.to_path_buf(), |dir| root.join(dir));
+  match tool_name {
+    "apply_patch" => arguments
+      .get("input")
+      .and_then(JsonValue::as_str)
+      .and_then(|input| codex_apply_patch::parse_patch(input).ok())
+      .map(|args| patch_hunk_paths(&args.hunks, &cwd))
+      .unwrap_or_default(),
+    "shell" => {
+      let argv: Vec<String> = arguments
+        .get("command")
+        .and_then(|command| serde_json::from_value(command.clone()).ok())
+        .unwrap_or_default();
+      shell_patch_paths(&argv, &cwd)
+    }
+    _ => {
+      let script = arguments
+        .get("command")
+        .or_else(|| arguments.get("cmd"))
+        .and_then(JsonValue::as_str);
+      match script {
+        Some(script) => shell_patch_paths(
+          &["bash".to_string(), "-lc".to_string(), script.to_string()],
+          &cwd,
+        ),
+        None => Vec::new(),
+      }
+    }
+  }
+}
+
+struct PatchPathPolicyInterceptor {
+  rules: Arc<PatchPathRules>,
+}
+
+#[async_trait]
+impl ToolInterceptor for PatchPathPolicyInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    let violations: Vec<String> = patch_targets(&invocation.tool_name, &invocation.payload, &self.rules.root)
+      .iter()
+      .filter_map(|path| self.rules.violation(path))
+      .collect();
+    if violations.is_empty() {
+      return next(invocation).await;
+    }
+    Err(FunctionCallError::RespondToModel(format!(
+      "patch rejected by patchPathPolicy: {}. These files are protected for this run; do not retry \
+       the change or work around it through other tools.",
+      violations.join("; ")
+    )))
+  }
+}
+
+/// Interceptors enforcing `options.patch_path_policy`, if one is configured.
+fn patch_path_policy_interceptors(
+  options: &InternalRunRequest,
+) -> napi::Result<Vec<ExternalInterceptorRegistration>> {
+  let Some(policy) = options.patch_path_policy.as_ref() else {
+    return Ok(Vec::new());
+  };
+  let root = match options.working_directory.clone() {
+    Some(dir) => dir,
+    None => std::env::current_dir()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve working directory: {e}")))?,
+  };
+  let rules = Arc::new(PatchPathRules::compile(policy, root)?);
+  Ok(
+    PATCH_POLICY_TOOLS
+      .iter()
+      .map(|tool| ExternalInterceptorRegistration {
+        name: (*tool).to_string(),
+        handler: Arc::new(PatchPathPolicyInterceptor {
+          rules: Arc::clone(&rules),
+        }),
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests_patch_policy {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn rules() -> PatchPathRules {
+    PatchPathRules::compile(
+      &PatchPathPolicy {
+        allow: Some(vec!["src/**".to_string()]),
+        deny: Some(vec!["**/*.lock".to_string(), ".github/**".to_string()]),
+      },
+      PathBuf::from("/repo"),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn deny_wins_over_allow_and_allow_limits_scope() {
+    let rules = rules();
+    assert_eq!(rules.violation(Path::new("src/lib.rs")), None);
+    assert!(rules.violation(Path::new("src/vendor/Cargo.lock")).is_some());
+    assert!(rules.violation(Path::new(".github/workflows/ci.yml")).is_some());
+    assert!(rules.violation(Path::new("README.md")).is_some());
+    assert!(rules.violation(Path::new("src/../../etc/passwd")).is_some());
+  }
+
+  #[test]
+  fn targets_include_move_destinations_and_shell_heredocs() {
+    let patch = "*** Begin Patch\n*** Update File: src/a.rs\n*** Move to: .github/a.rs\n@@\n-x\n+y\n*** End Patch";
+    let targets = patch_targets(
+      "apply_patch",
+      &ToolPayload::Custom {
+        input: patch.to_string(),
+      },
+      Path::new("/repo"),
+    );
+    assert_eq!(
+      targets,
+      vec![PathBuf::from("/repo/src/a.rs"), PathBuf::from("/repo/.github/a.rs")]
+    );
+
+    let add = "*** Begin Patch\n*** Add File: Cargo.lock\n+x\n*** End Patch";
+    let arguments = json!({ "command": format!("apply_patch <<'EOF'\n{add}\nEOF\n") }).to_string();
+    let targets = patch_targets("shell_command", &ToolPayload::Function { arguments }, Path::new("/repo"));
+    assert_eq!(targets, vec![PathBuf::from("/repo/Cargo.lock")]);
+  }
+}
@@ -0,0 +1,184 @@
+// ============================================================================
+// Permission profiles (permissionProfile)
+// ============================================================================
+//
+// Named bundles of sandbox mode, approval mode, writable roots and tool
+// allow/deny lists. Products define them once per process with
+// definePermissionProfile() and select one per run with `permissionProfile`;
+// redefining a name updates every later run that selects it. Sandbox mode,
+// approval mode and writable roots set directly on the request win over the
+// profile's. The request's own `allowedTools`/`deniedTools` combine with the
+// profile's: a tool must be allowed by every allow list that is set and denied
+// by none. Tool lists are applied when the run's tool registry is built, so
+// filtered tools are never offered to the model and calls to them are
+// rejected.
+
+#[derive(Clone, Debug, Default)]
+#[napi(object)]
+pub struct PermissionProfile {
+  #[napi(js_name = "sandboxMode")]
+  pub sandbox_mode: Option<String>,
+  #[napi(js_name = "approvalMode")]
+  pub approval_mode: Option<String>,
+  /// When set, only these builtin and registered tools are exposed to the model.
+  #[napi(js_name = "allowedTools")]
+  pub allowed_tools: Option<Vec<String>>,
+  /// Tools never exposed to the model. Wins over `allowedTools`.
+  #[napi(js_name = "deniedTools")]
+  pub denied_tools: Option<Vec<String>>,
+  /// Extra writable directories for workspace-write mode.
+  #[napi(js_name = "writableRoots")]
+  pub writable_roots: Option<Vec<String>>,
+}
+
+fn permission_profiles() -> &'static Mutex<HashMap<String, PermissionProfile>> {
+  static PROFILES: OnceLock<Mutex<HashMap<String, PermissionProfile>>> = OnceLock::new();
+  PROFILES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[napi]
+pub fn define_permission_profile(name: String, profile: PermissionProfile) -> napi::Result<()> {
+  if name.trim().is_empty() {
+    return Err(napi::Error::from_reason(
+      "Permission profile name must not be empty".to_string(),
+    ));
+  }
+  parse_sandbox_mode(profile.sandbox_mode.as_deref())?;
+  parse_approval_mode(profile.approval_mode.as_deref())?;
+  permission_profiles()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("permission profiles mutex poisoned: {e}")))?
+    .insert(name, profile);
+  Ok(())
+}
+
+#[napi]
+pub fn clear_permission_profiles() -> napi::Result<()> {
+  permission_profiles()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("permission profiles mutex poisoned: {e}")))?
+    .clear();
+  Ok(())
+}
+
+fn permission_profile(name: &str) -> napi::Result<PermissionProfile> {
+  let profiles = permission_profiles()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("permission profiles mutex poisoned: {e}")))?;
+  profiles.get(name).cloned().ok_or_else(|| {
+    let mut known: Vec<&str> = profiles.keys().map(String::as_str).collect();
+    known.sort_unstable();
+    napi::Error::from_reason(format!(
+      "Unknown permission profile: {name}. Defined profiles: {}",
+      if known.is_empty() {
+        "none".to_string()
+      } else {
+        known.join(", ")
+      }
+    ))
+  })
+}
+
+impl RunRequest {
+  /// Fills sandbox mode, approval mode and writable roots the request leaves unset from its
+  /// permission profile, and returns the tool filter for the run.
+  fn resolve_permissions(&mut self) -> napi::Result<Option<ExternalToolFilter>> {
+    let request_allowed = self.allowed_tools.take();
+    let request_denied = self.denied_tools.take().unwrap_or_default();
+    let Some(name) = self.permission_profile.as_deref() else {
+      return Ok(tool_filter(
+        &[request_allowed.as_deref()],
+        &[&request_denied],
+      ));
+    };
+    let profile = permission_profile(name)?;
+    if self.sandbox_mode.is_none() {
+      self.sandbox_mode = profile.sandbox_mode;
+    }
+    if self.approval_mode.is_none() {
+      self.approval_mode = profile.approval_mode;
+    }
+    if let Some(roots) = profile.writable_roots {
+      let options = self
+        .workspace_write_options
+        .get_or_insert_with(|| WorkspaceWriteOptions {
+          network_access: None,
+          writable_roots: None,
+          exclude_tmpdir_env_var: None,
+          exclude_slash_tmp: None,
+        });
+      options.writable_roots.get_or_insert(roots);
+    }
+    let profile_denied = profile.denied_tools.unwrap_or_default();
+    Ok(tool_filter(
+      &[profile.allowed_tools.as_deref(), request_allowed.as_deref()],
+      &[&profile_denied, &request_denied],
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests_permissions {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn shell_names_stand_for_every_shell_tool() {
+    let filter = tool_filter(
+      &[Some(
+        &["read_file".to_string(), "apply_patch".to_string()][..],
+      )],
+      &[&["local_shell".to_string()]],
+    )
+    .unwrap();
+    assert_eq!(
+      filter.allowed,
+      Some(vec!["apply_patch".to_string(), "read_file".to_string()])
+    );
+    assert!(filter.permits("read_file"));
+    assert!(!filter.permits("exec_command"));
+    assert!(!filter.permits("shell_command"));
+    assert!(!filter.permits("web_search"));
+    assert_eq!(tool_filter(&[None, None], &[&[], &[]]), None);
+  }
+
+  #[test]
+  fn request_tool_lists_narrow_the_profile() {
+    let profile_allowed = vec!["read_file".to_string(), "shell".to_string()];
+    let request_allowed = vec![
+      "read_file".to_string(),
+      "local_shell".to_string(),
+      "web_search".to_string(),
+    ];
+    let filter = tool_filter(
+      &[Some(&profile_allowed[..]), Some(&request_allowed[..])],
+      &[&[], &["exec_command".to_string()]],
+    )
+    .unwrap();
+    assert!(filter.permits("read_file"));
+    assert!(!filter.permits("web_search"));
+    assert!(!filter.permits("shell"));
+    assert!(!filter.permits("apply_patch"));
+  }
+
+  #[test]
+  fn unknown_profiles_and_modes_are_rejected() {
+    let err = define_permission_profile(
+      "tests-permissions-bad-mode".to_string(),
+      PermissionProfile {
+        sandbox_mode: Some("everything".to_string()),
+        ..Default::default()
+      },
+    )
+    .unwrap_err();
+    assert!(err.reason.contains("sandbox mode"), "{}", err.reason);
+    let err = permission_profile("tests-permissions-missing").unwrap_err();
+    assert!(
+      err
+        .reason
+        .starts_with("Unknown permission profile: tests-permissions-missing."),
+      "{}",
+      err.reason
+    );
+  }
+}
@@ -0,0 +1,488 @@
+// ============================================================================
+// Post-turn checks (postTurnChecks)
+// ============================================================================
+//
+// After every turn that applied file changes, run the configured commands in
+// the working directory. Failures either start a follow-up turn on the same
+// thread with the check output (`feedback`), restore the working tree to its
+// pre-turn snapshot (`revert`), or fail the run (`stop`). Each check result is
+// emitted as a completed `command_execution` item so callers see it inline.
+
+const DEFAULT_MAX_CHECK_FEEDBACK_TURNS: u32 = 3;
+const DEFAULT_POST_TURN_CHECK_TIMEOUT_MS: u32 = 10 * 60 * 1000;
+/// Tail of the check output kept for events and feedback prompts.
+const POST_TURN_CHECK_OUTPUT_LIMIT: usize = 8 * 1024;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct PostTurnCheck {
+  /// Shell command to run, e.g. `cargo test` or `npm run lint`.
+  pub command: String,
+  /// Label used in events and feedback; defaults to the command.
+  pub name: Option<String>,
+  #[napi(js_name = "onFail", ts_type = "\"feedback\" | \"revert\" | \"stop\"")]
+  pub on_fail: Option<String>,
+  #[napi(js_name = "timeoutMs")]
+  pub timeout_ms: Option<u32>,
+}
+
+/// Ordered by severity: when several checks fail, the most severe action wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PostTurnFailAction {
+  Feedback,
+  Revert,
+  Stop,
+}
+
+#[derive(Clone, Debug)]
+pub struct InternalPostTurnCheck {
+  name: String,
+  command: String,
+  on_fail: PostTurnFailAction,
+  timeout: std::time::Duration,
+}
+
+fn parse_post_turn_checks(
+  checks: Option<Vec<PostTurnCheck>>,
+) -> napi::Result<Vec<InternalPostTurnCheck>> {
+  checks
+    .unwrap_or_default()
+    .into_iter()
+    .map(|check| {
+      if check.command.trim().is_empty() {
+        return Err(napi::Error::from_reason(
+          "postTurnChecks entries require a non-empty command".to_string(),
+        ));
+      }
+      let on_fail = match check.on_fail.as_deref() {
+        None | Some("feedback") => PostTurnFailAction::Feedback,
+        Some("revert") => PostTurnFailAction::Revert,
+        Some("stop") => PostTurnFailAction::Stop,
+        Some(other) => {
+          return Err(napi::Error::from_reason(format!(
+            "Unsupported postTurnChecks onFail: {other}. Expected feedback, revert or stop"
+          )));
+        }
+      };
+      Ok(InternalPostTurnCheck {
+        name: check.name.unwrap_or_else(|| check.command.clone()),
+        command: check.command,
+        on_fail,
+        timeout: std::time::Duration::from_millis(u64::from(
+          check
+            .timeout_ms
+            .unwrap_or(DEFAULT_POST_TURN_CHECK_TIMEOUT_MS),
+        )),
+      })
+    })
+    .collect()
+}
+
+#[derive(Debug)]
+struct PostTurnCheckOutcome {
+  exit_code: Option<i32>,
+  output: String,
+  timed_out: bool,
+}
+
+impl PostTurnCheckOutcome {
+  fn passed(&self) -> bool {
+    !self.timed_out && self.exit_code == Some(0)
+  }
+
+  fn status_label(&self) -> String {
+    match (self.timed_out, self.exit_code) {
+      (true, _) => "timed out".to_string(),
+      (false, Some(code)) => format!("exit code {code}"),
+      (false, None) => "terminated by signal".to_string(),
+    }
+  }
+}
+
+fn tail_of_output(output: &str) -> String {
+  if output.len() <= POST_TURN_CHECK_OUTPUT_LIMIT {
+    return output.to_string();
+  }
+  let mut start = output.len() - POST_TURN_CHECK_OUTPUT_LIMIT;
+  while !output.is_char_boundary(start) {
+    start += 1;
+  }
+  format!("[... truncated ...]\n{}", &output[start..])
+}
+
+fn run_post_turn_check(
+  check: &InternalPostTurnCheck,
+  cwd: &Path,
+) -> std::io::Result<PostTurnCheckOutcome> {
+  use std::io::Read;
+  use std::process::Stdio;
+
+  let mut command = if cfg!(windows) {
+    let mut command = std::process::Command::new("cmd");
+    command.arg("/C").arg(&check.command);
+    command
+  } else {
+    let mut command = std::process::Command::new("sh");
+    command.arg("-c").arg(&check.command);
+    command
+  };
+  let mut child = command
+    .current_dir(cwd)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()?;
+
+  let readers: Vec<std::thread::JoinHandle<Vec<u8>>> = [
+    child
+      .stdout
+      .take()
+      .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+    child
+      .stderr
+      .take()
+      .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+  ]
+  .into_iter()
+  .flatten()
+  .map(|mut pipe| {
+    std::thread::spawn(move || {
+      let mut buf = Vec::new();
+      let _ = pipe.read_to_end(&mut buf);
+      buf
+    })
+  })
+  .collect();
+
+  let started = std::time::Instant::now();
+  let mut timed_out = false;
+  let status = loop {
+    if let Some(status) = child.try_wait()? {
+      break status;
+    }
+    if started.elapsed() >= check.timeout {
+      timed_out = true;
+      let _ = child.kill();
+      break child.wait()?;
+    }
+    std::thread::sleep(std::time::Duration::from_millis(50));
+  };
+
+  let mut output = String::new();
+  for reader in readers {
+    if let Ok(bytes) = reader.join() {
+      output.push_str(&String::from_utf8_lossy(&bytes));
+    }
+  }
+  Ok(PostTurnCheckOutcome {
+    exit_code: status.code(),
+    output: tail_of_output(&output),
+    timed_out,
+  })
+}
+
+/// What the wrapped handler saw during one turn.
+#[derive(Clone, Debug, Default)]
+struct PostTurnObservation {
+  thread_id: Option<String>,
+  modified_files: bool,
+}
+
+impl PostTurnObservation {
+  fn observe(&mut self, event: &ExecThreadEvent) {
+    use codex_exec::exec_events::PatchApplyStatus;
+    use codex_exec::exec_events::ThreadItemDetails;
+
+    match event {
+      ExecThreadEvent::ThreadStarted(started) => self.thread_id = Some(started.thread_id.clone()),
+      ExecThreadEvent::ItemCompleted(completed) => {
+        if let ThreadItemDetails::FileChange(change) = &completed.item.details
+          && change.status == PatchApplyStatus::Completed
+          && !change.changes.is_empty()
+        {
+          self.modified_files = true;
+        }
+      }
+      ExecThreadEvent::TurnStarted(_)
+      | ExecThreadEvent::TurnCompleted(_)
+      | ExecThreadEvent::TurnFailed(_)
+      | ExecThreadEvent::ItemStarted(_)
+      | ExecThreadEvent::ItemUpdated(_)
+      | ExecThreadEvent::Error(_)
+      | ExecThreadEvent::ExitedReviewMode(_)
+      | ExecThreadEvent::BackgroundEvent(_)
+      | ExecThreadEvent::ToolOutputChunk(_)
+      | ExecThreadEvent::BackgroundTaskStarted(_)
+      | ExecThreadEvent::BackgroundTaskCompleted(_)
+      | ExecThreadEvent::PlanUpdated(_)
+      | ExecThreadEvent::ThreadStatus(_)
+      | ExecThreadEvent::TestResults(_)
+      | ExecThreadEvent::VerificationFailed(_)
+      | ExecThreadEvent::FilesTouched(_)
+      | ExecThreadEvent::CommandStarted(_)
+      | ExecThreadEvent::CommandCompleted(_)
+      | ExecThreadEvent::Raw(_) => {}
+    }
+  }
+}
+
+fn post_turn_check_event(
+  id: String,
+  check: &InternalPostTurnCheck,
+  outcome: &PostTurnCheckOutcome,
+) -> ExecThreadEvent {
+  use codex_exec::exec_events::CommandExecutionItem;
+  use codex_exec::exec_events::CommandExecutionStatus;
+
+  ExecThreadEvent::ItemCompleted(codex_exec::exec_events::ItemCompletedEvent {
+    item: codex_exec::exec_events::ThreadItem {
+      id,
+      details: codex_exec::exec_events::ThreadItemDetails::CommandExecution(CommandExecutionItem {
+        command: check.command.clone(),
+        aggregated_output: outcome.output.clone(),
+        exit_code: outcome.exit_code,
+        status: if outcome.passed() {
+          CommandExecutionStatus::Completed
+        } else {
+          CommandExecutionStatus::Failed
+        },
+        cache_hit: None,
+        is_tty: None,
+      }),
+    },
+  })
+}
+
+fn post_turn_background_event(message: String) -> ExecThreadEvent {
+  ExecThreadEvent::BackgroundEvent(BackgroundEventEvent { message })
+}
+
+/// Follow-up prompt describing every failed check.
+fn post_turn_feedback_prompt(
+  failures: &[(&InternalPostTurnCheck, &PostTurnCheckOutcome)],
+) -> String {
+  let mut prompt = String::from(
+    "<post_turn_checks>\nThe following checks failed after your last changes. Fix the problems, \
+     then finish your turn; the checks will run again automatically.\n",
+  );
+  for (check, outcome) in failures {
+    prompt.push_str(&format!(
+      "\n## {} ({})\nCommand: `{}`\n```\n{}\n```\n",
+      check.name,
+      outcome.status_label(),
+      check.command,
+      outcome.output.trim_end()
+    ));
+  }
+  prompt.push_str("</post_turn_checks>");
+  prompt
+}
+
+fn post_turn_feedback_request(
+  options: &InternalRunRequest,
+  thread_id: String,
+  prompt: String,
+) -> InternalRunRequest {
+  InternalRunRequest {
+    prompt,
+    input_items: None,
+    thread_id: Some(thread_id),
+    images: Vec::new(),
+    review_request: None,
+    // Dynamic tools are registered when the thread starts; cassettes cover the first turn only.
+    dynamic_tools: None,
+    record: None,
+    replay: None,
+    ..options.clone()
+  }
+}
+
+fn dispatch_post_turn_event<F>(handler: &Mutex<F>, event: ExecThreadEvent)
+where
+  F: FnMut(ExecThreadEvent),
+{
+  if let Ok(mut handler) = handler.lock() {
+    (*handler)(event);
+  }
+}
+
+/// `run_internal_sync`, followed by the configured post-turn checks after each turn that
+/// changed files.
+fn run_with_post_turn_checks<F>(options: InternalRunRequest, handler: F) -> napi::Result<()>
+where
+  F: FnMut(ExecThreadEvent) + Send + 'static,
+{
+  if options.post_turn_checks.is_empty() {
+    return run_internal_sync(options, handler);
+  }
+  let cwd = match options.working_directory.clone() {
+    Some(dir) => dir,
+    None => std::env::current_dir()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve working directory: {e}")))?,
+  };
+  let wants_snapshot = options
+    .post_turn_checks
+    .iter()
+    .any(|check| check.on_fail == PostTurnFailAction::Revert);
+  let handler = Arc::new(Mutex::new(handler));
+  let mut options = options;
+  let mut feedback_turns = 0;
+
+  loop {
+    let snapshot = if wants_snapshot {
+      let snapshot = codex_git::create_ghost_commit(&codex_git::CreateGhostCommitOptions::new(
+        &cwd,
+      ))
+      .map_err(|e| {
+        napi::Error::from_reason(format!(
+          "postTurnChecks with onFail \"revert\" require a git working tree: {e}"
+        ))
+      })?;
+      Some(snapshot)
+    } else {
+      None
+    };
+
+    let observation = Arc::new(Mutex::new(PostTurnObservation::default()));
+    let turn_observation = Arc::clone(&observation);
+    let turn_handler = Arc::clone(&handler);
+    run_internal_sync(options.clone(), move |event| {
+      if let Ok(mut observation) = turn_observation.lock() {
+        observation.observe(&event);
+      }
+      dispatch_post_turn_event(&turn_handler, event);
+    })?;
+    let observation = observation
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("post-turn observation poisoned: {e}")))?
+      .clone();
+    if !observation.modified_files {
+      return Ok(());
+    }
+
+    let checks = options.post_turn_checks.clone();
+    let mut outcomes = Vec::with_capacity(checks.len());
+    for (index, check) in checks.iter().enumerate() {
+      let outcome = run_post_turn_check(check, &cwd).map_err(|e| {
+        napi::Error::from_reason(format!("Failed to run post-turn check {}: {e}", check.name))
+      })?;
+      dispatch_post_turn_event(
+        &handler,
+        post_turn_check_event(
+          format!("post_turn_check_{feedback_turns}_{index}"),
+          check,
+          &outcome,
+        ),
+      );
+      outcomes.push(outcome);
+    }
+    let failures: Vec<(&InternalPostTurnCheck, &PostTurnCheckOutcome)> = checks
+      .iter()
+      .zip(outcomes.iter())
+      .filter(|(_, outcome)| !outcome.passed())
+      .collect();
+    let Some(action) = failures.iter().map(|(check, _)| check.on_fail).max() else {
+      return Ok(());
+    };
+    let summary = failures
+      .iter()
+      .map(|(check, outcome)| format!("{} ({})", check.name, outcome.status_label()))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    match action {
+      PostTurnFailAction::Stop => {
+        return Err(napi::Error::from_reason(format!(
+          "Post-turn checks failed: {summary}"
+        )));
+      }
+      PostTurnFailAction::Revert => {
+        if let Some(snapshot) = snapshot.as_ref() {
+          codex_git::restore_ghost_commit(&cwd, snapshot).map_err(|e| {
+            napi::Error::from_reason(format!(
+              "Failed to revert changes after post-turn checks: {e}"
+            ))
+          })?;
+        }
+        dispatch_post_turn_event(
+          &handler,
+          post_turn_background_event(format!(
+            "Post-turn checks failed ({summary}); reverted this turn's changes"
+          )),
+        );
+        return Ok(());
+      }
+      PostTurnFailAction::Feedback => {
+        if feedback_turns >= options.max_check_feedback_turns {
+          dispatch_post_turn_event(
+            &handler,
+            post_turn_background_event(format!(
+              "Post-turn checks still failing after {feedback_turns} feedback turns: {summary}"
+            )),
+          );
+          return Ok(());
+        }
+        let thread_id = observation
+          .thread_id
+          .or_else(|| options.thread_id.clone())
+          .ok_or_else(|| {
+            napi::Error::from_reason("Post-turn checks could not resolve the thread id".to_string())
+          })?;
+        feedback_turns += 1;
+        options =
+          post_turn_feedback_request(&options, thread_id, post_turn_feedback_prompt(&failures));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests_post_turn_checks {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parses_actions_and_rejects_unknown_ones() {
+    let checks = parse_post_turn_checks(Some(vec![PostTurnCheck {
+      command: "cargo test".to_string(),
+      name: None,
+      on_fail: Some("revert".to_string()),
+      timeout_ms: None,
+    }]))
+    .unwrap();
+    assert_eq!(checks[0].name, "cargo test");
+    assert_eq!(checks[0].on_fail, PostTurnFailAction::Revert);
+
+    let err = parse_post_turn_checks(Some(vec![PostTurnCheck {
+      command: "cargo test".to_string(),
+      name: None,
+      on_fail: Some("retry".to_string()),
+      timeout_ms: None,
+    }]))
+    .unwrap_err();
+    assert!(err.reason.contains("retry"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn runs_check_and_builds_feedback_prompt() {
+    let check = parse_post_turn_checks(Some(vec![PostTurnCheck {
+      command: "echo boom; exit 3".to_string(),
+      name: Some("tests".to_string()),
+      on_fail: None,
+      timeout_ms: None,
+    }]))
+    .unwrap()
+    .remove(0);
+    let dir = tempfile::tempdir().unwrap();
+    let outcome = run_post_turn_check(&check, dir.path()).unwrap();
+    assert!(!outcome.passed());
+    assert_eq!(outcome.exit_code, Some(3));
+    assert_eq!(outcome.output, "boom\n");
+
+    let prompt = post_turn_feedback_prompt(&[(&check, &outcome)]);
+    assert!(prompt.contains("## tests (exit code 3)"));
+    assert!(prompt.contains("Command: `echo boom; exit 3`"));
+  }
+}
@@ -0,0 +1,149 @@
+// ============================================================================
+// Offline prompt construction (buildPrompt)
+// ============================================================================
+
+const DEFAULT_PROMPT_REVERIE_LIMIT: i32 = 3;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct BuildPromptOptions {
+  /// When set, keyword-search past conversations and inject matching insights as context.
+  #[napi(js_name = "reverieQuery")]
+  pub reverie_query: Option<String>,
+  #[napi(js_name = "reverieLimit")]
+  pub reverie_limit: Option<i32>,
+}
+
+#[napi(object)]
+pub struct PromptPreviewResult {
+  pub model: String,
+  pub instructions: String,
+  pub input: Vec<JsonValue>,
+  pub tools: Vec<JsonValue>,
+  #[napi(js_name = "parallelToolCalls")]
+  pub parallel_tool_calls: bool,
+  #[napi(js_name = "outputSchema")]
+  pub output_schema: Option<JsonValue>,
+  /// Number of reverie insights injected ahead of the user turn.
+  #[napi(js_name = "reverieInsights")]
+  pub reverie_insights: u32,
+}
+
+fn user_turn_items(options: &InternalRunRequest) -> Vec<UserInput> {
+  if let Some(items) = options.input_items.clone() {
+    return items;
+  }
+  let mut items: Vec<UserInput> = options
+    .images
+    .iter()
+    .cloned()
+    .map(|path| UserInput::LocalImage { path })
+    .collect();
+  if !options.prompt.is_empty() {
+    items.push(UserInput::Text {
+      text: options.prompt.clone(),
+      text_elements: Vec::new(),
+    });
+  }
+  items
+}
+
+fn reverie_context_message(insights: &[String]) -> codex_protocol::models::ResponseItem {
+  let body = insights
+    .iter()
+    .map(|insight| format!("- {insight}"))
+    .collect::<Vec<_>>()
+    .join("\n");
+  codex_protocol::models::DeveloperInstructions::new(format!(
+    "<reverie_memories>\n{body}\n</reverie_memories>"
+  ))
+  .into()
+}
+
+/// Build the exact first-turn instructions, input and tool specs a run would send, offline.
+#[napi]
+pub async fn build_prompt(
+  req: RunRequest,
+  options: Option<BuildPromptOptions>,
+) -> napi::Result<PromptPreviewResult> {
+  let options_ext = options.unwrap_or_default();
+  let mut internal = req.into_internal()?;
+  inject_project_memories(&mut internal).await?;
+  let mut config = load_config_from_internal(&internal).await?;
+
+  let mut extra_context = Vec::new();
+  let mut reverie_insights = 0u32;
+  if let Some(query) = options_ext
+    .reverie_query
+    .as_deref()
+    .map(str::trim)
+    .filter(|query| !query.is_empty())
+  {
+    let results = reverie_search_conversations(
+      config.codex_home.to_string_lossy().into_owned(),
+      query.to_string(),
+      Some(options_ext.reverie_limit.unwrap_or(DEFAULT_PROMPT_REVERIE_LIMIT)),
+    )
+    .await?;
+    let insights: Vec<String> = results
+      .into_iter()
+      .flat_map(|result| result.insights)
+      .collect();
+    if !insights.is_empty() {
+      reverie_insights = insights.len() as u32;
+      extra_context.push(reverie_context_message(&insights));
+    }
+  }
+
+  // The same tool layers a run builds its router with, so allowedTools/deniedTools shape the
+  // previewed specs. Interceptors and escalation only act on calls, which a preview never makes.
+  config.external_tool_layers = ExecLayers {
+    external_tools: run_external_tools()?,
+    tool_filter: internal.tool_filter.clone(),
+    tool_limits: run_tool_limits(&internal)?,
+    ..Default::default()
+  }
+  .into_tool_layers();
+
+  let preview = codex_core::prompt_preview::build_prompt_preview(
+    &config,
+    user_turn_items(&internal),
+    extra_context,
+    internal.dynamic_tools.as_deref().unwrap_or_default(),
+    internal.output_schema.clone(),
+  )
+  .await
+  .map_err(|e| napi::Error::from_reason(format!("Failed to build prompt: {e}")))?;
+
+  let input = preview
+    .input
+    .iter()
+    .map(serde_json::to_value)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize prompt input: {e}")))?;
+
+  Ok(PromptPreviewResult {
+    model: preview.model,
+    instructions: preview.instructions,
+    input,
+    tools: preview.tools,
+    parallel_tool_calls: preview.parallel_tool_calls,
+    output_schema: preview.output_schema,
+    reverie_insights,
+  })
+}
+
+#[cfg(test)]
+mod tests_prompt_preview {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn reverie_insights_render_as_developer_context() {
+    let item = reverie_context_message(&["prefer pnpm".to_string(), "tests live in tests/".to_string()]);
+    assert_eq!(
+      serde_json::to_value(item).unwrap()["content"][0]["text"],
+      "<reverie_memories>\n- prefer pnpm\n- tests live in tests/\n</reverie_memories>"
+    );
+  }
+}
@@ -0,0 +1,568 @@
+// ============================================================================
+// Prompt templates (promptTemplate)
+// ============================================================================
+//
+// Named prompts registered once per process with registerPromptTemplate() and
+// rendered natively for runs that set `promptTemplate: { name, vars }`, in
+// place of `prompt`. The syntax is a handlebars subset: `{{path.to.var}}`,
+// `{{> partial}}` (another registered template, rendered with the current
+// variables), `{{#if path}}…{{else}}…{{/if}}`, `{{#each path}}…{{/each}}` with
+// `{{this}}` and `{{@index}}`, and `{{! comments }}`. Values are inserted
+// verbatim; nothing is HTML-escaped. A variable with no value is an error
+// rather than an empty string, so a renamed variable can't silently drop
+// context from a prompt.
+//
+// With `maxTokens` set, an over-budget render is retried with every inserted
+// value cut to the same number of tokens (ending in "…"), using the largest
+// cap that fits. Template text itself is never cut.
+
+/// Deepest chain of partials a render follows before assuming a cycle.
+const MAX_PARTIAL_DEPTH: usize = 16;
+
+const TRUNCATION_MARKER: &str = "…";
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateRequest {
+  /// Template registered with `registerPromptTemplate`.
+  pub name: String,
+  /// Values for the template's variables, usually an object.
+  pub vars: Option<JsonValue>,
+  /// Token budget for the rendered prompt, counted with the run model's tokenizer.
+  #[napi(js_name = "maxTokens")]
+  pub max_tokens: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplateNode {
+  Text(String),
+  Variable(String),
+  Partial(String),
+  If {
+    path: String,
+    then: Vec<TemplateNode>,
+    otherwise: Vec<TemplateNode>,
+  },
+  Each {
+    path: String,
+    body: Vec<TemplateNode>,
+  },
+}
+
+fn prompt_templates() -> &'static Mutex<HashMap<String, Arc<Vec<TemplateNode>>>> {
+  static TEMPLATES: OnceLock<Mutex<HashMap<String, Arc<Vec<TemplateNode>>>>> = OnceLock::new();
+  TEMPLATES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn prompt_templates_snapshot() -> napi::Result<HashMap<String, Arc<Vec<TemplateNode>>>> {
+  Ok(
+    prompt_templates()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("prompt templates mutex poisoned: {e}")))?
+      .clone(),
+  )
+}
+
+/// Registers or replaces a template. Syntax errors are reported here rather than at run time.
+#[napi]
+pub fn register_prompt_template(name: String, template: String) -> napi::Result<()> {
+  if name.trim().is_empty() {
+    return Err(napi::Error::from_reason(
+      "Prompt template name must not be empty".to_string(),
+    ));
+  }
+  let nodes = parse_prompt_template(&template)
+    .map_err(|err| napi::Error::from_reason(format!("Prompt template `{name}`: {err}")))?;
+  prompt_templates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("prompt templates mutex poisoned: {e}")))?
+    .insert(name, Arc::new(nodes));
+  Ok(())
+}
+
+#[napi]
+pub fn clear_prompt_templates() -> napi::Result<()> {
+  prompt_templates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("prompt templates mutex poisoned: {e}")))?
+    .clear();
+  Ok(())
+}
+
+/// Renders a template as a run with `promptTemplate` would, for previews and tests.
+#[napi]
+pub fn render_prompt_template(
+  request: PromptTemplateRequest,
+  model: Option<String>,
+) -> napi::Result<String> {
+  render_prompt_template_request(&request, model.as_deref())
+}
+
+enum TemplateTag<'a> {
+  Variable(&'a str),
+  Partial(&'a str),
+  Open { helper: &'a str, path: &'a str },
+  Else,
+  Close(&'a str),
+  Comment,
+}
+
+fn classify_tag(tag: &str) -> Result<TemplateTag<'_>, String> {
+  let tag = tag.trim();
+  if tag.starts_with('!') {
+    return Ok(TemplateTag::Comment);
+  }
+  if let Some(name) = tag.strip_prefix('>') {
+    let name = name.trim();
+    if name.is_empty() {
+      return Err("`{{>}}` needs a partial name".to_string());
+    }
+    return Ok(TemplateTag::Partial(name));
+  }
+  if let Some(rest) = tag.strip_prefix('#') {
+    let (helper, path) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let path = path.trim();
+    if path.is_empty() {
+      return Err(format!("`{{{{#{helper}}}}}` needs a variable"));
+    }
+    return Ok(TemplateTag::Open { helper, path });
+  }
+  if let Some(helper) = tag.strip_prefix('/') {
+    return Ok(TemplateTag::Close(helper.trim()));
+  }
+  if tag == "else" {
+    return Ok(TemplateTag::Else);
+  }
+  if tag.is_empty() {
+    return Err("empty `{{}}` tag".to_string());
+  }
+  Ok(TemplateTag::Variable(tag))
+}
+
+/// A block being parsed: its helper and path, the nodes seen so far, and for `#if` the
+/// nodes before `{{else}}` once one has been seen.
+struct OpenBlock {
+  helper: String,
+  path: String,
+  nodes: Vec<TemplateNode>,
+  then: Option<Vec<TemplateNode>>,
+}
+
+fn parse_prompt_template(template: &str) -> Result<Vec<TemplateNode>, String> {
+  let mut stack: Vec<OpenBlock> = Vec::new();
+  let mut nodes = Vec::new();
+  let mut rest = template;
+  while let Some(start) = rest.find("{{") {
+    if start > 0 {
+      nodes.push(TemplateNode::Text(rest[..start].to_string()));
+    }
+    let after = &rest[start + 2..];
+    // `{{!-- … --}}` comments may themselves contain `}}`.
+    let (tag, consumed) = if let Some(comment) = after.strip_prefix("!--") {
+      let end = comment
+        .find("--}}")
+        .ok_or_else(|| "unclosed `{{!--` comment".to_string())?;
+      ("!", 3 + end + 4)
+    } else {
+      let end = after
+        .find("}}")
+        .ok_or_else(|| "unclosed `{{` tag".to_string())?;
+      (&after[..end], end + 2)
+    };
+    rest = &after[consumed..];
+    match classify_tag(tag)? {
+      TemplateTag::Comment => {}
+      TemplateTag::Variable(path) => nodes.push(TemplateNode::Variable(path.to_string())),
+      TemplateTag::Partial(name) => nodes.push(TemplateNode::Partial(name.to_string())),
+      TemplateTag::Open { helper, path } => {
+        if helper != "if" && helper != "each" {
+          return Err(format!("unknown block helper `#{helper}`"));
+        }
+        stack.push(OpenBlock {
+          helper: helper.to_string(),
+          path: path.to_string(),
+          nodes: std::mem::take(&mut nodes),
+          then: None,
+        });
+      }
+      TemplateTag::Else => {
+        let block = stack
+          .last_mut()
+          .filter(|block| block.helper == "if" && block.then.is_none())
+          .ok_or_else(|| "`{{else}}` outside of `{{#if}}`".to_string())?;
+        block.then = Some(std::mem::take(&mut nodes));
+      }
+      TemplateTag::Close(helper) => {
+        let block = stack
+          .pop()
+          .ok_or_else(|| format!("`{{{{/{helper}}}}}` closes nothing"))?;
+        if block.helper != helper {
+          return Err(format!(
+            "`{{{{/{helper}}}}}` closes `{{{{#{}}}}}`",
+            block.helper
+          ));
+        }
+        let body = std::mem::replace(&mut nodes, block.nodes);
+        nodes.push(if block.helper == "each" {
+          TemplateNode::Each {
+            path: block.path,
+            body,
+          }
+        } else if let Some(then) = block.then {
+          TemplateNode::If {
+            path: block.path,
+            then,
+            otherwise: body,
+          }
+        } else {
+          TemplateNode::If {
+            path: block.path,
+            then: body,
+            otherwise: Vec::new(),
+          }
+        });
+      }
+    }
+  }
+  if let Some(block) = stack.last() {
+    return Err(format!(
+      "unclosed `{{{{#{} {}}}}}`",
+      block.helper, block.path
+    ));
+  }
+  if !rest.is_empty() {
+    nodes.push(TemplateNode::Text(rest.to_string()));
+  }
+  Ok(nodes)
+}
+
+/// One level of `{{#each}}`: the current item and its index.
+struct TemplateScope<'a> {
+  item: &'a JsonValue,
+  index: Option<usize>,
+}
+
+struct TemplateRenderer<'a> {
+  templates: &'a HashMap<String, Arc<Vec<TemplateNode>>>,
+  tokenizer: Option<&'a CoreBPE>,
+  /// Tokens every inserted value is cut to, when set.
+  cap: Option<usize>,
+  /// Token length of the longest inserted value, tracked when a tokenizer is set.
+  longest: usize,
+}
+
+impl TemplateRenderer<'_> {
+  fn lookup<'v>(&self, scopes: &[TemplateScope<'v>], path: &str) -> Option<&'v JsonValue> {
+    let innermost = scopes.last()?;
+    if path == "this" {
+      return Some(innermost.item);
+    }
+    let mut segments = path.split('.');
+    let first = segments.next()?;
+    let start = if first == "this" {
+      Some(innermost.item)
+    } else {
+      // Like a lexical scope: `{{name}}` inside `{{#each}}` falls back to outer values.
+      scopes.iter().rev().find_map(|scope| scope.item.get(first))
+    };
+    segments.try_fold(start?, |value, segment| match value {
+      JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+      _ => value.get(segment),
+    })
+  }
+
+  fn insert(&mut self, out: &mut String, value: &str) {
+    let Some(tokenizer) = self.tokenizer else {
+      out.push_str(value);
+      return;
+    };
+    let tokens = codex_native_core::tokenizer::encode(tokenizer, value, false);
+    self.longest = self.longest.max(tokens.len());
+    match self.cap {
+      Some(cap) if tokens.len() > cap => {
+        // A cut can land inside a multi-byte character; back off until the prefix decodes.
+        let prefix = (0..=cap)
+          .rev()
+          .find_map(|len| {
+            codex_native_core::tokenizer::decode(tokenizer, tokens[..len].to_vec()).ok()
+          })
+          .unwrap_or_default();
+        out.push_str(&prefix);
+        out.push_str(TRUNCATION_MARKER);
+      }
+      _ => out.push_str(value),
+    }
+  }
+
+  fn render(
+    &mut self,
+    nodes: &[TemplateNode],
+    scopes: &mut Vec<TemplateScope<'_>>,
+    depth: usize,
+    out: &mut String,
+  ) -> Result<(), String> {
+    for node in nodes {
+      match node {
+        TemplateNode::Text(text) => out.push_str(text),
+        TemplateNode::Variable(path) if path == "@index" => {
+          let index = scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.index)
+            .ok_or_else(|| "`{{@index}}` outside of `{{#each}}`".to_string())?;
+          out.push_str(&index.to_string());
+        }
+        TemplateNode::Variable(path) => {
+          let value = self
+            .lookup(scopes, path)
+            .ok_or_else(|| format!("no value for `{path}`"))?;
+          match value {
+            JsonValue::String(text) => self.insert(out, text),
+            JsonValue::Null => {}
+            JsonValue::Bool(_) | JsonValue::Number(_) => out.push_str(&value.to_string()),
+            JsonValue::Array(_) | JsonValue::Object(_) => self.insert(out, &value.to_string()),
+          }
+        }
+        TemplateNode::Partial(name) => {
+          if depth >= MAX_PARTIAL_DEPTH {
+            return Err(format!(
+              "partials nest deeper than {MAX_PARTIAL_DEPTH} at `{{{{> {name}}}}}`; is a partial including itself?"
+            ));
+          }
+          let partial = self
+            .templates
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("unknown partial `{name}`"))?;
+          self.render(&partial, scopes, depth + 1, out)?;
+        }
+        TemplateNode::If {
+          path,
+          then,
+          otherwise,
+        } => {
+          let branch = if self.lookup(scopes, path).is_some_and(template_truthy) {
+            then
+          } else {
+            otherwise
+          };
+          self.render(branch, scopes, depth, out)?;
+        }
+        TemplateNode::Each { path, body } => match self.lookup(scopes, path) {
+          Some(JsonValue::Array(items)) => {
+            for (index, item) in items.iter().enumerate() {
+              scopes.push(TemplateScope {
+                item,
+                index: Some(index),
+              });
+              let rendered = self.render(body, scopes, depth, out);
+              scopes.pop();
+              rendered?;
+            }
+          }
+          Some(JsonValue::Null) | None => {}
+          Some(_) => return Err(format!("`{{{{#each {path}}}}}` needs an array")),
+        },
+      }
+    }
+    Ok(())
+  }
+}
+
+fn template_truthy(value: &JsonValue) -> bool {
+  match value {
+    JsonValue::Null => false,
+    JsonValue::Bool(flag) => *flag,
+    JsonValue::Number(number) => number.as_f64().is_some_and(|n| n != 0.0),
+    JsonValue::String(text) => !text.is_empty(),
+    JsonValue::Array(items) => !items.is_empty(),
+    JsonValue::Object(map) => !map.is_empty(),
+  }
+}
+
+fn render_prompt_template_request(
+  request: &PromptTemplateRequest,
+  model: Option<&str>,
+) -> napi::Result<String> {
+  let name = request.name.as_str();
+  let templates = prompt_templates_snapshot()?;
+  let template = templates.get(name).cloned().ok_or_else(|| {
+    let mut known: Vec<&str> = templates.keys().map(String::as_str).collect();
+    known.sort_unstable();
+    napi::Error::from_reason(format!(
+      "Unknown prompt template: {name}. Registered templates: {}",
+      if known.is_empty() {
+        "none".to_string()
+      } else {
+        known.join(", ")
+      }
+    ))
+  })?;
+  let vars = request.vars.clone().unwrap_or(JsonValue::Null);
+  let tokenizer = match request.max_tokens {
+    // Models tiktoken doesn't know still get a budget, counted with the default encoding.
+    Some(_) => Some(build_tokenizer(model, None).or_else(|_| build_tokenizer(None, None))?),
+    None => None,
+  };
+  let render = |cap: Option<usize>| -> napi::Result<(String, usize)> {
+    let mut renderer = TemplateRenderer {
+      templates: &templates,
+      tokenizer: tokenizer.as_ref(),
+      cap,
+      longest: 0,
+    };
+    let mut out = String::new();
+    renderer
+      .render(
+        &template,
+        &mut vec![TemplateScope {
+          item: &vars,
+          index: None,
+        }],
+        0,
+        &mut out,
+      )
+      .map_err(|err| napi::Error::from_reason(format!("Prompt template `{name}`: {err}")))?;
+    Ok((out, renderer.longest))
+  };
+
+  let (full, longest) = render(None)?;
+  let (Some(budget), Some(tokenizer)) = (request.max_tokens, tokenizer.as_ref()) else {
+    return Ok(full);
+  };
+  let budget = budget as usize;
+  let count = |text: &str| codex_native_core::tokenizer::encode(tokenizer, text, false).len();
+  if count(&full) <= budget {
+    return Ok(full);
+  }
+  let (shortest, _) = render(Some(0))?;
+  let floor = count(&shortest);
+  if floor > budget {
+    return Err(napi::Error::from_reason(format!(
+      "Prompt template `{name}` needs {floor} tokens with every variable cut, over the maxTokens budget of {budget}"
+    )));
+  }
+  // The largest per-value cap whose render fits; rendered length only grows with the cap.
+  let (mut low, mut high) = (0, longest);
+  let mut best = shortest;
+  while low < high {
+    let mid = (low + high).div_ceil(2);
+    let (rendered, _) = render(Some(mid))?;
+    if count(&rendered) <= budget {
+      low = mid;
+      best = rendered;
+    } else {
+      high = mid - 1;
+    }
+  }
+  Ok(best)
+}
+
+impl RunRequest {
+  /// Replaces the prompt with the rendered `promptTemplate`, when one is set.
+  fn resolve_prompt_template(&mut self) -> napi::Result<()> {
+    let Some(template) = self.prompt_template.take() else {
+      return Ok(());
+    };
+    if !self.prompt.trim().is_empty() || self.input_items.is_some() {
+      return Err(napi::Error::from_reason(
+        "promptTemplate cannot be combined with prompt or inputItems".to_string(),
+      ));
+    }
+    self.prompt = render_prompt_template_request(&template, self.model.as_deref())?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests_prompt_templates {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn render(name: &str, vars: JsonValue, max_tokens: Option<u32>) -> napi::Result<String> {
+    render_prompt_template_request(
+      &PromptTemplateRequest {
+        name: name.to_string(),
+        vars: Some(vars),
+        max_tokens,
+      },
+      None,
+    )
+  }
+
+  #[test]
+  fn renders_variables_blocks_and_partials() {
+    register_prompt_template(
+      "tests-templates-file".to_string(),
+      "- {{path}}{{#if note}} ({{note}}){{/if}}\n".to_string(),
+    )
+    .unwrap();
+    register_prompt_template(
+      "tests-templates-review".to_string(),
+      "{{! reviewer prompt }}Review for {{repo.name}}:\n{{#each files}}{{> tests-templates-file}}{{/each}}{{#if files}}{{else}}nothing{{/if}}"
+        .to_string(),
+    )
+    .unwrap();
+    let rendered = render(
+      "tests-templates-review",
+      serde_json::json!({
+        "repo": { "name": "codex" },
+        "files": [{ "path": "a.rs", "note": "new" }, { "path": "b.rs" }],
+      }),
+      None,
+    )
+    .unwrap();
+    assert_eq!(rendered, "Review for codex:\n- a.rs (new)\n- b.rs\n");
+
+    let err = render("tests-templates-review", serde_json::json!({}), None).unwrap_err();
+    assert_eq!(
+      err.reason,
+      "Prompt template `tests-templates-review`: no value for `repo.name`"
+    );
+    let err = register_prompt_template(
+      "tests-templates-broken".to_string(),
+      "{{#each items}}{{this}}{{/if}}".to_string(),
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.reason,
+      "Prompt template `tests-templates-broken`: `{{/if}}` closes `{{#each}}`"
+    );
+  }
+
+  #[test]
+  fn truncates_values_to_fit_the_token_budget() {
+    register_prompt_template(
+      "tests-templates-budget".to_string(),
+      "Summarize:\n{{log}}\nEnd.".to_string(),
+    )
+    .unwrap();
+    let log = "error: build failed at step ".repeat(50);
+    let rendered = render(
+      "tests-templates-budget",
+      serde_json::json!({ "log": log }),
+      Some(40),
+    )
+    .unwrap();
+    let tokenizer = build_tokenizer(None, None).unwrap();
+    assert!(
+      tokenizer.encode_ordinary(&rendered).len() <= 40,
+      "{rendered}"
+    );
+    assert!(rendered.starts_with("Summarize:\nerror: build failed"));
+    assert!(rendered.ends_with("…\nEnd."), "{rendered}");
+
+    let err = render(
+      "tests-templates-budget",
+      serde_json::json!({ "log": log }),
+      Some(2),
+    )
+    .unwrap_err();
+    assert!(
+      err.reason.contains("over the maxTokens budget of 2"),
+      "{}",
+      err.reason
+    );
+  }
+}
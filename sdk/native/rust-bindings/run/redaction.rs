@@ -0,0 +1,92 @@
+// ============================================================================
+// Secret redaction (redaction)
+// ============================================================================
+//
+// Masks credentials before they leave the run. Tool outputs are scrubbed in an
+// interceptor wrapping every tool, so the model never sees the raw value;
+// command echoes and tool results are scrubbed in thread events; and the
+// thread's rollout is rewritten once the run ends, so resumed and replayed
+// sessions only ever load the masked text. Matches come from built-in patterns
+// for common API key formats, caller-supplied regexes, and an entropy check
+// for long random-looking tokens. Each `turn.completed` event reports how many
+// values were masked as `redaction_applied`. The redactor itself lives in
+// codex-native-core, so runs from every binding mask the same values.
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionOptions {
+  /// Defaults to true once `redaction` is set.
+  pub enabled: Option<bool>,
+  /// Extra regexes to mask alongside the built-in patterns. When a pattern has a capture
+  /// group, only the first group is masked.
+  pub patterns: Option<Vec<String>>,
+}
+
+/// The run's redactor, or `None` when redaction is not configured or explicitly disabled.
+fn run_secret_redactor(options: Option<&RedactionOptions>) -> napi::Result<Option<SecretRedactor>> {
+  let options = options.map(|options| codex_native_core::redaction::RedactionOptions {
+    enabled: options.enabled,
+    patterns: options.patterns.clone(),
+  });
+  SecretRedactor::compile(options.as_ref()).map_err(|err| napi::Error::from_reason(err.to_string()))
+}
+
+/// Rewrites the thread's persisted rollout with every secret masked.
+async fn redact_thread_rollout(
+  options: &InternalRunRequest,
+  thread_id: &str,
+  redactor: &SecretRedactor,
+) -> napi::Result<()> {
+  let config = load_config_from_internal(options).await?;
+  let Some(path) = find_thread_path_by_id_str(&config.codex_home, thread_id)
+    .await
+    .map_err(|e| {
+      napi::Error::from_reason(format!("Failed to locate rollout for redaction: {e}"))
+    })?
+  else {
+    return Ok(());
+  };
+  let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+  })?;
+  let (scrubbed, masked) = redact_rollout_lines(redactor, &contents);
+  if masked == 0 {
+    return Ok(());
+  }
+  let staging = path.with_extension("jsonl.redacting");
+  tokio::fs::write(&staging, scrubbed)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to write redacted rollout: {e}")))?;
+  tokio::fs::rename(&staging, &path).await.map_err(|e| {
+    napi::Error::from_reason(format!("Failed to replace rollout {}: {e}", path.display()))
+  })
+}
+
+#[cfg(test)]
+mod tests_redaction {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn run_options_compile_to_the_shared_redactor() {
+    let redactor = run_secret_redactor(Some(&RedactionOptions {
+      enabled: None,
+      patterns: Some(vec![r"internal-id=(\w+)".to_string()]),
+    }))
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      redactor.mask("GET /x?internal-id=abc123"),
+      ("GET /x?internal-id=[REDACTED]".to_string(), 1)
+    );
+    assert!(run_secret_redactor(None).unwrap().is_none());
+    let err = run_secret_redactor(Some(&RedactionOptions {
+      enabled: None,
+      patterns: Some(vec!["(".to_string()]),
+    }))
+    .err()
+    .unwrap();
+    assert!(err.reason.starts_with("Invalid redaction pattern \"(\""));
+  }
+}
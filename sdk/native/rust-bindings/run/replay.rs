@@ -0,0 +1,423 @@
+// ============================================================================
+// Golden-transcript replay (replayThread)
+// ============================================================================
+//
+// Re-derives the ThreadEvent stream from a recorded rollout without touching
+// the network or executing tools. Tool outputs come from the rollout unless a
+// mock is supplied (keyed by call id first, then tool name).
+
+const REPLAY_COMMAND_TOOLS: &[&str] = &["shell", "shell_command", "exec_command", "local_shell"];
+
+#[napi(object)]
+#[derive(Default)]
+pub struct ReplayThreadOptions {
+  /// Replacement tool outputs keyed by call id or tool name.
+  #[napi(js_name = "mockToolOutputs")]
+  pub mock_tool_outputs: Option<HashMap<String, String>>,
+}
+
+struct PendingReplayCall {
+  name: String,
+  arguments: JsonValue,
+}
+
+#[derive(Default)]
+struct RolloutReplayer {
+  events: Vec<ExecThreadEvent>,
+  next_item_id: u64,
+  turn_open: bool,
+  usage: codex_exec::exec_events::Usage,
+  pending_calls: HashMap<String, PendingReplayCall>,
+}
+
+impl RolloutReplayer {
+  fn next_item_id(&mut self) -> String {
+    let id = format!("item_{}", self.next_item_id);
+    self.next_item_id += 1;
+    id
+  }
+
+  fn complete_item(&mut self, details: codex_exec::exec_events::ThreadItemDetails) {
+    let item = codex_exec::exec_events::ThreadItem {
+      id: self.next_item_id(),
+      details,
+    };
+    self.events.push(ExecThreadEvent::ItemCompleted(
+      codex_exec::exec_events::ItemCompletedEvent { item },
+    ));
+  }
+
+  fn start_turn(&mut self) {
+    self.finish_turn();
+    self.turn_open = true;
+    self.events.push(ExecThreadEvent::TurnStarted(
+      codex_exec::exec_events::TurnStartedEvent::default(),
+    ));
+  }
+
+  fn finish_turn(&mut self) {
+    if std::mem::take(&mut self.turn_open) {
+      self.events.push(ExecThreadEvent::TurnCompleted(
+        codex_exec::exec_events::TurnCompletedEvent {
+          usage: self.usage.clone(),
+          redaction_applied: None,
+          dropped_events: None,
+        },
+      ));
+    }
+  }
+
+  fn handle_event_msg(&mut self, payload: &JsonValue) {
+    let text_field = |key: &str| {
+      payload
+        .get(key)
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string()
+    };
+    match payload.get("type").and_then(JsonValue::as_str) {
+      Some("user_message") => self.start_turn(),
+      Some("agent_message") => self.complete_item(
+        codex_exec::exec_events::ThreadItemDetails::AgentMessage(
+          codex_exec::exec_events::AgentMessageItem {
+            text: text_field("message"),
+          },
+        ),
+      ),
+      Some("agent_reasoning") => self.complete_item(
+        codex_exec::exec_events::ThreadItemDetails::Reasoning(
+          codex_exec::exec_events::ReasoningItem {
+            text: text_field("text"),
+          },
+        ),
+      ),
+      Some("token_count") => {
+        if let Some(total) = payload
+          .get("info")
+          .and_then(|info| info.get("total_token_usage"))
+        {
+          let count = |key: &str| total.get(key).and_then(JsonValue::as_i64).unwrap_or(0);
+          self.usage = codex_exec::exec_events::Usage {
+            input_tokens: count("input_tokens"),
+            cached_input_tokens: count("cached_input_tokens"),
+            output_tokens: count("output_tokens"),
+          };
+        }
+      }
+      Some("turn_aborted") => {
+        if std::mem::take(&mut self.turn_open) {
+          self.events.push(ExecThreadEvent::TurnFailed(
+            codex_exec::exec_events::TurnFailedEvent {
+              error: codex_exec::exec_events::ThreadErrorEvent {
+                message: format!("turn aborted: {}", text_field("reason")),
+              },
+            },
+          ));
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn handle_response_item(&mut self, payload: &JsonValue, mocks: &HashMap<String, String>) {
+    let call_id = payload
+      .get("call_id")
+      .and_then(JsonValue::as_str)
+      .unwrap_or_default()
+      .to_string();
+    match payload.get("type").and_then(JsonValue::as_str) {
+      Some("function_call") => {
+        let arguments = payload
+          .get("arguments")
+          .and_then(JsonValue::as_str)
+          .and_then(|raw| serde_json::from_str(raw).ok())
+          .unwrap_or(JsonValue::Null);
+        self.track_call(call_id, payload, arguments);
+      }
+      Some("custom_tool_call") => {
+        let arguments = payload.get("input").cloned().unwrap_or(JsonValue::Null);
+        self.track_call(call_id, payload, arguments);
+      }
+      Some("local_shell_call") => {
+        let arguments = payload
+          .get("action")
+          .cloned()
+          .unwrap_or(JsonValue::Null);
+        self.pending_calls.insert(
+          call_id,
+          PendingReplayCall {
+            name: "local_shell".to_string(),
+            arguments,
+          },
+        );
+      }
+      Some("function_call_output") | Some("custom_tool_call_output") => {
+        let Some(call) = self.pending_calls.remove(&call_id) else {
+          return;
+        };
+        let recorded = replay_output_text(payload.get("output"));
+        let output = mocks
+          .get(&call_id)
+          .or_else(|| mocks.get(&call.name))
+          .cloned()
+          .unwrap_or(recorded);
+        let details = replay_tool_item(&call, output);
+        self.complete_item(details);
+      }
+      _ => {}
+    }
+  }
+
+  fn track_call(&mut self, call_id: String, payload: &JsonValue, arguments: JsonValue) {
+    let name = payload
+      .get("name")
+      .and_then(JsonValue::as_str)
+      .unwrap_or_default()
+      .to_string();
+    self
+      .pending_calls
+      .insert(call_id, PendingReplayCall { name, arguments });
+  }
+}
+
+fn replay_output_text(output: Option<&JsonValue>) -> String {
+  match output {
+    Some(JsonValue::String(text)) => text.clone(),
+    Some(JsonValue::Array(parts)) => parts
+      .iter()
+      .filter_map(|part| part.get("text").and_then(JsonValue::as_str))
+      .collect::<Vec<_>>()
+      .join("\n"),
+    Some(JsonValue::Object(map)) => map
+      .get("content")
+      .and_then(JsonValue::as_str)
+      .map(str::to_string)
+      .unwrap_or_else(|| JsonValue::Object(map.clone()).to_string()),
+    Some(other) => other.to_string(),
+    None => String::new(),
+  }
+}
+
+fn replay_command_string(arguments: &JsonValue) -> String {
+  let command = arguments.get("command").or_else(|| arguments.get("cmd"));
+  match command {
+    Some(JsonValue::Array(parts)) => parts
+      .iter()
+      .filter_map(JsonValue::as_str)
+      .collect::<Vec<_>>()
+      .join(" "),
+    Some(JsonValue::String(command)) => command.clone(),
+    _ => String::new(),
+  }
+}
+
+fn replay_exit_code(output: &str) -> Option<i32> {
+  if let Ok(value) = serde_json::from_str::<JsonValue>(output)
+    && let Some(code) = value
+      .get("metadata")
+      .and_then(|meta| meta.get("exit_code"))
+      .and_then(JsonValue::as_i64)
+  {
+    return Some(code as i32);
+  }
+  output.lines().find_map(|line| {
+    line
+      .trim()
+      .strip_prefix("Exit code: ")
+      .or_else(|| line.trim().strip_prefix("Process exited with code "))
+      .and_then(|code| code.trim().parse().ok())
+  })
+}
+
+fn replay_patch_changes(patch: &str) -> Vec<codex_exec::exec_events::FileUpdateChange> {
+  use codex_exec::exec_events::PatchChangeKind;
+  patch
+    .lines()
+    .filter_map(|line| {
+      let (kind, path) = if let Some(path) = line.strip_prefix("*** Add File: ") {
+        (PatchChangeKind::Add, path)
+      } else if let Some(path) = line.strip_prefix("*** Delete File: ") {
+        (PatchChangeKind::Delete, path)
+      } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+        (PatchChangeKind::Update, path)
+      } else {
+        return None;
+      };
+      Some(codex_exec::exec_events::FileUpdateChange {
+        path: path.trim().to_string(),
+        kind,
+      })
+    })
+    .collect()
+}
+
+fn replay_tool_item(
+  call: &PendingReplayCall,
+  output: String,
+) -> codex_exec::exec_events::ThreadItemDetails {
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  if REPLAY_COMMAND_TOOLS.contains(&call.name.as_str()) {
+    let exit_code = replay_exit_code(&output);
+    let status = match exit_code {
+      Some(0) | None => codex_exec::exec_events::CommandExecutionStatus::Completed,
+      Some(_) => codex_exec::exec_events::CommandExecutionStatus::Failed,
+    };
+    return ThreadItemDetails::CommandExecution(codex_exec::exec_events::CommandExecutionItem {
+      command: replay_command_string(&call.arguments),
+      aggregated_output: output,
+      exit_code,
+      status,
+      cache_hit: None,
+      is_tty: None,
+    });
+  }
+
+  if call.name == "apply_patch" {
+    let patch = match &call.arguments {
+      JsonValue::String(patch) => patch.clone(),
+      other => other
+        .get("input")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string(),
+    };
+    return ThreadItemDetails::FileChange(codex_exec::exec_events::FileChangeItem {
+      changes: replay_patch_changes(&patch),
+      status: codex_exec::exec_events::PatchApplyStatus::Completed,
+    });
+  }
+
+  let (server, tool) = call
+    .name
+    .split_once("__")
+    .map(|(server, tool)| (server.to_string(), tool.to_string()))
+    .unwrap_or_else(|| (String::new(), call.name.clone()));
+  ThreadItemDetails::McpToolCall(codex_exec::exec_events::McpToolCallItem {
+    server,
+    tool,
+    arguments: call.arguments.clone(),
+    result: Some(codex_exec::exec_events::McpToolCallItemResult {
+      content: vec![json!({ "type": "text", "text": output })],
+      structured_content: None,
+    }),
+    error: None,
+    status: codex_exec::exec_events::McpToolCallStatus::Completed,
+    cache_hit: None,
+  })
+}
+
+/// Rebuild the ThreadEvent sequence recorded in a parsed rollout.
+fn replay_rollout_records(
+  rollout: &ParsedRollout,
+  mocks: &HashMap<String, String>,
+) -> napi::Result<Vec<ExecThreadEvent>> {
+  if let Some((line, err)) = rollout.invalid_lines.first() {
+    return Err(napi::Error::from_reason(format!("Invalid rollout line {line}: {err}")));
+  }
+  let mut replayer = RolloutReplayer::default();
+  for value in &rollout.records {
+    let payload = value.get("payload").unwrap_or(&JsonValue::Null);
+    match value.get("type").and_then(JsonValue::as_str) {
+      Some("session_meta") => {
+        if let Some(thread_id) = payload.get("id").and_then(JsonValue::as_str) {
+          let field = |name: &str| payload.get(name).and_then(JsonValue::as_str).map(str::to_string);
+          replayer.events.push(ExecThreadEvent::ThreadStarted(
+            codex_exec::exec_events::ThreadStartedEvent {
+              thread_id: thread_id.to_string(),
+              model_provider: field("model_provider"),
+              cwd: field("cwd"),
+              ..Default::default()
+            },
+          ));
+        }
+      }
+      Some("event_msg") => replayer.handle_event_msg(payload),
+      Some("response_item") => replayer.handle_response_item(payload, mocks),
+      _ => {}
+    }
+  }
+  replayer.finish_turn();
+  Ok(replayer.events)
+}
+
+/// Replay a recorded rollout through the event pipeline and return the ThreadEvents as JSON strings.
+#[napi]
+pub async fn replay_thread(
+  rollout_path: String,
+  options: Option<ReplayThreadOptions>,
+) -> napi::Result<Vec<String>> {
+  let mocks = options
+    .unwrap_or_default()
+    .mock_tool_outputs
+    .unwrap_or_default();
+  let rollout = load_cached_rollout(Path::new(&rollout_path))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read rollout {rollout_path}: {e}")))?;
+
+  let mut sequencer = ThreadEventSequencer::ephemeral();
+  replay_rollout_records(&rollout, &mocks)?
+    .iter()
+    .map(|event| {
+      let value = sequencer.stamp(event, event_to_json(event)?);
+      serde_json::to_string(&value).map_err(|e| napi::Error::from_reason(e.to_string()))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests_replay {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  const ROLLOUT: &str = concat!(
+    r#"{"timestamp":"t","type":"session_meta","payload":{"id":"thread-1","cwd":"/tmp"}}"#,
+    "\n",
+    r#"{"timestamp":"t","type":"event_msg","payload":{"type":"user_message","message":"list files"}}"#,
+    "\n",
+    r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"call_1"}}"#,
+    "\n",
+    r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"README.md"}}"#,
+    "\n",
+    r#"{"timestamp":"t","type":"event_msg","payload":{"type":"agent_message","message":"Done"}}"#,
+    "\n",
+    r#"{"timestamp":"t","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":10,"cached_input_tokens":2,"output_tokens":3}}}}"#,
+    "\n",
+  );
+
+  fn event_types(events: &[ExecThreadEvent]) -> Vec<String> {
+    events
+      .iter()
+      .map(|event| serde_json::to_value(event).unwrap()["type"].as_str().unwrap().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn replays_rollout_into_thread_events() {
+    let events = replay_rollout_records(&parse_rollout_contents(ROLLOUT), &HashMap::new()).unwrap();
+    assert_eq!(
+      event_types(&events),
+      vec![
+        "thread.started",
+        "turn.started",
+        "item.completed",
+        "item.completed",
+        "turn.completed",
+      ]
+    );
+    let started = serde_json::to_value(&events[0]).unwrap();
+    assert_eq!(started["cwd"], "/tmp");
+    let command = serde_json::to_value(&events[2]).unwrap();
+    assert_eq!(command["item"]["command"], "ls");
+    assert_eq!(command["item"]["aggregated_output"], "README.md");
+    let completed = serde_json::to_value(&events[4]).unwrap();
+    assert_eq!(completed["usage"]["input_tokens"], 10);
+  }
+
+  #[test]
+  fn mock_outputs_override_recorded_tool_results() {
+    let mocks = HashMap::from([("shell".to_string(), "mocked".to_string())]);
+    let events = replay_rollout_records(&parse_rollout_contents(ROLLOUT), &mocks).unwrap();
+    let command = serde_json::to_value(&events[2]).unwrap();
+    assert_eq!(command["item"]["aggregated_output"], "mocked");
+  }
+}
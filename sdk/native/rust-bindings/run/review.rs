@@ -0,0 +1,437 @@
+// ============================================================================
+// Structured review findings (runReview, parseReviewFindings)
+// ============================================================================
+//
+// Review runs end with `exited_review_mode`, whose findings are a flat list
+// with absolute paths and a numeric priority. ReviewFindings groups them by
+// file, maps priority to a severity, strips the `[P1]` title prefix and lifts
+// fenced `suggestion`/`diff` blocks out of the body as suggested patches.
+// runReview() starts a review of a diff or of the changes since a base ref
+// and returns the findings directly. reviewDiff() computes the diff itself,
+// from a base/head ref pair or a patch, so CI callers only pass refs.
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewComment {
+  pub title: String,
+  pub body: String,
+  /// `critical` (P0), `high` (P1), `medium` (P2) or `low` (P3 and below).
+  pub severity: String,
+  pub priority: i32,
+  pub confidence: f64,
+  #[napi(js_name = "startLine")]
+  pub start_line: i32,
+  #[napi(js_name = "endLine")]
+  pub end_line: i32,
+  /// Contents of the first fenced `suggestion`, `diff` or `patch` block in the body.
+  #[napi(js_name = "suggestedPatch")]
+  pub suggested_patch: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewFileComments {
+  pub path: String,
+  /// Most severe first, then by line.
+  pub comments: Vec<ReviewComment>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReviewFindings {
+  /// Files in the order their first finding was reported.
+  pub files: Vec<ReviewFileComments>,
+  #[napi(js_name = "overallCorrectness")]
+  pub overall_correctness: String,
+  #[napi(js_name = "overallExplanation")]
+  pub overall_explanation: String,
+  #[napi(js_name = "overallConfidence")]
+  pub overall_confidence: f64,
+}
+
+#[napi(object)]
+pub struct RunReviewRequest {
+  /// Unified diff to review. Exactly one of `diff` and `baseRef` is required.
+  pub diff: Option<String>,
+  /// Review the changes between this ref's merge base and the working tree.
+  #[napi(js_name = "baseRef")]
+  pub base_ref: Option<String>,
+  /// Label shown for the review, e.g. "changes against 'main'".
+  pub hint: Option<String>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[napi(object)]
+pub struct ReviewDiffRequest {
+  /// Repository to diff; defaults to `config.workingDirectory`, then the process cwd.
+  pub cwd: Option<String>,
+  /// Diff the changes since the merge base with this ref. Required unless `patchText` is set.
+  #[napi(js_name = "baseRef")]
+  pub base_ref: Option<String>,
+  /// Commit to review; the working tree when unset.
+  #[napi(js_name = "headRef")]
+  pub head_ref: Option<String>,
+  /// Unified diff to review instead of computing one from refs.
+  #[napi(js_name = "patchText")]
+  pub patch_text: Option<String>,
+  /// Project review guidelines appended to the prompt.
+  pub guidelines: Option<String>,
+  pub hint: Option<String>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[napi(object)]
+pub struct RunReviewResult {
+  /// Unset when the review ended without structured output.
+  pub findings: Option<ReviewFindings>,
+  #[napi(js_name = "finalMessage")]
+  pub final_message: String,
+  pub usage: Option<RunThreadUsage>,
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+}
+
+fn review_severity(priority: i32) -> &'static str {
+  match priority {
+    i32::MIN..=0 => "critical",
+    1 => "high",
+    2 => "medium",
+    _ => "low",
+  }
+}
+
+/// `title` without a leading `[P0]`..`[P3]` tag.
+fn strip_priority_tag(title: &str) -> &str {
+  let trimmed = title.trim();
+  if let Some(rest) = trimmed.strip_prefix("[P")
+    && let Some((digits, tail)) = rest.split_once(']')
+    && !digits.is_empty()
+    && digits.chars().all(|c| c.is_ascii_digit())
+  {
+    return tail.trim_start();
+  }
+  trimmed
+}
+
+fn suggested_patch(body: &str) -> Option<String> {
+  let mut lines = body.lines();
+  while let Some(line) = lines.next() {
+    let Some(info) = line.trim_start().strip_prefix("```") else {
+      continue;
+    };
+    if !matches!(info.trim(), "suggestion" | "diff" | "patch") {
+      continue;
+    }
+    let block: Vec<&str> = lines
+      .by_ref()
+      .take_while(|line| !line.trim_start().starts_with("```"))
+      .collect();
+    return Some(block.join("\n"));
+  }
+  None
+}
+
+fn review_findings_from_output(
+  output: &codex_exec::exec_events::ReviewOutputEvent,
+) -> ReviewFindings {
+  let mut files: Vec<ReviewFileComments> = Vec::new();
+  for finding in &output.findings {
+    let range = &finding.code_location.line_range;
+    let comment = ReviewComment {
+      title: strip_priority_tag(&finding.title).to_string(),
+      body: finding.body.clone(),
+      severity: review_severity(finding.priority).to_string(),
+      priority: finding.priority,
+      confidence: f64::from(finding.confidence_score),
+      start_line: range.start,
+      end_line: range.end.max(range.start),
+      suggested_patch: suggested_patch(&finding.body),
+    };
+    let path = &finding.code_location.absolute_file_path;
+    match files.iter_mut().find(|file| &file.path == path) {
+      Some(file) => file.comments.push(comment),
+      None => files.push(ReviewFileComments {
+        path: path.clone(),
+        comments: vec![comment],
+      }),
+    }
+  }
+  for file in &mut files {
+    file
+      .comments
+      .sort_by_key(|comment| (comment.priority, comment.start_line));
+  }
+  ReviewFindings {
+    files,
+    overall_correctness: output.overall_correctness.clone(),
+    overall_explanation: output.overall_explanation.clone(),
+    overall_confidence: f64::from(output.overall_confidence_score),
+  }
+}
+
+/// Parses review output JSON, optionally inside a fenced block or surrounded by prose.
+fn parse_review_output(text: &str) -> Option<codex_exec::exec_events::ReviewOutputEvent> {
+  let start = text.find('{')?;
+  let end = text.rfind('}')?;
+  (start < end)
+    .then(|| serde_json::from_str(&text[start..=end]).ok())
+    .flatten()
+}
+
+/// ReviewFindings from the review output format (`{ findings, overall_correctness, ... }`).
+#[napi]
+pub fn parse_review_findings(text: String) -> napi::Result<ReviewFindings> {
+  parse_review_output(&text)
+    .map(|output| review_findings_from_output(&output))
+    .ok_or_else(|| napi::Error::from_reason("Text does not contain review output JSON"))
+}
+
+fn diff_review_prompt(diff: &str) -> String {
+  format!(
+    "Review the code changes in the following diff and provide prioritized, actionable findings.\n\n```diff\n{}\n```",
+    diff.trim_end()
+  )
+}
+
+fn review_prompt(req: &RunReviewRequest) -> napi::Result<(String, String)> {
+  match (req.diff.as_deref(), req.base_ref.as_deref().map(str::trim)) {
+    (Some(diff), None) if !diff.trim().is_empty() => {
+      Ok((diff_review_prompt(diff), "diff".to_string()))
+    }
+    (None, Some(base_ref)) if !base_ref.is_empty() => Ok((
+      format!(
+        "Review the code changes against the base ref '{base_ref}'. Find the merge base with `git merge-base HEAD {base_ref}`, then run `git diff` against that SHA to see the changes to review. Provide prioritized, actionable findings."
+      ),
+      format!("changes against '{base_ref}'"),
+    )),
+    _ => Err(napi::Error::from_reason(
+      "runReview requires exactly one non-empty diff or baseRef",
+    )),
+  }
+}
+
+#[napi]
+pub async fn run_review(req: RunReviewRequest) -> napi::Result<RunReviewResult> {
+  let (prompt, default_hint) = review_prompt(&req)?;
+  run_review_prompt(prompt, req.hint.unwrap_or(default_hint), req.config).await
+}
+
+/// `cwd`, else the config's working directory, else the process cwd.
+fn repository_dir(
+  cwd: Option<&str>,
+  config: Option<&ConversationConfigRequest>,
+) -> napi::Result<PathBuf> {
+  cwd
+    .or_else(|| config?.working_directory.as_deref())
+    .map(PathBuf::from)
+    .map_or_else(std::env::current_dir, Ok)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve cwd: {e}")))
+}
+
+/// The diff `req` asks to review and the hint to show for it.
+fn review_diff_target(req: &ReviewDiffRequest) -> napi::Result<(String, String)> {
+  let base_ref = req
+    .base_ref
+    .as_deref()
+    .map(str::trim)
+    .filter(|r| !r.is_empty());
+  let head_ref = req
+    .head_ref
+    .as_deref()
+    .map(str::trim)
+    .filter(|r| !r.is_empty());
+  match (req.patch_text.as_deref(), base_ref) {
+    (Some(patch), None) => Ok((patch.to_string(), "patch".to_string())),
+    (None, Some(base_ref)) => {
+      let cwd = repository_dir(req.cwd.as_deref(), req.config.as_ref())?;
+      let diff = git_diff_since_merge_base(&cwd, base_ref, head_ref)
+        .map_err(|e| napi::Error::from_reason(format!("Failed to compute review diff: {e}")))?;
+      let hint = match head_ref {
+        Some(head_ref) => format!("changes between '{base_ref}' and '{head_ref}'"),
+        None => format!("changes against '{base_ref}'"),
+      };
+      Ok((diff, hint))
+    }
+    _ => Err(napi::Error::from_reason(
+      "reviewDiff requires exactly one of patchText or a non-empty baseRef",
+    )),
+  }
+}
+
+fn with_review_guidelines(prompt: String, guidelines: Option<&str>) -> String {
+  match guidelines.map(str::trim) {
+    Some(guidelines) if !guidelines.is_empty() => {
+      format!("{prompt}\n\nReview guidelines:\n{guidelines}")
+    }
+    _ => prompt,
+  }
+}
+
+/// Reviews a diff computed natively from refs, or a given patch. An empty diff
+/// returns no findings without starting a run.
+#[napi]
+pub async fn review_diff(req: ReviewDiffRequest) -> napi::Result<RunReviewResult> {
+  let (diff, default_hint) = review_diff_target(&req)?;
+  if diff.trim().is_empty() {
+    return Ok(RunReviewResult {
+      findings: Some(ReviewFindings::default()),
+      final_message: String::new(),
+      usage: None,
+      thread_id: None,
+    });
+  }
+  let prompt = with_review_guidelines(diff_review_prompt(&diff), req.guidelines.as_deref());
+  let mut config = req.config.unwrap_or_default();
+  if config.working_directory.is_none() {
+    config.working_directory = req.cwd;
+  }
+  run_review_prompt(prompt, req.hint.unwrap_or(default_hint), Some(config)).await
+}
+
+async fn run_review_prompt(
+  prompt: String,
+  hint: String,
+  config: Option<ConversationConfigRequest>,
+) -> napi::Result<RunReviewResult> {
+  let mut options = config.unwrap_or_default().into_internal_request()?;
+  options.review_request = Some(ReviewRequest {
+    prompt: prompt.clone(),
+    user_facing_hint: hint,
+  });
+  options.prompt = prompt;
+
+  let mut collector = tokio::task::spawn_blocking(move || collect_run(options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))??;
+  let review_output = collector.review_output.take();
+  let result = collector.finish()?;
+  let findings = review_output
+    .or_else(|| parse_review_output(&result.final_message))
+    .map(|output| review_findings_from_output(&output));
+  Ok(RunReviewResult {
+    findings,
+    final_message: result.final_message,
+    usage: result.usage,
+    thread_id: result.thread_id,
+  })
+}
+
+#[cfg(test)]
+mod tests_review {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn groups_findings_by_file_with_severity_and_patches() {
+    let text = r#"Here is the review:
+```json
+{
+  "findings": [
+    {
+      "title": "[P2] Off-by-one in pagination",
+      "body": "The last page is skipped.\n\n```suggestion\nfor page in 0..=last {\n```",
+      "confidence_score": 0.8,
+      "priority": 2,
+      "code_location": {"absolute_file_path": "/repo/src/page.rs", "line_range": {"start": 40, "end": 42}}
+    },
+    {
+      "title": "[P0] Token logged in plain text",
+      "body": "Remove the debug log.",
+      "confidence_score": 0.95,
+      "priority": 0,
+      "code_location": {"absolute_file_path": "/repo/src/auth.rs", "line_range": {"start": 12, "end": 12}}
+    },
+    {
+      "title": "[P1] Missing bounds check",
+      "body": "Index can exceed the slice.",
+      "confidence_score": 0.6,
+      "priority": 1,
+      "code_location": {"absolute_file_path": "/repo/src/page.rs", "line_range": {"start": 10, "end": 11}}
+    }
+  ],
+  "overall_correctness": "patch is incorrect",
+  "overall_explanation": "Leaks a token.",
+  "overall_confidence_score": 0.9
+}
+```"#;
+    let findings = parse_review_findings(text.to_string()).unwrap();
+    assert_eq!(findings.overall_correctness, "patch is incorrect");
+    let paths: Vec<&str> = findings
+      .files
+      .iter()
+      .map(|file| file.path.as_str())
+      .collect();
+    assert_eq!(paths, vec!["/repo/src/page.rs", "/repo/src/auth.rs"]);
+
+    let page = &findings.files[0].comments;
+    assert_eq!(
+      page
+        .iter()
+        .map(|comment| (
+          comment.title.as_str(),
+          comment.severity.as_str(),
+          comment.start_line
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        ("Missing bounds check", "high", 10),
+        ("Off-by-one in pagination", "medium", 40),
+      ]
+    );
+    assert_eq!(
+      page[1].suggested_patch.as_deref(),
+      Some("for page in 0..=last {")
+    );
+    assert_eq!(page[0].suggested_patch, None);
+    assert_eq!(findings.files[1].comments[0].severity, "critical");
+
+    assert!(parse_review_findings("no findings here".to_string()).is_err());
+  }
+
+  #[test]
+  fn review_prompt_requires_exactly_one_target() {
+    let request = |diff: Option<&str>, base_ref: Option<&str>| RunReviewRequest {
+      diff: diff.map(str::to_string),
+      base_ref: base_ref.map(str::to_string),
+      hint: None,
+      config: None,
+    };
+    let (prompt, hint) = review_prompt(&request(None, Some("main"))).unwrap();
+    assert!(prompt.contains("git merge-base HEAD main"));
+    assert_eq!(hint, "changes against 'main'");
+    let (prompt, hint) = review_prompt(&request(Some("--- a/x\n+++ b/x\n"), None)).unwrap();
+    assert!(prompt.contains("```diff\n--- a/x\n+++ b/x\n```"));
+    assert_eq!(hint, "diff");
+    assert!(review_prompt(&request(None, None)).is_err());
+    assert!(review_prompt(&request(Some("diff"), Some("main"))).is_err());
+  }
+
+  #[test]
+  fn review_diff_uses_the_patch_and_appends_guidelines() {
+    let request = ReviewDiffRequest {
+      cwd: None,
+      base_ref: None,
+      head_ref: None,
+      patch_text: Some("--- a/x\n+++ b/x\n".to_string()),
+      guidelines: Some("Flag any unwrap() in library code.".to_string()),
+      hint: None,
+      config: None,
+    };
+    let (diff, hint) = review_diff_target(&request).unwrap();
+    assert_eq!(hint, "patch");
+    let prompt = with_review_guidelines(diff_review_prompt(&diff), request.guidelines.as_deref());
+    assert!(prompt.ends_with(
+      "```diff\n--- a/x\n+++ b/x\n```\n\nReview guidelines:\nFlag any unwrap() in library code."
+    ));
+    assert_eq!(
+      with_review_guidelines("prompt".to_string(), Some("  ")),
+      "prompt"
+    );
+
+    let both = ReviewDiffRequest {
+      base_ref: Some("main".to_string()),
+      ..request
+    };
+    assert!(review_diff_target(&both).is_err());
+  }
+}
@@ -0,0 +1,68 @@
+struct TempSchemaFile {
+  path: PathBuf,
+  _guard: tempfile::TempPath,
+}
+
+fn prepare_schema(schema: Option<JsonValue>) -> napi::Result<Option<TempSchemaFile>> {
+  if let Some(schema_value) = schema {
+    let mut file = NamedTempFile::new().map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    serde_json::to_writer(&mut file, &schema_value)
+      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let path = file.path().to_path_buf();
+    let temp_path = file.into_temp_path();
+    Ok(Some(TempSchemaFile {
+      path,
+      _guard: temp_path,
+    }))
+  } else {
+    Ok(None)
+  }
+}
+
+/// Connection settings for a single run.
+///
+/// Runs in the same process share its environment, so these are never exported as
+/// `OPENAI_BASE_URL`, `CODEX_API_KEY` or `CODEX_LINUX_SANDBOX_EXE`: a concurrent run would
+/// pick them up, and restoring the previous values when one run ends would pull them out from
+/// under another. Instead they are handed to the exec `Cli` explicitly; configs the bindings load
+/// themselves take the endpoint in [`load_run_config`], and auth managers take the key in
+/// [`run_auth_manager`].
+#[derive(Debug, Default, PartialEq)]
+struct RunOverrides {
+  /// Endpoint for the built-in OpenAI provider.
+  base_url: Option<String>,
+  /// Key that replaces stored credentials and `CODEX_API_KEY`.
+  api_key: Option<String>,
+  /// The request's sandbox binary, else `CODEX_LINUX_SANDBOX_EXE`, else the embedded copy.
+  linux_sandbox_path: Option<PathBuf>,
+}
+
+impl RunOverrides {
+  fn resolve(options: &InternalRunRequest) -> napi::Result<Self> {
+    Ok(Self {
+      base_url: options.base_url.clone(),
+      api_key: options.api_key.clone(),
+      linux_sandbox_path: resolve_linux_sandbox_path(options.linux_sandbox_path.clone())?,
+    })
+  }
+
+  fn apply_to_cli(&self, cli: &mut Cli) {
+    cli.openai_base_url = self.base_url.clone();
+    cli.api_key = self.api_key.clone();
+  }
+}
+
+fn resolve_linux_sandbox_path(explicit: Option<PathBuf>) -> napi::Result<Option<PathBuf>> {
+  match explicit.or_else(|| std::env::var_os("CODEX_LINUX_SANDBOX_EXE").map(PathBuf::from)) {
+    Some(path) => Ok(Some(path)),
+    None => default_linux_sandbox_path(),
+  }
+}
+
+/// Requests report the SDK as their originator unless `CODEX_INTERNAL_ORIGINATOR_OVERRIDE` is set.
+fn ensure_native_originator() {
+  static ORIGINATOR_SET: OnceLock<()> = OnceLock::new();
+  ORIGINATOR_SET.get_or_init(|| {
+    let _ = codex_core::default_client::set_default_originator(NATIVE_ORIGINATOR.to_string());
+  });
+}
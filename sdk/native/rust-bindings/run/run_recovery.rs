@@ -0,0 +1,620 @@
+// ============================================================================
+// Interrupted run recovery (recoverInterruptedRuns)
+// ============================================================================
+//
+// Core only persists `turn_aborted` when a turn is cancelled in-process, so a
+// host that dies mid-run leaves a rollout that simply stops. Every native run
+// therefore keeps a journal entry under `$CODEX_HOME/native/active-runs/` for
+// as long as it executes; an entry whose owning process is gone marks an
+// unfinished turn. Recovery rebuilds the pending state from the rollout,
+// deletes temp files the dead run left behind, and can close the turn the way
+// an interrupt would so the thread resumes from a consistent history.
+
+const ACTIVE_RUNS_DIR: &str = "active-runs";
+const INTERRUPTED_TURN_GUIDANCE: &str = "The previous turn was interrupted because the host process exited. If any tools/commands were running, they may have partially executed; verify current state before retrying.";
+const INTERRUPTED_TOOL_OUTPUT: &str = "aborted: the host process exited before this call completed";
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct RunJournalEntry {
+  run_id: String,
+  pid: u32,
+  started_at: String,
+  #[serde(default)]
+  thread_id: Option<String>,
+  #[serde(default)]
+  prompt: String,
+  #[serde(default)]
+  working_directory: Option<String>,
+  /// Temp files created for the run that only its own drop guards remove.
+  #[serde(default)]
+  temp_files: Vec<PathBuf>,
+}
+
+/// Run ids journaled by this process that are still executing.
+fn active_run_ids() -> &'static Mutex<std::collections::HashSet<String>> {
+  static ACTIVE: OnceLock<Mutex<std::collections::HashSet<String>>> = OnceLock::new();
+  ACTIVE.get_or_init(|| Mutex::new(std::collections::HashSet::new()))
+}
+
+fn active_runs_dir(codex_home: &Path) -> PathBuf {
+  codex_home.join("native").join(ACTIVE_RUNS_DIR)
+}
+
+/// Journal entry for one in-flight run, removed when the run returns.
+///
+/// Journaling is best effort: a run never fails because its entry could not
+/// be written.
+struct RunJournal {
+  path: PathBuf,
+  entry: RunJournalEntry,
+}
+
+impl RunJournal {
+  fn start(options: &InternalRunRequest, temp_files: Vec<PathBuf>) -> Option<Self> {
+    let codex_home = find_codex_home().ok()?;
+    Self::start_in(&active_runs_dir(&codex_home), options, temp_files)
+  }
+
+  fn start_in(dir: &Path, options: &InternalRunRequest, temp_files: Vec<PathBuf>) -> Option<Self> {
+    std::fs::create_dir_all(dir).ok()?;
+    let run_id = Uuid::new_v4().to_string();
+    let entry = RunJournalEntry {
+      run_id: run_id.clone(),
+      pid: std::process::id(),
+      started_at: Utc::now().to_rfc3339(),
+      thread_id: options.thread_id.clone(),
+      prompt: options.prompt.clone(),
+      working_directory: options
+        .working_directory
+        .as_ref()
+        .map(|path| path.to_string_lossy().into_owned()),
+      temp_files,
+    };
+    let journal = Self {
+      path: dir.join(format!("{run_id}.json")),
+      entry,
+    };
+    if let Ok(mut active) = active_run_ids().lock() {
+      active.insert(run_id);
+    }
+    journal.write();
+    Some(journal)
+  }
+
+  fn observe(&mut self, event: &ExecThreadEvent) {
+    if let ExecThreadEvent::ThreadStarted(started) = event
+      && self.entry.thread_id.as_deref() != Some(started.thread_id.as_str())
+    {
+      self.entry.thread_id = Some(started.thread_id.clone());
+      self.write();
+    }
+  }
+
+  fn write(&self) {
+    if let Ok(encoded) = serde_json::to_vec_pretty(&self.entry) {
+      let _ = std::fs::write(&self.path, encoded);
+    }
+  }
+}
+
+impl Drop for RunJournal {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+    if let Ok(mut active) = active_run_ids().lock() {
+      active.remove(&self.entry.run_id);
+    }
+  }
+}
+
+fn process_is_alive(pid: u32) -> bool {
+  #[cfg(target_os = "linux")]
+  {
+    Path::new("/proc").join(pid.to_string()).exists()
+  }
+  #[cfg(all(unix, not(target_os = "linux")))]
+  {
+    std::process::Command::new("kill")
+      .args(["-0", &pid.to_string()])
+      .stderr(std::process::Stdio::null())
+      .status()
+      .is_ok_and(|status| status.success())
+  }
+  #[cfg(windows)]
+  {
+    std::process::Command::new("tasklist")
+      .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+      .output()
+      .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+  }
+}
+
+fn run_journal_is_live(entry: &RunJournalEntry) -> bool {
+  if entry.pid == std::process::id() {
+    // The pid may be a reused one from an earlier process; only runs this
+    // process actually started are live.
+    return active_run_ids()
+      .lock()
+      .is_ok_and(|active| active.contains(&entry.run_id));
+  }
+  process_is_alive(entry.pid)
+}
+
+/// Journal entries whose runs are no longer executing, oldest first.
+fn stale_run_journal_entries(dir: &Path) -> Vec<(PathBuf, RunJournalEntry)> {
+  let Ok(read_dir) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut entries: Vec<(PathBuf, RunJournalEntry)> = read_dir
+    .flatten()
+    .map(|dir_entry| dir_entry.path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+    .filter_map(|path| {
+      let contents = std::fs::read_to_string(&path).ok()?;
+      let entry: RunJournalEntry = serde_json::from_str(&contents).ok()?;
+      Some((path, entry))
+    })
+    .filter(|(_, entry)| !run_journal_is_live(entry))
+    .collect();
+  entries.sort_by(|a, b| a.1.started_at.cmp(&b.1.started_at));
+  entries
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PendingToolCallKind {
+  Function,
+  Custom,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct PendingToolCall {
+  call_id: String,
+  name: String,
+  kind: PendingToolCallKind,
+}
+
+/// What the rollout says about the most recent turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct InterruptedTurnState {
+  last_user_message: Option<String>,
+  last_agent_message: Option<String>,
+  completed_tool_calls: u32,
+  pending_tool_calls: Vec<PendingToolCall>,
+  /// A `turn_aborted` event already closes the turn.
+  aborted: bool,
+}
+
+fn interrupted_turn_state(records: &[JsonValue]) -> InterruptedTurnState {
+  let mut state = InterruptedTurnState::default();
+  for record in records {
+    let payload = record.get("payload");
+    let payload_type = payload
+      .and_then(|payload| payload.get("type"))
+      .and_then(JsonValue::as_str);
+    let text_field = |field: &str| {
+      payload
+        .and_then(|payload| payload.get(field))
+        .and_then(JsonValue::as_str)
+        .map(str::to_string)
+    };
+    match (record.get("type").and_then(JsonValue::as_str), payload_type) {
+      (Some("event_msg"), Some("user_message")) => {
+        state = InterruptedTurnState {
+          last_user_message: text_field("message"),
+          ..InterruptedTurnState::default()
+        };
+      }
+      (Some("event_msg"), Some("agent_message")) => {
+        state.last_agent_message = text_field("message");
+      }
+      (Some("event_msg"), Some("turn_aborted")) => state.aborted = true,
+      (Some("response_item"), Some(kind @ ("function_call" | "custom_tool_call"))) => {
+        if let Some(call_id) = text_field("call_id") {
+          state.pending_tool_calls.push(PendingToolCall {
+            call_id,
+            name: text_field("name").unwrap_or_default(),
+            kind: if kind == "function_call" {
+              PendingToolCallKind::Function
+            } else {
+              PendingToolCallKind::Custom
+            },
+          });
+        }
+      }
+      (Some("response_item"), Some("local_shell_call")) => {
+        if let Some(call_id) = text_field("call_id") {
+          state.pending_tool_calls.push(PendingToolCall {
+            call_id,
+            name: "local_shell".to_string(),
+            kind: PendingToolCallKind::Function,
+          });
+        }
+      }
+      (Some("response_item"), Some("function_call_output" | "custom_tool_call_output")) => {
+        if let Some(call_id) = text_field("call_id")
+          && let Some(index) = state
+            .pending_tool_calls
+            .iter()
+            .position(|call| call.call_id == call_id)
+        {
+          state.pending_tool_calls.remove(index);
+          state.completed_tool_calls += 1;
+        }
+      }
+      _ => {}
+    }
+  }
+  state
+}
+
+fn rollout_line(item: codex_protocol::protocol::RolloutItem) -> napi::Result<String> {
+  let line = codex_protocol::protocol::RolloutLine {
+    timestamp: Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+    item,
+  };
+  serde_json::to_string(&line)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to encode rollout line: {e}")))
+}
+
+/// Close the unfinished turn like an in-process interrupt: every pending tool
+/// call gets an aborted output, then the `<turn_aborted>` marker and event.
+fn finalize_interrupted_rollout(path: &Path, state: &InterruptedTurnState) -> napi::Result<()> {
+  use codex_protocol::models::ContentItem;
+  use codex_protocol::models::FunctionCallOutputPayload;
+  use codex_protocol::models::ResponseItem;
+  use codex_protocol::protocol::RolloutItem;
+  use std::io::Write;
+
+  let mut lines = Vec::new();
+  for call in &state.pending_tool_calls {
+    let call_id = call.call_id.clone();
+    let output = match call.kind {
+      PendingToolCallKind::Function => ResponseItem::FunctionCallOutput {
+        call_id,
+        output: FunctionCallOutputPayload::from_text(INTERRUPTED_TOOL_OUTPUT.to_string()),
+      },
+      PendingToolCallKind::Custom => ResponseItem::CustomToolCallOutput {
+        call_id,
+        output: INTERRUPTED_TOOL_OUTPUT.to_string(),
+      },
+    };
+    lines.push(rollout_line(RolloutItem::ResponseItem(output))?);
+  }
+  lines.push(rollout_line(RolloutItem::ResponseItem(ResponseItem::Message {
+    id: None,
+    role: "user".to_string(),
+    content: vec![ContentItem::InputText {
+      text: format!("<turn_aborted>\n{INTERRUPTED_TURN_GUIDANCE}\n</turn_aborted>"),
+    }],
+    end_turn: None,
+    phase: None,
+  }))?);
+  lines.push(rollout_line(RolloutItem::EventMsg(
+    codex_protocol::protocol::EventMsg::TurnAborted(codex_protocol::protocol::TurnAbortedEvent {
+      reason: codex_protocol::protocol::TurnAbortReason::Interrupted,
+    }),
+  ))?);
+
+  let mut file = std::fs::OpenOptions::new()
+    .append(true)
+    .open(path)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to open {}: {e}", path.display())))?;
+  let mut contents = String::new();
+  // A process killed mid-write can leave the last line unterminated.
+  if std::fs::read(path).is_ok_and(|bytes| bytes.last().is_some_and(|byte| *byte != b'\n')) {
+    contents.push('\n');
+  }
+  for line in lines {
+    contents.push_str(&line);
+    contents.push('\n');
+  }
+  file
+    .write_all(contents.as_bytes())
+    .map_err(|e| napi::Error::from_reason(format!("Failed to write {}: {e}", path.display())))
+}
+
+fn interrupted_resume_prompt(entry: &RunJournalEntry, state: &InterruptedTurnState) -> String {
+  let task = state
+    .last_user_message
+    .as_deref()
+    .unwrap_or(entry.prompt.as_str());
+  let mut prompt = format!(
+    "Your previous turn was interrupted before it finished. Continue the task below from where you left off.\n\n{task}"
+  );
+  if !state.pending_tool_calls.is_empty() {
+    let names: Vec<&str> = state
+      .pending_tool_calls
+      .iter()
+      .map(|call| call.name.as_str())
+      .collect();
+    prompt.push_str(&format!(
+      "\n\nThese tool calls were still running and may have partially executed: {}. Check the current state before repeating them.",
+      names.join(", ")
+    ));
+  }
+  prompt
+}
+
+#[napi(object)]
+pub struct RecoverInterruptedRunsOptions {
+  /// Default: `$CODEX_HOME` or `~/.codex`.
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  /// Close every unfinished turn and drop its journal entry. Default: false.
+  pub finalize: Option<bool>,
+  /// Delete temp files left behind by dead runs. Default: true.
+  #[napi(js_name = "cleanupTempFiles")]
+  pub cleanup_temp_files: Option<bool>,
+}
+
+#[napi(object)]
+pub struct FinalizeInterruptedRunOptions {
+  /// Default: `$CODEX_HOME` or `~/.codex`.
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterruptedRun {
+  #[napi(js_name = "runId")]
+  pub run_id: String,
+  pub pid: u32,
+  #[napi(js_name = "startedAt")]
+  pub started_at: String,
+  /// Unset when the process died before the thread started.
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  pub prompt: String,
+  #[napi(js_name = "workingDirectory")]
+  pub working_directory: Option<String>,
+  #[napi(js_name = "rolloutPath")]
+  pub rollout_path: Option<String>,
+  #[napi(js_name = "lastUserMessage")]
+  pub last_user_message: Option<String>,
+  #[napi(js_name = "lastAgentMessage")]
+  pub last_agent_message: Option<String>,
+  /// Tool calls of the unfinished turn that produced an output.
+  #[napi(js_name = "completedToolCalls")]
+  pub completed_tool_calls: u32,
+  /// Names of tool calls that were still running when the process died.
+  #[napi(js_name = "pendingToolCalls")]
+  pub pending_tool_calls: Vec<String>,
+  /// Orphaned temp files removed during recovery.
+  #[napi(js_name = "removedTempFiles")]
+  pub removed_temp_files: Vec<String>,
+  /// The rollout exists, so the thread can be resumed.
+  pub resumable: bool,
+  /// The turn has been closed and the journal entry removed.
+  pub finalized: bool,
+  /// Suggested prompt for continuing the interrupted task.
+  #[napi(js_name = "resumePrompt")]
+  pub resume_prompt: String,
+}
+
+fn recovery_codex_home(codex_home: Option<&str>) -> napi::Result<PathBuf> {
+  match codex_home {
+    Some(path) => Ok(PathBuf::from(path)),
+    None => find_codex_home()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}"))),
+  }
+}
+
+async fn interrupted_run_rollout(codex_home: &Path, entry: &RunJournalEntry) -> Option<PathBuf> {
+  let thread_id = entry.thread_id.as_deref()?;
+  find_thread_path_by_id_str(codex_home, thread_id)
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Build the recovery report for one stale entry, optionally closing the turn.
+fn recover_interrupted_run(
+  journal_path: &Path,
+  entry: RunJournalEntry,
+  rollout_path: Option<PathBuf>,
+  finalize: bool,
+  cleanup_temp_files: bool,
+) -> napi::Result<InterruptedRun> {
+  let state = match rollout_path.as_deref() {
+    Some(path) => load_cached_rollout(path)
+      .map(|rollout| interrupted_turn_state(&rollout.records))
+      .map_err(|e| napi::Error::from_reason(format!("Failed to read {}: {e}", path.display())))?,
+    None => InterruptedTurnState::default(),
+  };
+
+  let mut removed_temp_files = Vec::new();
+  if cleanup_temp_files {
+    for temp_file in &entry.temp_files {
+      if std::fs::remove_file(temp_file).is_ok() {
+        removed_temp_files.push(temp_file.to_string_lossy().into_owned());
+      }
+    }
+  }
+
+  let finalized = if finalize {
+    if let Some(path) = rollout_path.as_deref()
+      && !state.aborted
+    {
+      finalize_interrupted_rollout(path, &state)?;
+    }
+    match std::fs::remove_file(journal_path) {
+      Ok(()) => {}
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+      Err(err) => {
+        return Err(napi::Error::from_reason(format!(
+          "Failed to remove {}: {err}",
+          journal_path.display()
+        )));
+      }
+    }
+    true
+  } else {
+    false
+  };
+
+  Ok(InterruptedRun {
+    resume_prompt: interrupted_resume_prompt(&entry, &state),
+    run_id: entry.run_id,
+    pid: entry.pid,
+    started_at: entry.started_at,
+    thread_id: entry.thread_id,
+    prompt: entry.prompt,
+    working_directory: entry.working_directory,
+    resumable: rollout_path.is_some(),
+    rollout_path: rollout_path.map(|path| path.to_string_lossy().into_owned()),
+    last_user_message: state.last_user_message,
+    last_agent_message: state.last_agent_message,
+    completed_tool_calls: state.completed_tool_calls,
+    pending_tool_calls: state
+      .pending_tool_calls
+      .into_iter()
+      .map(|call| call.name)
+      .collect(),
+    removed_temp_files,
+    finalized,
+  })
+}
+
+/// Find runs that were still executing when their process exited.
+#[napi]
+pub async fn recover_interrupted_runs(
+  options: Option<RecoverInterruptedRunsOptions>,
+) -> napi::Result<Vec<InterruptedRun>> {
+  let options = options.unwrap_or(RecoverInterruptedRunsOptions {
+    codex_home: None,
+    finalize: None,
+    cleanup_temp_files: None,
+  });
+  let codex_home = recovery_codex_home(options.codex_home.as_deref())?;
+  let finalize = options.finalize.unwrap_or(false);
+  let cleanup_temp_files = options.cleanup_temp_files.unwrap_or(true);
+
+  let mut runs = Vec::new();
+  for (journal_path, entry) in stale_run_journal_entries(&active_runs_dir(&codex_home)) {
+    let rollout_path = interrupted_run_rollout(&codex_home, &entry).await;
+    runs.push(recover_interrupted_run(
+      &journal_path,
+      entry,
+      rollout_path,
+      finalize,
+      cleanup_temp_files,
+    )?);
+  }
+  Ok(runs)
+}
+
+/// Close one interrupted run so its thread can be resumed cleanly.
+#[napi]
+pub async fn finalize_interrupted_run(
+  run_id: String,
+  options: Option<FinalizeInterruptedRunOptions>,
+) -> napi::Result<InterruptedRun> {
+  let codex_home = recovery_codex_home(options.and_then(|options| options.codex_home).as_deref())?;
+  let (journal_path, entry) = stale_run_journal_entries(&active_runs_dir(&codex_home))
+    .into_iter()
+    .find(|(_, entry)| entry.run_id == run_id)
+    .ok_or_else(|| napi::Error::from_reason(format!("No interrupted run with id {run_id}")))?;
+  let rollout_path = interrupted_run_rollout(&codex_home, &entry).await;
+  recover_interrupted_run(&journal_path, entry, rollout_path, true, true)
+}
+
+#[cfg(test)]
+mod tests_run_recovery {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn rollout_record(kind: &str, payload: JsonValue) -> String {
+    json!({ "timestamp": "2026-01-01T00:00:00.000Z", "type": kind, "payload": payload }).to_string()
+  }
+
+  #[test]
+  fn stale_runs_are_reconstructed_and_finalized() {
+    let home = tempfile::tempdir().unwrap();
+    let journal_dir = active_runs_dir(home.path());
+    std::fs::create_dir_all(&journal_dir).unwrap();
+
+    let rollout_path = home.path().join("rollout.jsonl");
+    let rollout = [
+      rollout_record("event_msg", json!({ "type": "user_message", "message": "old task" })),
+      rollout_record("event_msg", json!({ "type": "agent_message", "message": "done" })),
+      rollout_record("event_msg", json!({ "type": "user_message", "message": "fix the tests" })),
+      rollout_record(
+        "response_item",
+        json!({ "type": "function_call", "name": "shell", "arguments": "{}", "call_id": "call-1" }),
+      ),
+      rollout_record(
+        "response_item",
+        json!({ "type": "function_call_output", "call_id": "call-1", "output": "ok" }),
+      ),
+      rollout_record("event_msg", json!({ "type": "agent_message", "message": "running tests" })),
+      rollout_record(
+        "response_item",
+        json!({ "type": "custom_tool_call", "name": "apply_patch", "input": "*** Begin Patch", "call_id": "call-2" }),
+      ),
+    ];
+    std::fs::write(&rollout_path, rollout.join("\n")).unwrap();
+
+    let temp_file = home.path().join("schema.json");
+    std::fs::write(&temp_file, "{}").unwrap();
+    let entry = RunJournalEntry {
+      run_id: "run-1".to_string(),
+      // Never a live pid: above the kernel's pid_max.
+      pid: u32::MAX,
+      started_at: "2026-01-01T00:00:00+00:00".to_string(),
+      thread_id: Some("thread-1".to_string()),
+      prompt: "fix the tests".to_string(),
+      working_directory: None,
+      temp_files: vec![temp_file.clone()],
+    };
+    let journal_path = journal_dir.join("run-1.json");
+    std::fs::write(&journal_path, serde_json::to_string(&entry).unwrap()).unwrap();
+
+    let stale = stale_run_journal_entries(&journal_dir);
+    assert_eq!(stale, vec![(journal_path.clone(), entry.clone())]);
+
+    let run = recover_interrupted_run(&journal_path, entry, Some(rollout_path.clone()), true, true)
+      .unwrap();
+    assert_eq!(run.last_user_message.as_deref(), Some("fix the tests"));
+    assert_eq!(run.last_agent_message.as_deref(), Some("running tests"));
+    assert_eq!(run.completed_tool_calls, 1);
+    assert_eq!(run.pending_tool_calls, vec!["apply_patch".to_string()]);
+    assert_eq!(run.removed_temp_files, vec![temp_file.to_string_lossy().into_owned()]);
+    assert!(run.resumable && run.finalized);
+    assert!(run.resume_prompt.contains("apply_patch"));
+    assert!(!temp_file.exists() && !journal_path.exists());
+
+    let records: Vec<JsonValue> = std::fs::read_to_string(&rollout_path)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect();
+    let state = interrupted_turn_state(&records);
+    assert_eq!(state.pending_tool_calls, Vec::new());
+    assert!(state.aborted);
+    assert_eq!(
+      records[records.len() - 3]["payload"],
+      json!({
+        "type": "custom_tool_call_output",
+        "call_id": "call-2",
+        "output": INTERRUPTED_TOOL_OUTPUT,
+      })
+    );
+  }
+
+  #[test]
+  fn journals_of_running_runs_are_not_stale() {
+    let home = tempfile::tempdir().unwrap();
+    let journal_dir = active_runs_dir(home.path());
+    let mut options = ConversationConfigRequest::default()
+      .into_internal_request()
+      .unwrap();
+    options.prompt = "hello".to_string();
+    let journal = RunJournal::start_in(&journal_dir, &options, Vec::new()).unwrap();
+    assert!(journal.path.exists());
+    assert_eq!(stale_run_journal_entries(&journal_dir), Vec::new());
+
+    let path = journal.path.clone();
+    drop(journal);
+    assert!(!path.exists());
+  }
+}
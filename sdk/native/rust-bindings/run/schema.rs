@@ -1,6 +1,13 @@
 async fn load_config_from_internal(options: &InternalRunRequest) -> napi::Result<Config> {
-  let (overrides, cli_kv_overrides) =
-    build_config_inputs(options, options.linux_sandbox_path.clone())?;
+  load_run_config(options, options.linux_sandbox_path.clone()).await
+}
+
+/// The run's config with its endpoint applied, as `codex exec` builds it for the runs it starts.
+async fn load_run_config(
+  options: &InternalRunRequest,
+  linux_sandbox_path: Option<PathBuf>,
+) -> napi::Result<Config> {
+  let (overrides, cli_kv_overrides) = build_config_inputs(options, linux_sandbox_path)?;
   Config::load_with_cli_overrides_and_harness_overrides(cli_kv_overrides, overrides)
     .await
     .map_err(|e| napi::Error::from_reason(e.to_string()))
@@ -29,18 +36,6 @@ fn build_config_inputs(
     .parse_overrides()
     .map_err(|e| napi::Error::from_reason(format!("Failed to parse config overrides: {e}")))?;
 
-  if let Some(effort) = options.reasoning_effort.as_ref() {
-    cli_kv_overrides.push((
-      "model_reasoning_effort".to_string(),
-      TomlValue::String(effort.to_string().to_lowercase()),
-    ));
-  }
-  if let Some(summary) = options.reasoning_summary.as_ref() {
-    cli_kv_overrides.push((
-      "model_reasoning_summary".to_string(),
-      TomlValue::String(summary.to_string().to_lowercase()),
-    ));
-  }
   if let Some(mode) = options.web_search_mode {
     cli_kv_overrides.push((
       "web_search".to_string(),
@@ -63,17 +58,19 @@ fn build_config_inputs(
       .model_provider
       .clone()
       .or_else(|| options.oss.then_some(codex_core::OLLAMA_OSS_PROVIDER_ID.to_string())),
-    config_profile: None,
+    config_profile: options.config_profile.clone(),
     codex_linux_sandbox_exe: linux_sandbox_path,
-    base_instructions: None,
-    developer_instructions: None,
+    base_instructions: options.base_instructions.clone(),
+    developer_instructions: options.developer_instructions.clone(),
     personality: options.personality,
-    compact_prompt: None,
+    compact_prompt: options.compact_prompt.clone(),
     include_apply_patch_tool: None,
     show_raw_agent_reasoning: options.oss.then_some(true),
     tools_web_search_request: None,
     ephemeral: options.ephemeral,
     additional_writable_roots: Vec::new(),
+    external_tool_layers: ExternalToolLayers::default(),
+    openai_base_url: options.base_url.clone(),
   };
 
   Ok((overrides, cli_kv_overrides))
@@ -0,0 +1,322 @@
+// ============================================================================
+// Task-driven runs (runFromTask)
+// ============================================================================
+//
+// Agents launched from an issue tracker all turn a ticket into a run the
+// same way: title and description become the prompt, screenshots become
+// images, logs are inlined and the checkout is the working directory.
+// runFromTask() does that normalization natively, runs the thread to
+// completion like runThreadCollect(), and records which task the thread
+// worked on in `$CODEX_HOME/thread-tasks.jsonl`, which listConversations()
+// reads.
+
+const THREAD_TASKS_FILE: &str = "thread-tasks.jsonl";
+const TASK_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct TaskAttachment {
+  /// File name or label shown in the prompt.
+  pub name: Option<String>,
+  /// Local file. Images are attached to the run; other files are listed for the agent to read.
+  pub path: Option<String>,
+  /// Text inlined into the prompt, e.g. a log excerpt.
+  pub content: Option<String>,
+  /// Link listed in the prompt.
+  pub url: Option<String>,
+  /// MIME type; `image/*` marks `path` as an image when its extension doesn't.
+  #[napi(js_name = "mimeType")]
+  pub mime_type: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct RunFromTaskRequest {
+  pub title: String,
+  /// Task description, e.g. an issue body in Markdown.
+  pub body: Option<String>,
+  pub attachments: Option<Vec<TaskAttachment>>,
+  /// Checkout to work in; relative attachment paths resolve against it.
+  #[napi(js_name = "repoPath")]
+  pub repo_path: Option<String>,
+  /// Permission profile defined with `definePermissionProfile`.
+  #[napi(js_name = "permissionProfile")]
+  pub permission_profile: Option<String>,
+  /// Tracker the task came from, e.g. `github` or `jira`.
+  pub source: Option<String>,
+  /// Task id in the tracker, e.g. `acme/widgets#42` or `PROJ-123`.
+  #[napi(js_name = "taskId")]
+  pub task_id: Option<String>,
+  pub url: Option<String>,
+  pub labels: Option<Vec<String>>,
+  /// Further RunRequest fields (`model`, `sandboxMode`, `threadId`, ...). Fields set here win
+  /// over `repoPath` and `permissionProfile`.
+  pub run: Option<JsonValue>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+pub struct ThreadTaskLink {
+  #[napi(js_name = "threadId")]
+  pub thread_id: String,
+  pub title: String,
+  pub source: Option<String>,
+  #[napi(js_name = "taskId")]
+  pub task_id: Option<String>,
+  pub url: Option<String>,
+  #[napi(js_name = "linkedAt")]
+  pub linked_at: String,
+}
+
+#[napi(object)]
+pub struct RunFromTaskResult {
+  pub result: RunThreadCollectResult,
+  pub task: ThreadTaskLink,
+}
+
+/// The latest task link for each thread id.
+fn read_thread_tasks(codex_home: &Path) -> HashMap<String, ThreadTaskLink> {
+  let Ok(contents) = std::fs::read_to_string(codex_home.join(THREAD_TASKS_FILE)) else {
+    return HashMap::new();
+  };
+  contents
+    .lines()
+    .filter_map(|line| serde_json::from_str::<ThreadTaskLink>(line).ok())
+    .map(|link| (link.thread_id.clone(), link))
+    .collect()
+}
+
+fn append_thread_task(codex_home: &Path, link: &ThreadTaskLink) -> napi::Result<()> {
+  let line = serde_json::to_string(link)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize task link: {e}")))?;
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(codex_home.join(THREAD_TASKS_FILE))
+    .and_then(|mut file| writeln!(file, "{line}"))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to store task link: {e}")))
+}
+
+fn is_task_image(attachment: &TaskAttachment, path: &Path) -> bool {
+  attachment
+    .mime_type
+    .as_deref()
+    .is_some_and(|mime| mime.starts_with("image/"))
+    || path
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .is_some_and(|extension| {
+        TASK_IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+      })
+}
+
+/// The prompt for `req` and the attachment images to send with it.
+fn task_prompt(req: &RunFromTaskRequest) -> (String, Vec<String>) {
+  let repo_path = req.repo_path.as_deref().map(Path::new);
+  let mut prompt = format!(
+    "Work on the following task.\n\nTask: {}\n",
+    req.title.trim()
+  );
+  let reference: Vec<&str> = [req.source.as_deref(), req.task_id.as_deref()]
+    .into_iter()
+    .flatten()
+    .collect();
+  if !reference.is_empty() {
+    prompt.push_str(&format!("Reference: {}\n", reference.join(" ")));
+  }
+  if let Some(url) = &req.url {
+    prompt.push_str(&format!("URL: {url}\n"));
+  }
+  if let Some(labels) = req.labels.as_ref().filter(|labels| !labels.is_empty()) {
+    prompt.push_str(&format!("Labels: {}\n", labels.join(", ")));
+  }
+  if let Some(body) = req
+    .body
+    .as_deref()
+    .map(str::trim)
+    .filter(|body| !body.is_empty())
+  {
+    prompt.push_str(&format!("\n{body}\n"));
+  }
+
+  let mut images = Vec::new();
+  let mut listed = Vec::new();
+  let mut inlined = Vec::new();
+  for attachment in req.attachments.iter().flatten() {
+    let path = attachment.path.as_deref().map(|path| match repo_path {
+      Some(repo_path) => repo_path.join(path),
+      None => PathBuf::from(path),
+    });
+    let name = attachment
+      .name
+      .clone()
+      .or_else(|| {
+        let file_name = path.as_ref()?.file_name()?;
+        Some(file_name.to_string_lossy().into_owned())
+      })
+      .or_else(|| attachment.url.clone())
+      .unwrap_or_else(|| "attachment".to_string());
+    if let Some(content) = &attachment.content {
+      inlined.push(format!(
+        "<attachment name=\"{name}\">\n{}\n</attachment>",
+        content.trim_end()
+      ));
+    } else if let Some(path) = path {
+      if is_task_image(attachment, &path) {
+        images.push(path.to_string_lossy().into_owned());
+        listed.push(format!("- {name} (attached image)"));
+      } else {
+        listed.push(format!("- {name}: {}", path.display()));
+      }
+    } else if let Some(url) = &attachment.url {
+      listed.push(format!("- {name}: {url}"));
+    }
+  }
+  if !listed.is_empty() {
+    prompt.push_str(&format!("\nAttachments:\n{}\n", listed.join("\n")));
+  }
+  for attachment in inlined {
+    prompt.push_str(&format!("\n{attachment}\n"));
+  }
+  (prompt.trim_end().to_string(), images)
+}
+
+/// The RunRequest for `req`: its `run` fields, with the task's prompt, images, cwd and profile.
+fn task_run_request(req: &RunFromTaskRequest) -> napi::Result<RunRequest> {
+  let mut fields = match req.run.clone() {
+    Some(JsonValue::Object(fields)) => fields,
+    None => JsonMap::new(),
+    Some(_) => return Err(napi::Error::from_reason("run must be an object")),
+  };
+  let (prompt, images) = task_prompt(req);
+  fields.insert("prompt".to_string(), JsonValue::String(prompt));
+  if !images.is_empty() {
+    let entry = fields
+      .entry("images")
+      .or_insert_with(|| JsonValue::Array(Vec::new()));
+    if let JsonValue::Array(existing) = entry {
+      existing.extend(images.into_iter().map(JsonValue::String));
+    }
+  }
+  if let Some(repo_path) = &req.repo_path {
+    fields
+      .entry("workingDirectory")
+      .or_insert_with(|| JsonValue::String(repo_path.clone()));
+  }
+  if let Some(profile) = &req.permission_profile {
+    fields
+      .entry("permissionProfile")
+      .or_insert_with(|| JsonValue::String(profile.clone()));
+  }
+  serde_json::from_value(JsonValue::Object(fields))
+    .map_err(|e| napi::Error::from_reason(format!("Invalid run options: {e}")))
+}
+
+/// Run a task from an issue tracker to completion and link the thread to it.
+#[napi]
+pub async fn run_from_task(req: RunFromTaskRequest) -> napi::Result<RunFromTaskResult> {
+  if req.title.trim().is_empty() {
+    return Err(napi::Error::from_reason("runFromTask requires a title"));
+  }
+  let options = task_run_request(&req)?.into_internal()?;
+  let config = load_config_from_internal(&options).await?;
+  let mut collector = tokio::task::spawn_blocking(move || collect_run(options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))??;
+
+  // Link the thread before reporting a failed turn, so the attempt stays traceable.
+  let thread_id = collector
+    .result
+    .thread_id
+    .clone()
+    .ok_or_else(|| napi::Error::from_reason("Run did not start a thread"))?;
+  let task = ThreadTaskLink {
+    thread_id: thread_id.clone(),
+    title: req.title.trim().to_string(),
+    source: req.source,
+    task_id: req.task_id,
+    url: req.url,
+    linked_at: format_rollout_timestamp(SystemTime::now()),
+  };
+  append_thread_task(&config.codex_home, &task)?;
+  let mut result = collector.finish()?;
+  if result.rollout_path.is_none() {
+    result.rollout_path = find_thread_path_by_id_str(&config.codex_home, &thread_id)
+      .await
+      .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?
+      .map(|path| path.to_string_lossy().into_owned());
+  }
+  Ok(RunFromTaskResult { result, task })
+}
+
+#[cfg(test)]
+mod tests_task_runs {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn normalizes_the_task_into_prompt_images_and_run_fields() {
+    let attachment =
+      |name: Option<&str>, path: Option<&str>, content: Option<&str>| TaskAttachment {
+        name: name.map(str::to_string),
+        path: path.map(str::to_string),
+        content: content.map(str::to_string),
+        ..Default::default()
+      };
+    let request = RunFromTaskRequest {
+      title: "Parser drops trailing commas".to_string(),
+      body: Some("Arrays like `[1, 2,]` fail to parse.".to_string()),
+      attachments: Some(vec![
+        attachment(None, Some("docs/screenshot.PNG"), None),
+        attachment(Some("build log"), None, Some("error: unexpected `]`\n")),
+        attachment(None, Some("fixtures/case.json"), None),
+      ]),
+      repo_path: Some("/repo".to_string()),
+      permission_profile: Some("ci".to_string()),
+      source: Some("github".to_string()),
+      task_id: Some("acme/widgets#42".to_string()),
+      labels: Some(vec!["bug".to_string(), "parser".to_string()]),
+      run: Some(json!({ "model": "gpt-5-codex", "permissionProfile": "readonly" })),
+      ..Default::default()
+    };
+    let (prompt, images) = task_prompt(&request);
+    assert_eq!(
+      prompt,
+      "Work on the following task.\n\n\
+       Task: Parser drops trailing commas\n\
+       Reference: github acme/widgets#42\n\
+       Labels: bug, parser\n\n\
+       Arrays like `[1, 2,]` fail to parse.\n\n\
+       Attachments:\n\
+       - screenshot.PNG (attached image)\n\
+       - case.json: /repo/fixtures/case.json\n\n\
+       <attachment name=\"build log\">\nerror: unexpected `]`\n</attachment>"
+    );
+    assert_eq!(images, vec!["/repo/docs/screenshot.PNG".to_string()]);
+
+    let run = task_run_request(&request).unwrap();
+    assert_eq!(run.prompt, prompt);
+    assert_eq!(run.images, Some(images));
+    assert_eq!(run.working_directory.as_deref(), Some("/repo"));
+    assert_eq!(run.permission_profile.as_deref(), Some("readonly"));
+    assert_eq!(run.model.as_deref(), Some("gpt-5-codex"));
+  }
+
+  #[test]
+  fn keeps_the_latest_task_link_per_thread() {
+    let home = tempfile::tempdir().unwrap();
+    let link = |title: &str| ThreadTaskLink {
+      thread_id: "thread-1".to_string(),
+      title: title.to_string(),
+      source: Some("jira".to_string()),
+      task_id: Some("PROJ-7".to_string()),
+      url: None,
+      linked_at: "2025-01-01T00:00:00Z".to_string(),
+    };
+    append_thread_task(home.path(), &link("First attempt")).unwrap();
+    append_thread_task(home.path(), &link("Second attempt")).unwrap();
+    let tasks = read_thread_tasks(home.path());
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks["thread-1"], link("Second attempt"));
+  }
+}
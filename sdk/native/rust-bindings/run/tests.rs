@@ -14,10 +14,14 @@ mod tests_run {
 	      images: Vec::new(),
 	      model: None,
 	      model_provider: None,
+	      config_profile: None,
       oss: false,
       sandbox_mode: None,
       approval_mode: None,
       workspace_write_options: None,
+      tool_filter: None,
+      tool_limits: HashMap::new(),
+      tool_cache_ttls: HashMap::new(),
       review_request: None,
       working_directory: None,
       skip_git_repo_check: true,
@@ -25,6 +29,7 @@ mod tests_run {
       tool_choice: None,
       base_url: None,
       api_key: None,
+      auth_home: None,
       linux_sandbox_path: None,
       reasoning_effort: None,
       reasoning_summary: None,
@@ -35,6 +40,24 @@ mod tests_run {
       dynamic_tools: None,
       mcp: None,
       inherit_mcp: true,
+      record: None,
+      replay: None,
+      patch_path_policy: None,
+      file_skeleton: None,
+      redaction: None,
+      post_turn_checks: Vec::new(),
+      max_check_feedback_turns: DEFAULT_MAX_CHECK_FEEDBACK_TURNS,
+      base_instructions: None,
+      developer_instructions: None,
+      compact_prompt: None,
+      include_raw_events: false,
+      exec_session: None,
+      shell_pty: None,
+      tool_env: None,
+      verify: None,
+      memory: None,
+      escalation_handler: None,
+      output_limits: None,
     }
   }
 
@@ -54,6 +77,145 @@ mod tests_run {
     assert_eq!(config.model_reasoning_summary, ReasoningSummary::Detailed);
   }
 
+  #[test]
+  fn build_cli_forwards_reasoning_overrides() {
+    let mut req = base_internal_request();
+    req.reasoning_effort = Some(ReasoningEffort::High);
+    req.reasoning_summary = Some(ReasoningSummary::Detailed);
+
+    let cli = build_cli(&req, None, false);
+    let overrides = &cli.config_overrides.raw_overrides;
+    assert!(overrides.contains(&"model_reasoning_effort=\"high\"".to_string()));
+    assert!(overrides.contains(&"model_reasoning_summary=\"detailed\"".to_string()));
+  }
+
+  #[tokio::test]
+  async fn load_config_applies_shell_pty_size() {
+    let tempdir = TempDir::new().expect("tempdir");
+    let mut req = base_internal_request();
+    req.working_directory = Some(tempdir.path().to_path_buf());
+    req.shell_pty = Some(ShellPtyOptions {
+      cols: Some(132),
+      rows: None,
+      term: Some("xterm".to_string()),
+    });
+
+    let config = load_config_from_internal(&req)
+      .await
+      .expect("config should load");
+
+    assert_eq!(
+      config.shell_pty,
+      Some(codex_core::config::types::ShellPtyConfig {
+        cols: 132,
+        rows: 24,
+        term: "xterm".to_string(),
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn load_config_applies_output_limits() {
+    let tempdir = TempDir::new().expect("tempdir");
+    let mut req = base_internal_request();
+    req.working_directory = Some(tempdir.path().to_path_buf());
+    req.output_limits = Some(OutputLimits {
+      max_tool_output_tokens: Some(40_000),
+      head_tail_split: Some(0.2),
+    });
+
+    let config = load_config_from_internal(&req)
+      .await
+      .expect("config should load");
+
+    assert_eq!(config.tool_output_token_limit, Some(40_000));
+    assert_eq!(config.tool_output_head_tail_split, Some(0.2));
+    assert_eq!(
+      validate_output_limits(OutputLimits {
+        max_tool_output_tokens: None,
+        head_tail_split: Some(1.5),
+      })
+      .err()
+      .unwrap()
+      .reason,
+      "outputLimits.headTailSplit must be between 0 and 1, got 1.5"
+    );
+    assert!(
+      validate_output_limits(OutputLimits {
+        max_tool_output_tokens: Some(0),
+        head_tail_split: None,
+      })
+      .is_err()
+    );
+  }
+
+  #[tokio::test]
+  async fn load_config_selects_the_requested_profile() {
+    let tempdir = TempDir::new().expect("tempdir");
+    let mut req = base_internal_request();
+    req.working_directory = Some(tempdir.path().to_path_buf());
+    req.config_profile = Some("sdk-test-missing-profile".to_string());
+
+    let err = load_config_from_internal(&req)
+      .await
+      .expect_err("unknown profile should fail");
+    assert!(err.reason.contains("config profile `sdk-test-missing-profile` not found"));
+    assert_eq!(
+      build_cli(&req, None, false).config_profile.as_deref(),
+      Some("sdk-test-missing-profile")
+    );
+  }
+
+  #[tokio::test]
+  async fn load_config_applies_instruction_overrides() {
+    let tempdir = TempDir::new().expect("tempdir");
+    let mut req = base_internal_request();
+    req.working_directory = Some(tempdir.path().to_path_buf());
+    req.base_instructions = Some("You are a release bot.".to_string());
+    req.developer_instructions = Some("Never push tags.".to_string());
+    req.compact_prompt = Some("Summarize the release so far.".to_string());
+
+    let config = load_config_from_internal(&req)
+      .await
+      .expect("config should load");
+
+    assert_eq!(config.base_instructions.as_deref(), Some("You are a release bot."));
+    assert_eq!(config.developer_instructions.as_deref(), Some("Never push tags."));
+    assert_eq!(config.compact_prompt.as_deref(), Some("Summarize the release so far."));
+
+    let cli = build_cli(&req, None, false);
+    assert_eq!(cli.developer_instructions.as_deref(), Some("Never push tags."));
+  }
+
+  #[tokio::test]
+  async fn connection_overrides_are_passed_explicitly() {
+    let tempdir = TempDir::new().expect("tempdir");
+    let mut req = base_internal_request();
+    req.working_directory = Some(tempdir.path().to_path_buf());
+    req.base_url = Some("http://127.0.0.1:9/v1".to_string());
+    req.api_key = Some("sk-run".to_string());
+    req.linux_sandbox_path = Some(PathBuf::from("/opt/codex-linux-sandbox"));
+
+    let overrides = RunOverrides::resolve(&req).expect("overrides resolve");
+    let mut cli = build_cli(&req, None, false);
+    overrides.apply_to_cli(&mut cli);
+    assert_eq!(cli.openai_base_url.as_deref(), Some("http://127.0.0.1:9/v1"));
+    assert_eq!(cli.api_key.as_deref(), Some("sk-run"));
+    assert_eq!(
+      overrides.linux_sandbox_path,
+      Some(PathBuf::from("/opt/codex-linux-sandbox"))
+    );
+
+    let config = load_config_from_internal(&req)
+      .await
+      .expect("config should load");
+    assert_eq!(config.model_provider.base_url.as_deref(), Some("http://127.0.0.1:9/v1"));
+    assert_ne!(
+      std::env::var("OPENAI_BASE_URL").ok().as_deref(),
+      Some("http://127.0.0.1:9/v1")
+    );
+  }
+
   #[test]
   fn parses_xhigh_reasoning_effort_alias() {
     let parsed = parse_reasoning_effort(Some("xhigh")).expect("parse succeeds");
@@ -79,6 +241,7 @@ mod tests_run {
   // MCP Configuration Tests
   mod mcp_tests {
     use super::*;
+    use codex_native_core::run::camel_to_snake;
 
     #[test]
     fn camel_to_snake_converts_correctly() {
@@ -227,15 +390,22 @@ mod tests_run {
     fn run_request_parses_mcp_config() {
       let request = RunRequest {
         prompt: "test".to_string(),
+        prompt_template: None,
         input_items: None,
         thread_id: None,
         images: None,
         model: None,
         model_provider: None,
+        profile: None,
         oss: None,
         sandbox_mode: None,
         approval_mode: None,
         workspace_write_options: None,
+        permission_profile: None,
+        allowed_tools: None,
+        denied_tools: None,
+        tool_limits: None,
+        cache_ttl_seconds: None,
         review_mode: None,
         review_hint: None,
         working_directory: None,
@@ -244,6 +414,7 @@ mod tests_run {
         tool_choice: None,
         base_url: None,
         api_key: None,
+        auth_profile: None,
         linux_sandbox_path: None,
         reasoning_effort: None,
         reasoning_summary: None,
@@ -251,11 +422,31 @@ mod tests_run {
         turn_personality: None,
         ephemeral: None,
         web_search_mode: None,
+        web_search: None,
         dynamic_tools: None,
         mcp: Some(serde_json::json!({
           "server1": {"command": "npx", "args": ["test"]}
         })),
         inherit_mcp: Some(false),
+        record: None,
+        replay: None,
+        patch_path_policy: None,
+        file_skeleton: None,
+        redaction: None,
+        post_turn_checks: None,
+        max_check_feedback_turns: None,
+        base_instructions: None,
+        developer_instructions: None,
+        compact_prompt: None,
+        event_delivery: None,
+        include_raw_events: None,
+        exec_session: None,
+        shell_pty: None,
+        tool_env: None,
+        verify: None,
+        memory: None,
+        escalation_handler: None,
+        output_limits: None,
       };
 
       let internal = request.into_internal().expect("parse should succeed");
@@ -267,15 +458,22 @@ mod tests_run {
     fn run_request_defaults_inherit_mcp_to_true() {
       let request = RunRequest {
         prompt: "test".to_string(),
+        prompt_template: None,
         input_items: None,
         thread_id: None,
         images: None,
         model: None,
         model_provider: None,
+        profile: None,
         oss: None,
         sandbox_mode: None,
         approval_mode: None,
         workspace_write_options: None,
+        permission_profile: None,
+        allowed_tools: None,
+        denied_tools: None,
+        tool_limits: None,
+        cache_ttl_seconds: None,
         review_mode: None,
         review_hint: None,
         working_directory: None,
@@ -284,6 +482,7 @@ mod tests_run {
         tool_choice: None,
         base_url: None,
         api_key: None,
+        auth_profile: None,
         linux_sandbox_path: None,
         reasoning_effort: None,
         reasoning_summary: None,
@@ -291,14 +490,112 @@ mod tests_run {
         turn_personality: None,
         ephemeral: None,
         web_search_mode: None,
+        web_search: None,
         dynamic_tools: None,
         mcp: None,
         inherit_mcp: None,
+        record: None,
+        replay: None,
+        patch_path_policy: None,
+        file_skeleton: None,
+        redaction: None,
+        post_turn_checks: None,
+        max_check_feedback_turns: None,
+        base_instructions: None,
+        developer_instructions: None,
+        compact_prompt: None,
+        event_delivery: None,
+        include_raw_events: None,
+        exec_session: None,
+        shell_pty: None,
+        tool_env: None,
+        verify: None,
+        memory: None,
+        escalation_handler: None,
+        output_limits: None,
       };
 
       let internal = request.into_internal().expect("parse should succeed");
       assert!(internal.inherit_mcp);
     }
+
+    #[test]
+    fn run_request_web_search_toggle_maps_to_mode() {
+      let request = |web_search: Option<bool>, web_search_mode: Option<&str>| RunRequest {
+        prompt: "test".to_string(),
+        prompt_template: None,
+        input_items: None,
+        thread_id: None,
+        images: None,
+        model: None,
+        model_provider: None,
+        profile: None,
+        oss: None,
+        sandbox_mode: None,
+        approval_mode: None,
+        workspace_write_options: None,
+        permission_profile: None,
+        allowed_tools: None,
+        denied_tools: None,
+        tool_limits: None,
+        cache_ttl_seconds: None,
+        review_mode: None,
+        review_hint: None,
+        working_directory: None,
+        skip_git_repo_check: Some(true),
+        output_schema: None,
+        tool_choice: None,
+        base_url: None,
+        api_key: None,
+        auth_profile: None,
+        linux_sandbox_path: None,
+        reasoning_effort: None,
+        reasoning_summary: None,
+        personality: None,
+        turn_personality: None,
+        ephemeral: None,
+        web_search_mode: web_search_mode.map(str::to_string),
+        web_search,
+        dynamic_tools: None,
+        mcp: None,
+        inherit_mcp: None,
+        record: None,
+        replay: None,
+        patch_path_policy: None,
+        file_skeleton: None,
+        redaction: None,
+        post_turn_checks: None,
+        max_check_feedback_turns: None,
+        base_instructions: None,
+        developer_instructions: None,
+        compact_prompt: None,
+        event_delivery: None,
+        include_raw_events: None,
+        exec_session: None,
+        shell_pty: None,
+        tool_env: None,
+        verify: None,
+        memory: None,
+        escalation_handler: None,
+        output_limits: None,
+      };
+      let mode = |web_search, web_search_mode| {
+        request(web_search, web_search_mode)
+          .into_internal()
+          .expect("parse should succeed")
+          .web_search_mode
+      };
+
+      assert_eq!(mode(Some(true), None), Some(WebSearchMode::Live));
+      assert_eq!(mode(Some(false), None), Some(WebSearchMode::Disabled));
+      assert_eq!(mode(Some(true), Some("cached")), Some(WebSearchMode::Cached));
+      assert_eq!(mode(None, None), None);
+
+      let mut internal = base_internal_request();
+      internal.web_search_mode = mode(Some(true), None);
+      let cli = build_cli(&internal, None, false);
+      assert!(cli.config_overrides.raw_overrides.contains(&"web_search=\"live\"".to_string()));
+    }
   }
 
 }
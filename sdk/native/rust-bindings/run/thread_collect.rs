@@ -0,0 +1,271 @@
+// ============================================================================
+// Collected runs (runThreadCollect)
+// ============================================================================
+//
+// Most runThread callers parse every event string and fold them the way
+// Thread.run() does: keep completed items, take the last agent message and
+// the turn's usage, and throw on a failed turn. runThreadCollect() does that
+// fold natively and reports the settings `thread.started` resolved.
+
+#[napi(object)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunThreadUsage {
+  #[napi(js_name = "input_tokens")]
+  pub input_tokens: i64,
+  #[napi(js_name = "cached_input_tokens")]
+  pub cached_input_tokens: i64,
+  #[napi(js_name = "output_tokens")]
+  pub output_tokens: i64,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunThreadCollectResult {
+  /// Text of the last agent message; empty when the agent sent none.
+  #[napi(js_name = "finalMessage")]
+  pub final_message: String,
+  /// Usage reported by the last completed turn.
+  pub usage: Option<RunThreadUsage>,
+  /// Completed items in order, as they appear in `item.completed` events.
+  pub items: Vec<JsonValue>,
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+  #[napi(js_name = "rolloutPath")]
+  pub rollout_path: Option<String>,
+  /// Model the session resolved to.
+  pub model: Option<String>,
+  #[napi(js_name = "modelProvider")]
+  pub model_provider: Option<String>,
+  /// Sandbox policy in effect, e.g. `workspace-write`.
+  #[napi(js_name = "sandboxPolicy")]
+  pub sandbox_policy: Option<String>,
+  pub cwd: Option<String>,
+  /// Config profile the session was loaded with, when one was selected.
+  #[napi(js_name = "configProfile")]
+  pub config_profile: Option<String>,
+}
+
+#[derive(Default)]
+struct RunThreadCollector {
+  result: RunThreadCollectResult,
+  failure: Option<String>,
+  /// Structured output of a review run, from `exited_review_mode`.
+  review_output: Option<codex_exec::exec_events::ReviewOutputEvent>,
+}
+
+impl RunThreadCollector {
+  fn observe(&mut self, event: &ExecThreadEvent) -> napi::Result<()> {
+    match event {
+      ExecThreadEvent::ThreadStarted(started) => {
+        self.result.thread_id = Some(started.thread_id.clone());
+        self.result.rollout_path = started.rollout_path.clone();
+        self.result.model = started.model.clone();
+        self.result.model_provider = started.model_provider.clone();
+        self.result.sandbox_policy = started.sandbox_policy.clone();
+        self.result.cwd = started.cwd.clone();
+        self.result.config_profile = started.config_profile.clone();
+      }
+      ExecThreadEvent::ItemCompleted(completed) => {
+        if let codex_exec::exec_events::ThreadItemDetails::AgentMessage(message) =
+          &completed.item.details
+        {
+          self.result.final_message = message.text.clone();
+        }
+        let item = serde_json::to_value(&completed.item)
+          .map_err(|e| napi::Error::from_reason(format!("Failed to serialize item: {e}")))?;
+        self.result.items.push(item);
+      }
+      ExecThreadEvent::TurnCompleted(completed) => {
+        self.result.usage = Some(RunThreadUsage {
+          input_tokens: completed.usage.input_tokens,
+          cached_input_tokens: completed.usage.cached_input_tokens,
+          output_tokens: completed.usage.output_tokens,
+        });
+      }
+      ExecThreadEvent::ExitedReviewMode(exited) => {
+        if let Some(output) = &exited.review_output {
+          self.review_output = Some(output.clone());
+        }
+      }
+      ExecThreadEvent::TurnFailed(failed) => {
+        self
+          .failure
+          .get_or_insert_with(|| failed.error.message.clone());
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+
+  fn finish(self) -> napi::Result<RunThreadCollectResult> {
+    match self.failure {
+      Some(message) => Err(napi::Error::from_reason(message)),
+      None => Ok(self.result),
+    }
+  }
+}
+
+#[napi]
+pub fn run_thread_collect(
+  req: RunRequest,
+) -> napi::Result<napi::bindgen_prelude::AsyncTask<RunThreadCollectTask>> {
+  let options = req.into_internal()?;
+  Ok(napi::bindgen_prelude::AsyncTask::new(
+    RunThreadCollectTask { options },
+  ))
+}
+
+pub struct RunThreadCollectTask {
+  options: InternalRunRequest,
+}
+
+impl napi::bindgen_prelude::Task for RunThreadCollectTask {
+  type Output = RunThreadCollectResult;
+  type JsValue = RunThreadCollectResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let mut result = collect_run(self.options.clone())?.finish()?;
+    if result.rollout_path.is_none()
+      && let Some(thread_id) = result.thread_id.clone()
+    {
+      result.rollout_path = resolve_rollout_path(&thread_id)?;
+    }
+    Ok(result)
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// Runs `options` to completion, folding its events.
+fn collect_run(options: InternalRunRequest) -> napi::Result<RunThreadCollector> {
+  let collector = Arc::new(Mutex::new(RunThreadCollector {
+    result: RunThreadCollectResult {
+      thread_id: options.thread_id.clone(),
+      ..Default::default()
+    },
+    ..Default::default()
+  }));
+  let error_holder: Arc<Mutex<Option<napi::Error>>> = Arc::new(Mutex::new(None));
+
+  let collector_clone = Arc::clone(&collector);
+  let error_clone = Arc::clone(&error_holder);
+  run_with_session_hooks(options, move |event| {
+    let observed = match collector_clone.lock() {
+      Ok(mut guard) => guard.observe(&event),
+      Err(e) => Err(napi::Error::from_reason(format!(
+        "collector mutex poisoned: {e}"
+      ))),
+    };
+    if let Err(err) = observed
+      && let Ok(mut guard) = error_clone.lock()
+    {
+      guard.get_or_insert(err);
+    }
+  })?;
+
+  if let Some(err) = error_holder.lock().unwrap().take() {
+    return Err(err);
+  }
+  Ok(std::mem::take(&mut *collector.lock().unwrap()))
+}
+
+fn resolve_rollout_path(thread_id: &str) -> napi::Result<Option<String>> {
+  let codex_home = find_codex_home()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to resolve CODEX_HOME: {e}")))?;
+  let runtime = tokio::runtime::Runtime::new()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
+  let path = runtime
+    .block_on(find_thread_path_by_id_str(&codex_home, thread_id))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?;
+  Ok(path.map(|path| path.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests_thread_collect {
+  use super::*;
+  use codex_exec::exec_events::AgentMessageItem;
+  use codex_exec::exec_events::ItemCompletedEvent;
+  use codex_exec::exec_events::ThreadErrorEvent;
+  use codex_exec::exec_events::ThreadItem;
+  use codex_exec::exec_events::ThreadItemDetails;
+  use codex_exec::exec_events::ThreadStartedEvent;
+  use codex_exec::exec_events::TurnCompletedEvent;
+  use codex_exec::exec_events::TurnFailedEvent;
+  use codex_exec::exec_events::Usage;
+  use pretty_assertions::assert_eq;
+
+  fn agent_message(id: &str, text: &str) -> ExecThreadEvent {
+    ExecThreadEvent::ItemCompleted(ItemCompletedEvent {
+      item: ThreadItem {
+        id: id.to_string(),
+        details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+          text: text.to_string(),
+        }),
+      },
+    })
+  }
+
+  #[test]
+  fn folds_items_final_message_and_usage() {
+    let mut collector = RunThreadCollector::default();
+    let events = [
+      ExecThreadEvent::ThreadStarted(ThreadStartedEvent {
+        thread_id: "thread-1".to_string(),
+        model: Some("gpt-5-codex".to_string()),
+        sandbox_policy: Some("workspace-write".to_string()),
+        rollout_path: Some("/tmp/rollout-thread-1.jsonl".to_string()),
+        ..Default::default()
+      }),
+      agent_message("item_0", "Looking at the parser"),
+      agent_message("item_1", "Fixed the trailing comma"),
+      ExecThreadEvent::TurnCompleted(TurnCompletedEvent {
+        usage: Usage {
+          input_tokens: 120,
+          cached_input_tokens: 20,
+          output_tokens: 30,
+        },
+        redaction_applied: None,
+        dropped_events: None,
+      }),
+    ];
+    for event in &events {
+      collector.observe(event).unwrap();
+    }
+    let result = collector.finish().unwrap();
+    assert_eq!(result.thread_id.as_deref(), Some("thread-1"));
+    assert_eq!(result.model.as_deref(), Some("gpt-5-codex"));
+    assert_eq!(result.sandbox_policy.as_deref(), Some("workspace-write"));
+    assert_eq!(
+      result.rollout_path.as_deref(),
+      Some("/tmp/rollout-thread-1.jsonl")
+    );
+    assert_eq!(result.final_message, "Fixed the trailing comma");
+    assert_eq!(
+      result.usage,
+      Some(RunThreadUsage {
+        input_tokens: 120,
+        cached_input_tokens: 20,
+        output_tokens: 30,
+      })
+    );
+    let ids: Vec<&str> = result
+      .items
+      .iter()
+      .filter_map(|item| item["id"].as_str())
+      .collect();
+    assert_eq!(ids, vec!["item_0", "item_1"]);
+    assert_eq!(result.items[1]["type"], "agent_message");
+
+    let mut failed = RunThreadCollector::default();
+    failed
+      .observe(&ExecThreadEvent::TurnFailed(TurnFailedEvent {
+        error: ThreadErrorEvent {
+          message: "model overloaded".to_string(),
+        },
+      }))
+      .unwrap();
+    assert_eq!(failed.finish().unwrap_err().reason, "model overloaded");
+  }
+}
@@ -0,0 +1,230 @@
+// ============================================================================
+// Edit-and-resume (resumeThreadWithEdits)
+// ============================================================================
+//
+// forkThread() can only cut history before the nth user message. To drop or
+// rewrite individual items, resumeThreadWithEdits() takes item ids from
+// getThreadItems(), writes a derived rollout with those records removed or
+// edited, and forks a new thread from it. Messages are recorded twice, once
+// as the UI event and once as the model-facing response item, so both copies
+// are edited together.
+
+#[napi(object)]
+pub struct UserMessageEdit {
+  /// Id of a `user_message` item from getThreadItems().
+  #[napi(js_name = "itemId")]
+  pub item_id: String,
+  pub text: String,
+}
+
+#[napi(object)]
+pub struct ThreadEdits {
+  /// Items from getThreadItems() to remove. Tool calls lose both the call and its output.
+  #[napi(js_name = "dropItemIds")]
+  pub drop_item_ids: Option<Vec<String>>,
+  #[napi(js_name = "replaceUserMessage")]
+  pub replace_user_message: Option<UserMessageEdit>,
+}
+
+fn history_item_text(item: &ThreadHistoryItem) -> Option<&str> {
+  item.item.get("text").and_then(JsonValue::as_str)
+}
+
+/// Text of a message or reasoning response item with the given kind.
+fn response_item_text(payload: &JsonValue, item_type: &str) -> Option<String> {
+  let (kind, role, parts_key, part_type) = match item_type {
+    "user_message" => ("message", Some("user"), "content", "input_text"),
+    "agent_message" => ("message", Some("assistant"), "content", "output_text"),
+    "reasoning" => ("reasoning", None, "summary", "summary_text"),
+    _ => return None,
+  };
+  if payload.get("type").and_then(JsonValue::as_str) != Some(kind)
+    || role.is_some_and(|role| payload.get("role").and_then(JsonValue::as_str) != Some(role))
+  {
+    return None;
+  }
+  let parts = payload.get(parts_key)?.as_array()?;
+  Some(
+    parts
+      .iter()
+      .filter(|part| part.get("type").and_then(JsonValue::as_str) == Some(part_type))
+      .filter_map(|part| part.get("text").and_then(JsonValue::as_str))
+      .collect::<Vec<_>>()
+      .join("\n"),
+  )
+}
+
+/// The response item recording the same message as `item`, nearest to it in the rollout.
+fn paired_response_record(rollout: &ParsedRollout, item: &ThreadHistoryItem) -> Option<usize> {
+  let text = history_item_text(item)?.trim();
+  let anchor = *item.record_indices.first()? as usize;
+  rollout
+    .records
+    .iter()
+    .enumerate()
+    .filter(|(_, record)| record.get("type").and_then(JsonValue::as_str) == Some("response_item"))
+    .filter(|(_, record)| {
+      record
+        .get("payload")
+        .and_then(|payload| response_item_text(payload, &item.item_type))
+        .is_some_and(|candidate| candidate.trim() == text)
+    })
+    .map(|(index, _)| index)
+    .min_by_key(|index| index.abs_diff(anchor))
+}
+
+/// The rollout's records with `edits` applied.
+fn edit_rollout_records(
+  rollout: &ParsedRollout,
+  edits: &ThreadEdits,
+) -> napi::Result<Vec<JsonValue>> {
+  let history = collect_thread_history(rollout);
+  let find_item = |id: &str| {
+    history
+      .iter()
+      .find(|item| item.id == id)
+      .ok_or_else(|| napi::Error::from_reason(format!("Unknown thread item id {id}")))
+  };
+
+  let mut dropped = std::collections::HashSet::new();
+  for id in edits.drop_item_ids.iter().flatten() {
+    let item = find_item(id)?;
+    dropped.extend(item.record_indices.iter().map(|index| *index as usize));
+    dropped.extend(paired_response_record(rollout, item));
+  }
+
+  let mut records = rollout.records.clone();
+  if let Some(edit) = &edits.replace_user_message {
+    let item = find_item(&edit.item_id)?;
+    if item.item_type != "user_message" {
+      return Err(napi::Error::from_reason(format!(
+        "Thread item {} is a {}, not a user_message",
+        edit.item_id, item.item_type
+      )));
+    }
+    if let Some(payload) = records[item.record_indices[0] as usize].get_mut("payload") {
+      payload["message"] = JsonValue::String(edit.text.clone());
+    }
+    if let Some(index) = paired_response_record(rollout, item)
+      && let Some(payload) = records[index].get_mut("payload")
+    {
+      payload["content"] = json!([{ "type": "input_text", "text": edit.text }]);
+    }
+  }
+
+  Ok(
+    records
+      .into_iter()
+      .enumerate()
+      .filter(|(index, _)| !dropped.contains(index))
+      .map(|(_, record)| record)
+      .collect(),
+  )
+}
+
+/// Write the edited copy of the rollout at `path` to a temporary file.
+fn write_edited_rollout(path: &Path, edits: &ThreadEdits) -> napi::Result<NamedTempFile> {
+  let rollout = load_cached_rollout(path).map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+  })?;
+  if let Some((line, err)) = rollout.invalid_lines.first() {
+    return Err(napi::Error::from_reason(format!(
+      "Invalid rollout line {line}: {err}"
+    )));
+  }
+  let records = edit_rollout_records(&rollout, edits)?;
+  let mut file = NamedTempFile::new()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create edited rollout: {e}")))?;
+  for record in &records {
+    writeln!(file, "{record}")
+      .map_err(|e| napi::Error::from_reason(format!("Failed to write edited rollout: {e}")))?;
+  }
+  file
+    .flush()
+    .map_err(|e| napi::Error::from_reason(format!("Failed to write edited rollout: {e}")))?;
+  Ok(file)
+}
+
+/// Fork `req.threadId` with some items dropped or edited. The original thread is left untouched;
+/// `nthUserMessage` is ignored.
+#[napi]
+pub async fn resume_thread_with_edits(
+  req: ForkRequest,
+  edits: ThreadEdits,
+) -> napi::Result<ForkResult> {
+  let mut internal = ForkRequest {
+    nth_user_message: Some(0),
+    ..req
+  }
+  .into_internal()?;
+  internal.nth_user_message = usize::MAX;
+  tokio::task::spawn_blocking(move || fork_thread_sync(internal, Some(edits)))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))?
+}
+
+#[cfg(test)]
+mod tests_thread_edits {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn edits_drop_tool_calls_and_rewrite_both_copies_of_a_user_message() {
+    let rollout = parse_rollout_contents(concat!(
+      r#"{"type":"session_meta","payload":{"id":"thread-1"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"list files"}]}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"list files"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"c1"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"README.md"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Done"}]}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"agent_message","message":"Done"}}"#,
+      "\n",
+    ));
+    let history = collect_thread_history(&rollout);
+    let id_of = |item_type: &str| {
+      history
+        .iter()
+        .find(|item| item.item_type == item_type)
+        .unwrap()
+        .id
+        .clone()
+    };
+
+    let records = edit_rollout_records(
+      &rollout,
+      &ThreadEdits {
+        drop_item_ids: Some(vec![id_of("command_execution"), id_of("agent_message")]),
+        replace_user_message: Some(UserMessageEdit {
+          item_id: id_of("user_message"),
+          text: "list only rust files".to_string(),
+        }),
+      },
+    )
+    .unwrap();
+
+    assert_eq!(
+      records,
+      vec![
+        json!({"type":"session_meta","payload":{"id":"thread-1"}}),
+        json!({"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"list only rust files"}]}}),
+        json!({"type":"event_msg","payload":{"type":"user_message","message":"list only rust files"}}),
+      ]
+    );
+
+    let err = edit_rollout_records(
+      &rollout,
+      &ThreadEdits {
+        drop_item_ids: Some(vec!["item_99".to_string()]),
+        replace_user_message: None,
+      },
+    )
+    .unwrap_err();
+    assert_eq!(err.reason, "Unknown thread item id item_99");
+  }
+}
@@ -0,0 +1,316 @@
+// ============================================================================
+// Conversation history query (getThreadItems)
+// ============================================================================
+//
+// History views need the items of a past thread, not the raw rollout. This
+// reuses the replay walker to turn rollout records into the same typed items
+// ThreadEvents carry, adds the user messages that replay only uses as turn
+// boundaries, and reports calls that never got an output as in progress.
+
+#[napi(object)]
+#[derive(Default)]
+pub struct GetThreadItemsOptions {
+  /// Item types to keep, e.g. `["user_message", "agent_message", "command_execution"]`.
+  pub types: Option<Vec<String>>,
+  /// Matching items to skip (default 0).
+  pub offset: Option<u32>,
+  /// Maximum number of items to return. Defaults to all.
+  pub limit: Option<u32>,
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct ThreadHistoryItem {
+  pub id: String,
+  #[napi(js_name = "type")]
+  pub item_type: String,
+  /// 0-based turn, counted by user messages.
+  #[napi(js_name = "turnIndex")]
+  pub turn_index: u32,
+  /// Indices of the rollout records the item was built from.
+  #[napi(js_name = "recordIndices")]
+  pub record_indices: Vec<u32>,
+  /// The item as it appears in `item.completed` events: `{ id, type, ...details }`.
+  /// User messages are `{ id, type: "user_message", text }`.
+  pub item: JsonValue,
+}
+
+#[napi(object)]
+pub struct ThreadItemsPage {
+  pub items: Vec<ThreadHistoryItem>,
+  /// Matching items before `offset` and `limit` were applied.
+  pub total: u32,
+}
+
+/// The same item with its status set to in progress, for calls the rollout has no output for.
+fn in_progress_item_details(
+  details: codex_exec::exec_events::ThreadItemDetails,
+) -> codex_exec::exec_events::ThreadItemDetails {
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  match details {
+    ThreadItemDetails::CommandExecution(mut command) => {
+      command.status = codex_exec::exec_events::CommandExecutionStatus::InProgress;
+      command.exit_code = None;
+      ThreadItemDetails::CommandExecution(command)
+    }
+    ThreadItemDetails::FileChange(mut change) => {
+      change.status = codex_exec::exec_events::PatchApplyStatus::InProgress;
+      ThreadItemDetails::FileChange(change)
+    }
+    ThreadItemDetails::McpToolCall(mut call) => {
+      call.status = codex_exec::exec_events::McpToolCallStatus::InProgress;
+      call.result = None;
+      ThreadItemDetails::McpToolCall(call)
+    }
+    other @ (ThreadItemDetails::AgentMessage(_)
+    | ThreadItemDetails::Reasoning(_)
+    | ThreadItemDetails::CollabToolCall(_)
+    | ThreadItemDetails::WebSearch(_)
+    | ThreadItemDetails::TodoList(_)
+    | ThreadItemDetails::Error(_)) => other,
+  }
+}
+
+fn thread_history_item(
+  item: codex_exec::exec_events::ThreadItem,
+  turn_index: u32,
+  record_indices: Vec<u32>,
+) -> ThreadHistoryItem {
+  let value = serde_json::to_value(&item).unwrap_or(JsonValue::Null);
+  ThreadHistoryItem {
+    item_type: value
+      .get("type")
+      .and_then(JsonValue::as_str)
+      .unwrap_or_default()
+      .to_string(),
+    id: item.id,
+    turn_index,
+    record_indices,
+    item: value,
+  }
+}
+
+/// Every item recorded in a rollout, in record order.
+fn collect_thread_history(rollout: &ParsedRollout) -> Vec<ThreadHistoryItem> {
+  let mut replayer = RolloutReplayer::default();
+  let no_mocks = HashMap::new();
+  let mut items = Vec::new();
+  let mut user_messages = 0u32;
+  let mut call_records: HashMap<String, u32> = HashMap::new();
+  for (index, value) in rollout.records.iter().enumerate() {
+    let index = index as u32;
+    let payload = value.get("payload").unwrap_or(&JsonValue::Null);
+    let before = replayer.events.len();
+    match value.get("type").and_then(JsonValue::as_str) {
+      Some("event_msg") => {
+        if payload.get("type").and_then(JsonValue::as_str) == Some("user_message") {
+          user_messages += 1;
+          let id = replayer.next_item_id();
+          let text = payload
+            .get("message")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+          items.push(ThreadHistoryItem {
+            item: json!({ "id": id, "type": "user_message", "text": text }),
+            id,
+            item_type: "user_message".to_string(),
+            turn_index: user_messages - 1,
+            record_indices: vec![index],
+          });
+        }
+        replayer.handle_event_msg(payload);
+      }
+      Some("response_item") => {
+        let call_id = payload.get("call_id").and_then(JsonValue::as_str);
+        if let Some(call_id) = call_id
+          && matches!(
+            payload.get("type").and_then(JsonValue::as_str),
+            Some("function_call" | "custom_tool_call" | "local_shell_call")
+          )
+        {
+          call_records.insert(call_id.to_string(), index);
+        }
+        replayer.handle_response_item(payload, &no_mocks);
+      }
+      _ => {}
+    }
+    let turn_index = user_messages.saturating_sub(1);
+    for event in replayer.events.drain(before..) {
+      match event {
+        ExecThreadEvent::ItemCompleted(completed) => {
+          let mut record_indices: Vec<u32> = payload
+            .get("call_id")
+            .and_then(JsonValue::as_str)
+            .and_then(|call_id| call_records.remove(call_id))
+            .into_iter()
+            .collect();
+          record_indices.push(index);
+          items.push(thread_history_item(
+            completed.item,
+            turn_index,
+            record_indices,
+          ));
+        }
+        ExecThreadEvent::ThreadStarted(_)
+        | ExecThreadEvent::TurnStarted(_)
+        | ExecThreadEvent::TurnCompleted(_)
+        | ExecThreadEvent::TurnFailed(_)
+        | ExecThreadEvent::ItemStarted(_)
+        | ExecThreadEvent::ItemUpdated(_)
+        | ExecThreadEvent::Error(_)
+        | ExecThreadEvent::ExitedReviewMode(_)
+        | ExecThreadEvent::BackgroundEvent(_)
+        | ExecThreadEvent::ToolOutputChunk(_)
+        | ExecThreadEvent::BackgroundTaskStarted(_)
+        | ExecThreadEvent::BackgroundTaskCompleted(_)
+        | ExecThreadEvent::PlanUpdated(_)
+        | ExecThreadEvent::ThreadStatus(_)
+        | ExecThreadEvent::TestResults(_)
+        | ExecThreadEvent::VerificationFailed(_)
+        | ExecThreadEvent::FilesTouched(_)
+        | ExecThreadEvent::CommandStarted(_)
+        | ExecThreadEvent::CommandCompleted(_)
+        | ExecThreadEvent::Raw(_) => {}
+      }
+    }
+  }
+
+  // Sorted so item ids stay stable across reads.
+  let mut pending: Vec<(u32, PendingReplayCall)> = std::mem::take(&mut replayer.pending_calls)
+    .into_iter()
+    .filter_map(|(call_id, call)| Some((call_records.remove(&call_id)?, call)))
+    .collect();
+  pending.sort_by_key(|(index, _)| *index);
+  for (index, call) in pending {
+    let item = codex_exec::exec_events::ThreadItem {
+      id: replayer.next_item_id(),
+      details: in_progress_item_details(replay_tool_item(&call, String::new())),
+    };
+    let turn_index = items
+      .iter()
+      .filter(|item| item.item_type == "user_message" && item.record_indices[0] < index)
+      .count()
+      .saturating_sub(1) as u32;
+    items.push(thread_history_item(item, turn_index, vec![index]));
+  }
+  items.sort_by_key(|item| item.record_indices.first().copied());
+  items
+}
+
+fn page_thread_history(
+  items: Vec<ThreadHistoryItem>,
+  options: &GetThreadItemsOptions,
+) -> ThreadItemsPage {
+  let matching: Vec<ThreadHistoryItem> = match options.types.as_deref() {
+    Some(types) => items
+      .into_iter()
+      .filter(|item| types.contains(&item.item_type))
+      .collect(),
+    None => items,
+  };
+  let total = matching.len() as u32;
+  let limit = options.limit.map_or(usize::MAX, |limit| limit as usize);
+  ThreadItemsPage {
+    items: matching
+      .into_iter()
+      .skip(options.offset.unwrap_or(0) as usize)
+      .take(limit)
+      .collect(),
+    total,
+  }
+}
+
+/// Locate a thread's rollout under codex home.
+async fn thread_rollout_path(thread_id: &str, codex_home: Option<&str>) -> napi::Result<PathBuf> {
+  let codex_home = match codex_home {
+    Some(path) => PathBuf::from(path),
+    None => find_codex_home()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?,
+  };
+  find_thread_path_by_id_str(&codex_home, thread_id)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?
+    .ok_or_else(|| napi::Error::from_reason(format!("No rollout found for thread {thread_id}")))
+}
+
+/// Typed items recorded for a thread, read from its rollout.
+#[napi]
+pub async fn get_thread_items(
+  thread_id: String,
+  options: Option<GetThreadItemsOptions>,
+) -> napi::Result<ThreadItemsPage> {
+  let options = options.unwrap_or_default();
+  let path = thread_rollout_path(&thread_id, options.codex_home.as_deref()).await?;
+  let rollout = load_cached_rollout(&path).map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+  })?;
+  Ok(page_thread_history(
+    collect_thread_history(&rollout),
+    &options,
+  ))
+}
+
+#[cfg(test)]
+mod tests_thread_items {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn history_lists_user_messages_tool_calls_and_unfinished_calls() {
+    let rollout = parse_rollout_contents(concat!(
+      r#"{"type":"session_meta","payload":{"id":"thread-1"}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"list files"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"c1"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"Exit code: 2"}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"agent_message","message":"ls failed"}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"patch it"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Add File: a.txt","call_id":"c2"}}"#,
+      "\n",
+    ));
+    let items = collect_thread_history(&rollout);
+    let summary: Vec<(String, u32, Vec<u32>)> = items
+      .iter()
+      .map(|item| {
+        (
+          item.item_type.clone(),
+          item.turn_index,
+          item.record_indices.clone(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        ("user_message".to_string(), 0, vec![1]),
+        ("command_execution".to_string(), 0, vec![2, 3]),
+        ("agent_message".to_string(), 0, vec![4]),
+        ("user_message".to_string(), 1, vec![5]),
+        ("file_change".to_string(), 1, vec![6]),
+      ]
+    );
+    assert_eq!(items[0].item["text"], "list files");
+    assert_eq!(items[1].item["status"], "failed");
+    assert_eq!(items[4].item["status"], "in_progress");
+
+    let page = page_thread_history(
+      items,
+      &GetThreadItemsOptions {
+        types: Some(vec!["user_message".to_string()]),
+        offset: Some(1),
+        ..Default::default()
+      },
+    );
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].item["text"], "patch it");
+  }
+}
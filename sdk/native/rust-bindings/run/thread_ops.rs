@@ -16,7 +16,21 @@ fn cursor_to_string(cursor: &codex_core::Cursor) -> napi::Result<String> {
     .map_err(|e| napi::Error::from_reason(format!("Failed to serialize cursor: {e}")))
 }
 
-fn conversation_item_to_summary(item: codex_core::ThreadItem) -> ConversationSummary {
+fn conversation_item_to_summary(
+  item: codex_core::ThreadItem,
+  titles: &HashMap<codex_protocol::ThreadId, String>,
+  summaries: &HashMap<String, ThreadSummary>,
+  tasks: &HashMap<String, ThreadTaskLink>,
+) -> ConversationSummary {
+  let title = item.thread_id.and_then(|id| titles.get(&id).cloned());
+  let summary = item
+    .thread_id
+    .and_then(|id| summaries.get(&id.to_string()))
+    .map(|entry| entry.summary.clone());
+  let task = item
+    .thread_id
+    .and_then(|id| tasks.get(&id.to_string()))
+    .cloned();
   let id = item
     .path
     .file_stem()
@@ -29,29 +43,15 @@ fn conversation_item_to_summary(item: codex_core::ThreadItem) -> ConversationSum
     path: item.path.to_string_lossy().into_owned(),
     created_at: item.created_at,
     updated_at: item.updated_at,
+    title,
+    summary,
+    task,
   }
 }
 
 fn event_to_json(event: &ExecThreadEvent) -> napi::Result<JsonValue> {
-  match event {
-    ExecThreadEvent::ExitedReviewMode(inner) => {
-      let review_output = match &inner.review_output {
-        Some(output) => {
-          serde_json::to_value(output).map_err(|e| napi::Error::from_reason(e.to_string()))?
-        }
-        None => JsonValue::Null,
-      };
-      let mut map = JsonMap::new();
-      map.insert(
-        "type".to_string(),
-        JsonValue::String("exited_review_mode".to_string()),
-      );
-      map.insert("review_output".to_string(), review_output);
-      Ok(JsonValue::Object(map))
-    }
-    ExecThreadEvent::Raw(_) => Ok(JsonValue::Null),
-    _ => serde_json::to_value(event).map_err(|e| napi::Error::from_reason(e.to_string())),
-  }
+  codex_native_core::events::event_to_json(event)
+    .map_err(|e| napi::Error::from_reason(e.to_string()))
 }
 
 
@@ -76,8 +76,10 @@ impl napi::bindgen_prelude::Task for RunThreadTask {
     let events_clone = Arc::clone(&events);
     let error_clone: Arc<Mutex<Option<napi::Error>>> = Arc::clone(&error_holder);
 
-    run_internal_sync(self.options.clone(), move |event| match event_to_json(&event) {
+    let mut sequencer = ThreadEventSequencer::new(self.options.thread_id.clone());
+    run_with_session_hooks(self.options.clone(), move |event| match event_to_json(&event) {
       Ok(value) => {
+        let value = sequencer.stamp(&event, value);
         if let Ok(mut guard) = events_clone.lock() {
           match serde_json::to_string(&value) {
             Ok(text) => guard.push(text),
@@ -111,7 +113,6 @@ impl napi::bindgen_prelude::Task for RunThreadTask {
 
 #[napi]
 pub async fn compact_thread(req: RunRequest) -> napi::Result<Vec<String>> {
-  ensure_apply_patch_aliases()?;
   let options = req.into_internal()?;
   let events: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
   let error_holder: Arc<Mutex<Option<napi::Error>>> = Arc::new(Mutex::new(None));
@@ -122,14 +123,11 @@ pub async fn compact_thread(req: RunRequest) -> napi::Result<Vec<String>> {
   tokio::task::spawn_blocking(move || {
     let schema_file = prepare_schema(options.output_schema.clone())?;
     let schema_path = schema_file.as_ref().map(|file| file.path.clone());
-    let cli = build_cli(&options, schema_path, true);
-    let pending_tools = {
-      let guard = registered_native_tools()
-        .lock()
-        .map_err(|e| napi::Error::from_reason(format!("tools mutex poisoned: {e}")))?;
-      guard.clone()
-    };
-    set_pending_external_tools(pending_tools);
+    let mut cli = build_cli(&options, schema_path, true);
+    let pending_tools = registered_native_tools()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("tools mutex poisoned: {e}")))?
+      .clone();
     let pending_interceptors = {
       let guard = registered_native_interceptors()
         .lock()
@@ -142,19 +140,26 @@ pub async fn compact_thread(req: RunRequest) -> napi::Result<Vec<String>> {
         })
         .collect::<Vec<_>>()
     };
-    set_pending_external_interceptors(pending_interceptors);
-    let linux_sandbox_path = if let Some(path) = options.linux_sandbox_path.clone() {
-      Some(path)
-    } else if let Ok(path) = std::env::var("CODEX_LINUX_SANDBOX_EXE") {
-      Some(PathBuf::from(path))
-    } else {
-      default_linux_sandbox_path()?
+    // Compaction runs no tools, but it still gets this run's filter and limits so none are
+    // left over from an earlier run.
+    let layers = ExecLayers {
+      external_tools: pending_tools,
+      tool_filter: options.tool_filter.clone(),
+      tool_limits: run_tool_limits(&options)?,
+      interceptors: pending_interceptors,
+      escalation_handler: None,
     };
+    ensure_native_originator();
+    let overrides = RunOverrides::resolve(&options)?;
+    overrides.apply_to_cli(&mut cli);
+    let linux_sandbox_path = overrides.linux_sandbox_path;
     let rt = tokio::runtime::Runtime::new().map_err(|e| napi::Error::from_reason(e.to_string()))?;
     rt.block_on(async move {
-      let fut = run_with_thread_event_callback(cli, linux_sandbox_path, move |event| {
+      let mut sequencer = ThreadEventSequencer::new(options.thread_id.clone());
+      let fut = run_exec(cli, linux_sandbox_path, layers, move |event| {
         match event_to_json(&event) {
           Ok(value) => {
+            let value = sequencer.stamp(&event, value);
             if let Ok(mut guard) = events_clone.lock() {
               match serde_json::to_string(&value) {
                 Ok(text) => guard.push(text),
@@ -192,7 +197,7 @@ pub async fn compact_thread(req: RunRequest) -> napi::Result<Vec<String>> {
 #[napi]
 pub async fn fork_thread(req: ForkRequest) -> napi::Result<ForkResult> {
   let internal = req.into_internal()?;
-  tokio::task::spawn_blocking(move || fork_thread_sync(internal))
+  tokio::task::spawn_blocking(move || fork_thread_sync(internal, None))
     .await
     .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))?
 }
@@ -214,22 +219,29 @@ pub async fn list_conversations(req: ListConversationsRequest) -> napi::Result<C
 
   let page_size = req.page_size.unwrap_or(20).max(1) as usize;
 
-  let page = RolloutRecorder::list_threads(
-    &config.codex_home,
-    page_size,
-    cursor.as_ref(),
-    codex_core::ThreadSortKey::UpdatedAt,
-    &[],
-    provider_slice,
-    &config.model_provider_id,
-  )
-  .await
-  .map_err(|e| napi::Error::from_reason(format!("Failed to list conversations: {e}")))?;
+  let cwd = req.cwd.map(PathBuf::from);
+  let store = codex_core::rollout_store_for_config(&config).await;
+  let page = store
+    .list_threads(codex_core::ThreadListQuery {
+      cursor: cursor.as_ref(),
+      model_providers: provider_slice,
+      cwd: cwd.as_deref(),
+      ..codex_core::ThreadListQuery::new(page_size)
+    })
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to list conversations: {e}")))?;
 
+  let thread_ids: std::collections::HashSet<codex_protocol::ThreadId> =
+    page.items.iter().filter_map(|item| item.thread_id).collect();
+  let titles = codex_core::find_thread_names_by_ids(&config.codex_home, &thread_ids)
+    .await
+    .unwrap_or_default();
+  let summaries = read_thread_summaries(&config.codex_home);
+  let tasks = read_thread_tasks(&config.codex_home);
   let conversations = page
     .items
     .into_iter()
-    .map(conversation_item_to_summary)
+    .map(|item| conversation_item_to_summary(item, &titles, &summaries, &tasks))
     .collect();
   let next_cursor = match page.next_cursor.as_ref() {
     Some(c) => Some(cursor_to_string(c)?),
@@ -244,6 +256,7 @@ pub async fn list_conversations(req: ListConversationsRequest) -> napi::Result<C
     next_cursor,
     num_scanned_files: num_scanned,
     reached_scan_cap: page.reached_scan_cap,
+    store_backend: store.name().to_string(),
   })
 }
 
@@ -263,6 +276,7 @@ pub async fn delete_conversation(
   if let Some(path) = path {
     match tokio::fs::remove_file(&path).await {
       Ok(_) => {
+        bump_reverie_corpus_version();
         return Ok(DeleteConversationResult { deleted: true });
       }
       Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
@@ -284,11 +298,7 @@ pub async fn resume_conversation_from_rollout(
   let config = load_config_from_internal(&options).await?;
   ensure_trusted_directory_from_options(&options, &config)?;
 
-  let auth_manager = AuthManager::shared(
-    config.codex_home.clone(),
-    true,
-    config.cli_auth_credentials_store_mode,
-  );
+  let auth_manager = run_auth_manager(&options, &config);
   let manager =
     ThreadManager::new(config.codex_home.clone(), auth_manager.clone(), SessionSource::Exec);
   let rollout_path = PathBuf::from(req.rollout_path);
@@ -317,48 +327,22 @@ pub async fn resume_conversation_from_rollout(
   })
 }
 
-fn fork_thread_sync(req: InternalForkRequest) -> napi::Result<ForkResult> {
+fn fork_thread_sync(
+  req: InternalForkRequest,
+  edits: Option<ThreadEdits>,
+) -> napi::Result<ForkResult> {
   let thread_id = req.thread_id;
   let nth_user_message = req.nth_user_message;
   let options = req.run_options;
 
-  let mut env_pairs: Vec<(&'static str, Option<String>, bool)> = Vec::new();
-  if std::env::var(ORIGINATOR_ENV).is_err() {
-    env_pairs.push((ORIGINATOR_ENV, Some(NATIVE_ORIGINATOR.to_string()), true));
-  }
-  if let Some(base_url) = options.base_url.clone() {
-    env_pairs.push(("OPENAI_BASE_URL", Some(base_url), true));
-  }
-  if let Some(api_key) = options.api_key.clone() {
-    env_pairs.push(("CODEX_API_KEY", Some(api_key), true));
-  }
-
-  let linux_sandbox_path = if let Some(path) = options.linux_sandbox_path.clone() {
-    Some(path)
-  } else if let Ok(path) = std::env::var("CODEX_LINUX_SANDBOX_EXE") {
-    Some(PathBuf::from(path))
-  } else {
-    default_linux_sandbox_path()?
-  };
-
-  if let Some(path) = linux_sandbox_path.as_ref() {
-    env_pairs.push((
-      "CODEX_LINUX_SANDBOX_EXE",
-      Some(path.to_string_lossy().to_string()),
-      false,
-    ));
-  }
-
-  let _env_guard = EnvOverrides::apply(env_pairs);
+  ensure_native_originator();
+  let linux_sandbox_path = RunOverrides::resolve(&options)?.linux_sandbox_path;
 
   let runtime = tokio::runtime::Runtime::new()
     .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
 
   runtime.block_on(async move {
-    let (overrides, cli_kv_overrides) = build_config_inputs(&options, linux_sandbox_path.clone())?;
-    let config = Config::load_with_cli_overrides_and_harness_overrides(cli_kv_overrides, overrides)
-      .await
-      .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let config = load_run_config(&options, linux_sandbox_path).await?;
 
     if !options.skip_git_repo_check && get_git_repo_root(&config.cwd).is_none() {
       return Err(napi::Error::from_reason(
@@ -366,11 +350,7 @@ fn fork_thread_sync(req: InternalForkRequest) -> napi::Result<ForkResult> {
       ));
     }
 
-    let auth_manager = AuthManager::shared(
-      config.codex_home.clone(),
-      true,
-      config.cli_auth_credentials_store_mode,
-    );
+    let auth_manager = run_auth_manager(&options, &config);
 
     let path_opt = find_thread_path_by_id_str(&config.codex_home, &thread_id)
       .await
@@ -386,10 +366,17 @@ fn fork_thread_sync(req: InternalForkRequest) -> napi::Result<ForkResult> {
       ))
     })?;
 
+    let edited = edits
+      .map(|edits| write_edited_rollout(&path, &edits))
+      .transpose()?;
+    let fork_path = edited
+      .as_ref()
+      .map_or_else(|| path.clone(), |file| file.path().to_path_buf());
+
     let manager = ThreadManager::new(config.codex_home.clone(), auth_manager, SessionSource::Exec);
 
     let new_conv = manager
-      .fork_thread(nth_user_message, config.clone(), path.clone())
+      .fork_thread(nth_user_message, config.clone(), fork_path)
       .await
       .map_err(|e| napi::Error::from_reason(format!("Failed to fork conversation: {e}")))?;
 
@@ -405,6 +392,8 @@ fn fork_thread_sync(req: InternalForkRequest) -> napi::Result<ForkResult> {
       .to_string();
 
     manager.remove_thread(&new_conv.thread_id).await;
+    record_agent_graph_fork(&thread_id, &new_id);
+    inherit_thread_plan(&thread_plans_path()?, &thread_id, &new_id);
 
     Ok(ForkResult {
       thread_id: new_id,
@@ -413,6 +402,13 @@ fn fork_thread_sync(req: InternalForkRequest) -> napi::Result<ForkResult> {
   })
 }
 
+/// Ends the thread's persistent shell (`execSession: "persistent"`), so its next shell command
+/// starts from a fresh one. Returns `false` when the thread has no shell running.
+#[napi]
+pub fn reset_exec_session(thread_id: String) -> bool {
+  codex_core::reset_exec_session(&thread_id)
+}
+
 #[napi]
 pub fn run_apply_patch(patch: String) -> napi::Result<()> {
   let mut stdout = std::io::stdout();
@@ -424,6 +420,7 @@ pub fn run_apply_patch(patch: String) -> napi::Result<()> {
 pub struct RunThreadStreamTask {
   options: InternalRunRequest,
   on_event: Option<ThreadsafeFunction<JsonValue>>,
+  delivery: Option<Arc<EventDeliveryQueue>>,
 }
 
 impl napi::bindgen_prelude::Task for RunThreadStreamTask {
@@ -438,29 +435,62 @@ impl napi::bindgen_prelude::Task for RunThreadStreamTask {
     let error_holder: Arc<Mutex<Option<napi::Error>>> = Arc::new(Mutex::new(None));
     let error_clone: Arc<Mutex<Option<napi::Error>>> = Arc::clone(&error_holder);
 
-    run_internal_sync(self.options.clone(), move |event| match event_to_json(&event) {
-      Ok(value) => match serde_json::to_string(&value) {
-        Ok(text) => {
-          let status = on_event.call(
-            Ok(JsonValue::String(text)),
-            ThreadsafeFunctionCallMode::NonBlocking,
-          );
-          if status != Status::Ok
-            && let Ok(mut guard) = error_clone.lock()
-          {
-            *guard = Some(napi::Error::from_status(status));
-          }
+    let delivery = self.delivery.take();
+    let mut sequencer = ThreadEventSequencer::new(self.options.thread_id.clone());
+    run_with_session_hooks(self.options.clone(), move |mut event| {
+      if let Some(queue) = &delivery {
+        if !queue.acquire(&event) {
+          return;
+        }
+        if let ExecThreadEvent::TurnCompleted(completed) = &mut event {
+          completed.dropped_events = Some(queue.take_dropped());
         }
+      }
+      let release = || {
+        if let Some(queue) = &delivery {
+          queue.release();
+        }
+      };
+      match event_to_json(&event) {
+        Ok(value) => match serde_json::to_string(&sequencer.stamp(&event, value)) {
+          Ok(text) => {
+            let status = match &delivery {
+              Some(queue) => {
+                let queue = Arc::clone(queue);
+                on_event.call_with_return_value(
+                  Ok(JsonValue::String(text)),
+                  ThreadsafeFunctionCallMode::NonBlocking,
+                  move |_, _| {
+                    queue.release();
+                    Ok(())
+                  },
+                )
+              }
+              None => on_event.call(
+                Ok(JsonValue::String(text)),
+                ThreadsafeFunctionCallMode::NonBlocking,
+              ),
+            };
+            if status != Status::Ok {
+              release();
+              if let Ok(mut guard) = error_clone.lock() {
+                *guard = Some(napi::Error::from_status(status));
+              }
+            }
+          }
+          Err(err) => {
+            release();
+            if let Ok(mut guard) = error_clone.lock() {
+              *guard = Some(napi::Error::from_reason(err.to_string()));
+            }
+          }
+        },
         Err(err) => {
+          release();
           if let Ok(mut guard) = error_clone.lock() {
-            *guard = Some(napi::Error::from_reason(err.to_string()));
+            *guard = Some(err);
           }
         }
-      },
-      Err(err) => {
-        if let Ok(mut guard) = error_clone.lock() {
-          *guard = Some(err);
-        }
       }
     })?;
 
@@ -478,14 +508,21 @@ impl napi::bindgen_prelude::Task for RunThreadStreamTask {
 
 #[napi]
 pub fn run_thread_stream(
-  req: RunRequest,
+  mut req: RunRequest,
   #[napi(ts_arg_type = "(err: unknown, eventJson?: string) => void")] on_event: ThreadsafeFunction<
     JsonValue,
   >,
 ) -> napi::Result<napi::bindgen_prelude::AsyncTask<RunThreadStreamTask>> {
+  let delivery = req
+    .event_delivery
+    .take()
+    .map(EventDeliveryQueue::new)
+    .transpose()?
+    .map(Arc::new);
   let options = req.into_internal()?;
   Ok(napi::bindgen_prelude::AsyncTask::new(RunThreadStreamTask {
     options,
     on_event: Some(on_event),
+    delivery,
   }))
 }
@@ -0,0 +1,273 @@
+// ============================================================================
+// Thread rollups (summarizeAndStore)
+// ============================================================================
+//
+// Very long sessions are expensive to embed and noisy to search chunk by
+// chunk. summarizeAndStore() condenses every `turnsPerRollup` turns of a
+// thread into one summary and stores them in `<rollout>.rollups.json`, next to
+// the rollout (the name doesn't end in `.jsonl`, so thread listings skip it).
+// Calls are incremental: turns already rolled up are kept, and only complete
+// groups after them are summarized, each with the previous summary as
+// context. The latest turn is never rolled up, since it may still be running.
+// Reverie indexing embeds a thread's rollups in place of the raw messages
+// they cover.
+
+const DEFAULT_TURNS_PER_ROLLUP: u32 = 8;
+const DEFAULT_ROLLUP_MAX_TOKENS: u32 = 200;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct SummarizeAndStoreOptions {
+  /// Turns condensed into each rollup (default 8).
+  #[napi(js_name = "turnsPerRollup")]
+  pub turns_per_rollup: Option<u32>,
+  /// Model for the summary turns. Defaults to the configured model.
+  pub model: Option<String>,
+  /// Upper bound on each rollup's length (default 200).
+  #[napi(js_name = "maxTokens")]
+  pub max_tokens: Option<u32>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadRollup {
+  /// First turn covered, 0-based and counted by user messages.
+  #[napi(js_name = "startTurn")]
+  pub start_turn: u32,
+  /// Turn after the last one covered.
+  #[napi(js_name = "endTurn")]
+  pub end_turn: u32,
+  /// Index of the first rollout record after the covered turns.
+  #[napi(js_name = "endRecord")]
+  pub end_record: u32,
+  pub summary: String,
+  pub model: Option<String>,
+  #[napi(js_name = "generatedAt")]
+  pub generated_at: String,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredThreadRollups {
+  thread_id: String,
+  rollups: Vec<ThreadRollup>,
+}
+
+fn thread_rollups_path(rollout_path: &Path) -> PathBuf {
+  rollout_path.with_extension("rollups.json")
+}
+
+/// The rollups stored next to `rollout_path`, oldest first; empty when there are none.
+fn read_thread_rollups(rollout_path: &Path) -> Vec<ThreadRollup> {
+  std::fs::read_to_string(thread_rollups_path(rollout_path))
+    .ok()
+    .and_then(|contents| serde_json::from_str::<StoredThreadRollups>(&contents).ok())
+    .map(|stored| stored.rollups)
+    .unwrap_or_default()
+}
+
+fn write_thread_rollups(rollout_path: &Path, stored: &StoredThreadRollups) -> napi::Result<()> {
+  let path = thread_rollups_path(rollout_path);
+  let contents = serde_json::to_string_pretty(stored)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize thread rollups: {e}")))?;
+  let temp = path.with_extension("json.tmp");
+  std::fs::write(&temp, contents)
+    .and_then(|()| std::fs::rename(&temp, &path))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to store thread rollups: {e}")))
+}
+
+/// The next groups of `turns_per_rollup` turns after `covered_turns`, as `(start, end)` turn
+/// ranges. Only turns followed by another user message count as complete.
+fn pending_rollup_ranges(
+  items: &[ThreadHistoryItem],
+  covered_turns: u32,
+  turns_per_rollup: u32,
+) -> Vec<(u32, u32)> {
+  let turns = items
+    .iter()
+    .map(|item| item.turn_index + 1)
+    .max()
+    .unwrap_or(0);
+  let complete = turns.saturating_sub(1);
+  let mut ranges = Vec::new();
+  let mut start = covered_turns;
+  while start + turns_per_rollup <= complete {
+    ranges.push((start, start + turns_per_rollup));
+    start += turns_per_rollup;
+  }
+  ranges
+}
+
+/// The first record of turn `turn`, which is where the turns before it end.
+fn turn_start_record(items: &[ThreadHistoryItem], turn: u32) -> u32 {
+  items
+    .iter()
+    .filter(|item| item.turn_index == turn)
+    .flat_map(|item| item.record_indices.iter().copied())
+    .min()
+    .unwrap_or(0)
+}
+
+fn thread_rollup_prompt(
+  transcript: &str,
+  previous: Option<&str>,
+  range: (u32, u32),
+  max_tokens: u32,
+) -> String {
+  let earlier = previous
+    .map(|summary| format!("Summary of the session so far:\n{summary}\n\n"))
+    .unwrap_or_default();
+  format!(
+    "{earlier}Summarize turns {} to {} of the coding session transcript below in at most \
+     {max_tokens} tokens. Keep the concrete details later work may need: file and function \
+     names, commands, errors, decisions and their reasons. Do not run any tools.\n\n\
+     <transcript>\n{transcript}\n</transcript>",
+    range.0 + 1,
+    range.1
+  )
+}
+
+fn thread_rollup_schema() -> JsonValue {
+  json!({
+    "type": "object",
+    "properties": {
+      "summary": { "type": "string" }
+    },
+    "required": ["summary"],
+    "additionalProperties": false
+  })
+}
+
+/// Summarize the thread's complete, not yet rolled-up turns and return all of its rollups.
+#[napi]
+pub async fn summarize_and_store(
+  thread_id: String,
+  options: Option<SummarizeAndStoreOptions>,
+) -> napi::Result<Vec<ThreadRollup>> {
+  #[derive(serde::Deserialize)]
+  struct Output {
+    summary: String,
+  }
+  let options = options.unwrap_or_default();
+  let turns_per_rollup = options
+    .turns_per_rollup
+    .unwrap_or(DEFAULT_TURNS_PER_ROLLUP)
+    .max(1);
+  let max_tokens = options
+    .max_tokens
+    .unwrap_or(DEFAULT_ROLLUP_MAX_TOKENS)
+    .max(1);
+  let mut run_options = options.config.unwrap_or_default().into_internal_request()?;
+  let config = load_config_from_internal(&run_options).await?;
+  let path = find_thread_path_by_id_str(&config.codex_home, &thread_id)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?
+    .ok_or_else(|| napi::Error::from_reason(format!("No rollout found for thread {thread_id}")))?;
+  let rollout = load_cached_rollout(&path).map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+  })?;
+  let items = collect_thread_history(&rollout);
+  let mut rollups = read_thread_rollups(&path);
+  let covered = rollups.last().map_or(0, |rollup| rollup.end_turn);
+  let ranges = pending_rollup_ranges(&items, covered, turns_per_rollup);
+  if ranges.is_empty() {
+    return Ok(rollups);
+  }
+
+  let model = options.model.or(run_options.model.take());
+  run_options.model = model.clone();
+  make_summary_turn(&mut run_options);
+  run_options.output_schema = Some(thread_rollup_schema());
+  for range in ranges {
+    let group: Vec<ThreadHistoryItem> = items
+      .iter()
+      .filter(|item| (range.0..range.1).contains(&item.turn_index))
+      .cloned()
+      .collect();
+    let transcript = thread_summary_transcript(&group, THREAD_SUMMARY_TRANSCRIPT_CHARS);
+    let previous = rollups.last().map(|rollup| rollup.summary.as_str());
+    let mut turn_options = run_options.clone();
+    turn_options.prompt = thread_rollup_prompt(&transcript, previous, range, max_tokens);
+    let message = tokio::task::spawn_blocking(move || run_summary_turn(turn_options))
+      .await
+      .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))??;
+    let output: Output = serde_json::from_str(message.trim())
+      .map_err(|e| napi::Error::from_reason(format!("Model returned an invalid rollup: {e}")))?;
+    rollups.push(ThreadRollup {
+      start_turn: range.0,
+      end_turn: range.1,
+      end_record: turn_start_record(&items, range.1),
+      summary: truncate_summary_tokens(output.summary.trim(), model.as_deref(), max_tokens)?,
+      model: model.clone(),
+      generated_at: format_rollout_timestamp(SystemTime::now()),
+    });
+    // Store after every group so an interrupted call keeps the work already done.
+    write_thread_rollups(
+      &path,
+      &StoredThreadRollups {
+        thread_id: thread_id.clone(),
+        rollups: rollups.clone(),
+      },
+    )?;
+  }
+  Ok(rollups)
+}
+
+#[cfg(test)]
+mod tests_thread_rollups {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn groups_complete_turns_after_the_stored_rollups() {
+    let mut contents = String::new();
+    for turn in 0..7 {
+      contents.push_str(&format!(
+        r#"{{"type":"event_msg","payload":{{"type":"user_message","message":"step {turn}"}}}}"#
+      ));
+      contents.push('\n');
+      contents.push_str(&format!(
+        r#"{{"type":"event_msg","payload":{{"type":"agent_message","message":"did {turn}"}}}}"#
+      ));
+      contents.push('\n');
+    }
+    let items = collect_thread_history(&parse_rollout_contents(&contents));
+    // Seven turns, the last possibly still running: six complete.
+    assert_eq!(pending_rollup_ranges(&items, 0, 3), vec![(0, 3), (3, 6)]);
+    assert_eq!(pending_rollup_ranges(&items, 3, 3), vec![(3, 6)]);
+    assert_eq!(
+      pending_rollup_ranges(&items, 6, 3),
+      Vec::<(u32, u32)>::new()
+    );
+    assert_eq!(turn_start_record(&items, 3), 6);
+
+    let dir = tempfile::tempdir().unwrap();
+    let rollout = dir.path().join("rollout-2025-01-01T00-00-00-thread.jsonl");
+    assert_eq!(read_thread_rollups(&rollout), Vec::new());
+    let rollup = ThreadRollup {
+      start_turn: 0,
+      end_turn: 3,
+      end_record: 6,
+      summary: "Took steps 0 to 2".to_string(),
+      model: None,
+      generated_at: "2025-01-01T00:00:00.000Z".to_string(),
+    };
+    write_thread_rollups(
+      &rollout,
+      &StoredThreadRollups {
+        thread_id: "thread".to_string(),
+        rollups: vec![rollup.clone()],
+      },
+    )
+    .unwrap();
+    assert!(
+      dir
+        .path()
+        .join("rollout-2025-01-01T00-00-00-thread.rollups.json")
+        .exists()
+    );
+    assert_eq!(read_thread_rollups(&rollout), vec![rollup]);
+  }
+}
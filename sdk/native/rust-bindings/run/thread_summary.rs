@@ -0,0 +1,320 @@
+// ============================================================================
+// Thread titles and abstracts (generateThreadSummary)
+// ============================================================================
+//
+// Session pickers otherwise only have ids and timestamps to show.
+// generateThreadSummary() condenses a thread's history into a transcript,
+// asks the model for a title and abstract in one ephemeral, tool-less turn,
+// and stores the result: the title as the thread's name in the session index
+// (so the TUI resume picker shows it too) and both fields in
+// `$CODEX_HOME/thread-summaries.jsonl`, which listConversations() reads.
+
+const THREAD_SUMMARIES_FILE: &str = "thread-summaries.jsonl";
+const DEFAULT_THREAD_SUMMARY_MAX_TOKENS: u32 = 120;
+const THREAD_SUMMARY_TRANSCRIPT_CHARS: usize = 24_000;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct GenerateThreadSummaryOptions {
+  /// Model for the summary turn. Defaults to the configured model.
+  pub model: Option<String>,
+  /// Upper bound on the abstract's length (default 120).
+  #[napi(js_name = "maxTokens")]
+  pub max_tokens: Option<u32>,
+  pub config: Option<ConversationConfigRequest>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+pub struct ThreadSummary {
+  #[napi(js_name = "threadId")]
+  pub thread_id: String,
+  pub title: String,
+  pub summary: String,
+  pub model: Option<String>,
+  #[napi(js_name = "generatedAt")]
+  pub generated_at: String,
+}
+
+/// The latest stored summary for each thread id.
+fn read_thread_summaries(codex_home: &Path) -> HashMap<String, ThreadSummary> {
+  let Ok(contents) = std::fs::read_to_string(codex_home.join(THREAD_SUMMARIES_FILE)) else {
+    return HashMap::new();
+  };
+  contents
+    .lines()
+    .filter_map(|line| serde_json::from_str::<ThreadSummary>(line).ok())
+    .map(|summary| (summary.thread_id.clone(), summary))
+    .collect()
+}
+
+fn append_thread_summary(codex_home: &Path, summary: &ThreadSummary) -> napi::Result<()> {
+  let line = serde_json::to_string(summary)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize thread summary: {e}")))?;
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(codex_home.join(THREAD_SUMMARIES_FILE))
+    .and_then(|mut file| writeln!(file, "{line}"))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to store thread summary: {e}")))
+}
+
+/// One line per message or tool call, keeping the start and end of long threads.
+fn thread_summary_transcript(items: &[ThreadHistoryItem], max_chars: usize) -> String {
+  let lines: Vec<String> = items
+    .iter()
+    .filter_map(|item| {
+      let field = |name: &str| item.item.get(name).and_then(JsonValue::as_str);
+      let line = match item.item_type.as_str() {
+        "user_message" => format!("User: {}", field("text")?),
+        "agent_message" => format!("Assistant: {}", field("text")?),
+        "command_execution" => format!("Ran: {}", field("command")?),
+        "file_change" => {
+          let paths: Vec<&str> = item.item["changes"]
+            .as_array()?
+            .iter()
+            .filter_map(|change| change.get("path").and_then(JsonValue::as_str))
+            .collect();
+          format!("Edited: {}", paths.join(", "))
+        }
+        "mcp_tool_call" => format!("Tool: {}", field("tool")?),
+        _ => return None,
+      };
+      Some(line)
+    })
+    .collect();
+  let transcript = lines.join("\n");
+  if transcript.len() <= max_chars {
+    return transcript;
+  }
+  let half = max_chars / 2;
+  let head_end = (0..=half)
+    .rev()
+    .find(|index| transcript.is_char_boundary(*index))
+    .unwrap_or(0);
+  let tail_start = (transcript.len() - half..transcript.len())
+    .find(|index| transcript.is_char_boundary(*index))
+    .unwrap_or(transcript.len());
+  format!(
+    "{}\n[...]\n{}",
+    transcript[..head_end].trim_end(),
+    transcript[tail_start..].trim_start()
+  )
+}
+
+fn thread_summary_prompt(transcript: &str, max_tokens: u32) -> String {
+  format!(
+    "Write a title and an abstract for the coding session transcript below.\n\
+     - title: at most 8 words, naming the task, with no trailing punctuation.\n\
+     - summary: 1-3 sentences and at most {max_tokens} tokens, covering what was asked and what \
+     was done.\n\
+     Do not run any tools.\n\n<transcript>\n{transcript}\n</transcript>"
+  )
+}
+
+fn thread_summary_schema() -> JsonValue {
+  json!({
+    "type": "object",
+    "properties": {
+      "title": { "type": "string" },
+      "summary": { "type": "string" }
+    },
+    "required": ["title", "summary"],
+    "additionalProperties": false
+  })
+}
+
+/// The title and abstract from the model's final message, with the abstract cut to `max_tokens`.
+fn parse_thread_summary(
+  message: &str,
+  model: Option<&str>,
+  max_tokens: u32,
+) -> napi::Result<(String, String)> {
+  #[derive(serde::Deserialize)]
+  struct Output {
+    title: String,
+    summary: String,
+  }
+  let output: Output = serde_json::from_str(message.trim())
+    .map_err(|e| napi::Error::from_reason(format!("Model returned an invalid summary: {e}")))?;
+  let summary = truncate_summary_tokens(output.summary.trim(), model, max_tokens)?;
+  Ok((output.title.trim().to_string(), summary))
+}
+
+/// `summary` cut to its first `max_tokens` tokens.
+fn truncate_summary_tokens(
+  summary: &str,
+  model: Option<&str>,
+  max_tokens: u32,
+) -> napi::Result<String> {
+  let tokenizer = build_tokenizer(model, None).or_else(|_| build_tokenizer(None, None))?;
+  let tokens = tokenizer.encode_ordinary(summary);
+  if tokens.len() <= max_tokens as usize {
+    return Ok(summary.to_string());
+  }
+  tokenizer
+    .decode(tokens[..max_tokens as usize].to_vec())
+    .map_err(map_tokenizer_error)
+}
+
+/// Makes `options` an ephemeral, read-only turn with no tools.
+fn make_summary_turn(options: &mut InternalRunRequest) {
+  options.ephemeral = Some(true);
+  options.skip_git_repo_check = true;
+  options.sandbox_mode = Some(SandboxModeCliArg::ReadOnly);
+  options.approval_mode = Some(ApprovalModeCliArg::Never);
+  options.tool_filter = Some(ExternalToolFilter {
+    allowed: Some(Vec::new()),
+    denied: Vec::new(),
+  });
+}
+
+/// Run one ephemeral, tool-less turn and return its final agent message.
+fn run_summary_turn(options: InternalRunRequest) -> napi::Result<String> {
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  let outcome: Arc<Mutex<Result<String, String>>> = Arc::new(Mutex::new(Err(
+    "Summary turn produced no message".to_string(),
+  )));
+  let outcome_slot = Arc::clone(&outcome);
+  run_internal_sync(options, move |event| {
+    let update = match event {
+      ExecThreadEvent::ItemCompleted(completed) => match completed.item.details {
+        ThreadItemDetails::AgentMessage(message) => Some(Ok(message.text)),
+        ThreadItemDetails::Reasoning(_)
+        | ThreadItemDetails::CommandExecution(_)
+        | ThreadItemDetails::FileChange(_)
+        | ThreadItemDetails::McpToolCall(_)
+        | ThreadItemDetails::CollabToolCall(_)
+        | ThreadItemDetails::WebSearch(_)
+        | ThreadItemDetails::TodoList(_)
+        | ThreadItemDetails::Error(_) => None,
+      },
+      ExecThreadEvent::TurnFailed(failed) => Some(Err(failed.error.message)),
+      ExecThreadEvent::Error(error) => Some(Err(error.message)),
+      ExecThreadEvent::ThreadStarted(_)
+      | ExecThreadEvent::TurnStarted(_)
+      | ExecThreadEvent::TurnCompleted(_)
+      | ExecThreadEvent::ItemStarted(_)
+      | ExecThreadEvent::ItemUpdated(_)
+      | ExecThreadEvent::ExitedReviewMode(_)
+      | ExecThreadEvent::BackgroundEvent(_)
+      | ExecThreadEvent::ToolOutputChunk(_)
+      | ExecThreadEvent::BackgroundTaskStarted(_)
+      | ExecThreadEvent::BackgroundTaskCompleted(_)
+      | ExecThreadEvent::PlanUpdated(_)
+      | ExecThreadEvent::ThreadStatus(_)
+      | ExecThreadEvent::TestResults(_)
+      | ExecThreadEvent::VerificationFailed(_)
+      | ExecThreadEvent::FilesTouched(_)
+      | ExecThreadEvent::CommandStarted(_)
+      | ExecThreadEvent::CommandCompleted(_)
+      | ExecThreadEvent::Raw(_) => None,
+    };
+    if let Some(update) = update
+      && let Ok(mut slot) = outcome_slot.lock()
+    {
+      *slot = update;
+    }
+  })?;
+  let outcome = outcome
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("summary mutex poisoned: {e}")))?
+    .clone();
+  outcome.map_err(|message| napi::Error::from_reason(format!("Summary turn failed: {message}")))
+}
+
+/// Generate and store a title and abstract for a recorded thread.
+#[napi]
+pub async fn generate_thread_summary(
+  thread_id: String,
+  options: Option<GenerateThreadSummaryOptions>,
+) -> napi::Result<ThreadSummary> {
+  let options = options.unwrap_or_default();
+  let max_tokens = options
+    .max_tokens
+    .unwrap_or(DEFAULT_THREAD_SUMMARY_MAX_TOKENS)
+    .max(1);
+  let mut run_options = options.config.unwrap_or_default().into_internal_request()?;
+  let config = load_config_from_internal(&run_options).await?;
+  let codex_home = config.codex_home.clone();
+  let path = find_thread_path_by_id_str(&codex_home, &thread_id)
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread {thread_id}: {e}")))?
+    .ok_or_else(|| napi::Error::from_reason(format!("No rollout found for thread {thread_id}")))?;
+  let rollout = load_cached_rollout(&path).map_err(|e| {
+    napi::Error::from_reason(format!("Failed to read rollout {}: {e}", path.display()))
+  })?;
+  let transcript = thread_summary_transcript(
+    &collect_thread_history(&rollout),
+    THREAD_SUMMARY_TRANSCRIPT_CHARS,
+  );
+  if transcript.is_empty() {
+    return Err(napi::Error::from_reason(format!(
+      "Thread {thread_id} has no messages to summarize"
+    )));
+  }
+
+  let model = options.model.or(run_options.model.take());
+  run_options.prompt = thread_summary_prompt(&transcript, max_tokens);
+  run_options.model = model.clone();
+  make_summary_turn(&mut run_options);
+  run_options.output_schema = Some(thread_summary_schema());
+  let message = tokio::task::spawn_blocking(move || run_summary_turn(run_options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))??;
+  let (title, summary) = parse_thread_summary(&message, model.as_deref(), max_tokens)?;
+
+  let entry = ThreadSummary {
+    thread_id: thread_id.clone(),
+    title,
+    summary,
+    model,
+    generated_at: format_rollout_timestamp(SystemTime::now()),
+  };
+  append_thread_summary(&codex_home, &entry)?;
+  if let Ok(id) = codex_protocol::ThreadId::from_string(&thread_id) {
+    codex_core::append_thread_name(&codex_home, id, &entry.title)
+      .await
+      .map_err(|e| napi::Error::from_reason(format!("Failed to store thread title: {e}")))?;
+  }
+  Ok(entry)
+}
+
+#[cfg(test)]
+mod tests_thread_summary {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn transcript_and_parsed_summary_fit_their_budgets() {
+    let rollout = parse_rollout_contents(concat!(
+      r#"{"type":"event_msg","payload":{"type":"user_message","message":"fix the build"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"cargo\",\"build\"]}","call_id":"c1"}}"#,
+      "\n",
+      r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"ok"}}"#,
+      "\n",
+      r#"{"type":"event_msg","payload":{"type":"agent_message","message":"Build fixed"}}"#,
+      "\n",
+    ));
+    let items = collect_thread_history(&rollout);
+    assert_eq!(
+      thread_summary_transcript(&items, 1000),
+      "User: fix the build\nRan: cargo build\nAssistant: Build fixed"
+    );
+    assert_eq!(
+      thread_summary_transcript(&items, 20),
+      "User: fix\n[...]\nuild fixed"
+    );
+
+    let (title, summary) = parse_thread_summary(
+      r#"{"title":" Fix the build ","summary":"one two three four five six"}"#,
+      None,
+      3,
+    )
+    .unwrap();
+    assert_eq!(title, "Fix the build");
+    assert_eq!(summary, "one two three");
+  }
+}
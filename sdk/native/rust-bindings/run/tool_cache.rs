@@ -0,0 +1,415 @@
+// ============================================================================
+// Tool result cache (cacheTtlSeconds)
+// ============================================================================
+//
+// Serves repeated idempotent tool calls within a run from memory instead of
+// the sandbox. Caching is opt-in per tool, from registerTool()
+// (`cacheTtlSeconds`) or the run's `cacheTtlSeconds` map; a run entry replaces
+// the tool's registered TTL and 0 disables caching. The key is the tool name,
+// the arguments with object keys sorted, and the turn's working directory.
+// Only successful results are stored. Shell calls are cached only when the
+// command is known to be read-only, and apply_patch/write_stdin never are.
+// A hit skips approvals, JS interceptors and the tool itself, and is reported
+// as a completed item with `cache_hit: true` for tools that normally produce
+// one (shell commands and MCP calls).
+
+/// Tools whose calls always change state, whatever their arguments.
+const UNCACHEABLE_TOOLS: &[&str] = &["apply_patch", "write_stdin"];
+
+/// Cache TTLs keyed by registry tool name; shell tool names stand for every shell tool.
+fn tool_cache_ttls<'a>(
+  entries: impl IntoIterator<Item = (&'a str, Option<u32>)>,
+) -> HashMap<String, std::time::Duration> {
+  let mut ttls = HashMap::new();
+  for (name, seconds) in entries {
+    let Some(seconds) = seconds else {
+      continue;
+    };
+    for tool_name in expand_tool_names(&[name.to_string()]) {
+      ttls.insert(
+        tool_name,
+        std::time::Duration::from_secs(u64::from(seconds)),
+      );
+    }
+  }
+  ttls
+}
+
+impl RunRequest {
+  fn resolve_tool_cache_ttls(&self) -> HashMap<String, std::time::Duration> {
+    let Some(entries) = self.cache_ttl_seconds.as_ref() else {
+      return HashMap::new();
+    };
+    tool_cache_ttls(
+      entries
+        .iter()
+        .map(|(name, seconds)| (name.as_str(), Some(*seconds))),
+    )
+  }
+}
+
+/// Recursively sorts object keys, so argument order doesn't change the cache key.
+fn canonical_json(value: JsonValue) -> JsonValue {
+  match value {
+    JsonValue::Object(map) => {
+      let mut entries: Vec<(String, JsonValue)> = map.into_iter().collect();
+      entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+      JsonValue::Object(
+        entries
+          .into_iter()
+          .map(|(key, value)| (key, canonical_json(value)))
+          .collect(),
+      )
+    }
+    JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(canonical_json).collect()),
+    other => other,
+  }
+}
+
+fn normalized_arguments(raw: &str) -> String {
+  match serde_json::from_str::<JsonValue>(raw) {
+    Ok(value) => canonical_json(value).to_string(),
+    Err(_) => raw.trim().to_string(),
+  }
+}
+
+/// The argv a shell tool call would run, as `bash -lc <script>` for script-style tools.
+fn shell_command_argv(payload: &ToolPayload) -> Option<Vec<String>> {
+  match payload {
+    ToolPayload::LocalShell { params } => Some(params.command.clone()),
+    ToolPayload::Function { arguments } => {
+      let arguments: JsonValue = serde_json::from_str(arguments).ok()?;
+      match arguments.get("command").or_else(|| arguments.get("cmd"))? {
+        JsonValue::Array(parts) => parts
+          .iter()
+          .map(|part| part.as_str().map(ToString::to_string))
+          .collect(),
+        JsonValue::String(script) => {
+          Some(vec!["bash".to_string(), "-lc".to_string(), script.clone()])
+        }
+        _ => None,
+      }
+    }
+    ToolPayload::Custom { .. } | ToolPayload::Mcp { .. } => None,
+  }
+}
+
+/// The cache key for a call of `tool_name`, or `None` when the call may change state.
+fn tool_cache_key(tool_name: &str, payload: &ToolPayload, cwd: &Path) -> Option<String> {
+  if UNCACHEABLE_TOOLS.contains(&tool_name) {
+    return None;
+  }
+  if SHELL_TOOL_NAMES.contains(&tool_name) {
+    let argv = shell_command_argv(payload)?;
+    if !codex_core::is_safe_command::is_known_safe_command(&argv) {
+      return None;
+    }
+  }
+  let arguments = match payload {
+    ToolPayload::Function { arguments } => normalized_arguments(arguments),
+    ToolPayload::Mcp { raw_arguments, .. } => normalized_arguments(raw_arguments),
+    ToolPayload::Custom { input } => input.clone(),
+    ToolPayload::LocalShell { params } => json!({
+      "command": params.command,
+      "workdir": params.workdir,
+    })
+    .to_string(),
+  };
+  Some(json!([tool_name, cwd, arguments]).to_string())
+}
+
+fn tool_output_text(output: &ToolOutput) -> String {
+  use codex_protocol::models::FunctionCallOutputBody;
+
+  match output {
+    ToolOutput::Function {
+      body: FunctionCallOutputBody::Text(text),
+      ..
+    } => text.clone(),
+    ToolOutput::Function {
+      body: FunctionCallOutputBody::ContentItems(items),
+      ..
+    } => serde_json::to_value(items)
+      .ok()
+      .and_then(|value| {
+        value.as_array().map(|items| {
+          items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(JsonValue::as_str))
+            .collect::<Vec<_>>()
+            .join("\n")
+        })
+      })
+      .unwrap_or_default(),
+    ToolOutput::Mcp { result } => match result {
+      Ok(result) => serde_json::to_string(&result.content).unwrap_or_default(),
+      Err(message) => message.clone(),
+    },
+    ToolOutput::Stream { .. } => String::new(),
+  }
+}
+
+/// The completed item reporting a cache hit, for tools that normally produce one.
+fn cache_hit_item(
+  call_id: &str,
+  tool_name: &str,
+  payload: &ToolPayload,
+  output: &ToolOutput,
+) -> Option<codex_exec::exec_events::ThreadItem> {
+  use codex_exec::exec_events::CommandExecutionItem;
+  use codex_exec::exec_events::CommandExecutionStatus;
+  use codex_exec::exec_events::McpToolCallItem;
+  use codex_exec::exec_events::McpToolCallItemError;
+  use codex_exec::exec_events::McpToolCallItemResult;
+  use codex_exec::exec_events::McpToolCallStatus;
+  use codex_exec::exec_events::ThreadItemDetails;
+
+  let details = if let ToolPayload::Mcp {
+    server,
+    tool,
+    raw_arguments,
+  } = payload
+  {
+    let (result, error, status) = match output {
+      ToolOutput::Mcp { result: Ok(result) } => (
+        Some(McpToolCallItemResult {
+          content: result.content.clone(),
+          structured_content: result.structured_content.clone(),
+        }),
+        None,
+        McpToolCallStatus::Completed,
+      ),
+      ToolOutput::Mcp {
+        result: Err(message),
+      } => (
+        None,
+        Some(McpToolCallItemError {
+          message: message.clone(),
+        }),
+        McpToolCallStatus::Failed,
+      ),
+      ToolOutput::Function { .. } | ToolOutput::Stream { .. } => {
+        (None, None, McpToolCallStatus::Completed)
+      }
+    };
+    ThreadItemDetails::McpToolCall(McpToolCallItem {
+      server: server.clone(),
+      tool: tool.clone(),
+      arguments: serde_json::from_str(raw_arguments).unwrap_or(JsonValue::Null),
+      result,
+      error,
+      status,
+      cache_hit: Some(true),
+    })
+  } else if SHELL_TOOL_NAMES.contains(&tool_name) {
+    let argv = shell_command_argv(payload)?;
+    let command = match argv.as_slice() {
+      [shell, flag, script] if shell == "bash" && flag == "-lc" => script.clone(),
+      _ => argv.join(" "),
+    };
+    ThreadItemDetails::CommandExecution(CommandExecutionItem {
+      command,
+      aggregated_output: tool_output_text(output),
+      exit_code: None,
+      status: CommandExecutionStatus::Completed,
+      cache_hit: Some(true),
+      is_tty: None,
+    })
+  } else {
+    return None;
+  };
+  Some(codex_exec::exec_events::ThreadItem {
+    id: format!("cache_hit_{call_id}"),
+    details,
+  })
+}
+
+struct CachedToolResult {
+  stored_at: std::time::Instant,
+  output: ToolOutput,
+}
+
+struct ToolResultCacheInterceptor {
+  ttls: HashMap<String, std::time::Duration>,
+  entries: Mutex<HashMap<String, CachedToolResult>>,
+  redactor: Option<Arc<SecretRedactor>>,
+}
+
+impl ToolResultCacheInterceptor {
+  fn lookup(&self, key: &str, ttl: std::time::Duration) -> Option<ToolOutput> {
+    let mut entries = self.entries.lock().ok()?;
+    match entries.get(key) {
+      Some(entry) if entry.stored_at.elapsed() < ttl => Some(entry.output.clone()),
+      Some(_) => {
+        entries.remove(key);
+        None
+      }
+      None => None,
+    }
+  }
+
+  fn report_hit(&self, thread_id: &str, item: codex_exec::exec_events::ThreadItem) {
+    let item = match self.redactor.as_deref() {
+      Some(redactor) => redact_thread_item(redactor, item),
+      None => item,
+    };
+    let handler = active_thread_handlers()
+      .lock()
+      .ok()
+      .and_then(|handlers| handlers.get(thread_id).cloned());
+    if let Some(handler) = handler {
+      let event =
+        ExecThreadEvent::ItemCompleted(codex_exec::exec_events::ItemCompletedEvent { item });
+      if let Err(err) = dispatch_thread_event(&handler, event) {
+        eprintln!("Failed to report tool cache hit: {err}");
+      }
+    }
+  }
+}
+
+#[async_trait]
+impl ToolInterceptor for ToolResultCacheInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    let Some(ttl) = self
+      .ttls
+      .get(&invocation.tool_name)
+      .copied()
+      .filter(|ttl| !ttl.is_zero())
+    else {
+      return next(invocation).await;
+    };
+    let Some(key) = tool_cache_key(&invocation.tool_name, &invocation.payload, invocation.cwd())
+    else {
+      return next(invocation).await;
+    };
+    if let Some(output) = self.lookup(&key, ttl) {
+      if let Some(item) = cache_hit_item(
+        &invocation.call_id,
+        &invocation.tool_name,
+        &invocation.payload,
+        &output,
+      ) {
+        self.report_hit(&invocation.thread_id(), item);
+      }
+      return Ok(output);
+    }
+    let output = next(invocation).await?;
+    if output.success_for_logging()
+      && let Ok(mut entries) = self.entries.lock()
+    {
+      entries.insert(
+        key,
+        CachedToolResult {
+          stored_at: std::time::Instant::now(),
+          output: output.clone(),
+        },
+      );
+    }
+    Ok(output)
+  }
+}
+
+/// TTLs declared at registerTool(), overridden per tool by the run's own.
+fn run_tool_cache_ttls(
+  options: &InternalRunRequest,
+) -> napi::Result<HashMap<String, std::time::Duration>> {
+  let infos = registered_tool_infos()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("tool infos mutex poisoned: {e}")))?;
+  let mut ttls = tool_cache_ttls(
+    infos
+      .iter()
+      .map(|info| (info.name.as_str(), info.cache_ttl_seconds)),
+  );
+  ttls.extend(options.tool_cache_ttls.clone());
+  Ok(ttls)
+}
+
+/// The interceptor caching results of this run's cacheable tools, if any tool has a TTL.
+fn tool_result_cache_interceptors(
+  options: &InternalRunRequest,
+  redactor: Option<&Arc<SecretRedactor>>,
+) -> napi::Result<Vec<ExternalInterceptorRegistration>> {
+  let ttls: HashMap<String, std::time::Duration> = run_tool_cache_ttls(options)?
+    .into_iter()
+    .filter(|(_, ttl)| !ttl.is_zero())
+    .collect();
+  if ttls.is_empty() {
+    return Ok(Vec::new());
+  }
+  Ok(vec![ExternalInterceptorRegistration {
+    name: codex_core::ALL_TOOLS_INTERCEPTOR.to_string(),
+    handler: Arc::new(ToolResultCacheInterceptor {
+      ttls,
+      entries: Mutex::new(HashMap::new()),
+      redactor: redactor.cloned(),
+    }),
+  }])
+}
+
+#[cfg(test)]
+mod tests_tool_cache {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use pretty_assertions::assert_ne;
+
+  #[test]
+  fn keys_ignore_argument_order_and_skip_mutating_calls() {
+    let cwd = Path::new("/repo");
+    let grep = |arguments: &str| {
+      tool_cache_key(
+        "grep_files",
+        &ToolPayload::Function {
+          arguments: arguments.to_string(),
+        },
+        cwd,
+      )
+    };
+    assert_eq!(
+      grep(r#"{"pattern":"todo","path":"src"}"#),
+      grep(r#"{ "path": "src", "pattern": "todo" }"#)
+    );
+    assert_ne!(
+      grep(r#"{"pattern":"todo"}"#),
+      tool_cache_key(
+        "grep_files",
+        &ToolPayload::Function {
+          arguments: r#"{"pattern":"todo"}"#.to_string(),
+        },
+        Path::new("/other"),
+      )
+    );
+
+    let shell = |script: &str| {
+      tool_cache_key(
+        "shell_command",
+        &ToolPayload::Function {
+          arguments: json!({ "command": script }).to_string(),
+        },
+        cwd,
+      )
+    };
+    assert!(shell("ls -la").is_some());
+    assert_eq!(shell("rm -rf build"), None);
+    assert_eq!(
+      tool_cache_key(
+        "apply_patch",
+        &ToolPayload::Custom {
+          input: "*** Begin Patch".to_string(),
+        },
+        cwd,
+      ),
+      None
+    );
+  }
+}
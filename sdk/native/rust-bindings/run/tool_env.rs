@@ -0,0 +1,139 @@
+// ============================================================================
+// Tool environment policy (RunRequest.toolEnv)
+// ============================================================================
+//
+// Shell commands inherit the whole host environment by default, which puts CI
+// credentials within reach of every command the model runs and of the output
+// it reads back. `toolEnv` replaces config.toml's `shell_environment_policy`
+// for the run: `none` starts from an empty environment, `allowlist` keeps only
+// the `allow` patterns, and `set` entries are added on top either way.
+
+#[napi(object)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolEnvOptions {
+  /// `all` (default) inherits the host environment, `none` starts empty, `allowlist` keeps only
+  /// the variables matching `allow`.
+  pub inherit: Option<String>,
+  /// Variable names kept with `inherit: "allowlist"`. `*` and `?` are wildcards; matching
+  /// ignores case.
+  pub allow: Option<Vec<String>>,
+  /// Variables set for every command, whatever `inherit` is.
+  pub set: Option<HashMap<String, String>>,
+}
+
+/// `toolEnv` resolved to the `shell_environment_policy` it stands for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolEnvPolicy {
+  inherit_all: bool,
+  include_only: Vec<String>,
+  set: Vec<(String, String)>,
+}
+
+impl ToolEnvPolicy {
+  fn from_options(options: ToolEnvOptions) -> napi::Result<Self> {
+    let allow = options.allow.unwrap_or_default();
+    let mut set: Vec<(String, String)> = options.set.unwrap_or_default().into_iter().collect();
+    set.sort();
+    match options.inherit.as_deref() {
+      None | Some("all") | Some("none") if !allow.is_empty() => Err(napi::Error::from_reason(
+        "toolEnv.allow only applies with inherit: \"allowlist\"",
+      )),
+      None | Some("all") => Ok(Self {
+        inherit_all: true,
+        include_only: Vec::new(),
+        set,
+      }),
+      Some("none") => Ok(Self {
+        inherit_all: false,
+        include_only: Vec::new(),
+        set,
+      }),
+      Some("allowlist") => {
+        // `include_only` also filters `set` entries, so they are allowed explicitly. An empty
+        // `include_only` would keep everything, so an empty allowlist inherits nothing.
+        let mut include_only = allow;
+        include_only.extend(set.iter().map(|(name, _)| name.clone()));
+        Ok(Self {
+          inherit_all: !include_only.is_empty(),
+          include_only,
+          set,
+        })
+      }
+      Some(other) => Err(napi::Error::from_reason(format!(
+        "Invalid toolEnv.inherit {other:?}; expected \"all\", \"none\" or \"allowlist\""
+      ))),
+    }
+  }
+
+  /// The `shell_environment_policy` config override, replacing any table from config.toml.
+  fn config_override(&self) -> String {
+    let inherit = if self.inherit_all { "all" } else { "none" };
+    let mut fields = vec![format!("inherit = \"{inherit}\"")];
+    if !self.include_only.is_empty() {
+      let patterns: Vec<String> = self
+        .include_only
+        .iter()
+        .map(|pattern| json_to_toml_inline(&json!(pattern)))
+        .collect();
+      fields.push(format!("include_only = [{}]", patterns.join(", ")));
+    }
+    if !self.set.is_empty() {
+      let entries: Vec<String> = self
+        .set
+        .iter()
+        .map(|(name, value)| {
+          format!(
+            "{} = {}",
+            json_to_toml_inline(&json!(name)),
+            json_to_toml_inline(&json!(value))
+          )
+        })
+        .collect();
+      fields.push(format!("set = {{ {} }}", entries.join(", ")));
+    }
+    format!("shell_environment_policy={{ {} }}", fields.join(", "))
+  }
+}
+
+#[cfg(test)]
+mod tests_tool_env {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn allowlist_keeps_set_variables_and_renders_policy_override() {
+    let policy = ToolEnvPolicy::from_options(ToolEnvOptions {
+      inherit: Some("allowlist".to_string()),
+      allow: Some(vec!["PATH".to_string(), "LANG*".to_string()]),
+      set: Some(HashMap::from([("CI".to_string(), "1".to_string())])),
+    })
+    .unwrap();
+    assert_eq!(
+      policy.config_override(),
+      "shell_environment_policy={ inherit = \"all\", include_only = [\"PATH\", \"LANG*\", \"CI\"], set = { \"CI\" = \"1\" } }"
+    );
+
+    let empty = ToolEnvPolicy::from_options(ToolEnvOptions {
+      inherit: Some("allowlist".to_string()),
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(
+      empty.config_override(),
+      "shell_environment_policy={ inherit = \"none\" }"
+    );
+
+    let err = ToolEnvPolicy::from_options(ToolEnvOptions {
+      inherit: Some("none".to_string()),
+      allow: Some(vec!["PATH".to_string()]),
+      set: None,
+    })
+    .err()
+    .unwrap();
+    assert_eq!(
+      err.reason,
+      "toolEnv.allow only applies with inherit: \"allowlist\""
+    );
+  }
+}
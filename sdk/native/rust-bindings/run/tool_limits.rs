@@ -0,0 +1,101 @@
+// ============================================================================
+// Per-tool scheduling limits (toolLimits)
+// ============================================================================
+//
+// Caps how many calls of one tool run at once and how soon one call may start
+// after the previous one, so expensive tools (web search, heavyweight JS tools)
+// don't stampede when the model issues parallel tool calls. Limits come from
+// registerTool() (`maxConcurrent`, `minIntervalMs`) and from the run's
+// `toolLimits` map; a run entry replaces the tool's registered limits. The
+// tool registry enforces them in its dispatch path, before interceptors run.
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolLimitOptions {
+  /// Calls of the tool allowed to run at once. Must be at least 1.
+  #[napi(js_name = "maxConcurrent")]
+  pub max_concurrent: Option<u32>,
+  /// Minimum milliseconds between the starts of two calls of the tool.
+  #[napi(js_name = "minIntervalMs")]
+  pub min_interval_ms: Option<u32>,
+}
+
+/// Limits keyed by registry tool name. Shell tool names stand for every shell tool, as in
+/// tool allow/deny lists.
+fn tool_limits<'a>(
+  entries: impl IntoIterator<Item = (&'a str, Option<u32>, Option<u32>)>,
+) -> napi::Result<HashMap<String, ExternalToolLimit>> {
+  codex_native_core::tools::tool_limits(entries)
+    .map_err(|err| napi::Error::from_reason(err.to_string()))
+}
+
+impl RunRequest {
+  fn resolve_tool_limits(&self) -> napi::Result<HashMap<String, ExternalToolLimit>> {
+    let Some(entries) = self.tool_limits.as_ref() else {
+      return Ok(HashMap::new());
+    };
+    tool_limits(entries.iter().map(|(name, options)| {
+      (
+        name.as_str(),
+        options.max_concurrent,
+        options.min_interval_ms,
+      )
+    }))
+  }
+}
+
+/// Limits declared at registerTool(), overridden per tool by the run's own.
+fn run_tool_limits(
+  options: &InternalRunRequest,
+) -> napi::Result<HashMap<String, ExternalToolLimit>> {
+  let infos = registered_tool_infos()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("tool infos mutex poisoned: {e}")))?;
+  let mut limits = tool_limits(infos.iter().map(|info| {
+    (
+      info.name.as_str(),
+      info.max_concurrent,
+      info.min_interval_ms,
+    )
+  }))?;
+  limits.extend(options.tool_limits.clone());
+  Ok(limits)
+}
+
+#[cfg(test)]
+mod tests_tool_limits {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn limits_cover_shell_aliases_and_reject_zero_concurrency() {
+    let limits = tool_limits([
+      ("shell", Some(1), None),
+      ("web_search", None, Some(250)),
+      ("read_file", None, None),
+    ])
+    .unwrap();
+    assert_eq!(
+      limits.get("exec_command"),
+      Some(&ExternalToolLimit {
+        max_concurrent: Some(1),
+        min_interval: None,
+      })
+    );
+    assert_eq!(
+      limits.get("web_search"),
+      Some(&ExternalToolLimit {
+        max_concurrent: None,
+        min_interval: Some(std::time::Duration::from_millis(250)),
+      })
+    );
+    assert_eq!(limits.get("read_file"), None);
+
+    let err = tool_limits([("web_search", Some(0), None)]).unwrap_err();
+    assert_eq!(
+      err.reason,
+      "maxConcurrent for tool web_search must be at least 1"
+    );
+  }
+}
@@ -1,6 +1,11 @@
 #[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RunRequest {
   pub prompt: String,
+  /// Template registered with `registerPromptTemplate`, rendered natively in place of `prompt`.
+  #[napi(js_name = "promptTemplate")]
+  pub prompt_template: Option<PromptTemplateRequest>,
   #[napi(js_name = "inputItems")]
   pub input_items: Option<JsonValue>,
   #[napi(js_name = "threadId")]
@@ -9,6 +14,8 @@ pub struct RunRequest {
   pub model: Option<String>,
   #[napi(js_name = "modelProvider")]
   pub model_provider: Option<String>,
+  /// Config profile from config.toml's `[profiles]` table, as selected by `codex --profile`.
+  pub profile: Option<String>,
   #[napi(js_name = "oss")]
   pub oss: Option<bool>,
   #[napi(js_name = "sandboxMode")]
@@ -17,6 +24,24 @@ pub struct RunRequest {
   pub approval_mode: Option<String>,
   #[napi(js_name = "workspaceWriteOptions")]
   pub workspace_write_options: Option<WorkspaceWriteOptions>,
+  /// Permission profile defined with `definePermissionProfile`. Sandbox mode, approval mode and
+  /// writable roots set on this request win over the profile's.
+  #[napi(js_name = "permissionProfile")]
+  pub permission_profile: Option<String>,
+  /// When set, only these builtin and registered tools are exposed to the model for this run.
+  #[napi(js_name = "allowedTools")]
+  pub allowed_tools: Option<Vec<String>>,
+  /// Tools never exposed to the model for this run. Wins over `allowedTools`.
+  #[napi(js_name = "deniedTools")]
+  pub denied_tools: Option<Vec<String>>,
+  /// Concurrency and start-interval limits per tool name for this run. An entry replaces the
+  /// limits the tool was registered with.
+  #[napi(js_name = "toolLimits")]
+  pub tool_limits: Option<HashMap<String, ToolLimitOptions>>,
+  /// Seconds to reuse successful results of idempotent calls, per tool name. An entry replaces
+  /// the TTL the tool was registered with; 0 disables caching.
+  #[napi(js_name = "cacheTtlSeconds")]
+  pub cache_ttl_seconds: Option<HashMap<String, u32>>,
   #[napi(js_name = "reviewMode")]
   pub review_mode: Option<bool>,
   #[napi(js_name = "reviewHint")]
@@ -33,6 +58,9 @@ pub struct RunRequest {
   pub base_url: Option<String>,
   #[napi(js_name = "apiKey")]
   pub api_key: Option<String>,
+  /// Credential profile saved with `authLoginApiKey`/`authLoginChatGpt` to run this request as.
+  #[napi(js_name = "authProfile")]
+  pub auth_profile: Option<String>,
   #[napi(js_name = "linuxSandboxPath")]
   pub linux_sandbox_path: Option<String>,
   #[napi(js_name = "reasoningEffort")]
@@ -47,6 +75,10 @@ pub struct RunRequest {
   pub ephemeral: Option<bool>,
   #[napi(js_name = "webSearchMode")]
   pub web_search_mode: Option<String>,
+  /// Shorthand for `webSearchMode`, like the TUI's `--search`: `true` enables live search and
+  /// `false` disables the tool. Ignored when `webSearchMode` is set.
+  #[napi(js_name = "webSearch")]
+  pub web_search: Option<bool>,
   #[napi(js_name = "dynamicTools")]
   pub dynamic_tools: Option<JsonValue>,
   /// MCP servers to register, keyed by server name. Passed as JSON value.
@@ -54,9 +86,91 @@ pub struct RunRequest {
   /// When false, ignores globally registered MCP servers from config.toml.
   #[napi(js_name = "inheritMcp")]
   pub inherit_mcp: Option<bool>,
+  /// Capture model responses, tool outputs and timing into this cassette file.
+  pub record: Option<String>,
+  /// Serve model responses and tool outputs from this cassette instead of the network.
+  pub replay: Option<String>,
+  /// Allow/deny globs restricting which files apply_patch may touch.
+  #[napi(js_name = "patchPathPolicy")]
+  pub patch_path_policy: Option<PatchPathPolicy>,
+  /// Replace whole-file reads of oversized files with a skeleton of the file.
+  #[napi(js_name = "fileSkeleton")]
+  pub file_skeleton: Option<FileSkeletonOptions>,
+  /// Mask API keys and other secrets in tool output, thread events and the persisted rollout.
+  pub redaction: Option<RedactionOptions>,
+  /// Commands run after each turn that changed files, with the action to take on failure.
+  #[napi(js_name = "postTurnChecks")]
+  pub post_turn_checks: Option<Vec<PostTurnCheck>>,
+  /// Upper bound on follow-up turns started by failing `feedback` checks (default 3).
+  #[napi(js_name = "maxCheckFeedbackTurns")]
+  pub max_check_feedback_turns: Option<u32>,
+  /// Replaces the model's built-in base instructions for this run.
+  #[napi(js_name = "baseInstructions")]
+  pub base_instructions: Option<String>,
+  /// Developer message for this run, in place of config.toml's `developer_instructions`.
+  #[napi(js_name = "developerInstructions")]
+  pub developer_instructions: Option<String>,
+  /// Prompt used when this run compacts history, in place of config.toml's `compact_prompt`.
+  #[napi(js_name = "compactPrompt")]
+  pub compact_prompt: Option<String>,
+  /// Bounds events waiting for the runThreadStream() callback. Unbounded when unset.
+  #[napi(js_name = "eventDelivery")]
+  pub event_delivery: Option<EventDeliveryOptions>,
+  /// Forward every protocol event as a `raw_event`, next to the thread events mapped from it.
+  #[napi(js_name = "includeRawEvents")]
+  pub include_raw_events: Option<bool>,
+  /// `persistent` runs shell commands in one long-lived shell per thread; `per-call` (default)
+  /// starts a fresh process for each.
+  #[napi(js_name = "execSession")]
+  pub exec_session: Option<String>,
+  /// Run shell commands attached to a PTY of this size, so they see a terminal.
+  #[napi(js_name = "shellPty")]
+  pub shell_pty: Option<ShellPtyOptions>,
+  /// Environment shell commands see, in place of config.toml's `shell_environment_policy`.
+  #[napi(js_name = "toolEnv")]
+  pub tool_env: Option<ToolEnvOptions>,
+  /// Commands run in the sandbox after each applied patch, with failures fed back to the model.
+  pub verify: Option<VerifyOptions>,
+  /// Add the project's top memories for the prompt to a new thread's developer instructions.
+  pub memory: Option<RunMemoryOptions>,
+  /// Id from `registerEscalationHandler` of the handler that decides this run's approval
+  /// requests, such as retrying a sandbox-denied command under `approvalMode: "on-failure"`.
+  /// Without one they are denied.
+  #[napi(js_name = "escalationHandler")]
+  pub escalation_handler: Option<String>,
+  /// How much tool output the model sees before it is truncated.
+  #[napi(js_name = "outputLimits")]
+  pub output_limits: Option<OutputLimits>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputLimits {
+  /// Token budget for each tool output, in place of the model's default (usually 10000).
+  #[napi(js_name = "maxToolOutputTokens")]
+  pub max_tool_output_tokens: Option<u32>,
+  /// Share of the budget kept from the start of truncated command output, 0 to 1 (default 0.5).
+  /// The rest keeps its end, where build errors and test summaries usually are.
+  #[napi(js_name = "headTailSplit")]
+  pub head_tail_split: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[napi(object)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellPtyOptions {
+  /// Terminal width (default 80).
+  pub cols: Option<u32>,
+  /// Terminal height (default 24).
+  pub rows: Option<u32>,
+  /// `TERM` for the command (default `xterm-256color`).
+  pub term: Option<String>,
+}
+
+#[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ForkRequest {
   #[napi(js_name = "threadId")]
   pub thread_id: String,
@@ -66,6 +180,8 @@ pub struct ForkRequest {
   pub model: Option<String>,
   #[napi(js_name = "modelProvider")]
   pub model_provider: Option<String>,
+  /// Config profile from config.toml's `[profiles]` table, as selected by `codex --profile`.
+  pub profile: Option<String>,
   #[napi(js_name = "oss")]
   pub oss: Option<bool>,
   #[napi(js_name = "sandboxMode")]
@@ -82,6 +198,9 @@ pub struct ForkRequest {
   pub base_url: Option<String>,
   #[napi(js_name = "apiKey")]
   pub api_key: Option<String>,
+  /// Credential profile saved with `authLoginApiKey`/`authLoginChatGpt` to run this request as.
+  #[napi(js_name = "authProfile")]
+  pub auth_profile: Option<String>,
   #[napi(js_name = "linuxSandboxPath")]
   pub linux_sandbox_path: Option<String>,
   #[napi(js_name = "reasoningEffort")]
@@ -107,13 +226,16 @@ pub struct InternalForkRequest {
   pub run_options: InternalRunRequest,
 }
 
-#[derive(Default)]
+#[derive(Default, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
 pub struct ConversationConfigRequest {
   #[napi(js_name = "model")]
   pub model: Option<String>,
   #[napi(js_name = "modelProvider")]
   pub model_provider: Option<String>,
+  /// Config profile from config.toml's `[profiles]` table, as selected by `codex --profile`.
+  pub profile: Option<String>,
   #[napi(js_name = "oss")]
   pub oss: Option<bool>,
   #[napi(js_name = "sandboxMode")]
@@ -130,6 +252,9 @@ pub struct ConversationConfigRequest {
   pub base_url: Option<String>,
   #[napi(js_name = "apiKey")]
   pub api_key: Option<String>,
+  /// Credential profile saved with `authLoginApiKey`/`authLoginChatGpt` to run this request as.
+  #[napi(js_name = "authProfile")]
+  pub auth_profile: Option<String>,
   #[napi(js_name = "linuxSandboxPath")]
   pub linux_sandbox_path: Option<String>,
   #[napi(js_name = "reasoningEffort")]
@@ -145,6 +270,8 @@ pub struct ConversationConfigRequest {
 }
 
 #[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ListConversationsRequest {
   #[napi(js_name = "config")]
   pub config: Option<ConversationConfigRequest>,
@@ -153,9 +280,13 @@ pub struct ListConversationsRequest {
   pub cursor: Option<String>,
   #[napi(js_name = "modelProviders")]
   pub model_providers: Option<Vec<String>>,
+  /// Only conversations recorded in exactly this working directory.
+  pub cwd: Option<String>,
 }
 
 #[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ConversationSummary {
   pub id: String,
   pub path: String,
@@ -163,9 +294,17 @@ pub struct ConversationSummary {
   pub created_at: Option<String>,
   #[napi(js_name = "updatedAt")]
   pub updated_at: Option<String>,
+  /// Thread name, e.g. a title from generateThreadSummary().
+  pub title: Option<String>,
+  /// Abstract from generateThreadSummary().
+  pub summary: Option<String>,
+  /// Task the thread was started for by runFromTask().
+  pub task: Option<ThreadTaskLink>,
 }
 
 #[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ConversationListPage {
   pub conversations: Vec<ConversationSummary>,
   #[napi(js_name = "nextCursor")]
@@ -174,6 +313,9 @@ pub struct ConversationListPage {
   pub num_scanned_files: u32,
   #[napi(js_name = "reachedScanCap")]
   pub reached_scan_cap: bool,
+  /// `"sqlite"` when the listing came from the state database, `"jsonl"` for a file scan.
+  #[napi(js_name = "storeBackend")]
+  pub store_backend: String,
 }
 
 #[napi(object)]
@@ -197,6 +339,8 @@ pub struct ResumeFromRolloutRequest {
 }
 
 #[napi(object)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ForkResult {
   #[napi(js_name = "threadId")]
   pub thread_id: String,
@@ -218,10 +362,18 @@ pub struct InternalRunRequest {
   pub images: Vec<PathBuf>,
   pub model: Option<String>,
   pub model_provider: Option<String>,
+  pub config_profile: Option<String>,
   pub oss: bool,
   pub sandbox_mode: Option<SandboxModeCliArg>,
   pub approval_mode: Option<ApprovalModeCliArg>,
   pub workspace_write_options: Option<WorkspaceWriteOptions>,
+  /// Tools exposed to the model, from `allowedTools`/`deniedTools` and the permission profile.
+  pub tool_filter: Option<ExternalToolFilter>,
+  /// Per-tool limits from `toolLimits`; registered limits are merged in at run start.
+  pub tool_limits: HashMap<String, ExternalToolLimit>,
+  /// Per-tool result cache TTLs from `cacheTtlSeconds`; registered TTLs are merged in at run
+  /// start.
+  pub tool_cache_ttls: HashMap<String, std::time::Duration>,
   pub review_request: Option<ReviewRequest>,
   pub working_directory: Option<PathBuf>,
   pub skip_git_repo_check: bool,
@@ -229,6 +381,8 @@ pub struct InternalRunRequest {
   pub tool_choice: Option<JsonValue>,
   pub base_url: Option<String>,
   pub api_key: Option<String>,
+  /// Directory of the credential profile named by `authProfile`.
+  pub auth_home: Option<PathBuf>,
   pub linux_sandbox_path: Option<PathBuf>,
   pub reasoning_effort: Option<ReasoningEffort>,
   pub reasoning_summary: Option<ReasoningSummary>,
@@ -241,4 +395,22 @@ pub struct InternalRunRequest {
   pub mcp: Option<JsonValue>,
   /// When false, ignores globally registered MCP servers from config.toml.
   pub inherit_mcp: bool,
+  pub record: Option<PathBuf>,
+  pub replay: Option<PathBuf>,
+  pub patch_path_policy: Option<PatchPathPolicy>,
+  pub file_skeleton: Option<FileSkeletonOptions>,
+  pub redaction: Option<RedactionOptions>,
+  pub post_turn_checks: Vec<InternalPostTurnCheck>,
+  pub max_check_feedback_turns: u32,
+  pub base_instructions: Option<String>,
+  pub developer_instructions: Option<String>,
+  pub compact_prompt: Option<String>,
+  pub include_raw_events: bool,
+  pub exec_session: Option<ExecSessionMode>,
+  pub shell_pty: Option<ShellPtyOptions>,
+  pub tool_env: Option<ToolEnvPolicy>,
+  pub verify: Option<PatchVerification>,
+  pub memory: Option<RunMemoryOptions>,
+  pub escalation_handler: Option<String>,
+  pub output_limits: Option<OutputLimits>,
 }
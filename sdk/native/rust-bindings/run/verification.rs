@@ -0,0 +1,262 @@
+// ============================================================================
+// Post-patch verification (RunRequest.verify)
+// ============================================================================
+//
+// Post-turn checks only run once the turn is over, so the model can keep
+// editing on top of a broken build for the rest of it. `verify` runs quick
+// checks such as `cargo check` or `tsc --noEmit` in the turn's sandbox right
+// after every patch that applies, whether it came through apply_patch or as an
+// apply_patch heredoc in a shell tool. Failures are appended to the patch's
+// tool output, so the model sees them in the same step, and are emitted as a
+// `verification_failed` event.
+
+const DEFAULT_VERIFY_TIMEOUT_MS: u32 = 5 * 60 * 1000;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyOptions {
+  /// Shell commands run in order after each applied patch, e.g. `cargo check`.
+  pub commands: Vec<String>,
+  /// Timeout for each command (default 5 minutes).
+  #[napi(js_name = "timeoutMs")]
+  pub timeout_ms: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchVerification {
+  commands: Vec<String>,
+  timeout_ms: u32,
+}
+
+impl PatchVerification {
+  /// `None` when there is nothing to run.
+  fn from_options(options: VerifyOptions) -> napi::Result<Option<Self>> {
+    if options
+      .commands
+      .iter()
+      .any(|command| command.trim().is_empty())
+    {
+      return Err(napi::Error::from_reason(
+        "verify.commands entries must be non-empty",
+      ));
+    }
+    if options.commands.is_empty() {
+      return Ok(None);
+    }
+    Ok(Some(Self {
+      commands: options.commands,
+      timeout_ms: options.timeout_ms.unwrap_or(DEFAULT_VERIFY_TIMEOUT_MS),
+    }))
+  }
+
+  /// Run every command under `call`'s sandbox and return the ones that failed.
+  async fn run(
+    &self,
+    call: &codex_core::DirectToolCall,
+  ) -> Vec<codex_exec::exec_events::VerificationFailure> {
+    let mut failures = Vec::new();
+    for command in &self.commands {
+      let argv = if cfg!(windows) {
+        vec!["cmd".to_string(), "/C".to_string(), command.clone()]
+      } else {
+        vec!["sh".to_string(), "-c".to_string(), command.clone()]
+      };
+      let result = codex_core::run_direct_command(
+        call,
+        argv,
+        call.cwd.clone(),
+        Some(u64::from(self.timeout_ms)),
+      )
+      .await;
+      let failure = match result {
+        Ok(output) if output.exit_code == 0 && !output.timed_out => continue,
+        Ok(output) => codex_exec::exec_events::VerificationFailure {
+          command: command.clone(),
+          exit_code: Some(output.exit_code),
+          timed_out: output.timed_out,
+          output: tail_of_output(&output.aggregated_output.text),
+        },
+        Err(err) => codex_exec::exec_events::VerificationFailure {
+          command: command.clone(),
+          exit_code: None,
+          timed_out: false,
+          output: err.to_string(),
+        },
+      };
+      failures.push(failure);
+    }
+    failures
+  }
+}
+
+/// Appended to the patch's tool output so the model fixes the failures before moving on.
+fn verification_feedback(failures: &[codex_exec::exec_events::VerificationFailure]) -> String {
+  let mut text =
+    String::from("\n\nVerification failed after this patch. Fix these problems before continuing:");
+  for failure in failures {
+    let status = match (failure.timed_out, failure.exit_code) {
+      (true, _) => "timed out".to_string(),
+      (false, Some(code)) => format!("exit code {code}"),
+      (false, None) => "failed to start".to_string(),
+    };
+    text.push_str(&format!(
+      "\n\n$ {} ({status})\n{}",
+      failure.command,
+      failure.output.trim_end()
+    ));
+  }
+  text
+}
+
+struct PatchVerificationInterceptor {
+  verification: Arc<PatchVerification>,
+  redactor: Option<Arc<SecretRedactor>>,
+}
+
+impl PatchVerificationInterceptor {
+  fn report(&self, thread_id: &str, event: codex_exec::exec_events::VerificationFailedEvent) {
+    let event = ExecThreadEvent::VerificationFailed(event);
+    let event = match self.redactor.as_deref() {
+      Some(redactor) => redact_thread_event(redactor, event),
+      None => event,
+    };
+    let handler = active_thread_handlers()
+      .lock()
+      .ok()
+      .and_then(|handlers| handlers.get(thread_id).cloned());
+    if let Some(handler) = handler
+      && let Err(err) = dispatch_thread_event(&handler, event)
+    {
+      eprintln!("Failed to report verification failure: {err}");
+    }
+  }
+}
+
+#[async_trait]
+impl ToolInterceptor for PatchVerificationInterceptor {
+  async fn intercept(
+    &self,
+    invocation: ToolInvocation,
+    next: Box<
+      dyn FnOnce(
+          ToolInvocation,
+        ) -> std::pin::Pin<
+          Box<dyn std::future::Future<Output = Result<ToolOutput, FunctionCallError>> + Send>,
+        > + Send,
+    >,
+  ) -> Result<ToolOutput, FunctionCallError> {
+    if patch_targets(&invocation.tool_name, &invocation.payload, invocation.cwd()).is_empty() {
+      return next(invocation).await;
+    }
+    let call_id = invocation.call_id.clone();
+    let thread_id = invocation.thread_id();
+    let call = codex_core::DirectToolCall {
+      tool_name: invocation.tool_name.clone(),
+      arguments: String::new(),
+      cwd: invocation.cwd().to_path_buf(),
+      sandbox_policy: invocation.sandbox_policy().clone(),
+      codex_linux_sandbox_exe: invocation.codex_linux_sandbox_exe().map(Path::to_path_buf),
+    };
+    let (mut body, success) = match next(invocation).await? {
+      // A patch that failed to apply changed nothing to verify.
+      ToolOutput::Function { body, success } if success != Some(false) => (body, success),
+      other => return Ok(other),
+    };
+
+    let failures = self.verification.run(&call).await;
+    if failures.is_empty() {
+      return Ok(ToolOutput::Function { body, success });
+    }
+    let feedback = verification_feedback(&failures);
+    match &mut body {
+      codex_protocol::models::FunctionCallOutputBody::Text(text) => text.push_str(&feedback),
+      codex_protocol::models::FunctionCallOutputBody::ContentItems(items) => {
+        items.push(
+          codex_protocol::models::FunctionCallOutputContentItem::InputText {
+            text: feedback.trim_start().to_string(),
+          },
+        );
+      }
+    }
+    self.report(
+      &thread_id,
+      codex_exec::exec_events::VerificationFailedEvent { call_id, failures },
+    );
+    Ok(ToolOutput::Function { body, success })
+  }
+}
+
+/// Interceptors running `options.verify` after patches, if any commands are configured.
+fn patch_verification_interceptors(
+  options: &InternalRunRequest,
+  redactor: Option<&Arc<SecretRedactor>>,
+) -> Vec<ExternalInterceptorRegistration> {
+  let Some(verification) = options.verify.clone() else {
+    return Vec::new();
+  };
+  let verification = Arc::new(verification);
+  PATCH_POLICY_TOOLS
+    .iter()
+    .map(|tool| ExternalInterceptorRegistration {
+      name: (*tool).to_string(),
+      handler: Arc::new(PatchVerificationInterceptor {
+        verification: Arc::clone(&verification),
+        redactor: redactor.cloned(),
+      }),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests_verification {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn feedback_names_each_failed_command() {
+    let verification = PatchVerification::from_options(VerifyOptions {
+      commands: vec!["cargo check".to_string(), "pnpm tsc".to_string()],
+      timeout_ms: None,
+    })
+    .unwrap();
+    assert_eq!(
+      verification,
+      Some(PatchVerification {
+        commands: vec!["cargo check".to_string(), "pnpm tsc".to_string()],
+        timeout_ms: DEFAULT_VERIFY_TIMEOUT_MS,
+      })
+    );
+    assert_eq!(
+      PatchVerification::from_options(VerifyOptions::default()).unwrap(),
+      None
+    );
+    assert!(
+      PatchVerification::from_options(VerifyOptions {
+        commands: vec![" ".to_string()],
+        timeout_ms: None,
+      })
+      .is_err()
+    );
+
+    let feedback = verification_feedback(&[
+      codex_exec::exec_events::VerificationFailure {
+        command: "cargo check".to_string(),
+        exit_code: Some(101),
+        timed_out: false,
+        output: "error[E0308]: mismatched types\n".to_string(),
+      },
+      codex_exec::exec_events::VerificationFailure {
+        command: "pnpm tsc".to_string(),
+        exit_code: None,
+        timed_out: true,
+        output: String::new(),
+      },
+    ]);
+    assert_eq!(
+      feedback,
+      "\n\nVerification failed after this patch. Fix these problems before continuing:\n\n\
+       $ cargo check (exit code 101)\nerror[E0308]: mismatched types\n\n$ pnpm tsc (timed out)\n"
+    );
+  }
+}
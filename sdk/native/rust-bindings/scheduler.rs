@@ -0,0 +1,686 @@
+// ============================================================================
+// Scheduled runs (scheduleRun / listSchedules / cancelSchedule / resumeSchedules)
+// ============================================================================
+//
+// scheduleRun() starts a run from a RunRequest every time a cron expression
+// matches, so nightly dependency audits or triage agents need no external cron
+// wrapper. Expressions have the usual five fields (minute, hour, day of month,
+// month, day of week) with `*`, lists, ranges, steps and three-letter names, or
+// one of `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly`, and are
+// evaluated in local time. `jitterMs` delays each run by a random amount, and
+// a run that would exceed `maxConcurrent` runs of the schedule is skipped.
+//
+// Schedules are stored in `$CODEX_HOME/schedules.json` without the request's
+// `apiKey`. The rest of the request is kept as given, MCP server headers and env
+// and `toolEnv.set` included, so the file is readable only by its owner, like
+// `auth.json`. A schedule runs in the process that created it; after a restart,
+// resumeSchedules() picks the stored ones up again. Lifecycle events
+// (`schedule.run_started`, `schedule.run_skipped`, `schedule.run_completed`,
+// `schedule.error`) go to the optional callback.
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const MONTH_NAMES: &[&str] = &[
+  "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleRunOptions {
+  /// Runs of this schedule allowed at once; a run due while this many are in progress is
+  /// skipped (default 1).
+  #[napi(js_name = "maxConcurrent")]
+  pub max_concurrent: Option<u32>,
+  /// Each run starts after a random delay of up to this many milliseconds (default 0).
+  #[napi(js_name = "jitterMs")]
+  pub jitter_ms: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduleInfo {
+  pub id: String,
+  pub cron: String,
+  pub prompt: String,
+  #[napi(js_name = "maxConcurrent")]
+  pub max_concurrent: u32,
+  #[napi(js_name = "jitterMs")]
+  pub jitter_ms: u32,
+  #[napi(js_name = "createdAt")]
+  pub created_at: String,
+  #[napi(js_name = "lastRunAt")]
+  pub last_run_at: Option<String>,
+  /// Next time the expression matches, before jitter.
+  #[napi(js_name = "nextRunAt")]
+  pub next_run_at: Option<String>,
+  /// Whether this process runs the schedule.
+  pub active: bool,
+  /// Runs of the schedule in progress in this process.
+  pub running: u32,
+}
+
+/// A parsed cron expression; each field is a bit set of the values it matches.
+#[derive(Clone, Debug, PartialEq)]
+struct CronExpression {
+  minutes: u64,
+  hours: u64,
+  days: u64,
+  months: u64,
+  /// Sunday is 0.
+  weekdays: u64,
+  /// The day-of-month field is `*`; cron matches either day field when both are restricted.
+  any_day: bool,
+  any_weekday: bool,
+}
+
+fn cron_value(token: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+  if let Ok(value) = token.parse::<u32>() {
+    return Ok(value);
+  }
+  names
+    .iter()
+    .position(|name| name.eq_ignore_ascii_case(token))
+    .map(|index| index as u32 + min)
+    .ok_or_else(|| format!("invalid value {token:?}"))
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+  let mut bits = 0u64;
+  for part in field.split(',') {
+    let (range, step) = match part.split_once('/') {
+      Some((range, step)) => (
+        range,
+        step
+          .parse::<u32>()
+          .ok()
+          .filter(|step| *step > 0)
+          .ok_or_else(|| format!("invalid step in {part:?}"))?,
+      ),
+      None => (part, 1),
+    };
+    let (start, end) = match range.split_once('-') {
+      _ if range == "*" => (min, max),
+      Some((start, end)) => (cron_value(start, min, names)?, cron_value(end, min, names)?),
+      // `5/15` means every 15 starting at 5.
+      None if part.contains('/') => (cron_value(range, min, names)?, max),
+      None => {
+        let value = cron_value(range, min, names)?;
+        (value, value)
+      }
+    };
+    if start < min || end > max || start > end {
+      return Err(format!("{part:?} is outside {min}-{max}"));
+    }
+    for value in (start..=end).step_by(step as usize) {
+      bits |= 1 << value;
+    }
+  }
+  Ok(bits)
+}
+
+impl CronExpression {
+  fn parse(expression: &str) -> Result<Self, String> {
+    let expanded = match expression.trim() {
+      "@yearly" | "@annually" => "0 0 1 1 *",
+      "@monthly" => "0 0 1 * *",
+      "@weekly" => "0 0 * * 0",
+      "@daily" | "@midnight" => "0 0 * * *",
+      "@hourly" => "0 * * * *",
+      other => other,
+    };
+    let fields: Vec<&str> = expanded.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields.as_slice() else {
+      return Err(format!(
+        "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+        fields.len()
+      ));
+    };
+    let field = |name: &str, value: &str, min: u32, max: u32, names: &[&str]| {
+      parse_cron_field(value, min, max, names).map_err(|err| format!("{name}: {err}"))
+    };
+    // 7 is also Sunday.
+    let weekdays = field("day of week", weekday, 0, 7, WEEKDAY_NAMES)?;
+    Ok(Self {
+      minutes: field("minute", minute, 0, 59, &[])?,
+      hours: field("hour", hour, 0, 23, &[])?,
+      days: field("day of month", day, 1, 31, &[])?,
+      months: field("month", month, 1, 12, MONTH_NAMES)?,
+      weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+      any_day: day.starts_with('*'),
+      any_weekday: weekday.starts_with('*'),
+    })
+  }
+
+  fn matches_date(&self, date: chrono::NaiveDate) -> bool {
+    use chrono::Datelike;
+
+    if self.months & (1 << date.month()) == 0 {
+      return false;
+    }
+    let day = self.days & (1 << date.day()) != 0;
+    let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+    if self.any_day || self.any_weekday {
+      day && weekday
+    } else {
+      day || weekday
+    }
+  }
+
+  /// The first matching minute after `after`.
+  fn next_after(&self, after: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+    use chrono::Timelike;
+
+    let mut time =
+      after.date().and_hms_opt(after.hour(), after.minute(), 0)? + chrono::Duration::minutes(1);
+    // Long enough for any date that exists, including February 29.
+    let limit = time + chrono::Duration::days(8 * 366);
+    while time < limit {
+      let date = time.date();
+      if !self.matches_date(date) {
+        time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+      } else if self.hours & (1 << time.hour()) == 0 {
+        time = date.and_hms_opt(time.hour(), 0, 0)? + chrono::Duration::hours(1);
+      } else if self.minutes & (1 << time.minute()) == 0 {
+        time += chrono::Duration::minutes(1);
+      } else {
+        return Some(time);
+      }
+    }
+    None
+  }
+
+  /// The next local time the expression matches after `now`, skipping times a DST change skips.
+  fn next_fire(
+    &self,
+    now: chrono::DateTime<chrono::Local>,
+  ) -> Option<chrono::DateTime<chrono::Local>> {
+    use chrono::TimeZone;
+
+    let mut candidate = self.next_after(now.naive_local())?;
+    loop {
+      if let Some(time) = chrono::Local.from_local_datetime(&candidate).earliest() {
+        return Some(time);
+      }
+      candidate = self.next_after(candidate)?;
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct StoredSchedule {
+  id: String,
+  cron: String,
+  /// The RunRequest as JSON, without `apiKey`.
+  request: JsonValue,
+  max_concurrent: u32,
+  jitter_ms: u32,
+  created_at: String,
+  #[serde(default)]
+  last_run_at: Option<String>,
+}
+
+impl StoredSchedule {
+  fn info(&self, active: Option<&ActiveSchedule>) -> ScheduleInfo {
+    ScheduleInfo {
+      id: self.id.clone(),
+      cron: self.cron.clone(),
+      prompt: self
+        .request
+        .get("prompt")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string(),
+      max_concurrent: self.max_concurrent,
+      jitter_ms: self.jitter_ms,
+      created_at: self.created_at.clone(),
+      last_run_at: self.last_run_at.clone(),
+      next_run_at: CronExpression::parse(&self.cron)
+        .ok()
+        .and_then(|cron| cron.next_fire(chrono::Local::now()))
+        .map(|time| format_rollout_timestamp(time.into())),
+      active: active.is_some(),
+      running: active.map_or(0, |active| {
+        active.running.load(std::sync::atomic::Ordering::SeqCst)
+      }),
+    }
+  }
+}
+
+fn schedules_path() -> napi::Result<PathBuf> {
+  find_codex_home()
+    .map(|home| home.join(SCHEDULES_FILE))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate schedules: {e}")))
+}
+
+fn load_schedules(store: &Path) -> io::Result<Vec<StoredSchedule>> {
+  match std::fs::read_to_string(store) {
+    Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+    Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+    Err(err) => Err(err),
+  }
+}
+
+/// Read, change and rewrite the store at `store`, one caller at a time.
+fn update_schedules<T>(
+  store: &Path,
+  change: impl FnOnce(&mut Vec<StoredSchedule>) -> T,
+) -> napi::Result<T> {
+  static STORE: Mutex<()> = Mutex::new(());
+  let _guard = STORE
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("schedules mutex poisoned: {e}")))?;
+  let to_error =
+    |e: io::Error| napi::Error::from_reason(format!("Failed to update schedules: {e}"));
+  let mut schedules = load_schedules(store).map_err(to_error)?;
+  let result = change(&mut schedules);
+  let contents = serde_json::to_string_pretty(&schedules)
+    .map_err(io::Error::other)
+    .map_err(to_error)?;
+  let temp = store.with_extension("json.tmp");
+  write_owner_only(&temp, &contents)
+    .and_then(|()| std::fs::rename(&temp, store))
+    .map_err(to_error)?;
+  Ok(result)
+}
+
+/// Write `contents` to `path`, readable and writable only by its owner on Unix: stored requests
+/// can carry MCP server headers and tool env values.
+fn write_owner_only(path: &Path, contents: &str) -> io::Result<()> {
+  use std::io::Write;
+
+  let mut options = std::fs::OpenOptions::new();
+  options.truncate(true).write(true).create(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(path)?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    // `mode` applies only when the file is created, so also fix a leftover temp file.
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+  }
+  file.write_all(contents.as_bytes())
+}
+
+struct ActiveSchedule {
+  running: Arc<std::sync::atomic::AtomicU32>,
+  /// Dropping this wakes the schedule's thread, which then exits.
+  _stop: std::sync::mpsc::Sender<()>,
+}
+
+fn active_schedules() -> &'static Mutex<HashMap<String, ActiveSchedule>> {
+  static ACTIVE: OnceLock<Mutex<HashMap<String, ActiveSchedule>>> = OnceLock::new();
+  ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn emit_schedule_event(on_event: Option<&ThreadsafeFunction<JsonValue>>, event: JsonValue) {
+  let Some(on_event) = on_event else {
+    return;
+  };
+  let status = on_event.call(
+    Ok(JsonValue::String(event.to_string())),
+    ThreadsafeFunctionCallMode::NonBlocking,
+  );
+  if status != Status::Ok {
+    eprintln!("Failed to deliver schedule event: {status}");
+  }
+}
+
+/// Start one run of `schedule`, unless `maxConcurrent` runs are already in progress.
+fn fire_schedule(
+  store: &Path,
+  schedule: &StoredSchedule,
+  request: &JsonValue,
+  run: u32,
+  running: &Arc<std::sync::atomic::AtomicU32>,
+  on_event: Option<&Arc<ThreadsafeFunction<JsonValue>>>,
+) {
+  let options = match run_request_from_json(request) {
+    Ok(options) => options,
+    Err(err) => {
+      emit_schedule_event(
+        on_event.map(Arc::as_ref),
+        json!({ "type": "schedule.error", "schedule_id": schedule.id, "message": err.reason }),
+      );
+      return;
+    }
+  };
+  let reserved = running.fetch_update(
+    std::sync::atomic::Ordering::SeqCst,
+    std::sync::atomic::Ordering::SeqCst,
+    |count| (count < schedule.max_concurrent).then_some(count + 1),
+  );
+  if reserved.is_err() {
+    emit_schedule_event(
+      on_event.map(Arc::as_ref),
+      json!({
+        "type": "schedule.run_skipped",
+        "schedule_id": schedule.id,
+        "run": run,
+        "reason": format!("{} runs already in progress", schedule.max_concurrent),
+      }),
+    );
+    return;
+  }
+
+  let started_at = format_rollout_timestamp(SystemTime::now());
+  let id = schedule.id.clone();
+  if let Err(err) = update_schedules(store, |schedules| {
+    if let Some(stored) = schedules.iter_mut().find(|stored| stored.id == id) {
+      stored.last_run_at = Some(started_at.clone());
+    }
+  }) {
+    eprintln!("Failed to record scheduled run: {}", err.reason);
+  }
+  emit_schedule_event(
+    on_event.map(Arc::as_ref),
+    json!({ "type": "schedule.run_started", "schedule_id": id, "run": run, "started_at": started_at }),
+  );
+
+  let running = Arc::clone(running);
+  let on_event = on_event.cloned();
+  let spawned = std::thread::Builder::new()
+    .name("codex-scheduled-run".to_string())
+    .spawn(move || {
+      let thread_id = Arc::new(Mutex::new(options.thread_id.clone()));
+      let thread_id_for_events = Arc::clone(&thread_id);
+      let result = run_with_session_hooks(options, move |event| {
+        if let ExecThreadEvent::ThreadStarted(started) = &event
+          && let Ok(mut slot) = thread_id_for_events.lock()
+        {
+          *slot = Some(started.thread_id.clone());
+        }
+      });
+      running.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+      emit_schedule_event(
+        on_event.as_deref(),
+        json!({
+          "type": "schedule.run_completed",
+          "schedule_id": id,
+          "run": run,
+          "thread_id": thread_id.lock().ok().and_then(|slot| slot.clone()),
+          "error": result.err().map(|err| err.reason),
+        }),
+      );
+    });
+  if let Err(err) = spawned {
+    eprintln!("Failed to start scheduled run: {err}");
+  }
+}
+
+fn schedule_loop(
+  store: PathBuf,
+  schedule: StoredSchedule,
+  cron: CronExpression,
+  request: JsonValue,
+  stop: std::sync::mpsc::Receiver<()>,
+  running: Arc<std::sync::atomic::AtomicU32>,
+  on_event: Option<Arc<ThreadsafeFunction<JsonValue>>>,
+) {
+  let mut runs = 0u32;
+  loop {
+    let now = chrono::Local::now();
+    let Some(next) = cron.next_fire(now) else {
+      emit_schedule_event(
+        on_event.as_deref(),
+        json!({
+          "type": "schedule.error",
+          "schedule_id": schedule.id,
+          "message": format!("{:?} never matches", schedule.cron),
+        }),
+      );
+      return;
+    };
+    let jitter = match schedule.jitter_ms {
+      0 => 0,
+      max => (Uuid::new_v4().as_u128() % (u128::from(max) + 1)) as u64,
+    };
+    let wait = (next - now).to_std().unwrap_or_default() + std::time::Duration::from_millis(jitter);
+    match stop.recv_timeout(wait) {
+      Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+      // cancelSchedule() dropped the sender.
+      Ok(()) | Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+    }
+    runs += 1;
+    fire_schedule(
+      &store,
+      &schedule,
+      &request,
+      runs,
+      &running,
+      on_event.as_ref(),
+    );
+  }
+}
+
+/// `request` as JSON, and the copy to persist without its `apiKey`. Fails if the request would
+/// not start a run, so bad requests are rejected up front rather than when they first run.
+fn persistable_run_request(request: &RunRequest) -> napi::Result<(JsonValue, JsonValue)> {
+  let request = serde_json::to_value(request)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize request: {e}")))?;
+  run_request_from_json(&request)?;
+  let mut stored = request.clone();
+  if let Some(fields) = stored.as_object_mut() {
+    fields.remove("apiKey");
+  }
+  Ok((request, stored))
+}
+
+fn run_request_from_json(request: &JsonValue) -> napi::Result<InternalRunRequest> {
+  serde_json::from_value::<RunRequest>(request.clone())
+    .map_err(|e| napi::Error::from_reason(format!("Invalid stored request: {e}")))?
+    .into_internal()
+}
+
+/// Run `schedule` in this process. `request` may carry the `apiKey` the store leaves out.
+fn activate_schedule(
+  store: PathBuf,
+  schedule: StoredSchedule,
+  request: JsonValue,
+  on_event: Option<Arc<ThreadsafeFunction<JsonValue>>>,
+) -> napi::Result<ScheduleInfo> {
+  let cron = CronExpression::parse(&schedule.cron).map_err(|err| {
+    napi::Error::from_reason(format!(
+      "Invalid cron expression {:?}: {err}",
+      schedule.cron
+    ))
+  })?;
+  let mut active = active_schedules()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("schedules mutex poisoned: {e}")))?;
+  if let Some(existing) = active.get(&schedule.id) {
+    return Ok(schedule.info(Some(existing)));
+  }
+  let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+  let running = Arc::new(std::sync::atomic::AtomicU32::new(0));
+  let entry = ActiveSchedule {
+    running: Arc::clone(&running),
+    _stop: stop_tx,
+  };
+  let info = schedule.info(Some(&entry));
+  let id = schedule.id.clone();
+  std::thread::Builder::new()
+    .name("codex-schedule".to_string())
+    .spawn(move || schedule_loop(store, schedule, cron, request, stop_rx, running, on_event))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to start schedule: {e}")))?;
+  active.insert(id, entry);
+  Ok(info)
+}
+
+/// Persist a schedule that runs `request` whenever `cron` matches, and start running it in this
+/// process.
+#[napi]
+pub fn schedule_run(
+  cron: String,
+  request: RunRequest,
+  options: Option<ScheduleRunOptions>,
+  #[napi(ts_arg_type = "(err: unknown, eventJson?: string) => void")] on_event: Option<
+    ThreadsafeFunction<JsonValue>,
+  >,
+) -> napi::Result<ScheduleInfo> {
+  let options = options.unwrap_or_default();
+  let max_concurrent = options.max_concurrent.unwrap_or(1);
+  if max_concurrent == 0 {
+    return Err(napi::Error::from_reason(
+      "maxConcurrent must be greater than zero",
+    ));
+  }
+  CronExpression::parse(&cron)
+    .map_err(|err| napi::Error::from_reason(format!("Invalid cron expression {cron:?}: {err}")))?;
+  let (request, stored_request) = persistable_run_request(&request)?;
+  let schedule = StoredSchedule {
+    id: Uuid::new_v4().to_string(),
+    cron,
+    request: stored_request,
+    max_concurrent,
+    jitter_ms: options.jitter_ms.unwrap_or(0),
+    created_at: format_rollout_timestamp(SystemTime::now()),
+    last_run_at: None,
+  };
+  let store = schedules_path()?;
+  let stored = schedule.clone();
+  update_schedules(&store, move |schedules| schedules.push(stored))?;
+  activate_schedule(store, schedule, request, on_event.map(Arc::new))
+}
+
+/// Every stored schedule, whether or not this process runs it.
+#[napi]
+pub fn list_schedules() -> napi::Result<Vec<ScheduleInfo>> {
+  let schedules = load_schedules(&schedules_path()?)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read schedules: {e}")))?;
+  let active = active_schedules()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("schedules mutex poisoned: {e}")))?;
+  Ok(
+    schedules
+      .iter()
+      .map(|schedule| schedule.info(active.get(&schedule.id)))
+      .collect(),
+  )
+}
+
+/// Delete a schedule and stop running it. Runs in progress finish. Returns `false` for unknown
+/// ids.
+#[napi]
+pub fn cancel_schedule(id: String) -> napi::Result<bool> {
+  let removed = update_schedules(&schedules_path()?, |schedules| {
+    let before = schedules.len();
+    schedules.retain(|schedule| schedule.id != id);
+    schedules.len() != before
+  })?;
+  let stopped = active_schedules()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("schedules mutex poisoned: {e}")))?
+    .remove(&id)
+    .is_some();
+  Ok(removed || stopped)
+}
+
+/// Start running the stored schedules this process does not run yet, e.g. after a restart.
+#[napi]
+pub fn resume_schedules(
+  #[napi(ts_arg_type = "(err: unknown, eventJson?: string) => void")] on_event: Option<
+    ThreadsafeFunction<JsonValue>,
+  >,
+) -> napi::Result<Vec<ScheduleInfo>> {
+  let on_event = on_event.map(Arc::new);
+  let store = schedules_path()?;
+  load_schedules(&store)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to read schedules: {e}")))?
+    .into_iter()
+    .map(|schedule| {
+      let request = schedule.request.clone();
+      activate_schedule(store.clone(), schedule, request, on_event.clone())
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests_scheduler {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn at(text: &str) -> chrono::NaiveDateTime {
+    chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+  }
+
+  #[test]
+  fn cron_expressions_find_the_next_matching_minute() {
+    let next = |expression: &str, after: &str| {
+      CronExpression::parse(expression)
+        .unwrap()
+        .next_after(at(after))
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+    };
+    // 2026-10-14 is a Wednesday.
+    assert_eq!(
+      next("*/15 * * * *", "2026-10-14 09:07"),
+      Some("2026-10-14 09:15".to_string())
+    );
+    assert_eq!(
+      next("0 2 * * *", "2026-10-14 02:00"),
+      Some("2026-10-15 02:00".to_string())
+    );
+    assert_eq!(
+      next("30 9 * * MON-FRI", "2026-10-16 10:00"),
+      Some("2026-10-19 09:30".to_string())
+    );
+    assert_eq!(
+      next("0 0 1 jan *", "2026-10-14 00:00"),
+      Some("2027-01-01 00:00".to_string())
+    );
+    assert_eq!(
+      next("@weekly", "2026-10-14 12:00"),
+      Some("2026-10-18 00:00".to_string())
+    );
+    // Both day fields restricted: either one matches.
+    assert_eq!(
+      next("0 0 13 * 5", "2026-10-14 00:00"),
+      Some("2026-10-16 00:00".to_string())
+    );
+    assert_eq!(
+      next("0 0 29 2 *", "2026-10-14 00:00"),
+      Some("2028-02-29 00:00".to_string())
+    );
+    assert_eq!(next("0 0 31 2 *", "2026-10-14 00:00"), None);
+
+    assert!(CronExpression::parse("* * *").is_err());
+    assert!(CronExpression::parse("60 * * * *").is_err());
+    assert!(CronExpression::parse("*/0 * * * *").is_err());
+    assert!(CronExpression::parse("0 0 * * funday").is_err());
+  }
+
+  #[test]
+  fn store_updates_persist_and_replace_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = dir.path().join(SCHEDULES_FILE);
+    assert_eq!(load_schedules(&store).unwrap(), Vec::new());
+
+    let schedule = StoredSchedule {
+      id: "nightly".to_string(),
+      cron: "0 2 * * *".to_string(),
+      request: json!({ "prompt": "Audit dependencies" }),
+      max_concurrent: 1,
+      jitter_ms: 0,
+      created_at: "2026-10-14T02:00:00".to_string(),
+      last_run_at: None,
+    };
+    let stored = schedule.clone();
+    update_schedules(&store, move |schedules| schedules.push(stored)).unwrap();
+    assert_eq!(load_schedules(&store).unwrap(), vec![schedule]);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&store).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let removed = update_schedules(&store, |schedules| schedules.pop().is_some()).unwrap();
+    assert!(removed);
+    assert_eq!(load_schedules(&store).unwrap(), Vec::new());
+    assert!(!store.with_extension("json.tmp").exists());
+  }
+}
@@ -4,8 +4,7 @@
 // Section 7: Tokenizer Helpers
 // ============================================================================
 
-use tiktoken_rs::CoreBPE;
-use tiktoken_rs::{cl100k_base, get_bpe_from_model, o200k_base};
+use codex_utils_tokenizer::CoreBPE;
 
 #[napi(object)]
 pub struct TokenizerBaseOptions {
@@ -27,24 +26,9 @@ fn map_tokenizer_error<E: std::fmt::Display>(err: E) -> napi::Error {
   napi::Error::from_reason(format!("Tokenizer error: {err}"))
 }
 
-fn encoding_from_name(name: &str) -> Option<CoreBPE> {
-  let normalized = name.replace('-', "_").to_ascii_lowercase();
-  match normalized.as_str() {
-    "o200k_base" => o200k_base().ok(),
-    "cl100k_base" => cl100k_base().ok(),
-    _ => None,
-  }
-}
-
 fn build_tokenizer(model: Option<&str>, encoding: Option<&str>) -> napi::Result<CoreBPE> {
-  if let Some(enc_name) = encoding {
-    encoding_from_name(enc_name)
-      .ok_or_else(|| napi::Error::from_reason(format!("Unknown tokenizer encoding: {enc_name}")))
-  } else if let Some(model_name) = model {
-    get_bpe_from_model(model_name).map_err(map_tokenizer_error)
-  } else {
-    cl100k_base().map_err(map_tokenizer_error)
-  }
+  codex_native_core::tokenizer::build_tokenizer(model, encoding)
+    .map_err(|err| napi::Error::from_reason(err.to_string()))
 }
 
 #[napi]
@@ -69,11 +53,7 @@ pub fn tokenizer_encode(
     .as_ref()
     .and_then(|o| o.with_special_tokens)
     .unwrap_or(false);
-  let tokens = if with_special_tokens {
-    tokenizer.encode_with_special_tokens(&text)
-  } else {
-    tokenizer.encode_ordinary(&text)
-  };
+  let tokens = codex_native_core::tokenizer::encode(&tokenizer, &text, with_special_tokens);
   Ok(tokens.into_iter().map(|t| t as i32).collect())
 }
 
@@ -90,5 +70,6 @@ pub fn tokenizer_decode(
     .iter()
     .map(|t| (*t).try_into().map_err(|_| map_tokenizer_error("token id must be non-negative")))
     .collect::<Result<_, _>>()?;
-  tokenizer.decode(ids).map_err(map_tokenizer_error)
+  codex_native_core::tokenizer::decode(&tokenizer, ids)
+    .map_err(|err| napi::Error::from_reason(err.to_string()))
 }
@@ -0,0 +1,343 @@
+// ============================================================================
+// Background tool tasks (spawnBackgroundTool / check_background_task)
+// ============================================================================
+//
+// A registered JS tool can hand long work (dev servers, watch-mode builds, long
+// test suites) to spawnBackgroundTool() and answer the model straight away with
+// a task handle. The model polls or waits on the handle with the builtin
+// `check_background_task` tool, offered whenever JS tools are registered, and
+// clients see `background_task_started` / `background_task_completed` events.
+// Tasks outlive the turn that started them, so a later run on the same thread
+// can still collect the result.
+
+const CHECK_BACKGROUND_TASK_TOOL: &str = "check_background_task";
+/// Upper bound on how long one `check_background_task` call may wait.
+const MAX_BACKGROUND_TASK_WAIT_SECONDS: u64 = 300;
+
+#[derive(Clone, Debug, PartialEq)]
+enum BackgroundTaskState {
+  Running,
+  Completed(String),
+  Failed(String),
+}
+
+impl BackgroundTaskState {
+  fn status(&self) -> codex_exec::exec_events::BackgroundTaskStatus {
+    use codex_exec::exec_events::BackgroundTaskStatus;
+    match self {
+      BackgroundTaskState::Running => BackgroundTaskStatus::Running,
+      BackgroundTaskState::Completed(_) => BackgroundTaskStatus::Completed,
+      BackgroundTaskState::Failed(_) => BackgroundTaskStatus::Failed,
+    }
+  }
+}
+
+struct BackgroundTask {
+  thread_id: String,
+  call_id: String,
+  tool_name: String,
+  started_at: std::time::Instant,
+  state: tokio::sync::watch::Receiver<BackgroundTaskState>,
+}
+
+impl BackgroundTask {
+  fn event(
+    &self,
+    task_id: &str,
+    state: &BackgroundTaskState,
+  ) -> codex_exec::exec_events::BackgroundTaskEvent {
+    let (output, error) = match state {
+      BackgroundTaskState::Running => (None, None),
+      BackgroundTaskState::Completed(output) => (Some(output.clone()), None),
+      BackgroundTaskState::Failed(error) => (None, Some(error.clone())),
+    };
+    codex_exec::exec_events::BackgroundTaskEvent {
+      task_id: task_id.to_string(),
+      call_id: self.call_id.clone(),
+      tool_name: self.tool_name.clone(),
+      status: state.status(),
+      output,
+      error,
+    }
+  }
+
+  fn report(&self, event: ExecThreadEvent) {
+    let handler = active_thread_handlers()
+      .lock()
+      .ok()
+      .and_then(|handlers| handlers.get(&self.thread_id).cloned());
+    if let Some(handler) = handler
+      && let Err(err) = dispatch_thread_event(&handler, event)
+    {
+      eprintln!("Failed to report background task: {err}");
+    }
+  }
+}
+
+fn background_tasks() -> &'static Mutex<HashMap<String, BackgroundTask>> {
+  static TASKS: OnceLock<Mutex<HashMap<String, BackgroundTask>>> = OnceLock::new();
+  TASKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn next_background_task_id() -> String {
+  static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+  format!(
+    "bg_{}",
+    NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+  )
+}
+
+/// Runs `run` for a registered tool call in the background and returns the response the
+/// tool's handler should give the model: a task handle for `check_background_task`.
+#[napi]
+pub fn spawn_background_tool(
+  env: Env,
+  invocation: JsToolInvocation,
+  #[napi(
+    ts_arg_type = "(call: JsToolInvocation) => NativeToolResponse | Promise<NativeToolResponse>"
+  )]
+  run: Function<JsToolInvocation, ToolHandlerReturn>,
+) -> napi::Result<NativeToolResponse> {
+  let thread_id = streaming_tool_calls()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("streaming tool calls mutex poisoned: {e}")))?
+    .get(&invocation.call_id)
+    .map(|call| call.thread_id.clone())
+    .ok_or_else(|| {
+      napi::Error::from_reason(format!(
+        "No running tool call {}. spawnBackgroundTool() must be called from the tool's handler.",
+        invocation.call_id
+      ))
+    })?;
+  let mut tsfn = run
+    .build_threadsafe_function::<JsToolInvocation>()
+    .callee_handled::<false>()
+    .build()?;
+  #[allow(deprecated)]
+  let _ = tsfn.unref(&env);
+
+  let task_id = next_background_task_id();
+  let (state_tx, state_rx) = tokio::sync::watch::channel(BackgroundTaskState::Running);
+  let task = BackgroundTask {
+    thread_id,
+    call_id: invocation.call_id.clone(),
+    tool_name: invocation.tool_name.clone(),
+    started_at: std::time::Instant::now(),
+    state: state_rx,
+  };
+  task.report(ExecThreadEvent::BackgroundTaskStarted(
+    task.event(&task_id, &BackgroundTaskState::Running),
+  ));
+  background_tasks()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("background tasks mutex poisoned: {e}")))?
+    .insert(task_id.clone(), task);
+
+  let spawned_task_id = task_id.clone();
+  napi::bindgen_prelude::spawn(async move {
+    let state = match tsfn.call_async(invocation).await {
+      Ok(napi::Either::A(promise)) => match promise.await {
+        Ok(response) => background_task_state(response),
+        Err(err) => BackgroundTaskState::Failed(err.to_string()),
+      },
+      Ok(napi::Either::B(response)) => background_task_state(response),
+      Err(err) => BackgroundTaskState::Failed(err.to_string()),
+    };
+    let _ = state_tx.send(state.clone());
+    if let Ok(tasks) = background_tasks().lock()
+      && let Some(task) = tasks.get(&spawned_task_id)
+    {
+      task.report(ExecThreadEvent::BackgroundTaskCompleted(
+        task.event(&spawned_task_id, &state),
+      ));
+    }
+  });
+
+  Ok(NativeToolResponse {
+    output: Some(
+      json!({
+        "task_id": task_id,
+        "status": "running",
+        "message": format!(
+          "Started in the background. Call {CHECK_BACKGROUND_TASK_TOOL} with this task_id to poll or wait for the result."
+        ),
+      })
+      .to_string(),
+    ),
+    success: Some(true),
+    error: None,
+  })
+}
+
+fn background_task_state(response: NativeToolResponse) -> BackgroundTaskState {
+  match response.error {
+    Some(error) => BackgroundTaskState::Failed(error),
+    None if response.success == Some(false) => {
+      BackgroundTaskState::Failed(response.output.unwrap_or_default())
+    }
+    None => BackgroundTaskState::Completed(response.output.unwrap_or_default()),
+  }
+}
+
+#[derive(serde::Deserialize)]
+struct CheckBackgroundTaskArgs {
+  task_id: String,
+  #[serde(default)]
+  wait_seconds: Option<u64>,
+}
+
+struct CheckBackgroundTaskTool;
+
+#[async_trait]
+impl ToolHandler for CheckBackgroundTaskTool {
+  fn kind(&self) -> ToolKind {
+    ToolKind::Function
+  }
+
+  async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let ToolPayload::Function { arguments } = &invocation.payload else {
+      return Err(FunctionCallError::RespondToModel(format!(
+        "{CHECK_BACKGROUND_TASK_TOOL} expects function arguments"
+      )));
+    };
+    let args: CheckBackgroundTaskArgs = serde_json::from_str(arguments).map_err(|err| {
+      FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err}"))
+    })?;
+    let (mut state, started_at, tool_name) = {
+      let tasks = background_tasks()
+        .lock()
+        .map_err(|e| FunctionCallError::Fatal(format!("background tasks mutex poisoned: {e}")))?;
+      match tasks.get(&args.task_id) {
+        Some(task) if task.thread_id == invocation.thread_id() => {
+          (task.state.clone(), task.started_at, task.tool_name.clone())
+        }
+        _ => {
+          return Err(FunctionCallError::RespondToModel(format!(
+            "Unknown background task: {}",
+            args.task_id
+          )));
+        }
+      }
+    };
+    if let Some(wait_seconds) = args.wait_seconds.filter(|seconds| *seconds > 0) {
+      let wait = std::time::Duration::from_secs(wait_seconds.min(MAX_BACKGROUND_TASK_WAIT_SECONDS));
+      // A timeout just means the task is still running.
+      let _ = tokio::time::timeout(
+        wait,
+        state.wait_for(|state| *state != BackgroundTaskState::Running),
+      )
+      .await;
+    }
+    let current = state.borrow().clone();
+    let mut report = json!({
+      "task_id": args.task_id,
+      "tool_name": tool_name,
+      "status": current.status(),
+      "elapsed_ms": started_at.elapsed().as_millis() as u64,
+    });
+    match current {
+      BackgroundTaskState::Running => {}
+      BackgroundTaskState::Completed(output) => report["output"] = json!(output),
+      BackgroundTaskState::Failed(error) => report["error"] = json!(error),
+    }
+    Ok(ToolOutput::Function {
+      body: codex_protocol::models::FunctionCallOutputBody::Text(report.to_string()),
+      success: Some(true),
+    })
+  }
+}
+
+fn check_background_task_registration() -> napi::Result<ExternalToolRegistration> {
+  let spec = create_function_tool_spec_from_schema(
+    CHECK_BACKGROUND_TASK_TOOL.to_string(),
+    Some(
+      "Check on a task a tool started in the background. Returns its status, and its output \
+       once it finished. Set wait_seconds to wait for it to finish first."
+        .to_string(),
+    ),
+    json!({
+      "type": "object",
+      "properties": {
+        "task_id": {
+          "type": "string",
+          "description": "The task_id the tool returned."
+        },
+        "wait_seconds": {
+          "type": "number",
+          "description": format!("Seconds to wait for the task to finish, at most {MAX_BACKGROUND_TASK_WAIT_SECONDS}.")
+        }
+      },
+      "required": ["task_id"]
+    }),
+    false,
+  )
+  .map_err(|err| napi::Error::from_reason(format!("invalid tool schema: {err}")))?;
+  Ok(ExternalToolRegistration {
+    spec,
+    handler: Arc::new(CheckBackgroundTaskTool),
+    supports_parallel_tool_calls: true,
+  })
+}
+
+/// The registered tools for a run, plus `check_background_task` when any JS tool is registered,
+/// `run_tests`, and the language server tools while a server is attached, unless a JS tool takes
+/// their name.
+fn run_external_tools() -> napi::Result<Vec<ExternalToolRegistration>> {
+  let mut tools = registered_native_tools()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("tools mutex poisoned: {e}")))?
+    .clone();
+  let registered_names: Vec<String> = registered_tool_infos()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("tools infos mutex poisoned: {e}")))?
+    .iter()
+    .map(|info| info.name.clone())
+    .collect();
+  let overridden = |name: &str| registered_names.iter().any(|registered| registered == name);
+  if !tools.is_empty() && !overridden(CHECK_BACKGROUND_TASK_TOOL) {
+    tools.push(check_background_task_registration()?);
+  }
+  if !overridden(RUN_TESTS_TOOL) {
+    tools.push(run_tests_registration()?);
+  }
+  for (name, registration) in lsp_tool_registrations()? {
+    if !overridden(name) {
+      tools.push(registration);
+    }
+  }
+  Ok(tools)
+}
+
+#[cfg(test)]
+mod tests_background {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn tool_responses_map_to_task_states() {
+    assert_eq!(
+      background_task_state(NativeToolResponse {
+        output: Some("server listening on :3000".to_string()),
+        success: None,
+        error: None,
+      }),
+      BackgroundTaskState::Completed("server listening on :3000".to_string())
+    );
+    assert_eq!(
+      background_task_state(NativeToolResponse {
+        output: Some("3 tests failed".to_string()),
+        success: Some(false),
+        error: None,
+      }),
+      BackgroundTaskState::Failed("3 tests failed".to_string())
+    );
+    assert_eq!(
+      background_task_state(NativeToolResponse {
+        output: None,
+        success: None,
+        error: Some("build crashed".to_string()),
+      }),
+      BackgroundTaskState::Failed("build crashed".to_string())
+    );
+  }
+}
@@ -1,14 +1,21 @@
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
 pub struct NativeToolInfo {
   pub name: String,
   pub description: Option<String>,
   pub parameters: Option<JsonValue>,
   pub strict: Option<bool>,
   pub supports_parallel: Option<bool>,
+  /// Calls of the tool allowed to run at once.
+  pub max_concurrent: Option<u32>,
+  /// Minimum milliseconds between the starts of two calls of the tool.
+  pub min_interval_ms: Option<u32>,
+  /// Seconds to reuse successful results of identical calls within a run.
+  pub cache_ttl_seconds: Option<u32>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
 pub struct NativeToolResponse {
   pub output: Option<String>,
@@ -24,6 +31,15 @@ pub struct JsEmitBackgroundEventRequest {
   pub message: String,
 }
 
+#[derive(Clone, Default)]
+#[napi(object)]
+pub struct JsThreadStatus {
+  pub text: Option<String>,
+  /// Completion percentage, 0-100.
+  #[napi(js_name = "progressPct")]
+  pub progress_pct: Option<f64>,
+}
+
 #[derive(Clone)]
 #[napi(object)]
 pub struct JsEmitPlanUpdateRequest {
@@ -119,8 +135,9 @@ struct JsToolInterceptor {
   >,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[napi(object)]
+#[serde(rename_all = "camelCase")]
 pub struct WorkspaceWriteOptions {
   #[napi(js_name = "networkAccess")]
   pub network_access: Option<bool>,
@@ -154,7 +171,7 @@ pub fn clear_registered_tools() -> napi::Result<()> {
     .lock()
     .map_err(|e| napi::Error::from_reason(format!("test tool callbacks mutex poisoned: {e}")))?
     .clear();
-  Ok(())
+  clear_hooks()
 }
 
 #[napi]
@@ -205,20 +222,6 @@ pub fn register_tool(
   )]
   handler: Function<JsToolInvocation, ToolHandlerReturn>,
 ) -> napi::Result<()> {
-  let schema = info.parameters.clone().unwrap_or_else(|| {
-    json!({
-        "type": "object",
-        "properties": {}
-    })
-  });
-  let spec = create_function_tool_spec_from_schema(
-    info.name.clone(),
-    info.description.clone(),
-    schema,
-    info.strict.unwrap_or(false),
-  )
-  .map_err(|err| napi::Error::from_reason(format!("invalid tool schema: {err}")))?;
-
   // Use callee_handled::<false>() so JS callback receives single arg (payload) not (err, payload)
   let mut tsfn = handler
     .build_threadsafe_function::<JsToolInvocation>()
@@ -227,16 +230,42 @@ pub fn register_tool(
   #[allow(deprecated)]
   let _ = tsfn.unref(&env);
   let tsfn = Arc::new(tsfn);
+  let name = info.name.clone();
+  push_registered_tool(
+    info,
+    Arc::new(JsToolHandler {
+      callback: tsfn.clone(),
+    }),
+  )?;
 
   // Keep a copy for test-only direct invocation to validate payload delivery.
   test_tool_callbacks()
     .lock()
     .map_err(|e| napi::Error::from_reason(format!("test tool callbacks mutex poisoned: {e}")))?
-    .insert(info.name.clone(), tsfn.clone());
+    .insert(name, tsfn);
+  Ok(())
+}
+
+/// Register `handler` as the tool `info` describes, for runs started from now on.
+fn push_registered_tool(info: NativeToolInfo, handler: Arc<dyn ToolHandler>) -> napi::Result<()> {
+  tool_limit(&info.name, info.max_concurrent, info.min_interval_ms)?;
+  let schema = info.parameters.clone().unwrap_or_else(|| {
+    json!({
+        "type": "object",
+        "properties": {}
+    })
+  });
+  let spec = create_function_tool_spec_from_schema(
+    info.name.clone(),
+    info.description.clone(),
+    schema,
+    info.strict.unwrap_or(false),
+  )
+  .map_err(|err| napi::Error::from_reason(format!("invalid tool schema: {err}")))?;
 
   let registration = ExternalToolRegistration {
     spec,
-    handler: Arc::new(JsToolHandler { callback: tsfn.clone() }),
+    handler,
     supports_parallel_tool_calls: info.supports_parallel.unwrap_or(true),
   };
 
@@ -325,7 +354,9 @@ pub async fn call_tool_builtin(
     .lock()
     .map_err(|e| napi::Error::from_reason(format!("pending builtin mutex poisoned: {e}")))?
     .remove(&token)
-    .ok_or_else(|| napi::Error::from_reason(format!("No pending builtin call for token {token}")))?;
+    .ok_or_else(|| {
+      napi::Error::from_reason(format!("No pending builtin call for token {token}"))
+    })?;
 
   let next = entry
     .next
@@ -355,13 +386,11 @@ pub async fn call_tool_builtin(
 
   match next.call(invocation).await {
     Ok(output) => tool_output_to_native_response(output).map_err(napi::Error::from_reason),
-    Err(FunctionCallError::RespondToModel(message)) => {
-      Ok(NativeToolResponse {
-        output: None,
-        success: Some(false),
-        error: Some(message),
-      })
-    }
+    Err(FunctionCallError::RespondToModel(message)) => Ok(NativeToolResponse {
+      output: None,
+      success: Some(false),
+      error: Some(message),
+    }),
     Err(FunctionCallError::MissingLocalShellCallId) => Err(napi::Error::from_reason(
       "callBuiltin failed: missing local shell call id",
     )),
@@ -393,8 +422,72 @@ pub fn emit_background_event(req: JsEmitBackgroundEventRequest) -> napi::Result<
   )
 }
 
+/// Reports progress for a thread. Active runs stream it as a `thread_status` event and a TUI
+/// showing the thread displays it under its working indicator. `null`, or a status without text,
+/// clears it.
+#[napi]
+pub fn set_thread_status(thread_id: String, status: Option<JsThreadStatus>) -> napi::Result<()> {
+  let status = status.unwrap_or_default();
+  if let Some(pct) = status.progress_pct
+    && !pct.is_finite()
+  {
+    return Err(napi::Error::from_reason(format!(
+      "progressPct must be a finite number, got {pct}"
+    )));
+  }
+  let text = status.text.filter(|text| !text.trim().is_empty());
+  let progress_pct = status.progress_pct.map(|pct| pct.clamp(0.0, 100.0));
+
+  let handler = {
+    let map = active_thread_handlers()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("thread handlers mutex poisoned: {e}")))?;
+    map.get(&thread_id).cloned()
+  };
+  if let Some(handler) = &handler {
+    dispatch_thread_event(
+      handler,
+      ExecThreadEvent::ThreadStatus(codex_exec::exec_events::ThreadStatusEvent {
+        text: text.clone(),
+        progress_pct,
+      }),
+    )?;
+  }
+  let shown_in_tui = codex_tui::set_thread_status(
+    &thread_id,
+    text.map(|text| codex_tui::ThreadStatus { text, progress_pct }),
+  );
+
+  if handler.is_none() && !shown_in_tui {
+    return Err(napi::Error::from_reason(format!(
+      "No active run or TUI for thread {thread_id}. Thread status requires an ongoing runStreamed call or TUI session."
+    )));
+  }
+  Ok(())
+}
+
+/// Streams `chunk` of a registered tool's output to clients while the tool is still running.
+/// The model still receives the tool's final response.
+#[napi]
+pub fn emit_tool_output_chunk(call_id: String, chunk: String) -> napi::Result<()> {
+  let calls = streaming_tool_calls()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("streaming tool calls mutex poisoned: {e}")))?;
+  let call = calls.get(&call_id).ok_or_else(|| {
+    napi::Error::from_reason(format!(
+      "No running tool call {call_id}. Output chunks can only be emitted while the tool's handler runs."
+    ))
+  })?;
+  call.sender.chunk(chunk);
+  Ok(())
+}
+
 #[napi]
 pub fn emit_plan_update(req: JsEmitPlanUpdateRequest) -> napi::Result<()> {
+  emit_plan_update_in(&thread_plans_path()?, req)
+}
+
+fn emit_plan_update_in(sidecar: &Path, req: JsEmitPlanUpdateRequest) -> napi::Result<()> {
   let plan_items = req
     .plan
     .into_iter()
@@ -423,26 +516,23 @@ pub fn emit_plan_update(req: JsEmitPlanUpdateRequest) -> napi::Result<()> {
     plan: plan_items,
   };
 
-  pending_plan_updates()
-    .lock()
-    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?
-    .insert(req.thread_id, args);
-
-  Ok(())
+  schedule_thread_plan(sidecar, req.thread_id, args)
 }
 
 #[napi]
 pub fn modify_plan(req: JsModifyPlanRequest) -> napi::Result<()> {
-  let mut pending_updates = pending_plan_updates()
-    .lock()
-    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?;
-
-  let current_plan = pending_updates.get(&req.thread_id).cloned();
+  modify_plan_in(&thread_plans_path()?, req)
+}
 
-  let mut plan_items = if let Some(existing) = current_plan {
-    existing.plan
-  } else {
-    Vec::new()
+fn modify_plan_in(sidecar: &Path, req: JsModifyPlanRequest) -> napi::Result<()> {
+  let pending = pending_plan_updates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?
+    .get(&req.thread_id)
+    .map(|pending| pending.plan.clone());
+  let mut plan_items = match pending {
+    Some(plan) => plan,
+    None => current_plan_items(sidecar, &req.thread_id).unwrap_or_default(),
   };
 
   for op in req.operations {
@@ -513,7 +603,5 @@ pub fn modify_plan(req: JsModifyPlanRequest) -> napi::Result<()> {
     plan: plan_items,
   };
 
-  pending_updates.insert(req.thread_id, args);
-
-  Ok(())
+  schedule_thread_plan(sidecar, req.thread_id, args)
 }
@@ -5,6 +5,7 @@ impl ToolHandler for JsToolHandler {
   }
 
   async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let thread_id = invocation.thread_id();
     let js_invocation = match invocation.payload {
       ToolPayload::Function { arguments } => JsToolInvocation {
         call_id: invocation.call_id.clone(),
@@ -26,16 +27,33 @@ impl ToolHandler for JsToolHandler {
       }
     };
 
-    match self.callback.call_async(js_invocation).await {
-      Ok(napi::Either::A(promise)) => {
-        let native_response = promise
-          .await
-          .map_err(|err| FunctionCallError::Fatal(err.to_string()))?;
-        native_response_to_tool_output(native_response)
+    // The handler answers with a stream right away, so chunks the JS tool emits with
+    // emitToolOutputChunk() reach clients while it runs.
+    let call_id = invocation.call_id.clone();
+    let (sender, stream) = ToolOutputStream::channel();
+    streaming_tool_calls()
+      .lock()
+      .map_err(|e| FunctionCallError::Fatal(format!("streaming tool calls mutex poisoned: {e}")))?
+      .insert(call_id.clone(), RunningToolCall { thread_id, sender });
+    let callback = Arc::clone(&self.callback);
+    tokio::spawn(async move {
+      let result = match callback.call_async(js_invocation).await {
+        Ok(napi::Either::A(promise)) => match promise.await {
+          Ok(native_response) => native_response_to_tool_output(native_response),
+          Err(err) => Err(FunctionCallError::Fatal(err.to_string())),
+        },
+        Ok(napi::Either::B(native_response)) => native_response_to_tool_output(native_response),
+        Err(err) => Err(FunctionCallError::Fatal(err.to_string())),
+      };
+      let call = streaming_tool_calls()
+        .lock()
+        .ok()
+        .and_then(|mut calls| calls.remove(&call_id));
+      if let Some(call) = call {
+        call.sender.finish(result);
       }
-      Ok(napi::Either::B(native_response)) => native_response_to_tool_output(native_response),
-      Err(err) => Err(FunctionCallError::Fatal(err.to_string())),
-    }
+    });
+    Ok(ToolOutput::Stream { stream })
   }
 }
 
@@ -70,20 +88,34 @@ impl ToolInterceptor for JsApprovalInterceptor {
       }),
     };
 
-    let approved = match self
+    let thread_id = invocation.thread_id();
+    set_thread_pending_approval(&thread_id, Some(&invocation.tool_name));
+    let decision = match self
       .callback
       .call_async(Ok(JsApprovalRequest {
-        type_: req_type,
+        type_: req_type.clone(),
         details: Some(details),
       }))
       .await
     {
       Ok(napi::Either::A(promise)) => promise
         .await
-        .map_err(|err| FunctionCallError::Fatal(err.to_string()))?,
-      Ok(napi::Either::B(value)) => value,
-      Err(err) => return Err(FunctionCallError::Fatal(err.to_string())),
+        .map_err(|err| FunctionCallError::Fatal(err.to_string())),
+      Ok(napi::Either::B(value)) => Ok(value),
+      Err(err) => Err(FunctionCallError::Fatal(err.to_string())),
     };
+    set_thread_pending_approval(&thread_id, None);
+    let approved = decision?;
+    audit_record(
+      "approval",
+      Some(&thread_id),
+      json!({
+        "call_id": invocation.call_id,
+        "tool": invocation.tool_name,
+        "type": req_type,
+        "approved": approved,
+      }),
+    );
 
     if !approved {
       return Err(FunctionCallError::RespondToModel(format!(
@@ -0,0 +1,867 @@
+// ============================================================================
+// Language server bridge (lspAttach, get_diagnostics, goto_definition)
+// ============================================================================
+//
+// lspAttach() starts a language server for a workspace root over stdio and
+// keeps it running until lspDetach() or process exit. While any server is
+// attached, runs get two builtin tools: `get_diagnostics` returns the errors
+// and warnings the server reports for a file, and `goto_definition` resolves
+// a position to the symbol's definition. Each call re-sends the file from
+// disk, so results reflect the edits the agent just made. A file is served by
+// the attached server with the deepest root containing it.
+
+const GET_DIAGNOSTICS_TOOL: &str = "get_diagnostics";
+const GOTO_DEFINITION_TOOL: &str = "goto_definition";
+const LSP_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// How long get_diagnostics waits for the server to publish diagnostics for a file it was sent.
+const LSP_DIAGNOSTICS_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long a detached server gets to exit after `exit` before it is killed.
+const LSP_EXIT_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct LspAttachOptions {
+  /// Language server executable, e.g. `rust-analyzer` or `typescript-language-server`.
+  pub server: String,
+  pub args: Option<Vec<String>>,
+  /// `languageId` for opened files. Guessed from the file extension when unset.
+  #[napi(js_name = "languageId")]
+  pub language_id: Option<String>,
+  /// Sent as `initializationOptions` in the `initialize` request.
+  #[napi(js_name = "initializationOptions")]
+  pub initialization_options: Option<JsonValue>,
+}
+
+#[napi(object)]
+pub struct LspServerInfo {
+  pub root: String,
+  pub server: String,
+  /// Name the server reported in its `initialize` response.
+  pub name: Option<String>,
+}
+
+type LspResponse = Result<JsonValue, String>;
+
+struct LspClient {
+  root: PathBuf,
+  language_id: Option<String>,
+  outgoing: tokio::sync::mpsc::UnboundedSender<JsonValue>,
+  next_id: std::sync::atomic::AtomicI64,
+  pending: Mutex<HashMap<i64, tokio::sync::oneshot::Sender<LspResponse>>>,
+  /// Latest published diagnostics by canonical file path, with the generation they arrived in.
+  diagnostics: Mutex<HashMap<PathBuf, (u64, Vec<JsonValue>)>>,
+  diagnostics_generation: std::sync::atomic::AtomicU64,
+  diagnostics_changed: tokio::sync::Notify,
+  /// Versions of the documents sent to the server, by URI.
+  documents: tokio::sync::Mutex<HashMap<String, i32>>,
+  /// Cancelled once the server exits or is detached.
+  stopped: CancellationToken,
+}
+
+fn lsp_clients() -> &'static Mutex<Vec<Arc<LspClient>>> {
+  static CLIENTS: OnceLock<Mutex<Vec<Arc<LspClient>>>> = OnceLock::new();
+  CLIENTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// The attached server with the deepest root containing `path`.
+fn lsp_client_for(path: &Path) -> Option<Arc<LspClient>> {
+  lsp_clients()
+    .lock()
+    .ok()?
+    .iter()
+    .filter(|client| path.starts_with(&client.root) && !client.stopped.is_cancelled())
+    .max_by_key(|client| client.root.components().count())
+    .cloned()
+}
+
+impl LspClient {
+  fn send(&self, message: JsonValue) -> Result<(), String> {
+    if self.stopped.is_cancelled() {
+      return Err("the language server exited".to_string());
+    }
+    self
+      .outgoing
+      .send(message)
+      .map_err(|_| "the language server exited".to_string())
+  }
+
+  fn notify(&self, method: &str, params: JsonValue) -> Result<(), String> {
+    self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+  }
+
+  async fn request(
+    &self,
+    method: &str,
+    params: JsonValue,
+    timeout: std::time::Duration,
+  ) -> LspResponse {
+    let id = self
+      .next_id
+      .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    self
+      .pending
+      .lock()
+      .map_err(|e| format!("lsp requests mutex poisoned: {e}"))?
+      .insert(id, tx);
+    self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+    match tokio::time::timeout(timeout, rx).await {
+      Ok(Ok(response)) => response,
+      Ok(Err(_)) => Err("the language server exited".to_string()),
+      Err(_) => {
+        if let Ok(mut pending) = self.pending.lock() {
+          pending.remove(&id);
+        }
+        Err(format!("{method} timed out after {}s", timeout.as_secs()))
+      }
+    }
+  }
+
+  fn handle_message(&self, message: JsonValue) {
+    let method = message.get("method").and_then(JsonValue::as_str);
+    match (method, message.get("id")) {
+      (Some("textDocument/publishDiagnostics"), None) => {
+        self.record_diagnostics(&message["params"]);
+      }
+      (Some(method), Some(id)) => {
+        // Servers ask for settings and register capabilities; defaults are fine for both.
+        let result = match method {
+          "workspace/configuration" => {
+            let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+            JsonValue::Array(vec![JsonValue::Null; items])
+          }
+          _ => JsonValue::Null,
+        };
+        let _ = self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+      }
+      (None, Some(id)) => {
+        let Some(id) = id.as_i64() else {
+          return;
+        };
+        let Some(tx) = self
+          .pending
+          .lock()
+          .ok()
+          .and_then(|mut pending| pending.remove(&id))
+        else {
+          return;
+        };
+        let response = match message.get("error") {
+          Some(error) => Err(
+            error
+              .get("message")
+              .and_then(JsonValue::as_str)
+              .unwrap_or("request failed")
+              .to_string(),
+          ),
+          None => Ok(message.get("result").cloned().unwrap_or(JsonValue::Null)),
+        };
+        let _ = tx.send(response);
+      }
+      (Some(_), None) | (None, None) => {}
+    }
+  }
+
+  fn record_diagnostics(&self, params: &JsonValue) {
+    let Some(path) = params["uri"].as_str().and_then(uri_path) else {
+      return;
+    };
+    let diagnostics = params["diagnostics"]
+      .as_array()
+      .cloned()
+      .unwrap_or_default();
+    let generation = self
+      .diagnostics_generation
+      .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+      + 1;
+    if let Ok(mut published) = self.diagnostics.lock() {
+      published.insert(canonical_path(&path), (generation, diagnostics));
+    }
+    self.diagnostics_changed.notify_waiters();
+  }
+
+  fn published_diagnostics(&self, path: &Path, after: u64) -> Option<Vec<JsonValue>> {
+    let published = self.diagnostics.lock().ok()?;
+    let (generation, diagnostics) = published.get(path)?;
+    (*generation > after).then(|| diagnostics.clone())
+  }
+
+  /// Send the file's current contents to the server. Returns its URI and text.
+  async fn sync_document(&self, path: &Path) -> Result<(String, String), String> {
+    let text = tokio::fs::read_to_string(path)
+      .await
+      .map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+    let uri = file_uri(path);
+    let mut documents = self.documents.lock().await;
+    match documents.get_mut(&uri) {
+      Some(version) => {
+        *version += 1;
+        self.notify(
+          "textDocument/didChange",
+          json!({
+            "textDocument": { "uri": uri, "version": *version },
+            "contentChanges": [{ "text": text }],
+          }),
+        )?;
+      }
+      None => {
+        let language_id = self
+          .language_id
+          .clone()
+          .unwrap_or_else(|| language_id_for(path).to_string());
+        self.notify(
+          "textDocument/didOpen",
+          json!({
+            "textDocument": { "uri": uri, "languageId": language_id, "version": 1, "text": text },
+          }),
+        )?;
+        documents.insert(uri.clone(), 1);
+      }
+    }
+    // Some servers only re-check a file once it is saved.
+    self.notify(
+      "textDocument/didSave",
+      json!({ "textDocument": { "uri": uri } }),
+    )?;
+    Ok((uri, text))
+  }
+
+  async fn diagnostics(&self, path: &Path) -> Result<Vec<JsonValue>, String> {
+    let before = self
+      .diagnostics_generation
+      .load(std::sync::atomic::Ordering::SeqCst);
+    self.sync_document(path).await?;
+    let deadline = tokio::time::Instant::now() + LSP_DIAGNOSTICS_WAIT;
+    loop {
+      let changed = self.diagnostics_changed.notified();
+      if let Some(diagnostics) = self.published_diagnostics(path, before) {
+        return Ok(diagnostics);
+      }
+      if tokio::time::timeout_at(deadline, changed).await.is_err() {
+        // Servers may not publish again when nothing changed; report what they last sent.
+        return Ok(self.published_diagnostics(path, 0).unwrap_or_default());
+      }
+    }
+  }
+
+  async fn definition(
+    &self,
+    path: &Path,
+    line: u32,
+    column: u32,
+  ) -> Result<Vec<(String, u64, u64)>, String> {
+    let (uri, text) = self.sync_document(path).await?;
+    let result = self
+      .request(
+        "textDocument/definition",
+        json!({
+          "textDocument": { "uri": uri },
+          "position": {
+            "line": line.saturating_sub(1),
+            "character": utf16_character(&text, line, column),
+          },
+        }),
+        LSP_REQUEST_TIMEOUT,
+      )
+      .await?;
+    Ok(definition_locations(&result))
+  }
+
+  async fn shutdown(&self) {
+    let _ = self
+      .request("shutdown", JsonValue::Null, LSP_EXIT_GRACE)
+      .await;
+    let _ = self.notify("exit", JsonValue::Null);
+    self.stopped.cancel();
+  }
+}
+
+async fn read_lsp_message<R>(reader: &mut R) -> io::Result<Option<JsonValue>>
+where
+  R: tokio::io::AsyncBufRead + Unpin,
+{
+  use tokio::io::AsyncBufReadExt;
+  use tokio::io::AsyncReadExt;
+
+  let mut content_length = None;
+  let length = loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+      return Ok(None);
+    }
+    let line = line.trim_end();
+    if line.is_empty() {
+      match content_length {
+        Some(length) => break length,
+        None => continue,
+      }
+    }
+    if let Some((name, value)) = line.split_once(':')
+      && name.eq_ignore_ascii_case("content-length")
+    {
+      content_length = value.trim().parse::<usize>().ok();
+    }
+  };
+  let mut body = vec![0; length];
+  reader.read_exact(&mut body).await?;
+  // A malformed message is skipped rather than ending the connection.
+  Ok(Some(
+    serde_json::from_slice(&body).unwrap_or(JsonValue::Null),
+  ))
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+  std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn file_uri(path: &Path) -> String {
+  let path = path.to_string_lossy().replace('\\', "/");
+  let mut uri = String::from("file://");
+  if !path.starts_with('/') {
+    uri.push('/');
+  }
+  for byte in path.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+        uri.push(byte as char);
+      }
+      _ => uri.push_str(&format!("%{byte:02X}")),
+    }
+  }
+  uri
+}
+
+fn uri_path(uri: &str) -> Option<PathBuf> {
+  let encoded = uri.strip_prefix("file://")?.as_bytes();
+  let mut bytes = Vec::with_capacity(encoded.len());
+  let mut index = 0;
+  while index < encoded.len() {
+    let decoded = (encoded[index] == b'%')
+      .then(|| encoded.get(index + 1..index + 3))
+      .flatten()
+      .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+    match decoded {
+      Some(byte) => {
+        bytes.push(byte);
+        index += 3;
+      }
+      None => {
+        bytes.push(encoded[index]);
+        index += 1;
+      }
+    }
+  }
+  let path = String::from_utf8(bytes).ok()?;
+  // `file:///C:/src` names `C:/src` on Windows.
+  if cfg!(windows) {
+    return Some(PathBuf::from(path.trim_start_matches('/')));
+  }
+  Some(PathBuf::from(path))
+}
+
+fn language_id_for(path: &Path) -> &'static str {
+  match path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .unwrap_or_default()
+  {
+    "rs" => "rust",
+    "ts" | "mts" | "cts" => "typescript",
+    "tsx" => "typescriptreact",
+    "js" | "mjs" | "cjs" => "javascript",
+    "jsx" => "javascriptreact",
+    "py" | "pyi" => "python",
+    "go" => "go",
+    "java" => "java",
+    "kt" | "kts" => "kotlin",
+    "c" | "h" => "c",
+    "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+    "cs" => "csharp",
+    "rb" => "ruby",
+    "php" => "php",
+    "swift" => "swift",
+    "lua" => "lua",
+    "json" => "json",
+    _ => "plaintext",
+  }
+}
+
+/// The UTF-16 offset LSP expects for a 1-based line and character column.
+fn utf16_character(text: &str, line: u32, column: u32) -> usize {
+  text
+    .lines()
+    .nth(line.saturating_sub(1) as usize)
+    .map(|line| {
+      line
+        .chars()
+        .take(column.saturating_sub(1) as usize)
+        .map(char::len_utf16)
+        .sum()
+    })
+    .unwrap_or(0)
+}
+
+/// `(uri, line, character)` of each target in a `Location`, `Location[]` or `LocationLink[]`.
+fn definition_locations(result: &JsonValue) -> Vec<(String, u64, u64)> {
+  let entries: Vec<&JsonValue> = match result {
+    JsonValue::Null => Vec::new(),
+    JsonValue::Array(entries) => entries.iter().collect(),
+    entry => vec![entry],
+  };
+  entries
+    .into_iter()
+    .filter_map(|entry| {
+      let (uri, range) = match entry.get("targetUri") {
+        Some(uri) => (
+          uri,
+          entry
+            .get("targetSelectionRange")
+            .or_else(|| entry.get("targetRange"))?,
+        ),
+        None => (entry.get("uri")?, entry.get("range")?),
+      };
+      let start = range.get("start")?;
+      Some((
+        uri.as_str()?.to_string(),
+        start.get("line")?.as_u64()?,
+        start.get("character")?.as_u64()?,
+      ))
+    })
+    .collect()
+}
+
+/// `path` relative to the server root when it is inside it.
+fn lsp_display_path(root: &Path, path: &Path) -> String {
+  path
+    .strip_prefix(root)
+    .unwrap_or(path)
+    .display()
+    .to_string()
+}
+
+fn format_diagnostics(display_path: &str, diagnostics: &[JsonValue]) -> String {
+  if diagnostics.is_empty() {
+    return format!("No diagnostics reported for {display_path}.");
+  }
+  diagnostics
+    .iter()
+    .map(|diagnostic| {
+      let start = &diagnostic["range"]["start"];
+      let line = start["line"].as_u64().unwrap_or(0) + 1;
+      let column = start["character"].as_u64().unwrap_or(0) + 1;
+      let severity = match diagnostic["severity"].as_u64() {
+        Some(2) => "warning",
+        Some(3) => "info",
+        Some(4) => "hint",
+        _ => "error",
+      };
+      let message = diagnostic["message"].as_str().unwrap_or_default().trim();
+      let code = match &diagnostic["code"] {
+        JsonValue::String(code) => Some(code.clone()),
+        JsonValue::Number(code) => Some(code.to_string()),
+        _ => None,
+      };
+      let label = match (diagnostic["source"].as_str(), code.as_deref()) {
+        (Some(source), Some(code)) => format!(" [{source} {code}]"),
+        (Some(label), None) | (None, Some(label)) => format!(" [{label}]"),
+        (None, None) => String::new(),
+      };
+      format!("{display_path}:{line}:{column}: {severity}: {message}{label}")
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Start a language server for `root` and keep it running, replacing one already attached there.
+#[napi]
+pub async fn lsp_attach(root: String, options: LspAttachOptions) -> napi::Result<LspServerInfo> {
+  use tokio::io::AsyncWriteExt;
+
+  let root = std::fs::canonicalize(&root)
+    .map_err(|e| napi::Error::from_reason(format!("Invalid LSP root {root}: {e}")))?;
+  let mut child = tokio::process::Command::new(&options.server)
+    .args(options.args.clone().unwrap_or_default())
+    .current_dir(&root)
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::null())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|e| {
+      napi::Error::from_reason(format!(
+        "Failed to start language server {}: {e}",
+        options.server
+      ))
+    })?;
+  let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+    return Err(napi::Error::from_reason(
+      "Language server stdio is unavailable",
+    ));
+  };
+
+  let (outgoing, mut outgoing_rx) = tokio::sync::mpsc::unbounded_channel::<JsonValue>();
+  let client = Arc::new(LspClient {
+    root: root.clone(),
+    language_id: options.language_id.clone(),
+    outgoing,
+    next_id: std::sync::atomic::AtomicI64::new(1),
+    pending: Mutex::new(HashMap::new()),
+    diagnostics: Mutex::new(HashMap::new()),
+    diagnostics_generation: std::sync::atomic::AtomicU64::new(0),
+    diagnostics_changed: tokio::sync::Notify::new(),
+    documents: tokio::sync::Mutex::new(HashMap::new()),
+    stopped: CancellationToken::new(),
+  });
+
+  tokio::spawn(async move {
+    while let Some(message) = outgoing_rx.recv().await {
+      let body = message.to_string();
+      let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+      if stdin.write_all(frame.as_bytes()).await.is_err() || stdin.flush().await.is_err() {
+        break;
+      }
+    }
+  });
+  let reader_client = Arc::clone(&client);
+  tokio::spawn(async move {
+    let mut reader = tokio::io::BufReader::new(stdout);
+    while let Ok(Some(message)) = read_lsp_message(&mut reader).await {
+      reader_client.handle_message(message);
+    }
+    reader_client.stopped.cancel();
+    if let Ok(mut pending) = reader_client.pending.lock() {
+      pending.clear();
+    }
+  });
+  let stopped = client.stopped.clone();
+  tokio::spawn(async move {
+    tokio::select! {
+      _ = child.wait() => return,
+      _ = stopped.cancelled() => {}
+    }
+    if tokio::time::timeout(LSP_EXIT_GRACE, child.wait())
+      .await
+      .is_err()
+    {
+      let _ = child.kill().await;
+    }
+  });
+
+  let root_uri = file_uri(&root);
+  let folder_name = root
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let initialized = client
+    .request(
+      "initialize",
+      json!({
+        "processId": std::process::id(),
+        "clientInfo": { "name": "codex-native" },
+        "rootUri": root_uri,
+        "rootPath": root.display().to_string(),
+        "workspaceFolders": [{ "uri": root_uri, "name": folder_name }],
+        "initializationOptions": options.initialization_options,
+        "capabilities": {
+          "textDocument": {
+            "synchronization": { "didSave": true },
+            "publishDiagnostics": {},
+            "definition": { "linkSupport": true },
+          },
+          "workspace": { "configuration": true, "workspaceFolders": true },
+        },
+      }),
+      LSP_REQUEST_TIMEOUT,
+    )
+    .await
+    .and_then(|response| client.notify("initialized", json!({})).map(|()| response));
+  let response = match initialized {
+    Ok(response) => response,
+    Err(err) => {
+      client.stopped.cancel();
+      return Err(napi::Error::from_reason(format!(
+        "Language server {} failed to initialize: {err}",
+        options.server
+      )));
+    }
+  };
+
+  let replaced = {
+    let mut clients = lsp_clients()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("lsp clients mutex poisoned: {e}")))?;
+    let replaced = clients
+      .iter()
+      .position(|existing| existing.root == root)
+      .map(|index| clients.remove(index));
+    clients.push(client);
+    replaced
+  };
+  if let Some(replaced) = replaced {
+    replaced.shutdown().await;
+  }
+  Ok(LspServerInfo {
+    root: root.display().to_string(),
+    server: options.server,
+    name: response["serverInfo"]["name"].as_str().map(str::to_string),
+  })
+}
+
+/// Shut down the language server attached at `root`. Returns false when none is attached there.
+#[napi]
+pub async fn lsp_detach(root: String) -> napi::Result<bool> {
+  let root = canonical_path(Path::new(&root));
+  let client = {
+    let mut clients = lsp_clients()
+      .lock()
+      .map_err(|e| napi::Error::from_reason(format!("lsp clients mutex poisoned: {e}")))?;
+    clients
+      .iter()
+      .position(|client| client.root == root)
+      .map(|index| clients.remove(index))
+  };
+  let Some(client) = client else {
+    return Ok(false);
+  };
+  client.shutdown().await;
+  Ok(true)
+}
+
+#[derive(serde::Deserialize)]
+struct LspToolArgs {
+  path: String,
+  #[serde(default)]
+  line: Option<u32>,
+  #[serde(default)]
+  column: Option<u32>,
+}
+
+/// Parse the call's arguments and find the server for its file.
+fn lsp_tool_target(
+  tool_name: &str,
+  invocation: &ToolInvocation,
+) -> Result<(LspToolArgs, PathBuf, Arc<LspClient>), FunctionCallError> {
+  let ToolPayload::Function { arguments } = &invocation.payload else {
+    return Err(FunctionCallError::RespondToModel(format!(
+      "{tool_name} expects function arguments"
+    )));
+  };
+  let args: LspToolArgs = serde_json::from_str(arguments).map_err(|err| {
+    FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err}"))
+  })?;
+  let path = invocation.cwd().join(&args.path);
+  let path = std::fs::canonicalize(&path).map_err(|err| {
+    FunctionCallError::RespondToModel(format!("Cannot read {}: {err}", path.display()))
+  })?;
+  let client = lsp_client_for(&path).ok_or_else(|| {
+    FunctionCallError::RespondToModel(format!(
+      "No language server is attached for {}",
+      path.display()
+    ))
+  })?;
+  Ok((args, path, client))
+}
+
+fn lsp_tool_output(text: String) -> ToolOutput {
+  ToolOutput::Function {
+    body: codex_protocol::models::FunctionCallOutputBody::Text(text),
+    success: Some(true),
+  }
+}
+
+struct GetDiagnosticsTool;
+
+#[async_trait]
+impl ToolHandler for GetDiagnosticsTool {
+  fn kind(&self) -> ToolKind {
+    ToolKind::Function
+  }
+
+  async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let (_, path, client) = lsp_tool_target(GET_DIAGNOSTICS_TOOL, &invocation)?;
+    let diagnostics = client
+      .diagnostics(&path)
+      .await
+      .map_err(FunctionCallError::RespondToModel)?;
+    Ok(lsp_tool_output(format_diagnostics(
+      &lsp_display_path(&client.root, &path),
+      &diagnostics,
+    )))
+  }
+}
+
+struct GotoDefinitionTool;
+
+#[async_trait]
+impl ToolHandler for GotoDefinitionTool {
+  fn kind(&self) -> ToolKind {
+    ToolKind::Function
+  }
+
+  async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let (args, path, client) = lsp_tool_target(GOTO_DEFINITION_TOOL, &invocation)?;
+    let (Some(line), Some(column)) = (args.line, args.column) else {
+      return Err(FunctionCallError::RespondToModel(format!(
+        "{GOTO_DEFINITION_TOOL} needs line and column"
+      )));
+    };
+    let locations = client
+      .definition(&path, line, column)
+      .await
+      .map_err(FunctionCallError::RespondToModel)?;
+    let position = format!("{}:{line}:{column}", lsp_display_path(&client.root, &path));
+    if locations.is_empty() {
+      return Ok(lsp_tool_output(format!(
+        "No definition found at {position}."
+      )));
+    }
+    let mut lines = vec![format!("Definition of the symbol at {position}:")];
+    for (uri, target_line, character) in locations {
+      let Some(target) = uri_path(&uri) else {
+        lines.push(uri);
+        continue;
+      };
+      let source = std::fs::read_to_string(&target).unwrap_or_default();
+      let text = source.lines().nth(target_line as usize).unwrap_or_default();
+      // LSP columns count UTF-16 units.
+      let mut units = 0;
+      let target_column = text
+        .chars()
+        .take_while(|ch| {
+          units += ch.len_utf16() as u64;
+          units <= character
+        })
+        .count()
+        + 1;
+      lines.push(format!(
+        "{}:{}:{target_column}: {}",
+        lsp_display_path(&client.root, &canonical_path(&target)),
+        target_line + 1,
+        text.trim()
+      ));
+    }
+    Ok(lsp_tool_output(lines.join("\n")))
+  }
+}
+
+/// `get_diagnostics` and `goto_definition`, while any language server is attached.
+fn lsp_tool_registrations() -> napi::Result<Vec<(&'static str, ExternalToolRegistration)>> {
+  let attached = lsp_clients()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("lsp clients mutex poisoned: {e}")))?
+    .iter()
+    .any(|client| !client.stopped.is_cancelled());
+  if !attached {
+    return Ok(Vec::new());
+  }
+  let path_property = json!({
+    "type": "string",
+    "description": "Path of the file, absolute or relative to the working directory."
+  });
+  let tools: [(&'static str, String, JsonValue, Arc<dyn ToolHandler>); 2] = [
+    (
+      GET_DIAGNOSTICS_TOOL,
+      "Get the errors and warnings the language server reports for a file, as it is on disk \
+       now. Use it after editing a file to check the edit compiles."
+        .to_string(),
+      json!({
+        "type": "object",
+        "properties": { "path": path_property.clone() },
+        "required": ["path"]
+      }),
+      Arc::new(GetDiagnosticsTool),
+    ),
+    (
+      GOTO_DEFINITION_TOOL,
+      "Find where the symbol at a position in a file is defined, using the language server."
+        .to_string(),
+      json!({
+        "type": "object",
+        "properties": {
+          "path": path_property,
+          "line": { "type": "number", "description": "1-based line of the symbol." },
+          "column": { "type": "number", "description": "1-based column of the symbol." }
+        },
+        "required": ["path", "line", "column"]
+      }),
+      Arc::new(GotoDefinitionTool),
+    ),
+  ];
+  tools
+    .into_iter()
+    .map(|(name, description, parameters, handler)| {
+      let spec = create_function_tool_spec_from_schema(
+        name.to_string(),
+        Some(description),
+        parameters,
+        false,
+      )
+      .map_err(|err| napi::Error::from_reason(format!("invalid tool schema: {err}")))?;
+      Ok((
+        name,
+        ExternalToolRegistration {
+          spec,
+          handler,
+          supports_parallel_tool_calls: true,
+        },
+      ))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests_lsp {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[tokio::test]
+  async fn reads_framed_messages_and_resolves_positions() {
+    let mut input: &[u8] =
+      b"Content-Length: 17\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{\"jsonrpc\":\"2.0\"}";
+    assert_eq!(
+      read_lsp_message(&mut input).await.unwrap(),
+      Some(json!({ "jsonrpc": "2.0" }))
+    );
+    assert_eq!(read_lsp_message(&mut input).await.unwrap(), None);
+
+    assert_eq!(utf16_character("let a = 1;\nfoo(\u{1F600}x)", 2, 6), 6);
+    assert_eq!(
+      definition_locations(&json!([
+        {
+          "targetUri": "file:///src/lib.rs",
+          "targetRange": { "start": { "line": 1, "character": 0 } },
+          "targetSelectionRange": { "start": { "line": 3, "character": 7 } }
+        }
+      ])),
+      vec![("file:///src/lib.rs".to_string(), 3, 7)]
+    );
+    assert_eq!(
+      definition_locations(&json!({
+        "uri": "file:///src/main.rs",
+        "range": { "start": { "line": 0, "character": 4 } }
+      })),
+      vec![("file:///src/main.rs".to_string(), 0, 4)]
+    );
+
+    #[cfg(unix)]
+    {
+      let uri = file_uri(Path::new("/work/my crate/lib.rs"));
+      assert_eq!(uri, "file:///work/my%20crate/lib.rs");
+      assert_eq!(uri_path(&uri), Some(PathBuf::from("/work/my crate/lib.rs")));
+    }
+
+    assert_eq!(
+      format_diagnostics(
+        "src/lib.rs",
+        &[json!({
+          "range": { "start": { "line": 9, "character": 4 } },
+          "severity": 1,
+          "message": "mismatched types",
+          "source": "rustc",
+          "code": "E0308"
+        })]
+      ),
+      "src/lib.rs:10:5: error: mismatched types [rustc E0308]"
+    );
+  }
+}
@@ -10,6 +10,11 @@
 //   - register_tool()
 //   - register_tool_interceptor()
 //   - register_approval_callback()
+//   - emit_tool_output_chunk()
+//   - spawn_background_tool()
+//   - get_thread_plan()
+//   - lsp_attach() / lsp_detach()
+//   - run_tests()
 //
 // ============================================================================
 
@@ -17,4 +22,8 @@ include!("exports.rs");
 include!("state.rs");
 include!("types.rs");
 include!("js_handlers.rs");
+include!("background.rs");
+include!("plan.rs");
+include!("lsp.rs");
+include!("test_runner.rs");
 include!("tests.rs");
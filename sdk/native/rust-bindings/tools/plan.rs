@@ -0,0 +1,253 @@
+// ============================================================================
+// Thread plans (getThreadPlan)
+// ============================================================================
+//
+// The model revises its plan with `update_plan`, and emitPlanUpdate() /
+// modifyPlan() schedule one for the thread's next run. Either way the result
+// becomes the thread's current plan, which `plan_updated` events carry and
+// getThreadPlan() returns. A scheduled plan no run has applied yet is
+// reported as pending, and modifyPlan() edits whichever plan is newest.
+//
+// Plans are also appended to `$CODEX_HOME/thread-plans.jsonl` (latest entry
+// per thread wins), so scheduled plans are still applied and getThreadPlan()
+// still answers after a restart, and forks start from their parent's plan.
+
+const THREAD_PLANS_FILE: &str = "thread-plans.jsonl";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredThreadPlan {
+  thread_id: String,
+  pending: bool,
+  plan: codex_exec::exec_events::PlanUpdatedEvent,
+  recorded_at: String,
+}
+
+fn thread_plans_path() -> napi::Result<PathBuf> {
+  find_codex_home()
+    .map(|home| home.join(THREAD_PLANS_FILE))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to locate thread plans: {e}")))
+}
+
+fn persist_thread_plan(
+  sidecar: &Path,
+  thread_id: &str,
+  plan: &codex_exec::exec_events::PlanUpdatedEvent,
+  pending: bool,
+) -> io::Result<()> {
+  let entry = StoredThreadPlan {
+    thread_id: thread_id.to_string(),
+    pending,
+    plan: plan.clone(),
+    recorded_at: format_rollout_timestamp(SystemTime::now()),
+  };
+  let line = serde_json::to_string(&entry).map_err(io::Error::other)?;
+  let mut file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(sidecar)?;
+  writeln!(file, "{line}")
+}
+
+/// The latest plan stored in `sidecar` for `thread_id`.
+fn stored_thread_plan(sidecar: &Path, thread_id: &str) -> Option<StoredThreadPlan> {
+  let contents = std::fs::read_to_string(sidecar).ok()?;
+  contents
+    .lines()
+    .rev()
+    .filter_map(|line| serde_json::from_str::<StoredThreadPlan>(line).ok())
+    .find(|entry| entry.thread_id == thread_id)
+}
+
+fn current_thread_plans()
+-> &'static Mutex<HashMap<String, codex_exec::exec_events::PlanUpdatedEvent>> {
+  static PLANS: OnceLock<Mutex<HashMap<String, codex_exec::exec_events::PlanUpdatedEvent>>> =
+    OnceLock::new();
+  PLANS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_thread_plan(sidecar: &Path, thread_id: &str, event: &ExecThreadEvent) {
+  let ExecThreadEvent::PlanUpdated(plan) = event else {
+    return;
+  };
+  if let Ok(mut plans) = current_thread_plans().lock() {
+    plans.insert(thread_id.to_string(), plan.clone());
+  }
+  if let Err(err) = persist_thread_plan(sidecar, thread_id, plan, false) {
+    eprintln!("codex-native: failed to persist plan for thread {thread_id}: {err}");
+  }
+}
+
+/// Schedule `args` for the thread's next run.
+fn schedule_thread_plan(
+  sidecar: &Path,
+  thread_id: String,
+  args: codex_protocol::plan_tool::UpdatePlanArgs,
+) -> napi::Result<()> {
+  persist_thread_plan(
+    sidecar,
+    &thread_id,
+    &codex_exec::exec_events::PlanUpdatedEvent::from(&args),
+    true,
+  )
+  .map_err(|e| napi::Error::from_reason(format!("Failed to persist plan update: {e}")))?;
+  pending_plan_updates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?
+    .insert(thread_id, args);
+  Ok(())
+}
+
+/// Remove and return the plan scheduled for the thread's next run, including one scheduled by an
+/// earlier process.
+fn take_pending_plan(
+  sidecar: &Path,
+  thread_id: &str,
+) -> napi::Result<Option<codex_protocol::plan_tool::UpdatePlanArgs>> {
+  let pending = pending_plan_updates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?
+    .remove(thread_id);
+  if pending.is_some() {
+    return Ok(pending);
+  }
+  let applied_here = current_thread_plans()
+    .lock()
+    .is_ok_and(|plans| plans.contains_key(thread_id));
+  Ok(
+    stored_thread_plan(sidecar, thread_id)
+      .filter(|entry| entry.pending && !applied_here)
+      .map(|entry| update_plan_args(&entry.plan)),
+  )
+}
+
+fn update_plan_args(
+  plan: &codex_exec::exec_events::PlanUpdatedEvent,
+) -> codex_protocol::plan_tool::UpdatePlanArgs {
+  use codex_exec::exec_events::PlanStepStatus;
+  use codex_protocol::plan_tool::StepStatus;
+
+  codex_protocol::plan_tool::UpdatePlanArgs {
+    explanation: plan.explanation.clone(),
+    plan: plan
+      .plan
+      .iter()
+      .map(|step| codex_protocol::plan_tool::PlanItemArg {
+        step: step.step.clone(),
+        status: match step.status {
+          PlanStepStatus::Pending => StepStatus::Pending,
+          PlanStepStatus::InProgress => StepStatus::InProgress,
+          PlanStepStatus::Completed => StepStatus::Completed,
+        },
+      })
+      .collect(),
+  }
+}
+
+/// Steps of the thread's current plan, as `update_plan` arguments.
+fn current_plan_items(
+  sidecar: &Path,
+  thread_id: &str,
+) -> Option<Vec<codex_protocol::plan_tool::PlanItemArg>> {
+  let current = current_thread_plans()
+    .lock()
+    .ok()?
+    .get(thread_id)
+    .cloned()
+    .or_else(|| stored_thread_plan(sidecar, thread_id).map(|entry| entry.plan))?;
+  Some(update_plan_args(&current).plan)
+}
+
+/// Start a fork with its parent's plan.
+fn inherit_thread_plan(sidecar: &Path, parent_id: &str, fork_id: &str) {
+  let pending = pending_plan_updates()
+    .lock()
+    .ok()
+    .and_then(|updates| updates.get(parent_id).cloned());
+  let inherited = match pending {
+    Some(args) => Some((codex_exec::exec_events::PlanUpdatedEvent::from(&args), true)),
+    None => current_thread_plans()
+      .lock()
+      .ok()
+      .and_then(|plans| plans.get(parent_id).cloned())
+      .map(|plan| (plan, false))
+      .or_else(|| stored_thread_plan(sidecar, parent_id).map(|entry| (entry.plan, entry.pending))),
+  };
+  let Some((plan, pending)) = inherited else {
+    return;
+  };
+  if pending {
+    if let Ok(mut updates) = pending_plan_updates().lock() {
+      updates.insert(fork_id.to_string(), update_plan_args(&plan));
+    }
+  } else if let Ok(mut plans) = current_thread_plans().lock() {
+    plans.insert(fork_id.to_string(), plan.clone());
+  }
+  if let Err(err) = persist_thread_plan(sidecar, fork_id, &plan, pending) {
+    eprintln!("codex-native: failed to persist plan for thread {fork_id}: {err}");
+  }
+}
+
+#[napi(object)]
+pub struct ThreadPlanStep {
+  pub step: String,
+  /// `pending`, `in_progress` or `completed`.
+  pub status: String,
+}
+
+#[napi(object)]
+pub struct ThreadPlan {
+  pub explanation: Option<String>,
+  pub plan: Vec<ThreadPlanStep>,
+  /// Scheduled with emitPlanUpdate() or modifyPlan() and not yet applied by a run.
+  pub pending: bool,
+}
+
+impl ThreadPlan {
+  fn new(plan: codex_exec::exec_events::PlanUpdatedEvent, pending: bool) -> Self {
+    use codex_exec::exec_events::PlanStepStatus;
+
+    Self {
+      explanation: plan.explanation,
+      plan: plan
+        .plan
+        .into_iter()
+        .map(|step| ThreadPlanStep {
+          step: step.step,
+          status: match step.status {
+            PlanStepStatus::Pending => "pending",
+            PlanStepStatus::InProgress => "in_progress",
+            PlanStepStatus::Completed => "completed",
+          }
+          .to_string(),
+        })
+        .collect(),
+      pending,
+    }
+  }
+}
+
+/// The thread's newest plan, or `null` when neither the model nor the SDK has set one.
+#[napi]
+pub fn get_thread_plan(thread_id: String) -> napi::Result<Option<ThreadPlan>> {
+  thread_plan_in(&thread_plans_path()?, &thread_id)
+}
+
+fn thread_plan_in(sidecar: &Path, thread_id: &str) -> napi::Result<Option<ThreadPlan>> {
+  let pending = pending_plan_updates()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("plan updates mutex poisoned: {e}")))?
+    .get(thread_id)
+    .map(codex_exec::exec_events::PlanUpdatedEvent::from);
+  if let Some(plan) = pending {
+    return Ok(Some(ThreadPlan::new(plan, true)));
+  }
+  let current = current_thread_plans()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("thread plans mutex poisoned: {e}")))?
+    .get(thread_id)
+    .cloned();
+  if let Some(plan) = current {
+    return Ok(Some(ThreadPlan::new(plan, false)));
+  }
+  Ok(stored_thread_plan(sidecar, thread_id).map(|entry| ThreadPlan::new(entry.plan, entry.pending)))
+}
@@ -24,6 +24,18 @@ fn test_tool_callbacks() -> &'static Mutex<HashMap<String, ToolTsfn>> {
   CALLBACKS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// A registered JS tool call whose handler is still running.
+struct RunningToolCall {
+  thread_id: String,
+  sender: ToolOutputSender,
+}
+
+/// Registered JS tool calls currently running, by call id.
+fn streaming_tool_calls() -> &'static Mutex<HashMap<String, RunningToolCall>> {
+  static CALLS: OnceLock<Mutex<HashMap<String, RunningToolCall>>> = OnceLock::new();
+  CALLS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 fn registered_tool_infos() -> &'static Mutex<Vec<NativeToolInfo>> {
   static TOOLS: OnceLock<Mutex<Vec<NativeToolInfo>>> = OnceLock::new();
   TOOLS.get_or_init(|| Mutex::new(Vec::new()))
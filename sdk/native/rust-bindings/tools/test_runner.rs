@@ -0,0 +1,627 @@
+// ============================================================================
+// Test runner (runTests, run_tests)
+// ============================================================================
+//
+// Verification loops otherwise have to scrape test output to learn what
+// failed. runTests() and the builtin `run_tests` tool run the project's test
+// command under the sandbox, parse cargo test, jest or pytest output into
+// per-test results, and emit them as a `test_results` event. The framework is
+// detected from the project files unless one is given, and `selector` is
+// passed to the test command to narrow the run.
+
+const RUN_TESTS_TOOL: &str = "run_tests";
+const DEFAULT_RUN_TESTS_TIMEOUT_MS: u32 = 10 * 60 * 1000;
+/// Longest failure message kept per test.
+const TEST_FAILURE_MESSAGE_LIMIT: usize = 2 * 1024;
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct RunTestsOptions {
+  /// Project directory the tests run in. Defaults to the process cwd.
+  pub cwd: Option<String>,
+  /// `cargo`, `jest` or `pytest`. Detected from the project files when unset.
+  pub framework: Option<String>,
+  /// Passed to the test command: a `cargo test` name filter, a jest test path pattern, or pytest
+  /// paths and node ids.
+  pub selector: Option<String>,
+  /// Defaults to `workspace-write`, so build outputs and caches can be written.
+  #[napi(js_name = "sandboxMode")]
+  pub sandbox_mode: Option<String>,
+  #[napi(js_name = "linuxSandboxPath")]
+  pub linux_sandbox_path: Option<String>,
+  /// Defaults to 10 minutes.
+  #[napi(js_name = "timeoutMs")]
+  pub timeout_ms: Option<u32>,
+  /// Thread whose active run also receives the results as a `test_results` event.
+  #[napi(js_name = "threadId")]
+  pub thread_id: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TestFramework {
+  Cargo,
+  Jest,
+  Pytest,
+}
+
+impl TestFramework {
+  fn parse(name: &str) -> Result<Self, String> {
+    match name {
+      "cargo" => Ok(Self::Cargo),
+      "jest" => Ok(Self::Jest),
+      "pytest" => Ok(Self::Pytest),
+      other => Err(format!(
+        "Unsupported test framework {other:?}; expected \"cargo\", \"jest\" or \"pytest\""
+      )),
+    }
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Cargo => "cargo",
+      Self::Jest => "jest",
+      Self::Pytest => "pytest",
+    }
+  }
+
+  fn detect(cwd: &Path) -> Option<Self> {
+    let mentions = |file: &str, needle: &str| {
+      std::fs::read_to_string(cwd.join(file)).is_ok_and(|contents| contents.contains(needle))
+    };
+    if cwd.join("Cargo.toml").is_file() {
+      Some(Self::Cargo)
+    } else if mentions("package.json", "jest") {
+      Some(Self::Jest)
+    } else if ["pytest.ini", "conftest.py"]
+      .iter()
+      .any(|file| cwd.join(file).is_file())
+      || ["pyproject.toml", "setup.cfg", "tox.ini"]
+        .iter()
+        .any(|file| mentions(file, "pytest"))
+    {
+      Some(Self::Pytest)
+    } else {
+      None
+    }
+  }
+
+  fn command(self, selector: Option<&str>) -> Vec<String> {
+    let base: &[&str] = match self {
+      Self::Cargo => &["cargo", "test", "--no-fail-fast"],
+      // The JSON report goes to stdout; console output and progress go to stderr.
+      Self::Jest => &["npx", "jest", "--ci", "--json"],
+      // `-rA` lists every test's outcome in the short summary.
+      Self::Pytest => &["python", "-m", "pytest", "-rA", "--color=no"],
+    };
+    let mut command: Vec<String> = base.iter().map(ToString::to_string).collect();
+    command.extend(selector.map(str::to_string));
+    command
+  }
+}
+
+fn failure_message(text: &str) -> Option<String> {
+  let text = text.trim();
+  if text.is_empty() {
+    return None;
+  }
+  if text.len() <= TEST_FAILURE_MESSAGE_LIMIT {
+    return Some(text.to_string());
+  }
+  let mut end = TEST_FAILURE_MESSAGE_LIMIT;
+  while !text.is_char_boundary(end) {
+    end -= 1;
+  }
+  Some(format!("{}\n[... truncated ...]", &text[..end]))
+}
+
+fn test_case(
+  name: &str,
+  status: codex_exec::exec_events::TestCaseStatus,
+  file: Option<String>,
+  message: Option<String>,
+) -> codex_exec::exec_events::TestCaseResult {
+  codex_exec::exec_events::TestCaseResult {
+    name: name.to_string(),
+    status,
+    file,
+    message,
+    duration_ms: None,
+  }
+}
+
+/// `test <name> ... ok|FAILED|ignored` lines, with each failure's captured output as its message.
+fn parse_cargo_test_output(output: &str) -> Vec<codex_exec::exec_events::TestCaseResult> {
+  use codex_exec::exec_events::TestCaseStatus;
+
+  let mut tests: Vec<codex_exec::exec_events::TestCaseResult> = Vec::new();
+  let mut failures: Vec<(String, String)> = Vec::new();
+  let mut failure: Option<(String, Vec<&str>)> = None;
+  let mut file: Option<String> = None;
+  for line in output.lines() {
+    let trimmed = line.trim();
+    if let Some((name, lines)) = failure.as_mut() {
+      if trimmed != "failures:" && !trimmed.starts_with("---- ") {
+        lines.push(line);
+        continue;
+      }
+      failures.push((std::mem::take(name), lines.join("\n")));
+      failure = None;
+    }
+    if let Some(name) = trimmed
+      .strip_prefix("---- ")
+      .and_then(|rest| rest.strip_suffix(" stdout ----"))
+    {
+      failure = Some((name.to_string(), Vec::new()));
+    } else if let Some(target) = trimmed.strip_prefix("Running ") {
+      // `Running unittests src/lib.rs (target/debug/deps/...)` or `Running tests/it.rs (...)`.
+      let target = target.strip_prefix("unittests ").unwrap_or(target);
+      file = target.split(" (").next().map(str::to_string);
+    } else if trimmed.starts_with("Doc-tests ") {
+      file = None;
+    } else if let Some(rest) = line.strip_prefix("test ")
+      && let Some((name, outcome)) = rest.rsplit_once(" ... ")
+    {
+      let (status, message) = match outcome {
+        "ok" => (TestCaseStatus::Passed, None),
+        outcome if outcome.starts_with("FAILED") => (TestCaseStatus::Failed, None),
+        outcome if outcome.starts_with("ignored") => (
+          TestCaseStatus::Skipped,
+          outcome.strip_prefix("ignored, ").map(str::to_string),
+        ),
+        _ => continue,
+      };
+      tests.push(test_case(name, status, file.clone(), message));
+    }
+  }
+  if let Some((name, lines)) = failure {
+    failures.push((name, lines.join("\n")));
+  }
+  for (name, text) in failures {
+    if let Some(test) = tests.iter_mut().find(|test| {
+      test.status == TestCaseStatus::Failed && test.name == name && test.message.is_none()
+    }) {
+      test.message = failure_message(&text);
+    }
+  }
+  tests
+}
+
+/// The report `jest --json` prints to stdout. Suites that fail before running any test, e.g. on
+/// a syntax error, are reported as one failed test named after the file.
+fn parse_jest_output(stdout: &str, cwd: &Path) -> Vec<codex_exec::exec_events::TestCaseResult> {
+  use codex_exec::exec_events::TestCaseStatus;
+
+  #[derive(serde::Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct Report {
+    #[serde(default)]
+    test_results: Vec<Suite>,
+  }
+  #[derive(serde::Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct Suite {
+    name: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    assertion_results: Vec<Assertion>,
+  }
+  #[derive(serde::Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct Assertion {
+    full_name: String,
+    status: String,
+    duration: Option<f64>,
+    #[serde(default)]
+    failure_messages: Vec<String>,
+  }
+
+  let Some(start) = stdout.find('{') else {
+    return Vec::new();
+  };
+  let Some(Ok(report)) = serde_json::Deserializer::from_str(&stdout[start..])
+    .into_iter::<Report>()
+    .next()
+  else {
+    return Vec::new();
+  };
+  let canonical_cwd = canonical_path(cwd);
+  let mut tests = Vec::new();
+  for suite in report.test_results {
+    let path = Path::new(&suite.name);
+    let file = path
+      .strip_prefix(cwd)
+      .or_else(|_| path.strip_prefix(&canonical_cwd))
+      .unwrap_or(path)
+      .display()
+      .to_string();
+    if suite.assertion_results.is_empty() {
+      if let Some(message) = failure_message(&suite.message) {
+        tests.push(test_case(
+          &file,
+          TestCaseStatus::Failed,
+          Some(file.clone()),
+          Some(message),
+        ));
+      }
+      continue;
+    }
+    for assertion in suite.assertion_results {
+      let status = match assertion.status.as_str() {
+        "passed" => TestCaseStatus::Passed,
+        "failed" => TestCaseStatus::Failed,
+        "pending" | "skipped" | "todo" | "disabled" => TestCaseStatus::Skipped,
+        _ => continue,
+      };
+      let mut test = test_case(
+        &assertion.full_name,
+        status,
+        Some(file.clone()),
+        failure_message(&assertion.failure_messages.join("\n")),
+      );
+      test.duration_ms = assertion.duration.map(|ms| ms.max(0.0).round() as u32);
+      tests.push(test);
+    }
+  }
+  tests
+}
+
+/// The short test summary `pytest -rA` prints: one `OUTCOME nodeid[ - message]` line per test.
+fn parse_pytest_output(output: &str) -> Vec<codex_exec::exec_events::TestCaseResult> {
+  use codex_exec::exec_events::TestCaseStatus;
+
+  let mut tests = Vec::new();
+  let mut in_summary = false;
+  for line in output.lines() {
+    if line.starts_with('=') {
+      if in_summary {
+        break;
+      }
+      in_summary = line.contains("short test summary info");
+      continue;
+    }
+    if !in_summary {
+      continue;
+    }
+    let Some((outcome, rest)) = line.split_once(' ') else {
+      continue;
+    };
+    let status = match outcome {
+      "PASSED" | "XPASS" => TestCaseStatus::Passed,
+      "FAILED" | "ERROR" => TestCaseStatus::Failed,
+      "SKIPPED" | "XFAIL" => TestCaseStatus::Skipped,
+      _ => continue,
+    };
+    // Skips are reported by location: `SKIPPED [1] tests/test_a.py:10: reason`.
+    let (name, message) = if outcome == "SKIPPED" {
+      let rest = rest.split_once("] ").map_or(rest, |(_, location)| location);
+      rest
+        .split_once(": ")
+        .map_or((rest, None), |(location, reason)| (location, Some(reason)))
+    } else {
+      rest
+        .split_once(" - ")
+        .map_or((rest, None), |(name, message)| (name, Some(message)))
+    };
+    let path = name.split("::").next().unwrap_or(name);
+    let file = match path.rsplit_once(':') {
+      Some((file, line)) if line.chars().all(|ch| ch.is_ascii_digit()) => file,
+      _ => path,
+    };
+    tests.push(test_case(
+      name,
+      status,
+      Some(file.to_string()),
+      message.and_then(failure_message),
+    ));
+  }
+  tests
+}
+
+fn test_results_event(
+  framework: TestFramework,
+  command: &[String],
+  output: &codex_core::exec::ExecToolCallOutput,
+  tests: Vec<codex_exec::exec_events::TestCaseResult>,
+) -> codex_exec::exec_events::TestResultsEvent {
+  use codex_exec::exec_events::TestCaseStatus;
+
+  let count =
+    |status: TestCaseStatus| tests.iter().filter(|test| test.status == status).count() as u32;
+  let failed = count(TestCaseStatus::Failed);
+  codex_exec::exec_events::TestResultsEvent {
+    framework: framework.name().to_string(),
+    command: command.join(" "),
+    call_id: None,
+    exit_code: output.exit_code,
+    success: output.exit_code == 0 && !output.timed_out && failed == 0,
+    passed: count(TestCaseStatus::Passed),
+    failed,
+    skipped: count(TestCaseStatus::Skipped),
+    tests,
+  }
+}
+
+/// Run the tests under `call`'s sandbox in its cwd. Returns the parsed results and the command's
+/// combined output.
+async fn run_test_command(
+  call: &codex_core::DirectToolCall,
+  framework: Option<&str>,
+  selector: Option<&str>,
+  timeout_ms: u32,
+) -> Result<(codex_exec::exec_events::TestResultsEvent, String), String> {
+  let framework = match framework {
+    Some(name) => TestFramework::parse(name)?,
+    None => TestFramework::detect(&call.cwd).ok_or_else(|| {
+      format!(
+        "No cargo, jest or pytest project found in {}; pass the framework explicitly",
+        call.cwd.display()
+      )
+    })?,
+  };
+  let command = framework.command(selector);
+  let output = codex_core::run_direct_command(
+    call,
+    command.clone(),
+    call.cwd.clone(),
+    Some(u64::from(timeout_ms)),
+  )
+  .await
+  .map_err(|err| err.to_string())?;
+  let tests = match framework {
+    TestFramework::Cargo => parse_cargo_test_output(&output.aggregated_output.text),
+    TestFramework::Jest => parse_jest_output(&output.stdout.text, &call.cwd),
+    TestFramework::Pytest => parse_pytest_output(&output.aggregated_output.text),
+  };
+  let results = test_results_event(framework, &command, &output, tests);
+  Ok((results, output.aggregated_output.text))
+}
+
+fn report_test_results(thread_id: &str, results: &codex_exec::exec_events::TestResultsEvent) {
+  let handler = active_thread_handlers()
+    .lock()
+    .ok()
+    .and_then(|handlers| handlers.get(thread_id).cloned());
+  if let Some(handler) = handler
+    && let Err(err) = dispatch_thread_event(&handler, ExecThreadEvent::TestResults(results.clone()))
+  {
+    eprintln!("Failed to report test results: {err}");
+  }
+}
+
+/// What `run_tests` returns to the model: the counts, then each failure, or the end of the output
+/// when the command failed without a failing test (e.g. a build error).
+fn format_test_results(
+  results: &codex_exec::exec_events::TestResultsEvent,
+  output: &str,
+) -> String {
+  use codex_exec::exec_events::TestCaseStatus;
+
+  let mut text = format!(
+    "{}: {} passed, {} failed, {} skipped (exit code {})",
+    results.command, results.passed, results.failed, results.skipped, results.exit_code
+  );
+  let failures: Vec<_> = results
+    .tests
+    .iter()
+    .filter(|test| test.status == TestCaseStatus::Failed)
+    .collect();
+  if !failures.is_empty() {
+    text.push_str("\n\nFailures:");
+    for test in failures {
+      text.push_str(&format!("\n- {}", test.name));
+      for line in test.message.iter().flat_map(|message| message.lines()) {
+        text.push_str(&format!("\n    {line}"));
+      }
+    }
+  } else if !results.success {
+    text.push_str(&format!("\n\n{}", tail_of_output(output)));
+  }
+  text
+}
+
+/// Run the project's tests in the sandbox and return structured per-test results.
+#[napi]
+pub async fn run_tests(options: Option<RunTestsOptions>) -> napi::Result<JsonValue> {
+  let options = options.unwrap_or_default();
+  let cwd = match options.cwd {
+    Some(cwd) => PathBuf::from(cwd),
+    None => std::env::current_dir()
+      .map_err(|err| napi::Error::from_reason(format!("failed to read current dir: {err}")))?,
+  };
+  let call = codex_core::DirectToolCall {
+    tool_name: RUN_TESTS_TOOL.to_string(),
+    arguments: String::new(),
+    cwd,
+    sandbox_policy: direct_sandbox_policy(Some(
+      options.sandbox_mode.as_deref().unwrap_or("workspace-write"),
+    ))?,
+    codex_linux_sandbox_exe: resolve_linux_sandbox_path(
+      options.linux_sandbox_path.map(PathBuf::from),
+    )?,
+  };
+  let (results, _) = run_test_command(
+    &call,
+    options.framework.as_deref(),
+    options.selector.as_deref(),
+    options.timeout_ms.unwrap_or(DEFAULT_RUN_TESTS_TIMEOUT_MS),
+  )
+  .await
+  .map_err(napi::Error::from_reason)?;
+  if let Some(thread_id) = &options.thread_id {
+    report_test_results(thread_id, &results);
+  }
+  serde_json::to_value(&results)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to serialize test results: {e}")))
+}
+
+#[derive(serde::Deserialize)]
+struct RunTestsToolArgs {
+  framework: Option<String>,
+  selector: Option<String>,
+}
+
+struct RunTestsTool;
+
+#[async_trait]
+impl ToolHandler for RunTestsTool {
+  fn kind(&self) -> ToolKind {
+    ToolKind::Function
+  }
+
+  async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+    let ToolPayload::Function { arguments } = &invocation.payload else {
+      return Err(FunctionCallError::RespondToModel(format!(
+        "{RUN_TESTS_TOOL} expects function arguments"
+      )));
+    };
+    let args: RunTestsToolArgs = serde_json::from_str(arguments).map_err(|err| {
+      FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err}"))
+    })?;
+    let call = codex_core::DirectToolCall {
+      tool_name: RUN_TESTS_TOOL.to_string(),
+      arguments: arguments.clone(),
+      cwd: invocation.cwd().to_path_buf(),
+      sandbox_policy: invocation.sandbox_policy().clone(),
+      codex_linux_sandbox_exe: invocation.codex_linux_sandbox_exe().map(Path::to_path_buf),
+    };
+    let (mut results, output) = run_test_command(
+      &call,
+      args.framework.as_deref(),
+      args.selector.as_deref(),
+      DEFAULT_RUN_TESTS_TIMEOUT_MS,
+    )
+    .await
+    .map_err(FunctionCallError::RespondToModel)?;
+    results.call_id = Some(invocation.call_id.clone());
+    report_test_results(&invocation.thread_id(), &results);
+    Ok(ToolOutput::Function {
+      body: codex_protocol::models::FunctionCallOutputBody::Text(format_test_results(
+        &results, &output,
+      )),
+      success: Some(results.success),
+    })
+  }
+}
+
+fn run_tests_registration() -> napi::Result<ExternalToolRegistration> {
+  let spec = create_function_tool_spec_from_schema(
+    RUN_TESTS_TOOL.to_string(),
+    Some(
+      "Run the project's tests (cargo test, jest or pytest) and get each failing test with its \
+       message. Prefer it over running the test command through the shell."
+        .to_string(),
+    ),
+    json!({
+      "type": "object",
+      "properties": {
+        "framework": {
+          "type": "string",
+          "enum": ["cargo", "jest", "pytest"],
+          "description": "Test framework. Detected from the project files when omitted."
+        },
+        "selector": {
+          "type": "string",
+          "description": "Narrows the run: a cargo test name filter, a jest test path pattern, or pytest paths and node ids."
+        }
+      }
+    }),
+    false,
+  )
+  .map_err(|err| napi::Error::from_reason(format!("invalid tool schema: {err}")))?;
+  Ok(ExternalToolRegistration {
+    spec,
+    handler: Arc::new(RunTestsTool),
+    supports_parallel_tool_calls: false,
+  })
+}
+
+#[cfg(test)]
+mod tests_test_runner {
+  use super::*;
+  use codex_exec::exec_events::TestCaseStatus;
+  use pretty_assertions::assert_eq;
+
+  fn outcomes(tests: &[codex_exec::exec_events::TestCaseResult]) -> Vec<(&str, TestCaseStatus)> {
+    tests
+      .iter()
+      .map(|test| (test.name.as_str(), test.status))
+      .collect()
+  }
+
+  #[test]
+  fn parses_cargo_jest_and_pytest_results() {
+    let cargo = parse_cargo_test_output(concat!(
+      "     Running unittests src/lib.rs (target/debug/deps/demo-1a2b)\n",
+      "\n",
+      "running 3 tests\n",
+      "test math::adds ... ok\n",
+      "test math::divides ... FAILED\n",
+      "test slow ... ignored, needs network\n",
+      "\n",
+      "failures:\n",
+      "\n",
+      "---- math::divides stdout ----\n",
+      "thread 'math::divides' panicked at src/lib.rs:9:5:\n",
+      "attempt to divide by zero\n",
+      "\n",
+      "\n",
+      "failures:\n",
+      "    math::divides\n",
+      "\n",
+      "test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out\n",
+    ));
+    assert_eq!(
+      outcomes(&cargo),
+      vec![
+        ("math::adds", TestCaseStatus::Passed),
+        ("math::divides", TestCaseStatus::Failed),
+        ("slow", TestCaseStatus::Skipped),
+      ]
+    );
+    assert_eq!(cargo[1].file.as_deref(), Some("src/lib.rs"));
+    assert_eq!(
+      cargo[1].message.as_deref(),
+      Some("thread 'math::divides' panicked at src/lib.rs:9:5:\nattempt to divide by zero")
+    );
+    assert_eq!(cargo[2].message.as_deref(), Some("needs network"));
+
+    let jest = parse_jest_output(
+      r#"{"numFailedTests":1,"testResults":[{"name":"/repo/src/sum.test.js","message":"","assertionResults":[{"fullName":"sum adds","status":"passed","duration":3,"failureMessages":[]},{"fullName":"sum overflows","status":"failed","duration":1.4,"failureMessages":["Expected: 3\nReceived: 4"]}]},{"name":"/repo/src/broken.test.js","message":"SyntaxError: Unexpected token","assertionResults":[]}]}"#,
+      Path::new("/repo"),
+    );
+    assert_eq!(
+      outcomes(&jest),
+      vec![
+        ("sum adds", TestCaseStatus::Passed),
+        ("sum overflows", TestCaseStatus::Failed),
+        ("src/broken.test.js", TestCaseStatus::Failed),
+      ]
+    );
+    assert_eq!(jest[1].file.as_deref(), Some("src/sum.test.js"));
+    assert_eq!(jest[1].duration_ms, Some(1));
+    assert_eq!(jest[1].message.as_deref(), Some("Expected: 3\nReceived: 4"));
+
+    let pytest = parse_pytest_output(concat!(
+      "tests/test_math.py .Fs\n",
+      "=========================== short test summary info ============================\n",
+      "PASSED tests/test_math.py::test_add\n",
+      "FAILED tests/test_math.py::test_div - ZeroDivisionError: division by zero\n",
+      "SKIPPED [1] tests/test_math.py:12: slow\n",
+      "==================== 1 failed, 1 passed, 1 skipped in 0.02s ====================\n",
+    ));
+    assert_eq!(
+      outcomes(&pytest),
+      vec![
+        ("tests/test_math.py::test_add", TestCaseStatus::Passed),
+        ("tests/test_math.py::test_div", TestCaseStatus::Failed),
+        ("tests/test_math.py:12", TestCaseStatus::Skipped),
+      ]
+    );
+    assert_eq!(pytest[2].file.as_deref(), Some("tests/test_math.py"));
+    assert_eq!(
+      pytest[1].message.as_deref(),
+      Some("ZeroDivisionError: division by zero")
+    );
+  }
+}
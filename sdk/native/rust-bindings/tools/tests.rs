@@ -15,6 +15,9 @@ mod tests {
         parameters: Some(json!({ "type": "object" })),
         strict: Some(true),
         supports_parallel: Some(false),
+        max_concurrent: None,
+        min_interval_ms: None,
+        cache_ttl_seconds: None,
       });
     }
 
@@ -72,6 +75,9 @@ mod tests {
         parameters: None,
         strict: None,
         supports_parallel: Some(true),
+        max_concurrent: None,
+        min_interval_ms: None,
+        cache_ttl_seconds: None,
       });
     }
 
@@ -104,4 +110,157 @@ mod tests {
     let messages = received.lock().unwrap();
     assert_eq!(messages.as_slice(), &["LSP diagnostics ready"]);
   }
+
+  #[test]
+  fn set_thread_status_streams_clamped_status_to_registered_handler() {
+    let thread_id = "status-thread";
+    let received: Arc<Mutex<Vec<codex_exec::exec_events::ThreadStatusEvent>>> =
+      Arc::new(Mutex::new(Vec::new()));
+    let collector = Arc::clone(&received);
+    let handler: ThreadEventHandler = Arc::new(Mutex::new(Box::new(move |event| {
+      if let ExecThreadEvent::ThreadStatus(status) = event {
+        collector.lock().unwrap().push(status);
+      }
+    })));
+    register_thread_handler(thread_id, &handler);
+
+    set_thread_status(
+      thread_id.to_string(),
+      Some(JsThreadStatus {
+        text: Some("Indexing workspace".to_string()),
+        progress_pct: Some(140.0),
+      }),
+    )
+    .expect("status should dispatch");
+    set_thread_status(thread_id.to_string(), None).expect("clearing should dispatch");
+    let invalid = set_thread_status(
+      thread_id.to_string(),
+      Some(JsThreadStatus {
+        text: None,
+        progress_pct: Some(f64::NAN),
+      }),
+    );
+
+    unregister_thread_handler(thread_id);
+
+    assert!(invalid.is_err());
+    assert_eq!(
+      received.lock().unwrap().as_slice(),
+      &[
+        codex_exec::exec_events::ThreadStatusEvent {
+          text: Some("Indexing workspace".to_string()),
+          progress_pct: Some(100.0),
+        },
+        codex_exec::exec_events::ThreadStatusEvent::default(),
+      ]
+    );
+    assert!(set_thread_status("no-such-thread".to_string(), None).is_err());
+  }
+
+  #[test]
+  fn thread_plan_prefers_pending_updates_and_modify_builds_on_current_plan() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidecar = dir.path().join(THREAD_PLANS_FILE);
+    let thread_id = "plan-thread";
+    assert!(thread_plan_in(&sidecar, thread_id).unwrap().is_none());
+
+    record_thread_plan(
+      &sidecar,
+      thread_id,
+      &ExecThreadEvent::PlanUpdated(codex_exec::exec_events::PlanUpdatedEvent {
+        explanation: Some("model plan".to_string()),
+        plan: vec![codex_exec::exec_events::PlanStep {
+          step: "write tests".to_string(),
+          status: codex_exec::exec_events::PlanStepStatus::InProgress,
+        }],
+      }),
+    );
+    let current = thread_plan_in(&sidecar, thread_id).unwrap().unwrap();
+    assert!(!current.pending);
+    assert_eq!(current.explanation.as_deref(), Some("model plan"));
+    assert_eq!(current.plan[0].status, "in_progress");
+
+    modify_plan_in(
+      &sidecar,
+      JsModifyPlanRequest {
+        thread_id: thread_id.to_string(),
+        operations: vec![JsPlanOperation {
+          type_: "add".to_string(),
+          item: Some(JsPlanItem {
+            step: "ship it".to_string(),
+            status: None,
+          }),
+          index: None,
+          updates: None,
+          new_order: None,
+        }],
+      },
+    )
+    .unwrap();
+    let pending = thread_plan_in(&sidecar, thread_id).unwrap().unwrap();
+    pending_plan_updates().lock().unwrap().remove(thread_id);
+    current_thread_plans().lock().unwrap().remove(thread_id);
+
+    assert!(pending.pending);
+    let steps: Vec<(&str, &str)> = pending
+      .plan
+      .iter()
+      .map(|step| (step.step.as_str(), step.status.as_str()))
+      .collect();
+    assert_eq!(
+      steps,
+      vec![("write tests", "in_progress"), ("ship it", "pending")]
+    );
+  }
+
+  #[test]
+  fn thread_plans_survive_a_restart_and_carry_over_to_forks() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidecar = dir.path().join(THREAD_PLANS_FILE);
+    let thread_id = "persisted-plan-thread";
+    emit_plan_update_in(
+      &sidecar,
+      JsEmitPlanUpdateRequest {
+        thread_id: thread_id.to_string(),
+        explanation: Some("scheduled".to_string()),
+        plan: vec![JsPlanItem {
+          step: "migrate".to_string(),
+          status: Some("pending".to_string()),
+        }],
+      },
+    )
+    .unwrap();
+    inherit_thread_plan(&sidecar, thread_id, "persisted-plan-fork");
+
+    // A new process only has the sidecar.
+    for id in [thread_id, "persisted-plan-fork"] {
+      pending_plan_updates().lock().unwrap().remove(id);
+      current_thread_plans().lock().unwrap().remove(id);
+    }
+    let restored = thread_plan_in(&sidecar, thread_id).unwrap().unwrap();
+    assert!(restored.pending);
+    assert_eq!(restored.explanation.as_deref(), Some("scheduled"));
+    let fork = thread_plan_in(&sidecar, "persisted-plan-fork")
+      .unwrap()
+      .unwrap();
+    assert!(fork.pending);
+
+    let applied = take_pending_plan(&sidecar, thread_id).unwrap().unwrap();
+    assert_eq!(applied.plan[0].step, "migrate");
+    record_thread_plan(
+      &sidecar,
+      thread_id,
+      &ExecThreadEvent::PlanUpdated(codex_exec::exec_events::PlanUpdatedEvent::from(
+        &applied,
+      )),
+    );
+    current_thread_plans().lock().unwrap().remove(thread_id);
+    assert!(take_pending_plan(&sidecar, thread_id).unwrap().is_none());
+    assert!(
+      !thread_plan_in(&sidecar, thread_id)
+        .unwrap()
+        .unwrap()
+        .pending
+    );
+  }
 }
@@ -1,12 +1,54 @@
 use napi::Error;
 
 pub(crate) fn encode_json_value_to_toon(value: &JsonValue) -> Option<String> {
-  toon_rust::encode(value, None).ok()
+  codex_utils_tokenizer::toon::encode(value).ok()
 }
 
 #[napi]
 pub fn toon_encode(value: JsonValue) -> napi::Result<String> {
-  toon_rust::encode(&value, None).map_err(|err| Error::from_reason(format!(
-    "Failed to encode value to TOON: {err}",
-  )))
+  codex_utils_tokenizer::toon::encode(&value).map_err(|err| Error::from_reason(err.to_string()))
+}
+
+#[napi(object)]
+pub struct DecodedToon {
+  pub value: JsonValue,
+  /// JSON paths (e.g. `$.users[2].id`) of values JavaScript can't hold exactly, such as
+  /// integers past `Number.MAX_SAFE_INTEGER`.
+  #[napi(js_name = "lossyFields")]
+  pub lossy_fields: Vec<String>,
+}
+
+#[napi]
+pub fn toon_decode(text: String) -> napi::Result<DecodedToon> {
+  let decoded =
+    codex_utils_tokenizer::toon::decode(&text).map_err(|err| Error::from_reason(err.to_string()))?;
+  Ok(DecodedToon {
+    value: decoded.value,
+    lossy_fields: decoded.lossy_fields,
+  })
+}
+
+#[cfg(test)]
+mod tests_toon {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn decoding_round_trips_encoded_values_and_flags_unsafe_integers() {
+    let value = json!({
+      "name": "reverie",
+      "tags": ["a", "b"],
+      "users": [
+        { "id": 1, "active": true },
+        { "id": 2, "active": false }
+      ],
+      "nested": { "ratio": 0.25 }
+    });
+    let decoded = toon_decode(toon_encode(value.clone()).unwrap()).unwrap();
+    assert_eq!(decoded.value, value);
+    assert_eq!(decoded.lossy_fields, Vec::<String>::new());
+
+    let big = toon_decode("nested:\n  big: 9007199254740993".to_string()).unwrap();
+    assert_eq!(big.lossy_fields, vec!["$.nested.big".to_string()]);
+  }
 }
@@ -10,6 +10,7 @@
 // Key exports:
 //   - run_tui(): Launch full-screen interactive TUI
 //   - tui_test_run(): Headless TUI rendering for testing
+//   - TuiTestSession: Interactive headless driver (see tui_test_session.rs)
 //
 // ============================================================================
 
@@ -60,8 +61,6 @@ pub struct TuiRequest {
 #[allow(dead_code)]
 struct InternalTuiRequest {
   cli: TuiCli,
-  base_url: Option<String>,
-  api_key: Option<String>,
   linux_sandbox_path: Option<PathBuf>,
   reasoning_effort: Option<ReasoningEffort>,
   reasoning_summary: Option<ReasoningSummary>,
@@ -114,12 +113,13 @@ impl TuiRequest {
       config_overrides: CliConfigOverrides {
         raw_overrides: self.config_overrides.unwrap_or_default(),
       },
+      tool_layers: ExternalToolLayers::default(),
+      api_key: self.api_key,
+      openai_base_url: self.base_url,
     };
 
     Ok(InternalTuiRequest {
       cli,
-      base_url: self.base_url,
-      api_key: self.api_key,
       linux_sandbox_path: self.linux_sandbox_path.map(PathBuf::from),
       reasoning_effort,
       reasoning_summary,
@@ -326,8 +326,6 @@ fn run_tui_sync(
   ensure_apply_patch_aliases()?;
   let InternalTuiRequest {
     mut cli,
-    base_url,
-    api_key,
     linux_sandbox_path,
     reasoning_effort,
     reasoning_summary,
@@ -335,15 +333,7 @@ fn run_tui_sync(
 
   apply_reasoning_overrides(&mut cli, reasoning_effort, reasoning_summary);
 
-  let pending_tools = {
-    let guard = registered_native_tools()
-      .lock()
-      .map_err(|e| napi::Error::from_reason(format!("tools mutex poisoned: {e}")))?;
-    guard.clone()
-  };
-  set_pending_external_tools(pending_tools);
-
-  let pending_interceptors = {
+  let interceptors = {
     let guard = registered_native_interceptors()
       .lock()
       .map_err(|e| napi::Error::from_reason(format!("interceptors mutex poisoned: {e}")))?;
@@ -355,36 +345,15 @@ fn run_tui_sync(
       })
       .collect::<Vec<_>>()
   };
-  set_pending_external_interceptors(pending_interceptors);
-
-  let mut env_pairs: Vec<(&'static str, Option<String>, bool)> = Vec::new();
-  if std::env::var(ORIGINATOR_ENV).is_err() {
-    env_pairs.push((ORIGINATOR_ENV, Some(NATIVE_ORIGINATOR.to_string()), true));
-  }
-  if let Some(base_url) = base_url {
-    env_pairs.push(("OPENAI_BASE_URL", Some(base_url), true));
-  }
-  if let Some(api_key) = api_key {
-    env_pairs.push(("CODEX_API_KEY", Some(api_key), true));
-  }
-
-  let linux_sandbox_path = if let Some(path) = linux_sandbox_path {
-    Some(path)
-  } else if let Ok(path) = std::env::var("CODEX_LINUX_SANDBOX_EXE") {
-    Some(PathBuf::from(path))
-  } else {
-    default_linux_sandbox_path()?
-  };
-
-  if let Some(path) = linux_sandbox_path.as_ref() {
-    env_pairs.push((
-      "CODEX_LINUX_SANDBOX_EXE",
-      Some(path.to_string_lossy().to_string()),
-      false,
-    ));
+  cli.tool_layers = ExecLayers {
+    external_tools: run_external_tools()?,
+    interceptors,
+    ..Default::default()
   }
+  .into_tool_layers();
 
-  let _env_guard = EnvOverrides::apply(env_pairs);
+  ensure_native_originator();
+  let linux_sandbox_path = resolve_linux_sandbox_path(linux_sandbox_path)?;
 
   let runtime = tokio::runtime::Runtime::new()
     .map_err(|e| napi::Error::from_reason(format!("Failed to create runtime: {e}")))?;
@@ -5,11 +5,11 @@
 // ============================================================================
 
 // Note: This file is included via include!() in lib.rs, so it shares the same namespace
-// and doesn't need its own imports
+// and doesn't need its own imports (crossterm key types come from tui_test_session.rs)
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color as RataColor, Style};
-use ratatui::widgets::{Block, Borders, Paragraph, List, ListItem, Widget};
+use ratatui::widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Sparkline, Widget};
 use ratatui::buffer::Buffer as RatatuiBuffer;
 
 // Component Types
@@ -48,12 +48,11 @@ pub struct Dimensions {
 
 #[allow(dead_code)]
 trait Component: Send + Sync {
-  fn render(&self, area: Rect, buf: &mut RatatuiBuffer);
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool);
   fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()>;
   fn get_id(&self) -> String;
 }
 
-#[allow(dead_code)]
 #[derive(Clone, Debug)]
 enum ComponentEvent {
   KeyPress(String), // Changed from KeyCode to String for simplicity
@@ -62,78 +61,654 @@ enum ComponentEvent {
   Data(serde_json::Value),
 }
 
+fn component_border_style(focused: bool) -> Style {
+  if focused {
+    Style::default().fg(RataColor::Yellow)
+  } else {
+    Style::default()
+  }
+}
+
 // TUI Application
 // ============================================================================
+//
+// `run()` takes over the terminal on a dedicated thread: crossterm input is
+// polled with a tick timeout, key presses go to the focused component (Tab /
+// Shift+Tab move focus), resizes reflow every component, and the screen is
+// redrawn after each event and tick so updates made from JS through shared
+// AgentView/StatusBoard handles show up without an explicit render call.
+// Components paint in ascending z-index and clear their area first, so higher
+// layers cover lower ones. `stop()` ends the loop and restores the terminal.
+
+const DEFAULT_TUI_APP_TICK_MS: u32 = 250;
+const DEFAULT_TUI_APP_WIDTH: u16 = 80;
+const DEFAULT_TUI_APP_HEIGHT: u16 = 24;
+
+#[napi(object)]
+pub struct TuiAppRunOptions {
+  /// Interval between `tick` events and redraws (default 250ms).
+  #[napi(js_name = "tickRateMs")]
+  pub tick_rate_ms: Option<u32>,
+  /// End the loop on Ctrl+C (default true).
+  #[napi(js_name = "exitOnCtrlC")]
+  pub exit_on_ctrl_c: Option<bool>,
+  /// Draw on the alternate screen (default true).
+  #[napi(js_name = "altScreen")]
+  pub alt_screen: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuiAppEvent {
+  #[napi(
+    js_name = "type",
+    ts_type = "\"key\" | \"paste\" | \"resize\" | \"tick\" | \"focus\" | \"stop\""
+  )]
+  pub event_type: String,
+  /// Key name such as "a", "enter", "ctrl+c" or "shift+up".
+  pub key: Option<String>,
+  /// Pasted text.
+  pub text: Option<String>,
+  /// Component that received the key or gained focus.
+  #[napi(js_name = "componentId")]
+  pub component_id: Option<String>,
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+}
+
+impl TuiAppEvent {
+  fn new(event_type: &str) -> Self {
+    Self {
+      event_type: event_type.to_string(),
+      key: None,
+      text: None,
+      component_id: None,
+      width: None,
+      height: None,
+    }
+  }
+}
 
 #[napi]
 pub struct TuiApp {
   inner: Arc<Mutex<TuiAppInner>>,
+  event_loop: Option<TuiAppLoop>,
+}
+
+struct TuiAppLoop {
+  stop: Arc<std::sync::atomic::AtomicBool>,
+  join: JoinHandle<napi::Result<()>>,
 }
 
-#[allow(dead_code)]
 struct TuiAppInner {
-  components: HashMap<String, Box<dyn Component>>,
-  layout: Option<Box<dyn Component>>,
-  terminal: Option<ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>>,
+  title: Option<String>,
+  width: u16,
+  height: u16,
+  /// Mounted components in insertion order; paint order is by `z_index`, then insertion.
+  components: Vec<MountedComponent>,
+  focused: Option<String>,
+  /// Screen layout set with `setLayout`; components it doesn't place are hidden.
+  layout: Option<LayoutNode>,
 }
 
-#[allow(dead_code)]
-enum AppEvent {
-  AddComponent(String, Box<dyn Component>),
-  RemoveComponent(String),
-  UpdateComponent(String, serde_json::Value),
-  Quit,
+struct MountedComponent {
+  id: String,
+  component: Box<dyn Component>,
+  z_index: i32,
+  /// Explicit placement; `None` follows the layout, or fills the screen without one.
+  area: Option<Rect>,
+}
+
+impl TuiAppInner {
+  fn mount(&mut self, id: String, component: Box<dyn Component>) {
+    match self.components.iter_mut().find(|mounted| mounted.id == id) {
+      Some(mounted) => mounted.component = component,
+      None => self.components.push(MountedComponent {
+        id: id.clone(),
+        component,
+        z_index: 0,
+        area: None,
+      }),
+    }
+    if self.focused.is_none() {
+      self.focused = Some(id);
+    }
+  }
+
+  fn component_mut(&mut self, id: &str) -> napi::Result<&mut MountedComponent> {
+    self
+      .components
+      .iter_mut()
+      .find(|mounted| mounted.id == id)
+      .ok_or_else(|| napi::Error::from_reason(format!("Unknown component: {id}")))
+  }
+
+  /// Mounted components in paint order (lowest z-index first).
+  fn z_order(&self) -> Vec<&MountedComponent> {
+    let mut ordered: Vec<&MountedComponent> = self.components.iter().collect();
+    ordered.sort_by_key(|mounted| mounted.z_index);
+    ordered
+  }
+
+  fn resolve_layout(&self, area: Rect) -> ResolvedLayout {
+    let mut resolved = ResolvedLayout::default();
+    if let Some(layout) = &self.layout {
+      layout.resolve(area, &mut resolved);
+    }
+    resolved
+  }
+
+  /// Visible components and their screen rectangles, in paint order.
+  fn placements<'a>(&'a self, area: Rect, resolved: &ResolvedLayout) -> Vec<(&'a MountedComponent, Rect)> {
+    self
+      .z_order()
+      .into_iter()
+      .filter_map(|mounted| {
+        let placed = match (mounted.area, &self.layout) {
+          (Some(explicit), _) => explicit.intersection(area),
+          (None, Some(_)) => *resolved.areas.get(&mounted.id)?,
+          (None, None) => area,
+        };
+        (!placed.is_empty()).then_some((mounted, placed))
+      })
+      .collect()
+  }
+
+  /// Move focus to the next (or previous) visible component in paint order, wrapping around.
+  fn cycle_focus(&mut self, forward: bool) -> Option<String> {
+    let screen = Rect::new(0, 0, self.width, self.height);
+    let resolved = self.resolve_layout(screen);
+    let ids: Vec<String> = self
+      .placements(screen, &resolved)
+      .iter()
+      .map(|(mounted, _)| mounted.id.clone())
+      .collect();
+    if ids.is_empty() {
+      return None;
+    }
+    let current = self
+      .focused
+      .as_ref()
+      .and_then(|focused| ids.iter().position(|id| id == focused));
+    let next = match (current, forward) {
+      (Some(index), true) => (index + 1) % ids.len(),
+      (Some(index), false) => (index + ids.len() - 1) % ids.len(),
+      (None, true) => 0,
+      (None, false) => ids.len() - 1,
+    };
+    self.focused = Some(ids[next].clone());
+    self.focused.clone()
+  }
+
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer) {
+    let resolved = self.resolve_layout(area);
+    for (bar, titles, active) in &resolved.tab_bars {
+      let tabs = ratatui::widgets::Tabs::new(titles.iter().map(String::as_str))
+        .select(*active)
+        .highlight_style(Style::default().fg(RataColor::Yellow));
+      Widget::render(tabs, *bar, buf);
+    }
+    for (mounted, target) in self.placements(area, &resolved) {
+      Widget::render(Clear, target, buf);
+      let focused = self.focused.as_deref() == Some(mounted.id.as_str());
+      mounted.component.render(target, buf, focused);
+    }
+  }
+
+  /// Route a key press, returning the events to report and whether the loop should exit.
+  fn handle_key(&mut self, key: KeyEvent, exit_on_ctrl_c: bool) -> napi::Result<(Vec<TuiAppEvent>, bool)> {
+    if exit_on_ctrl_c && key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+      return Ok((Vec::new(), true));
+    }
+    if matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+      let forward = key.code == KeyCode::Tab && !key.modifiers.contains(KeyModifiers::SHIFT);
+      let mut event = TuiAppEvent::new("focus");
+      event.component_id = self.cycle_focus(forward);
+      return Ok((vec![event], false));
+    }
+    let name = tui_key_name(&key);
+    let mut event = TuiAppEvent::new("key");
+    event.key = Some(name.clone());
+    if let Some(focused) = self.focused.clone() {
+      self
+        .component_mut(&focused)?
+        .component
+        .handle_event(ComponentEvent::KeyPress(name))?;
+      event.component_id = Some(focused);
+    }
+    Ok((vec![event], false))
+  }
+
+  /// Keep focus on a visible component after the layout changes.
+  fn focus_visible(&mut self) {
+    let screen = Rect::new(0, 0, self.width, self.height);
+    let resolved = self.resolve_layout(screen);
+    let visible = self.focused.as_ref().is_some_and(|focused| {
+      self
+        .placements(screen, &resolved)
+        .iter()
+        .any(|(mounted, _)| &mounted.id == focused)
+    });
+    if !visible {
+      self.focused = None;
+      self.cycle_focus(true);
+    }
+  }
+
+  fn handle_resize(&mut self, width: u16, height: u16) -> napi::Result<TuiAppEvent> {
+    self.width = width;
+    self.height = height;
+    for mounted in &mut self.components {
+      mounted.component.handle_event(ComponentEvent::Resize(width, height))?;
+    }
+    let mut event = TuiAppEvent::new("resize");
+    event.width = Some(u32::from(width));
+    event.height = Some(u32::from(height));
+    Ok(event)
+  }
+}
+
+/// Stable, lowercase name for a key press, e.g. "ctrl+s", "shift+up" or "f5".
+fn tui_key_name(key: &KeyEvent) -> String {
+  let code = match key.code {
+    KeyCode::Char(' ') => "space".to_string(),
+    KeyCode::Char(c) => c.to_string(),
+    KeyCode::Enter => "enter".to_string(),
+    KeyCode::Tab => "tab".to_string(),
+    KeyCode::BackTab => "backtab".to_string(),
+    KeyCode::Backspace => "backspace".to_string(),
+    KeyCode::Esc => "escape".to_string(),
+    KeyCode::Up => "up".to_string(),
+    KeyCode::Down => "down".to_string(),
+    KeyCode::Left => "left".to_string(),
+    KeyCode::Right => "right".to_string(),
+    KeyCode::Home => "home".to_string(),
+    KeyCode::End => "end".to_string(),
+    KeyCode::PageUp => "pageup".to_string(),
+    KeyCode::PageDown => "pagedown".to_string(),
+    KeyCode::Delete => "delete".to_string(),
+    KeyCode::Insert => "insert".to_string(),
+    KeyCode::F(n) => format!("f{n}"),
+    KeyCode::Null
+    | KeyCode::CapsLock
+    | KeyCode::ScrollLock
+    | KeyCode::NumLock
+    | KeyCode::PrintScreen
+    | KeyCode::Pause
+    | KeyCode::Menu
+    | KeyCode::KeypadBegin
+    | KeyCode::Media(_)
+    | KeyCode::Modifier(_) => "unknown".to_string(),
+  };
+  let mut name = String::new();
+  if key.modifiers.contains(KeyModifiers::CONTROL) {
+    name.push_str("ctrl+");
+  }
+  if key.modifiers.contains(KeyModifiers::ALT) {
+    name.push_str("alt+");
+  }
+  // Shifted characters already arrive uppercased.
+  if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
+    name.push_str("shift+");
+  }
+  name.push_str(&code);
+  name
+}
+
+fn emit_tui_app_event(on_event: Option<&ThreadsafeFunction<TuiAppEvent>>, event: TuiAppEvent) {
+  if let Some(on_event) = on_event {
+    on_event.call(Ok(event), ThreadsafeFunctionCallMode::NonBlocking);
+  }
+}
+
+fn tui_app_lock_error<T>(e: std::sync::PoisonError<T>) -> napi::Error {
+  napi::Error::from_reason(format!("TUI app mutex poisoned: {e}"))
+}
+
+fn run_tui_app_loop(
+  inner: &Mutex<TuiAppInner>,
+  stop: &std::sync::atomic::AtomicBool,
+  tick_rate: std::time::Duration,
+  exit_on_ctrl_c: bool,
+  on_event: Option<&ThreadsafeFunction<TuiAppEvent>>,
+) -> napi::Result<()> {
+  use crossterm::event::Event;
+  use crossterm::event::KeyEventKind;
+
+  let io_error = |e: std::io::Error| napi::Error::from_reason(format!("TUI app terminal error: {e}"));
+  let mut terminal = ratatui::Terminal::new(ratatui::backend::CrosstermBackend::new(std::io::stdout()))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to create terminal: {e}")))?;
+  terminal.clear().map_err(io_error)?;
+  let mut last_tick = std::time::Instant::now();
+  while !stop.load(std::sync::atomic::Ordering::SeqCst) {
+    terminal
+      .draw(|frame| {
+        if let Ok(inner) = inner.lock() {
+          inner.render(frame.area(), frame.buffer_mut());
+        }
+      })
+      .map_err(io_error)?;
+
+    let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+    if crossterm::event::poll(timeout).map_err(io_error)? {
+      match crossterm::event::read().map_err(io_error)? {
+        Event::Key(key) if key.kind != KeyEventKind::Release => {
+          let (events, exit) = inner.lock().map_err(tui_app_lock_error)?.handle_key(key, exit_on_ctrl_c)?;
+          for event in events {
+            emit_tui_app_event(on_event, event);
+          }
+          if exit {
+            break;
+          }
+        }
+        Event::Key(_) => {}
+        Event::Resize(width, height) => {
+          terminal.autoresize().map_err(io_error)?;
+          let event = inner.lock().map_err(tui_app_lock_error)?.handle_resize(width, height)?;
+          emit_tui_app_event(on_event, event);
+        }
+        Event::Paste(text) => {
+          let mut inner = inner.lock().map_err(tui_app_lock_error)?;
+          let mut event = TuiAppEvent::new("paste");
+          if let Some(focused) = inner.focused.clone() {
+            inner
+              .component_mut(&focused)?
+              .component
+              .handle_event(ComponentEvent::Message(text.clone()))?;
+            event.component_id = Some(focused);
+          }
+          event.text = Some(text);
+          emit_tui_app_event(on_event, event);
+        }
+        Event::FocusGained | Event::FocusLost | Event::Mouse(_) => {}
+      }
+    }
+    if last_tick.elapsed() >= tick_rate {
+      last_tick = std::time::Instant::now();
+      emit_tui_app_event(on_event, TuiAppEvent::new("tick"));
+    }
+  }
+  Ok(())
+}
+
+fn enter_tui_app_screen(title: Option<&str>, alt_screen: bool) -> napi::Result<()> {
+  let io_error = |e: std::io::Error| napi::Error::from_reason(format!("Failed to prepare terminal: {e}"));
+  crossterm::terminal::enable_raw_mode().map_err(io_error)?;
+  let mut stdout = std::io::stdout();
+  if alt_screen {
+    crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen).map_err(io_error)?;
+  }
+  crossterm::execute!(stdout, crossterm::event::EnableBracketedPaste, crossterm::cursor::Hide)
+    .map_err(io_error)?;
+  if let Some(title) = title {
+    crossterm::execute!(stdout, crossterm::terminal::SetTitle(title)).map_err(io_error)?;
+  }
+  Ok(())
+}
+
+fn leave_tui_app_screen(alt_screen: bool) {
+  let mut stdout = std::io::stdout();
+  let _ = crossterm::execute!(stdout, crossterm::event::DisableBracketedPaste, crossterm::cursor::Show);
+  if alt_screen {
+    let _ = crossterm::execute!(stdout, crossterm::terminal::LeaveAlternateScreen);
+  }
+  let _ = crossterm::terminal::disable_raw_mode();
+}
+
+impl TuiApp {
+  fn lock_inner(&self) -> napi::Result<std::sync::MutexGuard<'_, TuiAppInner>> {
+    self.inner.lock().map_err(tui_app_lock_error)
+  }
+
+  fn stop_loop(&mut self) -> napi::Result<()> {
+    let Some(event_loop) = self.event_loop.take() else {
+      return Ok(());
+    };
+    event_loop.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    event_loop
+      .join
+      .join()
+      .map_err(|e| napi::Error::from_reason(format!("TUI app loop panicked: {e:?}")))?
+  }
 }
 
 #[napi]
 impl TuiApp {
   #[napi(constructor)]
-  pub fn new(_title: Option<String>, _width: Option<u16>, _height: Option<u16>) -> napi::Result<Self> {
+  pub fn new(title: Option<String>, width: Option<u16>, height: Option<u16>) -> napi::Result<Self> {
     Ok(Self {
       inner: Arc::new(Mutex::new(TuiAppInner {
-        components: HashMap::new(),
+        title,
+        width: width.unwrap_or(DEFAULT_TUI_APP_WIDTH),
+        height: height.unwrap_or(DEFAULT_TUI_APP_HEIGHT),
+        components: Vec::new(),
+        focused: None,
         layout: None,
-        terminal: None,
       })),
+      event_loop: None,
     })
   }
 
+  /// Mount a new built-in component; an existing component with the same id is replaced.
   #[napi]
   pub fn add_component(&mut self, id: String, component_type: WidgetType) -> napi::Result<()> {
     let component = create_component(&id, component_type)?;
-    let mut inner = self.inner.lock().unwrap();
-    inner.components.insert(id, component);
+    self.lock_inner()?.mount(id, component);
+    Ok(())
+  }
+
+  /// Mount an AgentView; updates made through `view` appear on the next redraw.
+  #[napi]
+  pub fn mount_agent_view(&mut self, id: String, view: &AgentView) -> napi::Result<()> {
+    let component = AgentView {
+      inner: Arc::clone(&view.inner),
+    };
+    self.lock_inner()?.mount(id, Box::new(component));
     Ok(())
   }
 
+  /// Mount a StatusBoard; tile updates made through `board` appear on the next redraw.
   #[napi]
-  pub fn start_terminal(&mut self) -> napi::Result<()> {
-    // Initialize terminal
-    let backend = ratatui::backend::CrosstermBackend::new(std::io::stdout());
-    let terminal = ratatui::Terminal::new(backend)
-      .map_err(|e| napi::Error::from_reason(format!("Failed to create terminal: {}", e)))?;
+  pub fn mount_status_board(&mut self, id: String, board: &StatusBoard) -> napi::Result<()> {
+    let component = StatusBoard {
+      inner: Arc::clone(&board.inner),
+    };
+    self.lock_inner()?.mount(id, Box::new(component));
+    Ok(())
+  }
 
-    {
-      let mut inner = self.inner.lock().unwrap();
-      inner.terminal = Some(terminal);
+  /// Mount a live view of `graph`; events from native runs of its threads redraw it.
+  #[napi]
+  pub fn mount_agent_graph(&mut self, id: String, graph: &AgentGraphRenderer) -> napi::Result<()> {
+    let component = AgentGraphWidget {
+      id: id.clone(),
+      graph: Arc::clone(&graph.graph),
+      title: "Agents".to_string(),
+    };
+    self.lock_inner()?.mount(id, Box::new(component));
+    Ok(())
+  }
+
+  #[napi]
+  pub fn remove_component(&mut self, id: String) -> napi::Result<()> {
+    let mut inner = self.lock_inner()?;
+    inner.components.retain(|mounted| mounted.id != id);
+    if inner.focused.as_deref() == Some(id.as_str()) {
+      inner.focused = None;
+      inner.cycle_focus(true);
     }
+    Ok(())
+  }
 
+  /// Pin a component to a screen rectangle instead of filling the screen.
+  #[napi]
+  pub fn set_component_area(&mut self, id: String, area: Dimensions) -> napi::Result<()> {
+    self.lock_inner()?.component_mut(&id)?.area = Some(Rect::new(area.x, area.y, area.width, area.height));
     Ok(())
   }
 
+  /// Components with a higher z-index are painted over lower ones (default 0).
   #[napi]
-  pub fn stop_terminal(&mut self) -> napi::Result<()> {
-    let mut inner = self.inner.lock().unwrap();
-    inner.terminal = None;
+  pub fn set_z_index(&mut self, id: String, z_index: i32) -> napi::Result<()> {
+    self.lock_inner()?.component_mut(&id)?.z_index = z_index;
     Ok(())
   }
 
-  #[allow(dead_code)]
-  fn render(&mut self) -> napi::Result<()> {
-    // For now, just a placeholder implementation
-    // Real implementation would properly handle the borrow checker
+  /// Arrange components with a split, grid or tabs layout, recomputed on every redraw and resize:
+  /// `{ type: "split", direction: "horizontal", children: [{ componentId, constraint: "30%" }] }`.
+  #[napi]
+  pub fn set_layout(&mut self, #[napi(ts_arg_type = "Record<string, unknown>")] layout: JsonValue) -> napi::Result<()> {
+    let spec: TuiLayoutSpec = serde_json::from_value(layout)
+      .map_err(|e| napi::Error::from_reason(format!("Invalid layout: {e}")))?;
+    let node = LayoutNode::from_spec(spec)?;
+    let mut inner = self.lock_inner()?;
+    inner.layout = Some(node);
+    inner.focus_visible();
+    Ok(())
+  }
+
+  /// Remove the layout; components without an explicit area fill the screen again.
+  #[napi]
+  pub fn clear_layout(&mut self) -> napi::Result<()> {
+    self.lock_inner()?.layout = None;
+    Ok(())
+  }
+
+  /// Show the tab with `id` (its `id`, `componentId` or title) in whichever tabs layout holds it.
+  #[napi]
+  pub fn select_tab(&mut self, id: String) -> napi::Result<()> {
+    let mut inner = self.lock_inner()?;
+    let selected = inner.layout.as_mut().is_some_and(|layout| layout.select_tab(&id));
+    if !selected {
+      return Err(napi::Error::from_reason(format!("Unknown tab: {id}")));
+    }
+    inner.focus_visible();
     Ok(())
   }
+
+  /// Send data to a component: text for Text widgets, output lines for AgentViews.
+  #[napi]
+  pub fn update_component(&mut self, id: String, data: JsonValue) -> napi::Result<()> {
+    self
+      .lock_inner()?
+      .component_mut(&id)?
+      .component
+      .handle_event(ComponentEvent::Data(data))
+  }
+
+  /// Append streamed text to a Text or Markdown component.
+  #[napi]
+  pub fn append_to_component(&mut self, id: String, text: String) -> napi::Result<()> {
+    self
+      .lock_inner()?
+      .component_mut(&id)?
+      .component
+      .handle_event(ComponentEvent::Message(text))
+  }
+
+  #[napi]
+  pub fn focus(&mut self, id: String) -> napi::Result<()> {
+    let mut inner = self.lock_inner()?;
+    inner.component_mut(&id)?;
+    inner.focused = Some(id);
+    Ok(())
+  }
+
+  #[napi]
+  pub fn focus_next(&mut self) -> napi::Result<Option<String>> {
+    Ok(self.lock_inner()?.cycle_focus(true))
+  }
+
+  #[napi]
+  pub fn focus_previous(&mut self) -> napi::Result<Option<String>> {
+    Ok(self.lock_inner()?.cycle_focus(false))
+  }
+
+  #[napi(getter)]
+  pub fn focused(&self) -> napi::Result<Option<String>> {
+    Ok(self.lock_inner()?.focused.clone())
+  }
+
+  /// Render every component into a plain-text screen without touching the terminal.
+  #[napi]
+  pub fn render_to_string(&self, width: Option<u16>, height: Option<u16>) -> napi::Result<String> {
+    let inner = self.lock_inner()?;
+    let area = Rect::new(0, 0, width.unwrap_or(inner.width), height.unwrap_or(inner.height));
+    let mut buf = RatatuiBuffer::empty(area);
+    inner.render(area, &mut buf);
+    let rows: Vec<String> = (0..area.height)
+      .map(|y| {
+        let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+        row.trim_end().to_string()
+      })
+      .collect();
+    Ok(rows.join("\n"))
+  }
+
+  /// Take over the terminal and start the event loop on a background thread.
+  #[napi]
+  pub fn run(
+    &mut self,
+    options: Option<TuiAppRunOptions>,
+    #[napi(ts_arg_type = "(err: unknown, event?: TuiAppEvent) => void")] on_event: Option<
+      ThreadsafeFunction<TuiAppEvent>,
+    >,
+  ) -> napi::Result<()> {
+    if self.event_loop.as_ref().is_some_and(|event_loop| !event_loop.join.is_finished()) {
+      return Err(napi::Error::from_reason("TUI app is already running"));
+    }
+    self.stop_loop()?;
+    let options = options.unwrap_or(TuiAppRunOptions {
+      tick_rate_ms: None,
+      exit_on_ctrl_c: None,
+      alt_screen: None,
+    });
+    let tick_rate = std::time::Duration::from_millis(u64::from(
+      options.tick_rate_ms.unwrap_or(DEFAULT_TUI_APP_TICK_MS).max(1),
+    ));
+    let exit_on_ctrl_c = options.exit_on_ctrl_c.unwrap_or(true);
+    let alt_screen = options.alt_screen.unwrap_or(true);
+    let title = self.lock_inner()?.title.clone();
+
+    enter_tui_app_screen(title.as_deref(), alt_screen)?;
+    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let inner = Arc::clone(&self.inner);
+    let loop_stop = Arc::clone(&stop);
+    let join = std::thread::Builder::new()
+      .name("codex-tui-app".to_string())
+      .spawn(move || {
+        let result = run_tui_app_loop(&inner, &loop_stop, tick_rate, exit_on_ctrl_c, on_event.as_ref());
+        leave_tui_app_screen(alt_screen);
+        emit_tui_app_event(on_event.as_ref(), TuiAppEvent::new("stop"));
+        result
+      })
+      .map_err(|e| {
+        leave_tui_app_screen(alt_screen);
+        napi::Error::from_reason(format!("Failed to start TUI app loop: {e}"))
+      })?;
+    self.event_loop = Some(TuiAppLoop { stop, join });
+    Ok(())
+  }
+
+  /// End the event loop and restore the terminal; returns the loop's error, if any.
+  #[napi]
+  pub fn stop(&mut self) -> napi::Result<()> {
+    self.stop_loop()
+  }
+
+  #[napi(getter)]
+  pub fn running(&self) -> bool {
+    self
+      .event_loop
+      .as_ref()
+      .is_some_and(|event_loop| !event_loop.join.is_finished())
+  }
+}
+
+impl Drop for TuiApp {
+  fn drop(&mut self) {
+    let _ = self.stop_loop();
+  }
 }
 
 // Agent View Component
@@ -196,14 +771,14 @@ impl AgentView {
 }
 
 impl Component for AgentView {
-  fn render(&self, area: Rect, buf: &mut RatatuiBuffer) {
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool) {
     let inner = self.inner.lock().unwrap();
 
     // Create layout
     let chunks = Layout::default()
       .direction(Direction::Vertical)
       .constraints([
-        Constraint::Length(1),  // Status line
+        Constraint::Length(2),  // Status line
         Constraint::Min(5),      // Messages
         Constraint::Length(5),  // Output
       ])
@@ -216,23 +791,37 @@ impl Component for AgentView {
       .block(Block::default().borders(Borders::BOTTOM));
     Widget::render(status, chunks[0], buf);
 
-    // Render messages
-    let messages: Vec<ListItem> = inner.messages
+    // Render messages, keeping the newest ones in view
+    let visible = usize::from(chunks[1].height.saturating_sub(2));
+    let messages: Vec<ListItem> = inner.messages[inner.messages.len().saturating_sub(visible)..]
       .iter()
       .map(|m| ListItem::new(m.as_str()))
       .collect();
     let messages_list = List::new(messages)
-      .block(Block::default().borders(Borders::ALL).title("Chat"));
+      .block(
+        Block::default()
+          .borders(Borders::ALL)
+          .border_style(component_border_style(focused))
+          .title("Chat"),
+      );
     Widget::render(messages_list, chunks[1], buf);
 
     // Render output
-    let output = inner.output_buffer.join("\n");
+    let visible = inner.output_buffer.len().saturating_sub(usize::from(chunks[2].height.saturating_sub(2)));
+    let output = inner.output_buffer[visible..].join("\n");
     let output_widget = Paragraph::new(output)
       .block(Block::default().borders(Borders::ALL).title("Output"));
     Widget::render(output_widget, chunks[2], buf);
   }
 
-  fn handle_event(&mut self, _event: ComponentEvent) -> napi::Result<()> {
+  fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()> {
+    let mut inner = self.inner.lock().unwrap();
+    match event {
+      ComponentEvent::Message(message) => inner.messages.push(format!("< {message}")),
+      ComponentEvent::Data(JsonValue::String(output)) => inner.output_buffer.push(output),
+      ComponentEvent::Data(data) => inner.output_buffer.push(data.to_string()),
+      ComponentEvent::KeyPress(_) | ComponentEvent::Resize(..) => {}
+    }
     Ok(())
   }
 
@@ -322,6 +911,447 @@ impl StatusBoard {
   }
 }
 
+impl Component for StatusBoard {
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool) {
+    let inner = self.inner.lock().unwrap();
+    let board = Block::default()
+      .borders(Borders::ALL)
+      .border_style(component_border_style(focused))
+      .title("Status");
+    let content = board.inner(area);
+    Widget::render(board, area, buf);
+    if inner.tiles.is_empty() {
+      return;
+    }
+
+    let cells: Vec<Rect> = match inner.layout {
+      LayoutType::Stack => Layout::vertical(inner.tiles.iter().map(|_| Constraint::Length(3)))
+        .split(content)
+        .to_vec(),
+      LayoutType::Grid | LayoutType::Split | LayoutType::Tabs => {
+        let columns = inner.tiles.len().min(3) as u32;
+        let rows = (inner.tiles.len() as u32).div_ceil(columns);
+        Layout::vertical((0..rows).map(|_| Constraint::Ratio(1, rows)))
+          .split(content)
+          .iter()
+          .flat_map(|row| {
+            Layout::horizontal((0..columns).map(|_| Constraint::Ratio(1, columns)))
+              .split(*row)
+              .to_vec()
+          })
+          .collect()
+      }
+    };
+
+    for (tile, cell) in inner.tiles.iter().zip(cells) {
+      let block = Block::default().borders(Borders::ALL).title(tile.title.as_str());
+      match &tile.tile_type {
+        StatusTileType::Text => Widget::render(Paragraph::new(tile.value.as_str()).block(block), cell, buf),
+        StatusTileType::Progress(ratio) => Widget::render(
+          Gauge::default()
+            .block(block)
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(tile.value.as_str()),
+          cell,
+          buf,
+        ),
+        StatusTileType::Chart(values) => {
+          let data: Vec<u64> = values.iter().map(|value| value.max(0.0).round() as u64).collect();
+          Widget::render(Sparkline::default().block(block).data(&data), cell, buf);
+        }
+      }
+    }
+  }
+
+  fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()> {
+    // `{ id, value }` updates a tile, mirroring `updateTile`.
+    if let ComponentEvent::Data(data) = event
+      && let (Some(id), Some(value)) = (data.get("id").and_then(JsonValue::as_str), data.get("value"))
+    {
+      let value = value.as_str().map_or_else(|| value.to_string(), str::to_string);
+      let mut inner = self.inner.lock().unwrap();
+      if let Some(tile) = inner.tiles.iter_mut().find(|t| t.id == id) {
+        tile.value = value;
+      }
+    }
+    Ok(())
+  }
+
+  fn get_id(&self) -> String {
+    "status-board".to_string()
+  }
+}
+
+// Text Component
+// ============================================================================
+
+struct TextComponent {
+  id: String,
+  title: Option<String>,
+  text: String,
+}
+
+impl Component for TextComponent {
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool) {
+    let mut block = Block::default()
+      .borders(Borders::ALL)
+      .border_style(component_border_style(focused));
+    if let Some(title) = &self.title {
+      block = block.title(title.as_str());
+    }
+    Widget::render(Paragraph::new(self.text.as_str()).block(block), area, buf);
+  }
+
+  fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()> {
+    match event {
+      ComponentEvent::Data(JsonValue::String(text)) => self.text = text,
+      ComponentEvent::Data(data) => {
+        if let Some(text) = data.get("text").and_then(JsonValue::as_str) {
+          self.text = text.to_string();
+        }
+        if let Some(title) = data.get("title").and_then(JsonValue::as_str) {
+          self.title = Some(title.to_string());
+        }
+      }
+      ComponentEvent::Message(text) => self.text.push_str(&text),
+      ComponentEvent::KeyPress(_) | ComponentEvent::Resize(..) => {}
+    }
+    Ok(())
+  }
+
+  fn get_id(&self) -> String {
+    self.id.clone()
+  }
+}
+
+// Markdown Component
+// ============================================================================
+//
+// Prose goes through codex-tui's history-cell renderer, so headings, lists,
+// emphasis and links look exactly like the chat transcript. Fenced code blocks
+// are split out first and highlighted per language. An unterminated fence
+// runs to the end of the text, which keeps code highlighted while it streams.
+// The view follows the tail until the user scrolls up; "end" follows again.
+
+struct MarkdownComponent {
+  id: String,
+  title: Option<String>,
+  source: String,
+  /// Rows scrolled up from the bottom of the document.
+  scroll_from_bottom: u16,
+}
+
+enum MarkdownSegment<'a> {
+  Prose(String),
+  Code { lang: &'a str, lines: Vec<&'a str> },
+}
+
+/// Split markdown into prose and fenced code blocks (``` or ~~~).
+fn split_markdown_fences(source: &str) -> Vec<MarkdownSegment<'_>> {
+  let fence_char = |line: &str| {
+    ['`', '~']
+      .into_iter()
+      .find(|ch| line.chars().take(3).filter(|c| c == ch).count() == 3)
+  };
+  let mut segments = Vec::new();
+  let mut prose = String::new();
+  let mut code: Option<(char, &str, Vec<&str>)> = None;
+  for line in source.lines() {
+    let trimmed = line.trim_start();
+    if let Some((fence, lang, lines)) = code.as_mut() {
+      if fence_char(trimmed) == Some(*fence) && trimmed.trim_end().chars().all(|ch| ch == *fence) {
+        segments.push(MarkdownSegment::Code {
+          lang,
+          lines: std::mem::take(lines),
+        });
+        code = None;
+      } else {
+        lines.push(line);
+      }
+      continue;
+    }
+    if let Some(fence) = fence_char(trimmed) {
+      if !prose.is_empty() {
+        segments.push(MarkdownSegment::Prose(std::mem::take(&mut prose)));
+      }
+      let lang = trimmed
+        .trim_start_matches(fence)
+        .split_whitespace()
+        .next()
+        .unwrap_or("");
+      code = Some((fence, lang, Vec::new()));
+    } else {
+      prose.push_str(line);
+      prose.push('\n');
+    }
+  }
+  if let Some((_, lang, lines)) = code {
+    segments.push(MarkdownSegment::Code { lang, lines });
+  }
+  if !prose.is_empty() {
+    segments.push(MarkdownSegment::Prose(prose));
+  }
+  segments
+}
+
+struct CodeSyntax {
+  keywords: &'static [&'static str],
+  line_comment: &'static str,
+  quotes: &'static [char],
+}
+
+fn code_syntax(lang: &str) -> Option<CodeSyntax> {
+  let syntax = match lang.to_ascii_lowercase().as_str() {
+    "rust" | "rs" => CodeSyntax {
+      keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+        "type", "unsafe", "use", "where", "while",
+      ],
+      line_comment: "//",
+      // Single quotes are lifetimes as often as chars.
+      quotes: &['"'],
+    },
+    "ts" | "typescript" | "tsx" | "js" | "javascript" | "jsx" => CodeSyntax {
+      keywords: &[
+        "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+        "delete", "else", "export", "extends", "false", "finally", "for", "from", "function",
+        "if", "import", "in", "instanceof", "interface", "let", "new", "null", "of", "return",
+        "static", "super", "switch", "this", "throw", "true", "try", "type", "typeof",
+        "undefined", "var", "void", "while", "yield",
+      ],
+      line_comment: "//",
+      quotes: &['"', '\'', '`'],
+    },
+    "py" | "python" => CodeSyntax {
+      keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "if", "import", "in", "is",
+        "lambda", "None", "not", "or", "pass", "raise", "return", "True", "try", "while", "with",
+        "yield",
+      ],
+      line_comment: "#",
+      quotes: &['"', '\''],
+    },
+    "go" => CodeSyntax {
+      keywords: &[
+        "break", "case", "chan", "const", "continue", "default", "defer", "else", "false", "for",
+        "func", "go", "if", "import", "interface", "map", "nil", "package", "range", "return",
+        "select", "struct", "switch", "true", "type", "var",
+      ],
+      line_comment: "//",
+      quotes: &['"', '`'],
+    },
+    "sh" | "bash" | "shell" | "zsh" | "console" => CodeSyntax {
+      keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "while",
+      ],
+      line_comment: "#",
+      quotes: &['"', '\''],
+    },
+    "json" | "jsonc" => CodeSyntax {
+      keywords: &["false", "null", "true"],
+      line_comment: "//",
+      quotes: &['"'],
+    },
+    "toml" | "yaml" | "yml" => CodeSyntax {
+      keywords: &["false", "true", "null"],
+      line_comment: "#",
+      quotes: &['"', '\''],
+    },
+    _ => return None,
+  };
+  Some(syntax)
+}
+
+/// Keyword, string, number and comment spans for one line of code.
+fn highlight_code_line(line: &str, syntax: &CodeSyntax) -> ratatui::text::Line<'static> {
+  use ratatui::text::Span;
+
+  let keyword = Style::default().fg(RataColor::Magenta);
+  let string = Style::default().fg(RataColor::Green);
+  let number = Style::default().fg(RataColor::Yellow);
+  let comment = Style::default().fg(RataColor::DarkGray);
+
+  let mut spans = Vec::new();
+  let mut plain = String::new();
+  let mut rest = line;
+  while let Some(ch) = rest.chars().next() {
+    let (token_len, style) = if rest.starts_with(syntax.line_comment) {
+      (rest.len(), Some(comment))
+    } else if syntax.quotes.contains(&ch) {
+      let mut escaped = false;
+      let end = rest[ch.len_utf8()..]
+        .char_indices()
+        .find(|(_, c)| {
+          let closes = !escaped && *c == ch;
+          escaped = !escaped && *c == '\\';
+          closes
+        })
+        .map_or(rest.len(), |(index, c)| ch.len_utf8() + index + c.len_utf8());
+      (end, Some(string))
+    } else if ch.is_alphanumeric() || ch == '_' {
+      let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+      let word = &rest[..end];
+      let style = if ch.is_ascii_digit() {
+        Some(number)
+      } else if syntax.keywords.contains(&word) {
+        Some(keyword)
+      } else {
+        None
+      };
+      (end, style)
+    } else {
+      (ch.len_utf8(), None)
+    };
+    let (token, remainder) = rest.split_at(token_len);
+    match style {
+      Some(style) => {
+        if !plain.is_empty() {
+          spans.push(Span::raw(std::mem::take(&mut plain)));
+        }
+        spans.push(Span::styled(token.to_string(), style));
+      }
+      None => plain.push_str(token),
+    }
+    rest = remainder;
+  }
+  if !plain.is_empty() {
+    spans.push(Span::raw(plain));
+  }
+  ratatui::text::Line::from(spans)
+}
+
+fn render_markdown_lines(source: &str) -> Vec<ratatui::text::Line<'static>> {
+  let mut lines: Vec<ratatui::text::Line<'static>> = Vec::new();
+  for segment in split_markdown_fences(source) {
+    match segment {
+      MarkdownSegment::Prose(prose) => {
+        lines.extend(codex_tui::render_markdown_text(&prose).lines);
+      }
+      MarkdownSegment::Code { lang, lines: code } => {
+        if !lines.is_empty() {
+          lines.push(ratatui::text::Line::default());
+        }
+        let syntax = code_syntax(lang);
+        for line in code {
+          lines.push(match &syntax {
+            Some(syntax) => highlight_code_line(line, syntax),
+            None => ratatui::text::Line::raw(line.to_string()),
+          });
+        }
+        lines.push(ratatui::text::Line::default());
+      }
+    }
+  }
+  lines
+}
+
+impl Component for MarkdownComponent {
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool) {
+    let mut block = Block::default()
+      .borders(Borders::ALL)
+      .border_style(component_border_style(focused));
+    if let Some(title) = &self.title {
+      block = block.title(title.as_str());
+    }
+    let inner = block.inner(area);
+    let lines = render_markdown_lines(&self.source);
+    let width = usize::from(inner.width.max(1));
+    let rows: usize = lines
+      .iter()
+      .map(|line| line.width().div_ceil(width).max(1))
+      .sum();
+    let max_scroll = rows.saturating_sub(usize::from(inner.height));
+    let top = max_scroll.saturating_sub(usize::from(self.scroll_from_bottom));
+    let paragraph = Paragraph::new(lines)
+      .block(block)
+      .wrap(ratatui::widgets::Wrap { trim: false })
+      .scroll((u16::try_from(top).unwrap_or(u16::MAX), 0));
+    Widget::render(paragraph, area, buf);
+  }
+
+  fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()> {
+    match event {
+      ComponentEvent::Data(JsonValue::String(text)) => self.source = text,
+      ComponentEvent::Data(data) => {
+        if let Some(text) = data.get("text").and_then(JsonValue::as_str) {
+          self.source = text.to_string();
+        }
+        if let Some(title) = data.get("title").and_then(JsonValue::as_str) {
+          self.title = Some(title.to_string());
+        }
+      }
+      ComponentEvent::Message(text) => self.source.push_str(&text),
+      ComponentEvent::KeyPress(key) => {
+        self.scroll_from_bottom = match key.as_str() {
+          "up" | "k" => self.scroll_from_bottom.saturating_add(1),
+          "down" | "j" => self.scroll_from_bottom.saturating_sub(1),
+          "pageup" => self.scroll_from_bottom.saturating_add(10),
+          "pagedown" => self.scroll_from_bottom.saturating_sub(10),
+          "end" => 0,
+          _ => self.scroll_from_bottom,
+        };
+      }
+      ComponentEvent::Resize(..) => {}
+    }
+    Ok(())
+  }
+
+  fn get_id(&self) -> String {
+    self.id.clone()
+  }
+}
+
+// Agent Graph Widget
+// ============================================================================
+
+struct AgentGraphWidget {
+  id: String,
+  graph: Arc<Mutex<AgentGraph>>,
+  title: String,
+}
+
+impl Component for AgentGraphWidget {
+  fn render(&self, area: Rect, buf: &mut RatatuiBuffer, focused: bool) {
+    let lines = self
+      .graph
+      .lock()
+      .map(|graph| {
+        // Fit the box: cut long activity lines and keep the newest rows.
+        let options = AsciiGraphOptions {
+          width: Some(usize::from(area.width.saturating_sub(2))),
+          max_rows: Some(usize::from(area.height.saturating_sub(2))),
+          ..AsciiGraphOptions::default()
+        };
+        render_agent_graph_ascii(&graph, &options)
+      })
+      .unwrap_or_default();
+    let block = Block::default()
+      .title(self.title.as_str())
+      .borders(Borders::ALL)
+      .border_style(component_border_style(focused));
+    Widget::render(Paragraph::new(lines.join("\n")).block(block), area, buf);
+  }
+
+  fn handle_event(&mut self, event: ComponentEvent) -> napi::Result<()> {
+    if let ComponentEvent::Data(data) = event
+      && let Some(title) = data.get("title").and_then(JsonValue::as_str)
+    {
+      self.title = title.to_string();
+    }
+    Ok(())
+  }
+
+  fn get_id(&self) -> String {
+    self.id.clone()
+  }
+}
+
 // Agent Orchestrator
 // ============================================================================
 
@@ -410,9 +1440,20 @@ pub struct AgentConfig {
 
 fn create_component(id: &str, widget_type: WidgetType) -> napi::Result<Box<dyn Component>> {
   match widget_type {
+    WidgetType::Text => Ok(Box::new(TextComponent {
+      id: id.to_string(),
+      title: None,
+      text: String::new(),
+    })),
     WidgetType::Chat => {
       Ok(Box::new(AgentView::new(id.to_string(), None)?))
     }
+    WidgetType::Markdown => Ok(Box::new(MarkdownComponent {
+      id: id.to_string(),
+      title: None,
+      source: String::new(),
+      scroll_from_bottom: 0,
+    })),
     _ => Err(napi::Error::from_reason("Widget type not yet implemented")),
   }
 }
@@ -425,13 +1466,14 @@ pub struct LayoutManager {
   root: Arc<Mutex<LayoutNode>>,
 }
 
-#[allow(dead_code)]
 enum LayoutNode {
   Split {
     orientation: Direction,
-    ratio: f32,
-    left: Box<LayoutNode>,
-    right: Box<LayoutNode>,
+    children: Vec<(Constraint, LayoutNode)>,
+  },
+  Grid {
+    columns: u16,
+    children: Vec<LayoutNode>,
   },
   Tabs {
     tabs: Vec<Tab>,
@@ -442,13 +1484,213 @@ enum LayoutNode {
   },
 }
 
-#[allow(dead_code)]
 struct Tab {
   id: String,
   title: String,
   content: LayoutNode,
 }
 
+/// Layout description accepted by `TuiApp.setLayout`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TuiLayoutSpec {
+  Split {
+    direction: Option<String>,
+    children: Vec<TuiLayoutChildSpec>,
+  },
+  /// Children stacked top to bottom; shorthand for a vertical split.
+  Stack { children: Vec<TuiLayoutChildSpec> },
+  Grid {
+    columns: Option<u16>,
+    children: Vec<TuiLayoutChildSpec>,
+  },
+  Tabs {
+    active: Option<usize>,
+    children: Vec<TuiLayoutChildSpec>,
+  },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TuiLayoutChildSpec {
+  id: Option<String>,
+  component_id: Option<String>,
+  /// Size along the split direction: a cell count, "30%", "1/3", "min:5", "max:10" or "fill".
+  constraint: Option<JsonValue>,
+  title: Option<String>,
+  layout: Option<Box<TuiLayoutSpec>>,
+}
+
+/// Screen rectangles computed from a layout for one frame.
+#[derive(Debug, Default)]
+struct ResolvedLayout {
+  areas: HashMap<String, Rect>,
+  /// Tab bar rectangle, tab titles and active index for every visible tabs layout.
+  tab_bars: Vec<(Rect, Vec<String>, usize)>,
+}
+
+fn parse_layout_constraint(value: Option<&JsonValue>) -> napi::Result<Constraint> {
+  let invalid = |value: &JsonValue| napi::Error::from_reason(format!("Invalid layout constraint: {value}"));
+  let Some(value) = value else {
+    return Ok(Constraint::Fill(1));
+  };
+  if let Some(length) = value.as_u64() {
+    return u16::try_from(length).map(Constraint::Length).map_err(|_| invalid(value));
+  }
+  let text = value.as_str().ok_or_else(|| invalid(value))?.trim();
+  let number = |raw: &str| raw.trim().parse::<u16>().map_err(|_| invalid(value));
+  if text == "fill" {
+    return Ok(Constraint::Fill(1));
+  }
+  if let Some(weight) = text.strip_prefix("fill:") {
+    return Ok(Constraint::Fill(number(weight)?));
+  }
+  if let Some(min) = text.strip_prefix("min:") {
+    return Ok(Constraint::Min(number(min)?));
+  }
+  if let Some(max) = text.strip_prefix("max:") {
+    return Ok(Constraint::Max(number(max)?));
+  }
+  if let Some(percent) = text.strip_suffix('%') {
+    return Ok(Constraint::Percentage(number(percent)?.min(100)));
+  }
+  if let Some((numerator, denominator)) = text.split_once('/') {
+    let numerator = number(numerator)?;
+    let denominator = number(denominator)?;
+    if denominator == 0 {
+      return Err(invalid(value));
+    }
+    return Ok(Constraint::Ratio(u32::from(numerator), u32::from(denominator)));
+  }
+  Ok(Constraint::Length(number(text)?))
+}
+
+impl LayoutNode {
+  fn from_spec(spec: TuiLayoutSpec) -> napi::Result<Self> {
+    let child_node = |child: TuiLayoutChildSpec| -> napi::Result<LayoutNode> {
+      match (child.layout, child.component_id) {
+        (Some(layout), _) => LayoutNode::from_spec(*layout),
+        (None, Some(id)) => Ok(LayoutNode::Widget { id }),
+        (None, None) => Err(napi::Error::from_reason(
+          "Layout children need a componentId or a nested layout",
+        )),
+      }
+    };
+    match spec {
+      TuiLayoutSpec::Split { direction, children } => {
+        let orientation = match direction.as_deref() {
+          None | Some("horizontal") => Direction::Horizontal,
+          Some("vertical") => Direction::Vertical,
+          Some(other) => {
+            return Err(napi::Error::from_reason(format!(
+              "Invalid split direction {other:?}; expected \"horizontal\" or \"vertical\""
+            )));
+          }
+        };
+        let children = children
+          .into_iter()
+          .map(|child| Ok((parse_layout_constraint(child.constraint.as_ref())?, child_node(child)?)))
+          .collect::<napi::Result<Vec<_>>>()?;
+        Ok(LayoutNode::Split { orientation, children })
+      }
+      TuiLayoutSpec::Stack { children } => {
+        let children = children
+          .into_iter()
+          .map(|child| Ok((parse_layout_constraint(child.constraint.as_ref())?, child_node(child)?)))
+          .collect::<napi::Result<Vec<_>>>()?;
+        Ok(LayoutNode::Split {
+          orientation: Direction::Vertical,
+          children,
+        })
+      }
+      TuiLayoutSpec::Grid { columns, children } => {
+        let columns = columns.unwrap_or_else(|| (children.len() as f64).sqrt().ceil() as u16).max(1);
+        let children = children.into_iter().map(child_node).collect::<napi::Result<Vec<_>>>()?;
+        Ok(LayoutNode::Grid { columns, children })
+      }
+      TuiLayoutSpec::Tabs { active, children } => {
+        let tabs = children
+          .into_iter()
+          .enumerate()
+          .map(|(index, child)| {
+            let id = child
+              .id
+              .clone()
+              .or_else(|| child.component_id.clone())
+              .unwrap_or_else(|| format!("tab-{index}"));
+            let title = child.title.clone().unwrap_or_else(|| id.clone());
+            Ok(Tab {
+              id,
+              title,
+              content: child_node(child)?,
+            })
+          })
+          .collect::<napi::Result<Vec<_>>>()?;
+        let active = active.unwrap_or(0).min(tabs.len().saturating_sub(1));
+        Ok(LayoutNode::Tabs { tabs, active })
+      }
+    }
+  }
+
+  fn resolve(&self, area: Rect, out: &mut ResolvedLayout) {
+    match self {
+      LayoutNode::Widget { id } => {
+        out.areas.insert(id.clone(), area);
+      }
+      LayoutNode::Split { orientation, children } => {
+        let areas = Layout::default()
+          .direction(*orientation)
+          .constraints(children.iter().map(|(constraint, _)| *constraint))
+          .split(area);
+        for ((_, child), child_area) in children.iter().zip(areas.iter()) {
+          child.resolve(*child_area, out);
+        }
+      }
+      LayoutNode::Grid { columns, children } => {
+        if children.is_empty() {
+          return;
+        }
+        let columns = u32::from(*columns);
+        let rows = (children.len() as u32).div_ceil(columns);
+        let row_areas = Layout::vertical((0..rows).map(|_| Constraint::Ratio(1, rows))).split(area);
+        let cells = row_areas.iter().flat_map(|row| {
+          Layout::horizontal((0..columns).map(|_| Constraint::Ratio(1, columns)))
+            .split(*row)
+            .to_vec()
+        });
+        for (child, cell) in children.iter().zip(cells) {
+          child.resolve(cell, out);
+        }
+      }
+      LayoutNode::Tabs { tabs, active } => {
+        let [bar, content] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        out
+          .tab_bars
+          .push((bar, tabs.iter().map(|tab| tab.title.clone()).collect(), *active));
+        if let Some(tab) = tabs.get(*active) {
+          tab.content.resolve(content, out);
+        }
+      }
+    }
+  }
+
+  /// Activate the tab with `id` anywhere in the tree; returns whether one was found.
+  fn select_tab(&mut self, id: &str) -> bool {
+    match self {
+      LayoutNode::Widget { .. } => false,
+      LayoutNode::Split { children, .. } => children.iter_mut().any(|(_, child)| child.select_tab(id)),
+      LayoutNode::Grid { children, .. } => children.iter_mut().any(|child| child.select_tab(id)),
+      LayoutNode::Tabs { tabs, active } => {
+        if let Some(index) = tabs.iter().position(|tab| tab.id == id || tab.title == id) {
+          *active = index;
+          return true;
+        }
+        tabs.iter_mut().any(|tab| tab.content.select_tab(id))
+      }
+    }
+  }
+}
+
 #[napi]
 impl LayoutManager {
   #[napi(constructor)]
@@ -474,14 +1716,251 @@ impl LayoutManager {
       _ => Direction::Horizontal,
     };
 
+    let left_percent = (ratio.clamp(0.0, 1.0) * 100.0).round() as u16;
     let mut root = self.root.lock().unwrap();
     *root = LayoutNode::Split {
       orientation: dir,
-      ratio: ratio as f32,
-      left: Box::new(LayoutNode::Widget { id: left_id }),
-      right: Box::new(LayoutNode::Widget { id: right_id }),
+      children: vec![
+        (Constraint::Percentage(left_percent), LayoutNode::Widget { id: left_id }),
+        (Constraint::Percentage(100 - left_percent), LayoutNode::Widget { id: right_id }),
+      ],
     };
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests_tui_components {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn text_app() -> TuiApp {
+    let mut app = TuiApp::new(None, Some(20), Some(5)).unwrap();
+    app.add_component("base".to_string(), WidgetType::Text).unwrap();
+    app.add_component("popup".to_string(), WidgetType::Text).unwrap();
+    app
+      .update_component("base".to_string(), JsonValue::String("base".to_string()))
+      .unwrap();
+    app
+      .update_component("popup".to_string(), json!({ "text": "POP" }))
+      .unwrap();
+    app
+      .set_component_area(
+        "popup".to_string(),
+        Dimensions {
+          width: 10,
+          height: 3,
+          x: 2,
+          y: 1,
+        },
+      )
+      .unwrap();
+    app
+  }
+
+  #[test]
+  fn higher_z_index_paints_over_lower_layers() {
+    let mut app = text_app();
+    app.set_z_index("popup".to_string(), 1).unwrap();
+    assert_eq!(
+      app.render_to_string(None, None).unwrap(),
+      [
+        "┌──────────────────┐",
+        "│b┌────────┐       │",
+        "│ │POP     │       │",
+        "│ └────────┘       │",
+        "└──────────────────┘",
+      ]
+      .join("\n")
+    );
+
+    app.set_z_index("popup".to_string(), -1).unwrap();
+    assert_eq!(
+      app.render_to_string(None, None).unwrap().lines().nth(1),
+      Some("│base              │")
+    );
+  }
+
+  fn visible_ids(app: &TuiApp) -> Vec<String> {
+    let inner = app.inner.lock().unwrap();
+    let screen = Rect::new(0, 0, inner.width, inner.height);
+    let resolved = inner.resolve_layout(screen);
+    inner
+      .placements(screen, &resolved)
+      .iter()
+      .map(|(mounted, _)| mounted.id.clone())
+      .collect()
+  }
+
+  #[test]
+  fn split_layout_places_components_by_constraint() {
+    let mut app = TuiApp::new(None, Some(20), Some(5)).unwrap();
+    for id in ["left", "right"] {
+      app.add_component(id.to_string(), WidgetType::Text).unwrap();
+      app
+        .update_component(id.to_string(), JsonValue::String(id.to_string()))
+        .unwrap();
+    }
+    app
+      .set_layout(json!({
+        "type": "split",
+        "children": [
+          { "componentId": "left", "constraint": 10 },
+          { "componentId": "right", "constraint": "fill" },
+        ],
+      }))
+      .unwrap();
+    assert_eq!(
+      app.render_to_string(None, None).unwrap(),
+      [
+        "┌────────┐┌────────┐",
+        "│left    ││right   │",
+        "│        ││        │",
+        "│        ││        │",
+        "└────────┘└────────┘",
+      ]
+      .join("\n")
+    );
+  }
+
+  #[test]
+  fn tabs_layout_shows_only_the_active_tab() {
+    let mut app = TuiApp::new(None, Some(20), Some(6)).unwrap();
+    app.add_component("logs".to_string(), WidgetType::Text).unwrap();
+    app.add_component("board".to_string(), WidgetType::Text).unwrap();
+    app
+      .set_layout(json!({
+        "type": "tabs",
+        "children": [
+          { "componentId": "logs", "title": "Logs" },
+          { "componentId": "board", "title": "Board" },
+        ],
+      }))
+      .unwrap();
+    assert_eq!(visible_ids(&app), vec!["logs".to_string()]);
+
+    app.select_tab("Board".to_string()).unwrap();
+    assert_eq!(visible_ids(&app), vec!["board".to_string()]);
+    assert_eq!(app.focused().unwrap().as_deref(), Some("board"));
+    assert!(app.select_tab("missing".to_string()).is_err());
+  }
+
+  #[test]
+  fn layout_constraints_parse_all_forms() {
+    let parse = |value: JsonValue| parse_layout_constraint(Some(&value)).unwrap();
+    assert_eq!(parse(json!(12)), Constraint::Length(12));
+    assert_eq!(parse(json!("30%")), Constraint::Percentage(30));
+    assert_eq!(parse(json!("1/3")), Constraint::Ratio(1, 3));
+    assert_eq!(parse(json!("min:5")), Constraint::Min(5));
+    assert_eq!(parse(json!("fill:2")), Constraint::Fill(2));
+    assert_eq!(parse_layout_constraint(None).unwrap(), Constraint::Fill(1));
+    assert!(parse_layout_constraint(Some(&json!("wide"))).is_err());
+  }
+
+  #[test]
+  fn tab_cycles_focus_and_keys_reach_the_focused_component() {
+    let app = text_app();
+    let mut inner = app.inner.lock().unwrap();
+    assert_eq!(inner.focused.as_deref(), Some("base"));
+
+    let (events, exit) = inner
+      .handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE), true)
+      .unwrap();
+    assert!(!exit);
+    assert_eq!(events[0].event_type, "focus");
+    assert_eq!(events[0].component_id.as_deref(), Some("popup"));
+
+    let (events, _) = inner
+      .handle_key(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT), true)
+      .unwrap();
+    assert_eq!(events[0].key.as_deref(), Some("shift+up"));
+    assert_eq!(events[0].component_id.as_deref(), Some("popup"));
+
+    inner
+      .handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT), true)
+      .unwrap();
+    assert_eq!(inner.focused.as_deref(), Some("base"));
+
+    let (_, exit) = inner
+      .handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL), true)
+      .unwrap();
+    assert!(exit);
+  }
+
+  #[test]
+  fn markdown_component_renders_streamed_text() {
+    let mut app = TuiApp::new(None, Some(30), Some(8)).unwrap();
+    app.add_component("doc".to_string(), WidgetType::Markdown).unwrap();
+    for chunk in ["# Title\n\n- [docs](https://x.io)\n", "\n```rust\nfn ma", "in() {\n"] {
+      app.append_to_component("doc".to_string(), chunk.to_string()).unwrap();
+    }
+    let rendered = app.render_to_string(None, None).unwrap();
+    let rows: Vec<&str> = rendered
+      .lines()
+      .map(|row| row.trim_matches(|ch| ch == '│' || ch == ' '))
+      .collect();
+    assert_eq!(
+      rows[1..rows.len() - 1],
+      ["# Title", "", "- docs (https://x.io)", "", "fn main() {", ""]
+    );
+  }
+
+  #[test]
+  fn agent_graph_widget_redraws_from_thread_events() {
+    let graph = AgentGraphRenderer::new().unwrap();
+    graph
+      .add_agent(AgentGraphNode {
+        id: "widget-root".to_string(),
+        parent_id: None,
+        label: Some("root".to_string()),
+        state: None,
+        activity: None,
+        turns: None,
+        started_at: None,
+        updated_at: None,
+        merged_into: None,
+      })
+      .unwrap();
+    let mut app = TuiApp::new(None, Some(26), Some(5)).unwrap();
+    app.mount_agent_graph("graph".to_string(), &graph).unwrap();
+
+    notify_agent_graphs(
+      "widget-root",
+      &ExecThreadEvent::TurnStarted(codex_exec::exec_events::TurnStartedEvent::default()),
+    );
+    record_agent_graph_fork("widget-root", "widget-fork");
+    assert_eq!(
+      app.render_to_string(None, None).unwrap(),
+      [
+        "┌Agents──────────────────┐",
+        "│*   root [running]      │",
+        "│├─┐                     │",
+        "││ * widget [pending]    │",
+        "└────────────────────────┘",
+      ]
+      .join("\n")
+    );
+  }
+
+  #[test]
+  fn code_lines_are_highlighted_by_language() {
+    use ratatui::text::Span;
+
+    let syntax = code_syntax("rust").unwrap();
+    let line = highlight_code_line("let n = \"a\\\"b\" + 42; // done", &syntax);
+    assert_eq!(
+      line.spans,
+      vec![
+        Span::styled("let", Style::default().fg(RataColor::Magenta)),
+        Span::raw(" n = "),
+        Span::styled("\"a\\\"b\"", Style::default().fg(RataColor::Green)),
+        Span::raw(" + "),
+        Span::styled("42", Style::default().fg(RataColor::Yellow)),
+        Span::raw("; "),
+        Span::styled("// done", Style::default().fg(RataColor::DarkGray)),
+      ]
+    );
+    assert!(code_syntax("brainfuck").is_none());
+  }
+}
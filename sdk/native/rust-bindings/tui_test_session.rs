@@ -0,0 +1,440 @@
+// Section 12: Headless TUI Test Sessions
+// ============================================================================
+//
+// `tui_test_run()` only paints static history lines. `TuiTestSession` keeps a
+// vt100-backed terminal alive and drives the codex-tui composer inside an
+// inline viewport, the way the interactive TUI lays it out: injected keys go
+// through the real composer key handling, submitted prompts are inserted into
+// history above the viewport, and every step returns a screen snapshot.
+// Styled captures expose per-cell colors and attributes, and two captures can
+// be compared cell by cell.
+//
+// Key exports:
+//   - TuiTestSession: sendKeys()/paste()/snapshot()/captureStyled() against a virtual screen
+//   - diff_tui_snapshots(): Structured cell diff between two styled captures
+//
+// ============================================================================
+
+use codex_tui::ComposerAction;
+use codex_tui::ComposerInput;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+const DEFAULT_TUI_TEST_WIDTH: u16 = 80;
+const DEFAULT_TUI_TEST_HEIGHT: u16 = 24;
+
+#[napi(object)]
+pub struct TuiTestSessionOptions {
+  pub width: Option<u16>,
+  pub height: Option<u16>,
+}
+
+#[napi(object)]
+pub struct TuiTestSnapshot {
+  /// Full screen contents, rows joined with newlines.
+  pub screen: String,
+  /// Screen rows with trailing whitespace removed.
+  pub rows: Vec<String>,
+  #[napi(js_name = "cursorRow")]
+  pub cursor_row: u16,
+  #[napi(js_name = "cursorCol")]
+  pub cursor_col: u16,
+  /// Prompts submitted so far, in order.
+  pub submitted: Vec<String>,
+}
+
+/// One screen cell with the style the vt100 parser resolved for it.
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct TuiStyledCell {
+  /// Cell text; empty for blank cells and the trailing half of wide characters.
+  pub text: String,
+  /// `"default"`, an ANSI name such as `"red"` or `"brightBlue"`, `"ansi{N}"` for the
+  /// 256-color palette, or `"#rrggbb"`.
+  pub fg: String,
+  pub bg: String,
+  pub bold: bool,
+  pub italic: bool,
+  pub underline: bool,
+  pub inverse: bool,
+}
+
+#[derive(Clone, Debug)]
+#[napi(object)]
+pub struct TuiStyledSnapshot {
+  pub width: u16,
+  pub height: u16,
+  /// Row-major cells, `height` rows of `width` cells.
+  pub cells: Vec<Vec<TuiStyledCell>>,
+}
+
+#[napi(object)]
+pub struct TuiCellChange {
+  pub row: u16,
+  pub col: u16,
+  /// Cell in the first snapshot; missing when the position only exists in the second.
+  pub before: Option<TuiStyledCell>,
+  pub after: Option<TuiStyledCell>,
+}
+
+#[napi(object)]
+pub struct TuiSnapshotDiff {
+  /// True when both snapshots have the same size and every cell matches.
+  pub identical: bool,
+  #[napi(js_name = "sizeChanged")]
+  pub size_changed: bool,
+  /// Changed cells in row-major order.
+  pub changes: Vec<TuiCellChange>,
+  /// Rows containing at least one change.
+  #[napi(js_name = "changedRows")]
+  pub changed_rows: Vec<u16>,
+}
+
+fn vt100_color_name(color: vt100::Color) -> String {
+  const ANSI_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+  match color {
+    vt100::Color::Default => "default".to_string(),
+    vt100::Color::Idx(index @ 0..=7) => ANSI_NAMES[index as usize].to_string(),
+    vt100::Color::Idx(index @ 8..=15) => {
+      let name = ANSI_NAMES[(index - 8) as usize];
+      let mut chars = name.chars();
+      let capitalized: String = chars
+        .next()
+        .map(|first| first.to_ascii_uppercase())
+        .into_iter()
+        .chain(chars)
+        .collect();
+      format!("bright{capitalized}")
+    }
+    vt100::Color::Idx(index) => format!("ansi{index}"),
+    vt100::Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+  }
+}
+
+fn capture_styled_screen(screen: &vt100::Screen) -> TuiStyledSnapshot {
+  let (height, width) = screen.size();
+  let cells = (0..height)
+    .map(|row| {
+      (0..width)
+        .map(|col| match screen.cell(row, col) {
+          Some(cell) => TuiStyledCell {
+            text: cell.contents().to_string(),
+            fg: vt100_color_name(cell.fgcolor()),
+            bg: vt100_color_name(cell.bgcolor()),
+            bold: cell.bold(),
+            italic: cell.italic(),
+            underline: cell.underline(),
+            inverse: cell.inverse(),
+          },
+          None => TuiStyledCell {
+            text: String::new(),
+            fg: "default".to_string(),
+            bg: "default".to_string(),
+            bold: false,
+            italic: false,
+            underline: false,
+            inverse: false,
+          },
+        })
+        .collect()
+    })
+    .collect();
+  TuiStyledSnapshot { width, height, cells }
+}
+
+/// Compare two styled captures cell by cell.
+#[napi]
+pub fn diff_tui_snapshots(a: TuiStyledSnapshot, b: TuiStyledSnapshot) -> TuiSnapshotDiff {
+  let size_changed = a.width != b.width || a.height != b.height;
+  let rows = a.cells.len().max(b.cells.len());
+  let mut changes = Vec::new();
+  let mut changed_rows = Vec::new();
+  for row in 0..rows {
+    let before_row = a.cells.get(row);
+    let after_row = b.cells.get(row);
+    let cols = before_row.map_or(0, Vec::len).max(after_row.map_or(0, Vec::len));
+    let mut row_changed = false;
+    for col in 0..cols {
+      let before = before_row.and_then(|cells| cells.get(col));
+      let after = after_row.and_then(|cells| cells.get(col));
+      if before != after {
+        row_changed = true;
+        changes.push(TuiCellChange {
+          row: row as u16,
+          col: col as u16,
+          before: before.cloned(),
+          after: after.cloned(),
+        });
+      }
+    }
+    if row_changed {
+      changed_rows.push(row as u16);
+    }
+  }
+  TuiSnapshotDiff {
+    identical: !size_changed && changes.is_empty(),
+    size_changed,
+    changes,
+    changed_rows,
+  }
+}
+
+/// Translate terminal input bytes (`"hello\r"`, `"\x1b[A"`, `"\x03"`) into key events.
+fn parse_tui_test_keys(input: &str) -> Vec<KeyEvent> {
+  let mut keys = Vec::new();
+  let mut chars = input.chars().peekable();
+  while let Some(ch) = chars.next() {
+    let key = match ch {
+      '\r' | '\n' => KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+      '\t' => KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+      '\u{7f}' | '\u{8}' => KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE),
+      '\u{1b}' => match chars.peek() {
+        Some('[') => {
+          chars.next();
+          let code = match chars.next() {
+            Some('A') => KeyCode::Up,
+            Some('B') => KeyCode::Down,
+            Some('C') => KeyCode::Right,
+            Some('D') => KeyCode::Left,
+            Some('H') => KeyCode::Home,
+            Some('F') => KeyCode::End,
+            Some('Z') => KeyCode::BackTab,
+            Some('3') if chars.peek() == Some(&'~') => {
+              chars.next();
+              KeyCode::Delete
+            }
+            _ => continue,
+          };
+          KeyEvent::new(code, KeyModifiers::NONE)
+        }
+        _ => KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+      },
+      '\u{1}'..='\u{1a}' => {
+        let letter = char::from(b'a' + (ch as u8 - 1));
+        KeyEvent::new(KeyCode::Char(letter), KeyModifiers::CONTROL)
+      }
+      ch => KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE),
+    };
+    keys.push(key);
+  }
+  keys
+}
+
+/// A virtual terminal running the codex-tui composer for end-to-end TUI tests.
+#[napi]
+pub struct TuiTestSession {
+  terminal: codex_tui::custom_terminal::Terminal<Vt100Backend>,
+  composer: ComposerInput,
+  submitted: Vec<String>,
+}
+
+impl TuiTestSession {
+  /// Let a pending paste burst settle so buffered characters reach the composer.
+  fn settle_paste_burst(&mut self) {
+    while self.composer.is_in_paste_burst() {
+      std::thread::sleep(ComposerInput::recommended_flush_delay());
+      self.composer.flush_paste_burst_if_due();
+    }
+  }
+
+  fn submit(&mut self, text: String) -> napi::Result<()> {
+    use ratatui::text::Line;
+
+    let lines: Vec<Line<'static>> = text
+      .lines()
+      .enumerate()
+      .map(|(index, line)| {
+        let prefix = if index == 0 { "› " } else { "  " };
+        Line::from(format!("{prefix}{line}"))
+      })
+      .collect();
+    codex_tui::insert_history::insert_history_lines(&mut self.terminal, lines)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to insert history: {e}")))?;
+    self.submitted.push(text);
+    Ok(())
+  }
+
+  fn render(&mut self) -> napi::Result<TuiTestSnapshot> {
+    use ratatui::layout::Rect;
+
+    let size = self
+      .terminal
+      .size()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to read screen size: {e}")))?;
+    let height = self.composer.desired_height(size.width).clamp(1, size.height);
+    let area = Rect::new(0, size.height - height, size.width, height);
+    let previous = self.terminal.viewport_area;
+    if area != previous {
+      // Like the interactive TUI, push history up when the composer grows.
+      if area.top() < previous.top() && previous.height > 0 {
+        self
+          .terminal
+          .backend_mut()
+          .scroll_region_up(0..previous.top(), previous.top() - area.top())
+          .map_err(|e| napi::Error::from_reason(format!("Failed to scroll history: {e}")))?;
+      }
+      self
+        .terminal
+        .clear()
+        .map_err(|e| napi::Error::from_reason(format!("Failed to clear viewport: {e}")))?;
+      self.terminal.set_viewport_area(area);
+    }
+
+    let composer = &self.composer;
+    self
+      .terminal
+      .draw(|frame| {
+        let area = frame.area();
+        composer.render_ref(area, frame.buffer_mut());
+        if let Some(position) = composer.cursor_pos(area) {
+          frame.set_cursor_position(position);
+        }
+      })
+      .map_err(|e| napi::Error::from_reason(format!("Failed to draw TUI frame: {e}")))?;
+    Ok(self.snapshot_now())
+  }
+
+  fn snapshot_now(&self) -> TuiTestSnapshot {
+    let screen = self.terminal.backend().parser().screen();
+    let (cursor_row, cursor_col) = screen.cursor_position();
+    let contents = screen.contents();
+    let (rows, cols) = screen.size();
+    TuiTestSnapshot {
+      rows: screen
+        .rows(0, cols)
+        .take(rows as usize)
+        .map(|row| row.trim_end().to_string())
+        .collect(),
+      screen: contents,
+      cursor_row,
+      cursor_col,
+      submitted: self.submitted.clone(),
+    }
+  }
+}
+
+#[napi]
+impl TuiTestSession {
+  #[napi(constructor)]
+  pub fn new(options: Option<TuiTestSessionOptions>) -> napi::Result<Self> {
+    let (width, height) = options.map_or((None, None), |options| (options.width, options.height));
+    let width = width.unwrap_or(DEFAULT_TUI_TEST_WIDTH);
+    let height = height.unwrap_or(DEFAULT_TUI_TEST_HEIGHT);
+    if width == 0 || height == 0 {
+      return Err(napi::Error::from_reason(format!(
+        "TUI test screen must be non-empty, got {width}x{height}"
+      )));
+    }
+    // Keep SGR sequences even under NO_COLOR so styled captures see real colors.
+    crossterm::style::force_color_output(true);
+    let terminal = codex_tui::custom_terminal::Terminal::with_options(Vt100Backend::new(width, height))
+      .map_err(|e| napi::Error::from_reason(format!("Failed to create TUI test terminal: {e}")))?;
+    let mut session = Self {
+      terminal,
+      composer: ComposerInput::new(),
+      submitted: Vec::new(),
+    };
+    session.render()?;
+    Ok(session)
+  }
+
+  /// Type `keys` into the composer (control bytes and ANSI arrow sequences are decoded)
+  /// and return the screen afterwards.
+  #[napi]
+  pub fn send_keys(&mut self, keys: String) -> napi::Result<TuiTestSnapshot> {
+    for key in parse_tui_test_keys(&keys) {
+      if key.code == KeyCode::Enter {
+        // A burst in flight would turn Enter into a newline, as it does for real pastes.
+        self.settle_paste_burst();
+      }
+      match self.composer.input(key) {
+        ComposerAction::Submitted(text) => self.submit(text)?,
+        ComposerAction::None => {}
+      }
+    }
+    self.settle_paste_burst();
+    self.render()
+  }
+
+  /// Deliver `text` as a bracketed paste.
+  #[napi]
+  pub fn paste(&mut self, text: String) -> napi::Result<TuiTestSnapshot> {
+    self.composer.handle_paste(text);
+    self.render()
+  }
+
+  /// Redraw and return the current screen without sending input.
+  #[napi]
+  pub fn snapshot(&mut self) -> napi::Result<TuiTestSnapshot> {
+    self.render()
+  }
+
+  /// Redraw and return every cell with its colors and attributes.
+  #[napi]
+  pub fn capture_styled(&mut self) -> napi::Result<TuiStyledSnapshot> {
+    self.render()?;
+    Ok(capture_styled_screen(self.terminal.backend().parser().screen()))
+  }
+}
+
+#[cfg(test)]
+mod tests_tui_test_session {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parses_control_bytes_and_arrow_sequences() {
+    let keys = parse_tui_test_keys("a\r\u{1b}[A\u{3}\u{1b}");
+    assert_eq!(
+      keys,
+      vec![
+        KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Up, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+      ]
+    );
+  }
+
+  #[test]
+  fn styled_capture_reports_colors_and_diffs_by_cell() {
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process(b"ab");
+    let before = capture_styled_screen(parser.screen());
+    parser.process(b"\x1b[1;31mc\x1b[0m");
+    let after = capture_styled_screen(parser.screen());
+
+    assert_eq!(after.cells[0][2].text, "c");
+    assert_eq!(after.cells[0][2].fg, "red");
+    assert!(after.cells[0][2].bold);
+    assert_eq!(vt100_color_name(vt100::Color::Idx(12)), "brightBlue");
+    assert_eq!(vt100_color_name(vt100::Color::Rgb(1, 2, 255)), "#0102ff");
+
+    let diff = diff_tui_snapshots(before, after);
+    assert!(!diff.identical);
+    assert!(!diff.size_changed);
+    assert_eq!(diff.changed_rows, vec![0]);
+    assert_eq!(
+      diff.changes.iter().map(|change| (change.row, change.col)).collect::<Vec<_>>(),
+      vec![(0, 2)]
+    );
+  }
+
+  #[test]
+  fn submitted_prompt_moves_into_history() {
+    let mut session = TuiTestSession::new(Some(TuiTestSessionOptions {
+      width: Some(60),
+      height: Some(12),
+    }))
+    .unwrap();
+    let typed = session.send_keys("hello".to_string()).unwrap();
+    assert!(typed.screen.contains("hello"));
+    assert!(typed.submitted.is_empty());
+
+    let submitted = session.send_keys("\r".to_string()).unwrap();
+    assert_eq!(submitted.submitted, vec!["hello".to_string()]);
+    assert!(submitted.rows.iter().any(|row| row.starts_with("› hello")));
+  }
+}
@@ -0,0 +1,631 @@
+// Section 13: Usage Reports
+// ============================================================================
+//
+// Aggregates token usage recorded in rollouts under codex home into a
+// chargeback-style report. Every `token_count` record carries the session's
+// cumulative usage, so each record contributes the delta from the previous
+// one; repeated records (rate-limit refreshes) add nothing, and forked
+// sessions start from the usage they inherited instead of counting it again.
+// Costs are estimates from a built-in per-model price table that callers can
+// override.
+//
+// Key exports:
+//   - usage_report(): Usage grouped by project, model or day, as rows plus CSV
+//
+// ============================================================================
+
+/// Built-in USD prices per million tokens: (model prefix, input, cached input, output).
+/// Longer prefixes are listed before the shorter ones they extend.
+const DEFAULT_MODEL_PRICING: &[(&str, f64, f64, f64)] = &[
+  ("gpt-5.1-codex-mini", 0.25, 0.025, 2.0),
+  ("gpt-5-nano", 0.05, 0.005, 0.4),
+  ("gpt-5-mini", 0.25, 0.025, 2.0),
+  ("gpt-5", 1.25, 0.125, 10.0),
+  ("codex-mini", 1.5, 0.375, 6.0),
+  ("gpt-4.1-nano", 0.1, 0.025, 0.4),
+  ("gpt-4.1-mini", 0.4, 0.1, 1.6),
+  ("gpt-4.1", 2.0, 0.5, 8.0),
+  ("gpt-4o-mini", 0.15, 0.075, 0.6),
+  ("gpt-4o", 2.5, 1.25, 10.0),
+  ("o4-mini", 1.1, 0.275, 4.4),
+  ("o3", 2.0, 0.5, 8.0),
+];
+
+/// Rollout directories scanned under codex home.
+const USAGE_ROLLOUT_DIRS: &[&str] = &["sessions", "archived_sessions"];
+
+#[derive(Clone, Debug)]
+#[napi(object)]
+pub struct ModelPricing {
+  /// USD per million uncached input tokens.
+  #[napi(js_name = "inputPerMillion")]
+  pub input_per_million: f64,
+  /// USD per million cached input tokens (defaults to the input price).
+  #[napi(js_name = "cachedInputPerMillion")]
+  pub cached_input_per_million: Option<f64>,
+  /// USD per million output tokens, reasoning included.
+  #[napi(js_name = "outputPerMillion")]
+  pub output_per_million: f64,
+}
+
+#[napi(object)]
+pub struct UsageReportOptions {
+  #[napi(js_name = "groupBy", ts_type = "\"project\" | \"model\" | \"day\"")]
+  pub group_by: Option<String>,
+  /// Inclusive lower bound: an RFC 3339 timestamp or a `YYYY-MM-DD` date (UTC).
+  pub since: Option<String>,
+  /// Upper bound: an RFC 3339 timestamp (exclusive) or a `YYYY-MM-DD` date (inclusive).
+  pub until: Option<String>,
+  /// Defaults to `$CODEX_HOME` or `~/.codex`.
+  #[napi(js_name = "codexHome")]
+  pub codex_home: Option<String>,
+  /// Prices keyed by model name or prefix; consulted before the built-in table.
+  pub pricing: Option<HashMap<String, ModelPricing>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct UsageReportRow {
+  /// Project (git remote or working directory), model name, or `YYYY-MM-DD` day.
+  pub key: String,
+  pub sessions: u32,
+  #[napi(js_name = "inputTokens")]
+  pub input_tokens: i64,
+  #[napi(js_name = "cachedInputTokens")]
+  pub cached_input_tokens: i64,
+  #[napi(js_name = "outputTokens")]
+  pub output_tokens: i64,
+  #[napi(js_name = "reasoningOutputTokens")]
+  pub reasoning_output_tokens: i64,
+  #[napi(js_name = "totalTokens")]
+  pub total_tokens: i64,
+  /// Estimated cost of the priced models in this row; `None` when none were priced.
+  #[napi(js_name = "estimatedCostUsd")]
+  pub estimated_cost_usd: Option<f64>,
+  /// Models in this row with no known price; their tokens are excluded from the cost.
+  #[napi(js_name = "unpricedModels")]
+  pub unpriced_models: Vec<String>,
+}
+
+#[napi(object)]
+pub struct UsageReport {
+  #[napi(js_name = "groupBy")]
+  pub group_by: String,
+  pub since: Option<String>,
+  pub until: Option<String>,
+  /// Rows sorted by total tokens, largest first.
+  pub rows: Vec<UsageReportRow>,
+  pub totals: UsageReportRow,
+  /// The rows and totals as CSV, with a header line.
+  pub csv: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UsageGroupBy {
+  Project,
+  Model,
+  Day,
+}
+
+impl UsageGroupBy {
+  fn parse(value: Option<&str>) -> napi::Result<Self> {
+    match value {
+      None | Some("project") => Ok(Self::Project),
+      Some("model") => Ok(Self::Model),
+      Some("day") => Ok(Self::Day),
+      Some(other) => Err(napi::Error::from_reason(format!(
+        "Invalid groupBy {other:?}; expected \"project\", \"model\" or \"day\""
+      ))),
+    }
+  }
+
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Project => "project",
+      Self::Model => "model",
+      Self::Day => "day",
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct UsageCounts {
+  input: i64,
+  cached_input: i64,
+  output: i64,
+  reasoning_output: i64,
+  total: i64,
+}
+
+impl UsageCounts {
+  fn from_json(value: &JsonValue) -> Option<Self> {
+    let field = |name: &str| value.get(name).and_then(JsonValue::as_i64).unwrap_or(0);
+    value.is_object().then(|| Self {
+      input: field("input_tokens"),
+      cached_input: field("cached_input_tokens"),
+      output: field("output_tokens"),
+      reasoning_output: field("reasoning_output_tokens"),
+      total: field("total_tokens"),
+    })
+  }
+
+  fn saturating_delta(self, previous: Self) -> Self {
+    Self {
+      input: (self.input - previous.input).max(0),
+      cached_input: (self.cached_input - previous.cached_input).max(0),
+      output: (self.output - previous.output).max(0),
+      reasoning_output: (self.reasoning_output - previous.reasoning_output).max(0),
+      total: (self.total - previous.total).max(0),
+    }
+  }
+
+  fn add(&mut self, other: Self) {
+    self.input += other.input;
+    self.cached_input += other.cached_input;
+    self.output += other.output;
+    self.reasoning_output += other.reasoning_output;
+    self.total += other.total;
+  }
+
+  fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ResolvedPricing {
+  input: f64,
+  cached_input: f64,
+  output: f64,
+}
+
+impl ResolvedPricing {
+  fn cost(&self, usage: &UsageCounts) -> f64 {
+    let cached = usage.cached_input.min(usage.input);
+    let uncached = usage.input - cached;
+    (uncached as f64 * self.input
+      + cached as f64 * self.cached_input
+      + usage.output as f64 * self.output)
+      / 1_000_000.0
+  }
+}
+
+fn resolve_model_pricing(
+  model: &str,
+  overrides: &HashMap<String, ModelPricing>,
+) -> Option<ResolvedPricing> {
+  let from_override = |pricing: &ModelPricing| ResolvedPricing {
+    input: pricing.input_per_million,
+    cached_input: pricing
+      .cached_input_per_million
+      .unwrap_or(pricing.input_per_million),
+    output: pricing.output_per_million,
+  };
+  if let Some(pricing) = overrides.get(model) {
+    return Some(from_override(pricing));
+  }
+  if let Some((_, pricing)) = overrides
+    .iter()
+    .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+    .max_by_key(|(prefix, _)| prefix.len())
+  {
+    return Some(from_override(pricing));
+  }
+  DEFAULT_MODEL_PRICING
+    .iter()
+    .find(|(prefix, ..)| model.starts_with(prefix))
+    .map(|(_, input, cached_input, output)| ResolvedPricing {
+      input: *input,
+      cached_input: *cached_input,
+      output: *output,
+    })
+}
+
+/// Parse a report bound; date-only `until` values cover the whole day.
+fn parse_usage_bound(value: &str, is_until: bool) -> napi::Result<DateTime<Utc>> {
+  if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+    return Ok(timestamp.with_timezone(&Utc));
+  }
+  let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+    napi::Error::from_reason(format!(
+      "Invalid usage report bound {value:?}; expected an RFC 3339 timestamp or YYYY-MM-DD"
+    ))
+  })?;
+  let date = if is_until {
+    date.succ_opt().unwrap_or(date)
+  } else {
+    date
+  };
+  Ok(date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+fn collect_usage_rollouts(dir: &Path, out: &mut Vec<PathBuf>) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      collect_usage_rollouts(&path, out);
+    } else if path.extension().is_some_and(|ext| ext == "jsonl")
+      && path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("rollout-"))
+    {
+      out.push(path);
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+struct UsageGroup {
+  sessions: std::collections::HashSet<usize>,
+  usage: UsageCounts,
+  cost: Option<f64>,
+  unpriced_models: std::collections::BTreeSet<String>,
+}
+
+struct UsageAggregator<'a> {
+  group_by: UsageGroupBy,
+  since: Option<DateTime<Utc>>,
+  until: Option<DateTime<Utc>>,
+  pricing: &'a HashMap<String, ModelPricing>,
+  groups: HashMap<String, UsageGroup>,
+}
+
+impl UsageAggregator<'_> {
+  /// Fold one rollout's `token_count` deltas into the groups.
+  fn add_rollout(&mut self, session: usize, contents: &str) {
+    let mut project: Option<String> = None;
+    let mut model: Option<String> = None;
+    let mut forked = false;
+    let mut previous: Option<UsageCounts> = None;
+
+    for line in contents.lines() {
+      let Ok(record) = serde_json::from_str::<JsonValue>(line) else {
+        continue;
+      };
+      let payload = record.get("payload").unwrap_or(&JsonValue::Null);
+      match record.get("type").and_then(JsonValue::as_str) {
+        Some("session_meta") => {
+          if project.is_none() {
+            forked = payload
+              .get("forked_from_id")
+              .is_some_and(|id| !id.is_null());
+            project = payload
+              .get("git")
+              .and_then(|git| git.get("repository_url"))
+              .and_then(JsonValue::as_str)
+              .map(|url| url.trim_end_matches(".git").to_string())
+              .or_else(|| {
+                payload
+                  .get("cwd")
+                  .and_then(JsonValue::as_str)
+                  .map(str::to_string)
+              });
+          }
+        }
+        Some("turn_context") => {
+          if let Some(name) = payload.get("model").and_then(JsonValue::as_str) {
+            model = Some(name.to_string());
+          }
+        }
+        Some("event_msg")
+          if payload.get("type").and_then(JsonValue::as_str) == Some("token_count") =>
+        {
+          let Some(total) = payload
+            .get("info")
+            .and_then(|info| info.get("total_token_usage"))
+            .and_then(UsageCounts::from_json)
+          else {
+            continue;
+          };
+          let delta = match previous {
+            // A fork starts from its parent's totals; only growth after that is new usage.
+            None if forked => UsageCounts::default(),
+            None => total,
+            Some(previous) if total.total < previous.total => total,
+            Some(previous) => total.saturating_delta(previous),
+          };
+          previous = Some(total);
+          if delta.is_empty() {
+            continue;
+          }
+          let timestamp = record
+            .get("timestamp")
+            .and_then(JsonValue::as_str)
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|value| value.with_timezone(&Utc));
+          let in_window = match timestamp {
+            Some(timestamp) => {
+              self.since.is_none_or(|since| timestamp >= since)
+                && self.until.is_none_or(|until| timestamp < until)
+            }
+            None => self.since.is_none() && self.until.is_none(),
+          };
+          if !in_window {
+            continue;
+          }
+          let model_name = model.clone().unwrap_or_else(|| "unknown".to_string());
+          let key = match self.group_by {
+            UsageGroupBy::Project => project.clone().unwrap_or_else(|| "unknown".to_string()),
+            UsageGroupBy::Model => model_name.clone(),
+            UsageGroupBy::Day => timestamp.map_or_else(
+              || "unknown".to_string(),
+              |t| t.format("%Y-%m-%d").to_string(),
+            ),
+          };
+          let group = self.groups.entry(key).or_default();
+          group.sessions.insert(session);
+          group.usage.add(delta);
+          match resolve_model_pricing(&model_name, self.pricing) {
+            Some(pricing) => *group.cost.get_or_insert(0.0) += pricing.cost(&delta),
+            None => {
+              group.unpriced_models.insert(model_name);
+            }
+          }
+        }
+        Some(_) | None => {}
+      }
+    }
+  }
+
+  fn into_rows(self) -> Vec<UsageReportRow> {
+    let mut rows: Vec<UsageReportRow> = self
+      .groups
+      .into_iter()
+      .map(|(key, group)| usage_row(key, group.sessions.len(), &group))
+      .collect();
+    rows.sort_by(|a, b| {
+      b.total_tokens
+        .cmp(&a.total_tokens)
+        .then_with(|| a.key.cmp(&b.key))
+    });
+    rows
+  }
+}
+
+fn usage_row(key: String, sessions: usize, group: &UsageGroup) -> UsageReportRow {
+  UsageReportRow {
+    key,
+    sessions: sessions as u32,
+    input_tokens: group.usage.input,
+    cached_input_tokens: group.usage.cached_input,
+    output_tokens: group.usage.output,
+    reasoning_output_tokens: group.usage.reasoning_output,
+    total_tokens: group.usage.total,
+    estimated_cost_usd: group
+      .cost
+      .map(|cost| (cost * 1_000_000.0).round() / 1_000_000.0),
+    unpriced_models: group.unpriced_models.iter().cloned().collect(),
+  }
+}
+
+fn usage_csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+fn usage_report_csv(
+  group_by: UsageGroupBy,
+  rows: &[UsageReportRow],
+  totals: &UsageReportRow,
+) -> String {
+  let mut csv = format!(
+    "{},sessions,input_tokens,cached_input_tokens,output_tokens,reasoning_output_tokens,total_tokens,estimated_cost_usd\n",
+    group_by.as_str()
+  );
+  for row in rows.iter().chain(std::iter::once(totals)) {
+    csv.push_str(&format!(
+      "{},{},{},{},{},{},{},{}\n",
+      usage_csv_field(&row.key),
+      row.sessions,
+      row.input_tokens,
+      row.cached_input_tokens,
+      row.output_tokens,
+      row.reasoning_output_tokens,
+      row.total_tokens,
+      row
+        .estimated_cost_usd
+        .map(|cost| format!("{cost:.6}"))
+        .unwrap_or_default(),
+    ));
+  }
+  csv
+}
+
+fn build_usage_report(options: UsageReportOptions) -> napi::Result<UsageReport> {
+  let group_by = UsageGroupBy::parse(options.group_by.as_deref())?;
+  let since = options
+    .since
+    .as_deref()
+    .map(|value| parse_usage_bound(value, false))
+    .transpose()?;
+  let until = options
+    .until
+    .as_deref()
+    .map(|value| parse_usage_bound(value, true))
+    .transpose()?;
+  let codex_home = match options.codex_home.as_deref() {
+    Some(path) => PathBuf::from(path),
+    None => find_codex_home()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to resolve codex home: {e}")))?,
+  };
+  let pricing = options.pricing.unwrap_or_default();
+
+  let mut rollouts = Vec::new();
+  for dir in USAGE_ROLLOUT_DIRS {
+    collect_usage_rollouts(&codex_home.join(dir), &mut rollouts);
+  }
+  rollouts.sort();
+
+  let mut aggregator = UsageAggregator {
+    group_by,
+    since,
+    until,
+    pricing: &pricing,
+    groups: HashMap::new(),
+  };
+  for (session, path) in rollouts.iter().enumerate() {
+    // A file last written before `since` cannot hold records inside the window.
+    if let (Some(since), Ok(modified)) = (since, std::fs::metadata(path).and_then(|m| m.modified()))
+      && DateTime::<Utc>::from(modified) < since
+    {
+      continue;
+    }
+    if let Ok(contents) = std::fs::read_to_string(path) {
+      aggregator.add_rollout(session, &contents);
+    }
+  }
+
+  let mut total = UsageGroup::default();
+  for group in aggregator.groups.values() {
+    total.sessions.extend(group.sessions.iter().copied());
+    total.usage.add(group.usage);
+    if let Some(cost) = group.cost {
+      *total.cost.get_or_insert(0.0) += cost;
+    }
+    total
+      .unpriced_models
+      .extend(group.unpriced_models.iter().cloned());
+  }
+  let totals = usage_row("total".to_string(), total.sessions.len(), &total);
+  let rows = aggregator.into_rows();
+  let csv = usage_report_csv(group_by, &rows, &totals);
+  Ok(UsageReport {
+    group_by: group_by.as_str().to_string(),
+    since: options.since,
+    until: options.until,
+    rows,
+    totals,
+    csv,
+  })
+}
+
+/// Token usage and estimated cost from every rollout under codex home, grouped by project, model or day.
+#[napi]
+pub async fn usage_report(options: Option<UsageReportOptions>) -> napi::Result<UsageReport> {
+  let options = options.unwrap_or(UsageReportOptions {
+    group_by: None,
+    since: None,
+    until: None,
+    codex_home: None,
+    pricing: None,
+  });
+  tokio::task::spawn_blocking(move || build_usage_report(options))
+    .await
+    .map_err(|e| napi::Error::from_reason(format!("Task join error: {e}")))?
+}
+
+#[cfg(test)]
+mod tests_usage_report {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn token_count(timestamp: &str, input: i64, cached: i64, output: i64) -> String {
+    json!({
+      "timestamp": timestamp,
+      "type": "event_msg",
+      "payload": {
+        "type": "token_count",
+        "info": {
+          "total_token_usage": {
+            "input_tokens": input,
+            "cached_input_tokens": cached,
+            "output_tokens": output,
+            "reasoning_output_tokens": 0,
+            "total_tokens": input + output,
+          },
+        },
+      },
+    })
+    .to_string()
+  }
+
+  fn write_rollout(dir: &Path, name: &str, lines: &[String]) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(dir.join(name), lines.join("\n")).unwrap();
+  }
+
+  #[test]
+  fn aggregates_deltas_and_skips_inherited_fork_usage() {
+    let home = tempfile::tempdir().unwrap();
+    let day = home.path().join("sessions/2025/03/01");
+    let meta = |cwd: &str, forked: Option<&str>| {
+      json!({
+        "timestamp": "2025-03-01T10:00:00Z",
+        "type": "session_meta",
+        "payload": { "cwd": cwd, "forked_from_id": forked },
+      })
+      .to_string()
+    };
+    let turn =
+      |model: &str| json!({ "type": "turn_context", "payload": { "model": model } }).to_string();
+    write_rollout(
+      &day,
+      "rollout-a.jsonl",
+      &[
+        meta("/work/app", None),
+        turn("gpt-5-codex"),
+        token_count("2025-03-01T10:01:00Z", 1_000_000, 0, 100_000),
+        // A repeated record (rate-limit refresh) adds nothing.
+        token_count("2025-03-01T10:01:05Z", 1_000_000, 0, 100_000),
+        token_count("2025-03-02T09:00:00Z", 1_500_000, 500_000, 100_000),
+      ],
+    );
+    write_rollout(
+      &day,
+      "rollout-b.jsonl",
+      &[
+        meta("/work/app", Some("parent")),
+        turn("mystery-model"),
+        token_count("2025-03-02T11:00:00Z", 1_500_000, 500_000, 100_000),
+        token_count("2025-03-02T11:05:00Z", 1_600_000, 500_000, 100_000),
+      ],
+    );
+    let options = |group_by: &str, since: Option<&str>| UsageReportOptions {
+      group_by: Some(group_by.to_string()),
+      since: since.map(str::to_string),
+      until: None,
+      codex_home: Some(home.path().to_string_lossy().into_owned()),
+      pricing: None,
+    };
+
+    let by_project = build_usage_report(options("project", None)).unwrap();
+    assert_eq!(by_project.rows.len(), 1);
+    assert_eq!(by_project.rows[0].key, "/work/app");
+    assert_eq!(by_project.rows[0].sessions, 2);
+    assert_eq!(by_project.rows[0].input_tokens, 1_600_000);
+    assert_eq!(
+      by_project.rows[0].unpriced_models,
+      vec!["mystery-model".to_string()]
+    );
+    // 1.0M uncached input + 0.1M output, then 0.5M cached input, all at gpt-5 prices.
+    assert_eq!(
+      by_project.rows[0].estimated_cost_usd,
+      Some(1.25 + 1.0 + 0.0625)
+    );
+
+    let by_day = build_usage_report(options("day", Some("2025-03-02"))).unwrap();
+    let keys: Vec<(&str, i64)> = by_day
+      .rows
+      .iter()
+      .map(|row| (row.key.as_str(), row.total_tokens))
+      .collect();
+    assert_eq!(keys, vec![("2025-03-02", 600_000)]);
+    assert!(by_day.csv.starts_with(
+      "day,sessions,input_tokens,cached_input_tokens,output_tokens,reasoning_output_tokens"
+    ));
+    assert!(
+      by_day
+        .csv
+        .ends_with("total,2,600000,500000,0,0,600000,0.062500\n"),
+      "{}",
+      by_day.csv
+    );
+  }
+}
@@ -0,0 +1,321 @@
+// ============================================================================
+// Watch mode (watchAndRun / stopWatch)
+// ============================================================================
+//
+// watchAndRun() watches a workspace and starts a run from `requestTemplate`
+// whenever files matching `globs` change, e.g. to re-run a review agent on
+// save. Changes are debounced into one batch, and the batch's paths are
+// appended to the template's prompt. Changes that arrive while a run is in
+// progress are suppressed rather than queued, so the agent's own edits never
+// start another run. The callback receives `watch.*` events alongside the
+// runs' thread events. stopWatch() ends the watcher and lets a run in
+// progress finish.
+
+const DEFAULT_WATCH_DEBOUNCE_MS: u32 = 500;
+/// Changed paths listed in a triggered run's prompt; the rest are counted.
+const MAX_WATCH_PROMPT_PATHS: usize = 50;
+
+#[napi(object)]
+pub struct WatchAndRunOptions {
+  /// Directory watched recursively.
+  pub root: String,
+  /// Globs relative to `root` selecting the files whose changes start a run. All files when
+  /// unset. Changes under `.git` never count.
+  pub globs: Option<Vec<String>>,
+  /// Quiet period after the last change before a run starts (default 500 ms).
+  #[napi(js_name = "debounceMs")]
+  pub debounce_ms: Option<u32>,
+  /// The run started for each batch of changes. Its prompt is followed by the changed paths.
+  #[napi(js_name = "requestTemplate")]
+  pub request_template: RunRequest,
+}
+
+fn watch_sessions() -> &'static Mutex<HashMap<String, notify::RecommendedWatcher>> {
+  static SESSIONS: OnceLock<Mutex<HashMap<String, notify::RecommendedWatcher>>> = OnceLock::new();
+  SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Decides which changed paths count, relative to the canonical root.
+struct WatchFilter {
+  root: PathBuf,
+  globs: Option<globset::GlobSet>,
+}
+
+impl WatchFilter {
+  fn compile(root: PathBuf, patterns: &[String]) -> napi::Result<Self> {
+    if patterns.is_empty() {
+      return Ok(Self { root, globs: None });
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+      let glob = globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| napi::Error::from_reason(format!("Invalid watch glob {pattern:?}: {e}")))?;
+      builder.add(glob);
+    }
+    let globs = builder
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Invalid watch globs: {e}")))?;
+    Ok(Self {
+      root,
+      globs: Some(globs),
+    })
+  }
+
+  /// `path` relative to the root when a change to it should start a run.
+  fn matches(&self, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(&self.root).ok()?;
+    if relative.as_os_str().is_empty()
+      || relative
+        .components()
+        .any(|component| component.as_os_str() == ".git")
+    {
+      return None;
+    }
+    if let Some(globs) = &self.globs
+      && !globs.is_match(relative)
+    {
+      return None;
+    }
+    Some(relative.to_string_lossy().replace('\\', "/"))
+  }
+}
+
+fn watch_prompt(prompt: &str, paths: &[String]) -> String {
+  let mut text = format!("{prompt}\n\nFiles changed:");
+  for path in paths.iter().take(MAX_WATCH_PROMPT_PATHS) {
+    text.push_str(&format!("\n- {path}"));
+  }
+  if paths.len() > MAX_WATCH_PROMPT_PATHS {
+    text.push_str(&format!(
+      "\n- and {} more",
+      paths.len() - MAX_WATCH_PROMPT_PATHS
+    ));
+  }
+  text
+}
+
+fn emit_watch_event(on_event: &ThreadsafeFunction<JsonValue>, event: JsonValue) {
+  let status = on_event.call(
+    Ok(JsonValue::String(event.to_string())),
+    ThreadsafeFunctionCallMode::NonBlocking,
+  );
+  if status != Status::Ok {
+    eprintln!("Failed to deliver watch event: {status}");
+  }
+}
+
+/// Run `template` for one batch of changes, forwarding its thread events.
+fn run_watch_batch(
+  run: u32,
+  paths: &[String],
+  template: &InternalRunRequest,
+  on_event: Arc<ThreadsafeFunction<JsonValue>>,
+) {
+  let mut options = template.clone();
+  options.prompt = watch_prompt(&options.prompt, paths);
+  let mut sequencer = ThreadEventSequencer::new(options.thread_id.clone());
+  let thread_id = Arc::new(Mutex::new(options.thread_id.clone()));
+  let thread_id_for_events = Arc::clone(&thread_id);
+  let on_event_for_run = Arc::clone(&on_event);
+  let result = run_with_session_hooks(options, move |event| {
+    if let ExecThreadEvent::ThreadStarted(started) = &event
+      && let Ok(mut slot) = thread_id_for_events.lock()
+    {
+      *slot = Some(started.thread_id.clone());
+    }
+    match event_to_json(&event) {
+      Ok(value) => emit_watch_event(&on_event_for_run, sequencer.stamp(&event, value)),
+      Err(err) => eprintln!("Failed to serialize watch run event: {err}"),
+    }
+  });
+  emit_watch_event(
+    &on_event,
+    json!({
+      "type": "watch.run_completed",
+      "run": run,
+      "thread_id": thread_id.lock().ok().and_then(|slot| slot.clone()),
+      "error": result.err().map(|err| err.reason),
+    }),
+  );
+}
+
+fn watch_loop(
+  events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+  filter: WatchFilter,
+  debounce: std::time::Duration,
+  template: Arc<InternalRunRequest>,
+  on_event: Arc<ThreadsafeFunction<JsonValue>>,
+) {
+  let running = Arc::new(std::sync::atomic::AtomicBool::new(false));
+  let mut pending = std::collections::BTreeSet::new();
+  let mut last_change: Option<std::time::Instant> = None;
+  let mut runs = 0u32;
+  loop {
+    let timeout = last_change.map_or(std::time::Duration::from_secs(3600), |at| {
+      debounce.saturating_sub(at.elapsed())
+    });
+    match events.recv_timeout(timeout) {
+      Ok(Ok(event)) => {
+        let relevant = match event.kind {
+          notify::EventKind::Access(_) => false,
+          notify::EventKind::Any
+          | notify::EventKind::Create(_)
+          | notify::EventKind::Modify(_)
+          | notify::EventKind::Remove(_)
+          | notify::EventKind::Other => true,
+        };
+        if relevant {
+          for path in event.paths.iter().filter_map(|path| filter.matches(path)) {
+            pending.insert(path);
+            last_change = Some(std::time::Instant::now());
+          }
+        }
+      }
+      Ok(Err(err)) => emit_watch_event(
+        &on_event,
+        json!({ "type": "watch.error", "message": err.to_string() }),
+      ),
+      Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+      // stopWatch() dropped the watcher.
+      Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+    }
+
+    let Some(at) = last_change else {
+      continue;
+    };
+    if at.elapsed() < debounce {
+      continue;
+    }
+    last_change = None;
+    let paths: Vec<String> = std::mem::take(&mut pending).into_iter().collect();
+    if running.swap(true, std::sync::atomic::Ordering::SeqCst) {
+      emit_watch_event(
+        &on_event,
+        json!({ "type": "watch.suppressed", "paths": paths }),
+      );
+      continue;
+    }
+    runs += 1;
+    let run = runs;
+    emit_watch_event(
+      &on_event,
+      json!({ "type": "watch.run_started", "run": run, "paths": paths }),
+    );
+    let template = Arc::clone(&template);
+    let on_event_for_run = Arc::clone(&on_event);
+    let running_for_run = Arc::clone(&running);
+    let spawned = std::thread::Builder::new()
+      .name("codex-watch-run".to_string())
+      .spawn(move || {
+        run_watch_batch(run, &paths, &template, on_event_for_run);
+        running_for_run.store(false, std::sync::atomic::Ordering::SeqCst);
+      });
+    if let Err(err) = spawned {
+      running.store(false, std::sync::atomic::Ordering::SeqCst);
+      emit_watch_event(
+        &on_event,
+        json!({ "type": "watch.error", "message": format!("Failed to start run: {err}") }),
+      );
+    }
+  }
+  emit_watch_event(&on_event, json!({ "type": "watch.stopped" }));
+}
+
+/// Watch `options.root` and start a run whenever matching files change. Returns the watcher's
+/// id for stopWatch().
+#[napi]
+pub fn watch_and_run(
+  options: WatchAndRunOptions,
+  #[napi(ts_arg_type = "(err: unknown, eventJson?: string) => void")] on_event: ThreadsafeFunction<
+    JsonValue,
+  >,
+) -> napi::Result<String> {
+  use notify::Watcher;
+
+  let root = std::fs::canonicalize(&options.root).map_err(|e| {
+    napi::Error::from_reason(format!(
+      "Failed to resolve watch root {}: {e}",
+      options.root
+    ))
+  })?;
+  let filter = WatchFilter::compile(root.clone(), &options.globs.unwrap_or_default())?;
+  let debounce = std::time::Duration::from_millis(u64::from(
+    options.debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS),
+  ));
+  let template = Arc::new(options.request_template.into_internal()?);
+
+  let (tx, rx) = std::sync::mpsc::channel();
+  let mut watcher = notify::recommended_watcher(move |event| {
+    let _ = tx.send(event);
+  })
+  .map_err(|e| napi::Error::from_reason(format!("Failed to create file watcher: {e}")))?;
+  watcher
+    .watch(&root, notify::RecursiveMode::Recursive)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to watch {}: {e}", root.display())))?;
+
+  let id = Uuid::new_v4().to_string();
+  let on_event = Arc::new(on_event);
+  emit_watch_event(
+    &on_event,
+    json!({ "type": "watch.started", "id": id, "root": root.to_string_lossy() }),
+  );
+  std::thread::Builder::new()
+    .name("codex-watch".to_string())
+    .spawn(move || watch_loop(rx, filter, debounce, template, on_event))
+    .map_err(|e| napi::Error::from_reason(format!("Failed to spawn file watcher: {e}")))?;
+  watch_sessions()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("watch sessions mutex poisoned: {e}")))?
+    .insert(id.clone(), watcher);
+  Ok(id)
+}
+
+/// Stop a watcher started by watchAndRun(). A run in progress finishes. Returns `false` for
+/// unknown ids.
+#[napi]
+pub fn stop_watch(id: String) -> napi::Result<bool> {
+  let watcher = watch_sessions()
+    .lock()
+    .map_err(|e| napi::Error::from_reason(format!("watch sessions mutex poisoned: {e}")))?
+    .remove(&id);
+  Ok(watcher.is_some())
+}
+
+#[cfg(test)]
+mod tests_watch {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn filter_matches_globs_and_skips_git() {
+    let filter = WatchFilter::compile(
+      PathBuf::from("/repo"),
+      &["src/**/*.rs".to_string(), "*.toml".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+      filter.matches(Path::new("/repo/src/run/mod.rs")),
+      Some("src/run/mod.rs".to_string())
+    );
+    assert_eq!(
+      filter.matches(Path::new("/repo/Cargo.toml")),
+      Some("Cargo.toml".to_string())
+    );
+    assert_eq!(filter.matches(Path::new("/repo/docs/Cargo.toml")), None);
+    assert_eq!(filter.matches(Path::new("/elsewhere/src/lib.rs")), None);
+
+    let everything = WatchFilter::compile(PathBuf::from("/repo"), &[]).unwrap();
+    assert_eq!(everything.matches(Path::new("/repo/.git/index")), None);
+    assert_eq!(
+      everything.matches(Path::new("/repo/README.md")),
+      Some("README.md".to_string())
+    );
+
+    let paths: Vec<String> = (0..52).map(|index| format!("src/file{index}.rs")).collect();
+    let prompt = watch_prompt("Review the change.", &paths);
+    assert!(prompt.starts_with("Review the change.\n\nFiles changed:\n- src/file0.rs\n"));
+    assert!(prompt.ends_with("\n- src/file49.rs\n- and 2 more"));
+  }
+}
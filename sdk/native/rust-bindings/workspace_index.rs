@@ -0,0 +1,398 @@
+// ============================================================================
+// Workspace semantic index (workspaceIndexBuild / workspaceSearchSemantic)
+// ============================================================================
+//
+// Code memory to go with reverie's conversation memory. workspaceIndexBuild()
+// lists a root's files the way searchWorkspace() does (ignore files honoured),
+// splits them into overlapping line windows, embeds every window with the
+// FastEmbed model from fastEmbedInit() and persists the vectors under
+// `$CODEX_HOME/workspace-index/`. workspaceSearchSemantic() embeds a query and
+// ranks the stored chunks by cosine similarity. Rebuilding is cheap for
+// unchanged code because embeddings go through the FastEmbed cache.
+
+const WORKSPACE_INDEX_VERSION: u32 = 1;
+const DEFAULT_CHUNK_LINES: u32 = 60;
+const DEFAULT_CHUNK_OVERLAP_LINES: u32 = 10;
+const DEFAULT_MAX_INDEXED_FILE_BYTES: u32 = 512 * 1024;
+const WORKSPACE_INDEX_EMBED_BATCH: usize = 64;
+
+#[derive(Clone, Debug, Default)]
+#[napi(object)]
+pub struct WorkspaceIndexChunking {
+  /// Lines per chunk. Defaults to 60.
+  #[napi(js_name = "maxLines")]
+  pub max_lines: Option<u32>,
+  /// Lines shared by consecutive chunks. Defaults to 10.
+  #[napi(js_name = "overlapLines")]
+  pub overlap_lines: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[napi(object)]
+pub struct WorkspaceIndexBuildOptions {
+  /// ripgrep globs selecting the files to index, e.g. `*.rs` or `!vendor/**`.
+  pub globs: Option<Vec<String>>,
+  pub chunking: Option<WorkspaceIndexChunking>,
+  /// Larger files are skipped. Defaults to 512 KiB.
+  #[napi(js_name = "maxFileBytes")]
+  pub max_file_bytes: Option<u32>,
+  #[napi(js_name = "batchSize")]
+  pub batch_size: Option<u32>,
+}
+
+#[napi(object)]
+pub struct WorkspaceIndexStats {
+  #[napi(js_name = "indexPath")]
+  pub index_path: String,
+  #[napi(js_name = "filesIndexed")]
+  pub files_indexed: u32,
+  #[napi(js_name = "filesSkipped")]
+  pub files_skipped: u32,
+  #[napi(js_name = "chunksEmbedded")]
+  pub chunks_embedded: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+#[napi(object)]
+pub struct WorkspaceSemanticSearchOptions {
+  /// Defaults to 10.
+  pub limit: Option<u32>,
+  /// Chunks scoring below this cosine similarity are dropped.
+  #[napi(js_name = "minScore")]
+  pub min_score: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct WorkspaceChunkMatch {
+  /// Path relative to the indexed root.
+  pub path: String,
+  #[napi(js_name = "startLine")]
+  pub start_line: u32,
+  #[napi(js_name = "endLine")]
+  pub end_line: u32,
+  pub text: String,
+  pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct IndexedChunk {
+  path: String,
+  start_line: u32,
+  end_line: u32,
+  text: String,
+  vector: Vec<f32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct WorkspaceIndexFile {
+  version: u32,
+  root: String,
+  /// FastEmbed namespace of the model that produced the vectors.
+  embedding_namespace: String,
+  chunks: Vec<IndexedChunk>,
+}
+
+fn workspace_index_path(root: &str) -> napi::Result<PathBuf> {
+  let codex_home = resolve_codex_home_for_cache().ok_or_else(|| {
+    napi::Error::from_reason("Unable to resolve CODEX_HOME for the workspace index")
+  })?;
+  Ok(
+    codex_home
+      .join("workspace-index")
+      .join(format!("{}.json", hash_string(root))),
+  )
+}
+
+fn fast_embed_namespace() -> napi::Result<String> {
+  FAST_EMBED_STATE
+    .get()
+    .map(|state| state.namespace.clone())
+    .ok_or_else(|| {
+      napi::Error::from_reason("FastEmbed not initialised; call fastEmbedInit() first")
+    })
+}
+
+/// Files under `root` that ripgrep would search, relative to `root`.
+fn workspace_index_files(root: &Path, globs: &[String]) -> napi::Result<Vec<String>> {
+  let mut command = std::process::Command::new("rg");
+  command.arg("--files").arg("--no-messages");
+  for glob in globs {
+    command.arg("--glob").arg(glob);
+  }
+  let output = command.current_dir(root).output().map_err(|err| {
+    napi::Error::from_reason(format!(
+      "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
+    ))
+  })?;
+  if output.status.code().is_some_and(|code| code > 1) {
+    return Err(napi::Error::from_reason(format!(
+      "rg failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    )));
+  }
+  let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .map(|line| line.strip_prefix("./").unwrap_or(line).to_string())
+    .collect();
+  files.sort_unstable();
+  Ok(files)
+}
+
+/// Overlapping windows of `max_lines` lines as `(start_line, end_line, text)`, 1-based and
+/// inclusive. Windows with nothing but whitespace are dropped.
+fn chunk_lines(contents: &str, max_lines: usize, overlap_lines: usize) -> Vec<(u32, u32, String)> {
+  let lines: Vec<&str> = contents.lines().collect();
+  let step = max_lines.saturating_sub(overlap_lines).max(1);
+  let mut chunks = Vec::new();
+  let mut start = 0;
+  while start < lines.len() {
+    let end = (start + max_lines).min(lines.len());
+    let text = lines[start..end].join("\n");
+    if !text.trim().is_empty() {
+      chunks.push((start as u32 + 1, end as u32, text));
+    }
+    if end == lines.len() {
+      break;
+    }
+    start += step;
+  }
+  chunks
+}
+
+#[napi]
+pub async fn workspace_index_build(
+  root: String,
+  options: Option<WorkspaceIndexBuildOptions>,
+) -> napi::Result<WorkspaceIndexStats> {
+  let namespace = fast_embed_namespace()?;
+  let opts = options.unwrap_or_default();
+  let chunking = opts.chunking.clone().unwrap_or_default();
+  let max_lines = chunking.max_lines.unwrap_or(DEFAULT_CHUNK_LINES).max(1) as usize;
+  let overlap_lines = chunking
+    .overlap_lines
+    .unwrap_or(DEFAULT_CHUNK_OVERLAP_LINES) as usize;
+  if overlap_lines >= max_lines {
+    return Err(napi::Error::from_reason(
+      "chunking.overlapLines must be smaller than chunking.maxLines",
+    ));
+  }
+  let max_file_bytes = u64::from(
+    opts
+      .max_file_bytes
+      .unwrap_or(DEFAULT_MAX_INDEXED_FILE_BYTES),
+  );
+  let root = canonicalize_to_string(Path::new(&root));
+  let root_path = PathBuf::from(&root);
+  if !root_path.is_dir() {
+    return Err(napi::Error::from_reason(format!(
+      "{root} is not a directory"
+    )));
+  }
+
+  let globs = opts.globs.clone().unwrap_or_default();
+  let (pending, files_indexed, files_skipped) = tokio::task::spawn_blocking(move || {
+    let mut pending = Vec::new();
+    let (mut indexed, mut skipped) = (0u32, 0u32);
+    for path in workspace_index_files(&root_path, &globs)? {
+      let full_path = root_path.join(&path);
+      let readable = std::fs::metadata(&full_path)
+        .ok()
+        .filter(|meta| meta.len() <= max_file_bytes)
+        .and_then(|_| std::fs::read(&full_path).ok())
+        .filter(|bytes| !bytes.contains(&0))
+        .and_then(|bytes| String::from_utf8(bytes).ok());
+      let Some(contents) = readable else {
+        skipped += 1;
+        continue;
+      };
+      indexed += 1;
+      for (start_line, end_line, text) in chunk_lines(&contents, max_lines, overlap_lines) {
+        pending.push((path.clone(), start_line, end_line, text));
+      }
+    }
+    Ok::<_, napi::Error>((pending, indexed, skipped))
+  })
+  .await
+  .map_err(|err| napi::Error::from_reason(format!("workspace index task failed: {err}")))??;
+
+  let mut chunks = Vec::with_capacity(pending.len());
+  for batch in pending.chunks(WORKSPACE_INDEX_EMBED_BATCH) {
+    let vectors = fast_embed_embed(FastEmbedEmbedRequest {
+      // The path leads each input so identical snippets in different files stay distinct.
+      inputs: batch
+        .iter()
+        .map(|(path, _, _, text)| format!("{path}\n{text}"))
+        .collect(),
+      batch_size: opts.batch_size,
+      normalize: Some(true),
+      project_root: Some(root.clone()),
+      cache: Some(true),
+    })
+    .await?;
+    for ((path, start_line, end_line, text), vector) in batch.iter().cloned().zip(vectors) {
+      chunks.push(IndexedChunk {
+        path,
+        start_line,
+        end_line,
+        text,
+        vector,
+      });
+    }
+  }
+
+  let index_path = workspace_index_path(&root)?;
+  let chunks_embedded = chunks.len() as u32;
+  let index = WorkspaceIndexFile {
+    version: WORKSPACE_INDEX_VERSION,
+    root,
+    embedding_namespace: namespace,
+    chunks,
+  };
+  let payload = serde_json::to_vec(&index).map_err(|err| {
+    napi::Error::from_reason(format!("failed to serialize workspace index: {err}"))
+  })?;
+  if let Some(parent) = index_path.parent() {
+    std::fs::create_dir_all(parent).map_err(|err| {
+      napi::Error::from_reason(format!("failed to create {}: {err}", parent.display()))
+    })?;
+  }
+  let temp_path = index_path.with_extension(format!("json.tmp-{}", Uuid::new_v4()));
+  std::fs::write(&temp_path, payload)
+    .and_then(|()| std::fs::rename(&temp_path, &index_path))
+    .map_err(|err| {
+      let _ = std::fs::remove_file(&temp_path);
+      napi::Error::from_reason(format!("failed to write {}: {err}", index_path.display()))
+    })?;
+
+  Ok(WorkspaceIndexStats {
+    index_path: index_path.to_string_lossy().into_owned(),
+    files_indexed,
+    files_skipped,
+    chunks_embedded,
+  })
+}
+
+fn rank_chunks(
+  chunks: Vec<IndexedChunk>,
+  query: &[f32],
+  limit: usize,
+  min_score: Option<f64>,
+) -> Vec<WorkspaceChunkMatch> {
+  let mut scored: Vec<WorkspaceChunkMatch> = chunks
+    .into_iter()
+    .map(|chunk| {
+      // Both sides are normalised, so the dot product is the cosine similarity.
+      let score = chunk
+        .vector
+        .iter()
+        .zip(query)
+        .map(|(a, b)| f64::from(*a) * f64::from(*b))
+        .sum::<f64>();
+      WorkspaceChunkMatch {
+        path: chunk.path,
+        start_line: chunk.start_line,
+        end_line: chunk.end_line,
+        text: chunk.text,
+        score,
+      }
+    })
+    .filter(|found| min_score.is_none_or(|min| found.score >= min))
+    .collect();
+  scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+  scored.truncate(limit);
+  scored
+}
+
+#[napi]
+pub async fn workspace_search_semantic(
+  root: String,
+  query: String,
+  options: Option<WorkspaceSemanticSearchOptions>,
+) -> napi::Result<Vec<WorkspaceChunkMatch>> {
+  let query = query.trim().to_string();
+  if query.is_empty() {
+    return Ok(Vec::new());
+  }
+  let namespace = fast_embed_namespace()?;
+  let opts = options.unwrap_or_default();
+  let root = canonicalize_to_string(Path::new(&root));
+  let index_path = workspace_index_path(&root)?;
+  let bytes = match std::fs::read(&index_path) {
+    Ok(bytes) => bytes,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+      return Err(napi::Error::from_reason(format!(
+        "No workspace index for {root}; call workspaceIndexBuild() first"
+      )));
+    }
+    Err(err) => {
+      return Err(napi::Error::from_reason(format!(
+        "failed to read {}: {err}",
+        index_path.display()
+      )));
+    }
+  };
+  let index: WorkspaceIndexFile = serde_json::from_slice(&bytes)
+    .map_err(|err| napi::Error::from_reason(format!("failed to parse workspace index: {err}")))?;
+  if index.version != WORKSPACE_INDEX_VERSION || index.embedding_namespace != namespace {
+    return Err(napi::Error::from_reason(format!(
+      "The workspace index for {root} was built with a different embedding model or format; rebuild it with workspaceIndexBuild()"
+    )));
+  }
+  let query_vector = fast_embed_embed(FastEmbedEmbedRequest {
+    inputs: vec![query],
+    batch_size: None,
+    normalize: Some(true),
+    project_root: Some(root),
+    cache: Some(false),
+  })
+  .await?
+  .pop()
+  .ok_or_else(|| napi::Error::from_reason("FastEmbed returned no query embedding"))?;
+  let limit = opts.limit.unwrap_or(10).max(1) as usize;
+  Ok(rank_chunks(
+    index.chunks,
+    &query_vector,
+    limit,
+    opts.min_score,
+  ))
+}
+
+#[cfg(test)]
+mod tests_workspace_index {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn chunks_overlap_and_chunks_rank_by_similarity() {
+    let contents = "one\ntwo\nthree\nfour\nfive\n";
+    assert_eq!(
+      chunk_lines(contents, 3, 1),
+      vec![
+        (1, 3, "one\ntwo\nthree".to_string()),
+        (3, 5, "three\nfour\nfive".to_string()),
+      ]
+    );
+    assert_eq!(chunk_lines("\n\n", 3, 1), Vec::new());
+
+    let chunk = |path: &str, vector: Vec<f32>| IndexedChunk {
+      path: path.to_string(),
+      start_line: 1,
+      end_line: 1,
+      text: String::new(),
+      vector,
+    };
+    let ranked = rank_chunks(
+      vec![
+        chunk("far.rs", vec![0.0, 1.0]),
+        chunk("near.rs", vec![1.0, 0.0]),
+        chunk("mid.rs", vec![0.6, 0.8]),
+      ],
+      &[1.0, 0.0],
+      2,
+      Some(0.5),
+    );
+    let paths: Vec<&str> = ranked.iter().map(|found| found.path.as_str()).collect();
+    assert_eq!(paths, vec!["near.rs", "mid.rs"]);
+  }
+}
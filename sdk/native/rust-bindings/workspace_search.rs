@@ -0,0 +1,229 @@
+// ============================================================================
+// Workspace search (searchWorkspace)
+// ============================================================================
+//
+// The same ripgrep search the agent's grep tool runs, exposed for host UIs so
+// they don't bundle a second grep implementation. Results honour .gitignore
+// and friends, carry 1-based line numbers and byte ranges of every match
+// within the line, and stop at `maxResults`.
+
+const DEFAULT_WORKSPACE_SEARCH_RESULTS: u32 = 200;
+const MAX_WORKSPACE_SEARCH_RESULTS: u32 = 5_000;
+/// Matched lines longer than this are cut, so minified files don't flood the result.
+const MAX_WORKSPACE_SNIPPET_BYTES: usize = 500;
+
+#[napi(object)]
+pub struct SearchWorkspaceOptions {
+  /// Directory to search. Defaults to the process cwd.
+  pub root: Option<String>,
+  /// A regular expression, or a literal with `fixedStrings`.
+  pub query: String,
+  /// ripgrep globs, e.g. `*.ts` or `!dist/**`.
+  pub globs: Option<Vec<String>>,
+  #[napi(js_name = "maxResults")]
+  pub max_results: Option<u32>,
+  #[napi(js_name = "fixedStrings")]
+  pub fixed_strings: Option<bool>,
+  #[napi(js_name = "caseSensitive")]
+  pub case_sensitive: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct WorkspaceMatchRange {
+  /// Byte offset of the match within `text`.
+  pub start: u32,
+  pub end: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[napi(object)]
+pub struct WorkspaceMatch {
+  /// Path relative to the search root.
+  pub path: String,
+  #[napi(js_name = "lineNumber")]
+  pub line_number: u32,
+  /// 1-based byte column of the first match.
+  pub column: u32,
+  /// The matched line, without its line ending.
+  pub text: String,
+  pub ranges: Vec<WorkspaceMatchRange>,
+}
+
+#[napi(object)]
+pub struct SearchWorkspaceResult {
+  pub matches: Vec<WorkspaceMatch>,
+  /// Whether the search stopped at `maxResults` before finishing.
+  pub truncated: bool,
+}
+
+#[napi]
+pub async fn search_workspace(
+  options: SearchWorkspaceOptions,
+) -> napi::Result<SearchWorkspaceResult> {
+  if options.query.is_empty() {
+    return Err(napi::Error::from_reason("query must not be empty"));
+  }
+  let root = match options.root.as_deref() {
+    Some(root) => PathBuf::from(root),
+    None => std::env::current_dir()
+      .map_err(|err| napi::Error::from_reason(format!("failed to read current dir: {err}")))?,
+  };
+  if !root.is_dir() {
+    return Err(napi::Error::from_reason(format!(
+      "search root {} is not a directory",
+      root.display()
+    )));
+  }
+  let max_results = options
+    .max_results
+    .unwrap_or(DEFAULT_WORKSPACE_SEARCH_RESULTS)
+    .clamp(1, MAX_WORKSPACE_SEARCH_RESULTS) as usize;
+
+  let mut command = std::process::Command::new("rg");
+  command
+    .arg("--json")
+    .arg("--no-messages")
+    .arg(if options.case_sensitive.unwrap_or(false) {
+      "--case-sensitive"
+    } else {
+      "--smart-case"
+    });
+  if options.fixed_strings.unwrap_or(false) {
+    command.arg("--fixed-strings");
+  }
+  for glob in options.globs.iter().flatten() {
+    command.arg("--glob").arg(glob);
+  }
+  command
+    .arg("--regexp")
+    .arg(&options.query)
+    .arg("--")
+    .arg(".")
+    .current_dir(&root)
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped());
+
+  tokio::task::spawn_blocking(move || run_workspace_search(command, max_results))
+    .await
+    .map_err(|err| napi::Error::from_reason(format!("workspace search task failed: {err}")))?
+}
+
+fn run_workspace_search(
+  mut command: std::process::Command,
+  max_results: usize,
+) -> napi::Result<SearchWorkspaceResult> {
+  use std::io::BufRead;
+  use std::io::Read;
+
+  let mut child = command.spawn().map_err(|err| {
+    napi::Error::from_reason(format!(
+      "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
+    ))
+  })?;
+  let stdout = child
+    .stdout
+    .take()
+    .ok_or_else(|| napi::Error::from_reason("rg stdout unavailable"))?;
+  let mut matches = Vec::new();
+  let mut truncated = false;
+  for line in std::io::BufReader::new(stdout).lines() {
+    let line =
+      line.map_err(|err| napi::Error::from_reason(format!("failed to read rg output: {err}")))?;
+    let Some(found) = parse_rg_match(&line) else {
+      continue;
+    };
+    if matches.len() == max_results {
+      truncated = true;
+      break;
+    }
+    matches.push(found);
+  }
+  if truncated {
+    let _ = child.kill();
+    let _ = child.wait();
+    return Ok(SearchWorkspaceResult { matches, truncated });
+  }
+  let status = child
+    .wait()
+    .map_err(|err| napi::Error::from_reason(format!("failed to wait for rg: {err}")))?;
+  // Exit code 1 means no matches; anything else past 1 is a real failure.
+  if status.code().is_some_and(|code| code > 1) {
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+      let _ = pipe.read_to_string(&mut stderr);
+    }
+    return Err(napi::Error::from_reason(format!(
+      "rg failed: {}",
+      stderr.trim()
+    )));
+  }
+  Ok(SearchWorkspaceResult { matches, truncated })
+}
+
+/// One `match` message of `rg --json`; other message types and non-UTF-8 paths yield `None`.
+fn parse_rg_match(line: &str) -> Option<WorkspaceMatch> {
+  let message: JsonValue = serde_json::from_str(line).ok()?;
+  if message.get("type")?.as_str()? != "match" {
+    return None;
+  }
+  let data = message.get("data")?;
+  let path = data.get("path")?.get("text")?.as_str()?;
+  let path = path.strip_prefix("./").unwrap_or(path).to_string();
+  let line_number = data.get("line_number")?.as_u64()? as u32;
+  let raw = data.get("lines")?.get("text")?.as_str()?;
+  let raw = raw.strip_suffix('\n').unwrap_or(raw);
+  let raw = raw.strip_suffix('\r').unwrap_or(raw);
+  let mut cut = raw.len().min(MAX_WORKSPACE_SNIPPET_BYTES);
+  while !raw.is_char_boundary(cut) {
+    cut -= 1;
+  }
+  let text = raw[..cut].to_string();
+  let ranges: Vec<WorkspaceMatchRange> = data
+    .get("submatches")?
+    .as_array()?
+    .iter()
+    .filter_map(|submatch| {
+      let start = submatch.get("start")?.as_u64()? as u32;
+      let end = submatch.get("end")?.as_u64()? as u32;
+      Some(WorkspaceMatchRange { start, end })
+    })
+    .collect();
+  let column = ranges.first().map_or(1, |range| range.start + 1);
+  Some(WorkspaceMatch {
+    path,
+    line_number,
+    column,
+    text,
+    ranges,
+  })
+}
+
+#[cfg(test)]
+mod tests_workspace_search {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn parses_rg_json_matches() {
+    let line = r#"{"type":"match","data":{"path":{"text":"./src/main.rs"},"lines":{"text":"fn main() { main_loop(); }\n"},"line_number":3,"absolute_offset":40,"submatches":[{"match":{"text":"main"},"start":3,"end":7},{"match":{"text":"main"},"start":12,"end":16}]}}"#;
+    assert_eq!(
+      parse_rg_match(line),
+      Some(WorkspaceMatch {
+        path: "src/main.rs".to_string(),
+        line_number: 3,
+        column: 4,
+        text: "fn main() { main_loop(); }".to_string(),
+        ranges: vec![
+          WorkspaceMatchRange { start: 3, end: 7 },
+          WorkspaceMatchRange { start: 12, end: 16 },
+        ],
+      })
+    );
+    assert_eq!(
+      parse_rg_match(r#"{"type":"begin","data":{"path":{"text":"./src/main.rs"}}}"#),
+      None
+    );
+  }
+}
@@ -1,39 +1,49 @@
 import { getNativeBinding } from "./nativeBinding";
 
-// Types reflect the Rust JSON (snake_case) to avoid extra transforms.
-export type CloudTaskStatus = "pending" | "ready" | "applied" | "error";
-
-export type DiffSummary = {
-  files_changed: number;
-  lines_added: number;
-  lines_removed: number;
+import type {
+  NativeCloudApplyOutcome,
+  NativeCloudAttemptStatus,
+  NativeCloudTaskApplyResultOptions,
+  NativeCloudTaskAwaitOptions,
+  NativeCloudTaskCreated,
+  NativeCloudTaskDetails,
+  NativeCloudTaskListPage,
+  NativeCloudTaskStatus,
+  NativeCloudTaskSummary,
+  NativeGitMergeConflict,
+} from "./nativeBinding";
+
+export type CloudTaskStatus = NativeCloudTaskStatus;
+
+export type DiffSummary = NativeCloudTaskSummary["summary"];
+
+export type CloudTaskSummary = NativeCloudTaskSummary;
+
+export type CloudTaskListPage = NativeCloudTaskListPage;
+
+export type CloudTaskListOptions = {
+  env?: string;
+  limit?: number;
+  cursor?: string;
+  /** Keep only tasks in this state; filtering happens per page, so pages may come back short. */
+  status?: CloudTaskStatus;
 };
 
-export type CloudTaskSummary = {
-  id: string; // serde transparent TaskId
-  title: string;
-  status: CloudTaskStatus;
-  updated_at: string; // ISO timestamp
-  environment_id?: string | null;
-  environment_label?: string | null;
-  summary: DiffSummary;
-  is_review?: boolean;
-  attempt_total?: number | null;
-};
+export type CloudAttemptStatus = NativeCloudAttemptStatus;
 
-export type CloudApplyStatus = "success" | "partial" | "error";
+export type CloudTaskDetails = NativeCloudTaskDetails;
 
-export type CloudApplyOutcome = {
-  applied: boolean;
-  status: CloudApplyStatus;
-  message: string;
-  skipped_paths: string[];
-  conflict_paths: string[];
-};
+export type CloudTaskAwaitOptions = NativeCloudTaskAwaitOptions;
 
-export type CloudTaskCreateResult = {
-  id: string;
-};
+export type CloudApplyStatus = NativeCloudApplyOutcome["status"];
+
+export type CloudApplyOutcome = NativeCloudApplyOutcome;
+
+export type CloudApplyConflict = NativeGitMergeConflict;
+
+export type CloudTaskApplyResultOptions = NativeCloudTaskApplyResultOptions;
+
+export type CloudTaskCreateResult = NativeCloudTaskCreated;
 
 export type CloudTasksOptions = {
   baseUrl?: string;
@@ -50,10 +60,40 @@ export class CloudTasks {
   }
 
   async list(env?: string): Promise<CloudTaskSummary[]> {
+    const page = await this.listPage({ env });
+    return page.tasks;
+  }
+
+  async listPage(options: CloudTaskListOptions = {}): Promise<CloudTaskListPage> {
     const b = this.binding();
     if (!b.cloudTasksList) throw new Error("cloudTasksList is not available in this build");
-    const json = await b.cloudTasksList(env, this.options.baseUrl, this.options.apiKey);
-    return JSON.parse(json) as CloudTaskSummary[];
+    return b.cloudTasksList(
+      options.env,
+      options.limit,
+      options.cursor,
+      this.options.baseUrl,
+      this.options.apiKey,
+      options.status,
+    );
+  }
+
+  async get(taskId: string): Promise<CloudTaskDetails> {
+    const b = this.binding();
+    if (!b.cloudTasksGet) throw new Error("cloudTasksGet is not available in this build");
+    return b.cloudTasksGet(taskId, this.options.baseUrl, this.options.apiKey);
+  }
+
+  async cancel(taskId: string): Promise<CloudTaskSummary> {
+    const b = this.binding();
+    if (!b.cloudTasksCancel) throw new Error("cloudTasksCancel is not available in this build");
+    return b.cloudTasksCancel(taskId, this.options.baseUrl, this.options.apiKey);
+  }
+
+  /** Polls until the task is no longer pending; rejects after `timeoutMs` (default 30 minutes). */
+  async wait(taskId: string, options?: CloudTaskAwaitOptions): Promise<CloudTaskSummary> {
+    const b = this.binding();
+    if (!b.cloudTasksAwait) throw new Error("cloudTasksAwait is not available in this build");
+    return b.cloudTasksAwait(taskId, options, this.options.baseUrl, this.options.apiKey);
   }
 
   async getDiff(taskId: string): Promise<string | null> {
@@ -69,25 +109,38 @@ export class CloudTasks {
     if (!b.cloudTasksApplyPreflight) {
       throw new Error("cloudTasksApplyPreflight is not available in this build");
     }
-    const json = await b.cloudTasksApplyPreflight(
+    return b.cloudTasksApplyPreflight(
       taskId,
       diffOverride,
       this.options.baseUrl,
       this.options.apiKey,
     );
-    return JSON.parse(json) as CloudApplyOutcome;
   }
 
   async apply(taskId: string, diffOverride?: string): Promise<CloudApplyOutcome> {
     const b = this.binding();
     if (!b.cloudTasksApply) throw new Error("cloudTasksApply is not available in this build");
-    const json = await b.cloudTasksApply(
+    return b.cloudTasksApply(
       taskId,
       diffOverride,
       this.options.baseUrl,
       this.options.apiKey,
     );
-    return JSON.parse(json) as CloudApplyOutcome;
+  }
+
+  /**
+   * Downloads the task's diff and applies it under `cwd`, on a new branch when `createBranch`
+   * is set. Nothing changes, including the branch, unless the diff applies cleanly.
+   */
+  async applyResult(
+    taskId: string,
+    options?: CloudTaskApplyResultOptions,
+  ): Promise<CloudApplyOutcome> {
+    const b = this.binding();
+    if (!b.cloudTasksApplyResult) {
+      throw new Error("cloudTasksApplyResult is not available in this build");
+    }
+    return b.cloudTasksApplyResult(taskId, options, this.options.baseUrl, this.options.apiKey);
   }
 
   async create(
@@ -97,7 +150,7 @@ export class CloudTasks {
   ): Promise<CloudTaskCreateResult> {
     const b = this.binding();
     if (!b.cloudTasksCreate) throw new Error("cloudTasksCreate is not available in this build");
-    const json = await b.cloudTasksCreate(
+    return b.cloudTasksCreate(
       envId,
       prompt,
       opts?.gitRef,
@@ -106,7 +159,6 @@ export class CloudTasks {
       this.options.baseUrl,
       this.options.apiKey,
     );
-    return JSON.parse(json) as CloudTaskCreateResult;
   }
 }
 
@@ -3,14 +3,20 @@ import { CodexExec } from "./exec";
 import {
   NativeBinding,
   getNativeBinding,
+  finalizeInterruptedRun,
   NativeToolInvocation,
   NativeToolResult,
   NativeToolInterceptorNativeContext,
+  NativeHookEvent,
+  NativeHookContext,
+  NativeHookResult,
   ApprovalRequest,
   NativeToolInfo,
 } from "./nativeBinding";
 import type {
   NativeConversationConfig,
+  NativeInterruptedRun,
+  NativeThreadEdits,
   NativeConversationListPage,
   NativeConversationSummary,
 } from "./nativeBinding";
@@ -41,6 +47,8 @@ export type ConversationListOptions = ThreadOptions & {
   pageSize?: number;
   cursor?: string;
   modelProviders?: string[];
+  /** Only conversations recorded in exactly this working directory. */
+  conversationCwd?: string;
 };
 
 export type { ApprovalRequest } from "./nativeBinding";
@@ -191,7 +199,26 @@ export class Codex {
   }
 
   /**
-   * Clear all registered tools, restoring built-in defaults.
+   * Register a lifecycle hook. `pre_turn` and `pre_tool_call` hooks can replace the prompt or
+   * the tool arguments (or reject the call); `post_turn` and `post_tool_call` hooks can annotate
+   * results; `session_end` hooks run once a run is over. Hooks apply to every thread.
+   */
+  registerHook(
+    event: NativeHookEvent,
+    handler: (context: NativeHookContext) => NativeHookResult | void | Promise<NativeHookResult | void>,
+  ): void {
+    if (!this.nativeBinding) {
+      throw new Error("Native hook registration requires the NAPI binding");
+    }
+    if (typeof this.nativeBinding.registerHook !== "function") {
+      console.warn("registerHook is not available in this build - hooks feature may be incomplete");
+      return;
+    }
+    this.nativeBinding.registerHook(event, handler);
+  }
+
+  /**
+   * Clear all registered tools, interceptors and hooks, restoring built-in defaults.
    */
   clearTools(): void {
     if (!this.nativeBinding) {
@@ -378,6 +405,7 @@ export class Codex {
       pageSize: options.pageSize,
       cursor: options.cursor,
       modelProviders: options.modelProviders,
+      cwd: options.conversationCwd,
     };
     return this.exec.listConversations(request);
   }
@@ -408,6 +436,52 @@ export class Codex {
     });
   }
 
+  /**
+   * Forks a thread with individual items dropped or a user message rewritten, for
+   * "edit my earlier message and re-run" flows. Item ids come from `getThreadItems()`.
+   * The original thread is left untouched.
+   *
+   * @returns A thread with a new id, resumed from the edited history.
+   */
+  async resumeThreadWithEdits(
+    threadId: string,
+    edits: NativeThreadEdits,
+    options: ThreadOptions = {},
+  ): Promise<Thread> {
+    const result = await this.exec.resumeWithEdits(
+      {
+        threadId,
+        ...this.buildConversationConfig(options),
+        authProfile: options.authProfile,
+        profile: options.profile,
+      },
+      edits,
+    );
+    return this.resumeThread(result.threadId, options);
+  }
+
+  /**
+   * Closes a run that was cut off when its host process exited and resumes its thread.
+   * Pass `run.resumePrompt` (or your own prompt) to `run()` on the returned thread to continue.
+   *
+   * @param run A run returned by `recoverInterruptedRuns()`.
+   */
+  async resumeInterruptedRun(
+    run: NativeInterruptedRun,
+    options: ThreadOptions = {},
+  ): Promise<Thread> {
+    if (!run.threadId || !run.resumable) {
+      throw new Error(`Interrupted run ${run.runId} has no rollout to resume`);
+    }
+    if (!run.finalized) {
+      await finalizeInterruptedRun(run.runId);
+    }
+    return this.resumeThread(run.threadId, {
+      workingDirectory: run.workingDirectory,
+      ...options,
+    });
+  }
+
   /**
    * Starts a review task using the built-in Codex review flow.
    */
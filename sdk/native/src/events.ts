@@ -7,6 +7,18 @@ export type ThreadStartedEvent = {
   type: "thread.started";
   /** The identifier of the new thread. Can be used to resume the thread later. */
   thread_id: string;
+  /** Model the session resolved to. */
+  model?: string;
+  /** Id of the model provider in effect. */
+  model_provider?: string;
+  /** Sandbox policy for model shell commands, e.g. `workspace-write`. */
+  sandbox_policy?: string;
+  /** Working directory of the session. */
+  cwd?: string;
+  /** Rollout file the session is recorded to. */
+  rollout_path?: string;
+  /** Config profile the session was loaded with, when one was selected. */
+  config_profile?: string;
 };
 
 /**
@@ -31,6 +43,10 @@ export type Usage = {
 export type TurnCompletedEvent = {
   type: "turn.completed";
   usage: Usage;
+  /** Number of secrets masked during the turn. Present only when `redaction` is enabled. */
+  redaction_applied?: number;
+  /** Events discarded during the turn. Present only when `eventDelivery` is set. */
+  dropped_events?: number;
 };
 
 /** Indicates that a turn failed with an error. */
@@ -45,6 +61,145 @@ export type BackgroundEvent = {
   message: string;
 };
 
+/** Incremental output from a running tool call, emitted before its item completes. */
+export type ToolOutputChunkEvent = {
+  type: "tool.output_chunk";
+  call_id: string;
+  /** Id of the command execution item the output belongs to, when there is one. */
+  item_id?: string;
+  chunk: string;
+};
+
+/** Status of a task a tool moved to the background with spawnBackgroundTool(). */
+export type BackgroundTaskStatus = "running" | "completed" | "failed";
+
+type BackgroundTaskFields = {
+  /** Handle the model passes to `check_background_task`. */
+  task_id: string;
+  call_id: string;
+  tool_name: string;
+  status: BackgroundTaskStatus;
+  output?: string;
+  error?: string;
+};
+
+/** Emitted when a tool call moves its work to the background. */
+export type BackgroundTaskStartedEvent = BackgroundTaskFields & { type: "background_task_started" };
+
+/** Emitted when a background task finishes, successfully or not. */
+export type BackgroundTaskCompletedEvent = BackgroundTaskFields & { type: "background_task_completed" };
+
+/** The thread's full plan after the model's `update_plan` call or an SDK plan update was applied. */
+export type PlanUpdatedEvent = {
+  type: "plan_updated";
+  explanation?: string;
+  plan: Array<{ step: string; status: "pending" | "in_progress" | "completed" }>;
+};
+
+/** Progress reported for the thread with setThreadStatus(). Neither field set means it was cleared. */
+export type ThreadStatusEvent = {
+  type: "thread_status";
+  text?: string;
+  /** Completion percentage, 0-100. */
+  progress_pct?: number;
+};
+
+export type TestCaseStatus = "passed" | "failed" | "skipped";
+
+export type TestCaseResult = {
+  /** Full name of the test, as the framework reports it. */
+  name: string;
+  status: TestCaseStatus;
+  file?: string;
+  /** Failure message or skip reason. */
+  message?: string;
+  duration_ms?: number;
+};
+
+/** Parsed results of a test command, as runTests() returns them. */
+export type TestResults = {
+  framework: "cargo" | "jest" | "pytest";
+  command: string;
+  /** The `run_tests` call that ran the tests, when the model did. */
+  call_id?: string;
+  exit_code: number;
+  /** The command exited 0 and no test failed. */
+  success: boolean;
+  passed: number;
+  failed: number;
+  skipped: number;
+  tests: TestCaseResult[];
+};
+
+/** Emitted when `run_tests` or runTests() with a `threadId` finishes a test run. */
+export type TestResultsEvent = TestResults & { type: "test_results" };
+
+export type VerificationFailure = {
+  command: string;
+  /** Unset when the command could not be started. */
+  exit_code?: number;
+  timed_out: boolean;
+  /** Tail of the command's combined stdout and stderr. */
+  output: string;
+};
+
+/** Emitted when `verify` commands fail after a patch was applied. */
+export type VerificationFailedEvent = {
+  type: "verification_failed";
+  /** The tool call that applied the patch. */
+  call_id: string;
+  failures: VerificationFailure[];
+};
+
+/** Emitted just before a turn ends when its tools read or modified any files. */
+export type FilesTouchedEvent = {
+  type: "files_touched";
+  /** Absolute paths of files tools read, sorted. */
+  read: string[];
+  /** Absolute paths of files patches added, updated or deleted, sorted. */
+  modified: string[];
+};
+
+/** Emitted when a shell command starts, just after its `command_execution` item. */
+export type CommandStartedEvent = {
+  type: "command_started";
+  call_id: string;
+  /** Id of the `command_execution` item tracking the command. */
+  item_id: string;
+  command: string[];
+  cwd: string;
+  source: "agent" | "user_shell" | "unified_exec_startup" | "unified_exec_interaction";
+  /** PTY process id, for unified exec sessions. */
+  process_id?: string;
+  /** Whether the sandbox policy runs commands in Codex's sandbox. */
+  sandboxed: boolean;
+};
+
+/**
+ * How the sandbox and approvals treated a command: `approved` and `denied` mean approval was
+ * requested, e.g. to retry outside the sandbox; `unsandboxed` means the policy has no sandbox.
+ */
+export type CommandSandboxDecision = "sandboxed" | "unsandboxed" | "approved" | "denied";
+
+/** Emitted when a shell command exits or is denied, just after its `command_execution` item completes. */
+export type CommandCompletedEvent = {
+  type: "command_completed";
+  call_id: string;
+  item_id: string;
+  command: string[];
+  exit_code: number;
+  duration_ms: number;
+  /** Tail of the command's combined stdout and stderr. */
+  output: string;
+  /** Whether `output` was cut to its last 16 KiB. */
+  output_truncated: boolean;
+  sandbox_decision: CommandSandboxDecision;
+  /** Why approval was requested, when it was. */
+  approval_reason?: string;
+  /** The output as the model received it, truncation markers included, when the tool output limit cut it. */
+  model_output?: string;
+};
+
 /** Emitted when a new item is added to the thread. Typically the item is initially "in progress". */
 export type ItemStartedEvent = {
   type: "item.started";
@@ -107,22 +262,49 @@ export type ExitedReviewModeEvent = {
   review_output?: ReviewOutputEvent | null;
 };
 
+/**
+ * Ordering metadata attached by the native layer to every serialized event.
+ * `sequence` increases strictly within a thread and continues across resumed runs;
+ * `event_id` is `<thread_id>:<sequence>` and is unique per thread.
+ */
+export type EventEnvelope = {
+  sequence?: number;
+  event_id?: string;
+};
+
 /** Top-level JSONL events emitted by codex exec. */
-export type ThreadEvent =
+export type ThreadEvent = EventEnvelope &
+  (
   | ThreadStartedEvent
   | TurnStartedEvent
   | TurnCompletedEvent
   | TurnFailedEvent
   | BackgroundEvent
+  | ToolOutputChunkEvent
+  | BackgroundTaskStartedEvent
+  | BackgroundTaskCompletedEvent
+  | PlanUpdatedEvent
+  | ThreadStatusEvent
+  | TestResultsEvent
+  | VerificationFailedEvent
+  | FilesTouchedEvent
+  | CommandStartedEvent
+  | CommandCompletedEvent
   | ItemStartedEvent
   | ItemUpdatedEvent
   | ItemCompletedEvent
   | ExitedReviewModeEvent
   | ThreadErrorEvent
-  | RawThreadEvent;
+  | RawThreadEvent
+  );
 
 /** Raw protocol event forwarded without transformation. */
 export type RawThreadEvent = {
   type: "raw_event";
+  /**
+   * `protocol` for codex-core events forwarded with `includeRawEvents` (`raw` is `{ id, msg }`).
+   * Unset when `raw` is the unconverted payload of the thread event that follows.
+   */
+  source?: "protocol";
   raw: unknown;
 };
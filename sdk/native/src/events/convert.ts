@@ -7,6 +7,7 @@ import type { ThreadEvent } from "../events";
 export function convertRustEventToThreadEvent(rustEvent: any): ThreadEvent {
   if (rustEvent?.ThreadStarted) {
     return {
+      ...rustEvent.ThreadStarted,
       type: "thread.started",
       thread_id: rustEvent.ThreadStarted.thread_id,
     };
@@ -18,6 +19,12 @@ export function convertRustEventToThreadEvent(rustEvent: any): ThreadEvent {
     return {
       type: "turn.completed",
       usage: rustEvent.TurnCompleted.usage,
+      ...(rustEvent.TurnCompleted.redaction_applied !== undefined
+        ? { redaction_applied: rustEvent.TurnCompleted.redaction_applied }
+        : {}),
+      ...(rustEvent.TurnCompleted.dropped_events !== undefined
+        ? { dropped_events: rustEvent.TurnCompleted.dropped_events }
+        : {}),
     };
   }
   if (rustEvent?.TurnFailed) {
@@ -62,6 +69,74 @@ export function convertRustEventToThreadEvent(rustEvent: any): ThreadEvent {
       message: rustEvent.message,
     };
   }
+  if (rustEvent?.ToolOutputChunk) {
+    return {
+      type: "tool.output_chunk",
+      call_id: rustEvent.ToolOutputChunk.call_id,
+      ...(rustEvent.ToolOutputChunk.item_id ? { item_id: rustEvent.ToolOutputChunk.item_id } : {}),
+      chunk: rustEvent.ToolOutputChunk.chunk,
+    };
+  }
+  if (rustEvent?.type === "tool.output_chunk" && typeof rustEvent.chunk === "string") {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.BackgroundTaskStarted) {
+    return { type: "background_task_started", ...rustEvent.BackgroundTaskStarted };
+  }
+  if (rustEvent?.BackgroundTaskCompleted) {
+    return { type: "background_task_completed", ...rustEvent.BackgroundTaskCompleted };
+  }
+  if (
+    (rustEvent?.type === "background_task_started" || rustEvent?.type === "background_task_completed") &&
+    typeof rustEvent.task_id === "string"
+  ) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.PlanUpdated) {
+    return { type: "plan_updated", ...rustEvent.PlanUpdated };
+  }
+  if (rustEvent?.type === "plan_updated" && Array.isArray(rustEvent.plan)) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.ThreadStatus) {
+    return { type: "thread_status", ...rustEvent.ThreadStatus };
+  }
+  if (rustEvent?.type === "thread_status") {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.TestResults) {
+    return { type: "test_results", ...rustEvent.TestResults };
+  }
+  if (rustEvent?.type === "test_results" && Array.isArray(rustEvent.tests)) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.VerificationFailed) {
+    return { type: "verification_failed", ...rustEvent.VerificationFailed };
+  }
+  if (rustEvent?.type === "verification_failed" && Array.isArray(rustEvent.failures)) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.FilesTouched) {
+    return { type: "files_touched", ...rustEvent.FilesTouched };
+  }
+  if (rustEvent?.type === "files_touched" && Array.isArray(rustEvent.modified)) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.CommandStarted) {
+    return { type: "command_started", ...rustEvent.CommandStarted };
+  }
+  if (rustEvent?.CommandCompleted) {
+    return { type: "command_completed", ...rustEvent.CommandCompleted };
+  }
+  if (
+    (rustEvent?.type === "command_started" || rustEvent?.type === "command_completed") &&
+    Array.isArray(rustEvent.command)
+  ) {
+    return rustEvent as ThreadEvent;
+  }
+  if (rustEvent?.type === "raw_event") {
+    return { type: "raw_event", source: "protocol", raw: rustEvent.raw };
+  }
   if (rustEvent?.type === "plan_update_scheduled" && rustEvent.plan) {
     const planData = rustEvent.plan;
     const planItems = planData.plan || [];
@@ -8,6 +8,17 @@ import {
   Personality,
   WebSearchMode,
   WorkspaceWriteOptions,
+  PatchPathPolicy,
+  FileSkeletonOptions,
+  RedactionOptions,
+  EventDeliveryOptions,
+  ShellPtyOptions,
+  OutputLimits,
+  ToolEnvOptions,
+  VerifyOptions,
+  MemoryOptions,
+  ToolLimit,
+  PostTurnCheck,
 } from "./threadOptions";
 import {
   NativeBinding,
@@ -15,18 +26,24 @@ import {
   NativeConversationListRequest,
   NativeDeleteConversationRequest,
   NativeDeleteConversationResult,
+  NativeEscalationHandler,
   NativeForkRequest,
   NativeForkResult,
+  NativePromptTemplateRequest,
   NativeResumeFromRolloutRequest,
   NativeRunRequest,
+  NativeThreadEdits,
   NativeUserInputItem,
   getNativeBinding,
 } from "./nativeBinding";
 
 export type CodexExecArgs = {
   input: string;
+  promptTemplate?: NativePromptTemplateRequest;
   baseUrl?: string;
   apiKey?: string;
+  authProfile?: string;
+  profile?: string;
   modelProvider?: string;
   threadId?: string | null;
   images?: string[];
@@ -36,7 +53,14 @@ export type CodexExecArgs = {
   oss?: boolean;
   sandboxMode?: SandboxMode;
   approvalMode?: ApprovalMode;
+  /** Decides approval requests raised during the run; registered for its duration. */
+  onEscalation?: NativeEscalationHandler;
   workspaceWriteOptions?: WorkspaceWriteOptions;
+  permissionProfile?: string;
+  allowedTools?: string[];
+  deniedTools?: string[];
+  toolLimits?: Record<string, ToolLimit>;
+  cacheTtlSeconds?: Record<string, number>;
   workingDirectory?: string;
   skipGitRepoCheck?: boolean;
   outputSchemaFile?: string;
@@ -48,6 +72,7 @@ export type CodexExecArgs = {
   turnPersonality?: Personality;
   ephemeral?: boolean;
   webSearchMode?: WebSearchMode;
+  webSearch?: boolean;
   dynamicTools?: DynamicToolSpec[];
   review?: ReviewExecOptions | null;
   /** MCP servers to register, keyed by server name */
@@ -57,6 +82,26 @@ export type CodexExecArgs = {
    * When true (default), merges the `mcp` option with global config.
    */
   inheritMcp?: boolean;
+  /** Cassette file to record this run into. */
+  record?: string;
+  /** Cassette file to replay this run from. */
+  replay?: string;
+  patchPathPolicy?: PatchPathPolicy;
+  fileSkeleton?: FileSkeletonOptions;
+  redaction?: RedactionOptions;
+  postTurnChecks?: PostTurnCheck[];
+  maxCheckFeedbackTurns?: number;
+  baseInstructions?: string;
+  developerInstructions?: string;
+  compactPrompt?: string;
+  eventDelivery?: EventDeliveryOptions;
+  includeRawEvents?: boolean;
+  execSession?: "per-call" | "persistent";
+  shellPty?: ShellPtyOptions;
+  outputLimits?: OutputLimits;
+  toolEnv?: ToolEnvOptions;
+  verify?: VerifyOptions;
+  memory?: MemoryOptions;
 };
 
 export type ReviewExecOptions = {
@@ -68,6 +113,8 @@ export type CodexForkArgs = {
   nthUserMessage: number;
   baseUrl?: string;
   apiKey?: string;
+  authProfile?: string;
+  profile?: string;
   modelProvider?: string;
   model?: string;
   oss?: boolean;
@@ -106,6 +153,7 @@ export class CodexExec {
 
     const request: NativeRunRequest = {
       prompt: args.input,
+      promptTemplate: args.promptTemplate,
       threadId: args.threadId ?? undefined,
       inputItems: args.inputItems,
       images: args.images && args.images.length > 0 ? args.images : undefined,
@@ -114,12 +162,19 @@ export class CodexExec {
       approvalMode: args.approvalMode,
       workspaceWriteOptions: args.workspaceWriteOptions,
       sandboxMode: args.sandboxMode,
+      permissionProfile: args.permissionProfile,
+      allowedTools: args.allowedTools,
+      deniedTools: args.deniedTools,
+      toolLimits: args.toolLimits,
+      cacheTtlSeconds: args.cacheTtlSeconds,
       workingDirectory: args.workingDirectory,
       skipGitRepoCheck: args.skipGitRepoCheck,
       outputSchema: args.outputSchema,
       toolChoice: args.toolChoice,
       baseUrl: args.baseUrl,
       apiKey: args.apiKey,
+      authProfile: args.authProfile,
+      profile: args.profile,
       modelProvider: args.modelProvider,
       reasoningEffort: args.reasoningEffort,
       reasoningSummary: args.reasoningSummary,
@@ -127,13 +182,41 @@ export class CodexExec {
       turnPersonality: args.turnPersonality,
       ephemeral: args.ephemeral,
       webSearchMode: args.webSearchMode,
+      webSearch: args.webSearch,
       dynamicTools: args.dynamicTools,
       reviewMode: args.review ? true : undefined,
       reviewHint: args.review?.userFacingHint,
       mcp: args.mcp,
       inheritMcp: args.inheritMcp,
+      record: args.record,
+      replay: args.replay,
+      patchPathPolicy: args.patchPathPolicy,
+      fileSkeleton: args.fileSkeleton,
+      redaction: args.redaction,
+      postTurnChecks: args.postTurnChecks,
+      maxCheckFeedbackTurns: args.maxCheckFeedbackTurns,
+      baseInstructions: args.baseInstructions,
+      developerInstructions: args.developerInstructions,
+      compactPrompt: args.compactPrompt,
+      eventDelivery: args.eventDelivery,
+      includeRawEvents: args.includeRawEvents,
+      execSession: args.execSession,
+      shellPty: args.shellPty,
+      outputLimits: args.outputLimits,
+      toolEnv: args.toolEnv,
+      verify: args.verify,
+      memory: args.memory,
     };
 
+    let escalationHandler: string | undefined;
+    if (args.onEscalation) {
+      if (typeof binding.registerEscalationHandler !== "function") {
+        throw new Error("Native binding does not support onEscalation");
+      }
+      escalationHandler = binding.registerEscalationHandler(args.onEscalation);
+      request.escalationHandler = escalationHandler;
+    }
+
     let runPromise: Promise<void> = Promise.resolve();
     try {
       runPromise = binding
@@ -158,6 +241,9 @@ export class CodexExec {
         );
     } catch (error) {
       queue.fail(error);
+      if (escalationHandler) {
+        binding.unregisterEscalationHandler?.(escalationHandler);
+      }
       throw error;
     }
 
@@ -175,6 +261,9 @@ export class CodexExec {
       if (loopError) {
         await runPromise.catch(() => {});
       }
+      if (escalationHandler) {
+        binding.unregisterEscalationHandler?.(escalationHandler);
+      }
     }
   }
 
@@ -195,6 +284,8 @@ export class CodexExec {
       toolChoice: args.toolChoice,
       baseUrl: args.baseUrl,
       apiKey: args.apiKey,
+      authProfile: args.authProfile,
+      profile: args.profile,
       reasoningEffort: args.reasoningEffort,
       reasoningSummary: args.reasoningSummary,
       reviewMode: args.review ? true : undefined,
@@ -204,10 +295,24 @@ export class CodexExec {
   }
 
   async fork(args: CodexForkArgs): Promise<NativeForkResult> {
+    return this.native.forkThread(this.forkRequest(args));
+  }
+
+  async resumeWithEdits(
+    args: Omit<CodexForkArgs, "nthUserMessage">,
+    edits: NativeThreadEdits,
+  ): Promise<NativeForkResult> {
+    if (!this.native.resumeThreadWithEdits) {
+      throw new Error("Native binding not available or resumeThreadWithEdits not supported");
+    }
+    return this.native.resumeThreadWithEdits(this.forkRequest({ ...args, nthUserMessage: 0 }), edits);
+  }
+
+  private forkRequest(args: CodexForkArgs): NativeForkRequest {
     if (!args.threadId) {
       throw new Error("threadId is required to fork a conversation");
     }
-    const request: NativeForkRequest = {
+    return {
       threadId: args.threadId,
       nthUserMessage: args.nthUserMessage,
       model: args.model,
@@ -219,10 +324,11 @@ export class CodexExec {
       skipGitRepoCheck: args.skipGitRepoCheck,
       baseUrl: args.baseUrl,
       apiKey: args.apiKey,
+      authProfile: args.authProfile,
+      profile: args.profile,
       modelProvider: args.modelProvider,
       linuxSandboxPath: args.linuxSandboxPath,
     };
-    return this.native.forkThread(request);
   }
 
   async listConversations(
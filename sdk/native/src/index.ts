@@ -1,5 +1,6 @@
 export type {
   ThreadEvent,
+  EventEnvelope,
   ThreadStartedEvent,
   TurnStartedEvent,
   TurnCompletedEvent,
@@ -11,6 +12,22 @@ export type {
   ThreadErrorEvent,
   Usage,
   BackgroundEvent,
+  ToolOutputChunkEvent,
+  BackgroundTaskStatus,
+  BackgroundTaskStartedEvent,
+  BackgroundTaskCompletedEvent,
+  ThreadStatusEvent,
+  PlanUpdatedEvent,
+  TestCaseStatus,
+  TestCaseResult,
+  TestResults,
+  TestResultsEvent,
+  VerificationFailure,
+  VerificationFailedEvent,
+  FilesTouchedEvent,
+  CommandStartedEvent,
+  CommandCompletedEvent,
+  CommandSandboxDecision,
   ExitedReviewModeEvent,
   ReviewFinding,
   ReviewOutputEvent,
@@ -54,9 +71,33 @@ export type { NativeToolInterceptorContext } from "./codex";
 export type { NativeToolInvocation, NativeToolResult, NativeForkResult } from "./nativeBinding";
 export { getNativeBinding } from "./nativeBinding";
 export type { ApprovalRequest } from "./nativeBinding";
+export type {
+  NativeHookEvent as HookEvent,
+  NativeHookContext as HookContext,
+  NativeHookResult as HookResult,
+  NativeGuardrail as Guardrail,
+  NativeGuardrailDirection as GuardrailDirection,
+  NativeGuardrailRequest as GuardrailRequest,
+  NativeGuardrailDecision as GuardrailDecision,
+  NativePermissionProfile as PermissionProfile,
+  NativePromptTemplateRequest as PromptTemplateRequest,
+  NativeAuditLogOptions as AuditLogOptions,
+  NativeAuditLogVerification as AuditLogVerification,
+} from "./nativeBinding";
 export type { TokenizerOptions, TokenizerEncodeOptions } from "./nativeBinding";
 
-export { startTui, runTui } from "./tui";
+export {
+  startTui,
+  runTui,
+  createTuiTestSession,
+  diffSnapshots,
+  createTuiApp,
+  createAgentView,
+  createStatusBoard,
+  createAgentGraph,
+  loadAgentGraph,
+  TuiWidgetType,
+} from "./tui";
 export type {
   NativeTuiRequest,
   NativeTuiExitInfo,
@@ -65,6 +106,32 @@ export type {
   NativeUpdateActionKind,
   RunTuiOptions,
   TuiSession,
+  TuiTestSession,
+  TuiTestSessionOptions,
+  TuiTestSnapshot,
+  TuiStyledCell,
+  TuiStyledSnapshot,
+  TuiCellChange,
+  TuiSnapshotDiff,
+  TuiApp,
+  TuiAppEvent,
+  TuiAppOptions,
+  TuiAppRunOptions,
+  TuiDimensions,
+  TuiLayout,
+  TuiLayoutChild,
+  TuiLayoutConstraint,
+  TuiWidgetTypeValue,
+  AgentView,
+  StatusBoard,
+  AgentGraphRenderer,
+  AgentGraphNode,
+  AgentGraphUpdate,
+  AgentGraphState,
+  AgentGraphEdge,
+  AgentGraphJson,
+  AgentGraphHistoryOptions,
+  AgentGraphAsciiOptions,
 } from "./tui";
 
 export type {
@@ -77,6 +144,17 @@ export type {
   WebSearchMode,
   Personality,
   DynamicToolSpec,
+  PatchPathPolicy,
+  FileSkeletonOptions,
+  RedactionOptions,
+  EventDeliveryOptions,
+  ShellPtyOptions,
+  OutputLimits,
+  ToolEnvOptions,
+  VerifyOptions,
+  MemoryOptions,
+  ToolLimit,
+  PostTurnCheck,
 } from "./threadOptions";
 export type { TurnOptions } from "./turnOptions";
 export type { SkillDefinition, SkillMentionTrigger } from "./skills";
@@ -140,10 +218,18 @@ export {
 export { CloudTasks } from "./cloudTasks";
 export type {
   CloudTaskSummary,
+  CloudTaskListPage,
+  CloudTaskListOptions,
   CloudTasksOptions,
   CloudApplyOutcome,
+  CloudApplyConflict,
+  CloudTaskApplyResultOptions,
   CloudTaskStatus,
   CloudApplyStatus,
+  CloudAttemptStatus,
+  CloudTaskDetails,
+  CloudTaskAwaitOptions,
+  CloudTaskCreateResult,
   DiffSummary as CloudDiffSummary,
 } from "./cloudTasks";
 
@@ -215,19 +301,211 @@ export {
   reverieListConversations,
   reverieSearchConversations,
   reverieSearchSemantic,
+  reverieSearchSemanticWithStats,
+  reverieRelatedThreads,
   reverieIndexSemantic,
   reverieGetConversationInsights,
   encodeToToon,
+  decodeToon,
   fastEmbedInit,
   fastEmbedEmbed,
   tokenizerCount,
   tokenizerEncode,
   tokenizerDecode,
   collectRepoDiffSummary,
+  searchWorkspace,
+  workspaceIndexBuild,
+  workspaceSearchSemantic,
+  registerModelProvider,
+  listRegisteredModelProviders,
+  clearRegisteredModelProviders,
+  registerGuardrail,
+  clearGuardrails,
+  definePermissionProfile,
+  clearPermissionProfiles,
+  registerPromptTemplate,
+  clearPromptTemplates,
+  renderPromptTemplate,
+  enableAuditLog,
+  disableAuditLog,
+  verifyAuditLog,
+  emitToolOutputChunk,
+  setThreadStatus,
+  getThreadPlan,
+  resetExecSession,
+  lspAttach,
+  lspDetach,
+  runTests,
+  spawnBackgroundTool,
+  callBuiltinTool,
+  captureEnvironmentReport,
+  configDoctor,
+  runThreadCollect,
+  runFromTask,
+  registerEscalationHandler,
+  unregisterEscalationHandler,
+  runReview,
+  reviewDiff,
+  fetchPrContext,
+  postReviewComments,
+  parseReviewFindings,
+  buildPrompt,
+  replayThread,
+  getThreadItems,
+  generateThreadSummary,
+  summarizeAndStore,
+  generateCommitMessage,
+  generatePrDescription,
+  prepareImage,
+  memoryPromote,
+  memoryQuery,
+  memoryForget,
+  getThreadActivity,
+  getThreadFileAccess,
+  watchAndRun,
+  stopWatch,
+  scheduleRun,
+  listSchedules,
+  cancelSchedule,
+  resumeSchedules,
+  enqueueRun,
+  queueStatus,
+  cancelJob,
+  configureJobQueue,
+  startHttpServer,
+  stopHttpServer,
+  startEventBus,
+  stopEventBus,
+  startGrpcServer,
+  stopGrpcServer,
+  startMockModelServer,
+  stopMockModelServer,
+  configureRolloutCache,
+  invalidateRolloutCache,
+  rolloutCacheStats,
+  openRolloutReader,
+  archiveRollouts,
+  configureArchiveEncryption,
+  encryptRolloutArchive,
+  usageReport,
+  recoverInterruptedRuns,
+  finalizeInterruptedRun,
+  authStatus,
+  authLoginApiKey,
+  authLoginChatGpt,
+  authLogout,
+  authProfiles,
 } from "./nativeBinding";
 
 export type {
+  NativeModelProviderRegistration as ModelProviderRegistration,
+  NativeBuiltinToolCallRequest as BuiltinToolCallRequest,
+  NativeDecodedToon as DecodedToon,
+  NativeSearchWorkspaceOptions as SearchWorkspaceOptions,
+  NativeWorkspaceMatch as WorkspaceMatch,
+  NativeSearchWorkspaceResult as SearchWorkspaceResult,
+  NativeWorkspaceIndexBuildOptions as WorkspaceIndexBuildOptions,
+  NativeWorkspaceIndexStats as WorkspaceIndexStats,
+  NativeWorkspaceSemanticSearchOptions as WorkspaceSemanticSearchOptions,
+  NativeWorkspaceChunkMatch as WorkspaceChunkMatch,
+  NativeModelProviderWireApi as ModelProviderWireApi,
+  NativeEnvironmentReport as EnvironmentReport,
+  NativeConfigDoctorOptions as ConfigDoctorOptions,
+  NativeConfigProblem as ConfigProblem,
+  NativeConfigDoctorReport as ConfigDoctorReport,
+  NativeBuildPromptOptions as BuildPromptOptions,
+  NativeRunThreadCollectResult as RunThreadCollectResult,
+  NativeTaskAttachment as TaskAttachment,
+  NativeRunFromTaskRequest as RunFromTaskRequest,
+  NativeRunFromTaskResult as RunFromTaskResult,
+  NativeThreadTaskLink as ThreadTaskLink,
+  NativeEscalationRequest as EscalationRequest,
+  NativeEscalationHandler as EscalationHandler,
+  NativeReviewComment as ReviewComment,
+  NativeReviewFileComments as ReviewFileComments,
+  NativeReviewFindings as ReviewFindings,
+  NativeRunReviewRequest as RunReviewRequest,
+  NativeReviewDiffRequest as ReviewDiffRequest,
+  NativeRunReviewResult as RunReviewResult,
+  NativeFetchPrContextRequest as FetchPrContextRequest,
+  NativeGithubPrContext as GithubPrContext,
+  NativeGithubPrComment as GithubPrComment,
+  NativeGithubCheck as GithubCheck,
+  NativeGithubCiStatus as GithubCiStatus,
+  NativePostReviewCommentsRequest as PostReviewCommentsRequest,
+  NativePostReviewCommentsResult as PostReviewCommentsResult,
+  NativePromptPreview as PromptPreview,
+  NativeReplayThreadOptions as ReplayThreadOptions,
+  NativeGetThreadItemsOptions as GetThreadItemsOptions,
+  NativeUserMessageItem as UserMessageItem,
+  NativeThreadHistoryItem as ThreadHistoryItem,
+  NativeThreadItemsPage as ThreadItemsPage,
+  NativeThreadEdits as ThreadEdits,
+  NativeUserMessageEdit as UserMessageEdit,
+  NativeThreadStatus as ThreadStatus,
+  NativeThreadPlan as ThreadPlan,
+  NativeLspAttachOptions as LspAttachOptions,
+  NativeLspServerInfo as LspServerInfo,
+  NativeRunTestsOptions as RunTestsOptions,
+  NativeGenerateThreadSummaryOptions as GenerateThreadSummaryOptions,
+  NativeThreadSummary as ThreadSummary,
+  NativeSummarizeAndStoreOptions as SummarizeAndStoreOptions,
+  NativeThreadRollup as ThreadRollup,
+  NativeGenerateCommitMessageOptions as GenerateCommitMessageOptions,
+  NativeGeneratedCommitMessage as GeneratedCommitMessage,
+  NativeGeneratePrDescriptionOptions as GeneratePrDescriptionOptions,
+  NativeGeneratedPrDescription as GeneratedPrDescription,
+  NativePrepareImageOptions as PrepareImageOptions,
+  NativePreparedImage as PreparedImage,
+  NativeMemoryPromoteOptions as MemoryPromoteOptions,
+  NativeMemoryQueryOptions as MemoryQueryOptions,
+  NativeMemoryEntry as MemoryEntry,
+  NativeMemoryMatch as MemoryMatch,
+  NativeThreadActivity as ThreadActivity,
+  NativeFileAccessEntry as FileAccessEntry,
+  NativeThreadPhase as ThreadPhase,
+  NativeMockModelFixture as MockModelFixture,
+  NativeMockModelServer as MockModelServer,
+  NativeWatchAndRunOptions as WatchAndRunOptions,
+  NativeWatchEvent as WatchEvent,
+  NativeScheduleRunOptions as ScheduleRunOptions,
+  NativeScheduleInfo as ScheduleInfo,
+  NativeScheduleEvent as ScheduleEvent,
+  NativeEnqueueRunOptions as EnqueueRunOptions,
+  NativeJobStatus as JobStatus,
+  NativeJobInfo as JobInfo,
+  NativeQueueStatus as QueueStatus,
+  NativeHttpServerOptions as HttpServerOptions,
+  NativeHttpServer as HttpServer,
+  NativeEventBusOptions as EventBusOptions,
+  NativeEventBus as EventBus,
+  NativeEventBusMessage as EventBusMessage,
+  NativeGrpcServerOptions as GrpcServerOptions,
+  NativeGrpcServer as GrpcServer,
+  NativeMockModelRequest as MockModelRequest,
+  NativeRolloutCacheStats as RolloutCacheStats,
+  NativeRolloutReader as RolloutReader,
+  NativeArchiveRolloutsOptions as ArchiveRolloutsOptions,
+  NativeArchivedRollout as ArchivedRollout,
+  NativeArchiveRolloutsResult as ArchiveRolloutsResult,
+  NativeArchiveEncryptionOptions as ArchiveEncryptionOptions,
+  NativeEncryptRolloutArchiveOptions as EncryptRolloutArchiveOptions,
+  NativeEncryptRolloutArchiveResult as EncryptRolloutArchiveResult,
+  NativeRolloutReaderRecord as RolloutReaderRecord,
+  NativeModelPricing as ModelPricing,
+  NativeUsageReportOptions as UsageReportOptions,
+  NativeUsageReportRow as UsageReportRow,
+  NativeUsageReport as UsageReport,
+  NativeRecoverInterruptedRunsOptions as RecoverInterruptedRunsOptions,
+  NativeFinalizeInterruptedRunOptions as FinalizeInterruptedRunOptions,
+  NativeInterruptedRun as InterruptedRun,
+  NativeAuthOptions as AuthOptions,
+  NativeAuthStatus as AuthStatus,
+  NativeAuthLoginPrompt as AuthLoginPrompt,
+  NativeAuthChatGptLoginOptions as AuthChatGptLoginOptions,
   ReverieSemanticSearchOptions,
+  ReverieSemanticSearchResponse,
+  ReverieSearchStats,
   ReverieSemanticIndexStats,
   FastEmbedRerankerModelCode,
 } from "./nativeBinding";
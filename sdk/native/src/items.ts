@@ -15,6 +15,10 @@ export type CommandExecutionItem = {
   exit_code?: number;
   /** Current status of the command execution. */
   status: CommandExecutionStatus;
+  /** Set when the output was served from the tool result cache instead of running the command. */
+  cache_hit?: boolean;
+  /** Whether the command ran attached to a terminal (`shellPty` or a persistent shell). */
+  is_tty?: boolean;
 };
 
 /** Indicates the type of the file change. */
@@ -66,6 +70,8 @@ export type McpToolCallItem = {
   };
   /** Current status of the tool invocation. */
   status: McpToolCallStatus;
+  /** Set when the result was served from the tool result cache instead of calling the tool. */
+  cache_hit?: boolean;
 };
 
 /** The status of a collab tool call. */
@@ -7,6 +7,17 @@ import type {
   ApprovalMode,
   SandboxMode,
   WorkspaceWriteOptions,
+  PatchPathPolicy,
+  FileSkeletonOptions,
+  RedactionOptions,
+  EventDeliveryOptions,
+  ShellPtyOptions,
+  OutputLimits,
+  ToolEnvOptions,
+  VerifyOptions,
+  MemoryOptions,
+  ToolLimit,
+  PostTurnCheck,
   ReasoningEffort,
   ReasoningSummary,
   McpServerConfig,
@@ -14,6 +25,9 @@ import type {
   WebSearchMode,
   DynamicToolSpec,
 } from "./threadOptions";
+import type { TestResults, ThreadEvent, Usage } from "./events";
+import type { ThreadItem } from "./items";
+import { convertRustEventToThreadEvent } from "./events/convert";
 
 const CLI_ENTRYPOINT_ENV = "CODEX_NODE_CLI_ENTRYPOINT";
 
@@ -37,6 +51,8 @@ export type NativeUserInputItem =
 
 export type NativeRunRequest = {
   prompt: string;
+  /** Template registered with `registerPromptTemplate`, rendered in place of `prompt`. */
+  promptTemplate?: NativePromptTemplateRequest;
   threadId?: string;
   inputItems?: NativeUserInputItem[];
   images?: string[];
@@ -46,12 +62,25 @@ export type NativeRunRequest = {
   sandboxMode?: SandboxMode;
   approvalMode?: ApprovalMode;
   workspaceWriteOptions?: WorkspaceWriteOptions;
+  /** Profile defined with `definePermissionProfile`. */
+  permissionProfile?: string;
+  /** When set, only these builtin and registered tools are exposed to the model. */
+  allowedTools?: string[];
+  /** Tools never exposed to the model. Wins over `allowedTools`. */
+  deniedTools?: string[];
+  /** Concurrency and start-interval limits per tool name. */
+  toolLimits?: Record<string, ToolLimit>;
+  /** Seconds to reuse successful results of idempotent calls, per tool name. */
+  cacheTtlSeconds?: Record<string, number>;
   workingDirectory?: string;
   skipGitRepoCheck?: boolean;
   outputSchema?: unknown;
   toolChoice?: unknown;
   baseUrl?: string;
   apiKey?: string;
+  authProfile?: string;
+  /** Config profile from config.toml's `[profiles]` table. */
+  profile?: string;
   linuxSandboxPath?: string;
   reasoningEffort?: ReasoningEffort;
   reasoningSummary?: ReasoningSummary;
@@ -59,6 +88,8 @@ export type NativeRunRequest = {
   turnPersonality?: Personality;
   ephemeral?: boolean;
   webSearchMode?: WebSearchMode;
+  /** `true` enables live web search, `false` disables it. Ignored when `webSearchMode` is set. */
+  webSearch?: boolean;
   dynamicTools?: DynamicToolSpec[];
   reviewMode?: boolean;
   reviewHint?: string;
@@ -69,6 +100,43 @@ export type NativeRunRequest = {
    * When true (default), merges the `mcp` option with global config.
    */
   inheritMcp?: boolean;
+  /** Capture model responses, tool outputs and timing into this cassette file. */
+  record?: string;
+  /** Serve model responses and tool outputs from this cassette instead of the network. */
+  replay?: string;
+  /** Allow/deny globs restricting which files apply_patch may touch. */
+  patchPathPolicy?: PatchPathPolicy;
+  /** Replace whole-file reads of oversized files with a skeleton of the file. */
+  fileSkeleton?: FileSkeletonOptions;
+  /** Mask API keys and other secrets in tool output, thread events and the persisted rollout. */
+  redaction?: RedactionOptions;
+  /** Commands run after each turn that changed files, with the action to take on failure. */
+  postTurnChecks?: PostTurnCheck[];
+  maxCheckFeedbackTurns?: number;
+  baseInstructions?: string;
+  developerInstructions?: string;
+  compactPrompt?: string;
+  /** Bounds events waiting for the `runThreadStream` callback. */
+  eventDelivery?: EventDeliveryOptions;
+  /** Forward every protocol event as a `raw_event`. */
+  includeRawEvents?: boolean;
+  /** `persistent` runs shell commands in one long-lived shell per thread. */
+  execSession?: "per-call" | "persistent";
+  /** Run shell commands attached to a PTY of this size. */
+  shellPty?: ShellPtyOptions;
+  /** Environment shell commands see, in place of config.toml's `shell_environment_policy`. */
+  toolEnv?: ToolEnvOptions;
+  /** Commands run in the sandbox after each applied patch, with failures fed back to the model. */
+  verify?: VerifyOptions;
+  /** Add the project's top memories for the prompt to a new thread's developer instructions. */
+  memory?: MemoryOptions;
+  /**
+   * Id from `registerEscalationHandler` of the handler that decides this run's approval requests.
+   * Without one they are denied.
+   */
+  escalationHandler?: string;
+  /** How much tool output the model sees before it is truncated. */
+  outputLimits?: OutputLimits;
 };
 
 export type NativeForkRequest = {
@@ -84,9 +152,24 @@ export type NativeForkRequest = {
   skipGitRepoCheck?: boolean;
   baseUrl?: string;
   apiKey?: string;
+  authProfile?: string;
+  /** Config profile from config.toml's `[profiles]` table. */
+  profile?: string;
   linuxSandboxPath?: string;
 };
 
+export type NativeUserMessageEdit = {
+  /** Id of a `user_message` item from `getThreadItems()`. */
+  itemId: string;
+  text: string;
+};
+
+export type NativeThreadEdits = {
+  /** Items from `getThreadItems()` to remove. Tool calls lose both the call and its output. */
+  dropItemIds?: string[];
+  replaceUserMessage?: NativeUserMessageEdit;
+};
+
 export type NativeConversationConfig = {
   model?: string;
   modelProvider?: string;
@@ -98,6 +181,9 @@ export type NativeConversationConfig = {
   skipGitRepoCheck?: boolean;
   baseUrl?: string;
   apiKey?: string;
+  authProfile?: string;
+  /** Config profile from config.toml's `[profiles]` table. */
+  profile?: string;
   linuxSandboxPath?: string;
   reasoningEffort?: ReasoningEffort;
   reasoningSummary?: ReasoningSummary;
@@ -111,6 +197,8 @@ export type NativeConversationListRequest = {
   pageSize?: number;
   cursor?: string;
   modelProviders?: string[];
+  /** Only conversations recorded in exactly this working directory. */
+  cwd?: string;
 };
 
 export type NativeConversationSummary = {
@@ -118,6 +206,12 @@ export type NativeConversationSummary = {
   path: string;
   createdAt?: string;
   updatedAt?: string;
+  /** Thread name, e.g. a title from `generateThreadSummary()`. */
+  title?: string;
+  /** Abstract from `generateThreadSummary()`. */
+  summary?: string;
+  /** Task the thread was started for by `runFromTask()`. */
+  task?: NativeThreadTaskLink;
 };
 
 export type NativeConversationListPage = {
@@ -125,6 +219,8 @@ export type NativeConversationListPage = {
   nextCursor?: string;
   numScannedFiles: number;
   reachedScanCap: boolean;
+  /** `"sqlite"` when the listing came from the state database, `"jsonl"` for a file scan. */
+  storeBackend: "jsonl" | "sqlite";
 };
 
 export type NativeDeleteConversationRequest = {
@@ -172,6 +268,52 @@ export type NativeEmitBackgroundEventRequest = {
   message: string;
 };
 
+export type NativeThreadStatus = {
+  text?: string;
+  /** Completion percentage, 0-100. */
+  progressPct?: number;
+};
+
+export type NativeThreadPlan = {
+  explanation?: string;
+  plan: Array<{ step: string; status: PlanStatus }>;
+  /** Scheduled with `emitPlanUpdate()` or `modifyPlan()` and not yet applied by a run. */
+  pending: boolean;
+};
+
+export type NativeLspAttachOptions = {
+  /** Language server executable, e.g. `rust-analyzer` or `typescript-language-server`. */
+  server: string;
+  args?: string[];
+  /** LSP language id for opened files. Inferred from the file extension when omitted. */
+  languageId?: string;
+  initializationOptions?: unknown;
+};
+
+export type NativeLspServerInfo = {
+  /** The canonical workspace root the server was started for. */
+  root: string;
+  server: string;
+  /** Name the server reported during initialization. */
+  name?: string;
+};
+
+export type NativeRunTestsOptions = {
+  /** Project directory the tests run in. Defaults to the process cwd. */
+  cwd?: string;
+  /** Detected from the project files when omitted. */
+  framework?: "cargo" | "jest" | "pytest";
+  /** A `cargo test` name filter, a jest test path pattern, or pytest paths and node ids. */
+  selector?: string;
+  /** Defaults to `workspace-write`. */
+  sandboxMode?: SandboxMode;
+  linuxSandboxPath?: string;
+  /** Defaults to 10 minutes. */
+  timeoutMs?: number;
+  /** Thread whose active run also receives the results as a `test_results` event. */
+  threadId?: string;
+};
+
 export type NativeEmitPlanUpdateRequest = {
   threadId: string;
   explanation?: string;
@@ -197,6 +339,113 @@ export type NativeToolInterceptorNativeContext = {
   token: string;
 };
 
+export type NativeHookEvent =
+  | "pre_turn"
+  | "post_turn"
+  | "pre_tool_call"
+  | "post_tool_call"
+  | "session_end";
+
+export type NativeHookContext = {
+  event: NativeHookEvent;
+  threadId?: string;
+  /** Turn prompt (`pre_turn`, `post_turn`). */
+  prompt?: string;
+  /** Tool call being made (`pre_tool_call`, `post_tool_call`). */
+  invocation?: NativeToolInvocation;
+  /** Tool output (`post_tool_call`) or the turn's final response (`post_turn`). */
+  output?: string;
+  success?: boolean;
+  error?: string;
+};
+
+export type NativeGuardrailDirection = "input" | "output";
+
+export type NativeGuardrailRequest = {
+  direction: NativeGuardrailDirection;
+  threadId?: string;
+  content: string;
+};
+
+export type NativeGuardrailDecision = {
+  /** Defaults to `allow`. */
+  action?: "allow" | "redact" | "rewrite" | "block";
+  /** `rewrite`: replacement content. */
+  content?: string;
+  /** `redact`: substrings replaced with `[REDACTED]`. */
+  redact?: string[];
+  /** `block`: why the content was blocked. */
+  reason?: string;
+};
+
+export type NativeGuardrail = {
+  direction: NativeGuardrailDirection;
+  handler: (
+    request: NativeGuardrailRequest,
+  ) => NativeGuardrailDecision | void | Promise<NativeGuardrailDecision | void>;
+};
+
+export type NativeBuiltinToolCallRequest = {
+  /** `shell`, `shell_command`, `exec_command`, `apply_patch` or `read_file`. */
+  toolName: string;
+  /** The tool's arguments, as an object or JSON string, in the shape the model would send. */
+  arguments?: unknown;
+  /** Directory commands run in and relative paths resolve against. Defaults to the process cwd. */
+  cwd?: string;
+  /** Defaults to `read-only`. */
+  sandboxMode?: SandboxMode;
+  linuxSandboxPath?: string;
+};
+
+export type NativePermissionProfile = {
+  sandboxMode?: SandboxMode;
+  approvalMode?: ApprovalMode;
+  /** When set, only these builtin and registered tools are exposed to the model. */
+  allowedTools?: string[];
+  /** Tools never exposed to the model. Wins over `allowedTools`. */
+  deniedTools?: string[];
+  /** Extra writable directories for workspace-write mode. */
+  writableRoots?: string[];
+};
+
+export type NativePromptTemplateRequest = {
+  /** Template registered with `registerPromptTemplate`. */
+  name: string;
+  /** Values for the template's variables. */
+  vars?: Record<string, unknown>;
+  /** Token budget for the rendered prompt; longer variable values are cut to fit. */
+  maxTokens?: number;
+};
+
+export type NativeAuditLogOptions = {
+  /** Chain records by SHA-256 so tampering is detectable. Default: true */
+  hashChain?: boolean;
+};
+
+export type NativeAuditLogVerification = {
+  valid: boolean;
+  /** Records verified before the first invalid one (all of them when `valid`). */
+  records: number;
+  /** 1-based line of the first record that failed verification. */
+  invalidLine?: number;
+  error?: string;
+};
+
+export type NativeHookResult = {
+  /** `pre_turn`: replacement prompt. */
+  prompt?: string;
+  /** `pre_tool_call`: replacement arguments for function and MCP tools. */
+  arguments?: string;
+  /** `pre_tool_call`: replacement input for freeform tools. */
+  input?: string;
+  /** `pre_tool_call`: reject the call, returning this message to the model. */
+  error?: string;
+  /** `post_tool_call`: replacement output. */
+  output?: string;
+  /** `post_tool_call`: note appended to the output. `post_turn`: background event after the turn. */
+  annotation?: string;
+};
+
 export type NativeTokenUsage = {
   inputTokens: number;
   cachedInputTokens: number;
@@ -224,6 +473,213 @@ export type NativeTuiSession = {
   readonly closed: boolean;
 };
 
+export type NativeTuiTestSessionOptions = {
+  width?: number;
+  height?: number;
+};
+
+export type NativeTuiTestSnapshot = {
+  /** Full screen contents, rows joined with newlines. */
+  screen: string;
+  /** Screen rows with trailing whitespace removed. */
+  rows: string[];
+  cursorRow: number;
+  cursorCol: number;
+  /** Prompts submitted so far, in order. */
+  submitted: string[];
+};
+
+export type NativeTuiStyledCell = {
+  /** Cell text; empty for blank cells and the trailing half of wide characters. */
+  text: string;
+  /** "default", an ANSI name ("red", "brightBlue"), "ansi{N}" for the 256-color palette, or "#rrggbb". */
+  fg: string;
+  bg: string;
+  bold: boolean;
+  italic: boolean;
+  underline: boolean;
+  inverse: boolean;
+};
+
+export type NativeTuiStyledSnapshot = {
+  width: number;
+  height: number;
+  /** Row-major cells, `height` rows of `width` cells. */
+  cells: NativeTuiStyledCell[][];
+};
+
+export type NativeTuiCellChange = {
+  row: number;
+  col: number;
+  before?: NativeTuiStyledCell;
+  after?: NativeTuiStyledCell;
+};
+
+export type NativeTuiSnapshotDiff = {
+  identical: boolean;
+  sizeChanged: boolean;
+  changes: NativeTuiCellChange[];
+  changedRows: number[];
+};
+
+export type NativeTuiTestSession = {
+  sendKeys(keys: string): NativeTuiTestSnapshot;
+  paste(text: string): NativeTuiTestSnapshot;
+  snapshot(): NativeTuiTestSnapshot;
+  captureStyled(): NativeTuiStyledSnapshot;
+};
+
+export type NativeTuiDimensions = {
+  width: number;
+  height: number;
+  x: number;
+  y: number;
+};
+
+/**
+ * Size of a layout child along its split direction: a cell count, `"30%"`, a ratio such as
+ * `"1/3"`, `"min:5"`, `"max:10"`, `"fill"` or a weighted `"fill:2"`. Default: `"fill"`
+ */
+export type NativeTuiLayoutConstraint = number | string;
+
+export type NativeTuiLayoutChild = {
+  /** Component placed in this slot; omit when `layout` nests another layout. */
+  componentId?: string;
+  layout?: NativeTuiLayout;
+  constraint?: NativeTuiLayoutConstraint;
+  /** Tabs layouts: the id used by `selectTab`. Default: `componentId` */
+  id?: string;
+  /** Tabs layouts: the label in the tab bar. Default: the tab id */
+  title?: string;
+};
+
+export type NativeTuiLayout =
+  | { type: "split"; direction?: "horizontal" | "vertical"; children: NativeTuiLayoutChild[] }
+  | { type: "stack"; children: NativeTuiLayoutChild[] }
+  | { type: "grid"; columns?: number; children: NativeTuiLayoutChild[] }
+  | { type: "tabs"; active?: number; children: NativeTuiLayoutChild[] };
+
+export type NativeTuiAppRunOptions = {
+  /** Interval between `tick` events and redraws. Default: 250 */
+  tickRateMs?: number;
+  /** End the loop on Ctrl+C. Default: true */
+  exitOnCtrlC?: boolean;
+  /** Draw on the alternate screen. Default: true */
+  altScreen?: boolean;
+};
+
+export type NativeTuiAppEvent = {
+  type: "key" | "paste" | "resize" | "tick" | "focus" | "stop";
+  /** Key name such as "a", "enter", "ctrl+c" or "shift+up". */
+  key?: string;
+  text?: string;
+  /** Component that received the key or gained focus. */
+  componentId?: string;
+  width?: number;
+  height?: number;
+};
+
+export type NativeAgentView = {
+  sendMessage(message: string): void;
+  receiveMessage(message: string): void;
+  updateStatus(status: string): void;
+  appendOutput(output: string): void;
+};
+
+export type NativeStatusBoard = {
+  addTextTile(id: string, title: string, value: string): void;
+  addProgressTile(id: string, title: string, value: number): void;
+  updateTile(id: string, value: string): void;
+};
+
+export type NativeAgentGraphState = "pending" | "running" | "completed" | "failed";
+
+export type NativeAgentGraphNode = {
+  /** Thread id; events from native runs of this thread update the node. */
+  id: string;
+  parentId?: string;
+  /** Default: the first group of the id. */
+  label?: string;
+  state?: NativeAgentGraphState;
+  activity?: string;
+  turns?: number;
+  /** RFC 3339 timestamp of the thread's first turn. */
+  startedAt?: string;
+  /** RFC 3339 timestamp of the last change; `renderAscii({ since })` filters on it. Default: now. */
+  updatedAt?: string;
+  /** Agent this one's work was merged back into; drawn as a merge line into that lane. */
+  mergedInto?: string;
+};
+
+export type NativeAgentGraphUpdate = {
+  label?: string;
+  state?: NativeAgentGraphState;
+  activity?: string;
+};
+
+export type NativeAgentGraphEdge = {
+  from: string;
+  to: string;
+  kind: "fork" | "merge";
+};
+
+export type NativeAgentGraphJson = {
+  nodes: NativeAgentGraphNode[];
+  edges: NativeAgentGraphEdge[];
+};
+
+export type NativeAgentGraphHistoryOptions = {
+  /** Only this thread and the threads forked or spawned from it, transitively. */
+  rootThreadId?: string;
+};
+
+export type NativeAgentGraphAsciiOptions = {
+  /** Terminal width; longer lines are cut with an ellipsis. */
+  width?: number;
+  /** Color each agent's glyph and state with ANSI escapes. Default: false. */
+  color?: boolean;
+  /** Show only the last rows, under a row counting the ones left out. */
+  maxRows?: number;
+  /** RFC 3339 timestamp; agents that have not changed since then are left out. */
+  since?: string;
+};
+
+export type NativeAgentGraphRenderer = {
+  addAgent(agent: NativeAgentGraphNode): void;
+  updateAgent(id: string, update: NativeAgentGraphUpdate): void;
+  mergeAgent(id: string, intoId: string): void;
+  removeAgent(id: string): void;
+  readonly agents: NativeAgentGraphNode[];
+  renderAscii(options?: NativeAgentGraphAsciiOptions): string;
+  renderDot(): string;
+  renderMermaid(): string;
+  toJson(): NativeAgentGraphJson;
+};
+
+export type NativeTuiApp = {
+  addComponent(id: string, componentType: number): void;
+  mountAgentView(id: string, view: NativeAgentView): void;
+  mountStatusBoard(id: string, board: NativeStatusBoard): void;
+  mountAgentGraph(id: string, graph: NativeAgentGraphRenderer): void;
+  removeComponent(id: string): void;
+  setComponentArea(id: string, area: NativeTuiDimensions): void;
+  setZIndex(id: string, zIndex: number): void;
+  setLayout(layout: NativeTuiLayout): void;
+  clearLayout(): void;
+  selectTab(id: string): void;
+  updateComponent(id: string, data: unknown): void;
+  /** Append streamed text to a Text or Markdown component. */
+  appendToComponent(id: string, text: string): void;
+  focus(id: string): void;
+  focusNext(): string | null;
+  focusPrevious(): string | null;
+  readonly focused: string | null;
+  renderToString(width?: number, height?: number): string;
+  run(options?: NativeTuiAppRunOptions, onEvent?: (err: unknown, event?: NativeTuiAppEvent) => void): void;
+  stop(): void;
+  readonly running: boolean;
+};
+
 // ============================================================================ 
 // Repo diff summaries
 // ============================================================================ 
@@ -258,144 +714,1295 @@ export type RepoDiffSummaryOptions = {
 };
 
 // ============================================================================
-// Reverie System Types
+// Cloud Tasks Types
 // ============================================================================
 
-export type ReverieConversation = {
+export type NativeCloudTaskStatus = "pending" | "ready" | "applied" | "error";
+
+export type NativeCloudTaskSummary = {
   id: string;
-  path: string;
+  title: string;
+  status: NativeCloudTaskStatus;
+  updatedAt: string;
+  environmentId?: string;
+  environmentLabel?: string;
+  summary: {
+    filesChanged: number;
+    linesAdded: number;
+    linesRemoved: number;
+  };
+  isReview: boolean;
+  attemptTotal?: number;
+};
+
+export type NativeCloudTaskListPage = {
+  tasks: NativeCloudTaskSummary[];
+  nextCursor?: string;
+};
+
+export type NativeCloudAttemptStatus =
+  | "pending"
+  | "running"
+  | "completed"
+  | "failed"
+  | "cancelled"
+  | "unknown";
+
+export type NativeCloudTaskCreated = {
+  id: string;
+};
+
+export type NativeCloudTaskDetails = {
+  task: NativeCloudTaskSummary;
+  /** The prompt the task was created with. */
+  prompt?: string;
+  /** Assistant messages of the latest attempt. */
+  messages: string[];
+  turnId?: string;
+  attemptStatus: NativeCloudAttemptStatus;
+  /** Unified diff of the latest attempt, once it has produced one. */
+  diff?: string;
+};
+
+export type NativeCloudTaskAwaitOptions = {
+  /** Default: 5000. */
+  pollIntervalMs?: number;
+  /** Default: 30 minutes. */
+  timeoutMs?: number;
+};
+
+export type NativeCloudApplyOutcome = {
+  applied: boolean;
+  status: "success" | "partial" | "error";
+  message: string;
+  skippedPaths: string[];
+  conflictPaths: string[];
+  /** True when files touched by the task had uncommitted local edits. */
+  diverged: boolean;
+  conflicts: NativeGitMergeConflict[];
+  /** Branch created for the result by `cloudTasksApplyResult`. */
+  branch?: string;
+};
+
+export type NativeCloudTaskApplyResultOptions = {
+  /** Workspace to apply into; defaults to the process working directory. */
   cwd?: string;
-  createdAt?: string;
-  updatedAt?: string;
-  headRecords: string[];
-  tailRecords: string[];
-  headRecordsToon: string[];
-  tailRecordsToon: string[];
+  /** Switch to a new branch of this name before applying. */
+  createBranch?: string;
 };
 
-export type ReverieSearchResult = {
-  conversation: ReverieConversation;
-  relevanceScore: number;
-  matchingExcerpts: string[];
-  insights: string[];
-  rerankerScore?: number;
+export type NativeGitConflictRegion = {
+  startLine: number;
+  endLine: number;
+  local: string;
+  base: string;
+  incoming: string;
 };
 
-export type FastEmbedRerankerModelCode =
-  | "BAAI/bge-reranker-base"
-  | "rozgo/bge-reranker-v2-m3"
-  | "jinaai/jina-reranker-v1-turbo-en"
-  | "jinaai/jina-reranker-v2-base-multilingual";
+export type NativeGitMergeConflict = {
+  path: string;
+  reason?: string;
+  regions: NativeGitConflictRegion[];
+  merged?: string;
+};
 
-export type ReverieSemanticSearchOptions = {
-  limit?: number;
-  maxCandidates?: number;
-  projectRoot?: string;
-  batchSize?: number;
-  normalize?: boolean;
-  cache?: boolean;
-  rerankerModel?: FastEmbedRerankerModelCode;
-  rerankerCacheDir?: string;
-  rerankerMaxLength?: number;
-  rerankerShowProgress?: boolean;
-  rerankerBatchSize?: number;
-  rerankerTopK?: number;
+// ============================================================================
+// Model Provider Types
+// ============================================================================
+
+export type NativeModelProviderWireApi = "responses" | "anthropic" | "gemini";
+
+export type NativeModelProviderRegistration = {
+  id: string;
+  name?: string;
+  baseUrl: string;
+  apiKey?: string;
+  envKey?: string;
+  wireApi?: NativeModelProviderWireApi;
+  httpHeaders?: Record<string, string>;
+  maxOutputTokens?: number;
 };
 
-export type ReverieSemanticIndexStats = {
-  conversationsIndexed: number;
-  documentsEmbedded: number;
-  batches: number;
+// ============================================================================
+// Collected Run Types
+// ============================================================================
+
+export type NativeRunThreadCollectResult = {
+  /** Text of the last agent message; empty when the agent sent none. */
+  finalMessage: string;
+  /** Usage reported by the last completed turn. */
+  usage: Usage | null;
+  /** Completed items in order, as they appear in `item.completed` events. */
+  items: ThreadItem[];
+  threadId: string | null;
+  rolloutPath: string | null;
+  /** Settings in effect, as reported by `thread.started`. */
+  model: string | null;
+  modelProvider: string | null;
+  sandboxPolicy: string | null;
+  cwd: string | null;
+  configProfile: string | null;
 };
 
 // ============================================================================
-// FastEmbed Types
+// Task Run Types
 // ============================================================================
 
-export type FastEmbedInitOptions = {
-  model?: string;
-  cacheDir?: string;
-  maxLength?: number;
-  showDownloadProgress?: boolean;
+export type NativeTaskAttachment = {
+  /** File name or label shown in the prompt. */
+  name?: string;
+  /** Local file. Images are attached to the run; other files are listed for the agent to read. */
+  path?: string;
+  /** Text inlined into the prompt, e.g. a log excerpt. */
+  content?: string;
+  /** Link listed in the prompt. */
+  url?: string;
+  /** MIME type; `image/*` marks `path` as an image when its extension doesn't. */
+  mimeType?: string;
 };
 
-export type FastEmbedEmbedRequest = {
-  inputs: string[];
-  batchSize?: number;
-  normalize?: boolean;
-  projectRoot?: string;
-  cache?: boolean;
+export type NativeRunFromTaskRequest = {
+  title: string;
+  /** Task description, e.g. an issue body in Markdown. */
+  body?: string;
+  attachments?: NativeTaskAttachment[];
+  /** Checkout to work in; relative attachment paths resolve against it. */
+  repoPath?: string;
+  /** Permission profile defined with `definePermissionProfile`. */
+  permissionProfile?: string;
+  /** Tracker the task came from, e.g. `github` or `jira`. */
+  source?: string;
+  /** Task id in the tracker, e.g. `acme/widgets#42` or `PROJ-123`. */
+  taskId?: string;
+  url?: string;
+  labels?: string[];
+  /** Further run options. Fields set here win over `repoPath` and `permissionProfile`. */
+  run?: Partial<Omit<NativeRunRequest, "prompt">>;
+};
+
+export type NativeThreadTaskLink = {
+  threadId: string;
+  title: string;
+  source?: string;
+  taskId?: string;
+  url?: string;
+  linkedAt: string;
+};
+
+export type NativeRunFromTaskResult = {
+  result: NativeRunThreadCollectResult;
+  task: NativeThreadTaskLink;
 };
 
 // ============================================================================
-// Tokenizer Types
+// Escalation Types
 // ============================================================================
 
-export type TokenizerOptions = {
-  model?: string;
-  encoding?: "o200k_base" | "cl100k_base";
+export type NativeEscalationRequest = {
+  /** `command`: retry a command outside the sandbox. `patch`: write outside the writable roots. */
+  type: "command" | "patch";
+  threadId: string;
+  callId: string;
+  /** Command awaiting approval; empty for patches. */
+  command: string[];
+  /** Working directory of the command, or the root a patch asks to write under. */
+  cwd?: string;
+  /** Files a patch touches; empty for commands. */
+  files: string[];
+  /** Why approval is needed, e.g. `command failed; retry without sandbox?`. */
+  reason?: string;
+  /** The end of what the command printed in the sandbox before it was denied. */
+  sandboxError?: string;
 };
 
-export type TokenizerEncodeOptions = TokenizerOptions & {
-  withSpecialTokens?: boolean;
+/** Return true to grant a one-time retry outside the sandbox, false to deny it. */
+export type NativeEscalationHandler = (request: NativeEscalationRequest) => boolean | Promise<boolean>;
+
+// ============================================================================
+// Review Types
+// ============================================================================
+
+export type NativeReviewComment = {
+  title: string;
+  body: string;
+  /** `critical` (P0), `high` (P1), `medium` (P2) or `low` (P3 and below). */
+  severity: "critical" | "high" | "medium" | "low";
+  priority: number;
+  confidence: number;
+  startLine: number;
+  endLine: number;
+  /** Contents of the first fenced `suggestion`, `diff` or `patch` block in the body. */
+  suggestedPatch?: string;
 };
 
-function ensureCliEntrypointEnv(): void {
-  if (process.env[CLI_ENTRYPOINT_ENV]) {
-    return;
-  }
+export type NativeReviewFileComments = {
+  path: string;
+  /** Most severe first, then by line. */
+  comments: NativeReviewComment[];
+};
 
-  const filename = fileURLToPath(import.meta.url);
-  const dirname = path.dirname(filename);
-  const candidates = [
-    path.resolve(dirname, "cli.cjs"),
-    path.resolve(dirname, "../cli.cjs"),
-    path.resolve(dirname, "../dist/cli.cjs"),
-  ];
+export type NativeReviewFindings = {
+  /** Files in the order their first finding was reported. */
+  files: NativeReviewFileComments[];
+  overallCorrectness: string;
+  overallExplanation: string;
+  overallConfidence: number;
+};
 
-  for (const candidate of candidates) {
-    if (fs.existsSync(candidate)) {
-      process.env[CLI_ENTRYPOINT_ENV] = candidate;
-      break;
-    }
-  }
-}
+export type NativeRunReviewRequest = {
+  /** Unified diff to review. Exactly one of `diff` and `baseRef` is required. */
+  diff?: string;
+  /** Review the changes between this ref's merge base and the working tree. */
+  baseRef?: string;
+  /** Label shown for the review, e.g. "changes against 'main'". */
+  hint?: string;
+  config?: NativeConversationConfig;
+};
 
-ensureCliEntrypointEnv();
+export type NativeReviewDiffRequest = {
+  /** Repository to diff; defaults to `config.workingDirectory`, then the process cwd. */
+  cwd?: string;
+  /** Diff the changes since the merge base with this ref. Required unless `patchText` is set. */
+  baseRef?: string;
+  /** Commit to review; the working tree when unset. */
+  headRef?: string;
+  /** Unified diff to review instead of computing one from refs. */
+  patchText?: string;
+  /** Project review guidelines appended to the prompt. */
+  guidelines?: string;
+  hint?: string;
+  config?: NativeConversationConfig;
+};
 
-export type NativeBinding = {
-  runThread(request: NativeRunRequest): Promise<string[]>;
-  runThreadStream(
-    request: NativeRunRequest,
-    onEvent: (err: unknown, eventJson?: string) => void,
-  ): Promise<void>;
-  compactThread(request: NativeRunRequest): Promise<string[]>;
-  forkThread(request: NativeForkRequest): Promise<NativeForkResult>;
-  listConversations(request: NativeConversationListRequest): Promise<NativeConversationListPage>;
-  deleteConversation(request: NativeDeleteConversationRequest): Promise<NativeDeleteConversationResult>;
-  resumeConversationFromRollout(request: NativeResumeFromRolloutRequest): Promise<NativeForkResult>;
-  runTui(request: NativeTuiRequest): Promise<NativeTuiExitInfo>;
-  tuiTestRun?(request: {
-    width: number;
-    height: number;
-    viewport: { x: number; y: number; width: number; height: number };
-    lines: string[];
-  }): Promise<string[]>;
-  callToolBuiltin(token: string, invocation?: NativeToolInvocation): Promise<NativeToolResult>;
-  callRegisteredToolForTest?(
-    toolName: string,
-    invocation: NativeToolInvocation,
-  ): Promise<NativeToolResult>;
+export type NativeRunReviewResult = {
+  /** Unset when the review ended without structured output. */
+  findings?: NativeReviewFindings;
+  finalMessage: string;
+  usage: Usage | null;
+  threadId: string | null;
+};
+
+// ============================================================================
+// GitHub Types
+// ============================================================================
+
+export type NativeFetchPrContextRequest = {
+  /** `owner/name`. */
+  repo: string;
+  number: number;
+  /** Defaults to GITHUB_TOKEN, then GH_TOKEN. Public repositories work without one. */
+  token?: string;
+  /** API root for GitHub Enterprise Server, e.g. `https://github.example.com/api/v3`. */
+  apiUrl?: string;
+};
+
+export type NativeGithubPrComment = {
+  id: number;
+  /** `issue` for conversation comments, `review` for inline comments on the diff. */
+  kind: "issue" | "review";
+  author: string;
+  body: string;
+  /** File an inline comment is attached to. */
+  path?: string;
+  /** Line an inline comment is attached to; unset once the line is outdated. */
+  line?: number;
+  createdAt: string;
+  url: string;
+};
+
+export type NativeGithubCheck = {
+  name: string;
+  /** `check_run` for GitHub Actions and apps, `status` for commit statuses. */
+  source: "check_run" | "status";
+  status: string;
+  /** Outcome of a completed check, e.g. `success`, `failure` or `skipped`. */
+  conclusion?: string;
+  url?: string;
+};
+
+export type NativeGithubCiStatus = {
+  /** `failure` when any check failed, else `pending` while any is running, else `success`. */
+  state: "success" | "failure" | "pending" | "none";
+  checks: NativeGithubCheck[];
+};
+
+export type NativeGithubPrContext = {
+  repo: string;
+  number: number;
+  title: string;
+  body: string;
+  author: string;
+  state: string;
+  draft: boolean;
+  url: string;
+  baseRef: string;
+  baseSha: string;
+  headRef: string;
+  headSha: string;
+  /** Unified diff of the pull request. */
+  diff: string;
+  /** Conversation and inline comments, oldest first. */
+  comments: NativeGithubPrComment[];
+  /** Checks and statuses on the head commit. */
+  ci: NativeGithubCiStatus;
+};
+
+export type NativePostReviewCommentsRequest = {
+  /** `owner/name`. */
+  repo: string;
+  number: number;
+  /** Defaults to GITHUB_TOKEN, then GH_TOKEN. */
+  token?: string;
+  apiUrl?: string;
+  findings: NativeReviewFindings;
+  /** Checkout root that absolute finding paths are made relative to. Defaults to the process cwd. */
+  root?: string;
+  event?: "COMMENT" | "APPROVE" | "REQUEST_CHANGES";
+  /** Review summary; defaults to the findings' overall explanation. */
+  body?: string;
+};
+
+export type NativePostReviewCommentsResult = {
+  reviewId: number;
+  url: string;
+  /** Findings posted as inline comments. */
+  posted: number;
+  /** `path:line` of findings outside the diff, listed in the review body instead. */
+  skipped: string[];
+};
+
+// ============================================================================
+// Prompt Preview Types
+// ============================================================================
+
+export type NativeBuildPromptOptions = {
+  /** Keyword-search past conversations and inject matching insights before the user turn. */
+  reverieQuery?: string;
+  reverieLimit?: number;
+};
+
+export type NativePromptPreview = {
+  model: string;
+  instructions: string;
+  /** Responses API input items, in send order. */
+  input: unknown[];
+  /** Responses API tool definitions (MCP tools are not included). */
+  tools: unknown[];
+  parallelToolCalls: boolean;
+  outputSchema?: unknown;
+  reverieInsights: number;
+};
+
+export type NativeReplayThreadOptions = {
+  /** Replacement tool outputs keyed by call id, falling back to tool name. */
+  mockToolOutputs?: Record<string, string>;
+};
+
+export type NativeGetThreadItemsOptions = {
+  /** Item types to keep, e.g. `["user_message", "agent_message", "command_execution"]`. */
+  types?: string[];
+  /** Matching items to skip. Defaults to 0. */
+  offset?: number;
+  /** Maximum number of items to return. Defaults to all. */
+  limit?: number;
+  codexHome?: string;
+};
+
+export type NativeUserMessageItem = {
+  id: string;
+  type: "user_message";
+  text: string;
+};
+
+export type NativeThreadHistoryItem = {
+  id: string;
+  type: string;
+  /** 0-based turn, counted by user messages. */
+  turnIndex: number;
+  /** Indices of the rollout records the item was built from. */
+  recordIndices: number[];
+  /** The item as it appears in `item.completed` events. */
+  item: ThreadItem | NativeUserMessageItem;
+};
+
+export type NativeThreadItemsPage = {
+  items: NativeThreadHistoryItem[];
+  /** Matching items before `offset` and `limit` were applied. */
+  total: number;
+};
+
+export type NativeGenerateThreadSummaryOptions = {
+  /** Model for the summary turn. Defaults to the configured model. */
+  model?: string;
+  /** Upper bound on the abstract's length. Defaults to 120. */
+  maxTokens?: number;
+  config?: NativeConversationConfig;
+};
+
+export type NativeThreadSummary = {
+  threadId: string;
+  title: string;
+  summary: string;
+  model?: string;
+  generatedAt: string;
+};
+
+export type NativeSummarizeAndStoreOptions = {
+  /** Turns condensed into each rollup. Defaults to 8. */
+  turnsPerRollup?: number;
+  /** Model for the summary turns. Defaults to the configured model. */
+  model?: string;
+  /** Upper bound on each rollup's length. Defaults to 200. */
+  maxTokens?: number;
+  config?: NativeConversationConfig;
+};
+
+export type NativeThreadRollup = {
+  /** First turn covered, 0-based and counted by user messages. */
+  startTurn: number;
+  /** Turn after the last one covered. */
+  endTurn: number;
+  /** Index of the first rollout record after the covered turns. */
+  endRecord: number;
+  summary: string;
+  model?: string;
+  generatedAt: string;
+};
+
+export type NativeGenerateCommitMessageOptions = {
+  /** Repository to describe; defaults to `config.workingDirectory`, then the process cwd. */
+  cwd?: string;
+  /** Describe only the staged changes. Defaults to true; false includes unstaged changes to tracked files. */
+  staged?: boolean;
+  /** Model for the turn. Defaults to the configured model. */
+  model?: string;
+  /** Extra instructions, e.g. a commit message convention to follow. */
+  instructions?: string;
+  /** Diff characters sent to the model. Defaults to 60000; longer diffs are cut. */
+  maxDiffChars?: number;
+  config?: NativeConversationConfig;
+};
+
+export type NativeGeneratedCommitMessage = {
+  subject: string;
+  /** Empty when the subject says enough. */
+  body: string;
+  /** `subject` and `body` joined the way `git commit -F` expects. */
+  message: string;
+  /** Whether the diff was cut to `maxDiffChars` before it was sent. */
+  diffTruncated: boolean;
+};
+
+export type NativePrepareImageOptions = {
+  /** Longest side of the prepared image in pixels. Defaults to 2048; smaller images are not scaled up. */
+  maxDimension?: number;
+  /** Defaults to `jpeg` for JPEG sources and `png` otherwise. */
+  format?: "png" | "jpeg";
+  /** Where to write the prepared image; defaults to a new file in the temp directory. */
+  outputPath?: string;
+};
+
+export type NativePreparedImage = {
+  path: string;
+  format: "png" | "jpeg";
+  width: number;
+  height: number;
+  originalWidth: number;
+  originalHeight: number;
+  /** Size of the prepared file in bytes. */
+  bytes: number;
+};
+
+export type NativeGeneratePrDescriptionOptions = {
+  /** Repository to describe; defaults to `config.workingDirectory`, then the process cwd. */
+  cwd?: string;
+  /** Branch the pull request targets, e.g. `origin/main`. */
+  baseRef: string;
+  /** Branch or commit to describe. Defaults to HEAD. */
+  headRef?: string;
+  /** Model for the turn. Defaults to the configured model. */
+  model?: string;
+  /** Extra instructions, e.g. sections the description must have. */
+  instructions?: string;
+  /** Diff characters sent to the model. Defaults to 60000; longer diffs are cut. */
+  maxDiffChars?: number;
+  config?: NativeConversationConfig;
+};
+
+export type NativeGeneratedPrDescription = {
+  title: string;
+  /** Markdown body. */
+  body: string;
+  /** Whether the diff was cut to `maxDiffChars` before it was sent. */
+  diffTruncated: boolean;
+};
+
+export type NativeMemoryPromoteOptions = {
+  tags?: string[];
+  /** Directory whose project stores the memory. Defaults to the thread's working directory. */
+  project?: string;
+};
+
+export type NativeMemoryQueryOptions = {
+  /** Directory whose project is searched. Defaults to the current directory. */
+  project?: string;
+  /** Defaults to 5. */
+  limit?: number;
+  /** Only memories carrying every one of these tags. */
+  tags?: string[];
+  /** Memories scoring below this (0 to 1) are dropped. */
+  minScore?: number;
+};
+
+export type NativeMemoryEntry = {
+  id: string;
+  text: string;
+  tags: string[];
+  /** Thread the memory was promoted from. */
+  threadId?: string;
+  createdAt: string;
+};
+
+export type NativeMemoryMatch = {
+  memory: NativeMemoryEntry;
+  /** 0 to 1. */
+  score: number;
+};
+
+export type NativeThreadPhase =
+  | "starting"
+  | "thinking"
+  | "executing_tool"
+  | "waiting_for_approval"
+  | "idle"
+  | "failed";
+
+export type NativeFileAccessEntry = {
+  /** Absolute path of the file. */
+  path: string;
+  /** Tool calls that read the file. */
+  reads: number;
+  /** Applied patches that added, updated or deleted the file. */
+  writes: number;
+};
+
+export type NativeThreadActivity = {
+  threadId: string;
+  phase: NativeThreadPhase;
+  /** Tool currently executing or awaiting approval. */
+  tool?: string;
+  /** Whether a run for this thread is in progress in this process. */
+  running: boolean;
+  lastEventAt: string;
+  lastEventType?: string;
+  /** Milliseconds since the last event (or approval transition). */
+  idleMs: number;
+  eventCount: number;
+};
+
+// ============================================================================
+// Diagnostics Types
+// ============================================================================
+
+export type NativeEnvironmentReport = {
+  generatedAt: string;
+  os: { platform: string; family: string; arch: string };
+  node: { version: string; napiVersion: number };
+  addon: { name: string; version: string };
+  codex: { userAgent: string; originator: string };
+  sandbox: { backend: string; available: boolean; [key: string]: unknown };
+  /** Redacted config summary, or `{ error }` when config failed to load. */
+  config: Record<string, unknown>;
+  environment: Record<string, "set (redacted)" | "unset">;
+  registeredTools: number;
+  recentRunErrors: Array<{ timestamp: string; threadId?: string | null; message: string }>;
+};
+
+export type NativeConfigDoctorOptions = {
+  /** Thread options to check, as a run would receive them. */
+  config?: NativeConversationConfig;
+  /** `key=value` overrides, as passed to `codex -c`. */
+  configOverrides?: string[];
+};
+
+export type NativeConfigProblem = {
+  severity: "error" | "warning";
+  /** Stable identifier such as `auth.missing`. */
+  code: string;
+  message: string;
+  fix?: string;
+};
+
+export type NativeConfigDoctorReport = {
+  /** No problem is an error. */
+  ok: boolean;
+  codexHome?: string;
+  cwd?: string;
+  model?: string;
+  modelProvider?: string;
+  problems: NativeConfigProblem[];
+};
+
+// ============================================================================
+// Watch Mode Types
+// ============================================================================
+
+export type NativeWatchAndRunOptions = {
+  /** Directory watched recursively. */
+  root: string;
+  /** Globs relative to `root` selecting the files whose changes start a run. All files when unset. */
+  globs?: string[];
+  /** Quiet period after the last change before a run starts (default 500 ms). */
+  debounceMs?: number;
+  /** The run started for each batch of changes. Its prompt is followed by the changed paths. */
+  requestTemplate: NativeRunRequest;
+};
+
+export type NativeWatchEvent =
+  | { type: "watch.started"; id: string; root: string }
+  | { type: "watch.run_started"; run: number; paths: string[] }
+  /** Changes that arrived while a run was in progress; they do not start another run. */
+  | { type: "watch.suppressed"; paths: string[] }
+  | { type: "watch.run_completed"; run: number; thread_id: string | null; error: string | null }
+  | { type: "watch.error"; message: string }
+  | { type: "watch.stopped" };
+
+// ============================================================================
+// Scheduler Types
+// ============================================================================
+
+export type NativeScheduleRunOptions = {
+  /** Runs of the schedule allowed at once; a run due while this many are in progress is skipped (default 1). */
+  maxConcurrent?: number;
+  /** Each run starts after a random delay of up to this many milliseconds (default 0). */
+  jitterMs?: number;
+};
+
+export type NativeScheduleInfo = {
+  id: string;
+  cron: string;
+  prompt: string;
+  maxConcurrent: number;
+  jitterMs: number;
+  createdAt: string;
+  lastRunAt?: string;
+  /** Next time the expression matches, before jitter. */
+  nextRunAt?: string;
+  /** Whether this process runs the schedule. */
+  active: boolean;
+  /** Runs of the schedule in progress in this process. */
+  running: number;
+};
+
+export type NativeScheduleEvent =
+  | { type: "schedule.run_started"; schedule_id: string; run: number; started_at: string }
+  | { type: "schedule.run_skipped"; schedule_id: string; run: number; reason: string }
+  | {
+      type: "schedule.run_completed";
+      schedule_id: string;
+      run: number;
+      thread_id: string | null;
+      error: string | null;
+    }
+  | { type: "schedule.error"; schedule_id: string; message: string };
+
+// ============================================================================
+// Job Queue Types
+// ============================================================================
+
+export type NativeEnqueueRunOptions = {
+  /** Higher runs first (default 0). */
+  priority?: number;
+  /** Attempts before a failing job is given up on (default 3). */
+  maxAttempts?: number;
+};
+
+export type NativeJobStatus = "queued" | "running" | "succeeded" | "failed" | "cancelled";
+
+export type NativeJobInfo = {
+  id: string;
+  prompt: string;
+  priority: number;
+  status: NativeJobStatus;
+  attempts: number;
+  maxAttempts: number;
+  enqueuedAt: string;
+  /** When the latest attempt started. */
+  startedAt?: string;
+  finishedAt?: string;
+  /** Thread of the latest attempt. */
+  threadId?: string;
+  /** Error of the latest failed attempt. */
+  error?: string;
+};
+
+export type NativeQueueStatus = {
+  queued: number;
+  running: number;
+  concurrency: number;
+  /** Queued, running and recently finished jobs, in enqueue order. */
+  jobs: NativeJobInfo[];
+};
+
+// ============================================================================
+// HTTP Server Types
+// ============================================================================
+
+export type NativeHttpServerOptions = {
+  /** Loopback port to listen on; any free port when unset. */
+  port?: number;
+  /** Bearer token clients must send. Generated when unset. */
+  authToken?: string;
+};
+
+export type NativeHttpServer = {
+  id: string;
+  /** `http://127.0.0.1:<port>`; routes live under `/v1`. */
+  url: string;
+  port: number;
+  authToken: string;
+};
+
+// ============================================================================
+// Event Bus Types
+// ============================================================================
+
+export type NativeEventBusOptions = {
+  /** Loopback port to listen on; any free port when unset. */
+  port?: number;
+  /** Token clients must send as `?token=` or a bearer header. Generated when unset. */
+  authToken?: string;
+};
+
+export type NativeEventBus = {
+  id: string;
+  /** `ws://127.0.0.1:<port>`. */
+  url: string;
+  port: number;
+  authToken: string;
+};
+
+/** Messages event bus clients receive. Thread events are in their raw native shape. */
+export type NativeEventBusMessage =
+  | { type: "thread_event"; thread_id: string | null; event: { type: string; [key: string]: unknown } }
+  | { type: "subscribed" }
+  | { type: "lagged"; skipped: number }
+  | { type: "error"; message: string };
+
+// ============================================================================
+// gRPC Server Types
+// ============================================================================
+
+export type NativeGrpcServerOptions = {
+  /** Loopback port to listen on; any free port when unset. */
+  port?: number;
+  /** Token callers must send as `authorization: Bearer <token>` metadata. Generated when unset. */
+  authToken?: string;
+};
+
+export type NativeGrpcServer = {
+  id: string;
+  /** `127.0.0.1:<port>`, ready for a gRPC channel. */
+  address: string;
+  port: number;
+  authToken: string;
+};
+
+// ============================================================================
+// Mock Model Server Types
+// ============================================================================
+
+export type NativeMockModelFixture = {
+  /** Event payloads (e.g. from `evAssistantMessage`) streamed as one SSE response. */
+  events?: string[];
+  /** Raw response body, sent verbatim instead of `events`. */
+  body?: string;
+  statusCode?: number;
+};
+
+export type NativeMockModelServer = {
+  id: string;
+  /** Base URL to pass as `baseUrl`; already includes the `/v1` prefix. */
+  url: string;
+  port: number;
+};
+
+export type NativeMockModelRequest = {
+  path: string;
+  body: string;
+  json?: unknown;
+  headers: Record<string, string>;
+};
+
+// ============================================================================
+// Rollout Cache Types
+// ============================================================================
+
+export type NativeRolloutCacheStats = {
+  entries: number;
+  capacity: number;
+  hits: number;
+  misses: number;
+};
+
+export type NativeArchiveRolloutsOptions = {
+  /** Rollouts last written at least this many days ago are archived. */
+  olderThanDays: number;
+  /** Defaults to `"zstd"`. */
+  compress?: "zstd" | "none";
+  /** Defaults to `$CODEX_HOME/rollout-archives`, the only archive reverie reads. */
+  destination?: string;
+  codexHome?: string;
+  /** Report what would be archived without moving anything. */
+  dryRun?: boolean;
+  /** Encrypt with the key set by `configureArchiveEncryption()`, adding an `.age` extension. */
+  encrypt?: boolean;
+};
+
+export type NativeArchivedRollout = {
+  id: string;
+  originalPath: string;
+  archivePath: string;
+  cwd?: string;
+  createdAt?: string;
+  updatedAt?: string;
+  originalBytes: number;
+  archivedBytes: number;
+};
+
+export type NativeArchiveRolloutsResult = {
+  archived: NativeArchivedRollout[];
+  indexPath: string;
+  bytesBefore: number;
+  bytesAfter: number;
+};
+
+export type NativeArchiveEncryptionOptions = {
+  /** Derive the key from this passphrase. Every process reading the archive needs the same one. */
+  passphrase?: string;
+  /** Use a key kept in the OS keychain, created on first use. */
+  useKeychain?: boolean;
+  /** Keychain keys are per codex home. Defaults to `$CODEX_HOME`. */
+  codexHome?: string;
+};
+
+export type NativeEncryptRolloutArchiveOptions = {
+  codexHome?: string;
+  /**
+   * Also archive, encrypted, live rollouts last written at least this many days ago. Without it,
+   * only rollouts already in the archive are encrypted.
+   */
+  olderThanDays?: number;
+};
+
+export type NativeEncryptRolloutArchiveResult = {
+  /** Archive entries encrypted in place, then newly archived rollouts. */
+  encrypted: NativeArchivedRollout[];
+  indexPath: string;
+};
+
+export type NativeRolloutReaderRecord = {
+  /** 1-based line number in the rollout file. */
+  lineNumber: number;
+  /** The parsed record, absent when the line isn't valid JSON. */
+  record?: unknown;
+  /** TOON encoding of the record, as in reverie's `headRecordsToon`. */
+  toon?: string;
+  messageType?: "user" | "agent" | "reasoning" | "tool" | "system";
+  /** Session metadata or injected instructions rather than conversation content. */
+  isMetadata: boolean;
+  parseError?: string;
+};
+
+export type NativeRolloutReader = {
+  /** The next `n` records (default 100); an empty array once the file is exhausted. */
+  nextBatch(n?: number): NativeRolloutReaderRecord[];
+  readonly done: boolean;
+  /** Lines consumed so far, including blank and invalid ones. */
+  readonly linesRead: number;
+};
+
+// ============================================================================
+// Usage Report Types
+// ============================================================================
+
+export type NativeModelPricing = {
+  /** USD per million uncached input tokens. */
+  inputPerMillion: number;
+  /** USD per million cached input tokens. Default: the input price */
+  cachedInputPerMillion?: number;
+  /** USD per million output tokens, reasoning included. */
+  outputPerMillion: number;
+};
+
+export type NativeUsageReportOptions = {
+  /** Default: "project" */
+  groupBy?: "project" | "model" | "day";
+  /** Inclusive lower bound: an RFC 3339 timestamp or a `YYYY-MM-DD` date (UTC). */
+  since?: string;
+  /** Upper bound: an RFC 3339 timestamp (exclusive) or a `YYYY-MM-DD` date (inclusive). */
+  until?: string;
+  /** Default: `$CODEX_HOME` or `~/.codex` */
+  codexHome?: string;
+  /** Prices keyed by model name or prefix, consulted before the built-in table. */
+  pricing?: Record<string, NativeModelPricing>;
+};
+
+export type NativeUsageReportRow = {
+  /** Project (git remote or working directory), model name, or `YYYY-MM-DD` day. */
+  key: string;
+  sessions: number;
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  reasoningOutputTokens: number;
+  totalTokens: number;
+  /** Estimated cost of the priced models in this row. */
+  estimatedCostUsd?: number;
+  /** Models with no known price; their tokens are excluded from the cost. */
+  unpricedModels: string[];
+};
+
+export type NativeUsageReport = {
+  groupBy: "project" | "model" | "day";
+  since?: string;
+  until?: string;
+  /** Sorted by total tokens, largest first. */
+  rows: NativeUsageReportRow[];
+  totals: NativeUsageReportRow;
+  /** Rows and totals as CSV, with a header line. */
+  csv: string;
+};
+
+export type NativeRecoverInterruptedRunsOptions = {
+  /** Default: `$CODEX_HOME` or `~/.codex` */
+  codexHome?: string;
+  /** Close every unfinished turn and drop its journal entry. Default: false */
+  finalize?: boolean;
+  /** Delete temp files left behind by dead runs. Default: true */
+  cleanupTempFiles?: boolean;
+};
+
+export type NativeFinalizeInterruptedRunOptions = {
+  /** Default: `$CODEX_HOME` or `~/.codex` */
+  codexHome?: string;
+};
+
+export type NativeInterruptedRun = {
+  runId: string;
+  pid: number;
+  startedAt: string;
+  /** Unset when the process died before the thread started. */
+  threadId?: string;
+  prompt: string;
+  workingDirectory?: string;
+  rolloutPath?: string;
+  lastUserMessage?: string;
+  lastAgentMessage?: string;
+  /** Tool calls of the unfinished turn that produced an output. */
+  completedToolCalls: number;
+  /** Names of tool calls that were still running when the process died. */
+  pendingToolCalls: string[];
+  /** Orphaned temp files removed during recovery. */
+  removedTempFiles: string[];
+  /** The rollout exists, so the thread can be resumed. */
+  resumable: boolean;
+  /** The turn has been closed and the journal entry removed. */
+  finalized: boolean;
+  /** Suggested prompt for continuing the interrupted task. */
+  resumePrompt: string;
+};
+
+export type NativeAuthOptions = {
+  /** Default: `$CODEX_HOME` or `~/.codex` */
+  codexHome?: string;
+  /** Named credential profile to act on instead of the default credentials. */
+  profile?: string;
+};
+
+export type NativeAuthStatus = {
+  loggedIn: boolean;
+  mode?: "apiKey" | "chatgpt";
+  /** The stored API key with all but its first 8 and last 5 characters masked. */
+  apiKey?: string;
+  email?: string;
+  planType?: string;
+  accountId?: string;
+  codexHome: string;
+  profile?: string;
+  /** `CODEX_API_KEY` is set, which runs use instead of stored credentials. */
+  envApiKey: boolean;
+};
+
+export type NativeAuthLoginPrompt = {
+  method: "deviceCode" | "browser";
+  url: string;
+  /** One-time code to enter at `url` (device-code method only). */
+  userCode?: string;
+};
+
+export type NativeAuthChatGptLoginOptions = NativeAuthOptions & {
+  /** Default: `"deviceCode"`, falling back to `"browser"` when device codes are disabled. */
+  method?: "deviceCode" | "browser";
+  /** Open the browser login page automatically (browser method only). Default: true. */
+  openBrowser?: boolean;
+  /** Default: 15 minutes. */
+  timeoutMs?: number;
+};
+
+// ============================================================================
+// Reverie System Types
+// ============================================================================
+
+export type ReverieConversation = {
+  id: string;
+  path: string;
+  cwd?: string;
+  createdAt?: string;
+  updatedAt?: string;
+  headRecords: string[];
+  tailRecords: string[];
+  headRecordsToon: string[];
+  tailRecordsToon: string[];
+};
+
+export type ReverieSearchResult = {
+  conversation: ReverieConversation;
+  relevanceScore: number;
+  matchingExcerpts: string[];
+  insights: string[];
+  rerankerScore?: number;
+};
+
+export type FastEmbedRerankerModelCode =
+  | "BAAI/bge-reranker-base"
+  | "rozgo/bge-reranker-v2-m3"
+  | "jinaai/jina-reranker-v1-turbo-en"
+  | "jinaai/jina-reranker-v2-base-multilingual";
+
+export type ReverieSemanticSearchOptions = {
+  limit?: number;
+  maxCandidates?: number;
+  projectRoot?: string;
+  batchSize?: number;
+  normalize?: boolean;
+  cache?: boolean;
+  rerankerModel?: FastEmbedRerankerModelCode;
+  rerankerCacheDir?: string;
+  rerankerMaxLength?: number;
+  rerankerShowProgress?: boolean;
+  rerankerBatchSize?: number;
+  rerankerTopK?: number;
+  /** Drop near-duplicate conversations (forks, retries) and order the rest by maximal marginal relevance. */
+  diversify?: boolean;
+  /** MMR trade-off between relevance (1) and novelty (0). Defaults to 0.7. */
+  diversityLambda?: number;
+  /**
+   * Language for keyword stemming and stop words, as an ISO 639-1 code (`de`) or English name.
+   * Detected from the query when unset.
+   */
+  language?: string;
+  /** Threads that read and prepare candidate conversations in parallel. Defaults to one per CPU. */
+  cpuThreads?: number;
+  /** Bytes of rollout text one search may read while preparing candidates. Defaults to 64 MiB. */
+  maxResidentBytes?: number;
+  /** Stop preparing candidates once twice `limit` strong lexical matches are ready. Defaults to true. */
+  earlyStop?: boolean;
+  /** How long identical searches reuse results, in milliseconds. Defaults to 30000; 0 disables. */
+  resultCacheTtlMs?: number;
+  /**
+   * Shares of the blended relevance score. Unset weights keep their defaults (0.55, 0.15, 0.15,
+   * 0.15), and together they must sum to 1.
+   */
+  semanticWeight?: number;
+  keywordWeight?: number;
+  recencyWeight?: number;
+  importanceWeight?: number;
+  /** Age in days at which the recency component halves. Defaults to 14. */
+  recencyHalfLifeDays?: number;
+};
+
+export type ReverieSearchStats = {
+  /** Rollouts streamed while preparing candidates. */
+  filesScanned: number;
+  /** Bytes of rollout text those reads consumed. */
+  bytesRead: number;
+  elapsedMs: number;
+  /** Conversations embedded and scored. */
+  candidates: number;
+  /** Preparation stopped once enough strong lexical matches were found. */
+  earlyTerminated: boolean;
+  /** `maxResidentBytes` ran out and later candidates used their head/tail previews only. */
+  budgetExhausted: boolean;
+  /** Served from the result cache without reading anything. */
+  cacheHit: boolean;
+};
+
+export type ReverieSemanticSearchResponse = {
+  results: ReverieSearchResult[];
+  stats: ReverieSearchStats;
+};
+
+export type ReverieSemanticIndexStats = {
+  conversationsIndexed: number;
+  documentsEmbedded: number;
+  batches: number;
+};
+
+// ============================================================================
+// FastEmbed Types
+// ============================================================================
+
+export type FastEmbedInitOptions = {
+  model?: string;
+  cacheDir?: string;
+  maxLength?: number;
+  showDownloadProgress?: boolean;
+};
+
+export type FastEmbedEmbedRequest = {
+  inputs: string[];
+  batchSize?: number;
+  normalize?: boolean;
+  projectRoot?: string;
+  cache?: boolean;
+};
+
+// ============================================================================
+// Tokenizer Types
+// ============================================================================
+
+export type TokenizerOptions = {
+  model?: string;
+  encoding?: "o200k_base" | "cl100k_base";
+};
+
+export type TokenizerEncodeOptions = TokenizerOptions & {
+  withSpecialTokens?: boolean;
+};
+
+function ensureCliEntrypointEnv(): void {
+  if (process.env[CLI_ENTRYPOINT_ENV]) {
+    return;
+  }
+
+  const filename = fileURLToPath(import.meta.url);
+  const dirname = path.dirname(filename);
+  const candidates = [
+    path.resolve(dirname, "cli.cjs"),
+    path.resolve(dirname, "../cli.cjs"),
+    path.resolve(dirname, "../dist/cli.cjs"),
+  ];
+
+  for (const candidate of candidates) {
+    if (fs.existsSync(candidate)) {
+      process.env[CLI_ENTRYPOINT_ENV] = candidate;
+      break;
+    }
+  }
+}
+
+ensureCliEntrypointEnv();
+
+export type NativeBinding = {
+  runThread(request: NativeRunRequest): Promise<string[]>;
+  runThreadStream(
+    request: NativeRunRequest,
+    onEvent: (err: unknown, eventJson?: string) => void,
+  ): Promise<void>;
+  compactThread(request: NativeRunRequest): Promise<string[]>;
+  runThreadCollect?(request: NativeRunRequest): Promise<NativeRunThreadCollectResult>;
+  runFromTask?(request: NativeRunFromTaskRequest): Promise<NativeRunFromTaskResult>;
+  runReview?(request: NativeRunReviewRequest): Promise<NativeRunReviewResult>;
+  reviewDiff?(request: NativeReviewDiffRequest): Promise<NativeRunReviewResult>;
+  fetchPrContext?(request: NativeFetchPrContextRequest): Promise<NativeGithubPrContext>;
+  postReviewComments?(request: NativePostReviewCommentsRequest): Promise<NativePostReviewCommentsResult>;
+  parseReviewFindings?(text: string): NativeReviewFindings;
+  buildPrompt?(request: NativeRunRequest, options?: NativeBuildPromptOptions): Promise<NativePromptPreview>;
+  replayThread?(rolloutPath: string, options?: NativeReplayThreadOptions): Promise<string[]>;
+  getThreadItems?(threadId: string, options?: NativeGetThreadItemsOptions | null): Promise<NativeThreadItemsPage>;
+  generateThreadSummary?(
+    threadId: string,
+    options?: NativeGenerateThreadSummaryOptions | null,
+  ): Promise<NativeThreadSummary>;
+  summarizeAndStore?(threadId: string, options?: NativeSummarizeAndStoreOptions | null): Promise<NativeThreadRollup[]>;
+  generateCommitMessage?(options?: NativeGenerateCommitMessageOptions | null): Promise<NativeGeneratedCommitMessage>;
+  generatePrDescription?(options: NativeGeneratePrDescriptionOptions): Promise<NativeGeneratedPrDescription>;
+  prepareImage?(source: string | { clipboard: true }, options?: NativePrepareImageOptions | null): Promise<NativePreparedImage>;
+  memoryPromote?(
+    threadId: string,
+    source: string | string[],
+    options?: NativeMemoryPromoteOptions | null,
+  ): Promise<NativeMemoryEntry[]>;
+  memoryQuery?(query: string, options?: NativeMemoryQueryOptions | null): Promise<NativeMemoryMatch[]>;
+  memoryForget?(id: string, project?: string | null): Promise<boolean>;
+  getThreadActivity?(threadId: string): NativeThreadActivity | null;
+  getThreadFileAccess?(threadId: string): NativeFileAccessEntry[];
+  forkThread(request: NativeForkRequest): Promise<NativeForkResult>;
+  resumeThreadWithEdits?(request: NativeForkRequest, edits: NativeThreadEdits): Promise<NativeForkResult>;
+  listConversations(request: NativeConversationListRequest): Promise<NativeConversationListPage>;
+  deleteConversation(request: NativeDeleteConversationRequest): Promise<NativeDeleteConversationResult>;
+  resumeConversationFromRollout(request: NativeResumeFromRolloutRequest): Promise<NativeForkResult>;
+  runTui(request: NativeTuiRequest): Promise<NativeTuiExitInfo>;
+  tuiTestRun?(request: {
+    width: number;
+    height: number;
+    viewport: { x: number; y: number; width: number; height: number };
+    lines: string[];
+  }): Promise<string[]>;
+  TuiTestSession?: new (options?: NativeTuiTestSessionOptions) => NativeTuiTestSession;
+  TuiApp?: new (title?: string, width?: number, height?: number) => NativeTuiApp;
+  AgentView?: new (threadId: string, title?: string) => NativeAgentView;
+  StatusBoard?: new (layout?: string) => NativeStatusBoard;
+  AgentGraphRenderer?: {
+    new (): NativeAgentGraphRenderer;
+    fromCodexHome(codexHomePath: string, options?: NativeAgentGraphHistoryOptions): NativeAgentGraphRenderer;
+  };
+  diffTuiSnapshots?(a: NativeTuiStyledSnapshot, b: NativeTuiStyledSnapshot): NativeTuiSnapshotDiff;
+  callToolBuiltin(token: string, invocation?: NativeToolInvocation): Promise<NativeToolResult>;
+  callRegisteredToolForTest?(
+    toolName: string,
+    invocation: NativeToolInvocation,
+  ): Promise<NativeToolResult>;
   clearRegisteredTools(): void;
   registerTool(info: NativeToolInfo, handler: (call: NativeToolInvocation) => Promise<NativeToolResult> | NativeToolResult): void;
   registerToolInterceptor(toolName: string, handler: (context: NativeToolInterceptorNativeContext) => Promise<NativeToolResult> | NativeToolResult): void;
   listRegisteredTools(): NativeToolInfo[];
+  emitToolOutputChunk?(callId: string, chunk: string): void;
+  setThreadStatus?(threadId: string, status: NativeThreadStatus | null): void;
+  callBuiltinTool?(request: NativeBuiltinToolCallRequest): Promise<NativeToolResult>;
+  spawnBackgroundTool?(
+    invocation: NativeToolInvocation,
+    run: (call: NativeToolInvocation) => Promise<NativeToolResult> | NativeToolResult,
+  ): NativeToolResult;
   registerApprovalCallback?(
     handler: (request: ApprovalRequest) => boolean | Promise<boolean>,
   ): void;
+  registerEscalationHandler?(handler: NativeEscalationHandler): string;
+  unregisterEscalationHandler?(id: string): boolean;
+  registerHook?(
+    event: NativeHookEvent,
+    handler: (context: NativeHookContext) => NativeHookResult | void | Promise<NativeHookResult | void>,
+  ): void;
+  clearHooks?(): void;
+  registerGuardrail?(direction: NativeGuardrailDirection, handler: NativeGuardrail["handler"]): void;
+  clearGuardrails?(): void;
+  definePermissionProfile?(name: string, profile: NativePermissionProfile): void;
+  clearPermissionProfiles?(): void;
+  registerPromptTemplate?(name: string, template: string): void;
+  clearPromptTemplates?(): void;
+  renderPromptTemplate?(request: NativePromptTemplateRequest, model?: string): string;
+  enableAuditLog?(path: string, options?: NativeAuditLogOptions): void;
+  disableAuditLog?(): void;
+  verifyAuditLog?(path: string): NativeAuditLogVerification;
   emitBackgroundEvent(request: NativeEmitBackgroundEventRequest): Promise<void>;
   emitPlanUpdate(request: NativeEmitPlanUpdateRequest): Promise<void>;
   modifyPlan(request: NativeModifyPlanRequest): Promise<void>;
+  getThreadPlan?(threadId: string): NativeThreadPlan | null;
+  resetExecSession?(threadId: string): boolean;
+  lspAttach?(root: string, options: NativeLspAttachOptions): Promise<NativeLspServerInfo>;
+  lspDetach?(root: string): Promise<boolean>;
+  runTests?(options?: NativeRunTestsOptions): Promise<TestResults>;
   startTui(request: NativeTuiRequest): NativeTuiSession;
   // SSE test helpers (exposed for TypeScript tests)
   ev_completed(id: string): string;
@@ -403,23 +2010,70 @@ export type NativeBinding = {
   ev_assistant_message(id: string, text: string): string;
   ev_function_call(callId: string, name: string, args: string): string;
   sse(events: string[]): string;
+  watchAndRun?(
+    options: NativeWatchAndRunOptions,
+    onEvent: (err: unknown, eventJson?: string) => void,
+  ): string;
+  stopWatch?(id: string): boolean;
+  scheduleRun?(
+    cron: string,
+    request: NativeRunRequest,
+    options?: NativeScheduleRunOptions,
+    onEvent?: (err: unknown, eventJson?: string) => void,
+  ): NativeScheduleInfo;
+  listSchedules?(): NativeScheduleInfo[];
+  cancelSchedule?(id: string): boolean;
+  resumeSchedules?(onEvent?: (err: unknown, eventJson?: string) => void): NativeScheduleInfo[];
+  enqueueRun?(request: NativeRunRequest, options?: NativeEnqueueRunOptions): NativeJobInfo;
+  queueStatus?(): NativeQueueStatus;
+  cancelJob?(id: string): boolean;
+  configureJobQueue?(concurrency: number): void;
+  startHttpServer?(options?: NativeHttpServerOptions): NativeHttpServer;
+  stopHttpServer?(id: string): boolean;
+  startEventBus?(options?: NativeEventBusOptions): NativeEventBus;
+  stopEventBus?(id: string): boolean;
+  startGrpcServer?(options?: NativeGrpcServerOptions): NativeGrpcServer;
+  stopGrpcServer?(id: string): boolean;
+  startMockModelServer?(fixtures: NativeMockModelFixture[]): NativeMockModelServer;
+  stopMockModelServer?(id: string): NativeMockModelRequest[];
   ensureTokioRuntime?: () => void;
   isTokioRuntimeAvailable?: () => boolean;
-  // Cloud tasks support (JSON-string payload responses)
-  cloudTasksList?(env?: string, baseUrl?: string, apiKey?: string): Promise<string>;
+  // Cloud tasks support
+  cloudTasksList?(
+    env?: string,
+    limit?: number,
+    cursor?: string,
+    baseUrl?: string,
+    apiKey?: string,
+    status?: NativeCloudTaskStatus,
+  ): Promise<NativeCloudTaskListPage>;
+  cloudTasksGet?(taskId: string, baseUrl?: string, apiKey?: string): Promise<NativeCloudTaskDetails>;
+  cloudTasksCancel?(taskId: string, baseUrl?: string, apiKey?: string): Promise<NativeCloudTaskSummary>;
+  cloudTasksAwait?(
+    taskId: string,
+    options?: NativeCloudTaskAwaitOptions,
+    baseUrl?: string,
+    apiKey?: string,
+  ): Promise<NativeCloudTaskSummary>;
   cloudTasksGetDiff?(taskId: string, baseUrl?: string, apiKey?: string): Promise<string>;
   cloudTasksApplyPreflight?(
     taskId: string,
     diffOverride?: string,
     baseUrl?: string,
     apiKey?: string,
-  ): Promise<string>;
+  ): Promise<NativeCloudApplyOutcome>;
   cloudTasksApply?(
     taskId: string,
     diffOverride?: string,
     baseUrl?: string,
     apiKey?: string,
-  ): Promise<string>;
+  ): Promise<NativeCloudApplyOutcome>;
+  cloudTasksApplyResult?(
+    taskId: string,
+    options?: NativeCloudTaskApplyResultOptions,
+    baseUrl?: string,
+    apiKey?: string,
+  ): Promise<NativeCloudApplyOutcome>;
   cloudTasksCreate?(
     envId: string,
     prompt: string,
@@ -428,7 +2082,41 @@ export type NativeBinding = {
     bestOfN?: number,
     baseUrl?: string,
     apiKey?: string,
-  ): Promise<string>;
+  ): Promise<NativeCloudTaskCreated>;
+  // Model providers - registrations forwarded to runs selecting `modelProvider`
+  registerModelProvider?(registration: NativeModelProviderRegistration): void;
+  listRegisteredModelProviders?(): NativeModelProviderRegistration[];
+  clearRegisteredModelProviders?(): void;
+  // Diagnostics
+  captureEnvironmentReport?(): NativeEnvironmentReport;
+  configDoctor?(options?: NativeConfigDoctorOptions): Promise<NativeConfigDoctorReport>;
+  // Rollout cache - parsed rollouts shared by replay and reverie
+  configureRolloutCache?(maxEntries: number): void;
+  invalidateRolloutCache?(path?: string | null): void;
+  rolloutCacheStats?(): NativeRolloutCacheStats;
+  openRolloutReader?(path: string): NativeRolloutReader;
+  archiveRollouts?(options: NativeArchiveRolloutsOptions): Promise<NativeArchiveRolloutsResult>;
+  configureArchiveEncryption?(options?: NativeArchiveEncryptionOptions | null): void;
+  encryptRolloutArchive?(
+    options?: NativeEncryptRolloutArchiveOptions | null,
+  ): Promise<NativeEncryptRolloutArchiveResult>;
+  // Usage reports - token usage and estimated cost aggregated from rollouts
+  usageReport?(options?: NativeUsageReportOptions): Promise<NativeUsageReport>;
+  // Run recovery - runs cut off when their host process exited
+  recoverInterruptedRuns?(options?: NativeRecoverInterruptedRunsOptions): Promise<NativeInterruptedRun[]>;
+  finalizeInterruptedRun?(
+    runId: string,
+    options?: NativeFinalizeInterruptedRunOptions,
+  ): Promise<NativeInterruptedRun>;
+  // Auth - credentials stored in codex home, as managed by `codex login`
+  authStatus?(options?: NativeAuthOptions): Promise<NativeAuthStatus>;
+  authLoginApiKey?(apiKey: string, options?: NativeAuthOptions): Promise<NativeAuthStatus>;
+  authLoginChatGpt?(
+    options?: NativeAuthChatGptLoginOptions,
+    onPrompt?: (err: unknown, prompt?: NativeAuthLoginPrompt) => void,
+  ): Promise<NativeAuthStatus>;
+  authLogout?(options?: NativeAuthOptions): Promise<boolean>;
+  authProfiles?(options?: NativeAuthOptions): Promise<NativeAuthStatus[]>;
   // Reverie system - conversation search and insights
   reverieListConversations(codexHomePath: string, limit?: number, offset?: number): Promise<ReverieConversation[]>;
   reverieSearchConversations(codexHomePath: string, query: string, limit?: number): Promise<ReverieSearchResult[]>;
@@ -437,12 +2125,23 @@ export type NativeBinding = {
     context: string,
     options?: ReverieSemanticSearchOptions,
   ): Promise<ReverieSearchResult[]>;
+  reverieSearchSemanticWithStats?(
+    codexHomePath: string,
+    contextText: string,
+    options?: ReverieSemanticSearchOptions,
+  ): Promise<ReverieSemanticSearchResponse>;
+  reverieRelatedThreads?(
+    threadId: string,
+    limit?: number,
+    options?: ReverieSemanticSearchOptions,
+  ): Promise<ReverieSearchResult[]>;
   reverieIndexSemantic?(
     codexHomePath: string,
     options?: ReverieSemanticSearchOptions,
   ): Promise<ReverieSemanticIndexStats>;
   reverieGetConversationInsights(conversationPath: string, query?: string): Promise<string[]>;
   toonEncode(value: unknown): string;
+  toonDecode?(text: string): NativeDecodedToon;
   // FastEmbed hooks
   fastEmbedInit?(options: FastEmbedInitOptions): Promise<void>;
   fastEmbedEmbed?(request: FastEmbedEmbedRequest): Promise<number[][]>;
@@ -450,6 +2149,16 @@ export type NativeBinding = {
   tokenizerCount(text: string, options?: TokenizerOptions): number;
   tokenizerEncode(text: string, options?: TokenizerEncodeOptions): number[];
   tokenizerDecode(tokens: number[], options?: TokenizerOptions): string;
+  searchWorkspace?(options: NativeSearchWorkspaceOptions): Promise<NativeSearchWorkspaceResult>;
+  workspaceIndexBuild?(
+    root: string,
+    options?: NativeWorkspaceIndexBuildOptions,
+  ): Promise<NativeWorkspaceIndexStats>;
+  workspaceSearchSemantic?(
+    root: string,
+    query: string,
+    options?: NativeWorkspaceSemanticSearchOptions,
+  ): Promise<NativeWorkspaceChunkMatch[]>;
   collectRepoDiffSummary?(
     cwd: string,
     baseBranchOverride?: string,
@@ -457,12 +2166,89 @@ export type NativeBinding = {
   ): Promise<RepoDiffSummary>;
 };
 
+export type NativeSearchWorkspaceOptions = {
+  /** Directory to search. Defaults to the process cwd. */
+  root?: string;
+  /** A regular expression, or a literal with `fixedStrings`. */
+  query: string;
+  /** ripgrep globs, e.g. `*.ts` or `!dist/**`. */
+  globs?: string[];
+  /** Defaults to 200, at most 5000. */
+  maxResults?: number;
+  fixedStrings?: boolean;
+  /** Defaults to smart case: case-insensitive unless the query has an uppercase letter. */
+  caseSensitive?: boolean;
+};
+
+export type NativeWorkspaceMatch = {
+  /** Path relative to the search root. */
+  path: string;
+  lineNumber: number;
+  /** 1-based byte column of the first match. */
+  column: number;
+  /** The matched line, without its line ending, cut at 500 bytes. */
+  text: string;
+  /** Byte ranges of every match within `text`. */
+  ranges: Array<{ start: number; end: number }>;
+};
+
+export type NativeSearchWorkspaceResult = {
+  matches: NativeWorkspaceMatch[];
+  /** Whether the search stopped at `maxResults` before finishing. */
+  truncated: boolean;
+};
+
+export type NativeWorkspaceIndexBuildOptions = {
+  /** ripgrep globs selecting the files to index, e.g. `*.rs` or `!vendor/**`. */
+  globs?: string[];
+  chunking?: {
+    /** Lines per chunk. Defaults to 60. */
+    maxLines?: number;
+    /** Lines shared by consecutive chunks. Defaults to 10. */
+    overlapLines?: number;
+  };
+  /** Larger files are skipped. Defaults to 512 KiB. */
+  maxFileBytes?: number;
+  batchSize?: number;
+};
+
+export type NativeWorkspaceIndexStats = {
+  indexPath: string;
+  filesIndexed: number;
+  /** Files that were binary, not UTF-8, unreadable or over `maxFileBytes`. */
+  filesSkipped: number;
+  chunksEmbedded: number;
+};
+
+export type NativeWorkspaceSemanticSearchOptions = {
+  /** Defaults to 10. */
+  limit?: number;
+  /** Chunks scoring below this cosine similarity are dropped. */
+  minScore?: number;
+};
+
+export type NativeWorkspaceChunkMatch = {
+  /** Path relative to the indexed root. */
+  path: string;
+  startLine: number;
+  endLine: number;
+  text: string;
+  /** Cosine similarity to the query. */
+  score: number;
+};
+
 export type NativeToolInfo = {
   name: string;
   description?: string;
   parameters?: unknown;
   strict?: boolean;
   supportsParallel?: boolean;
+  /** Calls of the tool allowed to run at once. Must be at least 1. */
+  maxConcurrent?: number;
+  /** Minimum milliseconds between the starts of two calls of the tool. */
+  minIntervalMs?: number;
+  /** Seconds to reuse successful results of identical calls within a run. */
+  cacheTtlSeconds?: number;
 };
 
 export type NativeToolInvocation = {
@@ -719,32 +2505,197 @@ export function getNativeBinding(): NativeBinding | null {
 // SSE test helpers (exposed for TypeScript tests)
 export function ev_completed(id: string): string {
   const binding = getNativeBinding();
-  if (!binding) throw new Error("Native binding not available");
-  return (binding as any).evCompleted(id);
+  if (!binding) throw new Error("Native binding not available");
+  return (binding as any).evCompleted(id);
+}
+
+export function ev_response_created(id: string): string {
+  const binding = getNativeBinding();
+  if (!binding) throw new Error("Native binding not available");
+  return (binding as any).evResponseCreated(id);
+}
+
+export function ev_assistant_message(id: string, text: string): string {
+  const binding = getNativeBinding();
+  if (!binding) throw new Error("Native binding not available");
+  return (binding as any).evAssistantMessage(id, text);
+}
+
+export function ev_function_call(callId: string, name: string, args: string): string {
+  const binding = getNativeBinding();
+  if (!binding) throw new Error("Native binding not available");
+  return (binding as any).evFunctionCall(callId, name, args);
+}
+
+export function sse(events: string[]): string {
+  const binding = getNativeBinding();
+  if (!binding) throw new Error("Native binding not available");
+  return (binding as any).sse(events);
+}
+
+/**
+ * Watch `options.root` and start `options.requestTemplate` whenever matching files change.
+ * `onEvent` receives `watch.*` events and the runs' thread events. Returns the watcher id.
+ */
+export function watchAndRun(
+  options: NativeWatchAndRunOptions,
+  onEvent: (event: NativeWatchEvent | ThreadEvent) => void,
+): string {
+  const binding = getNativeBinding();
+  if (!binding?.watchAndRun) throw new Error("Native binding not available or watch mode not supported");
+  return binding.watchAndRun(options, (err, eventJson) => {
+    if (err || !eventJson) return;
+    const parsed = JSON.parse(eventJson);
+    onEvent(
+      typeof parsed?.type === "string" && parsed.type.startsWith("watch.")
+        ? (parsed as NativeWatchEvent)
+        : convertRustEventToThreadEvent(parsed),
+    );
+  });
+}
+
+/** Stop a watcher started by watchAndRun(). A run in progress finishes. */
+export function stopWatch(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.stopWatch) throw new Error("Native binding not available or watch mode not supported");
+  return binding.stopWatch(id);
+}
+
+function scheduleEventCallback(onEvent?: (event: NativeScheduleEvent) => void) {
+  if (!onEvent) return undefined;
+  return (err: unknown, eventJson?: string) => {
+    if (err || !eventJson) return;
+    onEvent(JSON.parse(eventJson) as NativeScheduleEvent);
+  };
+}
+
+/**
+ * Run `request` every time the five-field cron expression (local time) matches. The schedule is
+ * stored in codex home without `apiKey`; `onEvent` receives `schedule.*` lifecycle events.
+ */
+export function scheduleRun(
+  cron: string,
+  request: NativeRunRequest,
+  options?: NativeScheduleRunOptions,
+  onEvent?: (event: NativeScheduleEvent) => void,
+): NativeScheduleInfo {
+  const binding = getNativeBinding();
+  if (!binding?.scheduleRun) throw new Error("Native binding not available or scheduling not supported");
+  return binding.scheduleRun(cron, request, options, scheduleEventCallback(onEvent));
+}
+
+/** Every stored schedule, whether or not this process runs it. */
+export function listSchedules(): NativeScheduleInfo[] {
+  const binding = getNativeBinding();
+  if (!binding?.listSchedules) throw new Error("Native binding not available or scheduling not supported");
+  return binding.listSchedules();
+}
+
+/** Delete a schedule and stop running it. Runs in progress finish. */
+export function cancelSchedule(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.cancelSchedule) throw new Error("Native binding not available or scheduling not supported");
+  return binding.cancelSchedule(id);
+}
+
+/** Start running the stored schedules this process does not run yet, e.g. after a restart. */
+export function resumeSchedules(onEvent?: (event: NativeScheduleEvent) => void): NativeScheduleInfo[] {
+  const binding = getNativeBinding();
+  if (!binding?.resumeSchedules) throw new Error("Native binding not available or scheduling not supported");
+  return binding.resumeSchedules(scheduleEventCallback(onEvent));
+}
+
+/**
+ * Queue `request` to run after the jobs ahead of it. The queue is journaled in codex home, so
+ * queued and interrupted jobs run again after a restart.
+ */
+export function enqueueRun(request: NativeRunRequest, options?: NativeEnqueueRunOptions): NativeJobInfo {
+  const binding = getNativeBinding();
+  if (!binding?.enqueueRun) throw new Error("Native binding not available or job queue not supported");
+  return binding.enqueueRun(request, options);
+}
+
+export function queueStatus(): NativeQueueStatus {
+  const binding = getNativeBinding();
+  if (!binding?.queueStatus) throw new Error("Native binding not available or job queue not supported");
+  return binding.queueStatus();
+}
+
+/** Cancel a queued job. A running job finishes its attempt but is not retried. */
+export function cancelJob(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.cancelJob) throw new Error("Native binding not available or job queue not supported");
+  return binding.cancelJob(id);
+}
+
+/** Set how many queued jobs run at once (default 1). */
+export function configureJobQueue(concurrency: number): void {
+  const binding = getNativeBinding();
+  if (!binding?.configureJobQueue) throw new Error("Native binding not available or job queue not supported");
+  binding.configureJobQueue(concurrency);
+}
+
+/**
+ * Serve runs, streaming, forks, thread listing, reverie search and webhook tool registration over
+ * HTTP on 127.0.0.1, for consumers that cannot load the native addon.
+ */
+export function startHttpServer(options?: NativeHttpServerOptions): NativeHttpServer {
+  const binding = getNativeBinding();
+  if (!binding?.startHttpServer) throw new Error("Native binding not available or HTTP server not supported");
+  return binding.startHttpServer(options);
+}
+
+/** Stop an HTTP server started by startHttpServer(). Runs in progress finish. */
+export function stopHttpServer(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.stopHttpServer) throw new Error("Native binding not available or HTTP server not supported");
+  return binding.stopHttpServer(id);
+}
+
+/**
+ * Broadcast every thread event of this process over WebSocket on 127.0.0.1, so another process
+ * can observe the runs. Clients filter with `?threadId=`/`?eventType=` or a `subscribe` message.
+ */
+export function startEventBus(options?: NativeEventBusOptions): NativeEventBus {
+  const binding = getNativeBinding();
+  if (!binding?.startEventBus) throw new Error("Native binding not available or event bus not supported");
+  return binding.startEventBus(options);
+}
+
+/** Disconnect the clients of an event bus and stop listening. */
+export function stopEventBus(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.stopEventBus) throw new Error("Native binding not available or event bus not supported");
+  return binding.stopEventBus(id);
 }
 
-export function ev_response_created(id: string): string {
+/**
+ * Serve `codex_native.v1.CodexNative` (proto/codex_native.proto) on 127.0.0.1 for non-Node
+ * callers. Only available in bindings built with the `grpc` feature.
+ */
+export function startGrpcServer(options?: NativeGrpcServerOptions): NativeGrpcServer {
   const binding = getNativeBinding();
-  if (!binding) throw new Error("Native binding not available");
-  return (binding as any).evResponseCreated(id);
+  if (!binding?.startGrpcServer) throw new Error("Native binding not available or gRPC server not supported");
+  return binding.startGrpcServer(options);
 }
 
-export function ev_assistant_message(id: string, text: string): string {
+/** Stop a gRPC server started by startGrpcServer(). */
+export function stopGrpcServer(id: string): boolean {
   const binding = getNativeBinding();
-  if (!binding) throw new Error("Native binding not available");
-  return (binding as any).evAssistantMessage(id, text);
+  if (!binding?.stopGrpcServer) throw new Error("Native binding not available or gRPC server not supported");
+  return binding.stopGrpcServer(id);
 }
 
-export function ev_function_call(callId: string, name: string, args: string): string {
+export function startMockModelServer(fixtures: NativeMockModelFixture[]): NativeMockModelServer {
   const binding = getNativeBinding();
-  if (!binding) throw new Error("Native binding not available");
-  return (binding as any).evFunctionCall(callId, name, args);
+  if (!binding?.startMockModelServer) throw new Error("Native binding not available or mock model server not supported");
+  return binding.startMockModelServer(fixtures);
 }
 
-export function sse(events: string[]): string {
+export function stopMockModelServer(id: string): NativeMockModelRequest[] {
   const binding = getNativeBinding();
-  if (!binding) throw new Error("Native binding not available");
-  return (binding as any).sse(events);
+  if (!binding?.stopMockModelServer) throw new Error("Native binding not available or mock model server not supported");
+  return binding.stopMockModelServer(id);
 }
 
 export function runApplyPatch(patch: string): void {
@@ -787,6 +2738,32 @@ export async function reverieSearchSemantic(
   return (binding as any).reverieSearchSemantic(codexHomePath, context, options);
 }
 
+/** `reverieSearchSemantic` plus stats on the files and bytes the search read and its duration. */
+export async function reverieSearchSemanticWithStats(
+  codexHomePath: string,
+  context: string,
+  options?: ReverieSemanticSearchOptions,
+): Promise<ReverieSemanticSearchResponse> {
+  const binding = getNativeBinding();
+  if (!binding?.reverieSearchSemanticWithStats) {
+    throw new Error("Native binding not available or reverieSearchSemanticWithStats not supported");
+  }
+  return binding.reverieSearchSemanticWithStats(codexHomePath, context, options);
+}
+
+/** Other sessions most similar to a thread, searched with its rollups or summary. */
+export async function reverieRelatedThreads(
+  threadId: string,
+  limit?: number,
+  options?: ReverieSemanticSearchOptions,
+): Promise<ReverieSearchResult[]> {
+  const binding = getNativeBinding();
+  if (!binding?.reverieRelatedThreads) {
+    throw new Error("Native binding not available or reverieRelatedThreads not supported");
+  }
+  return binding.reverieRelatedThreads(threadId, limit, options);
+}
+
 export async function reverieIndexSemantic(
   codexHomePath: string,
   options?: ReverieSemanticSearchOptions,
@@ -811,6 +2788,539 @@ export function encodeToToon(value: unknown): string {
   return (binding as any).toonEncode(value);
 }
 
+export type NativeDecodedToon = {
+  value: unknown;
+  /** JSON paths (e.g. `$.users[2].id`) of values JavaScript can't hold exactly, such as integers past `Number.MAX_SAFE_INTEGER`. */
+  lossyFields: string[];
+};
+
+export function decodeToon(text: string): NativeDecodedToon {
+  const binding = getNativeBinding();
+  if (!binding?.toonDecode) throw new Error("Native binding not available or toon decoder not supported");
+  return binding.toonDecode(text);
+}
+
+export function registerModelProvider(registration: NativeModelProviderRegistration): void {
+  const binding = getNativeBinding();
+  if (!binding?.registerModelProvider) throw new Error("Native binding not available or model providers not supported");
+  binding.registerModelProvider(registration);
+}
+
+export function listRegisteredModelProviders(): NativeModelProviderRegistration[] {
+  const binding = getNativeBinding();
+  if (!binding?.listRegisteredModelProviders) throw new Error("Native binding not available or model providers not supported");
+  return binding.listRegisteredModelProviders();
+}
+
+export function clearRegisteredModelProviders(): void {
+  const binding = getNativeBinding();
+  if (!binding?.clearRegisteredModelProviders) throw new Error("Native binding not available or model providers not supported");
+  binding.clearRegisteredModelProviders();
+}
+
+export function registerGuardrail(guardrail: NativeGuardrail): void {
+  const binding = getNativeBinding();
+  if (!binding?.registerGuardrail) throw new Error("Native binding not available or guardrails not supported");
+  binding.registerGuardrail(guardrail.direction, guardrail.handler);
+}
+
+export function clearGuardrails(): void {
+  const binding = getNativeBinding();
+  if (!binding?.clearGuardrails) throw new Error("Native binding not available or guardrails not supported");
+  binding.clearGuardrails();
+}
+
+export function definePermissionProfile(name: string, profile: NativePermissionProfile): void {
+  const binding = getNativeBinding();
+  if (!binding?.definePermissionProfile) {
+    throw new Error("Native binding not available or permission profiles not supported");
+  }
+  binding.definePermissionProfile(name, profile);
+}
+
+export function clearPermissionProfiles(): void {
+  const binding = getNativeBinding();
+  if (!binding?.clearPermissionProfiles) {
+    throw new Error("Native binding not available or permission profiles not supported");
+  }
+  binding.clearPermissionProfiles();
+}
+
+export function registerPromptTemplate(name: string, template: string): void {
+  const binding = getNativeBinding();
+  if (!binding?.registerPromptTemplate) {
+    throw new Error("Native binding not available or prompt templates not supported");
+  }
+  binding.registerPromptTemplate(name, template);
+}
+
+export function clearPromptTemplates(): void {
+  const binding = getNativeBinding();
+  if (!binding?.clearPromptTemplates) {
+    throw new Error("Native binding not available or prompt templates not supported");
+  }
+  binding.clearPromptTemplates();
+}
+
+export function renderPromptTemplate(request: NativePromptTemplateRequest, model?: string): string {
+  const binding = getNativeBinding();
+  if (!binding?.renderPromptTemplate) {
+    throw new Error("Native binding not available or prompt templates not supported");
+  }
+  return binding.renderPromptTemplate(request, model);
+}
+
+export function enableAuditLog(path: string, options?: NativeAuditLogOptions): void {
+  const binding = getNativeBinding();
+  if (!binding?.enableAuditLog) throw new Error("Native binding not available or audit log not supported");
+  binding.enableAuditLog(path, options);
+}
+
+export function disableAuditLog(): void {
+  const binding = getNativeBinding();
+  if (!binding?.disableAuditLog) throw new Error("Native binding not available or audit log not supported");
+  binding.disableAuditLog();
+}
+
+export function verifyAuditLog(path: string): NativeAuditLogVerification {
+  const binding = getNativeBinding();
+  if (!binding?.verifyAuditLog) throw new Error("Native binding not available or audit log not supported");
+  return binding.verifyAuditLog(path);
+}
+
+/** Streams partial output for a running registered tool call; `callId` comes from its invocation. */
+export function emitToolOutputChunk(callId: string, chunk: string): void {
+  const binding = getNativeBinding();
+  if (!binding?.emitToolOutputChunk) {
+    throw new Error("Native binding not available or tool output streaming not supported");
+  }
+  binding.emitToolOutputChunk(callId, chunk);
+}
+
+/** The thread's newest plan, from the model's `update_plan` calls or `emitPlanUpdate()`. */
+export function getThreadPlan(threadId: string): NativeThreadPlan | null {
+  const binding = getNativeBinding();
+  if (!binding?.getThreadPlan) throw new Error("Native binding not available or getThreadPlan not supported");
+  return binding.getThreadPlan(threadId);
+}
+
+/**
+ * Ends the thread's persistent shell (`execSession: "persistent"`), so its next shell command
+ * starts from a fresh one. Returns `false` when the thread has no shell running.
+ */
+export function resetExecSession(threadId: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.resetExecSession) {
+    throw new Error("Native binding not available or exec sessions not supported");
+  }
+  return binding.resetExecSession(threadId);
+}
+
+/**
+ * Starts a language server for the workspace at `root`. While one is attached, runs get the
+ * `get_diagnostics` and `goto_definition` tools. Replaces a server already attached at `root`.
+ */
+export function lspAttach(root: string, options: NativeLspAttachOptions): Promise<NativeLspServerInfo> {
+  const binding = getNativeBinding();
+  if (!binding?.lspAttach) throw new Error("Native binding not available or lspAttach not supported");
+  return binding.lspAttach(root, options);
+}
+
+/** Shuts down the language server attached at `root`. Resolves `false` when none is attached. */
+export function lspDetach(root: string): Promise<boolean> {
+  const binding = getNativeBinding();
+  if (!binding?.lspDetach) throw new Error("Native binding not available or lspDetach not supported");
+  return binding.lspDetach(root);
+}
+
+/**
+ * Runs the project's tests (cargo test, jest or pytest) in the sandbox and parses the output into
+ * per-test results.
+ */
+export function runTests(options?: NativeRunTestsOptions): Promise<TestResults> {
+  const binding = getNativeBinding();
+  if (!binding?.runTests) throw new Error("Native binding not available or runTests not supported");
+  return binding.runTests(options);
+}
+
+/**
+ * Reports progress for a thread: streamed as a `thread_status` event to an active run and shown
+ * under the TUI's working indicator. Pass `null` to clear it.
+ */
+export function setThreadStatus(threadId: string, status: NativeThreadStatus | null): void {
+  const binding = getNativeBinding();
+  if (!binding?.setThreadStatus) {
+    throw new Error("Native binding not available or thread status not supported");
+  }
+  binding.setThreadStatus(threadId, status);
+}
+
+/**
+ * Runs `run` in the background for a registered tool call and returns the result the tool's
+ * handler should return right away: a task handle the model checks with `check_background_task`.
+ */
+export function spawnBackgroundTool(
+  invocation: NativeToolInvocation,
+  run: (call: NativeToolInvocation) => Promise<NativeToolResult> | NativeToolResult,
+): NativeToolResult {
+  const binding = getNativeBinding();
+  if (!binding?.spawnBackgroundTool) {
+    throw new Error("Native binding not available or background tools not supported");
+  }
+  return binding.spawnBackgroundTool(invocation, run);
+}
+
+/** Runs a builtin tool outside any model turn, under the same sandbox a run would use. */
+export async function callBuiltinTool(request: NativeBuiltinToolCallRequest): Promise<NativeToolResult> {
+  const binding = getNativeBinding();
+  if (!binding?.callBuiltinTool) {
+    throw new Error("Native binding not available or direct builtin calls not supported");
+  }
+  return binding.callBuiltinTool(request);
+}
+
+/** Runs a turn and returns its items, final message, usage and rollout path; throws if the turn fails. */
+export async function runThreadCollect(request: NativeRunRequest): Promise<NativeRunThreadCollectResult> {
+  const binding = getNativeBinding();
+  if (!binding?.runThreadCollect) throw new Error("Native binding not available or runThreadCollect not supported");
+  return binding.runThreadCollect(request);
+}
+
+/** Runs an issue or ticket payload to completion and links the thread to the task. */
+export async function runFromTask(request: NativeRunFromTaskRequest): Promise<NativeRunFromTaskResult> {
+  const binding = getNativeBinding();
+  if (!binding?.runFromTask) throw new Error("Native binding not available or runFromTask not supported");
+  return binding.runFromTask(request);
+}
+
+/** Registers a handler for sandbox escalations; pass the returned id as a run's `escalationHandler`. */
+export function registerEscalationHandler(handler: NativeEscalationHandler): string {
+  const binding = getNativeBinding();
+  if (!binding?.registerEscalationHandler) {
+    throw new Error("Native binding not available or registerEscalationHandler not supported");
+  }
+  return binding.registerEscalationHandler(handler);
+}
+
+/** Drops a handler from `registerEscalationHandler`; returns false when the id is unknown. */
+export function unregisterEscalationHandler(id: string): boolean {
+  const binding = getNativeBinding();
+  if (!binding?.unregisterEscalationHandler) {
+    throw new Error("Native binding not available or unregisterEscalationHandler not supported");
+  }
+  return binding.unregisterEscalationHandler(id);
+}
+
+/** Reviews a diff or the changes since `baseRef` and returns the findings grouped by file. */
+export async function runReview(request: NativeRunReviewRequest): Promise<NativeRunReviewResult> {
+  const binding = getNativeBinding();
+  if (!binding?.runReview) throw new Error("Native binding not available or runReview not supported");
+  return binding.runReview(request);
+}
+
+/** Computes the diff between `baseRef` and `headRef` (or reviews `patchText`) and returns the findings. */
+export async function reviewDiff(request: NativeReviewDiffRequest): Promise<NativeRunReviewResult> {
+  const binding = getNativeBinding();
+  if (!binding?.reviewDiff) throw new Error("Native binding not available or reviewDiff not supported");
+  return binding.reviewDiff(request);
+}
+
+/** Fetches a pull request's diff, comments and CI status from GitHub. */
+export async function fetchPrContext(request: NativeFetchPrContextRequest): Promise<NativeGithubPrContext> {
+  const binding = getNativeBinding();
+  if (!binding?.fetchPrContext) throw new Error("Native binding not available or fetchPrContext not supported");
+  return binding.fetchPrContext(request);
+}
+
+/** Posts review findings to a pull request as one review with inline comments. */
+export async function postReviewComments(
+  request: NativePostReviewCommentsRequest,
+): Promise<NativePostReviewCommentsResult> {
+  const binding = getNativeBinding();
+  if (!binding?.postReviewComments) {
+    throw new Error("Native binding not available or postReviewComments not supported");
+  }
+  return binding.postReviewComments(request);
+}
+
+/** Parses review output JSON (as in `exited_review_mode`) into findings grouped by file. */
+export function parseReviewFindings(text: string): NativeReviewFindings {
+  const binding = getNativeBinding();
+  if (!binding?.parseReviewFindings) {
+    throw new Error("Native binding not available or parseReviewFindings not supported");
+  }
+  return binding.parseReviewFindings(text);
+}
+
+export async function buildPrompt(
+  request: NativeRunRequest,
+  options?: NativeBuildPromptOptions,
+): Promise<NativePromptPreview> {
+  const binding = getNativeBinding();
+  if (!binding?.buildPrompt) throw new Error("Native binding not available or buildPrompt not supported");
+  return binding.buildPrompt(request, options);
+}
+
+export async function replayThread(
+  rolloutPath: string,
+  options?: NativeReplayThreadOptions,
+): Promise<ThreadEvent[]> {
+  const binding = getNativeBinding();
+  if (!binding?.replayThread) throw new Error("Native binding not available or replayThread not supported");
+  const events = await binding.replayThread(rolloutPath, options);
+  return events.map((event) => JSON.parse(event) as ThreadEvent);
+}
+
+/** Typed items recorded for a thread, read from its rollout. */
+export async function getThreadItems(
+  threadId: string,
+  options?: NativeGetThreadItemsOptions,
+): Promise<NativeThreadItemsPage> {
+  const binding = getNativeBinding();
+  if (!binding?.getThreadItems) throw new Error("Native binding not available or getThreadItems not supported");
+  return binding.getThreadItems(threadId, options ?? null);
+}
+
+/** Generate a title and abstract for a thread and store them for `listConversations()`. */
+export async function generateThreadSummary(
+  threadId: string,
+  options?: NativeGenerateThreadSummaryOptions,
+): Promise<NativeThreadSummary> {
+  const binding = getNativeBinding();
+  if (!binding?.generateThreadSummary) {
+    throw new Error("Native binding not available or generateThreadSummary not supported");
+  }
+  return binding.generateThreadSummary(threadId, options ?? null);
+}
+
+/** Summarize a thread's new turns in groups and store the rollups next to its rollout. */
+export async function summarizeAndStore(
+  threadId: string,
+  options?: NativeSummarizeAndStoreOptions,
+): Promise<NativeThreadRollup[]> {
+  const binding = getNativeBinding();
+  if (!binding?.summarizeAndStore) {
+    throw new Error("Native binding not available or summarizeAndStore not supported");
+  }
+  return binding.summarizeAndStore(threadId, options ?? null);
+}
+
+/** Write a commit message for the staged (or all uncommitted) changes. */
+export async function generateCommitMessage(
+  options?: NativeGenerateCommitMessageOptions,
+): Promise<NativeGeneratedCommitMessage> {
+  const binding = getNativeBinding();
+  if (!binding?.generateCommitMessage) {
+    throw new Error("Native binding not available or generateCommitMessage not supported");
+  }
+  return binding.generateCommitMessage(options ?? null);
+}
+
+/**
+ * Read an image from a file or the clipboard, validate it, scale it to fit and re-encode it for
+ * attaching to a run.
+ */
+export async function prepareImage(
+  source: string | { clipboard: true },
+  options?: NativePrepareImageOptions,
+): Promise<NativePreparedImage> {
+  const binding = getNativeBinding();
+  if (!binding?.prepareImage) {
+    throw new Error("Native binding not available or prepareImage not supported");
+  }
+  return binding.prepareImage(source, options ?? null);
+}
+
+/** Write a pull request title and description for the commits since `baseRef`. */
+export async function generatePrDescription(
+  options: NativeGeneratePrDescriptionOptions,
+): Promise<NativeGeneratedPrDescription> {
+  const binding = getNativeBinding();
+  if (!binding?.generatePrDescription) {
+    throw new Error("Native binding not available or generatePrDescription not supported");
+  }
+  return binding.generatePrDescription(options);
+}
+
+/**
+ * Store a fact, or the text of the given thread items (ids from `getThreadItems`), in the
+ * thread's project memory.
+ */
+export async function memoryPromote(
+  threadId: string,
+  source: string | string[],
+  options?: NativeMemoryPromoteOptions,
+): Promise<NativeMemoryEntry[]> {
+  const binding = getNativeBinding();
+  if (!binding?.memoryPromote) {
+    throw new Error("Native binding not available or memoryPromote not supported");
+  }
+  return binding.memoryPromote(threadId, source, options ?? null);
+}
+
+/** Rank a project's memories against a query, best first. */
+export async function memoryQuery(
+  query: string,
+  options?: NativeMemoryQueryOptions,
+): Promise<NativeMemoryMatch[]> {
+  const binding = getNativeBinding();
+  if (!binding?.memoryQuery) {
+    throw new Error("Native binding not available or memoryQuery not supported");
+  }
+  return binding.memoryQuery(query, options ?? null);
+}
+
+/** Remove a memory. Resolves to false when the project has no memory with that id. */
+export async function memoryForget(id: string, project?: string): Promise<boolean> {
+  const binding = getNativeBinding();
+  if (!binding?.memoryForget) {
+    throw new Error("Native binding not available or memoryForget not supported");
+  }
+  return binding.memoryForget(id, project ?? null);
+}
+
+export function getThreadActivity(threadId: string): NativeThreadActivity | null {
+  const binding = getNativeBinding();
+  if (!binding?.getThreadActivity) throw new Error("Native binding not available or thread activity not supported");
+  return binding.getThreadActivity(threadId);
+}
+
+export function getThreadFileAccess(threadId: string): NativeFileAccessEntry[] {
+  const binding = getNativeBinding();
+  if (!binding?.getThreadFileAccess) throw new Error("Native binding not available or getThreadFileAccess not supported");
+  return binding.getThreadFileAccess(threadId);
+}
+
+export function captureEnvironmentReport(): NativeEnvironmentReport {
+  const binding = getNativeBinding();
+  if (!binding?.captureEnvironmentReport) throw new Error("Native binding not available or diagnostics not supported");
+  return binding.captureEnvironmentReport();
+}
+
+export async function configDoctor(options?: NativeConfigDoctorOptions): Promise<NativeConfigDoctorReport> {
+  const binding = getNativeBinding();
+  if (!binding?.configDoctor) throw new Error("Native binding not available or diagnostics not supported");
+  return binding.configDoctor(options);
+}
+
+export function configureRolloutCache(maxEntries: number): void {
+  const binding = getNativeBinding();
+  if (!binding?.configureRolloutCache) throw new Error("Native binding not available or rollout cache not supported");
+  binding.configureRolloutCache(maxEntries);
+}
+
+export function invalidateRolloutCache(path?: string): void {
+  const binding = getNativeBinding();
+  if (!binding?.invalidateRolloutCache) throw new Error("Native binding not available or rollout cache not supported");
+  binding.invalidateRolloutCache(path ?? null);
+}
+
+export function rolloutCacheStats(): NativeRolloutCacheStats {
+  const binding = getNativeBinding();
+  if (!binding?.rolloutCacheStats) throw new Error("Native binding not available or rollout cache not supported");
+  return binding.rolloutCacheStats();
+}
+
+/** Opens a rollout for incremental reading; records are parsed only as batches are requested. */
+export function openRolloutReader(path: string): NativeRolloutReader {
+  const binding = getNativeBinding();
+  if (!binding?.openRolloutReader) throw new Error("Native binding not available or rollout reader not supported");
+  return binding.openRolloutReader(path);
+}
+
+/** Moves old rollouts out of `sessions/` into a compressed, indexed archive. */
+export async function archiveRollouts(options: NativeArchiveRolloutsOptions): Promise<NativeArchiveRolloutsResult> {
+  const binding = getNativeBinding();
+  if (!binding?.archiveRollouts) throw new Error("Native binding not available or rollout archival not supported");
+  return binding.archiveRollouts(options);
+}
+
+/**
+ * Sets the key used to encrypt and read the rollout archive. Live sessions are still written in
+ * plaintext. Call with no options to forget the key.
+ */
+export function configureArchiveEncryption(options?: NativeArchiveEncryptionOptions): void {
+  const binding = getNativeBinding();
+  if (!binding?.configureArchiveEncryption) {
+    throw new Error("Native binding not available or archive encryption not supported");
+  }
+  binding.configureArchiveEncryption(options ?? null);
+}
+
+/** Encrypts the rollout archive, and with `olderThanDays` moves closed sessions into it. */
+export async function encryptRolloutArchive(
+  options?: NativeEncryptRolloutArchiveOptions,
+): Promise<NativeEncryptRolloutArchiveResult> {
+  const binding = getNativeBinding();
+  if (!binding?.encryptRolloutArchive) {
+    throw new Error("Native binding not available or archive encryption not supported");
+  }
+  return binding.encryptRolloutArchive(options ?? null);
+}
+
+export async function usageReport(options?: NativeUsageReportOptions): Promise<NativeUsageReport> {
+  const binding = getNativeBinding();
+  if (!binding?.usageReport) throw new Error("Native binding not available or usage reports not supported");
+  return binding.usageReport(options);
+}
+
+export async function recoverInterruptedRuns(
+  options?: NativeRecoverInterruptedRunsOptions,
+): Promise<NativeInterruptedRun[]> {
+  const binding = getNativeBinding();
+  if (!binding?.recoverInterruptedRuns) {
+    throw new Error("Native binding not available or run recovery not supported");
+  }
+  return binding.recoverInterruptedRuns(options);
+}
+
+export async function finalizeInterruptedRun(
+  runId: string,
+  options?: NativeFinalizeInterruptedRunOptions,
+): Promise<NativeInterruptedRun> {
+  const binding = getNativeBinding();
+  if (!binding?.finalizeInterruptedRun) {
+    throw new Error("Native binding not available or run recovery not supported");
+  }
+  return binding.finalizeInterruptedRun(runId, options);
+}
+
+export async function authStatus(options?: NativeAuthOptions): Promise<NativeAuthStatus> {
+  const binding = getNativeBinding();
+  if (!binding?.authStatus) throw new Error("Native binding not available or auth not supported");
+  return binding.authStatus(options);
+}
+
+export async function authLoginApiKey(apiKey: string, options?: NativeAuthOptions): Promise<NativeAuthStatus> {
+  const binding = getNativeBinding();
+  if (!binding?.authLoginApiKey) throw new Error("Native binding not available or auth not supported");
+  return binding.authLoginApiKey(apiKey, options);
+}
+
+/** Resolves once the user finishes signing in; `onPrompt` receives the URL (and code) to show them. */
+export async function authLoginChatGpt(
+  options: NativeAuthChatGptLoginOptions & { onPrompt?: (prompt: NativeAuthLoginPrompt) => void } = {},
+): Promise<NativeAuthStatus> {
+  const binding = getNativeBinding();
+  if (!binding?.authLoginChatGpt) throw new Error("Native binding not available or auth not supported");
+  const { onPrompt, ...nativeOptions } = options;
+  return binding.authLoginChatGpt(nativeOptions, (err, prompt) => {
+    if (!err && prompt) onPrompt?.(prompt);
+  });
+}
+
+export async function authLogout(options?: NativeAuthOptions): Promise<boolean> {
+  const binding = getNativeBinding();
+  if (!binding?.authLogout) throw new Error("Native binding not available or auth not supported");
+  return binding.authLogout(options);
+}
+
+export async function authProfiles(options?: NativeAuthOptions): Promise<NativeAuthStatus[]> {
+  const binding = getNativeBinding();
+  if (!binding?.authProfiles) throw new Error("Native binding not available or auth not supported");
+  return binding.authProfiles(options);
+}
+
 // FastEmbed helpers
 export async function fastEmbedInit(options: FastEmbedInitOptions): Promise<void> {
   const binding = getNativeBinding();
@@ -843,6 +3353,42 @@ export function tokenizerDecode(tokens: number[], options?: TokenizerOptions): s
   return (binding as any).tokenizerDecode(tokens, options);
 }
 
+/** Searches file contents under a root with the agent's ripgrep search, honouring ignore files. */
+export async function searchWorkspace(
+  options: NativeSearchWorkspaceOptions,
+): Promise<NativeSearchWorkspaceResult> {
+  const binding = getNativeBinding();
+  if (!binding?.searchWorkspace) {
+    throw new Error("Native binding not available or workspace search not supported");
+  }
+  return binding.searchWorkspace(options);
+}
+
+/** Embeds the files under `root` with the FastEmbed model and persists them as a searchable index. */
+export async function workspaceIndexBuild(
+  root: string,
+  options?: NativeWorkspaceIndexBuildOptions,
+): Promise<NativeWorkspaceIndexStats> {
+  const binding = getNativeBinding();
+  if (!binding?.workspaceIndexBuild) {
+    throw new Error("Native binding not available or workspace index not supported");
+  }
+  return binding.workspaceIndexBuild(root, options);
+}
+
+/** Ranks the chunks of a workspace index built by `workspaceIndexBuild` against a query. */
+export async function workspaceSearchSemantic(
+  root: string,
+  query: string,
+  options?: NativeWorkspaceSemanticSearchOptions,
+): Promise<NativeWorkspaceChunkMatch[]> {
+  const binding = getNativeBinding();
+  if (!binding?.workspaceSearchSemantic) {
+    throw new Error("Native binding not available or workspace index not supported");
+  }
+  return binding.workspaceSearchSemantic(root, query, options);
+}
+
 export async function collectRepoDiffSummary(
   options?: RepoDiffSummaryOptions,
 ): Promise<RepoDiffSummary> {
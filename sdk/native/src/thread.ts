@@ -10,12 +10,14 @@ import { ThreadOptions } from "./threadOptions";
 import { TurnOptions } from "./turnOptions";
 import { createOutputSchemaFile, normalizeOutputSchema } from "./outputSchemaFile";
 import { runTui, startTui } from "./tui";
-import { getNativeBinding } from "./nativeBinding";
+import { getNativeBinding, getThreadPlan, resetExecSession, setThreadStatus } from "./nativeBinding";
 import type {
   NativeTuiRequest,
   NativeTuiExitInfo,
   ApprovalRequest,
   NativeUserInputItem,
+  NativeThreadPlan,
+  NativeThreadStatus,
 } from "./nativeBinding";
 import type { RunTuiOptions, TuiSession } from "./tui";
 import { attachLspDiagnostics } from "./lsp";
@@ -204,6 +206,19 @@ export class Thread {
     await binding.emitBackgroundEvent({ threadId: this._id, message: trimmed });
   }
 
+  /**
+   * Report progress for this thread, e.g. from a long-running tool. Streamed as a `thread_status`
+   * event and shown under the TUI's working indicator. Pass `null` to clear it.
+   *
+   * @throws Error if the thread has not been started yet.
+   */
+  setStatus(status: NativeThreadStatus | null): void {
+    if (!this._id) {
+      throw new Error("Cannot set status before the thread has started");
+    }
+    setThreadStatus(this._id, status);
+  }
+
   /**
    * Programmatically update the agent's plan/todo list.
    * The plan will be applied at the start of the next turn.
@@ -234,6 +249,28 @@ export class Thread {
     });
   }
 
+  /**
+   * The thread's newest plan: the model's last `update_plan` call, or a plan scheduled with
+   * `updatePlan()`/`modifyPlan()` (reported with `pending: true` until a run applies it).
+   */
+  getPlan(): NativeThreadPlan | null {
+    if (!this._id) {
+      return null;
+    }
+    return getThreadPlan(this._id);
+  }
+
+  /**
+   * End the thread's persistent shell (`execSession: "persistent"`) so its next shell command
+   * starts from a fresh one. Returns `false` when no shell is running.
+   */
+  resetExecSession(): boolean {
+    if (!this._id) {
+      return false;
+    }
+    return resetExecSession(this._id);
+  }
+
   /**
    * Modify the agent's plan/todo list with granular operations.
    * Changes will be applied at the start of the next turn.
@@ -317,7 +354,9 @@ export class Thread {
       input: "compact",
       threadId: this._id,
       baseUrl: this._options.baseUrl,
-      apiKey: this._options.apiKey,
+      apiKey: this._threadOptions?.authProfile ? undefined : this._options.apiKey,
+      authProfile: this._threadOptions?.authProfile,
+      profile: this._threadOptions?.profile,
       model: this._threadOptions?.model ?? this._options.defaultModel,
       sandboxMode: this._threadOptions?.sandboxMode,
       approvalMode: this._threadOptions?.approvalMode,
@@ -374,7 +413,9 @@ export class Thread {
       threadId: this._id,
       nthUserMessage,
       baseUrl: this._options.baseUrl,
-      apiKey: this._options.apiKey,
+      apiKey: nextThreadOptions.authProfile ? undefined : this._options.apiKey,
+      authProfile: nextThreadOptions.authProfile,
+      profile: nextThreadOptions.profile,
       model: nextThreadOptions.model ?? this._options.defaultModel,
       oss: nextThreadOptions.oss,
       sandboxMode: nextThreadOptions.sandboxMode,
@@ -453,8 +494,12 @@ export class Thread {
     }
     const generator = this._exec.run({
       input: prompt,
+      promptTemplate: turnOptions?.promptTemplate,
       baseUrl: this._options.baseUrl,
-      apiKey: this._options.apiKey,
+      // A profile brings its own credentials; the Rust side rejects an apiKey alongside it.
+      apiKey: options?.authProfile ? undefined : this._options.apiKey,
+      authProfile: options?.authProfile,
+      profile: options?.profile,
       threadId: this._id,
       inputItems: inputItemsWithSkills,
       model: options?.model,
@@ -464,18 +509,43 @@ export class Thread {
       oss: turnOptions?.oss ?? options?.oss,
       sandboxMode: options?.sandboxMode,
       approvalMode: options?.approvalMode,
+      onEscalation: turnOptions?.onEscalation ?? options?.onEscalation,
       workspaceWriteOptions: options?.workspaceWriteOptions,
+      permissionProfile: options?.permissionProfile,
+      allowedTools: options?.allowedTools,
+      deniedTools: options?.deniedTools,
+      toolLimits: options?.toolLimits,
+      cacheTtlSeconds: options?.cacheTtlSeconds,
       workingDirectory: options?.workingDirectory,
       skipGitRepoCheck,
       outputSchemaFile: schemaFile.schemaPath,
       outputSchema: normalizedSchema,
       toolChoice: turnOptions?.toolChoice,
+      record: turnOptions?.record,
+      replay: turnOptions?.replay,
+      patchPathPolicy: options?.patchPathPolicy,
+      fileSkeleton: options?.fileSkeleton,
+      redaction: options?.redaction,
+      postTurnChecks: options?.postTurnChecks,
+      maxCheckFeedbackTurns: options?.maxCheckFeedbackTurns,
+      baseInstructions: options?.baseInstructions,
+      developerInstructions: options?.developerInstructions,
+      compactPrompt: options?.compactPrompt,
+      eventDelivery: options?.eventDelivery,
+      includeRawEvents: options?.includeRawEvents,
+      execSession: options?.execSession,
+      shellPty: options?.shellPty,
+      outputLimits: options?.outputLimits,
+      toolEnv: options?.toolEnv,
+      verify: options?.verify,
+      memory: options?.memory,
       mcp: options?.mcp,
       inheritMcp: options?.inheritMcp,
       personality: options?.personality,
       turnPersonality: turnOptions?.personality,
       ephemeral: options?.ephemeral,
       webSearchMode: options?.webSearchMode,
+      webSearch: options?.webSearch,
       dynamicTools,
     });
     try {
@@ -41,6 +41,128 @@ export type WorkspaceWriteOptions = {
   excludeSlashTmp?: boolean;
 };
 
+/**
+ * Globs restricting which files `apply_patch` may touch, relative to the working directory.
+ * Deny patterns win over allow patterns; an empty or missing `allow` permits every path.
+ */
+export type PatchPathPolicy = {
+  allow?: string[];
+  deny?: string[];
+};
+
+/**
+ * Replaces whole-file reads of files larger than `maxBytes` with a native skeleton:
+ * imports, declarations, and the lines around referenced `path:line` locations.
+ */
+export type FileSkeletonOptions = {
+  /** Default: 32 KiB */
+  maxBytes?: number;
+  /** Lines shown on each side of a referenced line. Default: 8 */
+  contextLines?: number;
+};
+
+/**
+ * Masks API keys and other secrets in tool output, command echoes and the persisted rollout.
+ * Built-in patterns cover common key formats (OpenAI, Anthropic, GitHub, AWS, Slack, Google,
+ * Stripe, JWTs, private keys) plus long random-looking tokens.
+ */
+export type RedactionOptions = {
+  /** Default: true */
+  enabled?: boolean;
+  /** Extra regexes to mask. When a pattern has a capture group, only the first group is masked. */
+  patterns?: string[];
+};
+
+/**
+ * Bounds the events waiting for delivery to JS during `runStreamed()`.
+ *
+ * - `block` (default): pause the run until the event consumer catches up
+ * - `drop`: discard the event; `turn.completed` reports the count as `dropped_events`
+ */
+export type EventDeliveryOptions = {
+  /** Events in flight before `overflow` applies. Default: 256 */
+  capacity?: number;
+  overflow?: "block" | "drop";
+};
+
+/** How much tool output the model sees before it is truncated. */
+export type OutputLimits = {
+  /** Token budget for each tool output, in place of the model's default (usually 10000). */
+  maxToolOutputTokens?: number;
+  /**
+   * Share of the budget kept from the start of truncated command output, 0 to 1. The rest keeps
+   * its end, where build errors and test summaries usually are. Default: 0.5
+   */
+  headTailSplit?: number;
+};
+
+/** Terminal the agent's shell commands run attached to. */
+export type ShellPtyOptions = {
+  /** Default: 80 */
+  cols?: number;
+  /** Default: 24 */
+  rows?: number;
+  /** `TERM` for the command. Default: "xterm-256color" */
+  term?: string;
+};
+
+/**
+ * Environment the agent's shell commands run with.
+ *
+ * - `all` (default): inherit the host environment
+ * - `none`: start from an empty environment
+ * - `allowlist`: keep only the variables matching `allow`
+ */
+export type ToolEnvOptions = {
+  inherit?: "all" | "none" | "allowlist";
+  /** Names kept with `inherit: "allowlist"`. `*` and `?` are wildcards; case-insensitive. */
+  allow?: string[];
+  /** Variables set for every command, whatever `inherit` is. */
+  set?: Record<string, string>;
+};
+
+/** Checks run after every patch the agent applies, e.g. `cargo check` or `pnpm tsc --noEmit`. */
+export type VerifyOptions = {
+  /** Shell commands run in order in the working directory. */
+  commands: string[];
+  /** Timeout for each command. Defaults to 5 minutes. */
+  timeoutMs?: number;
+};
+
+/** Project memories (see `memoryPromote`) added to a new thread's developer instructions. */
+export type MemoryOptions = {
+  /** Memories added, best match for the prompt first. Defaults to 5. */
+  limit?: number;
+  /** Only memories carrying every one of these tags. */
+  tags?: string[];
+  /** Memories scoring below this (0 to 1) are left out. */
+  minScore?: number;
+};
+
+/** Scheduling limits for one tool. */
+export type ToolLimit = {
+  /** Calls of the tool allowed to run at once. Must be at least 1. */
+  maxConcurrent?: number;
+  /** Minimum milliseconds between the starts of two calls of the tool. */
+  minIntervalMs?: number;
+};
+
+/**
+ * A command run natively after each turn that changed files.
+ *
+ * - `feedback` (default): send the failure output back to the model in a follow-up turn
+ * - `revert`: restore the working tree to its state before the turn
+ * - `stop`: fail the run
+ */
+export type PostTurnCheck = {
+  command: string;
+  /** Label used in events and feedback; defaults to the command. */
+  name?: string;
+  onFail?: "feedback" | "revert" | "stop";
+  /** Default: 10 minutes */
+  timeoutMs?: number;
+};
+
 // ============================================================================
 // MCP Server Configuration Types
 // ============================================================================
@@ -101,16 +223,123 @@ export type ThreadOptions = {
   sandboxMode?: SandboxMode;
   /** Approval policy for command execution */
   approvalMode?: ApprovalMode;
+  /**
+   * Decides approval requests raised during a run, most often a command the sandbox denied under
+   * `approvalMode: "on-failure"`. Return true to retry it once outside the sandbox. Without a
+   * handler these requests are denied.
+   */
+  onEscalation?: import("./nativeBinding").NativeEscalationHandler;
   /** Options for workspace-write sandbox mode */
   workspaceWriteOptions?: WorkspaceWriteOptions;
+  /**
+   * Permission profile defined with `definePermissionProfile`. Sandbox mode, approval mode and
+   * writable roots set on these options win over the profile's.
+   */
+  permissionProfile?: string;
+  /**
+   * When set, only these builtin and registered tools are exposed to the model. Any shell tool
+   * name (`shell`, `local_shell`, `exec_command`, ...) stands for all of them.
+   */
+  allowedTools?: string[];
+  /** Tools never exposed to the model. Wins over `allowedTools` and the permission profile. */
+  deniedTools?: string[];
+  /**
+   * Concurrency and start-interval limits per tool name, so parallel tool calls don't stampede
+   * expensive tools. An entry replaces the limits the tool was registered with.
+   */
+  toolLimits?: Record<string, ToolLimit>;
+  /**
+   * Seconds to reuse successful results of idempotent calls, per tool name. Calls with the same
+   * tool, arguments and working directory are served from memory within a run; read-only shell
+   * commands can be cached, while apply_patch and mutating commands never are. An entry replaces
+   * the TTL the tool was registered with; 0 disables caching.
+   */
+  cacheTtlSeconds?: Record<string, number>;
   workingDirectory?: string;
   skipGitRepoCheck?: boolean;
+  /**
+   * Files the agent may never patch, enforced natively even in full-auto mode.
+   * Violations are returned to the model as denied tool calls.
+   */
+  patchPathPolicy?: PatchPathPolicy;
+  /** Return oversized files read in full as a structural skeleton instead of truncated text. */
+  fileSkeleton?: FileSkeletonOptions;
+  /**
+   * Mask secrets before the model, event consumers or the rollout see them. Each
+   * `turn.completed` event reports the number of masked values as `redaction_applied`.
+   */
+  redaction?: RedactionOptions;
+  /** Backpressure for streamed events. Unbounded when unset. */
+  eventDelivery?: EventDeliveryOptions;
+  /**
+   * Stream every codex-core protocol event as a `raw_event` with `source: "protocol"`, before
+   * the thread events mapped from it. Useful for custom approval flows and debugging.
+   */
+  includeRawEvents?: boolean;
+  /**
+   * `persistent` runs the thread's shell commands in one long-lived shell, so `cd`, exported
+   * variables and activated virtualenvs carry over between commands and turns. The shell starts
+   * under the thread's sandbox; reset it with `resetExecSession(threadId)`. Defaults to
+   * `per-call`, a fresh process per command.
+   */
+  execSession?: "per-call" | "persistent";
+  /**
+   * Run shell commands attached to a PTY of this size, for tools that only color or paginate
+   * output on a terminal. stdout and stderr then arrive interleaved as one stream, and command
+   * items report `is_tty`.
+   */
+  shellPty?: ShellPtyOptions;
+  /**
+   * Raise the tool output cap for verbose build systems, or lower it for small-context models.
+   * Output over the budget reaches the model with a truncation marker where text was cut, and
+   * `command_completed` events carry that text as `model_output`.
+   */
+  outputLimits?: OutputLimits;
+  /**
+   * Environment shell commands see, in place of `shell_environment_policy` from config.toml.
+   * Use `inherit: "none"` or `"allowlist"` to keep host credentials out of commands and their
+   * output. The host environment is inherited in full when unset.
+   */
+  toolEnv?: ToolEnvOptions;
+  /**
+   * Commands run in the turn's sandbox right after each patch applies. Failures are appended to
+   * the patch's tool output, so the model fixes them in the next step, and reported as
+   * `verification_failed` events.
+   */
+  verify?: VerifyOptions;
+  /**
+   * Add the project's memories that best match the first prompt to the thread's developer
+   * instructions. Only applies when the thread starts; resumed threads keep their instructions.
+   */
+  memory?: MemoryOptions;
+  /**
+   * Credential profile (see `authLoginApiKey`/`authLoginChatGpt`) this thread runs as, instead
+   * of the default credentials. Takes the place of `CodexOptions.apiKey` for this thread.
+   */
+  authProfile?: string;
+  /** Config profile from config.toml's `[profiles]` table, as selected by `codex --profile`. */
+  profile?: string;
+  /** Format, lint or test commands run after each turn that modified files. */
+  postTurnChecks?: PostTurnCheck[];
+  /** Maximum follow-up turns started by failing `feedback` checks. Default: 3 */
+  maxCheckFeedbackTurns?: number;
+  /**
+   * Replaces the model's built-in base instructions. Deviating from them tends to degrade
+   * results, so prefer `developerInstructions` for product-specific guidance.
+   */
+  baseInstructions?: string;
+  /** Developer message for each run, in place of config.toml's `developer_instructions`. */
+  developerInstructions?: string;
+  /** Prompt used when compacting this thread's history, in place of config.toml's `compact_prompt`. */
+  compactPrompt?: string;
   /** Reasoning effort level (only honored for reasoning-capable models). Defaults to "medium" when undefined. */
   reasoningEffort?: ReasoningEffort;
   /** Reasoning summary preference (only honored for reasoning-capable models). Defaults to "auto" when undefined. */
   reasoningSummary?: ReasoningSummary;
   /** Web search mode (cached/live/disabled). */
   webSearchMode?: WebSearchMode;
+  /** Toggle the web search tool: `true` for live search, `false` to disable it. `webSearchMode` takes precedence. */
+  webSearch?: boolean;
   /** Set a default personality for the session. */
   personality?: Personality;
   /** Keep the session ephemeral (in-memory only). */
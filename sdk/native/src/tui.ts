@@ -6,6 +6,30 @@ import type {
   NativeUpdateActionInfo,
   NativeUpdateActionKind,
   NativeTuiSession,
+  NativeTuiTestSession,
+  NativeTuiTestSessionOptions,
+  NativeTuiTestSnapshot,
+  NativeTuiStyledCell,
+  NativeTuiStyledSnapshot,
+  NativeTuiCellChange,
+  NativeTuiSnapshotDiff,
+  NativeTuiApp,
+  NativeTuiAppEvent,
+  NativeTuiAppRunOptions,
+  NativeTuiDimensions,
+  NativeTuiLayout,
+  NativeTuiLayoutChild,
+  NativeTuiLayoutConstraint,
+  NativeAgentView,
+  NativeStatusBoard,
+  NativeAgentGraphRenderer,
+  NativeAgentGraphNode,
+  NativeAgentGraphUpdate,
+  NativeAgentGraphState,
+  NativeAgentGraphEdge,
+  NativeAgentGraphJson,
+  NativeAgentGraphHistoryOptions,
+  NativeAgentGraphAsciiOptions,
 } from "./nativeBinding";
 
 export interface TuiSession {
@@ -66,6 +90,108 @@ export async function runTui(
   }
 }
 
+/**
+ * Creates a headless TUI session on a virtual screen for end-to-end tests.
+ *
+ * Keys sent with `sendKeys` go through the real codex-tui composer; each call returns the
+ * resulting screen snapshot.
+ */
+export function createTuiTestSession(options?: NativeTuiTestSessionOptions): NativeTuiTestSession {
+  const binding = getNativeBinding();
+  if (!binding?.TuiTestSession) {
+    throw new Error("Native binding not available or TuiTestSession not supported");
+  }
+  return new binding.TuiTestSession(options);
+}
+
+/**
+ * Compares two `captureStyled()` results cell by cell, including colors and attributes.
+ */
+export function diffSnapshots(a: NativeTuiStyledSnapshot, b: NativeTuiStyledSnapshot): NativeTuiSnapshotDiff {
+  const binding = getNativeBinding();
+  if (!binding?.diffTuiSnapshots) {
+    throw new Error("Native binding not available or diffTuiSnapshots not supported");
+  }
+  return binding.diffTuiSnapshots(a, b);
+}
+
+/** Built-in component types accepted by `TuiApp.addComponent`, matching the native `WidgetType` enum. */
+export const TuiWidgetType = {
+  Text: 0,
+  Chat: 1,
+  Terminal: 2,
+  ProgressBar: 3,
+  StatusLine: 4,
+  Table: 5,
+  FileTree: 6,
+  Markdown: 7,
+} as const;
+
+export type TuiWidgetTypeValue = (typeof TuiWidgetType)[keyof typeof TuiWidgetType];
+
+export type TuiAppOptions = {
+  title?: string;
+  width?: number;
+  height?: number;
+};
+
+/**
+ * Creates a composable terminal dashboard. Mount components, then call `run()` to take over
+ * the terminal and `stop()` to restore it.
+ */
+export function createTuiApp(options?: TuiAppOptions): NativeTuiApp {
+  const binding = getNativeBinding();
+  if (!binding?.TuiApp) {
+    throw new Error("Native binding not available or TuiApp not supported");
+  }
+  return new binding.TuiApp(options?.title, options?.width, options?.height);
+}
+
+/** Creates an agent chat/output panel that can be mounted into a {@link createTuiApp} app. */
+export function createAgentView(threadId: string, title?: string): NativeAgentView {
+  const binding = getNativeBinding();
+  if (!binding?.AgentView) {
+    throw new Error("Native binding not available or AgentView not supported");
+  }
+  return new binding.AgentView(threadId, title);
+}
+
+/** Creates a tile board (`"grid"` or `"stack"`) that can be mounted into a {@link createTuiApp} app. */
+export function createStatusBoard(layout?: "grid" | "stack"): NativeStatusBoard {
+  const binding = getNativeBinding();
+  if (!binding?.StatusBoard) {
+    throw new Error("Native binding not available or StatusBoard not supported");
+  }
+  return new binding.StatusBoard(layout);
+}
+
+/**
+ * Creates an agent topology graph. Threads added with `addAgent` update live from native runs,
+ * and their forks and spawned agents join automatically. Mount it with `TuiApp.mountAgentGraph`.
+ */
+export function createAgentGraph(): NativeAgentGraphRenderer {
+  const binding = getNativeBinding();
+  if (!binding?.AgentGraphRenderer) {
+    throw new Error("Native binding not available or AgentGraphRenderer not supported");
+  }
+  return new binding.AgentGraphRenderer();
+}
+
+/**
+ * Rebuilds the fork tree of past sessions from the rollouts under `codexHomePath`. Pass
+ * `rootThreadId` to keep only that thread and the threads forked or spawned from it.
+ */
+export function loadAgentGraph(
+  codexHomePath: string,
+  options?: NativeAgentGraphHistoryOptions,
+): NativeAgentGraphRenderer {
+  const binding = getNativeBinding();
+  if (!binding?.AgentGraphRenderer) {
+    throw new Error("Native binding not available or AgentGraphRenderer not supported");
+  }
+  return binding.AgentGraphRenderer.fromCodexHome(codexHomePath, options);
+}
+
 function wrapNativeSession(nativeSession: NativeTuiSession): TuiSession {
   return {
     wait: () => nativeSession.wait(),
@@ -82,4 +208,28 @@ export type {
   NativeTokenUsage,
   NativeUpdateActionInfo,
   NativeUpdateActionKind,
+  NativeTuiTestSession as TuiTestSession,
+  NativeTuiTestSessionOptions as TuiTestSessionOptions,
+  NativeTuiTestSnapshot as TuiTestSnapshot,
+  NativeTuiStyledCell as TuiStyledCell,
+  NativeTuiStyledSnapshot as TuiStyledSnapshot,
+  NativeTuiCellChange as TuiCellChange,
+  NativeTuiSnapshotDiff as TuiSnapshotDiff,
+  NativeTuiApp as TuiApp,
+  NativeTuiAppEvent as TuiAppEvent,
+  NativeTuiAppRunOptions as TuiAppRunOptions,
+  NativeTuiDimensions as TuiDimensions,
+  NativeTuiLayout as TuiLayout,
+  NativeTuiLayoutChild as TuiLayoutChild,
+  NativeTuiLayoutConstraint as TuiLayoutConstraint,
+  NativeAgentView as AgentView,
+  NativeStatusBoard as StatusBoard,
+  NativeAgentGraphRenderer as AgentGraphRenderer,
+  NativeAgentGraphNode as AgentGraphNode,
+  NativeAgentGraphUpdate as AgentGraphUpdate,
+  NativeAgentGraphState as AgentGraphState,
+  NativeAgentGraphEdge as AgentGraphEdge,
+  NativeAgentGraphJson as AgentGraphJson,
+  NativeAgentGraphHistoryOptions as AgentGraphHistoryOptions,
+  NativeAgentGraphAsciiOptions as AgentGraphAsciiOptions,
 };
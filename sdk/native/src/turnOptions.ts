@@ -9,4 +9,15 @@ export type TurnOptions = {
   toolChoice?: unknown;
   /** Override the personality for this turn. */
   personality?: import("./threadOptions").Personality;
+  /** Record model responses, tool outputs and timing for this turn into a cassette file. */
+  record?: string;
+  /** Replay this turn from a cassette file instead of calling the model or running tools. */
+  replay?: string;
+  /**
+   * Render this registered template as the turn's prompt. Pass an empty input to `run`; the input
+   * and the template can't be combined.
+   */
+  promptTemplate?: import("./nativeBinding").NativePromptTemplateRequest;
+  /** Decide this turn's sandbox escalations, in place of the thread's `onEscalation`. */
+  onEscalation?: import("./nativeBinding").NativeEscalationHandler;
 };
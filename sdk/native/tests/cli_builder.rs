@@ -6,6 +6,7 @@ use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::dynamic_tools::DynamicToolSpec;
 use pretty_assertions::assert_eq;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 fn base_internal_request() -> InternalRunRequest {
@@ -16,10 +17,14 @@ fn base_internal_request() -> InternalRunRequest {
     images: Vec::new(),
     model: None,
     model_provider: None,
+    config_profile: None,
     oss: false,
     sandbox_mode: None,
     approval_mode: None,
     workspace_write_options: None,
+    tool_filter: None,
+    tool_limits: HashMap::new(),
+    tool_cache_ttls: HashMap::new(),
     review_request: None,
     working_directory: None,
     skip_git_repo_check: false,
@@ -27,6 +32,7 @@ fn base_internal_request() -> InternalRunRequest {
     tool_choice: None,
     base_url: None,
     api_key: None,
+    auth_home: None,
     linux_sandbox_path: None,
     reasoning_effort: None,
     reasoning_summary: None,
@@ -37,6 +43,24 @@ fn base_internal_request() -> InternalRunRequest {
     dynamic_tools: None,
     mcp: None,
     inherit_mcp: true,
+    record: None,
+    replay: None,
+    patch_path_policy: None,
+    file_skeleton: None,
+    redaction: None,
+    post_turn_checks: Vec::new(),
+    max_check_feedback_turns: 3,
+    base_instructions: None,
+    developer_instructions: None,
+    compact_prompt: None,
+    include_raw_events: false,
+    exec_session: None,
+    shell_pty: None,
+    tool_env: None,
+    verify: None,
+    memory: None,
+    escalation_handler: None,
+    output_limits: None,
   }
 }
 
@@ -5,15 +5,22 @@ use serde_json::json;
 fn base_run_request(prompt: &str) -> RunRequest {
   RunRequest {
     prompt: prompt.to_string(),
+    prompt_template: None,
     input_items: None,
     thread_id: None,
     images: None,
     model: None,
     model_provider: None,
+    profile: None,
     oss: None,
     sandbox_mode: None,
     approval_mode: None,
     workspace_write_options: None,
+    permission_profile: None,
+    allowed_tools: None,
+    denied_tools: None,
+    tool_limits: None,
+    cache_ttl_seconds: None,
     review_mode: None,
     review_hint: None,
     working_directory: None,
@@ -22,6 +29,7 @@ fn base_run_request(prompt: &str) -> RunRequest {
     tool_choice: None,
     base_url: None,
     api_key: None,
+    auth_profile: None,
     linux_sandbox_path: None,
     reasoning_effort: None,
     reasoning_summary: None,
@@ -29,9 +37,29 @@ fn base_run_request(prompt: &str) -> RunRequest {
     turn_personality: None,
     ephemeral: None,
     web_search_mode: None,
+    web_search: None,
     dynamic_tools: None,
     mcp: None,
     inherit_mcp: None,
+    record: None,
+    replay: None,
+    patch_path_policy: None,
+    file_skeleton: None,
+    redaction: None,
+    post_turn_checks: None,
+    max_check_feedback_turns: None,
+    base_instructions: None,
+    developer_instructions: None,
+    compact_prompt: None,
+    event_delivery: None,
+    include_raw_events: None,
+    exec_session: None,
+    shell_pty: None,
+    tool_env: None,
+    verify: None,
+    memory: None,
+    escalation_handler: None,
+    output_limits: None,
   }
 }
 
@@ -125,6 +153,9 @@ fn test_native_tool_info_construction() {
     })),
     strict: Some(true),
     supports_parallel: Some(false),
+    max_concurrent: None,
+    min_interval_ms: None,
+    cache_ttl_seconds: None,
   };
 
   assert_eq!(tool_info.name, "test_tool");
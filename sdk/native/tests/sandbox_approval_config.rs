@@ -4,21 +4,29 @@ use pretty_assertions::assert_eq;
 fn base_run_request(prompt: &str) -> RunRequest {
   RunRequest {
     prompt: prompt.to_string(),
+    prompt_template: None,
     input_items: None,
     thread_id: None,
     images: None,
     model: None,
     model_provider: None,
+    profile: None,
     oss: None,
     sandbox_mode: None,
     approval_mode: None,
     workspace_write_options: None,
+    permission_profile: None,
+    allowed_tools: None,
+    denied_tools: None,
+    tool_limits: None,
+    cache_ttl_seconds: None,
     working_directory: None,
     skip_git_repo_check: None,
     output_schema: None,
     tool_choice: None,
     base_url: None,
     api_key: None,
+    auth_profile: None,
     linux_sandbox_path: None,
     reasoning_effort: None,
     reasoning_summary: None,
@@ -28,9 +36,29 @@ fn base_run_request(prompt: &str) -> RunRequest {
     turn_personality: None,
     ephemeral: None,
     web_search_mode: None,
+    web_search: None,
     dynamic_tools: None,
     mcp: None,
     inherit_mcp: None,
+    record: None,
+    replay: None,
+    patch_path_policy: None,
+    file_skeleton: None,
+    redaction: None,
+    post_turn_checks: None,
+    max_check_feedback_turns: None,
+    base_instructions: None,
+    developer_instructions: None,
+    compact_prompt: None,
+    event_delivery: None,
+    include_raw_events: None,
+    exec_session: None,
+    shell_pty: None,
+    tool_env: None,
+    verify: None,
+    memory: None,
+    escalation_handler: None,
+    output_limits: None,
   }
 }
 
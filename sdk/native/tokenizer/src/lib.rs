@@ -0,0 +1,90 @@
+//! Token counting and TOON encoding for the Codex native SDK.
+//!
+//! The native runtime and, through the `wasm32` feature, web frontends use
+//! this one implementation, so counts and TOON previews computed in a browser
+//! match the native ones exactly. Nothing here touches the filesystem or
+//! threads, which keeps it buildable for `wasm32-unknown-unknown`.
+
+#![deny(clippy::all)]
+
+pub mod toon;
+#[cfg(feature = "wasm32")]
+mod wasm;
+
+use std::fmt;
+
+pub use tiktoken_rs::CoreBPE;
+use tiktoken_rs::cl100k_base;
+use tiktoken_rs::get_bpe_from_model;
+use tiktoken_rs::o200k_base;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+  pub fn new(message: impl Into<String>) -> Self {
+    Self(message.into())
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for Error {}
+
+fn tokenizer_error<E: fmt::Display>(err: E) -> Error {
+  Error::new(format!("Tokenizer error: {err}"))
+}
+
+fn encoding_from_name(name: &str) -> Option<CoreBPE> {
+  let normalized = name.replace('-', "_").to_ascii_lowercase();
+  match normalized.as_str() {
+    "o200k_base" => o200k_base().ok(),
+    "cl100k_base" => cl100k_base().ok(),
+    _ => None,
+  }
+}
+
+/// The tokenizer for `encoding`, else for `model`, else `cl100k_base`.
+pub fn build_tokenizer(model: Option<&str>, encoding: Option<&str>) -> Result<CoreBPE, Error> {
+  if let Some(enc_name) = encoding {
+    encoding_from_name(enc_name)
+      .ok_or_else(|| Error::new(format!("Unknown tokenizer encoding: {enc_name}")))
+  } else if let Some(model_name) = model {
+    get_bpe_from_model(model_name).map_err(tokenizer_error)
+  } else {
+    cl100k_base().map_err(tokenizer_error)
+  }
+}
+
+pub fn encode(tokenizer: &CoreBPE, text: &str, with_special_tokens: bool) -> Vec<u32> {
+  if with_special_tokens {
+    tokenizer.encode_with_special_tokens(text)
+  } else {
+    tokenizer.encode_ordinary(text)
+  }
+}
+
+pub fn decode(tokenizer: &CoreBPE, tokens: Vec<u32>) -> Result<String, Error> {
+  tokenizer.decode(tokens).map_err(tokenizer_error)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn encodes_and_decodes_with_named_encodings() {
+    let tokenizer = build_tokenizer(Some("gpt-4o"), Some("O200K-BASE")).unwrap();
+    let tokens = encode(&tokenizer, "hello world", false);
+    assert_eq!(decode(&tokenizer, tokens).unwrap(), "hello world");
+    assert_eq!(
+      build_tokenizer(None, Some("p50k")).unwrap_err(),
+      Error::new("Unknown tokenizer encoding: p50k")
+    );
+  }
+}
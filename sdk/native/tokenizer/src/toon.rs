@@ -0,0 +1,62 @@
+//! TOON encoding of JSON values, as shown in LLM-facing previews.
+
+use serde_json::Value as JsonValue;
+
+use crate::Error;
+
+/// Largest integer a JS number holds exactly (`Number.MAX_SAFE_INTEGER`).
+pub const MAX_SAFE_JS_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedToon {
+  pub value: JsonValue,
+  /// JSON paths (e.g. `$.users[2].id`) of values JavaScript can't hold exactly, such as
+  /// integers past `Number.MAX_SAFE_INTEGER`.
+  pub lossy_fields: Vec<String>,
+}
+
+pub fn encode(value: &JsonValue) -> Result<String, Error> {
+  toon_rust::encode(value, None)
+    .map_err(|err| Error::new(format!("Failed to encode value to TOON: {err}")))
+}
+
+pub fn decode(text: &str) -> Result<DecodedToon, Error> {
+  let value = toon_rust::decode(text, None)
+    .map_err(|err| Error::new(format!("Failed to decode TOON: {err}")))?;
+  let mut lossy_fields = Vec::new();
+  collect_lossy_fields(&value, "$", &mut lossy_fields);
+  Ok(DecodedToon {
+    value,
+    lossy_fields,
+  })
+}
+
+fn collect_lossy_fields(value: &JsonValue, path: &str, lossy: &mut Vec<String>) {
+  match value {
+    JsonValue::Number(number) => {
+      let exact = match (number.as_u64(), number.as_i64()) {
+        (Some(n), _) => n <= MAX_SAFE_JS_INTEGER,
+        (None, Some(n)) => n.unsigned_abs() <= MAX_SAFE_JS_INTEGER,
+        // An integer literal past 2^53 that was parsed as a float has already lost digits.
+        (None, None) => number.as_f64().is_some_and(|n| {
+          n.is_finite() && !(n.fract() == 0.0 && n.abs() > MAX_SAFE_JS_INTEGER as f64)
+        }),
+      };
+      if !exact {
+        lossy.push(path.to_string());
+      }
+    }
+    JsonValue::Array(items) => {
+      for (index, item) in items.iter().enumerate() {
+        collect_lossy_fields(item, &format!("{path}[{index}]"), lossy);
+      }
+    }
+    JsonValue::Object(map) => {
+      for (key, item) in map {
+        collect_lossy_fields(item, &format!("{path}.{key}"), lossy);
+      }
+    }
+    JsonValue::Null | JsonValue::Bool(_) | JsonValue::String(_) => {}
+  }
+}
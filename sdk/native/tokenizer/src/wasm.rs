@@ -0,0 +1,72 @@
+//! wasm-bindgen exports, named like the SDK's `tokenizerCount` family.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::CoreBPE;
+
+thread_local! {
+  /// Building a BPE takes far longer than encoding a prompt, so keep each one.
+  static TOKENIZERS: RefCell<HashMap<(Option<String>, Option<String>), Rc<CoreBPE>>> =
+    RefCell::new(HashMap::new());
+}
+
+fn tokenizer(model: Option<String>, encoding: Option<String>) -> Result<Rc<CoreBPE>, JsError> {
+  let key = (model, encoding);
+  if let Some(tokenizer) = TOKENIZERS.with_borrow(|cache| cache.get(&key).cloned()) {
+    return Ok(tokenizer);
+  }
+  let tokenizer = Rc::new(crate::build_tokenizer(key.0.as_deref(), key.1.as_deref())?);
+  TOKENIZERS.with_borrow_mut(|cache| cache.insert(key, Rc::clone(&tokenizer)));
+  Ok(tokenizer)
+}
+
+#[wasm_bindgen(js_name = tokenizerCount)]
+pub fn tokenizer_count(
+  text: &str,
+  model: Option<String>,
+  encoding: Option<String>,
+) -> Result<u32, JsError> {
+  let tokens = crate::encode(&tokenizer(model, encoding)?, text, false);
+  u32::try_from(tokens.len()).map_err(|_| JsError::new("token count exceeds u32"))
+}
+
+#[wasm_bindgen(js_name = tokenizerEncode)]
+pub fn tokenizer_encode(
+  text: &str,
+  model: Option<String>,
+  encoding: Option<String>,
+  with_special_tokens: Option<bool>,
+) -> Result<Vec<u32>, JsError> {
+  Ok(crate::encode(
+    &tokenizer(model, encoding)?,
+    text,
+    with_special_tokens.unwrap_or(false),
+  ))
+}
+
+#[wasm_bindgen(js_name = tokenizerDecode)]
+pub fn tokenizer_decode(
+  tokens: Vec<u32>,
+  model: Option<String>,
+  encoding: Option<String>,
+) -> Result<String, JsError> {
+  Ok(crate::decode(&tokenizer(model, encoding)?, tokens)?)
+}
+
+#[wasm_bindgen(js_name = toonEncode)]
+pub fn toon_encode(value: JsValue) -> Result<String, JsError> {
+  let value: serde_json::Value = serde_wasm_bindgen::from_value(value)?;
+  Ok(crate::toon::encode(&value)?)
+}
+
+/// `{ value, lossyFields }`, as `toonDecode` returns in Node.
+#[wasm_bindgen(js_name = toonDecode)]
+pub fn toon_decode(text: &str) -> Result<JsValue, JsError> {
+  let decoded = crate::toon::decode(text)?;
+  Ok(decoded.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}